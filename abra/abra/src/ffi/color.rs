@@ -6,7 +6,7 @@ pub fn test() {
 
 constructor_ffi!(abra_color_rgb, Color, Color::from_rgb, r: u8, g: u8, b: u8);
 constructor_ffi!(abra_color_rgba, Color, Color::from_rgba, r: u8, g: u8, b: u8, a: u8);
-constructor_ffi!(abra_color_hex, Color, Color::from_hex, hex: u32);
+constructor_ffi!(abra_color_hex, Color, Color::from_hex_value, hex: u32);
 constructor_ffi!(abra_color_hsl, Color, Color::from_hsl, h: f32, s: f32, l: f32);
 constructor_ffi!(abra_color_hsv, Color, Color::from_hsv, h: f32, s: f32, v: f32);
 // Accepts pointer + len for an array/slice of u8 colors to be combined
@@ -0,0 +1,61 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CString, c_char};
+use std::panic::{UnwindSafe, catch_unwind};
+
+/// The call succeeded.
+pub const ABRA_OK: i32 = 0;
+/// A required pointer argument was null.
+pub const ABRA_ERROR_NULL_POINTER: i32 = -1;
+/// An argument was invalid, e.g. a path that is not UTF-8 or has an unsupported extension.
+pub const ABRA_ERROR_INVALID_ARGUMENT: i32 = -2;
+/// Reading, decoding, encoding or writing a file failed.
+pub const ABRA_ERROR_IO: i32 = -3;
+/// The operation panicked.
+pub const ABRA_ERROR_INTERNAL: i32 = -4;
+
+thread_local! {
+  static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Records the error message returned by `abra_last_error` and passes the error code through.
+pub(crate) fn set_last_error(p_code: i32, p_message: impl Into<String>) -> i32 {
+  let message = p_message.into().replace('\0', " ");
+  LAST_ERROR.with(|error| *error.borrow_mut() = CString::new(message).ok());
+  p_code
+}
+
+/// Clears the last error at the start of a call, so `abra_last_error` returns null after a call that succeeds.
+pub(crate) fn clear_last_error() {
+  LAST_ERROR.with(|error| *error.borrow_mut() = None);
+}
+
+/// Runs an FFI call body, turning a panic into `ABRA_ERROR_INTERNAL` so it never unwinds into C.
+pub(crate) fn guard(p_body: impl FnOnce() -> i32 + UnwindSafe) -> i32 {
+  clear_last_error();
+  match catch_unwind(p_body) {
+    Ok(code) => code,
+    Err(panic) => set_last_error(ABRA_ERROR_INTERNAL, panic_message(panic.as_ref())),
+  }
+}
+
+/// Returns the message a panic was raised with, for reporting a caught panic as an error.
+pub(crate) fn panic_message(p_panic: &(dyn Any + Send)) -> String {
+  p_panic
+    .downcast_ref::<String>()
+    .cloned()
+    .or_else(|| p_panic.downcast_ref::<&str>().map(|message| message.to_string()))
+    .unwrap_or_else(|| "Unknown panic".to_string())
+}
+
+/// Returns the message of the last error on the calling thread, or null if the last call succeeded.
+/// The string is owned by Abra and stays valid until the next Abra call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn abra_last_error() -> *const c_char {
+  LAST_ERROR.with(|error| {
+    error
+      .borrow()
+      .as_ref()
+      .map_or(std::ptr::null(), |message| message.as_ptr())
+  })
+}
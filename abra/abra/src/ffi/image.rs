@@ -1,9 +1,126 @@
-use abra_core::{Color, Image};
+use std::ffi::{CStr, c_char};
+use std::panic::AssertUnwindSafe;
 
-constructor_ffi!(abra_image_new, Image, Image::new, width: u32, height: u32);
+use abra_core::image::image_ext::CoreImageFsExt;
+use abra_core::{Area, Channels, Color, Image, ImageFormat, WriterOptions};
+use filters::blur::gaussian_blur;
+use options::ApplyOptions;
 
+use super::error::*;
+
+/// Creates a new image filled with black.
+/// - `channels`: 4 for a transparent image, or 3 for an opaque one.
+///
+/// Returns null if `channels` is not 3 or 4. Free the image with `abra_image_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn abra_image_new(width: u32, height: u32, channels: u32) -> *mut Image {
+  clear_last_error();
+  let image = match channels {
+    3 => Image::new_from_color(width, height, Color::black()),
+    4 => Image::new(width, height),
+    _ => {
+      set_last_error(ABRA_ERROR_INVALID_ARGUMENT, format!("channels must be 3 or 4, got {}", channels));
+      return std::ptr::null_mut();
+    }
+  };
+  box_ffi!(image)
+}
+
+/// Creates an image from a buffer of pixels, row by row from the top left.
+/// The pixels are copied, so the caller keeps ownership of `data` and may free or reuse it right away.
+/// - `data`: The pixels, `width * height * channels` bytes.
+/// - `len`: The length of `data` in bytes.
+/// - `channels`: 4 for RGBA pixels, or 3 for RGB pixels which become opaque.
+///
+/// Returns null on failure; `abra_last_error` describes why. Free the image with `abra_image_free`.
+///
+/// # Safety
+/// `data` must be null or point to at least `len` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn abra_image_from_data(
+  width: u32, height: u32, data: *const u8, len: usize, channels: u32,
+) -> *mut Image {
+  clear_last_error();
+  if data.is_null() {
+    set_last_error(ABRA_ERROR_NULL_POINTER, "data is null");
+    return std::ptr::null_mut();
+  }
+  if channels != 3 && channels != 4 {
+    set_last_error(ABRA_ERROR_INVALID_ARGUMENT, format!("channels must be 3 or 4, got {}", channels));
+    return std::ptr::null_mut();
+  }
+  let expected = width as usize * height as usize * channels as usize;
+  if len != expected {
+    set_last_error(
+      ABRA_ERROR_INVALID_ARGUMENT,
+      format!("expected {} bytes for {}x{} with {} channels, got {}", expected, width, height, channels, len),
+    );
+    return std::ptr::null_mut();
+  }
+  let data = unsafe { std::slice::from_raw_parts(data, len) };
+  let pixels = match channels {
+    3 => data
+      .chunks_exact(3)
+      .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+      .collect(),
+    _ => data.to_vec(),
+  };
+  box_ffi!(Image::new_from_pixels(width, height, pixels, Channels::RGBA))
+}
+
+/// Gets a pointer to an image's RGBA pixels, 4 bytes per pixel, row by row from the top left.
+/// The pixels are borrowed from the image: do not free them, and do not use the pointer after the image
+/// is modified or freed. Copy them out if they are needed for longer.
+/// - `out_data`: Receives the pointer to the pixels.
+/// - `out_len`: Receives the length of the pixels in bytes.
+///
+/// Returns `ABRA_OK` or a negative error code.
+///
+/// # Safety
+/// `image` must be null or an image from Abra that has not been freed. `out_data` and `out_len` must be null or
+/// valid for writes.
 #[unsafe(no_mangle)]
-pub extern "C" fn abra_image_new_from_color(width: u32, height: u32, color: *const Color) -> *mut Image {
+pub unsafe extern "C" fn abra_image_data(image: *const Image, out_data: *mut *const u8, out_len: *mut usize) -> i32 {
+  clear_last_error();
+  if image.is_null() || out_data.is_null() || out_len.is_null() {
+    return set_last_error(ABRA_ERROR_NULL_POINTER, "image, out_data and out_len must not be null");
+  }
+  let pixels = unsafe { &*image }.rgba();
+  unsafe {
+    *out_data = pixels.as_ptr();
+    *out_len = pixels.len();
+  }
+  ABRA_OK
+}
+
+/// Gets the width and height of an image in pixels.
+/// Returns `ABRA_OK` or a negative error code.
+///
+/// # Safety
+/// `image` must be null or an image from Abra that has not been freed. `out_width` and `out_height` must be null or
+/// valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn abra_image_dimensions(image: *const Image, out_width: *mut u32, out_height: *mut u32) -> i32 {
+  clear_last_error();
+  if image.is_null() || out_width.is_null() || out_height.is_null() {
+    return set_last_error(ABRA_ERROR_NULL_POINTER, "image, out_width and out_height must not be null");
+  }
+  let (width, height) = unsafe { &*image }.dimensions::<u32>();
+  unsafe {
+    *out_width = width;
+    *out_height = height;
+  }
+  ABRA_OK
+}
+
+/// Creates a new image filled with a color, or with black when `color` is null.
+/// Free the image with `abra_image_free`.
+///
+/// # Safety
+/// `color` must be null or point to a valid `Color`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn abra_image_new_from_color(width: u32, height: u32, color: *const Color) -> *mut Image {
+  clear_last_error();
   let color = if color.is_null() {
     Color::black()
   } else {
@@ -13,3 +130,181 @@ pub extern "C" fn abra_image_new_from_color(width: u32, height: u32, color: *con
 }
 
 destructor_ffi!(abra_image_free, Image);
+
+/// Reads a C string argument as UTF-8.
+///
+/// # Safety
+/// `p_path` must be null or point to a nul-terminated string that outlives the returned slice.
+unsafe fn read_path<'a>(p_path: *const c_char) -> Result<&'a str, i32> {
+  if p_path.is_null() {
+    return Err(set_last_error(ABRA_ERROR_NULL_POINTER, "path is null"));
+  }
+  unsafe { CStr::from_ptr(p_path) }
+    .to_str()
+    .map_err(|_| set_last_error(ABRA_ERROR_INVALID_ARGUMENT, "path is not valid UTF-8"))
+}
+
+/// Loads an image from a file.
+/// Returns null on failure; `abra_last_error` describes why. Free the image with `abra_image_free`.
+///
+/// # Safety
+/// `path` must be null or point to a nul-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn abra_image_load(path: *const c_char) -> *mut Image {
+  let mut image = None;
+  guard(AssertUnwindSafe(|| {
+    let path = match unsafe { read_path(path) } {
+      Ok(path) => path,
+      Err(code) => return code,
+    };
+    match Image::try_new_from_path(path) {
+      Ok(loaded) => {
+        image = Some(loaded);
+        ABRA_OK
+      }
+      Err(message) => set_last_error(ABRA_ERROR_IO, format!("{}: {}", path, message)),
+    }
+  }));
+  image.map_or(std::ptr::null_mut(), |image| box_ffi!(image))
+}
+
+/// Blurs an image in place with a Gaussian blur.
+/// Returns `ABRA_OK` or a negative error code.
+///
+/// # Safety
+/// `image` must be null or an image from Abra that has not been freed, and must not be used by another thread
+/// during the call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn abra_image_gaussian_blur(image: *mut Image, radius: u32) -> i32 {
+  if image.is_null() {
+    return set_last_error(ABRA_ERROR_NULL_POINTER, "image is null");
+  }
+  let image = unsafe { &mut *image };
+  guard(AssertUnwindSafe(|| {
+    let (width, height) = image.dimensions::<u32>();
+    let options = ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (width as f32, height as f32)));
+    gaussian_blur(&mut *image, radius, options);
+    ABRA_OK
+  }))
+}
+
+/// Saves an image to a file, choosing the format from the extension (png, jpg, webp or gif).
+/// Missing folders are created.
+/// - `quality`: The quality for lossy formats, from 1 to 100.
+///
+/// Returns `ABRA_OK` or a negative error code.
+///
+/// # Safety
+/// `image` must be null or an image from Abra that has not been freed. `path` must be null or point to a
+/// nul-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn abra_image_save(image: *const Image, path: *const c_char, quality: u8) -> i32 {
+  guard(|| {
+    if image.is_null() {
+      return set_last_error(ABRA_ERROR_NULL_POINTER, "image is null");
+    }
+    let image = unsafe { &*image };
+    let path = match unsafe { read_path(path) } {
+      Ok(path) => path,
+      Err(code) => return code,
+    };
+    let Some(format) = ImageFormat::from_path(path) else {
+      return set_last_error(ABRA_ERROR_INVALID_ARGUMENT, format!("unsupported file format: {}", path));
+    };
+    let bytes = match image.encode(format, WriterOptions::new(quality)) {
+      Ok(bytes) => bytes,
+      Err(error) => return set_last_error(ABRA_ERROR_IO, error.to_string()),
+    };
+    if let Some(parent) = std::path::Path::new(path).parent()
+      && let Err(error) = std::fs::create_dir_all(parent)
+    {
+      return set_last_error(ABRA_ERROR_IO, format!("{}: {}", path, error));
+    }
+    match std::fs::write(path, bytes) {
+      Ok(()) => ABRA_OK,
+      Err(error) => set_last_error(ABRA_ERROR_IO, format!("{}: {}", path, error)),
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::ffi::CString;
+
+  fn last_error() -> Option<String> {
+    let message = abra_last_error();
+    (!message.is_null()).then(|| unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned())
+  }
+
+  #[test]
+  fn null_pointers_are_rejected() {
+    assert!(unsafe { abra_image_load(std::ptr::null()) }.is_null());
+    assert_eq!(last_error().as_deref(), Some("path is null"));
+    unsafe {
+      assert_eq!(abra_image_gaussian_blur(std::ptr::null_mut(), 2), ABRA_ERROR_NULL_POINTER);
+      assert_eq!(abra_image_save(std::ptr::null(), std::ptr::null(), 90), ABRA_ERROR_NULL_POINTER);
+      let code = abra_image_data(std::ptr::null(), std::ptr::null_mut(), std::ptr::null_mut());
+      assert_eq!(code, ABRA_ERROR_NULL_POINTER);
+      assert!(abra_image_from_data(1, 1, std::ptr::null(), 4, 4).is_null());
+      abra_image_free(std::ptr::null_mut());
+    }
+  }
+
+  #[test]
+  fn pixels_round_trip() {
+    let rgb = [10u8, 20, 30, 40, 50, 60];
+    unsafe {
+      let image = abra_image_from_data(2, 1, rgb.as_ptr(), rgb.len(), 3);
+      assert!(!image.is_null());
+      let (mut data, mut len) = (std::ptr::null(), 0usize);
+      assert_eq!(abra_image_data(image, &mut data, &mut len), ABRA_OK);
+      let pixels = std::slice::from_raw_parts(data, len);
+      assert_eq!(pixels, &[10, 20, 30, 255, 40, 50, 60, 255]);
+      let (mut width, mut height) = (0, 0);
+      assert_eq!(abra_image_dimensions(image, &mut width, &mut height), ABRA_OK);
+      assert_eq!((width, height), (2, 1));
+      abra_image_free(image);
+
+      assert!(abra_image_from_data(2, 2, rgb.as_ptr(), rgb.len(), 3).is_null());
+      assert!(last_error().unwrap().contains("expected 12 bytes"));
+      assert!(abra_image_from_data(2, 1, rgb.as_ptr(), rgb.len(), 2).is_null());
+      assert!(abra_image_new(2, 2, 1).is_null());
+      let opaque = abra_image_new(1, 1, 3);
+      // A call that succeeds clears the error of the call before it.
+      assert!(last_error().is_none());
+      assert_eq!((*opaque).get_pixel(0, 0), Some((0, 0, 0, 255)));
+      assert!(abra_image_from_data(2, 2, rgb.as_ptr(), rgb.len(), 3).is_null());
+      assert_eq!(abra_image_dimensions(opaque, &mut width, &mut height), ABRA_OK);
+      assert!(last_error().is_none());
+      abra_image_free(opaque);
+    }
+  }
+
+  #[test]
+  fn load_blur_and_save() {
+    let dir = std::env::temp_dir().join("abra_ffi_load_blur_and_save");
+    let path = CString::new(dir.join("image.png").to_str().unwrap()).unwrap();
+    let missing = CString::new(dir.join("missing.png").to_str().unwrap()).unwrap();
+    let unsupported = CString::new(dir.join("image.tiff").to_str().unwrap()).unwrap();
+    unsafe {
+      let image = abra_image_new_from_color(8, 8, &Color::red());
+      assert_eq!(abra_image_gaussian_blur(image, 2), ABRA_OK);
+      assert_eq!(abra_image_save(image, path.as_ptr(), 90), ABRA_OK);
+      assert!(last_error().is_none());
+      abra_image_free(image);
+
+      let loaded = abra_image_load(path.as_ptr());
+      assert!(!loaded.is_null());
+      assert_eq!((*loaded).dimensions::<u32>(), (8, 8));
+      abra_image_free(loaded);
+
+      assert!(abra_image_load(missing.as_ptr()).is_null());
+      assert!(last_error().unwrap().contains("missing.png"));
+      let image = abra_image_new(2, 2, 4);
+      assert_eq!(abra_image_save(image, unsupported.as_ptr(), 90), ABRA_ERROR_INVALID_ARGUMENT);
+      abra_image_free(image);
+    }
+    let _ = std::fs::remove_dir_all(dir);
+  }
+}
@@ -32,8 +32,12 @@ macro_rules! constructor_ffi {
 
 macro_rules! destructor_ffi {
   ($fn_name:ident, $ty:ty) => {
+    /// Frees a value created by Abra. Null is ignored.
+    ///
+    /// # Safety
+    /// `ptr` must be null or a value from Abra of this type that has not been freed yet.
     #[unsafe(no_mangle)]
-    pub extern "C" fn $fn_name(ptr: *mut $ty) {
+    pub unsafe extern "C" fn $fn_name(ptr: *mut $ty) {
       unbox_ffi!(ptr);
     }
   };
@@ -44,8 +48,10 @@ macro_rules! destructor_ffi {
 // Usage: constructor_ffi_slice!(abra_color_mean, Color, Color::mean, data: *const u8, len: usize);
 macro_rules! constructor_ffi_slice {
   ($fn_name:ident, $ty:ty, $ctor:path, $ptr_name:ident : *const $elem_ty:ty, $len_name:ident : usize) => {
+    /// # Safety
+    /// The pointer must be null or point to at least as many readable elements as the length.
     #[unsafe(no_mangle)]
-    pub extern "C" fn $fn_name($ptr_name: *const $elem_ty, $len_name: usize) -> *mut $ty {
+    pub unsafe extern "C" fn $fn_name($ptr_name: *const $elem_ty, $len_name: usize) -> *mut $ty {
       let slice: &[$elem_ty] = if $ptr_name.is_null() {
         &[]
       } else {
@@ -57,4 +63,5 @@ macro_rules! constructor_ffi_slice {
 }
 
 pub mod color;
+pub mod error;
 pub mod image;
@@ -6,6 +6,7 @@ use abra_core::Settings;
 use ctor::ctor;
 
 pub mod ffi;
+pub mod pipeline;
 pub mod plugin;
 
 pub use abra_core;
@@ -58,8 +59,11 @@ fn init_abra_core() {
 }
 
 /// Initialize global settings for Abra.
+/// Nothing can receive an error while the library loads, so an invalid environment override is reported on stderr.
 fn init_settings() {
-  Settings::init();
+  if let Err(e) = Settings::init() {
+    eprintln!("Abra settings: {}", e);
+  }
 }
 
 #[cfg(not(feature = "gpu"))]
@@ -0,0 +1,383 @@
+//! Batch processing: applies the same sequence of steps to many image files.
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use abra_core::image::image_ext::CoreImageFsExt;
+use abra_core::{Image, ImageFormat, Resize, Rotate, TransformAlgorithm, WriterOptions, get_paths_from_glob};
+use rayon::prelude::*;
+
+use crate::ffi::error::panic_message;
+
+/// A single step of a pipeline.
+type Step = Arc<dyn Fn(&mut Image) + Send + Sync>;
+
+/// The adjustments a pipeline can apply, each wrapping the function of the same name in the adjustments crate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Adjustment {
+  /// Automatically corrects the colors.
+  AutoColor,
+  /// Automatically corrects the tone.
+  AutoTone,
+  /// Converts the colors to grayscale.
+  Grayscale,
+  /// Inverts the colors.
+  Invert,
+  /// Changes the brightness (-100 to 100).
+  Brightness(i32),
+  /// Changes the contrast (-100 to 100).
+  Contrast(f32),
+  /// Changes the saturation (-100 to 100).
+  Saturation(i32),
+  /// Changes the exposure in stops.
+  Exposure(f32),
+  /// Changes the midtone local contrast (-100 to 100).
+  Clarity(f32),
+  /// Corrects the white balance with a temperature and a tint (-100 to 100).
+  WhiteBalance(f32, f32),
+}
+
+impl Adjustment {
+  /// Applies the adjustment to the whole image.
+  fn apply(&self, p_image: &mut Image) {
+    use ::adjustments::{color, levels};
+    match *self {
+      Adjustment::AutoColor => color::auto_color(p_image, None),
+      Adjustment::AutoTone => color::auto_tone(p_image, None),
+      Adjustment::Grayscale => color::grayscale(p_image, None),
+      Adjustment::Invert => color::invert(p_image, None),
+      Adjustment::Brightness(amount) => levels::brightness(p_image, amount, None),
+      Adjustment::Contrast(amount) => levels::contrast(p_image, amount, None),
+      Adjustment::Saturation(amount) => levels::saturation(p_image, amount, None),
+      Adjustment::Exposure(stops) => levels::exposure(p_image, stops, 0.0, 1.0, None),
+      Adjustment::Clarity(amount) => levels::clarity(p_image, amount, None),
+      Adjustment::WhiteBalance(temperature, tint) => color::white_balance(p_image, temperature, tint, None),
+    }
+  }
+}
+
+/// A file that was processed and saved by a pipeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessedFile {
+  /// The path of the source file.
+  pub input: String,
+  /// The path the result was saved to.
+  pub output: String,
+}
+
+/// A file (or glob pattern) that a pipeline could not process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineError {
+  /// The path of the source file, or the glob pattern that failed.
+  pub path: String,
+  /// What went wrong.
+  pub message: String,
+}
+
+impl Display for PipelineError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "Failed to process {}: {}", self.path, self.message)
+  }
+}
+
+impl std::error::Error for PipelineError {}
+
+/// The outcome of running a pipeline over a set of files.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PipelineReport {
+  /// The files that were processed and saved, in the order they were given.
+  pub succeeded: Vec<ProcessedFile>,
+  /// The files that failed to load, process or save.
+  pub failed: Vec<PipelineError>,
+}
+
+impl PipelineReport {
+  /// Whether every file was processed successfully.
+  pub fn is_success(&self) -> bool {
+    self.failed.is_empty()
+  }
+}
+
+/// A declarative sequence of steps that is applied to many image files in parallel.
+///
+/// Every step wraps an existing image function. Running the pipeline loads each file, applies the steps in order
+/// and saves the result into the output folder under the same file name with the extension of the output format.
+/// Files are processed in parallel, and a file that fails, or a step that panics, is recorded in the report instead of
+/// stopping the run.
+///
+/// ```ignore
+/// let report = Pipeline::new()
+///   .resize(800, 600, TransformAlgorithm::Lanczos3)
+///   .adjust(Adjustment::AutoColor)
+///   .sharpen()
+///   .run_glob("in/*.jpg", "out/", ImageFormat::WebP);
+/// for error in &report.failed {
+///   eprintln!("{}", error);
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct Pipeline {
+  steps: Vec<Step>,
+  writer_options: Option<WriterOptions>,
+}
+
+impl Pipeline {
+  /// Creates a pipeline without any steps.
+  pub fn new() -> Pipeline {
+    Pipeline::default()
+  }
+
+  /// Adds a custom step, for functions that have no dedicated pipeline method.
+  /// - `p_step`: The function applied to each image.
+  pub fn step(mut self, p_step: impl Fn(&mut Image) + Send + Sync + 'static) -> Self {
+    self.steps.push(Arc::new(p_step));
+    self
+  }
+
+  /// Adds a step that resizes each image to an exact size.
+  /// - `p_width`: The new width.
+  /// - `p_height`: The new height.
+  /// - `p_algorithm`: The resampling algorithm, or `None` for the default.
+  pub fn resize(self, p_width: u32, p_height: u32, p_algorithm: impl Into<Option<TransformAlgorithm>>) -> Self {
+    let algorithm = p_algorithm.into();
+    self.step(move |image| image.resize(p_width, p_height, algorithm))
+  }
+
+  /// Adds a step that rotates each image.
+  /// - `p_degrees`: The clockwise rotation in degrees.
+  /// - `p_algorithm`: The resampling algorithm, or `None` for the default.
+  pub fn rotate(self, p_degrees: f32, p_algorithm: impl Into<Option<TransformAlgorithm>>) -> Self {
+    let algorithm = p_algorithm.into();
+    self.step(move |image| image.rotate(p_degrees, algorithm))
+  }
+
+  /// Adds a step that applies an adjustment to each image.
+  /// - `p_adjustment`: The adjustment to apply.
+  pub fn adjust(self, p_adjustment: Adjustment) -> Self {
+    self.step(move |image| p_adjustment.apply(image))
+  }
+
+  /// Adds a step that sharpens each image.
+  pub fn sharpen(self) -> Self {
+    self.step(|image| ::filters::sharpen::sharpen(image, None))
+  }
+
+  /// Adds a step that applies a Gaussian blur to each image.
+  /// - `p_radius`: The blur radius in pixels.
+  pub fn blur(self, p_radius: u32) -> Self {
+    self.step(move |image| ::filters::blur::gaussian_blur(image, p_radius, None))
+  }
+
+  /// Sets the writer options used when saving, such as the quality.
+  /// - `p_options`: The writer options.
+  pub fn with_writer_options(mut self, p_options: WriterOptions) -> Self {
+    self.writer_options = Some(p_options);
+    self
+  }
+
+  /// Applies the steps to an image in memory.
+  /// - `p_image`: The image to process.
+  pub fn apply(&self, p_image: &mut Image) {
+    for step in &self.steps {
+      step(p_image);
+    }
+  }
+
+  /// Processes every file matching a glob pattern and saves the results into a folder.
+  /// Invalid patterns and unreadable directory entries are reported as failures.
+  /// - `p_pattern`: The glob pattern, e.g. `"in/*.jpg"`.
+  /// - `p_out_dir`: The folder the results are saved into. It is created if it does not exist.
+  /// - `p_format`: The format the results are saved in.
+  pub fn run_glob(
+    &self, p_pattern: impl Into<String>, p_out_dir: impl AsRef<str>, p_format: ImageFormat,
+  ) -> PipelineReport {
+    let (paths, errors) = get_paths_from_glob(vec![p_pattern.into()]);
+    let mut report = self.run_paths(paths, p_out_dir, p_format);
+    let glob_errors = errors.into_iter().map(|error| PipelineError {
+      path: error.path,
+      message: error.message,
+    });
+    report.failed.splice(0..0, glob_errors);
+    report
+  }
+
+  /// Processes the given files and saves the results into a folder.
+  /// Source files whose names differ only in the extension or folder would be saved to the same output file, so a
+  /// number is added to the name of every file after the first, e.g. `photo.png` and `photo-2.png`.
+  /// - `p_paths`: The paths of the files to process.
+  /// - `p_out_dir`: The folder the results are saved into. It is created if it does not exist.
+  /// - `p_format`: The format the results are saved in.
+  pub fn run_paths(
+    &self, p_paths: Vec<impl Into<String>>, p_out_dir: impl AsRef<str>, p_format: ImageFormat,
+  ) -> PipelineReport {
+    let paths: Vec<String> = p_paths.into_iter().map(Into::into).collect();
+    let out_dir = FsPath::new(p_out_dir.as_ref());
+    if let Err(error) = std::fs::create_dir_all(out_dir) {
+      let message = format!("Could not create the output folder {}: {}", out_dir.display(), error);
+      return PipelineReport {
+        succeeded: vec![],
+        failed: paths
+          .into_iter()
+          .map(|path| PipelineError {
+            path,
+            message: message.clone(),
+          })
+          .collect(),
+      };
+    }
+
+    let outputs = output_names(&paths, p_format);
+    let results: Vec<Result<ProcessedFile, PipelineError>> = paths
+      .into_par_iter()
+      .zip(outputs)
+      .map(|(path, name)| {
+        let output = out_dir.join(name);
+        catch_unwind(AssertUnwindSafe(|| self.process_file(&path, &output, p_format))).unwrap_or_else(|panic| {
+          Err(PipelineError {
+            path: path.clone(),
+            message: panic_message(panic.as_ref()),
+          })
+        })
+      })
+      .collect();
+    let mut report = PipelineReport::default();
+    for result in results {
+      match result {
+        Ok(file) => report.succeeded.push(file),
+        Err(error) => report.failed.push(error),
+      }
+    }
+    report
+  }
+
+  /// Loads, processes and saves a single file.
+  fn process_file(
+    &self, p_path: &str, p_output: &FsPath, p_format: ImageFormat,
+  ) -> Result<ProcessedFile, PipelineError> {
+    let fail = |message: String| PipelineError {
+      path: p_path.to_string(),
+      message,
+    };
+    let mut image = Image::try_new_from_path(p_path).map_err(fail)?;
+    self.apply(&mut image);
+
+    let bytes = image
+      .encode(p_format, self.writer_options.clone())
+      .map_err(|error| fail(error.to_string()))?;
+    std::fs::write(p_output, bytes).map_err(|error| fail(error.to_string()))?;
+    Ok(ProcessedFile {
+      input: p_path.to_string(),
+      output: p_output.to_string_lossy().into_owned(),
+    })
+  }
+}
+
+/// Picks the output file name of every source file, in order: the source file name with the extension of the format,
+/// with a number added when an earlier file already took the name.
+fn output_names(p_paths: &[String], p_format: ImageFormat) -> Vec<String> {
+  let mut taken = HashSet::new();
+  p_paths
+    .iter()
+    .map(|path| {
+      let stem = FsPath::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "image".to_string());
+      let mut name = format!("{}.{}", stem, p_format.extension());
+      let mut number = 2;
+      // Compared in lowercase, as the output folder may be on a case-insensitive file system.
+      while !taken.insert(name.to_lowercase()) {
+        name = format!("{}-{}.{}", stem, number, p_format.extension());
+        number += 1;
+      }
+      name
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::Color;
+
+  #[test]
+  fn processes_files_and_reports_failures() {
+    let dir = std::env::temp_dir().join("abra_pipeline_processes_files");
+    let (in_dir, out_dir) = (dir.join("in"), dir.join("out"));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&in_dir).unwrap();
+    for (i, name) in ["a.png", "b.png"].iter().enumerate() {
+      let image = Image::new_from_color(40, 20, Color::from_rgba(200, 100 + i as u8 * 50, 50, 255));
+      image.save(in_dir.join(name).to_string_lossy(), None);
+    }
+    std::fs::write(in_dir.join("broken.png"), b"not an image").unwrap();
+
+    let pattern = in_dir.join("*.png").to_string_lossy().into_owned();
+    let report = Pipeline::new()
+      .resize(20, 10, None)
+      .adjust(Adjustment::Invert)
+      .run_glob(pattern, out_dir.to_string_lossy(), ImageFormat::Png);
+
+    assert_eq!(report.succeeded.len(), 2);
+    assert_eq!(report.failed.len(), 1);
+    assert!(report.failed[0].path.ends_with("broken.png"));
+    assert!(!report.is_success());
+    for file in &report.succeeded {
+      let output = Image::new_from_path(file.output.as_str());
+      assert_eq!(output.dimensions::<u32>(), (20, 10));
+      assert_eq!(output.get_pixel(5, 5).unwrap().0, 55);
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+
+  #[test]
+  fn numbers_colliding_names_and_reports_panics() {
+    let dir = std::env::temp_dir().join("abra_pipeline_colliding_names");
+    let out_dir = dir.join("out");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    let mut paths = vec![];
+    for (name, width) in [
+      ("photo.png", 10),
+      ("photo.jpg", 20),
+      ("nested/photo.png", 30),
+      ("panic.png", 1),
+    ] {
+      let path = dir.join(name).to_string_lossy().into_owned();
+      Image::new_from_color(width, 10, Color::from_rgba(200, 100, 50, 255)).save(path.as_str(), None);
+      paths.push(path);
+    }
+
+    let report = Pipeline::new()
+      .step(|image| assert_ne!(image.dimensions::<u32>().0, 1, "Step failed"))
+      .run_paths(paths, out_dir.to_string_lossy(), ImageFormat::Png);
+
+    let widths: Vec<(String, u32)> = report
+      .succeeded
+      .iter()
+      .map(|file| {
+        let name = FsPath::new(&file.output)
+          .file_name()
+          .unwrap()
+          .to_string_lossy()
+          .into_owned();
+        (name, Image::new_from_path(file.output.as_str()).dimensions::<u32>().0)
+      })
+      .collect();
+    assert_eq!(
+      widths,
+      [
+        ("photo.png".to_string(), 10),
+        ("photo-2.png".to_string(), 20),
+        ("photo-3.png".to_string(), 30)
+      ]
+    );
+    assert_eq!(report.failed.len(), 1);
+    assert!(report.failed[0].path.ends_with("panic.png"));
+    assert!(report.failed[0].message.contains("Step failed"));
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+}
@@ -4,34 +4,53 @@
 // Re-export selected core types & traits under `abra::prelude` for ergonomic use by consumers
 pub use crate::abra_core::Channels;
 pub use crate::abra_core::Color;
+pub use crate::abra_core::ColorSpace;
 pub use crate::abra_core::Image;
+pub use crate::abra_core::LinearImage;
 pub use crate::abra_core::ImageLoader;
+pub use crate::abra_core::LoadError;
 pub use crate::abra_core::LoadedImages;
+pub use crate::abra_core::SortKey;
 pub use crate::abra_core::Settings;
 pub use crate::abra_core::WriterOptions;
+pub use crate::abra_core::ImageFormat;
+pub use crate::abra_core::PngFilter;
 pub use crate::abra_core::image::image_ext::*;
 
 // Commonly used transform traits (brought into prelude for ergonomics)
 pub use crate::abra_core::Crop;
 pub use crate::abra_core::Resize;
 pub use crate::abra_core::Rotate;
+pub use crate::abra_core::watermark::Watermark;
 
 // Common geometry and path helpers
 pub use crate::abra_core::Area;
+pub use crate::abra_core::BooleanOp;
+pub use crate::abra_core::FillRule;
+pub use crate::abra_core::Align;
+pub use crate::abra_core::Anchor;
 pub use crate::abra_core::AspectRatio;
 pub use crate::abra_core::Fill;
 pub use crate::abra_core::LineJoin;
 pub use crate::abra_core::Path;
 pub use crate::abra_core::Point;
 pub use crate::abra_core::PointF;
-pub use crate::abra_core::Polygon;
+pub use crate::abra_core::Rect;
+pub use crate::abra_core::RegularPolygon;
+pub use crate::abra_core::Arrow;
+pub use crate::abra_core::SpeechBubble;
 
 // Gradient and drawing helpers
 pub use crate::abra_core::Gradient;
+pub use crate::abra_core::Easing;
+pub use crate::abra_core::RepeatMode;
 // pub use crate::drawing::fill;
 
 // TransformAlgorithm enum
 pub use crate::abra_core::TransformAlgorithm;
 
+// Batch processing
+pub use crate::pipeline::{Adjustment, Pipeline, PipelineReport};
+
 // Plugins
 pub use crate::plugin::*;
@@ -16,6 +16,8 @@ pub enum AiError {
   ModelNotFound(String),
   /// Configuration error.
   ConfigError(String),
+  /// The operation was stopped by its cancellation token.
+  Cancelled,
   /// Generic/other error.
   Other(String),
 }
@@ -78,9 +80,16 @@ impl std::fmt::Display for AiError {
       AiError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
       AiError::ModelNotFound(name) => write!(f, "Model not found: {}", name),
       AiError::ConfigError(msg) => write!(f, "Configuration error: {}", msg),
+      AiError::Cancelled => write!(f, "Cancelled"),
       AiError::Other(msg) => write!(f, "Error: {}", msg),
     }
   }
 }
 
 impl std::error::Error for AiError {}
+
+impl From<abra_core::Cancelled> for AiError {
+  fn from(_: abra_core::Cancelled) -> Self {
+    AiError::Cancelled
+  }
+}
@@ -49,8 +49,8 @@ use crate::error::AiError;
 use crate::onnx::OnnxSession;
 use crate::tensor::image_to_nchw;
 use crate::tiled::{TileAccumulator, TileConfig, generate_tiles};
-use abra_core::Image;
 use abra_core::transform::cropped;
+use abra_core::{CancellationToken, Image};
 use saphyr::{LoadableYamlNode, Yaml};
 use std::path::Path;
 use std::time::Instant;
@@ -413,7 +413,17 @@ impl ImageModel {
   /// If the model has control input, uses the default control values.
   pub fn process(&self, input: &Image) -> Result<Image, AiError> {
     let control = self.spec.as_ref().expect("Model spec is not loaded").default_control();
-    self.process_tiles(input, control.as_ref())
+    self.process_tiles(input, control.as_ref(), &CancellationToken::new())
+  }
+
+  /// Processes an image like `process`, stopping between tiles when the token is cancelled.
+  ///
+  /// # Errors
+  ///
+  /// Returns `AiError::Cancelled` if the token is cancelled before the last tile is done.
+  pub fn process_cancellable(&self, input: &Image, cancel: &CancellationToken) -> Result<Image, AiError> {
+    let control = self.spec.as_ref().expect("Model spec is not loaded").default_control();
+    self.process_tiles(input, control.as_ref(), cancel)
   }
 
   /// Processes an image with custom control parameters.
@@ -441,11 +451,13 @@ impl ImageModel {
       )));
     }
 
-    self.process_tiles(input, Some(control))
+    self.process_tiles(input, Some(control), &CancellationToken::new())
   }
 
   /// Internal method to process image in tiles.
-  fn process_tiles(&self, input: &Image, control: Option<&ControlParams>) -> Result<Image, AiError> {
+  fn process_tiles(
+    &self, input: &Image, control: Option<&ControlParams>, cancel: &CancellationToken,
+  ) -> Result<Image, AiError> {
     let start = Instant::now();
     let (orig_w, orig_h) = input.dimensions::<u32>();
     let scale = self.spec.as_ref().expect("Model spec is not loaded").scale_factor;
@@ -473,6 +485,8 @@ impl ImageModel {
     let mut accumulator = TileAccumulator::new(out_w, out_h);
 
     for tile_info in &tiles {
+      // Tiles are the natural place to stop, as each one is a separate inference run.
+      cancel.check()?;
       if tile_info.index % 10 == 0 || tile_info.index == tile_info.total - 1 {
         println!("  Tile {}/{}", tile_info.index + 1, tile_info.total);
       }
@@ -557,3 +571,22 @@ pub fn discover_models(dir: impl AsRef<Path>) -> Result<Vec<ModelSpec>, AiError>
 
   Ok(models)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn cancelled_processing_stops_before_the_first_tile() {
+    // No session is needed, as the token is checked before each tile is run.
+    let model = ImageModel {
+      session: None,
+      spec: Some(ModelSpec::minimal("model.onnx")),
+      available_models: Vec::new(),
+    };
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let result = model.process_cancellable(&Image::new(300, 300), &cancel);
+    assert!(matches!(result, Err(AiError::Cancelled)));
+  }
+}
@@ -1,17 +1,17 @@
-use abra_core::{Histogram, Image, image::image_ext::ImageRef, lab_to_rgb, rgb_to_lab};
+use abra_core::{Color, Histogram, Image, image::image_ext::ImageRef, lab_to_rgb, rgb_to_lab};
 use options::Options;
 
 use rayon::prelude::*;
 
 use crate::apply_adjustment;
+use crate::color::auto_tone::DEFAULT_CLIP_PERCENT;
 
-fn apply_auto_color(p_image: &mut Image) {
+fn apply_auto_color(p_image: &mut Image, p_shadow_clip: f32, p_highlight_clip: f32, p_snap_neutral_midtones: bool) {
   let (width, height) = p_image.dimensions::<i32>();
   let src = p_image.rgba();
   let mut out = vec![0u8; (width * height * 4) as usize];
 
   // Algorithm parameters
-  let clip_fraction: f32 = 0.005; // 0.5% per tail
   let midtone_low: f32 = 0.3;
   let midtone_high: f32 = 0.7;
   let neutralize_intensity: f32 = 1.0;
@@ -20,16 +20,10 @@ fn apply_auto_color(p_image: &mut Image) {
   // Compute histogram using helper struct.
   let hist = Histogram::from_image_skip_transparent(p_image);
 
-  // Use histogram helpers to compute channel clip bounds
-  // Clip bounds still available via Histogram helpers, but to compute mapping we use LUTs
-  let _ = hist.red_clip_bounds(clip_fraction);
-  let _ = hist.green_clip_bounds(clip_fraction);
-  let _ = hist.blue_clip_bounds(clip_fraction);
-
   // Second pass: compute midtone mean a & b (Lab) using post-levels LUTs.
-  let lut_r = hist.red_levels_lut(clip_fraction);
-  let lut_g = hist.green_levels_lut(clip_fraction);
-  let lut_b = hist.blue_levels_lut(clip_fraction);
+  let lut_r = hist.levels_lut_from_slice_split(hist.red(), p_shadow_clip, p_highlight_clip);
+  let lut_g = hist.levels_lut_from_slice_split(hist.green(), p_shadow_clip, p_highlight_clip);
+  let lut_b = hist.levels_lut_from_slice_split(hist.blue(), p_shadow_clip, p_highlight_clip);
 
   let (sum_a, sum_b_lab, midtone_count) = src
     .par_chunks(4)
@@ -43,7 +37,7 @@ fn apply_auto_color(p_image: &mut Image) {
       let r = (r_u8 as f32) / 255.0;
       let g = (g_u8 as f32) / 255.0;
       let b = (b_u8 as f32) / 255.0;
-      let lum = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+      let lum = Color::luma_709(r, g, b);
       // exclude very dark or bright or clipped pixels
       if lum < midtone_low || lum > midtone_high {
         return (0.0f64, 0.0f64, 0u64);
@@ -70,7 +64,7 @@ fn apply_auto_color(p_image: &mut Image) {
 
   // If there are very few midtone samples we avoid neutralizing (prevents noise)
   let min_midtone_samples: u64 = 5;
-  let perform_neutralize = midtone_count >= min_midtone_samples;
+  let perform_neutralize = p_snap_neutral_midtones && midtone_count >= min_midtone_samples;
 
   // Third pass: produce final pixels with levels (via LUT) and neutralization applied
   out.par_chunks_mut(4).enumerate().for_each(|(idx, dst_px)| {
@@ -90,7 +84,7 @@ fn apply_auto_color(p_image: &mut Image) {
     let r_m = (r_u8 as f32) / 255.0;
     let g_m = (g_u8 as f32) / 255.0;
     let b_m = (b_u8 as f32) / 255.0;
-    let lum = 0.2126 * r_m + 0.7152 * g_m + 0.0722 * b_m;
+    let lum = Color::luma_709(r_m, g_m, b_m);
     // Apply neutralization only for midtones; optionally ramp at edges
     let mut applied_a_lab = a_lab as f64;
     let mut applied_b_lab = b_lab as f64;
@@ -119,12 +113,28 @@ fn apply_auto_color(p_image: &mut Image) {
 /// Applies an auto color adjustment to the image.
 /// This function analyzes the image's histogram to perform
 /// levels stretching and color neutralization.
+/// It clips 0.5% of the pixels from each tail and snaps neutral midtones.
 /// - `p_image`: The image to adjust.
 /// - `p_options`: Options to apply the adjustment.
 pub fn auto_color<'a>(p_image: impl Into<ImageRef<'a>>, p_options: impl Into<Options>) {
+  auto_color_ex(p_image, DEFAULT_CLIP_PERCENT, DEFAULT_CLIP_PERCENT, true, p_options);
+}
+
+/// Applies an auto color adjustment to the image with explicit clip percentages.
+/// - `p_image`: The image to adjust.
+/// - `p_shadow_clip`: The percentage of pixels per channel clipped to black, from `0.0` to `50.0`.
+/// - `p_highlight_clip`: The percentage of pixels per channel clipped to white, from `0.0` to `50.0`.
+/// - `p_snap_neutral_midtones`: Whether to remove the average color cast from the midtones.
+/// - `p_options`: Options to apply the adjustment.
+pub fn auto_color_ex<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_shadow_clip: f32, p_highlight_clip: f32, p_snap_neutral_midtones: bool,
+  p_options: impl Into<Options>,
+) {
   let mut image_ref: ImageRef = p_image.into();
   let image = &mut image_ref as &mut Image;
-  apply_adjustment!(apply_auto_color, image, p_options, 1);
+  let shadow_clip = p_shadow_clip.clamp(0.0, 50.0) / 100.0;
+  let highlight_clip = p_highlight_clip.clamp(0.0, 50.0) / 100.0;
+  apply_adjustment!(apply_auto_color, image, p_options, 1, shadow_clip, highlight_clip, p_snap_neutral_midtones);
 }
 
 #[cfg(test)]
@@ -137,13 +147,30 @@ mod tests {
     let mut img = Image::new(5u32, 5u32);
     // Fill with near-black color that has a slight green cast. If we neutralize
     // midtones incorrectly, these pixels could become tinted. They should remain dark.
-    img.clear_color(Color::from_rgba(6, 4, 5, 255));
+    img.fill_solid(Color::from_rgba(6, 4, 5, 255));
     // Apply auto color with default options
     auto_color(&mut img, None);
     let (r, g, b, _a) = img.get_pixel(2, 2).unwrap();
     // Assert maximum channel remains small (no hue pops)
     assert!(r <= 12 && g <= 12 && b <= 12, "Dark pixel got too bright: {},{},{}", r, g, b);
   }
+
+  #[test]
+  fn auto_color_ex_snaps_neutral_midtones_only_when_asked() {
+    // Black and white anchor the levels so the warm midtones keep their cast after the stretch
+    let mut img = Image::new(10u32, 10u32);
+    img.fill_solid(Color::from_rgba(190, 175, 160, 255));
+    img.set_pixel(0, 0, (0u8, 0u8, 0u8, 255u8));
+    img.set_pixel(9, 9, (255u8, 255u8, 255u8, 255u8));
+    let mut snapped = img.clone();
+    auto_color_ex(&mut img, 0.0, 0.0, false, None);
+    auto_color_ex(&mut snapped, 0.0, 0.0, true, None);
+    let (r, _, b, _) = img.get_pixel(5, 5).unwrap();
+    assert!(r as i32 - b as i32 > 20, "cast should remain: {},{}", r, b);
+    let (r, _, b, _) = snapped.get_pixel(5, 5).unwrap();
+    assert!((r as i32 - b as i32).abs() < 6, "cast should be removed: {},{}", r, b);
+  }
+
   #[test]
   fn debug_lab_roundtrip() {
     // Sanity check: converting a near-black value to Lab and back should produce a small color
@@ -5,21 +5,18 @@ use rayon::prelude::*;
 
 use crate::apply_adjustment;
 
-fn apply_auto_tone(p_image: &mut Image) {
+fn apply_auto_tone(p_image: &mut Image, p_shadow_clip: f32, p_highlight_clip: f32) {
   let (width, height) = p_image.dimensions::<i32>();
   let src = p_image.rgba();
   let mut out = vec![0u8; (width * height * 4) as usize];
 
-  // Parameters: default to 0.5% clip per tail (0.005)
-  let clip_fraction: f32 = 0.005;
-
   // Compute histogram skipping fully-transparent pixels
   let hist = Histogram::from_image_skip_transparent(p_image);
 
   // Build per-channel levels LUTs using histogram helpers
-  let lut_r = hist.red_levels_lut(clip_fraction);
-  let lut_g = hist.green_levels_lut(clip_fraction);
-  let lut_b = hist.blue_levels_lut(clip_fraction);
+  let lut_r = hist.levels_lut_from_slice_split(hist.red(), p_shadow_clip, p_highlight_clip);
+  let lut_g = hist.levels_lut_from_slice_split(hist.green(), p_shadow_clip, p_highlight_clip);
+  let lut_b = hist.levels_lut_from_slice_split(hist.blue(), p_shadow_clip, p_highlight_clip);
 
   // Apply the per-channel LUT transform in parallel
   out.par_chunks_mut(4).enumerate().for_each(|(idx, dst_px)| {
@@ -44,10 +41,31 @@ fn apply_auto_tone(p_image: &mut Image) {
   p_image.set_rgba(&out);
 }
 
+/// The percentage of pixels clipped from each tail by `auto_tone` and `auto_color`.
+pub(crate) const DEFAULT_CLIP_PERCENT: f32 = 0.5;
+
+/// Applies an auto tone adjustment to the image.
+/// Each channel is stretched so its darkest and brightest pixels reach black and white, clipping 0.5% of the
+/// pixels from each tail.
+/// - `p_image`: The image to adjust.
+/// - `p_options`: Options to apply the adjustment.
 pub fn auto_tone<'a>(p_image: impl Into<ImageRef<'a>>, p_options: impl Into<Options>) {
+  auto_tone_ex(p_image, DEFAULT_CLIP_PERCENT, DEFAULT_CLIP_PERCENT, p_options);
+}
+
+/// Applies an auto tone adjustment to the image with explicit clip percentages.
+/// - `p_image`: The image to adjust.
+/// - `p_shadow_clip`: The percentage of pixels per channel clipped to black, from `0.0` to `50.0`.
+/// - `p_highlight_clip`: The percentage of pixels per channel clipped to white, from `0.0` to `50.0`.
+/// - `p_options`: Options to apply the adjustment.
+pub fn auto_tone_ex<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_shadow_clip: f32, p_highlight_clip: f32, p_options: impl Into<Options>,
+) {
   let mut image_ref: ImageRef = p_image.into();
   let image = &mut image_ref as &mut Image;
-  apply_adjustment!(apply_auto_tone, image, p_options, 1);
+  let shadow_clip = p_shadow_clip.clamp(0.0, 50.0) / 100.0;
+  let highlight_clip = p_highlight_clip.clamp(0.0, 50.0) / 100.0;
+  apply_adjustment!(apply_auto_tone, image, p_options, 1, shadow_clip, highlight_clip);
 }
 
 #[cfg(test)]
@@ -76,4 +94,35 @@ mod tests {
     assert!(r1 <= 5, "low value not mapped to near 0: {}", r1);
     assert!(r2 >= 250, "high value not mapped to near 255: {}", r2);
   }
+
+  #[test]
+  fn auto_tone_ex_clips_tails_by_percentage() {
+    // A red ramp where each value 0..100 appears once
+    let mut img = Image::new(100u32, 1u32);
+    for x in 0..100 {
+      img.set_pixel(x, 0, (x as u8 + 50, 128u8, 128u8, 255u8));
+    }
+    let mut clipped = img.clone();
+    auto_tone_ex(&mut img, 1.0, 1.0, None);
+    auto_tone_ex(&mut clipped, 1.0, 20.0, None);
+    // Clipping 1% only sends the brightest pixel to white
+    assert_eq!(img.get_pixel(99, 0).unwrap().0, 255);
+    assert!(img.get_pixel(80, 0).unwrap().0 < 255);
+    // Clipping 20% of the highlights sends the top fifth of the ramp to white
+    assert_eq!(clipped.get_pixel(80, 0).unwrap().0, 255);
+    assert!(clipped.get_pixel(79, 0).unwrap().0 < 255);
+    assert_eq!(clipped.get_pixel(0, 0).unwrap().0, 0);
+  }
+
+  #[test]
+  fn auto_tone_ex_stretches_without_clipping() {
+    let mut img = Image::new(100u32, 1u32);
+    for x in 0..100 {
+      img.set_pixel(x, 0, (x as u8 + 50, 128u8, 128u8, 255u8));
+    }
+    auto_tone_ex(&mut img, 0.0, 0.0, None);
+    assert_eq!(img.get_pixel(0, 0).unwrap().0, 0);
+    assert_eq!(img.get_pixel(99, 0).unwrap().0, 255);
+    assert!(img.get_pixel(98, 0).unwrap().0 < 255);
+  }
 }
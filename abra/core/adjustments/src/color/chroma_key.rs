@@ -0,0 +1,153 @@
+use abra_core::{Color, Image, ImageRef};
+use options::Options;
+use rayon::prelude::*;
+
+use crate::apply_adjustment;
+
+/// Converts a color to its blue-difference and red-difference chroma (BT.601), centered on 0.
+fn chroma(p_r: f32, p_g: f32, p_b: f32) -> (f32, f32) {
+  (-0.168736 * p_r - 0.331264 * p_g + 0.5 * p_b, 0.5 * p_r - 0.418688 * p_g - 0.081312 * p_b)
+}
+
+fn apply_chroma_key(p_image: &mut Image, p_key: Color, p_tolerance: f32, p_softness: f32) {
+  let (key_cb, key_cr) = chroma(p_key.r as f32, p_key.g as f32, p_key.b as f32);
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+
+  pixels.par_chunks_mut(4).for_each(|pixel| {
+    let (cb, cr) = chroma(pixel[0] as f32, pixel[1] as f32, pixel[2] as f32);
+    // Comparing chroma only makes the key robust to shadows and uneven lighting on the backdrop.
+    let distance = (cb - key_cb).hypot(cr - key_cr) / 255.0;
+    let opacity = if distance <= p_tolerance {
+      0.0
+    } else if distance >= p_tolerance + p_softness {
+      1.0
+    } else {
+      let t = (distance - p_tolerance) / p_softness;
+      t * t * (3.0 - 2.0 * t)
+    };
+    pixel[3] = (pixel[3] as f32 * opacity).round() as u8;
+  });
+}
+
+fn apply_suppress_spill(p_image: &mut Image, p_key: Color, p_amount: f32) {
+  let (key_cb, key_cr) = chroma(p_key.r as f32, p_key.g as f32, p_key.b as f32);
+  let key_length = key_cb.hypot(key_cr);
+  if key_length <= f32::EPSILON {
+    // A gray key has no hue that could spill.
+    return;
+  }
+  let (dir_cb, dir_cr) = (key_cb / key_length, key_cr / key_length);
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+
+  pixels.par_chunks_mut(4).for_each(|pixel| {
+    let (r, g, b) = (pixel[0] as f32, pixel[1] as f32, pixel[2] as f32);
+    let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+    let (cb, cr) = chroma(r, g, b);
+    // Remove the part of the pixel's chroma that points toward the key, keeping its brightness.
+    let spill = (cb * dir_cb + cr * dir_cr).max(0.0) * p_amount;
+    let (cb, cr) = (cb - dir_cb * spill, cr - dir_cr * spill);
+    pixel[0] = (luma + 1.402 * cr).round().clamp(0.0, 255.0) as u8;
+    pixel[1] = (luma - 0.344136 * cb - 0.714136 * cr).round().clamp(0.0, 255.0) as u8;
+    pixel[2] = (luma + 1.772 * cb).round().clamp(0.0, 255.0) as u8;
+  });
+}
+
+/// Removes a solid-colored background, such as a green screen, by making pixels close to the key color transparent.
+/// Only the hue and saturation are compared, so shadows on the backdrop are keyed out as well.
+/// - `p_image`: The image to key.
+/// - `p_key`: The background color to remove.
+/// - `p_tolerance`: How far a color may be from the key (0-1) and still become fully transparent.
+/// - `p_softness`: The width of the falloff (0-1) past the tolerance over which pixels fade back to opaque,
+///   which avoids jagged edges.
+/// - `p_options`: Options for applying the adjustment.
+pub fn chroma_key<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_key: Color, p_tolerance: f32, p_softness: f32, p_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let tolerance = p_tolerance.clamp(0.0, 1.0);
+  let softness = p_softness.clamp(0.0, 1.0);
+  apply_adjustment!(apply_chroma_key, image, p_options, 0, p_key, tolerance, softness);
+}
+
+/// Removes the color cast that a backdrop reflects onto the subject (spill), e.g. the green fringe around hair
+/// after keying a green screen. Typically used after `chroma_key` with the same key color.
+/// - `p_image`: The image to correct.
+/// - `p_key`: The backdrop color whose hue should be removed.
+/// - `p_amount`: How much of the spill to remove, between 0 and 1.
+/// - `p_options`: Options for applying the adjustment.
+pub fn suppress_spill<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_key: Color, p_amount: f32, p_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let amount = p_amount.clamp(0.0, 1.0);
+  apply_adjustment!(apply_suppress_spill, image, p_options, 0, p_key, amount);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A green backdrop with a shadow on the left and a skin-colored subject in the middle.
+  fn green_screen() -> Image {
+    let mut image = Image::new(20, 20);
+    for y in 0..20u32 {
+      for x in 0..20u32 {
+        let pixel = if (6..14).contains(&x) && (6..14).contains(&y) {
+          (224, 172, 138, 255)
+        } else if x < 4 {
+          (0, 120, 0, 255)
+        } else {
+          (0, 200, 0, 255)
+        };
+        image.set_pixel(x, y, pixel);
+      }
+    }
+    image
+  }
+
+  #[test]
+  fn keys_out_green_and_keeps_subject() {
+    let mut image = green_screen();
+    apply_chroma_key(&mut image, Color::from_rgb(0, 200, 0), 0.2, 0.1);
+    for y in 0..20u32 {
+      for x in 0..20u32 {
+        let alpha = image.get_pixel(x, y).unwrap().3;
+        let is_subject = (6..14).contains(&x) && (6..14).contains(&y);
+        assert_eq!(alpha, if is_subject { 255 } else { 0 }, "alpha at ({}, {})", x, y);
+      }
+    }
+    // Colors are kept so the subject can be composited as-is.
+    assert_eq!(image.get_pixel(10, 10), Some((224, 172, 138, 255)));
+  }
+
+  #[test]
+  fn softness_fades_edges() {
+    let mut image = Image::new(1, 1);
+    // Halfway between the green key and a neutral gray.
+    image.set_pixel(0, 0, (64, 160, 64, 255));
+    apply_chroma_key(&mut image, Color::from_rgb(0, 200, 0), 0.1, 0.3);
+    let alpha = image.get_pixel(0, 0).unwrap().3;
+    assert!(alpha > 0 && alpha < 255, "alpha {}", alpha);
+  }
+
+  #[test]
+  fn spill_suppression_removes_green_cast() {
+    let mut image = Image::new(2, 1);
+    image.set_pixel(0, 0, (150, 190, 120, 255));
+    image.set_pixel(1, 0, (200, 60, 60, 255));
+    apply_suppress_spill(&mut image, Color::from_rgb(0, 200, 0), 1.0);
+    let (r, g, b, _) = image.get_pixel(0, 0).unwrap();
+    assert!(g < 190 && g <= r.max(b) + 2, "({}, {}, {})", r, g, b);
+    // Colors without any green in their hue are left alone.
+    let (r, g, b, _) = image.get_pixel(1, 0).unwrap();
+    assert!(r.abs_diff(200) <= 1 && g.abs_diff(60) <= 1 && b.abs_diff(60) <= 1, "({}, {}, {})", r, g, b);
+  }
+}
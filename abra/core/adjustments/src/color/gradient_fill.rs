@@ -0,0 +1,206 @@
+use abra_core::image::apply_area::process_image_with_origin;
+use abra_core::{Gradient, Image, ImageRef, Path, PointF};
+use options::Options;
+use rayon::prelude::*;
+
+/// Paints a gradient over the image with source-over compositing.
+/// - `p_origin`: The position of the image's top-left pixel in image space.
+/// - `p_time`: Maps an image-space position to the gradient time at that position.
+fn paint_gradient<F>(p_image: &mut Image, p_gradient: &Gradient, p_origin: (i32, i32), p_time: F)
+where
+  F: Fn(f32, f32) -> f32 + Sync,
+{
+  let (width, _) = p_image.dimensions::<usize>();
+  if width == 0 {
+    return;
+  }
+  let (origin_x, origin_y) = (p_origin.0 as f32, p_origin.1 as f32);
+  let mut pixels = p_image.rgba().to_vec();
+  pixels.par_chunks_mut(width * 4).enumerate().for_each(|(y, row)| {
+    for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+      // Sample at the pixel center in image space so the gradient lines up regardless of the area.
+      let time = p_time(origin_x + x as f32 + 0.5, origin_y + y as f32 + 0.5);
+      let (r, g, b, a) = p_gradient.get_color(time);
+      let src_alpha = a as f32 / 255.0;
+      let dst_alpha = pixel[3] as f32 / 255.0;
+      let out_alpha = src_alpha + dst_alpha * (1.0 - src_alpha);
+      if out_alpha <= 0.0 {
+        pixel.copy_from_slice(&[0, 0, 0, 0]);
+        continue;
+      }
+      for (channel, src) in pixel.iter_mut().zip([r, g, b]) {
+        let value = (src as f32 * src_alpha + *channel as f32 * dst_alpha * (1.0 - src_alpha)) / out_alpha;
+        *channel = value.round().clamp(0.0, 255.0) as u8;
+      }
+      pixel[3] = (out_alpha * 255.0).round() as u8;
+    }
+  });
+  p_image.set_rgba_owned(pixels);
+}
+
+/// The gradient time of a point for a radial gradient: `0.0` at the focal point and `1.0` on the circle,
+/// measured along the ray from the focal point through the point.
+fn radial_time(p_x: f32, p_y: f32, p_center: PointF, p_radius: f32, p_focal: PointF) -> f32 {
+  let (dx, dy) = (p_x - p_focal.x, p_y - p_focal.y);
+  let distance = dx.hypot(dy);
+  if distance == 0.0 {
+    return 0.0;
+  }
+  let (dir_x, dir_y) = (dx / distance, dy / distance);
+  // Where the ray leaves the circle: solve |focal + s * dir - center| = radius for the positive s.
+  let (fx, fy) = (p_focal.x - p_center.x, p_focal.y - p_center.y);
+  let b = dir_x * fx + dir_y * fy;
+  let c = fx * fx + fy * fy - p_radius * p_radius;
+  let edge = -b + (b * b - c).max(0.0).sqrt();
+  if edge <= 0.0 { 1.0 } else { distance / edge }
+}
+
+/// Fills the image with a linear gradient that runs along a path, painted over the existing pixels.
+/// Pixels before the start of the path get the first color of the gradient and pixels past its end the last.
+/// - `p_image`: The image to fill.
+/// - `p_path`: The direction of the gradient in image coordinates, usually a line from the first to the last color.
+/// - `p_gradient`: The gradient to fill with.
+/// - `p_apply_options`: Options to limit the fill to an area or mask. The path is still in the coordinates of
+///   the whole image, so a gradient across a shape's bounds only covers the shape.
+pub fn linear_gradient<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_path: impl Into<Path>, p_gradient: Gradient, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let path = p_path.into();
+  let options = p_apply_options.into();
+  let ctx = options::get_ctx(options.as_ref());
+  process_image_with_origin(image, ctx, 0, |img, origin| {
+    paint_gradient(img, &p_gradient, origin, |x, y| path.closest_time(x, y));
+  });
+}
+
+/// Fills the image with a radial gradient, painted over the existing pixels.
+/// The first color of the gradient is at the focal point and the last on the circle, and everything outside the
+/// circle gets the last color. Moving the focal point off center gives the off-center highlight of a lit sphere.
+/// - `p_image`: The image to fill.
+/// - `p_center`: The center of the circle in image coordinates.
+/// - `p_radius`: The radius of the circle in pixels.
+/// - `p_focal`: Where the gradient starts. If None, it starts at the center. Focal points outside the circle are
+///   moved just inside its edge.
+/// - `p_gradient`: The gradient to fill with.
+/// - `p_apply_options`: Options to limit the fill to an area or mask. The circle is still in the coordinates of
+///   the whole image.
+pub fn radial_gradient<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_center: impl Into<PointF>, p_radius: f32, p_focal: impl Into<Option<PointF>>,
+  p_gradient: Gradient, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let center = p_center.into();
+  let radius = p_radius.max(f32::EPSILON);
+  let mut focal = p_focal.into().unwrap_or(center);
+  // Keep the focal point inside the circle so every ray from it reaches the edge.
+  let (fx, fy) = (focal.x - center.x, focal.y - center.y);
+  let offset = fx.hypot(fy);
+  let max_offset = radius * 0.99;
+  if offset > max_offset {
+    focal = PointF::new(center.x + fx / offset * max_offset, center.y + fy / offset * max_offset);
+  }
+  let options = p_apply_options.into();
+  let ctx = options::get_ctx(options.as_ref());
+  process_image_with_origin(image, ctx, 0, |img, origin| {
+    paint_gradient(img, &p_gradient, origin, |x, y| radial_time(x, y, center, radius, focal));
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::{Area, Color};
+  use options::ApplyOptions;
+
+  fn black_to_white() -> Gradient {
+    Gradient::from_to(Color::from_rgba(0, 0, 0, 255), Color::from_rgba(255, 255, 255, 255))
+  }
+
+  #[test]
+  fn fills_along_the_path() {
+    let mut image = Image::new_from_color(20, 4, Color::from_rgba(255, 0, 0, 255));
+    linear_gradient(&mut image, Path::line((0.0, 0.0), (20.0, 0.0)), black_to_white(), None);
+    let left = image.get_pixel(0, 2).unwrap();
+    let middle = image.get_pixel(10, 2).unwrap();
+    let right = image.get_pixel(19, 2).unwrap();
+    assert!(left.0 < 10 && left.1 < 10, "{:?}", left);
+    assert!((120..=145).contains(&middle.0) && middle.0 == middle.1, "{:?}", middle);
+    assert!(right.0 > 245, "{:?}", right);
+  }
+
+  #[test]
+  fn area_clips_coverage_but_keeps_the_direction() {
+    let mut full = Image::new_from_color(20, 20, Color::from_rgba(255, 0, 0, 255));
+    linear_gradient(&mut full, Path::line((0.0, 0.0), (20.0, 0.0)), black_to_white(), None);
+
+    let mut clipped = Image::new_from_color(20, 20, Color::from_rgba(255, 0, 0, 255));
+    let area = Area::rect((10.0, 5.0), (8.0, 10.0));
+    linear_gradient(
+      &mut clipped,
+      Path::line((0.0, 0.0), (20.0, 0.0)),
+      black_to_white(),
+      ApplyOptions::new().with_area(area),
+    );
+    // Inside the area the colors match the full fill, so the gradient is not restarted at the area's edge.
+    assert_eq!(clipped.get_pixel(12, 10), full.get_pixel(12, 10));
+    assert_eq!(clipped.get_pixel(16, 8), full.get_pixel(16, 8));
+    // Outside the area nothing is painted.
+    assert_eq!(clipped.get_pixel(3, 10), Some((255, 0, 0, 255)));
+    assert_eq!(clipped.get_pixel(12, 2), Some((255, 0, 0, 255)));
+  }
+
+  #[test]
+  fn transparent_stops_let_the_image_show_through() {
+    let mut image = Image::new_from_color(10, 2, Color::from_rgba(255, 0, 0, 255));
+    let gradient = Gradient::from_to(Color::from_rgba(0, 0, 255, 255), Color::from_rgba(0, 0, 255, 0));
+    linear_gradient(&mut image, Path::line((0.0, 0.0), (10.0, 0.0)), gradient, None);
+    assert!(image.get_pixel(0, 0).unwrap().2 > 230);
+    let (r, _, b, a) = image.get_pixel(9, 0).unwrap();
+    assert!(r > 200 && b < 50 && a == 255, "{} {} {}", r, b, a);
+  }
+
+  fn white_to_black() -> Gradient {
+    Gradient::from_to(Color::from_rgba(255, 255, 255, 255), Color::from_rgba(0, 0, 0, 255))
+  }
+
+  #[test]
+  fn centered_radial_gradient_is_symmetric() {
+    let mut image = Image::new_from_color(21, 21, Color::from_rgba(255, 0, 0, 255));
+    radial_gradient(&mut image, (10.5, 10.5), 10.0, None, white_to_black(), None);
+    assert_eq!(image.get_pixel(10, 10), Some((255, 255, 255, 255)));
+    // The same distance from the center in any direction gives the same color.
+    let right = image.get_pixel(15, 10).unwrap();
+    assert_eq!(image.get_pixel(5, 10), Some(right));
+    assert_eq!(image.get_pixel(10, 5), Some(right));
+    assert_eq!(image.get_pixel(10, 15), Some(right));
+    assert!((100..=150).contains(&right.0), "{:?}", right);
+    // Outside the circle the last color is used.
+    assert_eq!(image.get_pixel(0, 0), Some((0, 0, 0, 255)));
+  }
+
+  #[test]
+  fn focal_point_moves_the_highlight() {
+    let mut image = Image::new_from_color(21, 21, Color::from_rgba(255, 0, 0, 255));
+    radial_gradient(&mut image, (10.5, 10.5), 10.0, PointF::new(6.5, 10.5), white_to_black(), None);
+    assert_eq!(image.get_pixel(6, 10), Some((255, 255, 255, 255)));
+    // The falloff is compressed toward the near edge and stretched toward the far one.
+    let near = image.get_pixel(3, 10).unwrap().0;
+    let far = image.get_pixel(9, 10).unwrap().0;
+    assert!(near < far, "{} >= {}", near, far);
+    assert!(image.get_pixel(19, 10).unwrap().0 < 30);
+  }
+
+  #[test]
+  fn radial_time_reaches_one_on_the_circle() {
+    let center = PointF::new(0.0, 0.0);
+    let focal = PointF::new(5.0, 0.0);
+    assert_eq!(radial_time(5.0, 0.0, center, 10.0, focal), 0.0);
+    assert!((radial_time(10.0, 0.0, center, 10.0, focal) - 1.0).abs() < 1e-5);
+    assert!((radial_time(-10.0, 0.0, center, 10.0, focal) - 1.0).abs() < 1e-5);
+    assert!((radial_time(0.0, 10.0, center, 10.0, focal) - 1.0).abs() < 1e-5);
+    assert!((radial_time(-2.5, 0.0, center, 10.0, focal) - 0.5).abs() < 1e-5);
+  }
+}
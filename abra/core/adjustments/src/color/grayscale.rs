@@ -1,17 +1,59 @@
-use abra_core::{Image, ImageRef};
+use abra_core::{Color, Image, ImageRef, linear_f32_to_srgb_u8, srgb_u8_to_linear_f32};
 use options::Options;
 
 use crate::apply_adjustment;
 
+/// How the gray value of a pixel is computed from its color. The method changes how bright each color ends up,
+/// which sets much of the mood of a black and white conversion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LumaMethod {
+  /// The BT.709 (HDTV and sRGB) weights applied to the encoded values: `0.2126 R + 0.7152 G + 0.0722 B`.
+  Rec709,
+  /// The BT.601 (SDTV) weights applied to the encoded values: `0.299 R + 0.587 G + 0.114 B`.
+  Rec601,
+  /// The average of the three channels, which makes saturated colors brighter than they look.
+  Average,
+  /// The HSL lightness, halfway between the brightest and darkest channel.
+  Lightness,
+  /// The BT.709 weights applied in linear light, which matches the physical brightness of the color.
+  Luminosity,
+  /// Custom red, green and blue weights applied to the encoded values. Weights that add up to 1 keep white white.
+  Custom(f32, f32, f32),
+}
+
+impl LumaMethod {
+  /// Returns the gray value of a color.
+  fn gray(&self, p_r: u8, p_g: u8, p_b: u8) -> u8 {
+    let weighted = |weights: (f32, f32, f32)| {
+      (p_r as f32 * weights.0 + p_g as f32 * weights.1 + p_b as f32 * weights.2)
+        .round()
+        .clamp(0.0, 255.0) as u8
+    };
+    match *self {
+      LumaMethod::Rec709 => Color::luma_709(p_r as f32, p_g as f32, p_b as f32)
+        .round()
+        .clamp(0.0, 255.0) as u8,
+      LumaMethod::Rec601 => weighted((0.299, 0.587, 0.114)),
+      LumaMethod::Average => ((p_r as u16 + p_g as u16 + p_b as u16) as f32 / 3.0).round() as u8,
+      LumaMethod::Lightness => {
+        let (max, min) = (p_r.max(p_g).max(p_b), p_r.min(p_g).min(p_b));
+        ((max as u16 + min as u16) as f32 / 2.0).round() as u8
+      }
+      LumaMethod::Luminosity => linear_f32_to_srgb_u8(Color::luma_709(
+        srgb_u8_to_linear_f32(p_r),
+        srgb_u8_to_linear_f32(p_g),
+        srgb_u8_to_linear_f32(p_b),
+      )),
+      LumaMethod::Custom(r, g, b) => weighted((r, g, b)),
+    }
+  }
+}
+
 /// Converts an image to grayscale
-fn apply_grayscale(image_ref: &mut Image) {
+fn apply_grayscale(image_ref: &mut Image, p_method: LumaMethod) {
   image_ref.mut_pixels(|mut pixel| {
-    let r = pixel[0] as f32;
-    let g = pixel[1] as f32;
-    let b = pixel[2] as f32;
-
     // Map the pixel to a grayscale value.
-    let gray = (r * 0.299 + g * 0.587 + b * 0.114) as u8;
+    let gray = p_method.gray(pixel[0], pixel[1], pixel[2]);
 
     // Set the pixel to the grayscale value.
     pixel[0] = gray;
@@ -20,8 +62,54 @@ fn apply_grayscale(image_ref: &mut Image) {
   });
 }
 
+/// Converts an image to grayscale using the BT.709 weights.
+/// - `image`: The image to convert.
+/// - `p_options`: Options for applying the adjustment.
 pub fn grayscale<'a>(image: impl Into<ImageRef<'a>>, p_options: impl Into<Options>) {
-  let mut image_ref: ImageRef = image.into();
+  grayscale_with(image, LumaMethod::Rec709, p_options);
+}
+
+/// Converts an image to grayscale using the given method to weigh the colors.
+/// - `p_image`: The image to convert.
+/// - `p_method`: How the gray value is computed from each color.
+/// - `p_options`: Options for applying the adjustment.
+pub fn grayscale_with<'a>(p_image: impl Into<ImageRef<'a>>, p_method: LumaMethod, p_options: impl Into<Options>) {
+  let mut image_ref: ImageRef = p_image.into();
   let image = &mut image_ref as &mut Image;
-  apply_adjustment!(apply_grayscale, image, p_options, 1);
+  apply_adjustment!(apply_grayscale, image, p_options, 1, p_method);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn saturated() -> Image {
+    let mut image = Image::new(3, 1);
+    image.set_pixel(0, 0, (255, 0, 0, 255));
+    image.set_pixel(1, 0, (0, 255, 0, 255));
+    image.set_pixel(2, 0, (0, 0, 255, 128));
+    image
+  }
+
+  fn grays(p_method: LumaMethod) -> Vec<u8> {
+    let mut image = saturated();
+    grayscale_with(&mut image, p_method, None);
+    (0..3).map(|x| image.get_pixel(x, 0).unwrap().0).collect()
+  }
+
+  #[test]
+  fn methods_weigh_colors_differently() {
+    assert_eq!(grays(LumaMethod::Rec709), vec![54, 182, 18]);
+    assert_eq!(grays(LumaMethod::Average), vec![85, 85, 85]);
+    assert_eq!(grays(LumaMethod::Rec601), vec![76, 150, 29]);
+    assert_eq!(grays(LumaMethod::Lightness), vec![128, 128, 128]);
+    assert_eq!(grays(LumaMethod::Custom(1.0, 0.0, 0.0)), vec![255, 0, 0]);
+    // In linear light the weights give brighter results for saturated colors.
+    let luminosity = grays(LumaMethod::Luminosity);
+    assert!(luminosity[0] > 54 && luminosity[1] > 182, "{:?}", luminosity);
+
+    let mut image = saturated();
+    grayscale(&mut image, None);
+    assert_eq!(image.get_pixel(2, 0), Some((18, 18, 18, 128)));
+  }
 }
@@ -1,18 +1,27 @@
 mod auto_color;
 mod auto_tone;
+mod chroma_key;
+mod gradient_fill;
 mod gradient_map;
 mod grayscale;
 mod invert;
 mod opacity;
 mod posterize;
+mod replace_color;
 mod threshold;
+mod white_balance;
 
-pub use auto_color::auto_color;
-pub use auto_tone::auto_tone;
+pub use auto_color::{auto_color, auto_color_ex};
+pub use auto_tone::{auto_tone, auto_tone_ex};
+pub use chroma_key::{chroma_key, suppress_spill};
+pub use gradient_fill::{linear_gradient, radial_gradient};
 pub use gradient_map::gradient_map;
 pub use gradient_map::gradient_map_reverse;
-pub use grayscale::grayscale;
+pub use grayscale::{LumaMethod, grayscale, grayscale_with};
 pub use invert::invert;
 pub use opacity::reduce_opacity;
 pub use posterize::posterize;
-pub use threshold::threshold;
+pub use replace_color::{ColorRange, HslShift, replace_color};
+pub use threshold::{AdaptiveMethod, threshold, threshold_adaptive};
+pub(crate) use threshold::local_gaussian_mean;
+pub use white_balance::{white_balance, white_balance_from_gray};
@@ -0,0 +1,207 @@
+use abra_core::{Color, Image, ImageRef};
+use options::Options;
+use rayon::prelude::*;
+
+use crate::apply_adjustment;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A change in hue, saturation and lightness.
+pub struct HslShift {
+  /// The hue rotation in degrees.
+  pub hue: f32,
+  /// The saturation multiplier, where 1 is unchanged and 0 is gray.
+  pub saturation: f32,
+  /// The lightness offset between -1 and 1, where 0 is unchanged.
+  pub lightness: f32,
+}
+
+impl HslShift {
+  /// Creates a new shift.
+  /// - `p_hue`: The hue rotation in degrees.
+  /// - `p_saturation`: The saturation multiplier, where 1 is unchanged.
+  /// - `p_lightness`: The lightness offset between -1 and 1.
+  pub fn new(p_hue: f32, p_saturation: f32, p_lightness: f32) -> HslShift {
+    HslShift {
+      hue: p_hue,
+      saturation: p_saturation,
+      lightness: p_lightness,
+    }
+  }
+}
+
+impl Default for HslShift {
+  /// Creates a shift that leaves colors unchanged.
+  fn default() -> Self {
+    HslShift::new(0.0, 1.0, 0.0)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// How far a color may be from the target color and still be fully selected. Colors further away fade out of the
+/// selection over another half of each range.
+pub struct ColorRange {
+  /// The largest hue difference in degrees, between 0 and 180.
+  pub hue: f32,
+  /// The largest saturation difference, between 0 and 1.
+  pub saturation: f32,
+  /// The largest lightness difference, between 0 and 1.
+  pub lightness: f32,
+}
+
+impl ColorRange {
+  /// Creates a new range.
+  /// - `p_hue`: The largest hue difference in degrees, between 0 and 180.
+  /// - `p_saturation`: The largest saturation difference, between 0 and 1.
+  /// - `p_lightness`: The largest lightness difference, between 0 and 1.
+  pub fn new(p_hue: f32, p_saturation: f32, p_lightness: f32) -> ColorRange {
+    ColorRange {
+      hue: p_hue.clamp(0.0, 180.0),
+      saturation: p_saturation.clamp(0.0, 1.0),
+      lightness: p_lightness.clamp(0.0, 1.0),
+    }
+  }
+}
+
+impl From<f32> for ColorRange {
+  /// Creates a range that selects by hue, with saturation and lightness ranges of a half, so grays, blacks and whites
+  /// are left out when the target is a saturated color.
+  fn from(p_hue: f32) -> Self {
+    ColorRange::new(p_hue, 0.5, 0.5)
+  }
+}
+
+/// A smooth 0 to 1 ramp between two edges. When the edges are equal the ramp is a step at the edge.
+fn smoothstep(p_edge0: f32, p_edge1: f32, p_value: f32) -> f32 {
+  if p_edge1 <= p_edge0 {
+    return if p_value <= p_edge0 { 0.0 } else { 1.0 };
+  }
+  let t = ((p_value - p_edge0) / (p_edge1 - p_edge0)).clamp(0.0, 1.0);
+  t * t * (3.0 - 2.0 * t)
+}
+
+/// How strongly a difference within a range is selected: fully up to the range, fading out over another half of it.
+fn falloff(p_range: f32, p_distance: f32) -> f32 {
+  1.0 - smoothstep(p_range, p_range * 1.5, p_distance)
+}
+
+/// Returns how strongly a color is selected, between 0 and 1.
+/// - `p_color`: The hue, saturation and lightness of the color.
+/// - `p_target`: The hue, saturation and lightness of the target color.
+/// - `p_range`: How far the color may be from the target.
+fn selection(p_color: (f32, f32, f32), p_target: (f32, f32, f32), p_range: ColorRange) -> f32 {
+  let distance = (p_color.0 - p_target.0).rem_euclid(360.0);
+  let hue_distance = distance.min(360.0 - distance);
+  falloff(p_range.hue, hue_distance)
+    * falloff(p_range.saturation, (p_color.1 - p_target.1).abs())
+    * falloff(p_range.lightness, (p_color.2 - p_target.2).abs())
+}
+
+fn apply_replace_color(p_image: &mut Image, p_target: Color, p_range: ColorRange, p_shift: HslShift) {
+  let target = p_target.hsl();
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+
+  pixels.par_chunks_mut(4).for_each(|pixel| {
+    let (h, s, l) = Color::from_rgb(pixel[0], pixel[1], pixel[2]).hsl();
+    let weight = selection((h, s, l), target, p_range);
+    if weight <= 0.0 {
+      return;
+    }
+    let hue = (h + p_shift.hue * weight).rem_euclid(360.0);
+    let saturation = (s * (1.0 + (p_shift.saturation - 1.0) * weight)).clamp(0.0, 1.0);
+    let lightness = (l + p_shift.lightness * weight).clamp(0.0, 1.0);
+    let color = Color::from_hsl(hue, saturation, lightness);
+    pixel[0] = color.r;
+    pixel[1] = color.g;
+    pixel[2] = color.b;
+  });
+}
+
+/// Shifts the hue, saturation and lightness of colors close to a target color, leaving other colors untouched,
+/// e.g. to turn red clothing blue without affecting skin tones.
+/// - `p_image`: The image to adjust.
+/// - `p_target`: The color to select.
+/// - `p_range`: How far a color may be from the target in hue, saturation and lightness and still be fully
+///   selected, or just a hue range in degrees. Colors fade out of the selection over another half of each range.
+/// - `p_result_shift`: The change applied to the selected colors.
+/// - `p_options`: Options for applying the adjustment.
+pub fn replace_color<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_target: Color, p_range: impl Into<ColorRange>, p_result_shift: HslShift,
+  p_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let range = p_range.into();
+  let range = ColorRange::new(range.hue, range.saturation, range.lightness);
+  apply_adjustment!(apply_replace_color, image, p_options, 0, p_target, range, p_result_shift);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rotates_selected_hue_only() {
+    let colors = [
+      (255, 0, 0),
+      (224, 172, 138),
+      (0, 0, 255),
+      (128, 128, 128),
+      (200, 20, 30),
+    ];
+    let mut image = Image::new(colors.len() as u32, 1);
+    for (x, (r, g, b)) in colors.iter().enumerate() {
+      image.set_pixel(x as u32, 0, (*r, *g, *b, 255));
+    }
+
+    apply_replace_color(&mut image, Color::red(), ColorRange::from(15.0), HslShift::new(120.0, 1.0, 0.0));
+
+    assert_eq!(image.get_pixel(0, 0), Some((0, 255, 0, 255)));
+    // Skin, blue and gray are outside of the selection.
+    assert_eq!(image.get_pixel(1, 0), Some((224, 172, 138, 255)));
+    assert_eq!(image.get_pixel(2, 0), Some((0, 0, 255, 255)));
+    assert_eq!(image.get_pixel(3, 0), Some((128, 128, 128, 255)));
+    // A darker red within the range turns green as well.
+    let (r, g, b, _) = image.get_pixel(4, 0).unwrap();
+    assert!(g > r && g > b, "({}, {}, {})", r, g, b);
+  }
+
+  #[test]
+  fn selection_falls_off_smoothly() {
+    let red = (0.0, 1.0, 0.5);
+    let range = ColorRange::from(20.0);
+    assert_eq!(selection((10.0, 1.0, 0.5), red, range), 1.0);
+    let partial = selection((25.0, 1.0, 0.5), red, range);
+    assert!(partial > 0.0 && partial < 1.0);
+    assert_eq!(selection((350.0, 1.0, 0.5), red, range), 1.0);
+    assert_eq!(selection((40.0, 1.0, 0.5), red, range), 0.0);
+    assert_eq!(selection((0.0, 0.0, 0.5), red, range), 0.0);
+  }
+
+  #[test]
+  fn saturation_and_lightness_ranges_limit_the_selection() {
+    let red = (0.0, 1.0, 0.5);
+    let narrow = ColorRange::new(20.0, 0.1, 0.1);
+    assert_eq!(selection((0.0, 0.95, 0.45), red, narrow), 1.0);
+    assert_eq!(selection((0.0, 0.5, 0.5), red, narrow), 0.0);
+    assert_eq!(selection((0.0, 1.0, 0.2), red, narrow), 0.0);
+    assert_eq!(selection((0.0, 0.5, 0.2), red, ColorRange::new(20.0, 1.0, 1.0)), 1.0);
+  }
+
+  #[test]
+  fn zero_ranges_select_only_the_target() {
+    let red = (0.0, 1.0, 0.5);
+    let exact = ColorRange::new(0.0, 0.0, 0.0);
+    assert_eq!(selection(red, red, exact), 1.0);
+    assert_eq!(selection((1.0, 1.0, 0.5), red, exact), 0.0);
+
+    let mut image = Image::new(2, 1);
+    image.set_pixel(0, 0, (255, 0, 0, 255));
+    image.set_pixel(1, 0, (255, 8, 0, 255));
+    apply_replace_color(&mut image, Color::red(), exact, HslShift::new(120.0, 1.0, 0.0));
+    assert_eq!(image.get_pixel(0, 0), Some((0, 255, 0, 255)));
+    assert_eq!(image.get_pixel(1, 0), Some((255, 8, 0, 255)));
+  }
+}
@@ -1,6 +1,19 @@
-use abra_core::{Image, ImageRef};
+use abra_core::{Image, ImageRef, gaussian_kernel};
+use options::Options;
 use rayon::prelude::*;
 
+use crate::apply_adjustment;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// How the local threshold of `threshold_adaptive` is computed from a pixel's neighborhood.
+pub enum AdaptiveMethod {
+  /// The plain average of the neighborhood.
+  #[default]
+  Mean,
+  /// A Gaussian-weighted average of the neighborhood, which favors nearby pixels and handles fine detail better.
+  Gaussian,
+}
+
 /// Apply a threshold to an image where all pixels above the threshold are set to white and all pixels below are set to black.
 /// * `image` - A mutable reference to the image to be processed.
 /// * `threshold` - The threshold value a value between 0 and 255.
@@ -26,3 +39,158 @@ pub fn threshold<'a>(p_image: impl Into<ImageRef<'a>>, p_threshold: u8) {
 
   // pixels already mutated in place on the image; no need to set back.
 }
+
+/// Computes the mean of every `p_block_size` square neighborhood using a summed-area table.
+fn local_mean(p_values: &[f32], p_width: usize, p_height: usize, p_block_size: usize) -> Vec<f32> {
+  let stride = p_width + 1;
+  let mut sums = vec![0.0f64; stride * (p_height + 1)];
+  for y in 0..p_height {
+    let mut row = 0.0f64;
+    for x in 0..p_width {
+      row += p_values[y * p_width + x] as f64;
+      sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row;
+    }
+  }
+
+  let radius = p_block_size / 2;
+  (0..p_width * p_height)
+    .into_par_iter()
+    .map(|i| {
+      let (x, y) = (i % p_width, i / p_width);
+      let (x0, x1) = (x.saturating_sub(radius), (x + radius + 1).min(p_width));
+      let (y0, y1) = (y.saturating_sub(radius), (y + radius + 1).min(p_height));
+      let sum = sums[y1 * stride + x1] - sums[y0 * stride + x1] - sums[y1 * stride + x0] + sums[y0 * stride + x0];
+      (sum / ((x1 - x0) * (y1 - y0)) as f64) as f32
+    })
+    .collect()
+}
+
+/// Computes the Gaussian-weighted mean of every `p_block_size` neighborhood with a separable blur.
+pub(crate) fn local_gaussian_mean(p_values: &[f32], p_width: usize, p_height: usize, p_block_size: usize) -> Vec<f32> {
+  let radius = (p_block_size / 2) as i32;
+  // The same sigma OpenCV derives from the block size.
+  let sigma = 0.3 * ((p_block_size as f32 - 1.0) * 0.5 - 1.0) + 0.8;
+  let kernel = gaussian_kernel(radius as u32, sigma);
+
+  let blur = |p_src: &[f32], p_step: (i32, i32)| -> Vec<f32> {
+    (0..p_width * p_height)
+      .into_par_iter()
+      .map(|i| {
+        let (x, y) = ((i % p_width) as i32, (i / p_width) as i32);
+        kernel
+          .iter()
+          .enumerate()
+          .map(|(k, weight)| {
+            let offset = k as i32 - radius;
+            let sx = (x + offset * p_step.0).clamp(0, p_width as i32 - 1) as usize;
+            let sy = (y + offset * p_step.1).clamp(0, p_height as i32 - 1) as usize;
+            p_src[sy * p_width + sx] * weight
+          })
+          .sum()
+      })
+      .collect()
+  };
+  blur(&blur(p_values, (1, 0)), (0, 1))
+}
+
+fn apply_threshold_adaptive(p_image: &mut Image, p_block_size: u32, p_offset: i32, p_method: AdaptiveMethod) {
+  let (width, height) = p_image.dimensions::<usize>();
+  if width == 0 || height == 0 {
+    return;
+  }
+  let values: Vec<f32> = p_image
+    .rgba()
+    .par_chunks(4)
+    .map(|pixel| (pixel[0] as f32 + pixel[1] as f32 + pixel[2] as f32) / 3.0)
+    .collect();
+  let block_size = p_block_size.max(3) as usize | 1;
+  let local = match p_method {
+    AdaptiveMethod::Mean => local_mean(&values, width, height, block_size),
+    AdaptiveMethod::Gaussian => local_gaussian_mean(&values, width, height, block_size),
+  };
+
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+  pixels.par_chunks_mut(4).enumerate().for_each(|(i, pixel)| {
+    let value = if values[i] > local[i] - p_offset as f32 { 255 } else { 0 };
+    pixel[0] = value;
+    pixel[1] = value;
+    pixel[2] = value;
+  });
+}
+
+/// Applies a threshold that adapts to the brightness around each pixel, so unevenly lit images such as scanned or
+/// photographed documents can be binarized. Pixels brighter than their local threshold become white and the
+/// others black.
+/// - `p_image`: The image to adjust.
+/// - `p_block_size`: The size of the square neighborhood in pixels. Even sizes are rounded up to the next odd size.
+///   It should be larger than the details to keep, e.g. the stroke width of text.
+/// - `p_offset`: The amount subtracted from the local average to get the threshold. Positive values keep flat
+///   areas white and suppress noise.
+/// - `p_method`: How the local average is computed.
+/// - `p_options`: Options for applying the adjustment.
+pub fn threshold_adaptive<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_block_size: u32, p_offset: i32, p_method: AdaptiveMethod,
+  p_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_adjustment!(
+    apply_threshold_adaptive,
+    image,
+    p_options,
+    p_block_size as i32 / 2,
+    p_block_size,
+    p_offset,
+    p_method
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A page lit from the left with dark square "letters" on it, returning the image and which pixels are ink.
+  fn unevenly_lit_page() -> (Image, Vec<bool>) {
+    let mut image = Image::new(96, 48);
+    let mut ink = Vec::new();
+    for y in 0..48u32 {
+      for x in 0..96u32 {
+        let paper = 70.0 + 180.0 * x as f32 / 95.0;
+        let is_ink = (4..7).contains(&(x % 12)) && (4..7).contains(&(y % 12));
+        let value = if is_ink { paper * 0.4 } else { paper } as u8;
+        image.set_pixel(x, y, (value, value, value, 255));
+        ink.push(is_ink);
+      }
+    }
+    (image, ink)
+  }
+
+  #[test]
+  fn adaptive_threshold_binarizes_uneven_lighting() {
+    for method in [AdaptiveMethod::Mean, AdaptiveMethod::Gaussian] {
+      let (mut image, ink) = unevenly_lit_page();
+      apply_threshold_adaptive(&mut image, 15, 10, method);
+      for (i, pixel) in image.rgba().chunks(4).enumerate() {
+        let expected = if ink[i] { 0 } else { 255 };
+        assert_eq!(pixel[0], expected, "{:?} pixel {} was wrong", method, i);
+        assert_eq!(pixel[3], 255);
+      }
+    }
+  }
+
+  #[test]
+  fn global_threshold_fails_on_uneven_lighting() {
+    let (mut image, ink) = unevenly_lit_page();
+    threshold(&mut image, 128);
+    let wrong = image
+      .rgba()
+      .chunks(4)
+      .enumerate()
+      .filter(|(i, pixel)| (pixel[0] == 0) != ink[*i])
+      .count();
+    assert!(wrong > 0);
+  }
+}
@@ -0,0 +1,128 @@
+use abra_core::{Color, Image, ImageRef, Point, linear_f32_to_srgb_u8, srgb_u8_to_linear_f32};
+use options::Options;
+use rayon::prelude::*;
+
+use crate::apply_adjustment;
+
+/// Scales the channels so that a neutral gray keeps its luminance.
+fn normalize_gains(p_gains: [f32; 3]) -> [f32; 3] {
+  let lum = Color::luma_709(p_gains[0], p_gains[1], p_gains[2]).max(f32::EPSILON);
+  [p_gains[0] / lum, p_gains[1] / lum, p_gains[2] / lum]
+}
+
+/// Multiplies each channel by its gain in linear light, like a camera's white balance.
+fn apply_white_balance(p_image: &mut Image, p_gains: [f32; 3]) {
+  let to_linear: Vec<f32> = (0..=255u8).map(srgb_u8_to_linear_f32).collect();
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+
+  pixels.par_chunks_mut(4).for_each(|pixel| {
+    for (channel, gain) in pixel.iter_mut().zip(p_gains) {
+      *channel = linear_f32_to_srgb_u8(to_linear[*channel as usize] * gain);
+    }
+  });
+}
+
+/// Corrects the white balance of an image by shifting its colors along the blue-yellow and green-magenta axes.
+/// The gray levels keep their brightness, so only the color cast changes.
+/// - `p_image`: The image to adjust.
+/// - `p_temperature`: The shift between blue (-100) and yellow (100). `0` leaves the image unchanged.
+/// - `p_tint`: The shift between green (-100) and magenta (100). `0` leaves the image unchanged.
+/// - `p_options`: Options for applying the adjustment.
+pub fn white_balance<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_temperature: f32, p_tint: f32, p_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let temperature = p_temperature.clamp(-100.0, 100.0) / 100.0;
+  let tint = p_tint.clamp(-100.0, 100.0) / 100.0;
+  // At the extremes red and blue move one stop apart, and green moves one stop against them.
+  let gains = normalize_gains([
+    (0.5 * temperature + 0.25 * tint).exp2(),
+    (-0.5 * tint).exp2(),
+    (-0.5 * temperature + 0.25 * tint).exp2(),
+  ]);
+  apply_adjustment!(apply_white_balance, image, p_options, 0, gains);
+}
+
+/// Corrects the white balance of an image so that the pixel at the sample point becomes a neutral gray of the
+/// same brightness, like picking a gray point with an eyedropper. All other pixels are shifted by the same amount.
+/// Nothing changes if the sample point is outside the image.
+/// - `p_image`: The image to adjust.
+/// - `p_sample_point`: The position of a pixel that should be gray.
+/// - `p_options`: Options for applying the adjustment.
+pub fn white_balance_from_gray<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_sample_point: impl Into<Point>, p_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let point: Point = p_sample_point.into();
+  if point.x() < 0 || point.y() < 0 {
+    return;
+  }
+  let Some((r, g, b, _)) = image.get_pixel(point.x() as u32, point.y() as u32) else {
+    return;
+  };
+
+  // Sample the full image before the options narrow it down to an area.
+  let sample = [r, g, b].map(|channel| srgb_u8_to_linear_f32(channel).max(1.0 / 255.0));
+  let gray = Color::luma_709(sample[0], sample[1], sample[2]);
+  let gains = [gray / sample[0], gray / sample[1], gray / sample[2]];
+  apply_adjustment!(apply_white_balance, image, p_options, 0, gains);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::Color;
+
+  #[test]
+  fn temperature_and_tint_shift_gray() {
+    let gray = Color::from_rgba(128, 128, 128, 255);
+    let balanced = |temperature: f32, tint: f32| {
+      let mut image = Image::new(2u32, 2u32);
+      image.fill_solid(gray);
+      white_balance(&mut image, temperature, tint, None);
+      let (r, g, b, _) = image.get_pixel(1, 1).unwrap();
+      (r as i32, g as i32, b as i32)
+    };
+
+    assert_eq!(balanced(0.0, 0.0), (128, 128, 128));
+    let (r, _, b) = balanced(50.0, 0.0);
+    assert!(r > 128 && b < 128 && r - b > 15, "warm should add yellow: {} {}", r, b);
+    let (r, _, b) = balanced(-50.0, 0.0);
+    assert!(r < 128 && b > 128 && b - r > 15, "cool should add blue: {} {}", r, b);
+    let (r, g, b) = balanced(0.0, 50.0);
+    assert!(g < r && g < b && (r - b).abs() <= 1, "magenta tint: {} {} {}", r, g, b);
+  }
+
+  #[test]
+  fn white_balance_from_gray_neutralizes_sample() {
+    // A warm cast on a gray card, a darker shadow of the same card and a white highlight
+    let mut image = Image::new(3u32, 1u32);
+    image.set_pixel(0, 0, (150u8, 128u8, 100u8, 255u8));
+    image.set_pixel(1, 0, (75u8, 64u8, 50u8, 255u8));
+    image.set_pixel(2, 0, (255u8, 240u8, 200u8, 255u8));
+    white_balance_from_gray(&mut image, (0, 0), None);
+
+    let (r, g, b, a) = image.get_pixel(0, 0).unwrap();
+    assert!(r.abs_diff(g) <= 1 && g.abs_diff(b) <= 1, "sample should be neutral: {} {} {}", r, g, b);
+    assert!(g.abs_diff(128) <= 8 && a == 255);
+    // The same cast at another brightness is neutralized too
+    let (r, g, b, _) = image.get_pixel(1, 0).unwrap();
+    assert!(r.abs_diff(g) <= 2 && g.abs_diff(b) <= 2, "shadow should be neutral: {} {} {}", r, g, b);
+    // Other colors move the same way: blue goes up, red goes down
+    let (r, _, b, _) = image.get_pixel(2, 0).unwrap();
+    assert!(r < 255 && b > 200, "highlight should cool down: {} {}", r, b);
+  }
+
+  #[test]
+  fn white_balance_from_gray_ignores_points_outside() {
+    let mut image = Image::new(2u32, 2u32);
+    image.fill_solid(Color::from_rgba(150, 128, 100, 255));
+    white_balance_from_gray(&mut image, (5, -1), None);
+    assert_eq!(image.get_pixel(0, 0), Some((150, 128, 100, 255)));
+  }
+}
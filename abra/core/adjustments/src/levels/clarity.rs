@@ -0,0 +1,96 @@
+use abra_core::{Color, Image, ImageRef};
+use options::Options;
+use rayon::prelude::*;
+
+use crate::apply_adjustment;
+use crate::color::local_gaussian_mean;
+
+/// How strongly the local detail is boosted at an amount of 100.
+const STRENGTH: f32 = 1.5;
+
+fn apply_clarity(p_image: &mut Image, p_amount: f32) {
+  let (width, height) = p_image.dimensions::<usize>();
+  if width == 0 || height == 0 {
+    return;
+  }
+  let luma: Vec<f32> = p_image
+    .rgba()
+    .par_chunks(4)
+    .map(|pixel| Color::luma_709(pixel[0] as f32, pixel[1] as f32, pixel[2] as f32) / 255.0)
+    .collect();
+  // A wide blur picks up the contrast between larger areas rather than fine detail, and its gentle falloff avoids
+  // hard halos along edges.
+  let radius = (width.max(height) as f32 / 40.0).round().clamp(3.0, 50.0) as usize;
+  let blurred = local_gaussian_mean(&luma, width, height, radius * 2 + 1);
+
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+  pixels
+    .par_chunks_mut(4)
+    .zip(luma.par_iter().zip(blurred.par_iter()))
+    .for_each(|(pixel, (&luma, &blurred))| {
+      // Midtones get the full effect, fading out toward black and white so they do not clip.
+      let midtones = 4.0 * luma * (1.0 - luma);
+      // Only the brightness changes, so every channel is shifted by the same amount.
+      let delta = p_amount * STRENGTH * midtones * (luma - blurred) * 255.0;
+      for channel in pixel.iter_mut().take(3) {
+        *channel = (*channel as f32 + delta).round().clamp(0.0, 255.0) as u8;
+      }
+    });
+}
+
+/// Increases or decreases the contrast between neighboring areas in the midtones, like the Clarity slider of a raw
+/// editor. Unlike sharpening, which targets fine detail, it works on the scale of whole shapes, giving positive
+/// amounts more punch and making negative amounts soften, e.g. for skin. Only the brightness is changed and the
+/// overall brightness stays the same.
+/// - `p_image`: The image to adjust.
+/// - `p_amount`: The amount of local contrast to add, from -100 to 100.
+/// - `p_options`: Options for applying the adjustment.
+pub fn clarity<'a>(p_image: impl Into<ImageRef<'a>>, p_amount: f32, p_options: impl Into<Options>) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let amount = p_amount.clamp(-100.0, 100.0) / 100.0;
+  apply_adjustment!(apply_clarity, image, p_options, 50, amount);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Low contrast gray stripes, four pixels wide.
+  fn stripes() -> Image {
+    let mut image = Image::new(64, 64);
+    for y in 0..64 {
+      for x in 0..64 {
+        let value = if (x / 4) % 2 == 0 { 110 } else { 146 };
+        image.set_pixel(x, y, (value, value, value, 255));
+      }
+    }
+    image
+  }
+
+  fn mean(p_image: &Image) -> f32 {
+    let total: u64 = p_image.rgba().chunks_exact(4).map(|pixel| pixel[0] as u64).sum();
+    total as f32 / (p_image.rgba().len() / 4) as f32
+  }
+
+  #[test]
+  fn positive_amount_adds_midtone_contrast_without_shifting_brightness() {
+    let mut image = stripes();
+    clarity(&mut image, 60.0, None);
+    let (dark, light) = (image.get_pixel(1, 30).unwrap().0, image.get_pixel(5, 30).unwrap().0);
+    assert!(dark < 110 && light > 146, "{} {}", dark, light);
+    assert!((mean(&image) - mean(&stripes())).abs() < 1.0);
+  }
+
+  #[test]
+  fn negative_amount_softens() {
+    let mut image = stripes();
+    clarity(&mut image, -60.0, None);
+    let (dark, light) = (image.get_pixel(1, 30).unwrap().0, image.get_pixel(5, 30).unwrap().0);
+    assert!(dark > 110 && light < 146, "{} {}", dark, light);
+    assert!((mean(&image) - mean(&stripes())).abs() < 1.0);
+  }
+}
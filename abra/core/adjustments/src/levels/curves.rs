@@ -0,0 +1,126 @@
+use abra_core::{Image, ImageRef};
+use options::Options;
+use rayon::prelude::*;
+
+use crate::apply_adjustment;
+
+/// Builds a 256 entry lookup table from curve control points using monotone cubic interpolation,
+/// which passes through every point without overshooting between them.
+/// Inputs before the first point or after the last point keep that point's output.
+/// An empty list gives the identity curve.
+/// - `p_points`: The `(input, output)` control points. They do not need to be sorted; for duplicate inputs the last point wins.
+pub fn curve_lut(p_points: &[(u8, u8)]) -> [u8; 256] {
+  let mut points: Vec<(f32, f32)> = vec![];
+  let mut sorted = p_points.to_vec();
+  sorted.sort_by_key(|(x, _)| *x);
+  for (x, y) in sorted.into_iter().rev() {
+    if points.last().is_none_or(|(last_x, _)| *last_x != x as f32) {
+      points.push((x as f32, y as f32));
+    }
+  }
+  points.reverse();
+
+  match points.len() {
+    0 => return std::array::from_fn(|i| i as u8),
+    1 => return [points[0].1 as u8; 256],
+    _ => {}
+  }
+
+  // Fritsch-Carlson tangents keep each segment monotonic.
+  let slopes: Vec<f32> = points
+    .windows(2)
+    .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+    .collect();
+  let mut tangents = vec![0.0f32; points.len()];
+  tangents[0] = slopes[0];
+  tangents[points.len() - 1] = slopes[slopes.len() - 1];
+  for i in 1..points.len() - 1 {
+    if slopes[i - 1] * slopes[i] > 0.0 {
+      tangents[i] = (slopes[i - 1] + slopes[i]) / 2.0;
+    }
+  }
+  for (i, slope) in slopes.iter().enumerate() {
+    if *slope == 0.0 {
+      tangents[i] = 0.0;
+      tangents[i + 1] = 0.0;
+      continue;
+    }
+    let (a, b) = (tangents[i] / slope, tangents[i + 1] / slope);
+    let length = a * a + b * b;
+    if length > 9.0 {
+      let scale = 3.0 / length.sqrt();
+      tangents[i] = scale * a * slope;
+      tangents[i + 1] = scale * b * slope;
+    }
+  }
+
+  let mut segment = 0;
+  std::array::from_fn(|i| {
+    let x = i as f32;
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let y = if x <= first.0 {
+      first.1
+    } else if x >= last.0 {
+      last.1
+    } else {
+      while x > points[segment + 1].0 {
+        segment += 1;
+      }
+      let ((x0, y0), (x1, y1)) = (points[segment], points[segment + 1]);
+      let h = x1 - x0;
+      let t = (x - x0) / h;
+      let (t2, t3) = (t * t, t * t * t);
+      (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+        + (t3 - 2.0 * t2 + t) * h * tangents[segment]
+        + (-2.0 * t3 + 3.0 * t2) * y1
+        + (t3 - t2) * h * tangents[segment + 1]
+    };
+    y.round().clamp(0.0, 255.0) as u8
+  })
+}
+
+fn apply_curves(p_image: &mut Image, p_points: &[(u8, u8)]) {
+  let lut = curve_lut(p_points);
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+  pixels.par_chunks_mut(4).for_each(|pixel| {
+    pixel[0] = lut[pixel[0] as usize];
+    pixel[1] = lut[pixel[1] as usize];
+    pixel[2] = lut[pixel[2] as usize];
+  });
+}
+
+/// Remaps the red, green and blue channels of an image through a tone curve. Alpha is unchanged.
+/// - `p_image`: The image to adjust.
+/// - `p_points`: The `(input, output)` control points of the curve, e.g. `[(0, 0), (64, 48), (192, 208), (255, 255)]`
+///   for an S-curve that adds contrast. See `curve_lut`.
+/// - `p_options`: Options for applying the adjustment.
+pub fn curves<'a>(p_image: impl Into<ImageRef<'a>>, p_points: &[(u8, u8)], p_options: impl Into<Options>) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_adjustment!(apply_curves, image, p_options, 0, p_points);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lut_passes_through_points_monotonically() {
+    let identity = curve_lut(&[(0, 0), (255, 255)]);
+    assert!(identity.iter().enumerate().all(|(i, v)| *v as usize == i));
+    assert_eq!(curve_lut(&[]), identity);
+
+    let lut = curve_lut(&[(255, 255), (64, 40), (0, 0), (192, 220)]);
+    assert_eq!((lut[0], lut[64], lut[192], lut[255]), (0, 40, 220, 255));
+    assert!(lut.windows(2).all(|w| w[0] <= w[1]));
+  }
+
+  #[test]
+  fn clamps_outside_points() {
+    let lut = curve_lut(&[(50, 20), (200, 230)]);
+    assert_eq!((lut[0], lut[50], lut[200], lut[255]), (20, 20, 230, 230));
+  }
+}
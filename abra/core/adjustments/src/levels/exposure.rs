@@ -1,4 +1,4 @@
-use abra_core::{ImageRef, image::Image};
+use abra_core::{ImageRef, image::Image, linear_f32_to_srgb_u8, srgb_u8_to_linear_f32};
 use options::Options;
 
 use rayon::prelude::*;
@@ -6,63 +6,38 @@ use rayon::prelude::*;
 use crate::apply_adjustment;
 
 fn apply_exposure(p_image: &mut Image, p_exposure: f32, p_offset: f32, p_gamma_correction: f32) {
-  let (width, height) = p_image.dimensions::<i32>();
-  let src = p_image.rgba();
-  let mut out = vec![0u8; (width * height * 4) as usize];
-
   // guard gamma correction
   let gamma_correction = if p_gamma_correction <= 0.0 {
     0.01
   } else {
     p_gamma_correction
   };
-  // fixed gamma for sRGB conversion
-  let gamma = 2.2;
-  let inv_gamma = 1.0 / gamma;
-  let exposure_factor = (1.4f32).powf(p_exposure);
-
-  out.par_chunks_mut(4).enumerate().for_each(|(idx, dst_px)| {
-    let i = idx * 4;
-
-    // read sRGB channels
-    let r_srgb = src[i] as f32 / 255.0;
-    let g_srgb = src[i + 1] as f32 / 255.0;
-    let b_srgb = src[i + 2] as f32 / 255.0;
-    let a = src[i + 3];
-
-    // convert sRGB -> linear (approximate with pow)
-    let r_lin = r_srgb.powf(gamma);
-    let g_lin = g_srgb.powf(gamma);
-    let b_lin = b_srgb.powf(gamma);
+  // Each stop doubles the light.
+  let exposure_factor = p_exposure.exp2();
 
+  // Every channel is adjusted on its own, so the adjustment fits in a lookup table.
+  let lut: [u8; 256] = std::array::from_fn(|i| {
     // apply exposure in linear space, add offset
-    let r_lin = (r_lin * exposure_factor + p_offset).max(0.0);
-    let g_lin = (g_lin * exposure_factor + p_offset).max(0.0);
-    let b_lin = (b_lin * exposure_factor + p_offset).max(0.0);
-
+    let linear = (srgb_u8_to_linear_f32(i as u8) * exposure_factor + p_offset).max(0.0);
     // apply gamma correction in linear space
-    let r_lin = r_lin.powf(1.0 / gamma_correction);
-    let g_lin = g_lin.powf(1.0 / gamma_correction);
-    let b_lin = b_lin.powf(1.0 / gamma_correction);
-
-    // convert back to sRGB
-    let r_out = r_lin.powf(inv_gamma).clamp(0.0, 1.0);
-    let g_out = g_lin.powf(inv_gamma).clamp(0.0, 1.0);
-    let b_out = b_lin.powf(inv_gamma).clamp(0.0, 1.0);
+    linear_f32_to_srgb_u8(linear.powf(1.0 / gamma_correction))
+  });
 
-    dst_px[0] = (r_out * 255.0).round() as u8;
-    dst_px[1] = (g_out * 255.0).round() as u8;
-    dst_px[2] = (b_out * 255.0).round() as u8;
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+  pixels.par_chunks_mut(4).for_each(|pixel| {
     // Preserve alpha as-is (do NOT gamma-correct alpha):
-    dst_px[3] = a;
+    for channel in pixel.iter_mut().take(3) {
+      *channel = lut[*channel as usize];
+    }
   });
-
-  p_image.set_rgba(&out);
 }
 
-/// Applies an exposure adjustment to the image.
+/// Applies an exposure adjustment to the image, like changing the exposure of a camera. Works in linear light.
 /// - `p_image`: The image to adjust.
-/// - `p_exposure`: The exposure value. Positive values increase exposure, negative values decrease it.
+/// - `p_exposure`: The exposure change in stops. Each stop doubles the light; negative values darken the image.
 /// - `p_offset`: The offset value to add to each color channel.
 /// - `p_gamma_correction`: The gamma correction value to apply. `1.0` means no correction.
 pub fn exposure<'a>(
@@ -93,3 +68,22 @@ pub fn exposure_plus_two(p_image: &mut Image, p_options: impl Into<Options>) {
 pub fn exposure_minus_two(p_image: &mut Image, p_options: impl Into<Options>) {
   exposure(p_image, -2.0, 0.0, 1.0, p_options);
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn one_stop_doubles_linear_light() {
+    let mut image = Image::new(1, 1);
+    image.set_pixel(0, 0, (128, 20, 255, 90));
+    exposure(&mut image, 1.0, 0.0, 1.0, None);
+    let (r, g, b, a) = image.get_pixel(0, 0).unwrap();
+    let doubled = |value: u8| linear_f32_to_srgb_u8(srgb_u8_to_linear_f32(value) * 2.0);
+    assert_eq!((r, g, b, a), (doubled(128), doubled(20), 255, 90));
+
+    exposure(&mut image, -1.0, 0.0, 1.0, None);
+    let (r, g, _, _) = image.get_pixel(0, 0).unwrap();
+    assert!((r as i32 - 128).abs() <= 1 && (g as i32 - 20).abs() <= 1, "{} {}", r, g);
+  }
+}
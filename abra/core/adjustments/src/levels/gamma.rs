@@ -0,0 +1,36 @@
+use abra_core::ImageRef;
+use options::Options;
+
+use super::levels;
+
+/// Applies a gamma correction to the red, green and blue channels. Black and white stay unchanged.
+/// - `p_image`: The image to adjust.
+/// - `p_gamma`: The gamma between 0.1 and 9.99, where values above 1 brighten the midtones and 1 means no change.
+///   Use `2.2` to roughly encode linear values and `1.0 / 2.2` to decode them.
+/// - `p_options`: Options for applying the adjustment.
+pub fn gamma<'a>(p_image: impl Into<ImageRef<'a>>, p_gamma: impl Into<f64>, p_options: impl Into<Options>) {
+  levels(p_image, 0, 255, p_gamma, 0, 255, p_options);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::{Color, Image};
+
+  #[test]
+  fn gamma_bends_midtones_only() {
+    let adjusted = |value: f64| {
+      let mut image = Image::new_from_color(3, 1, Color::from_rgba(128, 128, 128, 90));
+      image.set_pixel(0, 0, (0, 0, 0, 255));
+      image.set_pixel(2, 0, (255, 255, 255, 255));
+      gamma(&mut image, value, None);
+      (image.get_pixel(0, 0).unwrap(), image.get_pixel(1, 0).unwrap(), image.get_pixel(2, 0).unwrap())
+    };
+
+    let (black, mid, white) = adjusted(2.2);
+    assert_eq!((black, white), ((0, 0, 0, 255), (255, 255, 255, 255)));
+    assert_eq!(mid, (186, 186, 186, 90));
+    assert_eq!(adjusted(1.0).1, (128, 128, 128, 90));
+    assert!(adjusted(0.5).1.0 < 128);
+  }
+}
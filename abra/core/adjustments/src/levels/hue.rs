@@ -1,27 +1,78 @@
-use abra_core::Image;
-
-// TODO: Fix hue adjustment
-/// Adjust the hue of an image where 0.0 is no change, -180.0 is -180 degrees, and 180.0 is 180 degrees.
-pub fn hue(_image: &mut Image, _amount: i32) {
-  // let amount = (amount as f32).clamp(-180.0, 180.0);
-  // let amount = amount / 360.0; // Scale value to range [-0.5, 0.5] where 0.0 means no change
-
-  // let mut colors = image.colors.view_mut();
-  // colors.axis_iter_mut(Axis(1)).enumerate().for_each(|(i, mut color)| {
-  //   println!("Original Color: {:?}, {:?}, {:?}", color[[0, 0]], color[[0, 1]], color[[0, 2]]);
-  //   let (h, s, l) = rgb_to_hsl(color[[0, 0]], color[[0, 1]], color[[0, 2]]);
-  //   let (r, g, b) = hsl_to_rgb(h + amount, s, l);
-  //   color[[0, 0]] = r;
-  //   color[[0, 1]] = g;
-  //   color[[0, 2]] = b;
-  //   println!("Updated Color: {:?}, {:?}, {:?}", color[[0, 0]], color[[0, 1]], color[[0, 2]]);
-  // });
-
-  // for i in 0..(image.color_len) as usize {
-  //   let (h, s, l) = rgb_to_hsl(image.r[i], image.g[i], image.b[i]);
-  //   let (r, g, b) = hsl_to_rgb(h + amount, s, l);
-  //   image.r[i] = r;
-  //   image.g[i] = g;
-  //   image.b[i] = b;
-  // }
+use abra_core::{Color, Image, ImageRef};
+use options::Options;
+use rayon::prelude::*;
+
+use crate::apply_adjustment;
+
+fn apply_hue_saturation(p_image: &mut Image, p_hue: f32, p_saturation: f32, p_lightness: f32) {
+  let saturation = 1.0 + p_saturation / 100.0;
+  let lightness = p_lightness / 100.0;
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+
+  pixels.par_chunks_mut(4).for_each(|pixel| {
+    let (h, s, l) = Color::from_rgb(pixel[0], pixel[1], pixel[2]).hsl();
+    let color = Color::from_hsl((h + p_hue).rem_euclid(360.0), (s * saturation).clamp(0.0, 1.0), l);
+    for (channel, value) in pixel.iter_mut().zip([color.r, color.g, color.b]) {
+      // Lightness blends towards white or black so that it never changes the hue.
+      let value = value as f32;
+      let value = if lightness >= 0.0 {
+        value + (255.0 - value) * lightness
+      } else {
+        value * (1.0 + lightness)
+      };
+      *channel = value.round().clamp(0.0, 255.0) as u8;
+    }
+  });
+}
+
+/// Rotates the hue of an image.
+/// - `p_image`: The image to adjust.
+/// - `p_degrees`: The hue rotation in degrees between -180 and 180, where 0 means no change.
+/// - `p_options`: Options for applying the adjustment.
+pub fn hue<'a>(p_image: impl Into<ImageRef<'a>>, p_degrees: impl Into<f64>, p_options: impl Into<Options>) {
+  hue_saturation(p_image, p_degrees, 0.0, 0.0, p_options);
+}
+
+/// Adjusts the hue, saturation and lightness of an image.
+/// - `p_image`: The image to adjust.
+/// - `p_hue`: The hue rotation in degrees between -180 and 180, where 0 means no change.
+/// - `p_saturation`: The saturation change between -100 (grayscale) and 100 (double saturation), where 0 means no change.
+/// - `p_lightness`: The lightness change between -100 (black) and 100 (white), where 0 means no change.
+/// - `p_options`: Options for applying the adjustment.
+pub fn hue_saturation<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_hue: impl Into<f64>, p_saturation: impl Into<f64>, p_lightness: impl Into<f64>,
+  p_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let hue = (p_hue.into() as f32).clamp(-180.0, 180.0);
+  let saturation = (p_saturation.into() as f32).clamp(-100.0, 100.0);
+  let lightness = (p_lightness.into() as f32).clamp(-100.0, 100.0);
+  apply_adjustment!(apply_hue_saturation, image, p_options, 0, hue, saturation, lightness);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rotates_hue() {
+    let mut image = Image::new_from_color(2, 2, Color::red());
+    apply_hue_saturation(&mut image, 120.0, 0.0, 0.0);
+    assert_eq!(image.get_pixel(1, 1), Some((0, 255, 0, 255)));
+  }
+
+  #[test]
+  fn desaturates_and_lightens() {
+    let mut image = Image::new_from_color(2, 2, Color::from_rgba(200, 40, 40, 128));
+    apply_hue_saturation(&mut image, 0.0, -100.0, 0.0);
+    let (r, g, b, a) = image.get_pixel(0, 0).unwrap();
+    assert!(r == g && g == b);
+    assert_eq!(a, 128);
+    apply_hue_saturation(&mut image, 0.0, 0.0, 100.0);
+    assert_eq!(image.get_pixel(0, 0), Some((255, 255, 255, 128)));
+  }
 }
@@ -0,0 +1,72 @@
+use abra_core::{Image, ImageRef};
+use options::Options;
+use rayon::prelude::*;
+
+use crate::apply_adjustment;
+
+fn apply_levels(p_image: &mut Image, p_input: (u8, u8), p_gamma: f32, p_output: (u8, u8)) {
+  let (input_black, input_white) = (p_input.0 as f32, p_input.1 as f32);
+  let (output_black, output_white) = (p_output.0 as f32, p_output.1 as f32);
+  let lut: [u8; 256] = std::array::from_fn(|i| {
+    let value = ((i as f32 - input_black) / (input_white - input_black)).clamp(0.0, 1.0);
+    let value = value.powf(1.0 / p_gamma);
+    (output_black + value * (output_white - output_black)).round() as u8
+  });
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+  pixels.par_chunks_mut(4).for_each(|pixel| {
+    pixel[0] = lut[pixel[0] as usize];
+    pixel[1] = lut[pixel[1] as usize];
+    pixel[2] = lut[pixel[2] as usize];
+  });
+}
+
+/// Remaps the tonal range of an image. Values at or below the input black point become the output black point,
+/// values at or above the input white point become the output white point and the gamma bends the midtones.
+/// - `p_image`: The image to adjust.
+/// - `p_input_black`: The input black point. It is lowered when it is not below the input white point.
+/// - `p_input_white`: The input white point.
+/// - `p_gamma`: The midtone gamma between 0.1 and 9.99, where values above 1 brighten and 1 means no change.
+/// - `p_output_black`: The output black point.
+/// - `p_output_white`: The output white point. It may be below the output black point to invert the image.
+/// - `p_options`: Options for applying the adjustment.
+pub fn levels<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_input_black: u8, p_input_white: u8, p_gamma: impl Into<f64>, p_output_black: u8,
+  p_output_white: u8, p_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let input_white = p_input_white.max(1);
+  let input = (p_input_black.min(input_white - 1), input_white);
+  let gamma = (p_gamma.into() as f32).clamp(0.1, 9.99);
+  let output = (p_output_black, p_output_white);
+  apply_adjustment!(apply_levels, image, p_options, 0, input, gamma, output);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn stretches_input_range() {
+    let mut image = Image::new(3, 1);
+    image.set_pixel(0, 0, (20, 20, 20, 255));
+    image.set_pixel(1, 0, (120, 60, 220, 99));
+    image.set_pixel(2, 0, (230, 230, 230, 255));
+    apply_levels(&mut image, (20, 220), 1.0, (0, 255));
+    assert_eq!(image.get_pixel(0, 0), Some((0, 0, 0, 255)));
+    assert_eq!(image.get_pixel(1, 0), Some((128, 51, 255, 99)));
+    assert_eq!(image.get_pixel(2, 0), Some((255, 255, 255, 255)));
+  }
+
+  #[test]
+  fn gamma_and_output_range() {
+    let mut image = Image::new_from_color(1, 1, abra_core::Color::from_rgb(128, 128, 128));
+    apply_levels(&mut image, (0, 255), 2.0, (0, 255));
+    assert!(image.get_pixel(0, 0).unwrap().0 > 170);
+    apply_levels(&mut image, (0, 255), 1.0, (255, 0));
+    assert!(image.get_pixel(0, 0).unwrap().0 < 85);
+  }
+}
@@ -0,0 +1,323 @@
+use abra_core::{Image, ImageRef};
+use options::Options;
+use rayon::prelude::*;
+
+use super::curves::curve_lut;
+
+#[derive(Debug, Clone, PartialEq)]
+enum LutTable {
+  /// One 256 entry table for each of the red, green and blue channels.
+  Channels(Box<[[u8; 256]; 3]>),
+  /// A cube of output colors indexed by the input red, green and blue, with red changing fastest.
+  Cube {
+    size: usize,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    table: Vec<[f32; 3]>,
+  },
+}
+
+/// A color lookup table that remaps the colors of an image.
+/// A LUT is either a per-channel table, like the result of a curve, or a 3D table that can map every color to any
+/// other color, like the `.cube` LUTs shared by photographers and colorists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut {
+  title: Option<String>,
+  table: LutTable,
+}
+
+impl Lut {
+  /// Creates a LUT that leaves every color unchanged.
+  pub fn identity() -> Lut {
+    let identity = std::array::from_fn(|i| i as u8);
+    Lut::from_tables(identity, identity, identity)
+  }
+
+  /// Creates a LUT from per-channel lookup tables.
+  /// - `p_red`: The output red value for every input red value.
+  /// - `p_green`: The output green value for every input green value.
+  /// - `p_blue`: The output blue value for every input blue value.
+  pub fn from_tables(p_red: [u8; 256], p_green: [u8; 256], p_blue: [u8; 256]) -> Lut {
+    Lut {
+      title: None,
+      table: LutTable::Channels(Box::new([p_red, p_green, p_blue])),
+    }
+  }
+
+  /// Creates a LUT that applies the same tone curve to the red, green and blue channels.
+  /// - `p_points`: The `(input, output)` control points of the curve. See `curve_lut`.
+  pub fn from_curve(p_points: &[(u8, u8)]) -> Lut {
+    let curve = curve_lut(p_points);
+    Lut::from_tables(curve, curve, curve)
+  }
+
+  /// Creates a LUT with a separate tone curve for each channel, e.g. to warm the highlights by lifting red
+  /// and lowering blue. An empty list leaves that channel unchanged.
+  /// - `p_red`: The `(input, output)` control points of the red curve.
+  /// - `p_green`: The `(input, output)` control points of the green curve.
+  /// - `p_blue`: The `(input, output)` control points of the blue curve.
+  pub fn from_channel_curves(p_red: &[(u8, u8)], p_green: &[(u8, u8)], p_blue: &[(u8, u8)]) -> Lut {
+    Lut::from_tables(curve_lut(p_red), curve_lut(p_green), curve_lut(p_blue))
+  }
+
+  /// Loads a LUT from an Adobe/Resolve `.cube` file. Both 3D (`LUT_3D_SIZE`) and 1D (`LUT_1D_SIZE`) tables are
+  /// supported.
+  /// - `p_path`: The path to the `.cube` file.
+  pub fn from_cube(p_path: impl Into<String>) -> Result<Lut, String> {
+    let contents = std::fs::read_to_string(p_path.into()).map_err(|e| e.to_string())?;
+    Lut::parse_cube(&contents)
+  }
+
+  /// Parses the contents of a `.cube` file. See `from_cube`.
+  /// - `p_contents`: The text of the `.cube` file.
+  pub fn parse_cube(p_contents: &str) -> Result<Lut, String> {
+    let mut title = None;
+    let mut size_3d = None;
+    let mut size_1d = None;
+    let mut domain_min = [0.0f32; 3];
+    let mut domain_max = [1.0f32; 3];
+    let mut values: Vec<[f32; 3]> = vec![];
+
+    let parse_triplet = |p_parts: &[&str], p_line: usize| -> Result<[f32; 3], String> {
+      if p_parts.len() != 3 {
+        return Err(format!("Line {}: expected 3 values, found {}", p_line, p_parts.len()));
+      }
+      let mut triplet = [0.0f32; 3];
+      for (value, part) in triplet.iter_mut().zip(p_parts) {
+        *value = part
+          .parse()
+          .map_err(|_| format!("Line {}: invalid number '{}'", p_line, part))?;
+      }
+      Ok(triplet)
+    };
+    let parse_size = |p_value: Option<&str>, p_line: usize| -> Result<usize, String> {
+      p_value
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|size| *size >= 2)
+        .ok_or_else(|| format!("Line {}: invalid LUT size", p_line))
+    };
+
+    for (index, line) in p_contents.lines().enumerate() {
+      let line_number = index + 1;
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let mut parts = line.split_whitespace();
+      let keyword = parts.next().unwrap_or_default();
+      match keyword {
+        "TITLE" => {
+          let rest = line[keyword.len()..].trim();
+          title = Some(rest.trim_matches('"').to_string());
+        }
+        "LUT_3D_SIZE" => size_3d = Some(parse_size(parts.next(), line_number)?),
+        "LUT_1D_SIZE" => size_1d = Some(parse_size(parts.next(), line_number)?),
+        "DOMAIN_MIN" => domain_min = parse_triplet(&parts.collect::<Vec<_>>(), line_number)?,
+        "DOMAIN_MAX" => domain_max = parse_triplet(&parts.collect::<Vec<_>>(), line_number)?,
+        // Other keywords, such as LUT_3D_INPUT_RANGE, don't affect the colors.
+        _ if keyword.chars().next().is_some_and(|c| c.is_ascii_alphabetic()) => {}
+        _ => values.push(parse_triplet(&line.split_whitespace().collect::<Vec<_>>(), line_number)?),
+      }
+    }
+
+    if domain_min.iter().zip(&domain_max).any(|(min, max)| min >= max) {
+      return Err("DOMAIN_MIN must be less than DOMAIN_MAX".to_string());
+    }
+
+    let table = match (size_3d, size_1d) {
+      (Some(size), None) => {
+        if values.len() != size * size * size {
+          return Err(format!(
+            "Expected {} entries for a 3D LUT of size {}, found {}",
+            size.pow(3),
+            size,
+            values.len()
+          ));
+        }
+        LutTable::Cube {
+          size,
+          domain_min,
+          domain_max,
+          table: values,
+        }
+      }
+      (None, Some(size)) => {
+        if values.len() != size {
+          return Err(format!("Expected {} entries for a 1D LUT of size {}, found {}", size, size, values.len()));
+        }
+        // Resample the table to one entry per 8-bit input value.
+        let channel = |p_channel: usize| -> [u8; 256] {
+          std::array::from_fn(|i| {
+            let range = domain_max[p_channel] - domain_min[p_channel];
+            let position = ((i as f32 / 255.0 - domain_min[p_channel]) / range).clamp(0.0, 1.0) * (size - 1) as f32;
+            let low = position.floor() as usize;
+            let high = (low + 1).min(size - 1);
+            let t = position - low as f32;
+            let value = values[low][p_channel] * (1.0 - t) + values[high][p_channel] * t;
+            (value * 255.0).round().clamp(0.0, 255.0) as u8
+          })
+        };
+        LutTable::Channels(Box::new([channel(0), channel(1), channel(2)]))
+      }
+      (Some(_), Some(_)) => return Err("A .cube file can't define both LUT_1D_SIZE and LUT_3D_SIZE".to_string()),
+      (None, None) => return Err("Missing LUT_3D_SIZE or LUT_1D_SIZE".to_string()),
+    };
+
+    Ok(Lut { title, table })
+  }
+
+  /// The title of the LUT, if the `.cube` file defines one.
+  pub fn title(&self) -> Option<&str> {
+    self.title.as_deref()
+  }
+
+  /// The number of entries along each axis: the grid size of a 3D LUT or 256 for a per-channel LUT.
+  pub fn size(&self) -> usize {
+    match &self.table {
+      LutTable::Channels(_) => 256,
+      LutTable::Cube { size, .. } => *size,
+    }
+  }
+
+  /// Whether this is a 3D LUT, which can mix the channels, rather than a per-channel LUT.
+  pub fn is_3d(&self) -> bool {
+    matches!(self.table, LutTable::Cube { .. })
+  }
+
+  /// Remaps the red, green and blue channels of an image through the LUT. Alpha is unchanged.
+  /// 3D LUTs are sampled with trilinear interpolation.
+  /// - `p_image`: The image to adjust.
+  /// - `p_options`: Options for applying the adjustment.
+  pub fn apply<'a>(&self, p_image: impl Into<ImageRef<'a>>, p_options: impl Into<Options>) {
+    let mut image_ref: ImageRef = p_image.into();
+    let image = &mut image_ref as &mut Image;
+    let options = p_options.into();
+    let ctx = options::get_ctx(options.as_ref());
+    // Not `apply_adjustment!`, which would print the whole table.
+    abra_core::image::apply_area::process_image(image, ctx, 0, |img| self.apply_to_image(img));
+  }
+
+  fn apply_to_image(&self, p_image: &mut Image) {
+    let pixels = p_image
+      .colors()
+      .as_slice_mut()
+      .expect("Image colors must be contiguous");
+    match &self.table {
+      LutTable::Channels(channels) => pixels.par_chunks_mut(4).for_each(|pixel| {
+        for (value, channel) in pixel.iter_mut().zip(channels.iter()) {
+          *value = channel[*value as usize];
+        }
+      }),
+      LutTable::Cube {
+        size,
+        domain_min,
+        domain_max,
+        table,
+      } => pixels.par_chunks_mut(4).for_each(|pixel| {
+        let max_index = (*size - 1) as f32;
+        let mut low = [0usize; 3];
+        let mut high = [0usize; 3];
+        let mut t = [0.0f32; 3];
+        for (c, value) in pixel.iter().take(3).enumerate() {
+          let normalized = (*value as f32 / 255.0 - domain_min[c]) / (domain_max[c] - domain_min[c]);
+          let position = normalized.clamp(0.0, 1.0) * max_index;
+          low[c] = position.floor() as usize;
+          high[c] = (low[c] + 1).min(*size - 1);
+          t[c] = position - low[c] as f32;
+        }
+        let entry = |r: usize, g: usize, b: usize| table[r + g * size + b * size * size];
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        for (c, channel) in pixel.iter_mut().take(3).enumerate() {
+          let blue_plane = |b: usize| {
+            let bottom = lerp(entry(low[0], low[1], b)[c], entry(high[0], low[1], b)[c], t[0]);
+            let top = lerp(entry(low[0], high[1], b)[c], entry(high[0], high[1], b)[c], t[0]);
+            lerp(bottom, top, t[1])
+          };
+          let value = lerp(blue_plane(low[2]), blue_plane(high[2]), t[2]);
+          *channel = (value * 255.0).round().clamp(0.0, 255.0) as u8;
+        }
+      }),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// An image with a pixel for a range of colors.
+  fn swatches() -> Image {
+    let mut image = Image::new(8u32, 8u32);
+    for y in 0..8u32 {
+      for x in 0..8u32 {
+        image.set_pixel(x, y, ((x * 36) as u8, (y * 36) as u8, ((x + y) * 17) as u8, 200));
+      }
+    }
+    image
+  }
+
+  /// A 3D `.cube` of the given size that maps every color to itself, optionally with the red and blue swapped.
+  fn cube(p_size: usize, p_swap_red_blue: bool) -> String {
+    let mut contents = String::from("# Created for tests\nTITLE \"Test LUT\"\nLUT_3D_SIZE ");
+    contents.push_str(&format!("{}\n\n", p_size));
+    let step = (p_size - 1) as f32;
+    for b in 0..p_size {
+      for g in 0..p_size {
+        for r in 0..p_size {
+          let (r, g, b) = (r as f32 / step, g as f32 / step, b as f32 / step);
+          let (r, b) = if p_swap_red_blue { (b, r) } else { (r, b) };
+          contents.push_str(&format!("{:.6} {:.6} {:.6}\n", r, g, b));
+        }
+      }
+    }
+    contents
+  }
+
+  #[test]
+  fn identity_leaves_image_unchanged() {
+    let mut image = swatches();
+    Lut::identity().apply(&mut image, None);
+    assert_eq!(image.rgba(), swatches().rgba());
+
+    let mut image = swatches();
+    Lut::parse_cube(&cube(17, false)).unwrap().apply(&mut image, None);
+    assert_eq!(image.rgba(), swatches().rgba());
+  }
+
+  #[test]
+  fn parses_cube_title_and_size() {
+    let lut = Lut::parse_cube(&cube(5, false)).unwrap();
+    assert_eq!(lut.title(), Some("Test LUT"));
+    assert_eq!(lut.size(), 5);
+    assert!(lut.is_3d());
+
+    let one_d = Lut::parse_cube("LUT_1D_SIZE 2\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 1 1 1\n1 1 1\n0 0 0\n").unwrap();
+    assert_eq!(one_d.title(), None);
+    assert!(!one_d.is_3d());
+
+    assert!(Lut::parse_cube("LUT_3D_SIZE 2\n0 0 0\n").is_err());
+    assert!(Lut::parse_cube("0 0 0\n1 1 1\n").is_err());
+    assert!(Lut::parse_cube("LUT_1D_SIZE 2\n0 0 zero\n1 1 1\n").is_err());
+  }
+
+  #[test]
+  fn cube_lut_mixes_channels() {
+    let mut image = swatches();
+    Lut::parse_cube(&cube(9, true)).unwrap().apply(&mut image, None);
+    let (r, g, b, a) = swatches().get_pixel(5, 2).unwrap();
+    assert_eq!(image.get_pixel(5, 2), Some((b, g, r, a)));
+  }
+
+  #[test]
+  fn channel_curves_adjust_each_channel() {
+    let mut image = swatches();
+    let lut = Lut::from_channel_curves(&[(0, 0), (255, 200)], &[], &[(0, 55), (255, 255)]);
+    lut.apply(&mut image, None);
+    let (r, g, b, a) = image.get_pixel(7, 7).unwrap();
+    let (_, source_g, _, source_a) = swatches().get_pixel(7, 7).unwrap();
+    assert_eq!((g, a), (source_g, source_a));
+    assert!((195..252).contains(&r), "red should be pulled down: {}", r);
+    assert!(b > 238, "blue should be lifted: {}", b);
+    assert_eq!(Lut::from_curve(&[]), Lut::identity());
+  }
+}
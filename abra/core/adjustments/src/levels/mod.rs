@@ -1,15 +1,29 @@
 mod brightness;
+mod clarity;
 mod contrast;
+mod curves;
 mod exposure;
+mod gamma;
 mod hue;
+mod input_levels;
+mod lut;
 mod photo_filter;
 mod saturation;
+mod shadows_highlights;
+mod tone_map;
 mod vibrance;
 
 pub use brightness::*;
+pub use clarity::*;
 pub use contrast::*;
+pub use curves::*;
 pub use exposure::*;
+pub use gamma::*;
 pub use hue::*;
+pub use input_levels::*;
+pub use lut::*;
 pub use photo_filter::*;
 pub use saturation::*;
+pub use shadows_highlights::*;
+pub use tone_map::*;
 pub use vibrance::*;
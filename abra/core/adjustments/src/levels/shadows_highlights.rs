@@ -0,0 +1,99 @@
+use abra_core::{Color, Image, ImageRef, linear_f32_to_srgb_u8, srgb_u8_to_linear_f32};
+use options::Options;
+use rayon::prelude::*;
+
+use crate::apply_adjustment;
+use crate::color::local_gaussian_mean;
+
+/// The exposure change in stops at an amount of 100 for a pixel in a fully black or fully white neighborhood.
+const MAX_STOPS: f32 = 2.0;
+
+fn apply_shadows_highlights(p_image: &mut Image, p_shadows: f32, p_highlights: f32, p_radius: u32) {
+  let (width, height) = p_image.dimensions::<usize>();
+  if width == 0 || height == 0 {
+    return;
+  }
+  let to_linear: Vec<f32> = (0..=255u8).map(srgb_u8_to_linear_f32).collect();
+  // The perceived brightness of the neighborhood decides how much a pixel belongs to the shadows or highlights.
+  let luma: Vec<f32> = p_image
+    .rgba()
+    .par_chunks(4)
+    .map(|pixel| Color::luma_709(pixel[0] as f32, pixel[1] as f32, pixel[2] as f32) / 255.0)
+    .collect();
+  let mask = local_gaussian_mean(&luma, width, height, p_radius as usize * 2 + 1);
+
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+  pixels
+    .par_chunks_mut(4)
+    .zip(mask.par_iter())
+    .for_each(|(pixel, &local)| {
+      let shadow = (1.0 - local).powi(2);
+      let highlight = local * local;
+      // All channels get the same gain so colors keep their hue.
+      let gain = (MAX_STOPS * (p_shadows * shadow - p_highlights * highlight)).exp2();
+      for channel in pixel.iter_mut().take(3) {
+        *channel = linear_f32_to_srgb_u8(to_linear[*channel as usize] * gain);
+      }
+    });
+}
+
+/// Lifts the shadows and recovers the highlights of the image locally, like a fill light and a graduated filter.
+/// How much a pixel is brightened or darkened depends on the brightness of its blurred neighborhood rather than on
+/// the pixel itself, so dark subjects are lifted as a whole while the bright areas around them are left alone.
+/// - `p_image`: The image to adjust.
+/// - `p_shadow_amount`: How much dark areas are brightened, from 0 to 100.
+/// - `p_highlight_amount`: How much bright areas are darkened, from 0 to 100.
+/// - `p_radius`: The blur radius in pixels of the neighborhood. Larger values keep the local contrast of bigger
+///   details but spread the effect further across edges.
+/// - `p_options`: Options for applying the adjustment.
+pub fn shadows_highlights<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_shadow_amount: f32, p_highlight_amount: f32, p_radius: u32,
+  p_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let shadows = p_shadow_amount.clamp(0.0, 100.0) / 100.0;
+  let highlights = p_highlight_amount.clamp(0.0, 100.0) / 100.0;
+  apply_adjustment!(apply_shadows_highlights, image, p_options, p_radius as i32, shadows, highlights, p_radius);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use primitives::Color;
+
+  /// A dark subject in front of a bright sky.
+  fn backlit_portrait() -> Image {
+    let mut image = Image::new_from_color(60, 60, Color::from_rgba(230, 230, 230, 255));
+    for y in 20..40 {
+      for x in 20..40 {
+        image.set_pixel(x, y, (40, 35, 30, 255));
+      }
+    }
+    image
+  }
+
+  #[test]
+  fn shadows_brighten_the_subject_but_not_the_background() {
+    let mut image = backlit_portrait();
+    shadows_highlights(&mut image, 80.0, 0.0, 8, None);
+    let subject = image.get_pixel(30, 30).unwrap();
+    assert!(subject.0 > 55, "{:?}", subject);
+    // The subject keeps its warm cast.
+    assert!(subject.0 > subject.1 && subject.1 > subject.2, "{:?}", subject);
+    let background = image.get_pixel(2, 2).unwrap();
+    assert!(background.0 <= 233, "{:?}", background);
+    assert!(image.rgba().chunks_exact(4).all(|pixel| pixel[0] < 255));
+  }
+
+  #[test]
+  fn highlights_darken_the_background_but_not_the_subject() {
+    let mut image = backlit_portrait();
+    shadows_highlights(&mut image, 0.0, 80.0, 8, None);
+    assert!(image.get_pixel(2, 2).unwrap().0 < 200);
+    assert!(image.get_pixel(30, 30).unwrap().0 >= 38);
+  }
+}
@@ -0,0 +1,106 @@
+use abra_core::{Image, ImageRef, linear_f32_to_srgb_u8, srgb_u8_to_linear_f32};
+use options::Options;
+use rayon::prelude::*;
+
+use crate::apply_adjustment;
+
+/// The curve used by `tone_map` to compress bright linear values into the displayable range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMapOperator {
+  /// `x / (1 + x)`. Soft and neutral, but it never quite reaches white and lowers the contrast of midtones.
+  Reinhard,
+  /// Krzysztof Narkowicz's fit of the ACES filmic curve. Punchy contrast with a gentle highlight shoulder.
+  Aces,
+  /// John Hable's Uncharted 2 filmic curve, with a toe in the shadows and a long shoulder in the highlights.
+  Filmic,
+}
+
+impl ToneMapOperator {
+  /// Maps a linear value of 0 or more to a linear value between 0 and 1.
+  fn map(&self, p_value: f32) -> f32 {
+    match self {
+      ToneMapOperator::Reinhard => p_value / (1.0 + p_value),
+      ToneMapOperator::Aces => (p_value * (2.51 * p_value + 0.03)) / (p_value * (2.43 * p_value + 0.59) + 0.14),
+      ToneMapOperator::Filmic => {
+        // The curve is applied to twice the value and scaled so that a linear value of 11.2 becomes white.
+        const WHITE_POINT: f32 = 11.2;
+        hable(2.0 * p_value) / hable(WHITE_POINT)
+      }
+    }
+    .clamp(0.0, 1.0)
+  }
+}
+
+/// The Uncharted 2 filmic curve.
+fn hable(p_value: f32) -> f32 {
+  let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+  ((p_value * (a * p_value + c * b) + d * e) / (p_value * (a * p_value + b) + d * f)) - e / f
+}
+
+fn apply_tone_map(p_image: &mut Image, p_operator: ToneMapOperator, p_exposure: f32) {
+  let scale = p_exposure.exp2();
+  // Every channel is mapped on its own, so the whole adjustment fits in a lookup table.
+  let lut: [u8; 256] =
+    std::array::from_fn(|i| linear_f32_to_srgb_u8(p_operator.map(srgb_u8_to_linear_f32(i as u8) * scale)));
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+
+  pixels.par_chunks_mut(4).for_each(|pixel| {
+    for channel in pixel.iter_mut().take(3) {
+      *channel = lut[*channel as usize];
+    }
+  });
+}
+
+/// Brightens or darkens the image in linear light and then compresses the highlights with a tone mapping curve,
+/// so that values pushed past white roll off smoothly instead of clipping. Alpha is unchanged.
+/// - `p_image`: The image to adjust.
+/// - `p_operator`: The curve that compresses the highlights.
+/// - `p_exposure`: The exposure change in stops applied before the curve. Each stop doubles the light.
+/// - `p_options`: Options for applying the adjustment.
+pub fn tone_map<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_operator: ToneMapOperator, p_exposure: f32, p_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let exposure = p_exposure.clamp(-20.0, 20.0);
+  apply_adjustment!(apply_tone_map, image, p_options, 0, p_operator, exposure);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A horizontal gray ramp from black to white.
+  fn ramp() -> Image {
+    let mut image = Image::new(256, 1);
+    for x in 0..256 {
+      image.set_pixel(x, 0, (x as u8, x as u8, x as u8, 200));
+    }
+    image
+  }
+
+  #[test]
+  fn highlights_roll_off_instead_of_clipping() {
+    for operator in [
+      ToneMapOperator::Reinhard,
+      ToneMapOperator::Aces,
+      ToneMapOperator::Filmic,
+    ] {
+      let mut image = ramp();
+      // Two stops pushes everything above about 127 past white in linear light.
+      tone_map(&mut image, operator, 2.0, None);
+      let levels: Vec<u8> = (0..256).map(|x| image.get_pixel(x, 0).unwrap().0).collect();
+
+      assert!(levels.windows(2).all(|pair| pair[0] <= pair[1]), "{:?} is not monotonic", operator);
+      // The clipped range still holds distinct levels, ending just short of white.
+      assert!(levels[200] < levels[255], "{:?}: {} {}", operator, levels[200], levels[255]);
+      assert!(levels[160] < levels[200], "{:?}: {} {}", operator, levels[160], levels[200]);
+      assert!(levels[255] > 200, "{:?}: {}", operator, levels[255]);
+      assert_eq!(levels[0], 0);
+      assert_eq!(image.get_pixel(128, 0).unwrap().3, 200);
+    }
+  }
+}
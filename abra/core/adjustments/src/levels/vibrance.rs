@@ -1,4 +1,4 @@
-use abra_core::{Image, ImageRef};
+use abra_core::{Color, Image, ImageRef};
 use options::Options;
 
 use rayon::prelude::*;
@@ -31,10 +31,7 @@ fn apply_vibrance(p_image: &mut Image, p_vibrance: f32, p_saturation: f32) {
     let b_vib = ((b - avg) * vibrance_factor + avg).clamp(0.0, 1.0);
 
     // apply saturation
-    let lum_r = 0.2126;
-    let lum_g = 0.7152;
-    let lum_b = 0.0722;
-    let lum = r_vib * lum_r + g_vib * lum_g + b_vib * lum_b;
+    let lum = Color::luma_709(r_vib, g_vib, b_vib);
     let saturation_factor = 1.0 + (p_saturation / 100.0);
     let r_out = (lum + (r_vib - lum) * saturation_factor).clamp(0.0, 1.0);
     let g_out = (lum + (g_vib - lum) * saturation_factor).clamp(0.0, 1.0);
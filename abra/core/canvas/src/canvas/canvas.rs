@@ -8,7 +8,9 @@ use std::sync::{Arc, Mutex};
 use crate::canvas::AddCanvasOptions;
 use crate::canvas::Origin;
 
+use abra_core::Color;
 use abra_core::Image;
+use abra_core::Rect;
 use abra_core::WriterOptions;
 
 use super::canvas_inner::CanvasInner;
@@ -122,6 +124,17 @@ impl Canvas {
     self
   }
 
+  /// Flattens all layers into a single layer composited over a background color.
+  /// Use an opaque color to remove all transparency, or `Color::transparent()` to keep it.
+  /// - `p_background`: The color placed behind the flattened layers.
+  pub fn flatten_onto(self, p_background: Color) -> Self {
+    {
+      let mut canvas = self.inner_canvas.lock().unwrap();
+      canvas.flatten_onto(p_background);
+    }
+    self
+  }
+
   /// Updates the canvas by re-compositing all layers and child canvases.
   ///
   /// Internal-only: composition is triggered automatically by `save` and `as_image`.
@@ -149,6 +162,28 @@ impl Canvas {
     (width, height)
   }
 
+  /// Gets the size of the canvas in pixels.
+  pub fn size(&self) -> (u32, u32) {
+    self.dimensions()
+  }
+
+  /// Gets the union of the rectangles of all visible layers and child canvases, in canvas coordinates.
+  /// Layer effects such as drop shadows and strokes are included, so the bounds can reach past the canvas edges.
+  pub fn bounds(&self) -> Rect {
+    let mut canvas = self.inner_canvas.lock().unwrap();
+    canvas.bounds()
+  }
+
+  /// Resizes the canvas to its content bounds so nothing, including layer effects, is clipped on export.
+  /// Layers and child canvases keep their placement relative to each other; anchored layers stay where they are.
+  pub fn fit_to_content(self) -> Self {
+    {
+      let mut canvas = self.inner_canvas.lock().unwrap();
+      canvas.fit_to_content();
+    }
+    self
+  }
+
   /// Gets the position of the canvas within its parent
   pub fn position(&self) -> (i32, i32) {
     let canvas = self.inner_canvas.lock().unwrap();
@@ -293,7 +328,7 @@ impl Canvas {
   }
 
   /// Sets the position of this canvas to the given anchor point within its parent canvas.
-  pub fn anchor_to_canvas(&self, anchor: crate::Anchor) {
+  pub fn anchor_to_canvas(&self, anchor: abra_core::Anchor) {
     let mut canvas = self.inner_canvas.lock().unwrap();
     canvas.anchor_to_canvas(anchor);
   }
@@ -445,3 +480,87 @@ impl Canvas {
     CanvasTransform::new(self.inner_canvas.clone())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn half_transparent_canvas() -> Canvas {
+    let mut image = Image::new_from_color(16, 16, Color::black());
+    for y in 0..16 {
+      for x in 8..16 {
+        image.set_pixel(x, y, (0, 0, 0, 0));
+      }
+    }
+    Canvas::new_blank("canvas", 16, 16).add_layer_from_image("half", image, None)
+  }
+
+  #[test]
+  fn jpeg_save_uses_matte_for_transparency() {
+    let dir = std::env::temp_dir().join("abra_canvas_jpeg_matte");
+    let white = dir.join("white.jpg");
+    half_transparent_canvas().save(white.to_str().unwrap(), None);
+    let image = Image::new_from_path(white.to_str().unwrap());
+    let (r, g, b, _) = image.get_pixel(12, 8).unwrap();
+    assert!(r > 250 && g > 250 && b > 250, "expected white, got {:?}", (r, g, b));
+    let (r, _, _, _) = image.get_pixel(3, 8).unwrap();
+    assert!(r < 5, "expected the opaque half to stay black, got {}", r);
+
+    let red = dir.join("red.jpg");
+    half_transparent_canvas().save(red.to_str().unwrap(), WriterOptions::default().matte(Color::red()));
+    let (r, g, b, _) = Image::new_from_path(red.to_str().unwrap()).get_pixel(12, 8).unwrap();
+    assert!(r > 240 && g < 15 && b < 15, "expected red, got {:?}", (r, g, b));
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn flatten_onto_fills_transparency() {
+    let image = half_transparent_canvas().flatten_onto(Color::blue()).as_image();
+    assert_eq!(image.get_pixel(12, 8), Some((0, 0, 255, 255)));
+    assert_eq!(image.get_pixel(3, 8), Some((0, 0, 0, 255)));
+
+    let kept = half_transparent_canvas().flatten().as_image();
+    assert_eq!(kept.get_pixel(12, 8), Some((0, 0, 0, 0)));
+  }
+
+  fn canvas_with_shadowed_square() -> Canvas {
+    let options = NewLayerOptions::new()
+      .with_anchor(abra_core::Anchor::TopRight)
+      .with_offset(5, 20);
+    let canvas = Canvas::new_blank("canvas", 50, 50)
+      .add_layer_from_image("base", Image::new_from_color(50, 50, Color::blue()), None)
+      .add_layer_from_image("square", Image::new_from_color(10, 10, Color::white()), options);
+    let shadow = crate::DropShadow::new()
+      .with_color(Color::red())
+      .with_angle(0.0)
+      .with_distance(10.0)
+      .with_blur(2.0)
+      .with_opacity(1.0);
+    let square = canvas.get_layer_by_name("square").unwrap();
+    square.set_effects(crate::LayerEffects::new().with_drop_shadow(shadow));
+    canvas
+  }
+
+  #[test]
+  fn bounds_include_offsets_and_effects() {
+    let canvas = canvas_with_shadowed_square();
+    assert_eq!(canvas.size(), (50, 50));
+    // The square sits at (45, 20) and its shadow padding extends it to 24x14 from (43, 18).
+    assert_eq!(canvas.bounds(), Rect::new(0, 0, 67, 50));
+    // Recomposing must not apply the effects a second time.
+    canvas.get_layer_by_name("base").unwrap().set_opacity(1.0);
+    canvas.as_image();
+    assert_eq!(canvas.bounds(), Rect::new(0, 0, 67, 50));
+  }
+
+  #[test]
+  fn fit_to_content_keeps_effects() {
+    let canvas = canvas_with_shadowed_square().fit_to_content();
+    assert_eq!(canvas.size(), (67, 50));
+    assert_eq!(canvas.bounds(), Rect::new(0, 0, 67, 50));
+    let image = canvas.as_image();
+    assert_eq!(image.get_pixel(50, 25), Some((255, 255, 255, 255)));
+    assert_eq!(image.get_pixel(60, 25), Some((255, 0, 0, 255)));
+    assert_eq!(image.get_pixel(10, 10), Some((0, 0, 255, 255)));
+  }
+}
@@ -1,7 +1,9 @@
 //! The internal canvas implementation.
 
 use abra_core::Channels;
+use abra_core::Color;
 use abra_core::Image;
+use abra_core::Rect;
 use abra_core::Rotate;
 use abra_core::WriterOptions;
 use abra_core::image::image_ext::*;
@@ -11,11 +13,11 @@ use std::cell::Cell;
 use std::sync::Arc;
 use std::sync::Mutex;
 
-use crate::Anchor;
 use crate::Canvas;
 use crate::LayerEffects;
 use crate::canvas::AddCanvasOptions;
 use crate::canvas::Origin;
+use abra_core::Anchor;
 
 use super::layer_inner::LayerInner;
 use super::options_new_layer::NewLayerOptions;
@@ -265,7 +267,7 @@ impl CanvasInner {
         }
         let child_blend = child_canvas.blend_mode();
         let child_opacity = child_canvas.opacity();
-        blend_images_at_with_opacity(dest, &child_result, 0, 0, dest_x, dest_y, child_blend, child_opacity);
+        blend_images_at_with_opacity(dest, &child_result, child_blend, child_opacity, (dest_x, dest_y));
       }
     }
 
@@ -286,7 +288,7 @@ impl CanvasInner {
         };
         let (x, y) = layer_ref.position();
         let image = layer_ref.image();
-        blend_images_at_with_opacity(dest, &image, 0, 0, offset_x + x, offset_y + y, blend, opacity);
+        blend_images_at_with_opacity(dest, &image, blend, opacity, (offset_x + x, offset_y + y));
         first_layer = false;
       }
     }
@@ -393,6 +395,58 @@ impl CanvasInner {
     (width, height)
   }
 
+  /// Gets the union of all visible layer and child canvas rectangles in canvas coordinates.
+  /// Layer effects such as drop shadows and strokes are included.
+  pub fn bounds(&mut self) -> Rect {
+    if self.needs_recompose.get() {
+      self.update_canvas();
+    }
+    let mut bounds = Rect::default();
+    for child_canvas_rc in self.canvases.iter() {
+      let child_canvas = child_canvas_rc.lock().unwrap();
+      let (x, y) = child_canvas.position();
+      let (width, height) = child_canvas.get_result_image().dimensions::<u32>();
+      bounds = bounds.union(&Rect::new(x, y, width, height));
+    }
+    for layer in self.layers.iter() {
+      let mut layer_ref = layer.lock().unwrap();
+      layer_ref.apply_pending_effects();
+      if layer_ref.is_visible() {
+        let (x, y) = layer_ref.position();
+        let (width, height) = layer_ref.dimensions::<u32>();
+        bounds = bounds.union(&Rect::new(x, y, width, height));
+      }
+    }
+    bounds
+  }
+
+  /// Resizes the canvas to its content bounds and moves the content to the top-left corner.
+  /// Anchors are removed so layers and child canvases stay where they are relative to each other.
+  pub fn fit_to_content(&mut self) {
+    let bounds = self.bounds();
+    if bounds.is_empty() {
+      return;
+    }
+    for child_canvas_rc in self.canvases.iter() {
+      let child_inner_rc = child_canvas_rc.lock().unwrap().inner_rc();
+      let mut child_inner = child_inner_rc.lock().unwrap();
+      let (x, y) = child_inner.position();
+      child_inner.anchor = None;
+      child_inner.set_global_position(x - bounds.x, y - bounds.y);
+    }
+    for layer in self.layers.iter() {
+      let mut layer_ref = layer.lock().unwrap();
+      let (x, y) = layer_ref.position();
+      layer_ref.clear_anchor_internal();
+      layer_ref.set_position_internal(x - bounds.x, y - bounds.y);
+    }
+    // Keep the content in place within a parent canvas
+    self.x.set(self.x.get() + bounds.x);
+    self.y.set(self.y.get() + bounds.y);
+    self.set_canvas_size(bounds.width, bounds.height);
+    self.mark_dirty();
+  }
+
   /// Sets the origin point (anchor position within the canvas bounds).
   pub fn set_origin(&mut self, origin: Origin) {
     self.origin = origin;
@@ -406,8 +460,14 @@ impl CanvasInner {
   /// Flattens all layers in the canvas into a single layer.
   /// All layers will be merged into one layer and removed.
   pub fn flatten(&mut self) {
+    self.flatten_onto(Color::transparent());
+  }
+
+  /// Flattens all layers into a single layer composited over a solid background color.
+  pub fn flatten_onto(&mut self, background: Color) {
     self.update_canvas();
-    let flattened_image = (*self.result).clone();
+    let mut flattened_image = (*self.result).clone();
+    flattened_image.flatten_onto(background);
     self.layers.clear();
     let mut flattened_layer = LayerInner::new("Flattened Layer", std::sync::Arc::new(flattened_image));
     flattened_layer.set_visible(true);
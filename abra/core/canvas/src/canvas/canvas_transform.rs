@@ -1,9 +1,11 @@
 //! Transform operations for canvases.
 
 use abra_core::Crop;
+use abra_core::Orientation;
 use abra_core::Resize;
 use abra_core::Rotate;
 use abra_core::TransformAlgorithm;
+use abra_core::fit_size;
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -269,6 +271,19 @@ impl Resize for CanvasTransform {
       recenter_layers(&mut canvas, false);
     }
   }
+
+  fn resize_to_fit(
+    &mut self, p_max_width: u32, p_max_height: u32, p_allow_upscale: bool,
+    algorithm: impl Into<Option<TransformAlgorithm>>,
+  ) -> (u32, u32) {
+    let canvas = self.canvas.lock().unwrap();
+    let (old_width, old_height) = (canvas.width.get(), canvas.height.get());
+    drop(canvas);
+
+    let (new_width, new_height) = fit_size(old_width, old_height, p_max_width, p_max_height, p_allow_upscale);
+    self.resize(new_width, new_height, algorithm);
+    (new_width, new_height)
+  }
 }
 
 impl Crop for CanvasTransform {
@@ -289,8 +304,13 @@ impl Rotate for CanvasTransform {
       let canvas = self.canvas.lock().unwrap();
       let algorithm = p_algorithm.into();
       let degrees = p_degrees.into();
+      let orientation = Orientation::from_degrees(degrees);
       for i in 0..canvas.layers.len() {
-        canvas.layers[i].lock().unwrap().image_mut().rotate(degrees, algorithm);
+        let mut layer = canvas.layers[i].lock().unwrap();
+        match orientation {
+          Some(orientation) => layer.orient(orientation),
+          None => layer.image_mut().rotate(degrees, algorithm),
+        }
       }
       canvas.mark_dirty();
     }
@@ -300,7 +320,10 @@ impl Rotate for CanvasTransform {
     {
       let canvas = self.canvas.lock().unwrap();
       for i in 0..canvas.layers.len() {
-        canvas.layers[i].lock().unwrap().image_mut().flip_horizontal();
+        canvas.layers[i]
+          .lock()
+          .unwrap()
+          .orient(Orientation::IDENTITY.flipped_horizontal());
       }
       canvas.mark_dirty();
     }
@@ -310,7 +333,10 @@ impl Rotate for CanvasTransform {
     {
       let canvas = self.canvas.lock().unwrap();
       for i in 0..canvas.layers.len() {
-        canvas.layers[i].lock().unwrap().image_mut().flip_vertical();
+        canvas.layers[i]
+          .lock()
+          .unwrap()
+          .orient(Orientation::IDENTITY.flipped_vertical());
       }
       canvas.mark_dirty();
     }
@@ -11,9 +11,9 @@ use abra_core::blend::RGBA;
 use abra_core::image::image_ext::ImageRef;
 use std::sync::MutexGuard;
 
-pub use super::anchor::Anchor;
 pub use super::layer_transform::LayerTransform;
 pub use super::origin::Origin;
+use abra_core::Anchor;
 
 #[derive(Debug, Clone, Copy)]
 pub enum AdjustmentLayerType {
@@ -1,16 +1,18 @@
 //! The internal layer implementation.
 
 use abra_core::Image;
+use abra_core::Orientation;
 use abra_core::blend;
 use abra_core::blend::RGBA;
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::OnceLock;
 
-use crate::Anchor;
 use crate::Origin;
 use crate::canvas::canvas_inner::CanvasInner;
 use crate::effects::LayerEffects;
+use abra_core::Anchor;
 
 /// The internal layer implementation - provides the mutable reference API.
 pub struct LayerInner {
@@ -18,6 +20,12 @@ pub struct LayerInner {
   name: String,
   /// The image data of the layer.
   image: Arc<Image>,
+  /// Quarter turns and flips that have not been applied to `image` yet. They are combined as they come in and only
+  /// moved into the pixels when the image is read or edited, so turns that cancel out cost nothing.
+  orientation: Orientation,
+  /// `image` with `orientation` applied, built the first time the image is read while an orientation is pending.
+  /// Edits and new orientations move it into `image` or drop it.
+  oriented: OnceLock<Image>,
   /// Whether the layer is visible.
   visible: bool,
   /// The opacity of the layer.
@@ -41,8 +49,12 @@ pub struct LayerInner {
   anchor_dimensions: Option<(u32, u32)>,
   /// The positional offset applied when anchoring so effects like drop shadow don't shift placement.
   anchor_offset: (i32, i32),
+  /// An extra offset in pixels applied after anchoring, e.g. to nudge a centered layer to the right.
+  nudge: (i32, i32),
   /// The effects that will be applied to this layer during rendering.
   effects: LayerEffects,
+  /// Whether `effects` have already been baked into `image`, so recomposing doesn't apply them twice.
+  effects_applied: bool,
   /// The type of adjustment layer, if this is an adjustment layer.
   adjustment_layer_type: Option<crate::AdjustmentLayerType>,
 }
@@ -52,7 +64,7 @@ impl Debug for LayerInner {
     f.debug_struct("LayerInner")
       .field("id", &self.id())
       .field("name", &self.name)
-      .field("dimensions", &self.image.dimensions::<u32>())
+      .field("dimensions", &self.dimensions::<u32>())
       .field("visible", &self.visible)
       .field("opacity", &self.opacity)
       .field("blend_mode", &"function pointer")
@@ -67,6 +79,8 @@ impl Default for LayerInner {
       id: uuid::Uuid::new_v4().to_string(),
       name: "Layer".to_string(),
       image: Arc::new(Image::new(1, 1)),
+      orientation: Orientation::IDENTITY,
+      oriented: OnceLock::new(),
       visible: true,
       opacity: 1.0,
       blend_mode: blend::normal,
@@ -77,7 +91,9 @@ impl Default for LayerInner {
       origin: Origin::default(),
       anchor_dimensions: None,
       anchor_offset: (0, 0),
+      nudge: (0, 0),
       effects: LayerEffects::new(),
+      effects_applied: false,
       adjustment_layer_type: None,
     }
   }
@@ -164,6 +180,7 @@ impl LayerInner {
   /// Sets the effects configuration for this layer and marks canvas dirty.
   pub fn set_effects(&mut self, effects: LayerEffects) {
     self.effects = effects;
+    self.effects_applied = false;
     self.mark_dirty();
   }
 
@@ -198,9 +215,21 @@ impl LayerInner {
     self.anchor_offset = (0, 0);
   }
 
+  /// Sets an extra offset in pixels that moves the layer from its anchored position.
+  /// - `p_dx`: The horizontal offset, where positive values move the layer right.
+  /// - `p_dy`: The vertical offset, where positive values move the layer down.
+  pub fn set_nudge(&mut self, p_dx: i32, p_dy: i32) {
+    self.nudge = (p_dx, p_dy);
+  }
+
+  /// Gets the extra offset that moves the layer from its anchored position.
+  pub fn nudge(&self) -> (i32, i32) {
+    self.nudge
+  }
+
   /// Gets the anchor dimensions if set, otherwise returns image dimensions.
   pub fn anchor_dimensions(&self) -> (u32, u32) {
-    self.anchor_dimensions.unwrap_or_else(|| self.image.dimensions::<u32>())
+    self.anchor_dimensions.unwrap_or_else(|| self.dimensions::<u32>())
   }
 
   /// Sets the anchor dimensions to use for anchoring calculations.
@@ -221,8 +250,8 @@ impl LayerInner {
       let (x, y) = anchor.calculate_position(canvas_width, canvas_height, self_width as i32, self_height as i32);
       // Position the layer directly at the calculated anchor position
       // The anchor calculation already handles proper centering/positioning
-      self.x = x + self.anchor_offset.0;
-      self.y = y + self.anchor_offset.1;
+      self.x = x + self.anchor_offset.0 + self.nudge.0;
+      self.y = y + self.anchor_offset.1 + self.nudge.1;
     }
   }
 
@@ -232,7 +261,9 @@ impl LayerInner {
     T: TryFrom<u64>,
     <T as TryFrom<u64>>::Error: std::fmt::Debug,
   {
-    self.image.dimensions::<T>()
+    let (width, height) = self.image.dimensions::<u64>();
+    let (width, height) = self.orientation.dimensions(width, height);
+    (T::try_from(width).unwrap(), T::try_from(height).unwrap())
   }
 
   /// Gets the position of the image within the layer
@@ -265,19 +296,52 @@ impl LayerInner {
     self.visible
   }
 
-  /// Gets a reference to the image
+  /// Gets a reference to the image with any pending orientation applied.
+  /// The oriented copy is made on the first read and kept until the layer is edited or oriented again.
   pub fn image(&self) -> &Image {
-    &self.image
+    if self.orientation.is_identity() {
+      return &self.image;
+    }
+    self.oriented.get_or_init(|| {
+      let mut image = Image::clone(&self.image);
+      self.orientation.apply(&mut image);
+      image
+    })
   }
 
-  /// Gets a mutable reference to the image using copy-on-write semantics.
+  /// Gets a mutable reference to the image using copy-on-write semantics, applying any pending orientation first.
   /// If the Arc has multiple owners, this will clone the image.
   pub fn image_mut(&mut self) -> &mut Image {
+    self.apply_orientation();
     Arc::make_mut(&mut self.image)
   }
 
+  /// Adds quarter turns or flips to the layer without touching the pixels.
+  /// They are applied the next time the image is read or edited.
+  /// - `p_orientation`: The orientation applied after any pending one.
+  pub fn orient(&mut self, p_orientation: Orientation) {
+    self.orientation = self.orientation.then(p_orientation);
+    self.oriented = OnceLock::new();
+  }
+
+  /// Moves the pending orientation into the pixels of the image, reusing the oriented copy when it was read.
+  fn apply_orientation(&mut self) {
+    if self.orientation.is_identity() {
+      return;
+    }
+    match self.oriented.take() {
+      Some(oriented) => self.image = Arc::new(oriented),
+      None => self.orientation.apply(Arc::make_mut(&mut self.image)),
+    }
+    self.orientation = Orientation::IDENTITY;
+  }
+
   /// Applies any pending effects to the layer's image, updating anchor dimensions and offset as needed.
   pub fn apply_pending_effects(&mut self) {
+    if self.effects_applied {
+      return;
+    }
+    self.apply_orientation();
     let image_arc = self.image.clone();
     // Use the new apply_with_offset to get both the new image and padding offset
     let result = self.effects.apply_with_offset(image_arc);
@@ -288,6 +352,7 @@ impl LayerInner {
     let (pad_left, pad_top) = result.offset;
     self.set_anchor_offset(-pad_left, -pad_top);
     self.image = result.image;
+    self.effects_applied = true;
   }
 
   /// Sets the index of the layer within the canvas's layer stack
@@ -379,6 +444,12 @@ impl LayerInner {
     self.y = y;
   }
 
+  /// Removes the anchor so the layer stays at its current position, without triggering a recompose.
+  /// This is used internally when the canvas is resized to fit its content.
+  pub fn clear_anchor_internal(&mut self) {
+    self.anchor = None;
+  }
+
   /// Duplicates the layer within the same canvas.
   /// This returns a Layer (the public wrapper), not the raw Rc<Mutex<LayerInner>>.
   pub fn duplicate(&self) -> super::Layer {
@@ -413,20 +484,47 @@ mod tests {
   fn apply_pending_sets_anchor_offset_for_drop_shadow() {
     let img = Arc::new(Image::new(1, 1));
     let mut layer = LayerInner::new("test", img.clone());
-    let shadow = DropShadow::new().with_distance(2.0).with_size(3.0);
+    let shadow = DropShadow::new().with_distance(2.0).with_size(1.0).with_blur(3.0);
     layer.set_effects(LayerEffects::new().with_drop_shadow(shadow.clone()));
 
     // Compute expected padding following the same logic as apply_drop_shadow_with_offset
     let angle_rad = shadow.angle.to_radians();
     let offset_x = (shadow.distance * angle_rad.cos()).round() as i32;
     let offset_y = (shadow.distance * angle_rad.sin()).round() as i32;
-    let blur_padding = shadow.size as i32;
-    let pad_left = (-offset_x).max(0) + blur_padding;
-    let pad_top = (-offset_y).max(0) + blur_padding;
+    let extent_padding = (shadow.size + shadow.blur) as i32;
+    let pad_left = (-offset_x).max(0) + extent_padding;
+    let pad_top = (-offset_y).max(0) + extent_padding;
     layer.apply_pending_effects();
     assert_eq!(layer.anchor_dimensions(), (1, 1));
     assert_eq!(layer.anchor_offset, (-pad_left, -pad_top));
   }
+
+  #[test]
+  fn quarter_turns_are_deferred_until_the_image_is_read() {
+    let mut image = Image::new(3, 2);
+    image.set_pixel(0, 0, (255, 0, 0, 255));
+    let img = Arc::new(image);
+    let mut layer = LayerInner::new("test", img.clone());
+
+    // A full turn cancels out, so the pixels are never copied.
+    for _ in 0..4 {
+      layer.orient(Orientation::from_degrees(90).unwrap());
+    }
+    assert!(Arc::ptr_eq(&layer.image, &img));
+    assert_eq!(layer.image().get_pixel(0, 0), Some((255, 0, 0, 255)));
+
+    layer.orient(Orientation::from_degrees(90).unwrap());
+    assert!(Arc::ptr_eq(&layer.image, &img));
+    assert_eq!(layer.dimensions::<u32>(), (2, 3));
+    assert_eq!(layer.image().get_pixel(1, 0), Some((255, 0, 0, 255)));
+    assert_eq!(layer.image().dimensions::<u32>(), (2, 3));
+    // Reading leaves the stored pixels alone, and editing reuses the copy that was read.
+    assert!(Arc::ptr_eq(&layer.image, &img));
+    layer.image_mut().set_pixel(0, 0, (0, 255, 0, 255));
+    assert_eq!(layer.image().get_pixel(1, 0), Some((255, 0, 0, 255)));
+    assert_eq!(layer.image().get_pixel(0, 0), Some((0, 255, 0, 255)));
+    assert_eq!(img.dimensions::<u32>(), (3, 2));
+  }
 }
 
 impl Clone for LayerInner {
@@ -435,6 +533,8 @@ impl Clone for LayerInner {
       id: uuid::Uuid::new_v4().to_string(),
       name: self.name.clone(),
       image: self.image.clone(),
+      orientation: self.orientation,
+      oriented: self.oriented.clone(),
       blend_mode: self.blend_mode,
       opacity: self.opacity,
       visible: self.visible,
@@ -445,7 +545,9 @@ impl Clone for LayerInner {
       origin: self.origin,
       anchor_dimensions: self.anchor_dimensions,
       anchor_offset: self.anchor_offset,
+      nudge: self.nudge,
       effects: self.effects.clone(),
+      effects_applied: self.effects_applied,
       adjustment_layer_type: self.adjustment_layer_type.clone(),
     }
   }
@@ -1,9 +1,9 @@
 //! Utilities for applying layer options when creating a new layer.
 
-use super::anchor::Anchor;
 use super::layer_inner::LayerInner;
 use super::layer_size_applier;
 use super::options_new_layer::NewLayerOptions;
+use abra_core::Anchor;
 
 /// Applies layer options (anchor, size, opacity, blend mode) to a newly created layer.
 ///
@@ -26,6 +26,11 @@ pub(crate) fn apply_layer_options(
         layer.anchor_to_canvas(Anchor::Center);
       }
 
+      // Apply the offset from the anchored position
+      if let Some((dx, dy)) = opts.offset {
+        layer.set_nudge(dx, dy);
+      }
+
       // Apply size
       if let Some(size) = opts.size {
         layer_size_applier::apply_layer_size(layer, size, canvas_width, canvas_height);
@@ -47,3 +52,23 @@ pub(crate) fn apply_layer_options(
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::{Canvas, NewLayerOptions};
+  use abra_core::Anchor;
+  use abra_core::{Color, Image};
+  use std::sync::Arc;
+
+  #[test]
+  fn offset_moves_layer_from_anchor() {
+    let image = Arc::new(Image::new_from_color(20, 20, Color::red()));
+    let options = NewLayerOptions::new().with_anchor(Anchor::Center).with_offset(10, -5);
+    let canvas = Canvas::new_blank("canvas", 100, 100).add_layer_from_image("square", image, options);
+    // Centered at (40, 40), then moved to (50, 35).
+    let image = canvas.as_image();
+    let is_red = |x: u32, y: u32| image.get_pixel(x, y) == Some((255, 0, 0, 255));
+    assert!(is_red(50, 35) && is_red(69, 54));
+    assert!(!is_red(49, 45) && !is_red(60, 34) && !is_red(70, 45) && !is_red(60, 55));
+  }
+}
@@ -47,5 +47,50 @@ pub(crate) fn apply_layer_size(layer: &mut LayerInner, size: LayerSize, canvas_w
     LayerSize::Percentage(amount, algorithm) => {
       layer.image_mut().resize_percentage(amount, algorithm);
     }
+    LayerSize::FitWidth(width, algorithm) => {
+      let (layer_width, layer_height) = layer.dimensions::<u32>();
+      let height = (width as f32 * layer_height as f32 / layer_width.max(1) as f32)
+        .round()
+        .max(1.0) as u32;
+      layer.image_mut().resize(width, height, algorithm);
+    }
+    LayerSize::FitHeight(height, algorithm) => {
+      let (layer_width, layer_height) = layer.dimensions::<u32>();
+      let width = (height as f32 * layer_width as f32 / layer_height.max(1) as f32)
+        .round()
+        .max(1.0) as u32;
+      layer.image_mut().resize(width, height, algorithm);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{Canvas, LayerSize, NewLayerOptions};
+  use abra_core::Anchor;
+  use abra_core::{Color, Image};
+  use std::sync::Arc;
+
+  #[test]
+  fn fit_width_and_height_keep_aspect_ratio() {
+    let image = Arc::new(Image::new_from_color(400, 200, Color::red()));
+    let canvas = Canvas::new_blank("canvas", 400, 400)
+      .add_layer_from_image("wide", image.clone(), NewLayerOptions::new().with_size(LayerSize::FitWidth(200, None)))
+      .add_layer_from_image(
+        "tall",
+        image,
+        NewLayerOptions::new()
+          .with_size(LayerSize::FitHeight(50, None))
+          .with_anchor(Anchor::BottomRight),
+      );
+    assert_eq!(canvas.get_layer_by_name("wide").unwrap().dimensions::<u32>(), (200, 100));
+    assert_eq!(canvas.get_layer_by_name("tall").unwrap().dimensions::<u32>(), (100, 50));
+
+    // The anchors place the resized layers, centered and in the bottom right corner.
+    let image = canvas.as_image();
+    let is_red = |x: u32, y: u32| image.get_pixel(x, y) == Some((255, 0, 0, 255));
+    let is_empty = |x: u32, y: u32| image.get_pixel(x, y).is_some_and(|pixel| pixel.3 == 0);
+    assert!(is_red(105, 155) && is_red(294, 244) && is_empty(95, 200) && is_empty(200, 255));
+    assert!(is_red(305, 355) && is_red(395, 395) && is_empty(295, 375));
   }
 }
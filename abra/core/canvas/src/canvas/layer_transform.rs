@@ -7,8 +7,8 @@
 use std::sync::Arc;
 use std::sync::Mutex;
 
-use abra_core::Rotate;
 use abra_core::{Crop, Resize, TransformAlgorithm};
+use abra_core::{Orientation, Rotate};
 
 use super::layer_inner::LayerInner;
 
@@ -78,6 +78,19 @@ impl Resize for LayerTransform {
       .resize_height_relative(height, algorithm);
     self.layer.lock().unwrap().mark_dirty();
   }
+
+  fn resize_to_fit(
+    &mut self, max_width: u32, max_height: u32, allow_upscale: bool, algorithm: impl Into<Option<TransformAlgorithm>>,
+  ) -> (u32, u32) {
+    let size = self
+      .layer
+      .lock()
+      .unwrap()
+      .image_mut()
+      .resize_to_fit(max_width, max_height, allow_upscale, algorithm);
+    self.layer.lock().unwrap().mark_dirty();
+    size
+  }
 }
 
 impl Crop for LayerTransform {
@@ -89,21 +102,29 @@ impl Crop for LayerTransform {
 
 impl Rotate for LayerTransform {
   fn rotate(&mut self, angle_in_degrees: impl Into<f64>, algorithm: impl Into<Option<TransformAlgorithm>>) {
+    let degrees = angle_in_degrees.into();
+    // Quarter turns are deferred and combined with other quarter turns and flips until the pixels are needed.
+    match Orientation::from_degrees(degrees) {
+      Some(orientation) => self.layer.lock().unwrap().orient(orientation),
+      None => self.layer.lock().unwrap().image_mut().rotate(degrees, algorithm),
+    }
+    self.layer.lock().unwrap().mark_dirty();
+  }
+  fn flip_horizontal(&mut self) {
     self
       .layer
       .lock()
       .unwrap()
-      .image_mut()
-      .rotate(angle_in_degrees, algorithm);
-    self.layer.lock().unwrap().mark_dirty();
-  }
-  fn flip_horizontal(&mut self) {
-    self.layer.lock().unwrap().image_mut().flip_horizontal();
+      .orient(Orientation::IDENTITY.flipped_horizontal());
     self.layer.lock().unwrap().mark_dirty();
   }
 
   fn flip_vertical(&mut self) {
-    self.layer.lock().unwrap().image_mut().flip_vertical();
+    self
+      .layer
+      .lock()
+      .unwrap()
+      .orient(Orientation::IDENTITY.flipped_vertical());
     self.layer.lock().unwrap().mark_dirty();
   }
 }
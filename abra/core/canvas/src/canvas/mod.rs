@@ -1,6 +1,5 @@
 //! Canvas management and layer composition.
 
-mod anchor;
 mod canvas;
 pub(crate) mod canvas_inner;
 mod canvas_transform;
@@ -13,7 +12,6 @@ mod options_add_canvas;
 mod options_new_layer;
 mod origin;
 
-pub use anchor::Anchor;
 pub use canvas::Canvas;
 pub use canvas_transform::CanvasTransform;
 pub use layer::{AdjustmentLayerType, Layer};
@@ -1,4 +1,4 @@
-use crate::Anchor;
+use abra_core::Anchor;
 
 #[derive(Clone)]
 /// Additional options for adding a canvas to another canvas.
@@ -5,7 +5,7 @@ use abra_core::{
   blend::{self, RGBA},
 };
 
-use super::anchor::Anchor;
+use abra_core::Anchor;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 /// How the image will be resized when added as a layer.
@@ -35,6 +35,20 @@ pub enum LayerSize {
   /// Size::Percentage(percentage, None);
   /// ```
   Percentage(f32, Option<TransformAlgorithm>),
+  /// Resize the image to a width in pixels, with the height following from the image's aspect ratio.
+  /// Defaults to the Auto resize algorithm.
+  /// ```ignore
+  /// // A 400x200 image becomes 200x100.
+  /// Size::FitWidth(200, None);
+  /// ```
+  FitWidth(u32, Option<TransformAlgorithm>),
+  /// Resize the image to a height in pixels, with the width following from the image's aspect ratio.
+  /// Defaults to the Auto resize algorithm.
+  /// ```ignore
+  /// // A 400x200 image becomes 200x100.
+  /// Size::FitHeight(100, None);
+  /// ```
+  FitHeight(u32, Option<TransformAlgorithm>),
 }
 
 /// Additional options for creating a new layer in a canvas.
@@ -49,6 +63,8 @@ pub struct NewLayerOptions {
   /// How the image will be sized when added as a layer.
   /// The image can be left at its original size, stretched, or constrained to fit within the canvas.
   pub size: Option<LayerSize>,
+  /// An offset in pixels applied after anchoring, e.g. to nudge a centered layer to the right.
+  pub offset: Option<(i32, i32)>,
 }
 
 impl Default for NewLayerOptions {
@@ -58,6 +74,7 @@ impl Default for NewLayerOptions {
       opacity: Some(1.0),
       blend_mode: Some(blend::normal),
       size: Some(LayerSize::Maintain),
+      offset: None,
     }
   }
 }
@@ -82,6 +99,16 @@ impl NewLayerOptions {
     self
   }
 
+  /// Sets an offset that moves the layer from its anchored position.
+  /// ```ignore
+  /// // Centered, then nudged 10 pixels right and 5 pixels up.
+  /// NewLayerOptions::new().with_anchor(Anchor::Center).with_offset(10, -5);
+  /// ```
+  pub fn with_offset(mut self, dx: i32, dy: i32) -> Self {
+    self.offset = Some((dx, dy));
+    self
+  }
+
   /// Sets the opacity of the layer.
   /// The opacity value should be between 0.0 (completely transparent) and 1.0 (completely opaque).
   pub fn with_opacity(mut self, opacity: f32) -> Self {
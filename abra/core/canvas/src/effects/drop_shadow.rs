@@ -1,7 +1,9 @@
 use abra_core::blend::{self, RGBA, blend_images_at_with_opacity, normal};
-use abra_core::{Color, Fill, Image};
+use abra_core::{Channels, Color, Fill, Image};
 
 use filters::blur::gaussian_blur;
+
+use abra_core::image::distance::distance_field;
 use rayon::prelude::*;
 use std::sync::Arc;
 use std::time::Instant;
@@ -9,7 +11,7 @@ use std::time::Instant;
 #[derive(Clone, Debug)]
 /// Options for configuring a drop shadow effect.
 pub struct DropShadow {
-  /// The color of the shadow in RGBA format.
+  /// The color of the shadow in RGBA format. Only solid colors are supported.
   pub fill: Fill,
   /// The blend mode used to combine the shadow with the layer.
   pub blend_mode: fn(RGBA, RGBA) -> RGBA,
@@ -21,8 +23,12 @@ pub struct DropShadow {
   pub distance: f32,
   /// The spread of the shadow between 0.0 and 1.0
   pub spread: f32,
-  /// The blur radius of the shadow.
+  /// How far the shadow grows beyond the edges of the layer in pixels, before it is blurred.
   pub size: f32,
+  /// The blur radius of the shadow in pixels.
+  pub blur: f32,
+  /// Whether the layer hides the shadow beneath it, so the shadow does not show through semi-transparent pixels.
+  pub knockout: bool,
 }
 
 impl DropShadow {
@@ -30,8 +36,10 @@ impl DropShadow {
   /// Default values:
   /// - distance: 5.0 pixels
   /// - angle: 45.0 degrees
-  /// - blur_radius: 5.0 pixels
-  /// - color: black with 60% opacity (0, 0, 0, 153)
+  /// - size: 0.0 pixels
+  /// - blur: 5.0 pixels
+  /// - color: black with 35% opacity
+  /// - knockout: off
   pub fn new() -> Self {
     DropShadow {
       fill: Fill::Solid(Color::black()),
@@ -40,7 +48,9 @@ impl DropShadow {
       angle: 45.0,
       distance: 5.0,
       spread: 0.0,
-      size: 5.0,
+      size: 0.0,
+      blur: 5.0,
+      knockout: false,
     }
   }
 
@@ -56,9 +66,15 @@ impl DropShadow {
     self
   }
 
-  /// Sets the size of the shadow blur.
+  /// Sets how far the shadow grows beyond the edges of the layer in pixels, before it is blurred.
   pub fn with_size(mut self, size: impl Into<f64>) -> Self {
-    self.size = size.into() as f32;
+    self.size = size.into().max(0.0) as f32;
+    self
+  }
+
+  /// Sets the blur radius of the shadow in pixels.
+  pub fn with_blur(mut self, radius: impl Into<f64>) -> Self {
+    self.blur = radius.into().max(0.0) as f32;
     self
   }
 
@@ -74,6 +90,12 @@ impl DropShadow {
     self
   }
 
+  /// Sets the color of the shadow. The color's alpha is combined with the shadow's opacity.
+  pub fn with_color(mut self, color: Color) -> Self {
+    self.fill = Fill::Solid(color);
+    self
+  }
+
   /// Sets the opacity of the shadow (0.0 to 1.0).
   pub fn with_opacity(mut self, opacity: impl Into<f64>) -> Self {
     self.opacity = opacity.into() as f32;
@@ -85,6 +107,13 @@ impl DropShadow {
     self.blend_mode = blend_mode;
     self
   }
+
+  /// Sets whether the layer hides the shadow beneath it, so the shadow does not show through
+  /// semi-transparent pixels.
+  pub fn with_knockout(mut self, knockout: bool) -> Self {
+    self.knockout = knockout;
+    self
+  }
 }
 
 /// Variant of apply_drop_shadow that returns both the final image and the padding offset
@@ -92,45 +121,28 @@ impl DropShadow {
 pub(crate) fn apply_drop_shadow_with_offset(image: Arc<Image>, options: &DropShadow) -> (Arc<Image>, (i32, i32)) {
   let _duration = Instant::now();
 
-  // Skip if blur radius is 0 (no visible shadow)
-  if options.size <= 0.0 {
+  // Skip if the shadow is fully transparent
+  if options.opacity <= 0.0 {
     return (image, (0, 0));
   }
 
   let original_image = image.as_ref();
   let (width, height) = original_image.dimensions::<usize>();
 
-  // Create shadow by copying the original image
-  let mut shadow_image = original_image.clone();
-
-  // Extract alpha channel from original (or create one if the image has no alpha)
-  // This will be used to create the shadow shape
-  let shadow_pixels = shadow_image.rgba();
-  let alpha_channel: Vec<u8> = shadow_pixels
-    .chunks(4)
-    .map(|pixel| {
-      let alpha = pixel[3];
+  // Extract alpha channel from original to create the shadow shape
+  let mut shadow_shape = original_image.clone();
+  if let Some(shape_pixels) = shadow_shape.colors().as_slice_mut() {
+    for pixel in shape_pixels.chunks_mut(4) {
       // If alpha is mostly opaque, set it to fully opaque for the shadow mask
-      if alpha > 128 { 255 } else { alpha }
-    })
-    .collect();
-
-  // Colorize to the shadow color with opacity applied
-  colorize_image(&mut shadow_image, options.fill.clone(), options.opacity);
-
-  // Apply the alpha channel to create the shadow shape
-  // Write the alpha channel back into the shadow image using a mutable slice
-  if let Some(shadow_pixels_mut) = shadow_image.colors().as_slice_mut() {
-    for (i, &alpha) in alpha_channel.iter().enumerate() {
-      if i * 4 + 3 < shadow_pixels_mut.len() {
-        shadow_pixels_mut[i * 4 + 3] = alpha;
+      if pixel[3] > 128 {
+        pixel[3] = 255;
       }
     }
   }
 
   // Apply spread if needed (spread expands or contracts the shadow)
   if options.spread > 0.0 {
-    apply_spread(&mut shadow_image, options.spread);
+    apply_spread(&mut shadow_shape, options.spread);
   }
 
   // Calculate offset from distance and angle
@@ -140,69 +152,101 @@ pub(crate) fn apply_drop_shadow_with_offset(image: Arc<Image>, options: &DropSha
 
   // Determine padding needed for the expanded canvas
   // Positive offset means shadow is displaced in that direction, so we need padding on the opposite side
-  // Also add padding for the blur radius to prevent blur artifacts at the edges
-  let blur_padding = options.size as i32;
-  let padding_left = (-offset_x).max(0) + blur_padding;
-  let padding_top = (-offset_y).max(0) + blur_padding;
-  let padding_right = offset_x.max(0) + blur_padding;
-  let padding_bottom = offset_y.max(0) + blur_padding;
+  // Also add padding for the size and blur radius so the shadow is not cut off at the edges
+  let extent_padding = options.size.ceil() as i32 + options.blur.ceil() as i32;
+  let padding_left = (-offset_x).max(0) + extent_padding;
+  let padding_top = (-offset_y).max(0) + extent_padding;
+  let padding_right = offset_x.max(0) + extent_padding;
+  let padding_bottom = offset_y.max(0) + extent_padding;
 
   // Create an expanded canvas to contain shadow offset
-  let canvas_width = width as u32 + padding_left as u32 + padding_right as u32;
-  let canvas_height = height as u32 + padding_top as u32 + padding_bottom as u32;
+  let canvas_width = width + padding_left as usize + padding_right as usize;
+  let canvas_height = height + padding_top as usize + padding_bottom as usize;
 
   // Position shadow at offset
-  let shadow_x = padding_left + offset_x;
-  let shadow_y = padding_top + offset_y;
-
-  // Create an expanded image to contain shadow offset
-  let mut composite = Image::new(canvas_width, canvas_height);
-  let empty_pixels = vec![0u8; (canvas_width * canvas_height * 4) as usize];
-  composite.set_rgba_owned(empty_pixels);
+  let shadow_x = (padding_left + offset_x) as usize;
+  let shadow_y = (padding_top + offset_y) as usize;
+
+  // Place the shadow shape at the offset position in the expanded canvas
+  let shape_pixels = shadow_shape.rgba();
+  let mut mask = vec![0u8; canvas_width * canvas_height];
+  for y in 0..height {
+    let row = (shadow_y + y) * canvas_width + shadow_x;
+    for x in 0..width {
+      mask[row + x] = shape_pixels[(y * width + x) * 4 + 3];
+    }
+  }
 
-  // Composite shadow at offset position with the configured blend mode and opacity
-  blend_images_at_with_opacity(&mut composite, &shadow_image, 0, 0, shadow_x, shadow_y, options.blend_mode, 1.0);
+  // Grow the shadow beyond the edges of the layer
+  if options.size > 0.0 {
+    grow_mask(&mut mask, canvas_width, canvas_height, options.size);
+  }
 
-  // Blur the shadow area in the composite
-  gaussian_blur(&mut composite, options.size as u32, None);
+  // Color the whole shadow so that blurring only softens its alpha
+  let color = match options.fill {
+    Fill::Solid(color) => color,
+    _ => Color::black(),
+  };
+  let shadow_pixels: Vec<u8> = mask
+    .iter()
+    .flat_map(|&alpha| [color.r, color.g, color.b, alpha])
+    .collect();
+  let mut shadow_image =
+    Image::new_from_pixels(canvas_width as u32, canvas_height as u32, shadow_pixels, Channels::RGBA);
+  gaussian_blur(&mut shadow_image, options.blur.round() as u32, None);
+
+  // Apply the shadow color's alpha and opacity, and knock the shadow out beneath the layer
+  let original_pixels = original_image.rgba();
+  let strength = color.a as f32 / 255.0 * options.opacity.clamp(0.0, 1.0);
+  if let Some(pixels) = shadow_image.colors().as_slice_mut() {
+    pixels
+      .par_chunks_mut(canvas_width * 4)
+      .enumerate()
+      .for_each(|(y, row)| {
+        for (x, pixel) in row.chunks_mut(4).enumerate() {
+          let mut alpha = pixel[3] as f32 * strength;
+          if options.knockout {
+            let (layer_x, layer_y) = (x as i32 - padding_left, y as i32 - padding_top);
+            if (0..width as i32).contains(&layer_x) && (0..height as i32).contains(&layer_y) {
+              let layer_alpha = original_pixels[(layer_y as usize * width + layer_x as usize) * 4 + 3];
+              alpha *= 1.0 - layer_alpha as f32 / 255.0;
+            }
+          }
+          pixel[3] = alpha.round() as u8;
+        }
+      });
+  }
 
-  // Reapply opacity to the blurred shadow (blur operation may have increased alpha)
-  if let Some(composite_pixels) = composite.colors().as_slice_mut() {
-    for chunk in composite_pixels.chunks_mut(4) {
-      chunk[3] = ((chunk[3] as f32) * options.opacity) as u8;
-    }
+  // Composite the shadow with the configured blend mode. Normal blending onto the empty image would
+  // only darken the soft edges of the shadow, so the shadow is used as is.
+  let mut composite = shadow_image;
+  if !std::ptr::fn_addr_eq(options.blend_mode, normal as fn(RGBA, RGBA) -> RGBA) {
+    let shadow_image = composite;
+    composite = Image::new(canvas_width as u32, canvas_height as u32);
+    blend_images_at_with_opacity(&mut composite, &shadow_image, options.blend_mode, 1.0, (0, 0));
   }
 
   // Composite original at padding position
-  blend_images_at_with_opacity(&mut composite, &original_image, 0, 0, padding_left, padding_top, blend::normal, 1.0);
+  blend_images_at_with_opacity(&mut composite, &original_image, blend::normal, 1.0, (padding_left, padding_top));
 
   // DebugEffects::DropShadow(options.clone(), duration.elapsed()).log();
 
   (Arc::new(composite), (padding_left, padding_top))
 }
 
-/// Converts an image to a single color while preserving and applying opacity to the alpha channel.
-fn colorize_image(image: &mut Image, fill: impl Into<Fill>, opacity: impl Into<f64>) {
-  let pixels = image.rgba();
-
-  let fill = fill.into();
-  let opacity = opacity.into();
-  let colorized: Vec<u8> = pixels
-    .par_chunks(4)
-    .flat_map_iter(|pixel| {
-      let color = match fill {
-        Fill::Solid(c) => c,
-        _ => Color::black(),
-      };
-      // Preserve the alpha channel from the original, apply opacity and color's alpha
-      let original_alpha = pixel[3] as f32 / 255.0;
-      let shadow_alpha = (color.a as f32 / 255.0) * original_alpha * opacity as f32;
-
-      vec![color.r, color.g, color.b, (shadow_alpha * 255.0) as u8]
-    })
-    .collect();
-
-  image.set_rgba_owned(colorized);
+/// Grows the opaque parts of an alpha mask outward with rounded corners.
+/// - `p_mask`: The alpha mask, one byte per pixel.
+/// - `p_width`: The width of the mask in pixels.
+/// - `p_height`: The height of the mask in pixels.
+/// - `p_size`: How far to grow in pixels.
+fn grow_mask(p_mask: &mut [u8], p_width: usize, p_height: usize, p_size: f32) {
+  let inside: Vec<bool> = p_mask.iter().map(|&alpha| alpha >= 128).collect();
+  let distances = distance_field(&inside, p_width, p_height);
+  // Anti-alias the new edge over one pixel.
+  for (alpha, distance) in p_mask.iter_mut().zip(distances) {
+    let coverage = (p_size + 0.5 - distance).clamp(0.0, 1.0);
+    *alpha = (*alpha).max((coverage * 255.0).round() as u8);
+  }
 }
 
 /// Applies spread to the shadow by dilating or eroding the alpha channel.
@@ -281,3 +325,50 @@ fn apply_spread(image: &mut Image, spread: impl Into<f32>) {
     image.set_rgba_owned(result);
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn colored_offset_blurred_shadow() {
+    let square = Arc::new(Image::new_from_color(10, 10, Color::white()));
+    let shadow = DropShadow::new()
+      .with_color(Color::red())
+      .with_angle(0.0)
+      .with_distance(10.0)
+      .with_blur(2.0)
+      .with_opacity(1.0);
+    let (image, (left, top)) = apply_drop_shadow_with_offset(square, &shadow);
+    assert_eq!((left, top), (2, 2));
+    assert_eq!(image.dimensions::<u32>(), (24, 14));
+    let pixel = |x: i32, y: i32| image.get_pixel((left + x) as u32, (top + y) as u32).unwrap();
+    // The layer covers the shadow, which shows to its right and fades out at its far edge.
+    assert_eq!(pixel(5, 5), (255, 255, 255, 255));
+    assert_eq!(pixel(15, 5), (255, 0, 0, 255));
+    let (r, g, b, a) = pixel(20, 5);
+    assert_eq!((r, g, b), (255, 0, 0));
+    assert!(a > 0 && a < 255, "edge alpha was {}", a);
+  }
+
+  #[test]
+  fn size_grows_the_shadow_and_knockout_hides_it() {
+    let dot = Arc::new(Image::new_from_color(1, 1, Color::from_rgba(255, 255, 255, 128)));
+    let shadow = DropShadow::new()
+      .with_distance(0.0)
+      .with_size(3.0)
+      .with_blur(0.0)
+      .with_opacity(1.0);
+    let (image, (left, top)) = apply_drop_shadow_with_offset(dot.clone(), &shadow);
+    assert_eq!((left, top), (3, 3));
+    // The shadow grows into a disc with a soft rim, so its corners stay empty.
+    assert_eq!(image.get_pixel(3, 1).unwrap().3, 255);
+    assert_eq!(image.get_pixel(3, 0).unwrap().3, 128);
+    assert_eq!(image.get_pixel(0, 0).unwrap().3, 0);
+    // The semi-transparent dot lets the shadow show through unless it is knocked out.
+    assert_eq!(image.get_pixel(3, 3).unwrap().3, 255);
+    let (image, _) = apply_drop_shadow_with_offset(dot, &shadow.with_knockout(true));
+    assert!(image.get_pixel(3, 3).unwrap().3 < 255);
+    assert_eq!(image.get_pixel(3, 1).unwrap().3, 255);
+  }
+}
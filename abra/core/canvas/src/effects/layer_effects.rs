@@ -47,7 +47,9 @@ impl LayerEffects {
     let mut offset = (0i32, 0i32);
 
     if let Some(stroke_opts) = &self.stroke {
-      result_image = apply_stroke(result_image, stroke_opts);
+      let (img, pad) = apply_stroke(result_image, stroke_opts);
+      result_image = img;
+      offset = (offset.0 + pad.0, offset.1 + pad.1);
     }
 
     if let Some(drop_shadow_opts) = &self.drop_shadow {
@@ -1,33 +1,34 @@
-use abra_core::{Color, Fill, Image, Path, Point};
+use abra_core::{Channels, Color, Fill, Image};
 
+use rayon::prelude::*;
 use std::sync::Arc;
 use std::time::Instant;
 
-use drawing::Brush;
-use drawing::Painter;
+use abra_core::image::distance::distance_field;
 
-#[derive(Clone, Debug)]
-/// Position of the outline relative to the shape.
-pub enum OutlinePosition {
-  /// Outline is drawn inside the shape.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Alignment of the stroke relative to the edge of the layer's opaque pixels.
+pub enum StrokeAlign {
+  /// Stroke is drawn inside the shape, over the layer's own pixels.
+  #[default]
   Inside,
-  /// Outline is drawn outside the shape.
-  Outside,
-  /// Outline is centered on the shape's edge. Half inside, half outside.
+  /// Stroke is centered on the shape's edge. Half inside, half outside.
   Center,
+  /// Stroke is drawn outside the shape, growing the layer's bounds so none of the layer is covered.
+  Outside,
 }
 
 #[derive(Clone, Debug)]
 /// Options for configuring a stroke effect.
 pub struct Stroke {
-  /// The color of the outline in RGBA format.
+  /// The color of the outline in RGBA format. Only solid colors are supported.
   pub fill: Fill,
-  /// The blend mode used to combine the outline with the layer.
+  /// The opacity of the outline (0.0 to 1.0).
   pub opacity: f32,
   /// The thickness of the outline.
   pub size: u32,
-  /// The position of the outline relative to the shape.
-  pub position: OutlinePosition,
+  /// The alignment of the outline relative to the shape's edge.
+  pub alignment: StrokeAlign,
 }
 
 impl Stroke {
@@ -35,12 +36,13 @@ impl Stroke {
   /// Default values:
   /// - size: 3.0 pixels
   /// - color: black with 100% opacity (0, 0, 0, 255)
+  /// - alignment: inside
   pub fn new() -> Self {
     Stroke {
       fill: Fill::Solid(Color::black()),
       opacity: 1.0,
       size: 3,
-      position: OutlinePosition::Inside,
+      alignment: StrokeAlign::Inside,
     }
   }
 
@@ -62,60 +64,122 @@ impl Stroke {
     self
   }
 
-  /// Sets the position of the stroke relative to the path: Inside, Outside or Center.
-  pub fn with_position(mut self, position: OutlinePosition) -> Self {
-    self.position = position;
+  /// Sets the alignment of the stroke relative to the shape's edge: Inside, Center or Outside.
+  pub fn with_alignment(mut self, alignment: StrokeAlign) -> Self {
+    self.alignment = alignment;
     self
   }
 }
 
-/// Applies a stroke effect to an image by drawing an outline around its edges.
-pub(crate) fn apply_stroke(p_image: Arc<Image>, p_options: &Stroke) -> Arc<Image> {
+/// Applies a stroke effect to an image by drawing an outline along the edges of its opaque pixels.
+/// Returns the stroked image and the padding offset used to position the original content within it
+/// (padding_left, padding_top), which is only non-zero when the stroke reaches outside the image.
+pub(crate) fn apply_stroke(p_image: Arc<Image>, p_options: &Stroke) -> (Arc<Image>, (i32, i32)) {
   let _duration = Instant::now();
-  let original_image = p_image.as_ref();
-  let (width, height) = original_image.dimensions::<u32>();
-
-  let mut composite = Image::new(width, height);
-  composite.copy_channel_data(original_image);
-
-  let max_x = (width.saturating_sub(1)) as i32;
-  let max_y = (height.saturating_sub(1)) as i32;
-
-  // We will build our path at the original image border coordinates.
-  let relative_path = Point::array(vec![(0, 0), (max_x, 0), (max_x, max_y), (0, max_y), (0, 0)]);
-
-  // Create a path from the calculated points.
-  let mut path = Path::new();
-  if !relative_path.is_empty() {
-    path.move_to((relative_path[0].x() as f32, relative_path[0].y() as f32));
-    for point in relative_path.iter().skip(1) {
-      path.line_to((point.x() as f32, point.y() as f32));
-    }
+  if p_options.size == 0 || p_options.opacity <= 0.0 {
+    return (p_image, (0, 0));
   }
 
-  // Respect configured opacity by adjusting fill alpha.
-  let color = match p_options.fill.clone() {
-    Fill::Solid(mut c) => {
-      let factor = p_options.opacity.clamp(0.0, 1.0);
-      let new_a = ((c.a as f32) * factor).round().clamp(0.0, 255.0) as u8;
-      c.a = new_a;
-      c
-    }
-    _ => Color::black(),
+  let original_image = p_image.as_ref();
+  let (width, height) = original_image.dimensions::<usize>();
+  let size = p_options.size as f32;
+
+  // The band of signed distances from the edge that the stroke covers, negative inside the shape.
+  let (band_start, band_end) = match p_options.alignment {
+    StrokeAlign::Inside => (-size, 0.0),
+    StrokeAlign::Center => (-size / 2.0, size / 2.0),
+    StrokeAlign::Outside => (0.0, size),
   };
+  // Grow the image so that the part of the stroke outside the shape is not cut off, plus a transparent
+  // border so that the edges of the image count as edges of the shape.
+  let padding = band_end.ceil() as usize;
+  let work_padding = padding + 1;
+  let work_width = width + work_padding * 2;
+  let work_height = height + work_padding * 2;
+
+  // Place the original in the expanded image
+  let original_pixels = original_image.rgba();
+  let mut pixels = vec![0u8; work_width * work_height * 4];
+  for y in 0..height {
+    let start = ((y + work_padding) * work_width + work_padding) * 4;
+    pixels[start..start + width * 4].copy_from_slice(&original_pixels[y * width * 4..(y + 1) * width * 4]);
+  }
 
-  // Use the Painter to render the stroke using a brush. Build a brush based on options
-  // with a hardness of 1.0 and paint the path directly into the composite image.
-  let brush = Brush::new()
-    .with_size(p_options.size)
-    .with_color(color)
-    .with_hardness(1.0);
+  // The distance from each pixel's center to the shape's edge, which lies half a pixel from the nearest
+  // pixel on the other side.
+  let inside: Vec<bool> = pixels.chunks(4).map(|pixel| pixel[3] >= 128).collect();
+  let outside: Vec<bool> = inside.iter().map(|inside| !inside).collect();
+  let to_inside = distance_field(&inside, work_width, work_height);
+  let to_outside = distance_field(&outside, work_width, work_height);
 
-  let mut painter = Painter::new(&mut composite);
-  // Pass ownership of the mask to the shader (clone the area)
-  painter.stroke_with_brush(&path, &brush);
+  let color = match p_options.fill {
+    Fill::Solid(color) => color,
+    _ => Color::black(),
+  };
+  let strength = color.a as f32 / 255.0 * p_options.opacity.clamp(0.0, 1.0);
+
+  // Composite the stroke over the layer, anti-aliasing both edges of the band over one pixel.
+  pixels.par_chunks_mut(4).enumerate().for_each(|(i, pixel)| {
+    let edge_distance = if inside[i] {
+      0.5 - to_outside[i]
+    } else {
+      to_inside[i] - 0.5
+    };
+    let coverage = (0.5 + (edge_distance - band_start).min(band_end - edge_distance)).clamp(0.0, 1.0);
+    let stroke_alpha = coverage * strength;
+    if stroke_alpha <= 0.0 {
+      return;
+    }
+    let layer_alpha = pixel[3] as f32 / 255.0;
+    let out_alpha = stroke_alpha + layer_alpha * (1.0 - stroke_alpha);
+    for (channel, stroke_channel) in pixel.iter_mut().zip([color.r, color.g, color.b]) {
+      let blended = stroke_channel as f32 * stroke_alpha + *channel as f32 * layer_alpha * (1.0 - stroke_alpha);
+      *channel = (blended / out_alpha).round() as u8;
+    }
+    pixel[3] = (out_alpha * 255.0).round() as u8;
+  });
 
   // DebugEffects::Stroke(p_options.clone(), duration.elapsed()).log();
 
-  Arc::new(composite)
+  // Drop the transparent border
+  let canvas_width = width + padding * 2;
+  let canvas_height = height + padding * 2;
+  let cropped: Vec<u8> = pixels
+    .chunks(work_width * 4)
+    .skip(1)
+    .take(canvas_height)
+    .flat_map(|row| &row[4..(canvas_width + 1) * 4])
+    .copied()
+    .collect();
+  let composite = Image::new_from_pixels(canvas_width as u32, canvas_height as u32, cropped, Channels::RGBA);
+  (Arc::new(composite), (padding as i32, padding as i32))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn stroke_alignment_relative_to_edge() {
+    let square = Arc::new(Image::new_from_color(10, 10, Color::white()));
+    let black = Some((0, 0, 0, 255));
+    let white = Some((255, 255, 255, 255));
+    let stroke = Stroke::new().with_size(2);
+
+    // Outside strokes grow the bounds and leave the square untouched.
+    let (image, offset) = apply_stroke(square.clone(), &stroke.clone().with_alignment(StrokeAlign::Outside));
+    assert_eq!((image.dimensions::<u32>(), offset), ((14, 14), (2, 2)));
+    assert_eq!((image.get_pixel(0, 7), image.get_pixel(1, 7), image.get_pixel(2, 7)), (black, black, white));
+
+    // Inside strokes keep the bounds and cover the square's outer pixels.
+    let (image, offset) = apply_stroke(square.clone(), &stroke.clone().with_alignment(StrokeAlign::Inside));
+    assert_eq!((image.dimensions::<u32>(), offset), ((10, 10), (0, 0)));
+    assert_eq!((image.get_pixel(0, 5), image.get_pixel(1, 5), image.get_pixel(2, 5)), (black, black, white));
+
+    // Centered strokes straddle the edge.
+    let (image, offset) = apply_stroke(square, &stroke.with_alignment(StrokeAlign::Center));
+    assert_eq!((image.dimensions::<u32>(), offset), ((12, 12), (1, 1)));
+    assert_eq!((image.get_pixel(0, 6), image.get_pixel(1, 6), image.get_pixel(2, 6)), (black, black, white));
+    assert_eq!(image.get_pixel(6, 6), white);
+  }
 }
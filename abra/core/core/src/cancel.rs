@@ -0,0 +1,49 @@
+//! Cooperative cancellation for long-running operations.
+
+use std::fmt::Display;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A flag that asks a long-running operation to stop early.
+///
+/// Clones share the same flag, so one clone can be handed to the operation while another is kept to cancel it, e.g.
+/// from a UI thread when a preview becomes stale. Operations check the flag between units of work such as rows or
+/// tiles and return `Cancelled` once it is set.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+  cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+  /// Creates a token that is not cancelled.
+  pub fn new() -> CancellationToken {
+    CancellationToken::default()
+  }
+
+  /// Asks every operation holding a clone of this token to stop.
+  pub fn cancel(&self) {
+    self.cancelled.store(true, Ordering::Relaxed);
+  }
+
+  /// Whether the token has been cancelled.
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::Relaxed)
+  }
+
+  /// Returns `Err(Cancelled)` if the token has been cancelled, so operations can stop with `?`.
+  pub fn check(&self) -> Result<(), Cancelled> {
+    if self.is_cancelled() { Err(Cancelled) } else { Ok(()) }
+  }
+}
+
+/// The error returned by an operation that stopped because its `CancellationToken` was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "The operation was cancelled")
+  }
+}
+
+impl std::error::Error for Cancelled {}
@@ -0,0 +1,73 @@
+/// Easing curves that reshape a linear 0-1 progression, e.g. the transition between two gradient stops.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Easing {
+  /// Constant speed.
+  #[default]
+  Linear,
+  /// Starts slowly and speeds up (cubic).
+  EaseIn,
+  /// Starts quickly and slows down (cubic).
+  EaseOut,
+  /// Starts and ends slowly (cubic).
+  EaseInOut,
+  /// A CSS-style cubic Bezier curve through (0, 0), (x1, y1), (x2, y2) and (1, 1).
+  /// The x values are clamped to 0-1 so the curve stays a function of time.
+  CubicBezier(f32, f32, f32, f32),
+}
+
+impl Easing {
+  /// Applies the easing curve to a progression value.
+  /// - `p_t`: The linear progression between 0 and 1.
+  pub fn apply(&self, p_t: f32) -> f32 {
+    let t = p_t.clamp(0.0, 1.0);
+    match *self {
+      Easing::Linear => t,
+      Easing::EaseIn => t * t * t,
+      Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+      Easing::EaseInOut => {
+        if t < 0.5 {
+          4.0 * t * t * t
+        } else {
+          1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+        }
+      }
+      Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1.clamp(0.0, 1.0), y1, x2.clamp(0.0, 1.0), y2, t),
+    }
+  }
+
+  /// Returns the easing that plays this curve backwards, e.g. `EaseIn` becomes `EaseOut`.
+  pub fn reversed(&self) -> Easing {
+    match *self {
+      Easing::Linear => Easing::Linear,
+      Easing::EaseIn => Easing::EaseOut,
+      Easing::EaseOut => Easing::EaseIn,
+      Easing::EaseInOut => Easing::EaseInOut,
+      Easing::CubicBezier(x1, y1, x2, y2) => Easing::CubicBezier(1.0 - x2, 1.0 - y2, 1.0 - x1, 1.0 - y1),
+    }
+  }
+}
+
+/// Evaluates a unit cubic Bezier easing curve at `p_x` by solving for the curve parameter.
+fn cubic_bezier(p_x1: f32, p_y1: f32, p_x2: f32, p_y2: f32, p_x: f32) -> f32 {
+  let bezier = |p_a: f32, p_b: f32, p_s: f32| {
+    let inv = 1.0 - p_s;
+    3.0 * inv * inv * p_s * p_a + 3.0 * inv * p_s * p_s * p_b + p_s * p_s * p_s
+  };
+
+  // x(s) is monotonic for control points in 0-1, so bisection always converges.
+  let (mut low, mut high) = (0.0f32, 1.0f32);
+  let mut s = p_x;
+  for _ in 0..32 {
+    let x = bezier(p_x1, p_x2, s);
+    if (x - p_x).abs() < 1e-5 {
+      break;
+    }
+    if x < p_x {
+      low = s;
+    } else {
+      high = s;
+    }
+    s = (low + high) / 2.0;
+  }
+  bezier(p_y1, p_y2, s)
+}
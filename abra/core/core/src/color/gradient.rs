@@ -1,6 +1,7 @@
 use std::fmt::{self, Display, Formatter};
 
-use crate::Color;
+use crate::geometry::{Path, PathMeasure};
+use crate::{Color, ColorSpace, Easing, Image};
 
 #[derive(Clone, Debug, Copy)]
 /// The color stops for a gradient.
@@ -9,6 +10,8 @@ pub struct ColorStop {
   pub color: Color,
   /// A value between 0 and 1 representing the x position of the stop.
   pub time: f32,
+  /// The easing used for the transition from this stop to the next one.
+  pub easing: Easing,
 }
 
 impl Display for ColorStop {
@@ -24,12 +27,56 @@ impl ColorStop {
     ColorStop {
       color: Color::default(),
       time: 0.0,
+      easing: Easing::Linear,
     }
   }
 
   /// Creates a new gradient color stop with the given color and time.
   pub fn new(color: Color, time: f32) -> ColorStop {
-    ColorStop { color, time }
+    ColorStop {
+      color,
+      time,
+      easing: Easing::Linear,
+    }
+  }
+
+  /// Sets the easing used for the transition from this stop to the next one.
+  pub fn with_easing(mut self, easing: Easing) -> ColorStop {
+    self.easing = easing;
+    self
+  }
+}
+
+/// The number of samples used to approximate an eased transition that is cut by `Gradient::subrange`.
+const SUBRANGE_SAMPLES: usize = 8;
+
+/// Describes how a gradient is extended beyond the 0 to 1 range.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RepeatMode {
+  /// The first and last colors are extended forever.
+  #[default]
+  Pad,
+  /// The gradient starts over every time it reaches the end.
+  Repeat,
+  /// The gradient goes back and forth, mirroring every other repetition.
+  Reflect,
+}
+
+impl RepeatMode {
+  /// Maps a time outside of the 0 to 1 range back into it.
+  /// - `p_time`: The time to map.
+  fn map(&self, p_time: f32) -> f32 {
+    if (0.0..=1.0).contains(&p_time) {
+      return p_time;
+    }
+    match self {
+      RepeatMode::Pad => p_time.clamp(0.0, 1.0),
+      RepeatMode::Repeat => p_time.rem_euclid(1.0),
+      RepeatMode::Reflect => {
+        let time = p_time.rem_euclid(2.0);
+        if time > 1.0 { 2.0 - time } else { time }
+      }
+    }
   }
 }
 
@@ -40,12 +87,21 @@ pub struct Gradient {
   stops: Vec<ColorStop>,
   /// The path defining the gradient direction (optional).
   direction: Option<crate::geometry::Path>,
+  /// The color space the stops are interpolated in.
+  color_space: ColorSpace,
+  /// How the gradient is extended beyond the 0 to 1 range.
+  repeat: RepeatMode,
 }
 
 impl Gradient {
   /// Creates a new gradient with the given stops.
   pub fn new(stops: Vec<ColorStop>) -> Gradient {
-    Gradient { stops, direction: None }
+    Gradient {
+      stops,
+      direction: None,
+      color_space: ColorSpace::Srgb,
+      repeat: RepeatMode::Pad,
+    }
   }
 
   /// Creates a new gradient that goes from one color to another.
@@ -53,6 +109,8 @@ impl Gradient {
     Gradient {
       stops: vec![ColorStop::new(from, 0.0), ColorStop::new(to, 1.0)],
       direction: None,
+      color_space: ColorSpace::Srgb,
+      repeat: RepeatMode::Pad,
     }
   }
 
@@ -61,9 +119,11 @@ impl Gradient {
     Gradient {
       stops: vec![
         ColorStop::new(from, 0.0),
-        ColorStop::new(Color::from_hex(0x000000), 1.0),
+        ColorStop::new(Color::from_hex_value(0x000000), 1.0),
       ],
       direction: None,
+      color_space: ColorSpace::Srgb,
+      repeat: RepeatMode::Pad,
     }
   }
 
@@ -72,9 +132,11 @@ impl Gradient {
     Gradient {
       stops: vec![
         ColorStop::new(from, 0.0),
-        ColorStop::new(Color::from_hex(0xFFFFFF), 1.0),
+        ColorStop::new(Color::from_hex_value(0xFFFFFF), 1.0),
       ],
       direction: None,
+      color_space: ColorSpace::Srgb,
+      repeat: RepeatMode::Pad,
     }
   }
 
@@ -85,8 +147,51 @@ impl Gradient {
     for (i, color) in colors.iter().enumerate() {
       stops.push(ColorStop::new(color.clone(), i as f32 * step));
     }
-    Gradient { stops, direction: None }
+    Gradient {
+      stops,
+      direction: None,
+      color_space: ColorSpace::Srgb,
+      repeat: RepeatMode::Pad,
+    }
   }
+
+  /// Creates a gradient from the colors of an image along a line, e.g. to reuse the palette of a photo in a
+  /// gradient map. The stops are evenly spaced along the line, and each one is the average of the 3x3 pixels
+  /// around its point to reduce noise. Pixels outside of the image are left out of the average, and stops with no
+  /// pixels inside the image are transparent.
+  /// - `image`: The image to sample.
+  /// - `line`: The path to sample along, in pixel coordinates.
+  /// - `stops`: The number of stops, at least 2.
+  pub fn from_image(image: &Image, line: Path, stops: usize) -> Gradient {
+    let measure = PathMeasure::new(&line);
+    let (width, height) = image.dimensions::<i64>();
+    let count = stops.max(2);
+    let stops = (0..count)
+      .map(|i| {
+        let time = i as f32 / (count - 1) as f32;
+        let (point, _) = measure.point_at(time);
+        let (x, y) = (point.x.floor() as i64, point.y.floor() as i64);
+        let mut sum = [0u32; 4];
+        let mut pixels = 0;
+        for (dx, dy) in (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (dx, dy))) {
+          let (px, py) = (x + dx, y + dy);
+          if px < 0 || py < 0 || px >= width || py >= height {
+            continue;
+          }
+          if let Some((r, g, b, a)) = image.get_pixel(px as u32, py as u32) {
+            for (total, channel) in sum.iter_mut().zip([r, g, b, a]) {
+              *total += channel as u32;
+            }
+            pixels += 1;
+          }
+        }
+        let [r, g, b, a] = sum.map(|total| (total as f32 / pixels.max(1) as f32).round() as u8);
+        ColorStop::new(Color::from_rgba(r, g, b, a), time)
+      })
+      .collect();
+    Gradient::new(stops)
+  }
+
   /// Sets the length of the gradient using a path where the first point is the start and the last point is the end.
   pub fn with_direction(mut self, path: crate::geometry::Path) -> Self {
     self.direction = Some(path);
@@ -96,18 +201,47 @@ impl Gradient {
   pub fn direction(&self) -> Option<crate::geometry::Path> {
     self.direction.clone()
   }
+  /// Sets the color space the stops are interpolated in.
+  /// `ColorSpace::Oklab` gives perceptually even transitions and avoids gray midpoints between complementary colors.
+  pub fn with_color_space(mut self, color_space: ColorSpace) -> Self {
+    self.color_space = color_space;
+    self
+  }
+  /// Gets the color space the stops are interpolated in.
+  pub fn color_space(&self) -> ColorSpace {
+    self.color_space
+  }
+  /// Sets how the gradient is extended beyond the 0 to 1 range.
+  /// `RepeatMode::Reflect` lets a two-stop gradient tile back and forth across a large fill.
+  pub fn with_repeat(mut self, repeat: RepeatMode) -> Self {
+    self.repeat = repeat;
+    self
+  }
+  /// Gets how the gradient is extended beyond the 0 to 1 range.
+  pub fn repeat(&self) -> RepeatMode {
+    self.repeat
+  }
+  /// Sets the easing of every transition between stops.
+  /// Use `ColorStop::with_easing` to ease individual transitions.
+  pub fn with_easing(mut self, easing: Easing) -> Self {
+    for stop in self.stops.iter_mut() {
+      stop.easing = easing;
+    }
+    self
+  }
   /// Creates a new rainbow gradient.
   /// This gradient goes from red to orange to yellow to green to blue to indigo to violet.
   pub fn rainbow() -> Gradient {
     Gradient::evenly(vec![
-      Color::from_hex(0xFF0000),
-      Color::from_hex(0xFF7F00),
-      Color::from_hex(0xFFFF00),
-      Color::from_hex(0x00FF00),
-      Color::from_hex(0x0000FF),
-      Color::from_hex(0x4B0082),
-      Color::from_hex(0x9400D3),
+      Color::from_hex_value(0xFF0000),
+      Color::from_hex_value(0xFF7F00),
+      Color::from_hex_value(0xFFFF00),
+      Color::from_hex_value(0x00FF00),
+      Color::from_hex_value(0x0000FF),
+      Color::from_hex_value(0x4B0082),
+      Color::from_hex_value(0x9400D3),
     ])
+    .with_color_space(ColorSpace::Hsl)
   }
 
   /// Creates a gradient that is based on the hue of colors going from 360 to 0.
@@ -122,10 +256,13 @@ impl Gradient {
       Color::from_hsv(60.0, 1.0, 1.0),
       Color::from_hsv(0.0, 1.0, 1.0),
     ])
+    .with_color_space(ColorSpace::Hsl)
   }
 
   /// Gets the color of the gradient at the given time.
+  /// Times outside of 0 to 1 are mapped back according to the gradient's repeat mode.
   pub fn get_color(&self, time: f32) -> (u8, u8, u8, u8) {
+    let time = self.repeat.map(time);
     let mut start = ColorStop::default();
     let mut end = ColorStop::default();
     let mut found_start = false;
@@ -143,18 +280,13 @@ impl Gradient {
     }
 
     if found_start && found_end {
-      let t = (time - start.time) / (end.time - start.time);
-      let r = (start.color.r as f32 + (end.color.r as f32 - start.color.r as f32) * t) as u8;
-      let g = (start.color.g as f32 + (end.color.g as f32 - start.color.g as f32) * t) as u8;
-      let b = (start.color.b as f32 + (end.color.b as f32 - start.color.b as f32) * t) as u8;
-      let a = (start.color.a as f32 + (end.color.a as f32 - start.color.a as f32) * t) as u8;
-      (r, g, b, a)
+      let t = start.easing.apply((time - start.time) / (end.time - start.time));
+      start.color.lerp(&end.color, t, self.color_space).rgba()
     } else if found_start && !found_end {
       (start.color.r, start.color.g, start.color.b, start.color.a)
-    } else if !found_start && found_end {
-      (end.color.r, end.color.g, end.color.b, end.color.a)
     } else {
-      (0, 0, 0, 0)
+      // Before the first stop the first color is extended.
+      self.stops.first().map_or((0, 0, 0, 0), |stop| stop.color.rgba())
     }
   }
 
@@ -168,12 +300,89 @@ impl Gradient {
   pub fn reverse(&self) -> Gradient {
     let mut stops = Vec::new();
     let max_time = self.stops.last().map_or(1.0, |stop| stop.time);
-    for stop in self.stops.iter().rev() {
-      stops.push(ColorStop::new(stop.color.clone(), max_time - stop.time));
+    // The transition into a stop becomes the transition out of it, played backwards.
+    for (i, stop) in self.stops.iter().enumerate().rev() {
+      let easing = if i > 0 {
+        self.stops[i - 1].easing.reversed()
+      } else {
+        Easing::Linear
+      };
+      stops.push(ColorStop::new(stop.color, max_time - stop.time).with_easing(easing));
+    }
+    Gradient {
+      stops,
+      direction: self.direction.clone(),
+      color_space: self.color_space,
+      repeat: self.repeat,
+    }
+  }
+
+  /// Rotates the hue of every stop around the color wheel, keeping saturation, lightness and alpha.
+  /// - `degrees`: The amount to rotate the hue by. Negative values rotate backwards.
+  pub fn rotate_hue(&self, degrees: f32) -> Gradient {
+    let mut gradient = self.clone();
+    for stop in gradient.stops.iter_mut() {
+      let (h, s, l) = stop.color.hsl();
+      let mut color = Color::from_hsl((h + degrees).rem_euclid(360.0), s, l);
+      color.a = stop.color.a;
+      stop.color = color;
+    }
+    gradient
+  }
+
+  /// Extracts a portion of the gradient and stretches it over the 0 to 1 range.
+  /// The colors at the boundaries become the new first and last stops.
+  /// - `start`: The time where the new gradient starts, between 0 and 1.
+  /// - `end`: The time where the new gradient ends, between 0 and 1. When smaller than `start` the portion is reversed.
+  pub fn subrange(&self, start: f32, end: f32) -> Gradient {
+    let (start, end) = (start.clamp(0.0, 1.0), end.clamp(0.0, 1.0));
+    if end < start {
+      return self.subrange(end, start).reverse();
+    }
+    let length = end - start;
+    if length <= f32::EPSILON {
+      let color = self.get_color_type(start);
+      return Gradient {
+        stops: vec![ColorStop::new(color, 0.0), ColorStop::new(color, 1.0)],
+        direction: self.direction.clone(),
+        color_space: self.color_space,
+        repeat: self.repeat,
+      };
+    }
+
+    // Every original stop inside the range keeps its transition. A transition cut by a boundary keeps its
+    // colors exactly when linear; an eased one is approximated with evenly spaced samples.
+    let mut times = vec![start];
+    times.extend(
+      self
+        .stops
+        .iter()
+        .map(|stop| stop.time)
+        .filter(|time| *time > start && *time < end),
+    );
+    times.push(end);
+    let mut stops = Vec::new();
+    for pair in times.windows(2) {
+      let (from, to) = (pair[0], pair[1]);
+      let original = self.stops.iter().rev().find(|stop| stop.time <= from);
+      let easing = original.map_or(Easing::Linear, |stop| stop.easing);
+      let next_time = self.stops.iter().find(|stop| stop.time > from).map(|stop| stop.time);
+      let is_whole = original.is_some_and(|stop| stop.time == from) && next_time == Some(to);
+      if is_whole || easing == Easing::Linear {
+        stops.push(ColorStop::new(self.get_color_type(from), (from - start) / length).with_easing(easing));
+      } else {
+        for i in 0..SUBRANGE_SAMPLES {
+          let time = from + (to - from) * i as f32 / SUBRANGE_SAMPLES as f32;
+          stops.push(ColorStop::new(self.get_color_type(time), (time - start) / length));
+        }
+      }
     }
+    stops.push(ColorStop::new(self.get_color_type(end), 1.0));
     Gradient {
       stops,
       direction: self.direction.clone(),
+      color_space: self.color_space,
+      repeat: self.repeat,
     }
   }
 }
@@ -198,10 +407,12 @@ impl Default for Gradient {
   fn default() -> Gradient {
     Gradient {
       stops: vec![
-        ColorStop::new(Color::from_hex(0x000000), 0.0),
-        ColorStop::new(Color::from_hex(0xFFFFFF), 1.0),
+        ColorStop::new(Color::from_hex_value(0x000000), 0.0),
+        ColorStop::new(Color::from_hex_value(0xFFFFFF), 1.0),
       ],
       direction: None,
+      color_space: ColorSpace::Srgb,
+      repeat: RepeatMode::Pad,
     }
   }
 }
@@ -216,6 +427,121 @@ impl Clone for Gradient {
     Gradient {
       stops,
       direction: self.direction.clone(),
+      color_space: self.color_space,
+      repeat: self.repeat,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn from_image_reconstructs_a_ramp() {
+    let mut image = Image::new(256, 4);
+    for y in 0..4 {
+      for x in 0..256 {
+        image.set_pixel(x, y, (x as u8, x as u8, x as u8, 255));
+      }
+    }
+    let line = Path::line((0.5, 2.0), (255.5, 2.0));
+    let gradient = Gradient::from_image(&image, line, 9);
+    assert_eq!(gradient.stops.len(), 9);
+    for i in 0..=20 {
+      let time = i as f32 / 20.0;
+      let (r, g, b, a) = gradient.get_color(time);
+      assert!((r as f32 - time * 255.0).abs() <= 2.0, "{} {}", time, r);
+      assert_eq!((g, b, a), (r, r, 255));
+    }
+  }
+
+  #[test]
+  fn interpolates_in_selected_color_space() {
+    let gradient = Gradient::from_to(Color::black(), Color::white());
+    assert_eq!(gradient.get_color(0.5), (128, 128, 128, 255));
+    assert_eq!(gradient.with_color_space(ColorSpace::LinearRgb).get_color(0.5), (188, 188, 188, 255));
+  }
+
+  #[test]
+  fn complementary_colors_avoid_dark_midpoint_in_oklab() {
+    let srgb = Gradient::from_to(Color::blue(), Color::yellow());
+    let oklab = srgb.clone().with_color_space(ColorSpace::Oklab);
+    // The sRGB midpoint is a flat mid gray while the Oklab midpoint keeps the perceived lightness.
+    assert!(srgb.get_color_type(0.5).hsl().1 < 0.01);
+    assert!(oklab.get_color_type(0.5).luminance() > srgb.get_color_type(0.5).luminance());
+  }
+
+  #[test]
+  fn easing_reshapes_transitions() {
+    let linear = Gradient::from_to(Color::black(), Color::white());
+    let ease_in = linear.clone().with_easing(Easing::EaseIn);
+    assert!(ease_in.get_color(0.5).0 < linear.get_color(0.5).0);
+    assert_eq!(ease_in.get_color(1.0), linear.get_color(1.0));
+
+    let per_stop = Gradient::new(vec![
+      ColorStop::new(Color::black(), 0.0).with_easing(Easing::EaseOut),
+      ColorStop::new(Color::white(), 1.0),
+    ]);
+    assert!(per_stop.get_color(0.5).0 > linear.get_color(0.5).0);
+  }
+
+  #[test]
+  fn cubic_bezier_matches_endpoints_and_linear_curve() {
+    let linear = Easing::CubicBezier(0.25, 0.25, 0.75, 0.75);
+    for t in [0.0, 0.2, 0.5, 0.9, 1.0] {
+      assert!((linear.apply(t) - t).abs() < 1e-3);
+    }
+    let ease = Easing::CubicBezier(0.42, 0.0, 0.58, 1.0);
+    assert!(ease.apply(0.25) < 0.25);
+    assert!(ease.apply(0.75) > 0.75);
+  }
+
+  #[test]
+  fn repeat_modes_extend_beyond_range() {
+    let gradient = Gradient::from_to(Color::black(), Color::white());
+    assert_eq!(gradient.get_color(1.5), (255, 255, 255, 255));
+    assert_eq!(gradient.get_color(-0.5), (0, 0, 0, 255));
+
+    let repeat = gradient.clone().with_repeat(RepeatMode::Repeat);
+    assert_eq!(repeat.get_color(1.25), gradient.get_color(0.25));
+    assert_eq!(repeat.get_color(-0.75), gradient.get_color(0.25));
+
+    let reflect = gradient.clone().with_repeat(RepeatMode::Reflect);
+    assert_eq!(reflect.get_color(1.25), gradient.get_color(0.75));
+    assert_eq!(reflect.get_color(2.25), gradient.get_color(0.25));
+    assert_eq!(reflect.get_color(-0.25), gradient.get_color(0.25));
+  }
+
+  #[test]
+  fn rotate_hue_keeps_alpha() {
+    let gradient = Gradient::from_to(Color::from_rgba(255, 0, 0, 128), Color::green());
+    let rotated = gradient.rotate_hue(120.0);
+    assert_eq!(rotated.get_color(0.0), (0, 255, 0, 128));
+    assert_eq!(rotated.get_color(1.0), (0, 0, 255, 255));
+    assert_eq!(gradient.rotate_hue(-120.0).get_color(1.0), (255, 0, 0, 255));
+  }
+
+  #[test]
+  fn subrange_stretches_portion() {
+    let gradient = Gradient::evenly(vec![Color::black(), Color::white(), Color::red()]);
+    let sub = gradient.subrange(0.25, 0.75);
+    assert_eq!(sub.get_color(0.0), gradient.get_color(0.25));
+    assert_eq!(sub.get_color(0.5), (255, 255, 255, 255));
+    assert_eq!(sub.get_color(1.0), gradient.get_color(0.75));
+    assert_eq!(sub.stops.len(), 3);
+
+    let reversed = gradient.subrange(0.75, 0.25);
+    assert_eq!(reversed.get_color(0.0), gradient.get_color(0.75));
+    assert_eq!(reversed.get_color(1.0), gradient.get_color(0.25));
+  }
+
+  #[test]
+  fn reverse_mirrors_easing() {
+    let gradient = Gradient::from_to(Color::black(), Color::white()).with_easing(Easing::EaseIn);
+    let reversed = gradient.reverse();
+    for t in [0.1, 0.3, 0.5, 0.8] {
+      assert_eq!(reversed.get_color(t), gradient.get_color(1.0 - t));
     }
   }
 }
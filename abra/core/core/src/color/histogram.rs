@@ -123,17 +123,30 @@ impl Histogram {
   ///
   /// Behavior mirrors the closure previously embedded in `auto_color`:
   /// - clip_fraction is applied symmetrically to both tails.
+  /// - a clip_fraction of zero returns the darkest and brightest non-zero bins.
   /// - if the histogram is empty, returns (0, 255) as identity mapping.
   /// - if the histogram collapses to a single non-zero bin returns (bin, bin).
   pub fn clip_bounds_from_slice(&self, hist: &[u64; 256], clip_fraction: f32) -> (u8, u8) {
+    self.clip_bounds_from_slice_split(hist, clip_fraction, clip_fraction)
+  }
+  /// Compute low/high clip bounds for a given channel histogram slice using
+  /// separate clip fractions for the dark and bright tails. Returns (low, high) in the 0..=255 range,
+  /// with the same fallbacks as `clip_bounds_from_slice`.
+  /// - `hist`: The channel histogram.
+  /// - `low_fraction`: The fraction of pixels to clip from the dark tail.
+  /// - `high_fraction`: The fraction of pixels to clip from the bright tail.
+  pub fn clip_bounds_from_slice_split(&self, hist: &[u64; 256], low_fraction: f32, high_fraction: f32) -> (u8, u8) {
     let total_pixels = hist.iter().sum::<u64>();
     let mut lo_bin = 0usize;
     let mut hi_bin = 255usize;
-    let clip_count = (clip_fraction * (total_pixels as f32)).round() as u64;
+    let low_count = (low_fraction * (total_pixels as f32)).round() as u64;
+    let high_count = (high_fraction * (total_pixels as f32)).round() as u64;
     let mut cum: u64 = 0;
+    // A bound is the bin that holds the last clipped pixel, or the darkest or brightest used bin when nothing is
+    // clipped, so empty bins at the ends never count as reached.
     for (i, &c) in hist.iter().enumerate() {
       cum += c;
-      if cum >= clip_count {
+      if cum > 0 && cum >= low_count {
         lo_bin = i;
         break;
       }
@@ -141,7 +154,7 @@ impl Histogram {
     cum = 0;
     for (i, &c) in hist.iter().enumerate().rev() {
       cum += c;
-      if cum >= clip_count {
+      if cum > 0 && cum >= high_count {
         hi_bin = i;
         break;
       }
@@ -189,7 +202,14 @@ impl Histogram {
   /// The LUT maps an 8-bit channel value [0..255] to an 8-bit mapped value [0..255]
   /// according to the lo/hi clip bounds computed for the histogram slice.
   pub fn levels_lut_from_slice(&self, hist: &[u64; 256], clip_fraction: f32) -> [u8; 256] {
-    let (lo, hi) = self.clip_bounds_from_slice(hist, clip_fraction);
+    self.levels_lut_from_slice_split(hist, clip_fraction, clip_fraction)
+  }
+  /// Construct a levels lookup table (LUT) using separate clip fractions for the dark and bright tails.
+  /// - `hist`: The channel histogram.
+  /// - `low_fraction`: The fraction of pixels to clip to black.
+  /// - `high_fraction`: The fraction of pixels to clip to white.
+  pub fn levels_lut_from_slice_split(&self, hist: &[u64; 256], low_fraction: f32, high_fraction: f32) -> [u8; 256] {
+    let (lo, hi) = self.clip_bounds_from_slice_split(hist, low_fraction, high_fraction);
     let lo_i = lo as i32;
     let hi_i = hi as i32;
     let denom = ((hi_i - lo_i) as f32).max(1.0);
@@ -468,8 +488,8 @@ mod tests {
     // Set some counts
     hist.red[10] = 2;
     hist.red[200] = 3;
-    // clip fraction zero should return identity 0..255
-    assert_eq!(hist.red_clip_bounds(0.0), (0u8, 255u8));
+    // clip fraction zero should return the darkest and brightest used bins
+    assert_eq!(hist.red_clip_bounds(0.0), (10u8, 200u8));
   }
 
   #[test]
@@ -501,8 +521,9 @@ mod tests {
   #[test]
   fn levels_lut_identity_with_zero_clip() {
     let mut hist = Histogram::new();
-    // Any distribution doesn't matter for clip_fraction == 0
-    hist.red[10] = 10;
+    // A distribution that already spans the full range is left as is with clip_fraction == 0
+    hist.red[0] = 10;
+    hist.red[255] = 10;
     let lut = hist.red_levels_lut(0.0);
     // lut[i] should equal i for identity mapping
     assert_eq!(lut[0], 0u8);
@@ -523,4 +544,26 @@ mod tests {
     // mid mapping check for 125 -> approx 128
     assert!((lut[125] as i32 - 128).abs() <= 1, "lut[125]={} expected ~128", lut[125]);
   }
+
+  #[test]
+  fn clip_bounds_split_clips_tails_independently() {
+    let mut hist = Histogram::new();
+    hist.red[10] = 5;
+    hist.red[200] = 30;
+    hist.red[240] = 5;
+    // Clipping only the dark tail moves the low bound past the 5 pixels at 10
+    assert_eq!(hist.clip_bounds_from_slice_split(hist.red(), 0.2, 0.0), (200u8, 240u8));
+    assert_eq!(hist.clip_bounds_from_slice_split(hist.red(), 0.0, 0.2), (10u8, 200u8));
+  }
+
+  #[test]
+  fn levels_lut_stretches_with_zero_clip() {
+    let mut hist = Histogram::new();
+    hist.red[50] = 10;
+    hist.red[200] = 10;
+    let lut = hist.red_levels_lut(0.0);
+    assert_eq!(lut[50], 0u8);
+    assert_eq!(lut[200], 255u8);
+    assert!((lut[125] as i32 - 128).abs() <= 1, "lut[125]={} expected ~128", lut[125]);
+  }
 }
@@ -1,10 +1,12 @@
 //! Color management utilities.
 // Re-export color primitives from `primitives` crate and provide gradient/fill in core
+mod easing;
 mod fill;
 mod gradient;
 mod histogram;
 
+pub use easing::Easing;
 pub use fill::Fill;
-pub use gradient::Gradient;
+pub use gradient::{Gradient, RepeatMode};
 pub use histogram::Histogram;
 pub use primitives::color::*;
@@ -0,0 +1,290 @@
+use crate::{Image, Orientation, Rect};
+use std::collections::HashMap;
+
+/// Packs images into a single atlas image (a sprite sheet) using the MaxRects algorithm.
+///
+/// Sprites are placed largest first, each into the free area that leaves the least space along its
+/// shorter side. The atlas is cropped to the area the sprites use.
+///
+/// ```ignore
+/// let (atlas, placements) = AtlasPacker::new(1024)
+///   .with_padding(2)
+///   .add("player", player)
+///   .add("enemy", enemy)
+///   .pack()?;
+/// ```
+pub struct AtlasPacker {
+  max_size: u32,
+  padding: u32,
+  power_of_two: bool,
+  rotation: bool,
+  sprites: Vec<(String, Image)>,
+}
+
+impl AtlasPacker {
+  /// Creates a packer for an atlas of at most `p_max_size` by `p_max_size` pixels.
+  /// - `p_max_size`: The largest width and height the atlas may have.
+  pub fn new(p_max_size: u32) -> AtlasPacker {
+    AtlasPacker {
+      max_size: p_max_size,
+      padding: 0,
+      power_of_two: false,
+      rotation: false,
+      sprites: vec![],
+    }
+  }
+
+  /// Sets the number of transparent pixels kept between neighboring sprites.
+  /// - `p_padding`: The gap in pixels.
+  pub fn with_padding(mut self, p_padding: u32) -> Self {
+    self.padding = p_padding;
+    self
+  }
+
+  /// Sets whether the atlas width and height are rounded up to powers of two, as some GPUs require.
+  /// Packing fails when the rounded size is larger than the maximum size.
+  /// - `p_power_of_two`: Whether to round the atlas size up.
+  pub fn with_power_of_two(mut self, p_power_of_two: bool) -> Self {
+    self.power_of_two = p_power_of_two;
+    self
+  }
+
+  /// Sets whether sprites may be turned 90 degrees clockwise when that packs them tighter.
+  /// A rotated sprite's placement has its width and height swapped compared to the sprite image.
+  /// - `p_rotation`: Whether sprites may be rotated.
+  pub fn with_rotation(mut self, p_rotation: bool) -> Self {
+    self.rotation = p_rotation;
+    self
+  }
+
+  /// Adds a sprite to the atlas. Adding a sprite with a name that was already added replaces it.
+  /// - `p_name`: The name the sprite's placement is returned under.
+  /// - `p_image`: The sprite image.
+  pub fn add(mut self, p_name: impl Into<String>, p_image: Image) -> Self {
+    let name = p_name.into();
+    self.sprites.retain(|(existing, _)| *existing != name);
+    self.sprites.push((name, p_image));
+    self
+  }
+
+  /// Packs the sprites and returns the atlas together with the placement of every sprite by name.
+  /// Fails when the sprites do not fit in the maximum size.
+  pub fn pack(&self) -> Result<(Image, HashMap<String, Rect>), String> {
+    let padding = self.padding;
+    // The bin is grown by the padding so sprites touching the right or bottom edge do not need a trailing gap.
+    let bin_size = self.max_size + padding;
+    let mut free = vec![Rect::new(0, 0, bin_size, bin_size)];
+
+    let mut order: Vec<&(String, Image)> = self.sprites.iter().collect();
+    order.sort_by_key(|(_, image)| {
+      let (width, height) = image.dimensions::<u32>();
+      std::cmp::Reverse((width.max(height), width.min(height)))
+    });
+
+    let mut placements = HashMap::new();
+    let mut placed = vec![];
+    for (name, image) in order {
+      let (width, height) = image.dimensions::<u32>();
+      if width == 0 || height == 0 {
+        placements.insert(name.clone(), Rect::default());
+        continue;
+      }
+      let (node, rotated) = find_position(&free, width + padding, height + padding, self.rotation)
+        .ok_or_else(|| format!("Sprite \"{}\" does not fit in a {}px atlas", name, self.max_size))?;
+      split_free_rects(&mut free, &node);
+      let (width, height) = if rotated { (height, width) } else { (width, height) };
+      let rect = Rect::new(node.x, node.y, width, height);
+      placements.insert(name.clone(), rect);
+      placed.push((rect, image, rotated));
+    }
+
+    let used = placed
+      .iter()
+      .fold(Rect::default(), |used, (rect, _, _)| used.union(rect));
+    let (mut width, mut height) = (used.right().max(1) as u32, used.bottom().max(1) as u32);
+    if self.power_of_two {
+      (width, height) = (width.next_power_of_two(), height.next_power_of_two());
+      if width > self.max_size || height > self.max_size {
+        return Err(format!("A {}x{} power of two atlas does not fit in a {}px atlas", width, height, self.max_size));
+      }
+    }
+
+    let mut atlas = Image::new(width, height);
+    for (rect, image, rotated) in placed {
+      if rotated {
+        let mut turned = image.clone();
+        Orientation::IDENTITY.rotated(1).apply(&mut turned);
+        atlas.draw_image_at(&turned, (rect.x, rect.y));
+      } else {
+        atlas.draw_image_at(image, (rect.x, rect.y));
+      }
+    }
+    Ok((atlas, placements))
+  }
+}
+
+/// Finds the free rectangle that leaves the least space along its shorter side when the sprite is placed in its
+/// top left corner, returning where the sprite goes and whether it is rotated.
+fn find_position(p_free: &[Rect], p_width: u32, p_height: u32, p_rotation: bool) -> Option<(Rect, bool)> {
+  let mut best: Option<((u32, u32), Rect, bool)> = None;
+  for free in p_free {
+    let orientations: &[(u32, u32, bool)] = if p_rotation {
+      &[(p_width, p_height, false), (p_height, p_width, true)]
+    } else {
+      &[(p_width, p_height, false)]
+    };
+    for &(width, height, rotated) in orientations {
+      if width > free.width || height > free.height {
+        continue;
+      }
+      let (left_x, left_y) = (free.width - width, free.height - height);
+      let score = (left_x.min(left_y), left_x.max(left_y));
+      if best.is_none_or(|(best_score, _, _)| score < best_score) {
+        best = Some((score, Rect::new(free.x, free.y, width, height), rotated));
+      }
+    }
+  }
+  best.map(|(_, rect, rotated)| (rect, rotated))
+}
+
+/// Whether two rectangles share at least one pixel.
+fn intersects(p_a: &Rect, p_b: &Rect) -> bool {
+  p_a.x < p_b.right() && p_b.x < p_a.right() && p_a.y < p_b.bottom() && p_b.y < p_a.bottom()
+}
+
+/// Whether `p_outer` covers all of `p_inner`.
+fn contains(p_outer: &Rect, p_inner: &Rect) -> bool {
+  p_inner.x >= p_outer.x
+    && p_inner.y >= p_outer.y
+    && p_inner.right() <= p_outer.right()
+    && p_inner.bottom() <= p_outer.bottom()
+}
+
+/// Removes the used area from the free rectangles. Every free rectangle it overlaps is replaced by up to four
+/// maximal rectangles around it, and rectangles covered by another one are dropped.
+fn split_free_rects(p_free: &mut Vec<Rect>, p_used: &Rect) {
+  let mut result = Vec::with_capacity(p_free.len() + 4);
+  for free in p_free.iter() {
+    if !intersects(free, p_used) {
+      result.push(*free);
+      continue;
+    }
+    if p_used.x > free.x {
+      result.push(Rect::new(free.x, free.y, (p_used.x - free.x) as u32, free.height));
+    }
+    if p_used.right() < free.right() {
+      result.push(Rect::new(p_used.right(), free.y, (free.right() - p_used.right()) as u32, free.height));
+    }
+    if p_used.y > free.y {
+      result.push(Rect::new(free.x, free.y, free.width, (p_used.y - free.y) as u32));
+    }
+    if p_used.bottom() < free.bottom() {
+      result.push(Rect::new(free.x, p_used.bottom(), free.width, (free.bottom() - p_used.bottom()) as u32));
+    }
+  }
+
+  let mut pruned: Vec<Rect> = Vec::with_capacity(result.len());
+  for (i, rect) in result.iter().enumerate() {
+    // Of two identical rectangles only the first is kept.
+    let covered = result
+      .iter()
+      .enumerate()
+      .any(|(j, other)| i != j && contains(other, rect) && (other != rect || j < i));
+    if !covered {
+      pruned.push(*rect);
+    }
+  }
+  *p_free = pruned;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use primitives::Color;
+
+  fn sprite(p_width: u32, p_height: u32, p_shade: u8) -> Image {
+    Image::new_from_color(p_width, p_height, Color::from_rgba(p_shade, 0, 0, 255))
+  }
+
+  #[test]
+  fn placements_do_not_overlap_and_stay_in_bounds() {
+    let sizes = [
+      (40, 30),
+      (64, 20),
+      (10, 90),
+      (32, 32),
+      (25, 25),
+      (50, 12),
+      (8, 8),
+      (70, 40),
+    ];
+    let mut packer = AtlasPacker::new(128).with_padding(2);
+    for (i, &(width, height)) in sizes.iter().enumerate() {
+      packer = packer.add(format!("sprite{}", i), sprite(width, height, 10 + i as u8 * 20));
+    }
+    let (atlas, placements) = packer.pack().unwrap();
+    let (atlas_width, atlas_height) = atlas.dimensions::<i32>();
+    assert!(atlas_width <= 128 && atlas_height <= 128);
+    assert_eq!(placements.len(), sizes.len());
+
+    for (i, &(width, height)) in sizes.iter().enumerate() {
+      let rect = placements[&format!("sprite{}", i)];
+      assert_eq!((rect.width, rect.height), (width, height));
+      assert!(rect.x >= 0 && rect.y >= 0 && rect.right() <= atlas_width && rect.bottom() <= atlas_height);
+      let shade = 10 + i as u8 * 20;
+      assert_eq!(atlas.get_pixel(rect.x as u32, rect.y as u32), Some((shade, 0, 0, 255)));
+      assert_eq!(atlas.get_pixel((rect.right() - 1) as u32, (rect.bottom() - 1) as u32), Some((shade, 0, 0, 255)));
+
+      for j in i + 1..sizes.len() {
+        let other = placements[&format!("sprite{}", j)];
+        // Growing one rectangle by the padding checks that the gap is kept as well.
+        let padded = Rect::new(rect.x, rect.y, rect.width + 2, rect.height + 2);
+        let padded_other = Rect::new(other.x, other.y, other.width + 2, other.height + 2);
+        assert!(!intersects(&padded, &other) && !intersects(&padded_other, &rect), "{:?} {:?}", rect, other);
+      }
+    }
+  }
+
+  #[test]
+  fn rotation_and_power_of_two() {
+    let mut tall = Image::new(2, 8);
+    tall.set_pixel(0, 0, (255, 0, 0, 255));
+    let packer = AtlasPacker::new(8)
+      .with_rotation(true)
+      .with_power_of_two(true)
+      .add("wide", sprite(8, 3, 50))
+      .add("tall", tall);
+    let (atlas, placements) = packer.pack().unwrap();
+    assert_eq!(atlas.dimensions::<u32>(), (8, 8));
+    let rect = placements["tall"];
+    assert_eq!((rect.width, rect.height), (8, 2));
+    // The top left pixel ends up in the top right corner after a clockwise turn.
+    assert_eq!(atlas.get_pixel(rect.right() as u32 - 1, rect.y as u32), Some((255, 0, 0, 255)));
+
+    // 5 pixels round up to 8, which is larger than the atlas may be.
+    assert!(
+      AtlasPacker::new(6)
+        .with_power_of_two(true)
+        .add("sprite", sprite(5, 5, 50))
+        .pack()
+        .is_err()
+    );
+    assert_eq!(
+      AtlasPacker::new(6)
+        .add("sprite", sprite(5, 5, 50))
+        .pack()
+        .unwrap()
+        .0
+        .dimensions::<u32>(),
+      (5, 5)
+    );
+
+    assert!(
+      AtlasPacker::new(8)
+        .add("wide", sprite(8, 3, 50))
+        .add("tall", sprite(2, 8, 0))
+        .pack()
+        .is_err()
+    );
+  }
+}
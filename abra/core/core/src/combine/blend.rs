@@ -4,6 +4,9 @@ use rayon::prelude::*;
 /// A color with red, green, blue, and alpha channels.
 pub type RGBA = (u8, u8, u8, u8);
 
+/// A blend mode function that combines a bottom color with a top color.
+pub type BlendFn = fn(RGBA, RGBA) -> RGBA;
+
 /// Combine two images using a blend mode at the given position.
 pub fn blend_images_at(
   bottom_image: &mut Image, top_image: &Image, x1: i32, y1: i32, x2: i32, y2: i32, mode: fn(RGBA, RGBA) -> RGBA,
@@ -50,90 +53,98 @@ pub fn blend_images(bottom_image: &mut Image, top_image: &Image, mode: fn(RGBA,
   blend_images_at(bottom_image, top_image, 0, 0, 0, 0, mode);
 }
 
-/// Combine two images using a blend mode at the given position with opacity support.
-/// The opacity is applied during blending, not to the layer data.
+/// Combines two images using the given blend mode, with the top image moved by an offset and blended at partial
+/// strength, e.g. to place a layer or a watermark. The opacity is applied during blending, not to the layer data.
+/// The top image is clipped to the bounds of the bottom image, and only the pixels they share are visited.
+/// - `bottom_image`: The image blended onto, which keeps its size.
+/// - `top_image`: The image to blend.
+/// - `mode`: The blend mode.
+/// - `opacity`: The strength of the top image, from 0.0 to 1.0.
+/// - `offset`: The position of the top image's top-left corner in the bottom image, which may be negative.
 pub fn blend_images_at_with_opacity(
-  bottom_image: &mut Image, top_image: &Image, _x1: i32, _y1: i32, x2: i32, y2: i32, mode: fn(RGBA, RGBA) -> RGBA,
-  opacity: f32,
+  bottom_image: &mut Image, top_image: &Image, mode: BlendFn, opacity: f32, offset: (i32, i32),
 ) {
   let opacity = opacity.clamp(0.0, 1.0);
+  let (bottom_width, bottom_height) = bottom_image.dimensions::<i64>();
+  let (top_width, top_height) = top_image.dimensions::<i64>();
+  let (x2, y2) = (offset.0 as i64, offset.1 as i64);
+  // The rectangle the two images share, in bottom image coordinates.
+  let (left, top) = (x2.max(0), y2.max(0));
+  let (right, bottom) = ((x2 + top_width).min(bottom_width), (y2 + top_height).min(bottom_height));
+  if left >= right || top >= bottom {
+    return;
+  }
 
-  // get the width of the image including the x position
-  let (bottom_width, _bottom_height) = bottom_image.dimensions::<i32>();
-  let (top_width, top_height) = top_image.dimensions::<i32>();
-  let mut pixels = bottom_image.empty_pixel_vec();
-
-  pixels.par_chunks_mut(4).enumerate().for_each(|(i, chunk)| {
-    let x = (i as i32) % bottom_width;
-    let y = (i as i32) / bottom_width;
-
-    // Bottom image (canvas) pixel - always valid since x,y are within canvas bounds
-    let a = bottom_image.get_pixel(x as u32, y as u32);
-
-    // Top image (layer) pixel - check if this canvas position contains part of the layer
-    let b = if x >= x2 && y >= y2 && x < x2 + top_width && y < y2 + top_height {
-      top_image.get_pixel((x - x2) as u32, (y - y2) as u32)
-    } else {
-      None
-    };
-
-    let color = match (a, b) {
-      (Some(a), Some(b)) => {
-        // Apply blend mode first
-        let blended = mode(a, b);
-
-        // Then apply opacity to the result's alpha channel
-        let (r, g, b, alpha) = blended;
-        let new_alpha = (alpha as f32 * opacity) as u8;
-
-        // Composite the opacity-adjusted blended layer onto the base layer
-        // This implements proper alpha compositing
-        let a_r = a.0 as f32 / 255.0;
-        let a_g = a.1 as f32 / 255.0;
-        let a_b = a.2 as f32 / 255.0;
-        let a_a = a.3 as f32 / 255.0;
-
-        let b_r = r as f32 / 255.0;
-        let b_g = g as f32 / 255.0;
-        let b_b = b as f32 / 255.0;
-        let b_a = new_alpha as f32 / 255.0;
-
-        let out_a = b_a + a_a * (1.0 - b_a);
-        let out_r = if out_a > 0.0 {
-          (b_r * b_a + a_r * a_a * (1.0 - b_a)) / out_a
-        } else {
-          0.0
-        };
-        let out_g = if out_a > 0.0 {
-          (b_g * b_a + a_g * a_a * (1.0 - b_a)) / out_a
-        } else {
-          0.0
-        };
-        let out_b = if out_a > 0.0 {
-          (b_b * b_a + a_b * a_a * (1.0 - b_a)) / out_a
-        } else {
-          0.0
-        };
-
-        ((out_r * 255.0) as u8, (out_g * 255.0) as u8, (out_b * 255.0) as u8, (out_a * 255.0) as u8)
-      }
-      (Some(a), None) => a,
-      (None, Some(b)) => {
-        // Apply opacity to the top layer when bottom layer is transparent
-        let (r, g, b, alpha) = b;
-        let new_alpha = (alpha as f32 * opacity) as u8;
-        (r, g, b, new_alpha)
+  let top_pixels = top_image.rgba();
+  let bottom_pixels = bottom_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+  let row_len = bottom_width as usize * 4;
+  bottom_pixels[top as usize * row_len..bottom as usize * row_len]
+    .par_chunks_mut(row_len)
+    .enumerate()
+    .for_each(|(row, bottom_row)| {
+      let y = top + row as i64;
+      let top_row = ((y - y2) * top_width) as usize * 4;
+      for x in left..right {
+        let chunk = &mut bottom_row[x as usize * 4..x as usize * 4 + 4];
+        let t = top_row + (x - x2) as usize * 4;
+        let color = composite_with_opacity(
+          (chunk[0], chunk[1], chunk[2], chunk[3]),
+          (top_pixels[t], top_pixels[t + 1], top_pixels[t + 2], top_pixels[t + 3]),
+          mode,
+          opacity,
+        );
+        chunk.copy_from_slice(&[color.0, color.1, color.2, color.3]);
       }
-      (None, None) => (0, 0, 0, 0),
-    };
-
-    chunk[0] = color.0;
-    chunk[1] = color.1;
-    chunk[2] = color.2;
-    chunk[3] = color.3;
-  });
+    });
+}
+
+/// Blends a top color onto a bottom color with a blend mode, then composites the result over the bottom color at
+/// the given opacity.
+/// - `a`: The bottom color.
+/// - `b`: The top color.
+/// - `mode`: The blend mode.
+/// - `opacity`: The strength of the top color, from 0.0 to 1.0.
+pub(crate) fn composite_with_opacity(a: RGBA, b: RGBA, mode: BlendFn, opacity: f32) -> RGBA {
+  // Apply blend mode first
+  let blended = mode(a, b);
+
+  // Then apply opacity to the result's alpha channel
+  let (r, g, b, alpha) = blended;
+  let new_alpha = (alpha as f32 * opacity) as u8;
+
+  // Composite the opacity-adjusted blended layer onto the base layer
+  // This implements proper alpha compositing
+  let a_r = a.0 as f32 / 255.0;
+  let a_g = a.1 as f32 / 255.0;
+  let a_b = a.2 as f32 / 255.0;
+  let a_a = a.3 as f32 / 255.0;
+
+  let b_r = r as f32 / 255.0;
+  let b_g = g as f32 / 255.0;
+  let b_b = b as f32 / 255.0;
+  let b_a = new_alpha as f32 / 255.0;
+
+  let out_a = b_a + a_a * (1.0 - b_a);
+  let out_r = if out_a > 0.0 {
+    (b_r * b_a + a_r * a_a * (1.0 - b_a)) / out_a
+  } else {
+    0.0
+  };
+  let out_g = if out_a > 0.0 {
+    (b_g * b_a + a_g * a_a * (1.0 - b_a)) / out_a
+  } else {
+    0.0
+  };
+  let out_b = if out_a > 0.0 {
+    (b_b * b_a + a_b * a_a * (1.0 - b_a)) / out_a
+  } else {
+    0.0
+  };
 
-  bottom_image.set_rgba_owned(pixels);
+  ((out_r * 255.0) as u8, (out_g * 255.0) as u8, (out_b * 255.0) as u8, (out_a * 255.0) as u8)
 }
 
 /// Edits or paints each pixel to make it the result color.
@@ -657,43 +668,92 @@ pub fn reflect(a: RGBA, b: RGBA) -> RGBA {
   (red, green, blue, alpha)
 }
 
+/// Every blend mode as `(name, label, function)`, where the name is the kebab-case identifier
+/// (e.g. `"color-burn"`) and the label is the human readable name (e.g. `"Color Burn"`).
+pub const BLEND_MODES: [(&str, &str, BlendFn); 33] = [
+  ("normal", "Normal", normal),
+  ("darken", "Darken", darken),
+  ("darker-color", "Darker Color", darker_color),
+  ("average", "Average", average),
+  ("multiply", "Multiply", multiply),
+  ("color-burn", "Color Burn", color_burn),
+  ("linear-burn", "Linear Burn", linear_burn),
+  ("lighten", "Lighten", lighten),
+  ("lighter-color", "Lighter Color", lighter_color),
+  ("screen", "Screen", screen),
+  ("color-dodge", "Color Dodge", color_dodge),
+  ("linear-dodge", "Linear Dodge", linear_dodge),
+  ("overlay", "Overlay", overlay),
+  ("soft-light", "Soft Light", soft_light),
+  ("hard-light", "Hard Light", hard_light),
+  ("vivid-light", "Vivid Light", vivid_light),
+  ("linear-light", "Linear Light", linear_light),
+  ("pin-light", "Pin Light", pin_light),
+  ("hard-mix", "Hard Mix", hard_mix),
+  ("difference", "Difference", difference),
+  ("exclusion", "Exclusion", exclusion),
+  ("subtract", "Subtract", subtract),
+  ("divide", "Divide", divide),
+  ("hue", "Hue", hue),
+  ("saturation", "Saturation", saturation),
+  ("color", "Color", color),
+  ("luminosity", "Luminosity", luminosity),
+  ("reflect", "Reflect", reflect),
+  ("glow", "Glow", glow),
+  ("phoenix", "Phoenix", phoenix),
+  ("negation", "Negation", negation),
+  ("grain-extract", "Grain Extract", grain_extract),
+  ("grain-merge", "Grain Merge", grain_merge),
+];
+
 /// Returns the name of the blend mode function.
 #[allow(unpredictable_function_pointer_comparisons)]
 pub fn blend_mode_name(mode: fn(RGBA, RGBA) -> RGBA) -> (&'static str, &'static str) {
-  match () {
-    _ if mode == normal => ("normal", "Normal"),
-    _ if mode == darken => ("darken", "Darken"),
-    _ if mode == darker_color => ("darker-color", "Darker Color"),
-    _ if mode == average => ("average", "Average"),
-    _ if mode == multiply => ("multiply", "Multiply"),
-    _ if mode == color_burn => ("color-burn", "Color Burn"),
-    _ if mode == linear_burn => ("linear-burn", "Linear Burn"),
-    _ if mode == lighten => ("lighten", "Lighten"),
-    _ if mode == lighter_color => ("lighter-color", "Lighter Color"),
-    _ if mode == screen => ("screen", "Screen"),
-    _ if mode == color_dodge => ("color-dodge", "Color Dodge"),
-    _ if mode == linear_dodge => ("linear-dodge", "Linear Dodge"),
-    _ if mode == overlay => ("overlay", "Overlay"),
-    _ if mode == soft_light => ("soft-light", "Soft Light"),
-    _ if mode == hard_light => ("hard-light", "Hard Light"),
-    _ if mode == vivid_light => ("vivid-light", "Vivid Light"),
-    _ if mode == linear_light => ("linear-light", "Linear Light"),
-    _ if mode == pin_light => ("pin-light", "Pin Light"),
-    _ if mode == hard_mix => ("hard-mix", "Hard Mix"),
-    _ if mode == difference => ("difference", "Difference"),
-    _ if mode == exclusion => ("exclusion", "Exclusion"),
-    _ if mode == subtract => ("subtract", "Subtract"),
-    _ if mode == divide => ("divide", "Divide"),
-    _ if mode == hue => ("hue", "Hue"),
-    _ if mode == saturation => ("saturation", "Saturation"),
-    _ if mode == color => ("color", "Color"),
-    _ if mode == luminosity => ("luminosity", "Luminosity"),
-    _ if mode == reflect => ("reflect", "Reflect"),
-    _ if mode == glow => ("glow", "Glow"),
-    _ if mode == phoenix => ("phoenix", "Phoenix"),
-    _ if mode == negation => ("negation", "Negation"),
-    _ if mode == grain_extract => ("grain-extract", "Grain Extract"),
-    _ if mode == grain_merge => ("grain-merge", "Grain Merge"),
-    _ => ("unknown", "Unknown"),
+  BLEND_MODES
+    .iter()
+    .find(|(_, _, blend)| *blend == mode)
+    .map(|(name, label, _)| (*name, *label))
+    .unwrap_or(("unknown", "Unknown"))
+}
+
+/// Returns the blend mode function with the given kebab-case name, e.g. `"soft-light"`.
+/// - `name`: The name of the blend mode. See `BLEND_MODES` for the valid names.
+pub fn blend_mode_from_name(name: &str) -> Option<BlendFn> {
+  BLEND_MODES
+    .iter()
+    .find(|(mode_name, _, _)| *mode_name == name)
+    .map(|(_, _, blend)| *blend)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Color;
+
+  #[test]
+  #[allow(unpredictable_function_pointer_comparisons)]
+  fn blend_mode_names_round_trip() {
+    for (name, label, blend) in BLEND_MODES {
+      assert_eq!(blend_mode_name(blend), (name, label));
+      assert!(blend_mode_from_name(name).unwrap() == blend);
+    }
+    assert!(blend_mode_from_name("Multiply").is_none());
+  }
+
+  #[test]
+  fn blend_with_opacity_and_offset_mixes_overlapping_pixels() {
+    let mut base = Image::new_from_color(4, 4, Color::from_rgba(200, 100, 0, 255));
+    let overlay = Image::new_from_color(2, 2, Color::from_rgba(0, 100, 200, 255));
+    blend_images_at_with_opacity(&mut base, &overlay, normal, 0.5, (3, -1));
+
+    assert_eq!(base.dimensions::<u32>(), (4, 4));
+    // Only the overlay's bottom-left pixel lands on the base, at its top-right corner.
+    let (r, g, b, a) = base.get_pixel(3, 0).unwrap();
+    for (channel, expected) in [(r, 100), (g, 100), (b, 100)] {
+      assert!(channel.abs_diff(expected) <= 1, "{:?}", (r, g, b));
+    }
+    assert_eq!(a, 255);
+    assert_eq!(base.get_pixel(2, 0), Some((200, 100, 0, 255)));
+    assert_eq!(base.get_pixel(3, 1), Some((200, 100, 0, 255)));
   }
 }
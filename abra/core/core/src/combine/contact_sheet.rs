@@ -0,0 +1,72 @@
+use crate::transform::{fit_size, resize};
+use crate::{Image, LoadedImages};
+
+/// Lays the images out as thumbnails in a uniform grid for proofing, filling each row from left to right.
+///
+/// Every image is shrunk to fit its cell while keeping its aspect ratio and is centered in the cell; images
+/// smaller than the cell keep their size. The sheet is transparent around the thumbnails, so flatten it onto a
+/// color before saving it to a format without alpha.
+///
+/// Filename labels need text rendering, which is not available yet, so the sheet is laid out without them and
+/// `p_label` currently has no effect.
+/// - `p_images`: The images to lay out.
+/// - `p_columns`: The number of thumbnails per row.
+/// - `p_thumb_size`: The width and height of each cell.
+/// - `p_padding`: The gap in pixels between the cells and around the edges of the sheet.
+/// - `p_label`: Whether a filename label is drawn beneath each thumbnail.
+pub fn contact_sheet(
+  p_images: &LoadedImages, p_columns: u32, p_thumb_size: (u32, u32), p_padding: u32, p_label: bool,
+) -> Image {
+  let _ = p_label;
+  let columns = p_columns.max(1);
+  let rows = (p_images.len() as u32).div_ceil(columns).max(1);
+  let (cell_width, cell_height) = (p_thumb_size.0.max(1), p_thumb_size.1.max(1));
+  let width = columns * cell_width + (columns + 1) * p_padding;
+  let height = rows * cell_height + (rows + 1) * p_padding;
+
+  let mut sheet = Image::new(width, height);
+  for (i, image) in p_images.iter().enumerate() {
+    let (column, row) = (i as u32 % columns, i as u32 / columns);
+    let (image_width, image_height) = image.dimensions::<u32>();
+    if image_width == 0 || image_height == 0 {
+      continue;
+    }
+    let (thumb_width, thumb_height) = fit_size(image_width, image_height, cell_width, cell_height, false);
+    let mut thumb = Image::clone(image);
+    if (thumb_width, thumb_height) != (image_width, image_height) {
+      resize(&mut thumb, thumb_width, thumb_height, None);
+    }
+
+    let left = p_padding + column * (cell_width + p_padding) + (cell_width - thumb_width) / 2;
+    let top = p_padding + row * (cell_height + p_padding) + (cell_height - thumb_height) / 2;
+    sheet.draw_image_at(&thumb, (left as i32, top as i32));
+  }
+
+  sheet
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ImageLoader;
+  use primitives::Color;
+
+  #[test]
+  fn five_images_in_three_columns_fill_two_rows() {
+    let images = (0..5)
+      .map(|i| Image::new_from_color(40 + i * 10, 30, Color::from_rgba(255, 0, 0, 255)))
+      .collect();
+    let loaded = ImageLoader::FromImages(images).load_sync();
+    let sheet = contact_sheet(&loaded, 3, (20, 20), 4, true);
+    // Three cells and four gaps across, two cells and three gaps down.
+    assert_eq!(sheet.dimensions::<u32>(), (3 * 20 + 4 * 4, 2 * 20 + 3 * 4));
+
+    // The centers of the five cells are covered and the sixth cell is empty.
+    for i in 0..6u32 {
+      let (x, y) = (4 + (i % 3) * 24 + 10, 4 + (i / 3) * 24 + 10);
+      let alpha = sheet.get_pixel(x, y).unwrap().3;
+      assert_eq!(alpha, if i < 5 { 255 } else { 0 }, "cell {}", i);
+    }
+    assert_eq!(sheet.get_pixel(1, 1).unwrap().3, 0);
+  }
+}
@@ -1,4 +1,13 @@
 //! Combine multiple images into one.
 
+/// Packs images into a sprite sheet
+pub mod atlas;
+
 /// Blends two images using a blend mode
 pub mod blend;
+
+/// Lays images out in a grid for proofing
+pub mod contact_sheet;
+
+/// Stamps watermarks onto images
+pub mod watermark;
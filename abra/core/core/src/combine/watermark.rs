@@ -0,0 +1,152 @@
+use crate::combine::blend::{blend_images_at_with_opacity, composite_with_opacity, normal};
+use crate::transform::resize;
+use crate::{Anchor, Image};
+use primitives::Image as PrimitiveImage;
+use rayon::prelude::*;
+
+/// Where a watermark is placed on an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatermarkPosition {
+  /// A single mark at an anchor, such as a corner or the center, inset from the edges by the margin.
+  Anchored(Anchor),
+  /// Copies of the mark repeated across the whole image, with every row shifted by half a mark so the copies line up
+  /// diagonally. The margin is the gap between neighboring copies.
+  Tile,
+}
+
+/// Trait for stamping watermarks onto an image.
+pub trait Watermark {
+  /// Stamps a watermark onto the image. See [`watermark`] for the parameters.
+  fn watermark(
+    &mut self, p_mark: &Image, p_position: impl Into<WatermarkPosition>, p_opacity: f32, p_scale: f32, p_margin: u32,
+  );
+}
+
+impl From<Anchor> for WatermarkPosition {
+  fn from(anchor: Anchor) -> Self {
+    WatermarkPosition::Anchored(anchor)
+  }
+}
+
+/// Stamps a watermark onto an image, e.g. a logo in a corner or a repeated mark across a preview.
+///
+/// The mark is blended over the image like a normal layer, so transparent parts of the mark leave the image
+/// unchanged. Copies that reach past the edges of the image are clipped.
+/// - `p_image`: The image to mark.
+/// - `p_mark`: The watermark image.
+/// - `p_position`: Where the mark is placed, an `Anchor` or `WatermarkPosition::Tile`.
+/// - `p_opacity`: The strength of the mark, from 0.0 to 1.0.
+/// - `p_scale`: The size of the mark relative to its own size, e.g. 0.5 for half the size.
+/// - `p_margin`: The gap in pixels between an anchored mark and the edges, or between tiled copies.
+pub fn watermark(
+  p_image: &mut Image, p_mark: &Image, p_position: impl Into<WatermarkPosition>, p_opacity: f32, p_scale: f32,
+  p_margin: u32,
+) {
+  let opacity = p_opacity.clamp(0.0, 1.0);
+  let (mark_width, mark_height) = p_mark.dimensions::<u32>();
+  let scaled_width = (mark_width as f32 * p_scale).round() as u32;
+  let scaled_height = (mark_height as f32 * p_scale).round() as u32;
+  if opacity == 0.0 || scaled_width == 0 || scaled_height == 0 {
+    return;
+  }
+  let mut mark = Image::clone(p_mark);
+  if (scaled_width, scaled_height) != (mark_width, mark_height) {
+    resize(&mut mark, scaled_width, scaled_height, None);
+  }
+
+  let (width, height) = p_image.dimensions::<i32>();
+  let (mark_width, mark_height) = (scaled_width as i32, scaled_height as i32);
+  let margin = p_margin as i32;
+  match p_position.into() {
+    WatermarkPosition::Anchored(anchor) => {
+      let (x, y) = anchor.calculate_position(width - margin * 2, height - margin * 2, mark_width, mark_height);
+      blend_images_at_with_opacity(p_image, &mark, normal, opacity, (x + margin, y + margin));
+    }
+    WatermarkPosition::Tile => tile(p_image, &mark, opacity, margin),
+  }
+}
+
+/// Blends copies of the mark across the whole image in a single pass, looking up which copy, if any, covers each
+/// pixel instead of blending the copies one at a time.
+fn tile(p_image: &mut Image, p_mark: &Image, p_opacity: f32, p_margin: i32) {
+  let (width, _) = p_image.dimensions::<usize>();
+  if width == 0 {
+    return;
+  }
+  let (mark_width, mark_height) = p_mark.dimensions::<i32>();
+  let (step_x, step_y) = (mark_width + p_margin, mark_height + p_margin);
+  let mark = p_mark.rgba();
+  let pixels = p_image
+    .colors()
+    .as_slice_mut()
+    .expect("Image colors must be contiguous");
+  pixels.par_chunks_mut(width * 4).enumerate().for_each(|(y, row)| {
+    let (tile_row, mark_y) = (y as i32 / step_y, y as i32 % step_y);
+    if mark_y >= mark_height {
+      return;
+    }
+    // Every other row starts half a step to the left, so the copies form diagonals.
+    let shift = if tile_row % 2 == 1 { step_x / 2 } else { 0 };
+    for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+      let mark_x = (x as i32 + shift) % step_x;
+      if mark_x >= mark_width {
+        continue;
+      }
+      let i = ((mark_y * mark_width + mark_x) * 4) as usize;
+      let color = composite_with_opacity(
+        (pixel[0], pixel[1], pixel[2], pixel[3]),
+        (mark[i], mark[i + 1], mark[i + 2], mark[i + 3]),
+        normal,
+        p_opacity,
+      );
+      pixel.copy_from_slice(&[color.0, color.1, color.2, color.3]);
+    }
+  });
+}
+
+impl Watermark for PrimitiveImage {
+  fn watermark(
+    &mut self, p_mark: &Image, p_position: impl Into<WatermarkPosition>, p_opacity: f32, p_scale: f32, p_margin: u32,
+  ) {
+    watermark(self, p_mark, p_position, p_opacity, p_scale, p_margin);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use primitives::Color;
+
+  /// Counts the pixels of the image that the watermark changed.
+  fn marked(p_image: &Image) -> usize {
+    p_image.rgba().chunks_exact(4).filter(|pixel| pixel[0] != 0).count()
+  }
+
+  #[test]
+  fn corner_mark_is_placed_once_inside_the_margin() {
+    let mut image = Image::new_from_color(100, 80, Color::from_rgba(0, 0, 0, 255));
+    let mark = Image::new_from_color(20, 10, Color::from_rgba(255, 255, 255, 255));
+    image.watermark(&mark, Anchor::BottomRight, 0.5, 1.0, 5);
+
+    assert_eq!(marked(&image), 20 * 10);
+    assert_eq!(image.get_pixel(94, 74), Some((127, 127, 127, 255)));
+    assert_eq!(image.get_pixel(75, 65), Some((127, 127, 127, 255)));
+    assert_eq!(image.get_pixel(95, 75), Some((0, 0, 0, 255)));
+    assert_eq!(image.get_pixel(74, 64), Some((0, 0, 0, 255)));
+  }
+
+  #[test]
+  fn tiled_mark_repeats_across_the_image() {
+    let mut image = Image::new_from_color(100, 100, Color::from_rgba(0, 0, 0, 255));
+    let mark = Image::new_from_color(20, 20, Color::from_rgba(255, 255, 255, 255));
+    watermark(&mut image, &mark, WatermarkPosition::Tile, 1.0, 0.5, 10);
+
+    // 10x10 copies on a 20 pixel grid, with odd rows shifted by 10 pixels.
+    assert_eq!(image.get_pixel(5, 5).unwrap().0, 255);
+    assert_eq!(image.get_pixel(25, 5).unwrap().0, 255);
+    assert_eq!(image.get_pixel(15, 5).unwrap().0, 0);
+    assert_eq!(image.get_pixel(15, 25).unwrap().0, 255);
+    assert_eq!(image.get_pixel(5, 25).unwrap().0, 0);
+    assert_eq!(marked(&image), 25 * 100);
+  }
+}
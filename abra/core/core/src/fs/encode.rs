@@ -0,0 +1,151 @@
+//! In-memory encoding of images.
+
+use std::fmt::Display;
+
+use crate::Image;
+use crate::fs::writer_options::WriterOptions;
+use crate::fs::writers::{
+  dds::encode_dds, gif::encode_gif, jpeg::encode_jpg, ktx2::encode_ktx2, png::encode_png, pnm::encode_pnm,
+  webp::encode_webp,
+};
+
+/// The image formats that can be written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+  /// Portable Network Graphics.
+  Png,
+  /// JPEG (no alpha channel).
+  Jpeg,
+  /// WebP.
+  WebP,
+  /// Graphics Interchange Format (256 colors).
+  Gif,
+  /// Netpbm RGB pixmap (no alpha channel).
+  Ppm,
+  /// Netpbm gray map (no alpha channel).
+  Pgm,
+  /// Netpbm black and white bitmap.
+  Pbm,
+  /// DirectDraw Surface GPU texture.
+  Dds,
+  /// Khronos KTX2 GPU texture.
+  Ktx2,
+}
+
+impl ImageFormat {
+  /// Returns the format matching a file path's extension, if it is supported.
+  /// - `p_path`: The file path, e.g. `"out/photo.jpg"`.
+  pub fn from_path(p_path: &str) -> Option<ImageFormat> {
+    let extension = p_path.rsplit_once('.')?.1.to_ascii_lowercase();
+    match extension.as_str() {
+      "png" => Some(ImageFormat::Png),
+      "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+      "webp" => Some(ImageFormat::WebP),
+      "gif" => Some(ImageFormat::Gif),
+      "ppm" => Some(ImageFormat::Ppm),
+      "pgm" => Some(ImageFormat::Pgm),
+      "pbm" => Some(ImageFormat::Pbm),
+      "dds" => Some(ImageFormat::Dds),
+      "ktx2" => Some(ImageFormat::Ktx2),
+      _ => None,
+    }
+  }
+
+  /// Returns the format's preferred file extension (without the dot).
+  pub fn extension(&self) -> &'static str {
+    match self {
+      ImageFormat::Png => "png",
+      ImageFormat::Jpeg => "jpg",
+      ImageFormat::WebP => "webp",
+      ImageFormat::Gif => "gif",
+      ImageFormat::Ppm => "ppm",
+      ImageFormat::Pgm => "pgm",
+      ImageFormat::Pbm => "pbm",
+      ImageFormat::Dds => "dds",
+      ImageFormat::Ktx2 => "ktx2",
+    }
+  }
+}
+
+/// Errors that can occur while encoding an image.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeError {
+  /// The file extension does not match a supported format.
+  UnsupportedFormat(String),
+  /// The encoder failed.
+  Encoding(String),
+  /// The encoded image could not be written to the file.
+  Io(String),
+}
+
+impl Display for EncodeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      EncodeError::UnsupportedFormat(msg) => write!(f, "Unsupported image format: {}", msg),
+      EncodeError::Encoding(msg) => write!(f, "Encoding error: {}", msg),
+      EncodeError::Io(msg) => write!(f, "Write error: {}", msg),
+    }
+  }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Encodes an image into memory using the same encoders that are used when saving to a file.
+/// - `p_image`: The image to encode.
+/// - `p_format`: The output format.
+/// - `p_options`: Optional writer options.
+pub(crate) fn encode_image(
+  p_image: &Image, p_format: ImageFormat, p_options: &Option<WriterOptions>,
+) -> Result<Vec<u8>, EncodeError> {
+  let mut bytes = Vec::new();
+  match p_format {
+    ImageFormat::Png => encode_png(&mut bytes, p_image, p_options),
+    ImageFormat::Gif => encode_gif(&mut bytes, p_image, p_options),
+    ImageFormat::Jpeg => encode_jpg(p_image, p_options).map(|(data, _)| bytes = data),
+    ImageFormat::WebP => encode_webp(p_image, p_options).map(|(data, _)| bytes = data),
+    ImageFormat::Ppm | ImageFormat::Pgm | ImageFormat::Pbm => encode_pnm(&mut bytes, p_image, p_format, p_options),
+    ImageFormat::Dds => encode_dds(&mut bytes, p_image, p_options),
+    ImageFormat::Ktx2 => encode_ktx2(&mut bytes, p_image, p_options),
+  }
+  .map_err(EncodeError::Encoding)?;
+  Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::image::image_ext::CoreImageFsExt;
+
+  fn test_image() -> Image {
+    let mut image = Image::new(16, 16);
+    for y in 0..16u32 {
+      for x in 0..16u32 {
+        image.set_pixel(x, y, ((x * 16) as u8, (y * 16) as u8, 128, 255));
+      }
+    }
+    image
+  }
+
+  #[test]
+  fn format_from_path() {
+    assert_eq!(ImageFormat::from_path("a/b.PNG"), Some(ImageFormat::Png));
+    assert_eq!(ImageFormat::from_path("photo.jpeg"), Some(ImageFormat::Jpeg));
+    assert_eq!(ImageFormat::from_path("photo.tiff"), None);
+    assert_eq!(ImageFormat::from_path("no_extension"), None);
+  }
+
+  #[test]
+  fn encode_matches_save() {
+    let image = test_image();
+    let dir = std::env::temp_dir().join("abra_encode_matches_save");
+    for format in [ImageFormat::Png, ImageFormat::WebP, ImageFormat::Gif, ImageFormat::Pgm, ImageFormat::Dds] {
+      let options = Some(WriterOptions::new(80));
+      let path = dir.join(format!("image.{}", format.extension()));
+      let path = path.to_str().unwrap();
+      image.save(path, options.clone());
+      let saved = std::fs::read(path).unwrap();
+      assert_eq!(image.encode(format, options).unwrap(), saved, "{:?} bytes differ", format);
+    }
+    let _ = std::fs::remove_dir_all(dir);
+  }
+}
@@ -2,16 +2,26 @@
 
 /// The file info of an image.
 pub(crate) mod file_info;
+mod encode;
 pub(crate) mod path;
+mod quality_search;
 mod writer_options;
 /// The supported image reader formats.
 pub(crate) mod readers {
   /// Support for reading GIF images.
   pub mod gif;
+  /// Support for reading HEIC and HEIF images.
+  #[cfg(feature = "heic")]
+  pub mod heic;
   /// Support for reading JPEG images.
   pub mod jpeg;
+  /// Support for reading JPEG XL images.
+  #[cfg(feature = "jxl")]
+  pub mod jxl;
   /// Support for reading PNG images.
   pub mod png;
+  /// Support for reading Netpbm (PBM, PGM and PPM) images.
+  pub mod pnm;
   /// Support for reading SVG images.
   pub mod svg;
   /// Support for reading WebP images.
@@ -19,18 +29,28 @@ pub(crate) mod readers {
 }
 /// The supported image writer formats.
 pub(crate) mod writers {
+  /// Support for writing DDS textures.
+  pub mod dds;
   /// Support for writing GIF images.
   pub mod gif;
   /// Support for writing JPEG images.
   pub mod jpeg;
+  /// Support for writing KTX2 textures.
+  pub mod ktx2;
   /// Support for writing PNG images.
   pub mod png;
+  /// Support for writing Netpbm (PBM, PGM and PPM) images.
+  pub mod pnm;
+  /// Mipmaps and block compression for the texture writers.
+  mod texture;
   /// Support for writing WebP images.
   pub mod webp;
 }
 
 use std::{fs, path::Path};
-pub use writer_options::WriterOptions;
+pub(crate) use encode::encode_image;
+pub use encode::{EncodeError, ImageFormat};
+pub use writer_options::{PngFilter, TextureCompression, WriterOptions};
 
 /// Creates a directory and all its parent directories if they do not exist.
 pub fn mkdirp(path: impl Into<String>) -> Result<(), String> {
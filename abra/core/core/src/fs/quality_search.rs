@@ -0,0 +1,133 @@
+//! Quality search for lossy encoders.
+//!
+//! Instead of a fixed quality, `WriterOptions` can ask for a file size budget or a minimum SSIM.
+//! The encoders hand this module a closure that encodes at a given quality and it binary-searches
+//! the quality range, assuming that both size and similarity grow with quality.
+
+use crate::fs::writer_options::WriterOptions;
+
+/// The size of the square windows used when computing SSIM.
+const SSIM_WINDOW: usize = 8;
+
+/// Encodes an image at the quality requested by the options and returns the encoded bytes with the quality used.
+/// - `p_options`: The writer options. `target_size` takes precedence over `target_ssim`; without either, `quality` is used.
+/// - `p_original_rgb`: The original RGB pixels, used to measure SSIM.
+/// - `p_width`: The width of the image.
+/// - `p_height`: The height of the image.
+/// - `p_encode`: Encodes the image at a quality between 1 and 100.
+/// - `p_decode_rgb`: Decodes encoded bytes back into RGB pixels, used to measure SSIM.
+pub(crate) fn encode_with_quality_target<E, D>(
+  p_options: &Option<WriterOptions>, p_original_rgb: &[u8], p_width: usize, p_height: usize, p_encode: E,
+  p_decode_rgb: D,
+) -> Result<(Vec<u8>, u8), String>
+where
+  E: Fn(u8) -> Result<Vec<u8>, String>,
+  D: Fn(&[u8]) -> Result<Vec<u8>, String>,
+{
+  let Some(options) = p_options else {
+    return Ok((p_encode(100)?, 100));
+  };
+
+  if let Some(target_size) = options.target_size {
+    // Largest quality that still fits in the budget, or the smallest file possible when nothing fits.
+    let found = bisect(
+      |quality| {
+        let bytes = p_encode(quality)?;
+        Ok((bytes.len() <= target_size, bytes))
+      },
+      false,
+    )?;
+    return match found {
+      Some(found) => Ok(found),
+      None => Ok((p_encode(1)?, 1)),
+    };
+  }
+
+  if let Some(target_ssim) = options.target_ssim {
+    // Smallest quality that reaches the similarity threshold, or full quality when nothing does.
+    let found = bisect(
+      |quality| {
+        let bytes = p_encode(quality)?;
+        let decoded = p_decode_rgb(&bytes)?;
+        Ok((ssim(p_original_rgb, &decoded, p_width, p_height) >= target_ssim, bytes))
+      },
+      true,
+    )?;
+    return match found {
+      Some(found) => Ok(found),
+      None => Ok((p_encode(100)?, 100)),
+    };
+  }
+
+  let quality = options.quality.clamp(1, 100);
+  Ok((p_encode(quality)?, quality))
+}
+
+/// Binary-searches the quality range (1 to 100) for the lowest or highest quality that passes a check.
+/// The check must be monotonic in quality and returns whether it passed along with the encoded bytes.
+/// - `p_check`: Encodes at a quality and checks the result.
+/// - `p_lowest`: True to find the lowest passing quality, false for the highest.
+fn bisect<F>(p_check: F, p_lowest: bool) -> Result<Option<(Vec<u8>, u8)>, String>
+where
+  F: Fn(u8) -> Result<(bool, Vec<u8>), String>,
+{
+  let mut best = None;
+  let (mut low, mut high) = (1i32, 100i32);
+  while low <= high {
+    let quality = (low + high) / 2;
+    let (passed, bytes) = p_check(quality as u8)?;
+    if passed {
+      best = Some((bytes, quality as u8));
+    }
+    if passed == p_lowest {
+      high = quality - 1;
+    } else {
+      low = quality + 1;
+    }
+  }
+  Ok(best)
+}
+
+/// Computes the mean structural similarity (SSIM) of two RGB buffers on their luma channel.
+/// Returns 1.0 for identical images.
+/// - `p_a`: The first RGB buffer.
+/// - `p_b`: The second RGB buffer.
+/// - `p_width`: The width of both images.
+/// - `p_height`: The height of both images.
+pub(crate) fn ssim(p_a: &[u8], p_b: &[u8], p_width: usize, p_height: usize) -> f64 {
+  const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+  const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+  let luma = |p_buf: &[u8], p_i: usize| {
+    0.299 * p_buf[p_i * 3] as f64 + 0.587 * p_buf[p_i * 3 + 1] as f64 + 0.114 * p_buf[p_i * 3 + 2] as f64
+  };
+
+  let mut total = 0.0;
+  let mut windows = 0;
+  for wy in (0..p_height).step_by(SSIM_WINDOW) {
+    for wx in (0..p_width).step_by(SSIM_WINDOW) {
+      let (mut sum_a, mut sum_b, mut sum_aa, mut sum_bb, mut sum_ab) = (0.0, 0.0, 0.0, 0.0, 0.0);
+      let mut n = 0.0;
+      for y in wy..(wy + SSIM_WINDOW).min(p_height) {
+        for x in wx..(wx + SSIM_WINDOW).min(p_width) {
+          let a = luma(p_a, y * p_width + x);
+          let b = luma(p_b, y * p_width + x);
+          sum_a += a;
+          sum_b += b;
+          sum_aa += a * a;
+          sum_bb += b * b;
+          sum_ab += a * b;
+          n += 1.0;
+        }
+      }
+      let (mean_a, mean_b) = (sum_a / n, sum_b / n);
+      let var_a = sum_aa / n - mean_a * mean_a;
+      let var_b = sum_bb / n - mean_b * mean_b;
+      let covariance = sum_ab / n - mean_a * mean_b;
+      total += ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+        / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2));
+      windows += 1;
+    }
+  }
+
+  if windows == 0 { 1.0 } else { total / windows as f64 }
+}
@@ -0,0 +1,34 @@
+use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+use crate::Channels;
+use crate::fs::file_info::FileInfo;
+
+/// Reads a HEIC or HEIF file and returns the image data.
+/// Files that hold several images, such as bursts or live photos, return their primary image.
+/// The rotation and mirroring stored in the file are applied while decoding, so iOS photos come out upright.
+pub fn read_heic(file: impl Into<String>) -> Result<FileInfo, String> {
+  let file_path = file.into();
+  let context =
+    HeifContext::read_from_file(&file_path).map_err(|e| format!("Failed to open HEIF file {}: {}", file_path, e))?;
+  let handle = context
+    .primary_image_handle()
+    .map_err(|e| format!("Failed to find the primary HEIF image: {}", e))?;
+
+  let image = LibHeif::new()
+    .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+    .map_err(|e| format!("Failed to decode HEIF image: {}", e))?;
+  let plane = image
+    .planes()
+    .interleaved
+    .ok_or_else(|| "Failed to decode HEIF image: missing interleaved RGBA plane".to_string())?;
+
+  // Rows may be padded, so copy them one at a time.
+  let (width, height) = (plane.width, plane.height);
+  let row_bytes = width as usize * 4;
+  let mut pixels = Vec::with_capacity(row_bytes * height as usize);
+  for row in plane.data.chunks(plane.stride).take(height as usize) {
+    pixels.extend_from_slice(&row[..row_bytes]);
+  }
+
+  Ok(FileInfo::new(width, height, Channels::RGBA, pixels))
+}
@@ -0,0 +1,71 @@
+use jxl_oxide::{JxlImage, PixelFormat};
+
+use crate::Channels;
+use crate::fs::file_info::FileInfo;
+
+/// Reads a JPEG XL file, lossless or lossy, and returns the image data.
+/// Animated files return their first frame. The orientation stored in the file is applied while decoding.
+pub fn read_jxl(file: impl Into<String>) -> Result<FileInfo, String> {
+  let file_path = file.into();
+  let image = JxlImage::builder()
+    .open(&file_path)
+    .map_err(|e| format!("Failed to open JPEG XL file {}: {}", file_path, e))?;
+  if image.pixel_format().has_black() {
+    return Err("Failed to decode JPEG XL image: CMYK images are not supported".to_string());
+  }
+  let render = image
+    .render_frame(0)
+    .map_err(|e| format!("Failed to decode JPEG XL image: {}", e))?;
+
+  let mut stream = render.stream();
+  let (width, height) = (stream.width(), stream.height());
+  let channels = stream.channels() as usize;
+  let mut samples = vec![0u8; width as usize * height as usize * channels];
+  stream.write_to_buffer(&mut samples);
+
+  // Gray images and images without alpha are expanded to RGBA.
+  let pixels = match image.pixel_format() {
+    PixelFormat::Rgba => samples,
+    PixelFormat::Rgb => samples.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+    PixelFormat::Graya => samples.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+    _ => samples.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+  };
+  Ok(FileInfo::new(width, height, Channels::RGBA, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use zune_core::bit_depth::BitDepth;
+  use zune_core::colorspace::ColorSpace;
+  use zune_core::options::EncoderOptions;
+  use zune_jpegxl::JxlSimpleEncoder;
+
+  /// Encodes pixels as a lossless JPEG XL file in the temp folder and returns its path.
+  fn write_jxl(p_name: &str, p_pixels: &[u8], p_width: usize, p_height: usize, p_color: ColorSpace) -> String {
+    let options = EncoderOptions::new(p_width, p_height, p_color, BitDepth::Eight);
+    let mut bytes = vec![];
+    JxlSimpleEncoder::new(p_pixels, options).encode(&mut bytes).unwrap();
+    let path = std::env::temp_dir().join(p_name);
+    std::fs::write(&path, bytes).unwrap();
+    path.to_str().unwrap().to_string()
+  }
+
+  #[test]
+  fn lossless_files_decode_exactly() {
+    let (width, height) = (17, 9);
+    let rgba: Vec<u8> = (0..width * height * 4).map(|i| (i * 37 % 256) as u8).collect();
+    let path = write_jxl("abra_read_jxl_rgba.jxl", &rgba, width, height, ColorSpace::RGBA);
+    let info = read_jxl(&path).unwrap();
+    assert_eq!((info.width, info.height), (width as u32, height as u32));
+    assert_eq!(info.pixels, rgba);
+
+    let gray: Vec<u8> = (0..width * height).map(|i| (i * 11 % 256) as u8).collect();
+    let path = write_jxl("abra_read_jxl_gray.jxl", &gray, width, height, ColorSpace::Luma);
+    let info = read_jxl(&path).unwrap();
+    let expected: Vec<u8> = gray.iter().flat_map(|&v| [v, v, v, 255]).collect();
+    assert_eq!(info.pixels, expected);
+
+    assert!(read_jxl("missing.jxl").is_err());
+  }
+}
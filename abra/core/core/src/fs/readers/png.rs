@@ -9,10 +9,10 @@ pub fn read_png(file: impl Into<String>) -> Result<FileInfo, String> {
   // Larger buffer for better IO performance on large PNGs
   let reader = BufReader::with_capacity(1 << 20, file); // 1 MiB
   let decoder = Decoder::new(reader);
-  let mut reader = decoder.read_info().unwrap();
+  let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
   let output_size = reader.output_buffer_size().ok_or("Failed to get buffer size")?;
   let mut buf = vec![0; output_size];
-  let info = reader.next_frame(&mut buf).unwrap();
+  let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
   let bytes = &buf[..info.buffer_size()];
 
   let width = info.width as u32;
@@ -22,7 +22,7 @@ pub fn read_png(file: impl Into<String>) -> Result<FileInfo, String> {
   let channels = match info.color_type {
     png::ColorType::Rgb => Channels::RGB,
     png::ColorType::Rgba => Channels::RGBA,
-    _ => panic!("Unsupported color type"),
+    color_type => return Err(format!("Unsupported color type: {:?}", color_type)),
   };
 
   let info = FileInfo::new(width, height, channels, pixels);
@@ -0,0 +1,223 @@
+use crate::Channels;
+use crate::fs::file_info::FileInfo;
+
+/// Reads a Netpbm file (PBM, PGM or PPM) in either its ASCII or binary form and returns the image data.
+/// Gray and bitmap images are expanded to RGB, and samples with a maximum value other than 255 are rescaled.
+pub fn read_pnm(file: impl Into<String>) -> Result<FileInfo, String> {
+  let file_path = file.into();
+  let bytes = std::fs::read(&file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+  decode_pnm(&bytes)
+}
+
+/// Walks the whitespace separated tokens of a Netpbm file, skipping `#` comments.
+struct Tokens<'a> {
+  bytes: &'a [u8],
+  position: usize,
+}
+
+impl<'a> Tokens<'a> {
+  fn skip_whitespace(&mut self) {
+    while let Some(&byte) = self.bytes.get(self.position) {
+      if byte == b'#' {
+        while self.bytes.get(self.position).is_some_and(|&b| b != b'\n') {
+          self.position += 1;
+        }
+      } else if byte.is_ascii_whitespace() {
+        self.position += 1;
+      } else {
+        break;
+      }
+    }
+  }
+
+  fn next_token(&mut self) -> Option<&'a [u8]> {
+    self.skip_whitespace();
+    let start = self.position;
+    while self
+      .bytes
+      .get(self.position)
+      .is_some_and(|b| !b.is_ascii_whitespace() && *b != b'#')
+    {
+      self.position += 1;
+    }
+    (self.position > start).then(|| &self.bytes[start..self.position])
+  }
+
+  fn next_number(&mut self, p_what: &str) -> Result<u32, String> {
+    self
+      .next_token()
+      .and_then(|token| std::str::from_utf8(token).ok()?.parse().ok())
+      .ok_or_else(|| format!("Invalid Netpbm {}", p_what))
+  }
+
+  /// The next bit of an ASCII bitmap, where the digits do not need to be separated.
+  fn next_bit(&mut self) -> Result<bool, String> {
+    self.skip_whitespace();
+    let bit = match self.bytes.get(self.position) {
+      Some(b'0') => false,
+      Some(b'1') => true,
+      _ => return Err("Invalid Netpbm bitmap data".to_string()),
+    };
+    self.position += 1;
+    Ok(bit)
+  }
+}
+
+/// Decodes the bytes of a Netpbm file into RGB image data.
+pub(crate) fn decode_pnm(p_bytes: &[u8]) -> Result<FileInfo, String> {
+  let mut tokens = Tokens {
+    bytes: p_bytes,
+    position: 0,
+  };
+  let magic = tokens.next_token().ok_or("Empty Netpbm file")?;
+  let kind = match magic {
+    b"P1" | b"P2" | b"P3" | b"P4" | b"P5" | b"P6" => magic[1],
+    _ => return Err(format!("Unsupported Netpbm format: {}", String::from_utf8_lossy(magic))),
+  };
+  let width = tokens.next_number("width")?;
+  let height = tokens.next_number("height")?;
+  let is_bitmap = matches!(kind, b'1' | b'4');
+  let max_value = if is_bitmap {
+    1
+  } else {
+    tokens.next_number("maximum value")?
+  };
+  if width == 0 || height == 0 || max_value == 0 || max_value > 65535 {
+    return Err(format!("Invalid Netpbm header: {}x{} with maximum value {}", width, height, max_value));
+  }
+
+  let pixel_count = width as usize * height as usize;
+  let channels = if matches!(kind, b'3' | b'6') { 3 } else { 1 };
+  let sample_count = pixel_count.checked_mul(channels).ok_or("Netpbm image is too large")?;
+  let sample_bytes = if max_value > 255 { 2 } else { 1 };
+  let row_bytes = (width as usize).div_ceil(8);
+  // A single whitespace character separates the header from the binary data.
+  let data = p_bytes.get(tokens.position + 1..).unwrap_or_default();
+  // The smallest amount of data the header promises, checked before allocating so a forged header cannot request
+  // more memory than the file could fill. ASCII samples take at least one byte each.
+  let needed = match kind {
+    b'1' | b'2' | b'3' => Some(sample_count),
+    b'4' => row_bytes.checked_mul(height as usize),
+    _ => sample_count.checked_mul(sample_bytes),
+  }
+  .ok_or("Netpbm image is too large")?;
+  if data.len() < needed {
+    return Err("Netpbm pixel data is truncated".to_string());
+  }
+
+  let scale = |value: u32| ((value.min(max_value) * 255 + max_value / 2) / max_value) as u8;
+  let mut samples = Vec::with_capacity(sample_count);
+  match kind {
+    b'1' => {
+      for _ in 0..pixel_count {
+        // In a bitmap 1 is black.
+        samples.push(if tokens.next_bit()? { 0 } else { 255 });
+      }
+    }
+    b'2' | b'3' => {
+      for _ in 0..sample_count {
+        samples.push(scale(tokens.next_number("sample")?));
+      }
+    }
+    b'4' => {
+      for row in data.chunks(row_bytes).take(height as usize) {
+        for x in 0..width as usize {
+          let bit = (row[x / 8] >> (7 - x % 8)) & 1;
+          samples.push(if bit == 1 { 0 } else { 255 });
+        }
+      }
+    }
+    _ => {
+      if sample_bytes == 2 {
+        samples.extend(
+          data[..needed]
+            .chunks_exact(2)
+            .map(|pair| scale(u16::from_be_bytes([pair[0], pair[1]]) as u32)),
+        );
+      } else if max_value == 255 {
+        samples.extend_from_slice(&data[..needed]);
+      } else {
+        samples.extend(data[..needed].iter().map(|&value| scale(value as u32)));
+      }
+    }
+  }
+
+  let pixels = if channels == 3 {
+    samples
+  } else {
+    samples.iter().flat_map(|&value| [value, value, value]).collect()
+  };
+  Ok(FileInfo::new(width, height, Channels::RGB, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Image;
+  use crate::fs::writers::pnm::encode_pnm;
+  use crate::fs::{ImageFormat, WriterOptions};
+  use crate::image::image_ext::CoreImageFsExt;
+
+  /// Encodes and decodes an image through a Netpbm variant.
+  fn round_trip(p_image: &Image, p_format: ImageFormat, p_ascii: bool) -> Image {
+    let mut bytes = Vec::new();
+    encode_pnm(&mut bytes, p_image, p_format, &Some(WriterOptions::default().pnm_ascii(p_ascii))).unwrap();
+    let info = decode_pnm(&bytes).unwrap();
+    let mut decoded = Image::new(1u32, 1u32);
+    decoded.set_new_pixels(&info.pixels, info.width, info.height);
+    decoded
+  }
+
+  #[test]
+  fn round_trips_are_pixel_exact() {
+    let mut color = Image::new(13, 5);
+    let mut gray = Image::new(13, 5);
+    let mut bitmap = Image::new(13, 5);
+    for y in 0..5u32 {
+      for x in 0..13u32 {
+        color.set_pixel(x, y, ((x * 19) as u8, (y * 51) as u8, (x * y * 3) as u8, 255));
+        let level = (x * 20 + y) as u8;
+        gray.set_pixel(x, y, (level, level, level, 255));
+        let bit = if (x + y) % 3 == 0 { 0 } else { 255 };
+        bitmap.set_pixel(x, y, (bit, bit, bit, 255));
+      }
+    }
+    for ascii in [false, true] {
+      assert_eq!(round_trip(&color, ImageFormat::Ppm, ascii).rgba(), color.rgba());
+      assert_eq!(round_trip(&gray, ImageFormat::Pgm, ascii).rgba(), gray.rgba());
+      assert_eq!(round_trip(&bitmap, ImageFormat::Pbm, ascii).rgba(), bitmap.rgba());
+    }
+
+    let dir = std::env::temp_dir().join("abra_pnm_round_trip");
+    let path = dir.join("image.ppm");
+    let path = path.to_str().unwrap();
+    color.save(path, None);
+    assert_eq!(Image::new_from_path(path).rgba(), color.rgba());
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn reads_comments_and_other_maximum_values() {
+    let info = decode_pnm(b"P2\n# a comment\n3 1 # width and height\n15\n0 15 8\n").unwrap();
+    assert_eq!((info.width, info.height), (3, 1));
+    assert_eq!(info.pixels, vec![0, 0, 0, 255, 255, 255, 136, 136, 136]);
+
+    let info = decode_pnm(b"P1 4 1 0110").unwrap();
+    assert_eq!(info.pixels, vec![255, 255, 255, 0, 0, 0, 0, 0, 0, 255, 255, 255]);
+
+    let mut wide = b"P5 2 1 65535\n".to_vec();
+    wide.extend_from_slice(&[0xff, 0xff, 0x80, 0x00]);
+    assert_eq!(decode_pnm(&wide).unwrap().pixels, vec![255, 255, 255, 128, 128, 128]);
+
+    assert!(decode_pnm(b"P6 2 2 255\n\x01\x02").is_err());
+    assert!(decode_pnm(b"P7 1 1 255\n").is_err());
+  }
+
+  #[test]
+  fn rejects_headers_larger_than_the_data() {
+    // These would ask for terabytes of samples if the header were trusted.
+    assert!(decode_pnm(b"P6 4294967295 4294967295 255\n\x00").is_err());
+    assert!(decode_pnm(b"P3 100000 100000 255\n0 0 0").is_err());
+    assert!(decode_pnm(b"P4 100000 100000\n\x00").is_err());
+  }
+}
@@ -1,42 +1,89 @@
 use std::fs::{self, read};
+use std::path::PathBuf;
 
 use resvg::tiny_skia;
 use resvg::usvg::{self, Options};
 
-use crate::Channels;
 use crate::fs::file_info::FileInfo;
+use crate::{AspectRatio, Channels, PointF, ViewBox};
 
 /// Reads an SVG file and returns the image data
 pub fn read_svg(file: impl Into<String>) -> Result<FileInfo, String> {
   let file = file.into();
-  let file = file.as_str();
-  let tree = {
-    let mut opt = Options::default();
-    // Get file's absolute directory.
-    opt.resources_dir = fs::canonicalize(file)
-      .ok()
-      .and_then(|p| p.parent().map(|p| p.to_path_buf()));
-    opt.fontdb_mut().load_system_fonts();
-    let svg_data = read(file).unwrap();
-    usvg::Tree::from_data(&svg_data, &opt).unwrap()
+  let tree = parse_svg_file(&file)?;
+  let pix_map_size = tree.size().to_int_size();
+  render_svg(&tree, pix_map_size.width(), pix_map_size.height(), AspectRatio::none())
+}
+
+/// Reads an SVG file and rasterizes it at an exact pixel size instead of its intrinsic size.
+/// - `file`: The SVG file path.
+/// - `width`: The width of the rendered image.
+/// - `height`: The height of the rendered image.
+/// - `aspect_ratio`: How the SVG is scaled and positioned when its aspect ratio differs from the target size.
+pub fn read_svg_sized(
+  file: impl Into<String>, width: u32, height: u32, aspect_ratio: AspectRatio,
+) -> Result<FileInfo, String> {
+  let tree = parse_svg_file(&file.into())?;
+  render_svg(&tree, width, height, aspect_ratio)
+}
+
+/// Rasterizes SVG markup at an exact pixel size.
+/// - `svg`: The SVG markup.
+/// - `width`: The width of the rendered image.
+/// - `height`: The height of the rendered image.
+/// - `aspect_ratio`: How the SVG is scaled and positioned when its aspect ratio differs from the target size.
+pub fn read_svg_str(svg: &str, width: u32, height: u32, aspect_ratio: AspectRatio) -> Result<FileInfo, String> {
+  let tree = parse_svg(svg.as_bytes(), None)?;
+  render_svg(&tree, width, height, aspect_ratio)
+}
+
+/// Parses an SVG file, resolving relative resources such as images against the file's directory.
+fn parse_svg_file(file: &str) -> Result<usvg::Tree, String> {
+  // Get file's absolute directory.
+  let resources_dir = fs::canonicalize(file)
+    .ok()
+    .and_then(|p| p.parent().map(|p| p.to_path_buf()));
+  let svg_data = read(file).map_err(|e| e.to_string())?;
+  parse_svg(&svg_data, resources_dir)
+}
+
+fn parse_svg(svg_data: &[u8], resources_dir: Option<PathBuf>) -> Result<usvg::Tree, String> {
+  let mut opt = Options {
+    resources_dir,
+    ..Options::default()
   };
+  opt.fontdb_mut().load_system_fonts();
+  usvg::Tree::from_data(svg_data, &opt).map_err(|e| e.to_string())
+}
 
-  let pix_map_size = tree.size().to_int_size();
-  let mut pix_map = tiny_skia::Pixmap::new(pix_map_size.width(), pix_map_size.height()).unwrap();
-  resvg::render(&tree, tiny_skia::Transform::default(), &mut pix_map.as_mut());
+/// Renders a parsed SVG into a `width` x `height` image. The SVG's own viewBox and preserveAspectRatio map its
+/// content onto its intrinsic size, which is then mapped onto the target size using `aspect_ratio`.
+fn render_svg(tree: &usvg::Tree, width: u32, height: u32, aspect_ratio: AspectRatio) -> Result<FileInfo, String> {
+  let mut pix_map = tiny_skia::Pixmap::new(width, height).ok_or("SVG has an empty size")?;
+
+  let size = tree.size();
+  let viewbox = ViewBox::from_dimensions(size.width(), size.height());
+  let origin = viewbox.map_point(PointF::new(0.0, 0.0), width as f32, height as f32, aspect_ratio);
+  let corner = viewbox.map_point(PointF::new(size.width(), size.height()), width as f32, height as f32, aspect_ratio);
+  let transform = tiny_skia::Transform::from_row(
+    (corner.x - origin.x) / size.width(),
+    0.0,
+    0.0,
+    (corner.y - origin.y) / size.height(),
+    origin.x,
+    origin.y,
+  );
+  resvg::render(tree, transform, &mut pix_map.as_mut());
 
+  // The pixmap is premultiplied, the image is not.
   let pixels = pix_map
     .pixels()
-    .to_vec()
     .iter()
     .flat_map(|p| {
-      let r = p.red();
-      let g = p.green();
-      let b = p.blue();
-      let a = p.alpha();
-      vec![r, g, b, a]
+      let p = p.demultiply();
+      [p.red(), p.green(), p.blue(), p.alpha()]
     })
     .collect::<Vec<u8>>();
 
-  Ok(FileInfo::new(pix_map_size.width(), pix_map_size.height(), Channels::RGBA, pixels))
+  Ok(FileInfo::new(width, height, Channels::RGBA, pixels))
 }
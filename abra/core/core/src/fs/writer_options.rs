@@ -1,5 +1,173 @@
+use crate::Color;
+
+/// The row filter strategy used when writing PNG images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PngFilter {
+  /// No filtering. Fastest, but usually produces the largest files.
+  None,
+  /// Each byte is stored as the difference to the byte to its left.
+  Sub,
+  /// Each byte is stored as the difference to the byte above it.
+  Up,
+  /// Each byte is stored as the difference to the average of its left and upper neighbours.
+  Average,
+  /// Each byte is stored as the difference to the Paeth predictor of its neighbours.
+  Paeth,
+  /// The best filter is picked for every row. Slower, but usually produces the smallest files.
+  Adaptive,
+}
+
+/// The block compression used when writing GPU textures (DDS and KTX2).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextureCompression {
+  /// Uncompressed 8 bit RGBA, 4 bytes per pixel.
+  #[default]
+  None,
+  /// BC1 (DXT1), 8 bytes per 4x4 block. Alpha is either opaque or fully transparent.
+  Bc1,
+  /// BC3 (DXT5), 16 bytes per 4x4 block with a smooth alpha channel.
+  Bc3,
+}
+
 /// Options for saving an image.
+#[derive(Clone, Debug)]
 pub struct WriterOptions {
   /// The quality of the image between 0 and 100.
   pub quality: u8,
+  /// The PNG deflate compression level between 0 (none) and 9 (smallest).
+  /// When not set, the level is derived from `quality`.
+  pub png_compression: Option<u8>,
+  /// The PNG row filter strategy. When not set, the encoder picks one based on the compression level.
+  pub png_filter: Option<PngFilter>,
+  /// Whether WebP images are written without any loss. Defaults to false, so `quality` applies to WebP too.
+  ///
+  /// WebP is always encoded as VP8L. When this is false, `quality` only controls how coarsely the color channels
+  /// are quantized before encoding, at 100 nothing is lost.
+  pub webp_lossless: bool,
+  /// The quality of the WebP alpha channel between 0 and 100. Only used when `webp_lossless` is false.
+  pub webp_alpha_quality: u8,
+  /// A file size budget in bytes. Lossy encoders search for the highest quality that fits.
+  pub target_size: Option<usize>,
+  /// A minimum structural similarity (0 to 1). Lossy encoders search for the lowest quality that reaches it.
+  pub target_ssim: Option<f64>,
+  /// The color transparent pixels are composited over when the format has no alpha channel (JPEG, Netpbm).
+  pub matte: Color,
+  /// Whether Netpbm images are written in their plain ASCII form (P1, P2, P3) instead of binary.
+  pub pnm_ascii: bool,
+  /// Whether texture formats (DDS, KTX2) store a full mipmap chain down to 1x1.
+  pub generate_mipmaps: bool,
+  /// The block compression of texture formats (DDS, KTX2).
+  pub texture_compression: TextureCompression,
+}
+
+impl WriterOptions {
+  /// Creates writer options with the given quality.
+  /// - `p_quality`: The quality of the image between 0 and 100.
+  pub fn new(p_quality: u8) -> WriterOptions {
+    WriterOptions {
+      quality: p_quality.min(100),
+      ..Default::default()
+    }
+  }
+
+  /// Sets the quality of lossy encoders.
+  /// - `p_quality`: The quality of the image between 0 and 100.
+  pub fn quality(mut self, p_quality: u8) -> Self {
+    self.quality = p_quality.min(100);
+    self
+  }
+
+  /// Sets the PNG deflate compression level.
+  /// Level 1 saves fastest, level 9 produces the smallest files and 0 disables compression.
+  /// - `p_level`: The compression level between 0 and 9.
+  pub fn png_compression(mut self, p_level: u8) -> Self {
+    self.png_compression = Some(p_level.min(9));
+    self
+  }
+
+  /// Sets the PNG row filter strategy.
+  /// - `p_filter`: The filter to apply to every row.
+  pub fn png_filter(mut self, p_filter: PngFilter) -> Self {
+    self.png_filter = Some(p_filter);
+    self
+  }
+
+  /// Sets whether WebP images are written losslessly, ignoring `quality` and `webp_alpha_quality`.
+  /// - `p_lossless`: True to keep every pixel exactly as it is.
+  pub fn webp_lossless(mut self, p_lossless: bool) -> Self {
+    self.webp_lossless = p_lossless;
+    self
+  }
+
+  /// Sets the quality of the WebP alpha channel, independently of the color quality.
+  /// - `p_quality`: The alpha quality between 0 and 100.
+  pub fn webp_alpha_quality(mut self, p_quality: u8) -> Self {
+    self.webp_alpha_quality = p_quality.min(100);
+    self
+  }
+
+  /// Targets a file size instead of a fixed quality for lossy encoders (JPEG, lossy WebP).
+  /// The highest quality whose output fits in the budget is used. This also turns off WebP lossless mode.
+  /// Takes precedence over `target_ssim`.
+  /// - `p_bytes`: The maximum size of the encoded file in bytes.
+  pub fn target_size(mut self, p_bytes: usize) -> Self {
+    self.target_size = Some(p_bytes);
+    self.webp_lossless = false;
+    self
+  }
+
+  /// Targets a minimum structural similarity (SSIM) instead of a fixed quality for lossy encoders.
+  /// The lowest quality whose decoded output reaches the threshold is used. This also turns off WebP lossless mode.
+  /// - `p_ssim`: The minimum SSIM between 0 and 1 (e.g. 0.95).
+  pub fn target_ssim(mut self, p_ssim: f64) -> Self {
+    self.target_ssim = Some(p_ssim.clamp(0.0, 1.0));
+    self.webp_lossless = false;
+    self
+  }
+
+  /// Sets the background color used to flatten transparency for formats without an alpha channel.
+  /// - `p_color`: The matte color. Defaults to white.
+  pub fn matte(mut self, p_color: Color) -> Self {
+    self.matte = p_color;
+    self
+  }
+
+  /// Sets whether Netpbm images are written as human-readable ASCII, which suits golden files.
+  /// - `p_ascii`: Whether to write plain ASCII instead of binary.
+  pub fn pnm_ascii(mut self, p_ascii: bool) -> Self {
+    self.pnm_ascii = p_ascii;
+    self
+  }
+
+  /// Sets whether texture formats store mipmaps. Each level halves the previous one with a box filter.
+  /// - `p_generate`: Whether to generate mipmaps.
+  pub fn generate_mipmaps(mut self, p_generate: bool) -> Self {
+    self.generate_mipmaps = p_generate;
+    self
+  }
+
+  /// Sets the block compression of texture formats.
+  /// - `p_compression`: The compression to use.
+  pub fn texture_compression(mut self, p_compression: TextureCompression) -> Self {
+    self.texture_compression = p_compression;
+    self
+  }
+}
+
+impl Default for WriterOptions {
+  fn default() -> Self {
+    WriterOptions {
+      quality: 100,
+      png_compression: None,
+      png_filter: None,
+      webp_lossless: false,
+      webp_alpha_quality: 100,
+      target_size: None,
+      target_ssim: None,
+      matte: Color::white(),
+      pnm_ascii: false,
+      generate_mipmaps: false,
+      texture_compression: TextureCompression::None,
+    }
+  }
 }
@@ -0,0 +1,142 @@
+use crate::Image;
+use crate::fs::mkdirp;
+use crate::fs::path::dirname;
+use crate::fs::writer_options::{TextureCompression, WriterOptions};
+use crate::fs::writers::texture::{block_bytes, texture_levels};
+use std::fs::write;
+use std::io::Write;
+
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PITCH: u32 = 0x8;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDSD_LINEARSIZE: u32 = 0x80000;
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x400000;
+
+/// Writes the image to a DDS texture file, as uncompressed RGBA8 or BC1/BC3 (DXT1/DXT5) blocks
+/// depending on `WriterOptions::texture_compression`, with a full mipmap chain when
+/// `WriterOptions::generate_mipmaps` is set.
+pub fn write_dds(file: impl Into<String>, image: &Image, options: &Option<WriterOptions>) -> Result<(), String> {
+  let file = file.into();
+  let dir = dirname(&file);
+  mkdirp(&dir).unwrap_or_else(|_| panic!("Error creating directory {}", &dir));
+  let mut bytes = Vec::new();
+  encode_dds(&mut bytes, image, options)?;
+  write(file, bytes).map_err(|e| e.to_string())
+}
+
+/// Encodes the image as a DDS texture into the given writer.
+pub(crate) fn encode_dds<W: Write>(
+  mut writer: W, image: &Image, options: &Option<WriterOptions>,
+) -> Result<(), String> {
+  let compression = options
+    .as_ref()
+    .map_or(TextureCompression::None, |options| options.texture_compression);
+  let levels = texture_levels(image, options);
+  let (width, height) = image.dimensions::<u32>();
+  let has_mipmaps = levels.len() > 1;
+
+  let mut flags = DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT;
+  if has_mipmaps {
+    flags |= DDSD_MIPMAPCOUNT;
+  }
+  // Uncompressed files give the bytes per row, compressed files the size of the top level.
+  let pitch_or_linear_size = match block_bytes(compression) {
+    Some(_) => {
+      flags |= DDSD_LINEARSIZE;
+      levels[0].len() as u32
+    }
+    None => {
+      flags |= DDSD_PITCH;
+      width * 4
+    }
+  };
+  let mut caps = DDSCAPS_TEXTURE;
+  if has_mipmaps {
+    caps |= DDSCAPS_COMPLEX | DDSCAPS_MIPMAP;
+  }
+
+  let mut header: Vec<u32> = vec![124, flags, height, width, pitch_or_linear_size, 0, levels.len() as u32];
+  header.extend([0; 11]);
+  // The pixel format: either 8 bits per RGBA channel or a four character code naming the block format.
+  match compression {
+    TextureCompression::None => header.extend([
+      32,
+      DDPF_RGB | DDPF_ALPHAPIXELS,
+      0,
+      32,
+      0x0000_00ff,
+      0x0000_ff00,
+      0x00ff_0000,
+      0xff00_0000,
+    ]),
+    TextureCompression::Bc1 => header.extend([32, DDPF_FOURCC, u32::from_le_bytes(*b"DXT1"), 0, 0, 0, 0, 0]),
+    TextureCompression::Bc3 => header.extend([32, DDPF_FOURCC, u32::from_le_bytes(*b"DXT5"), 0, 0, 0, 0, 0]),
+  }
+  header.extend([caps, 0, 0, 0, 0]);
+
+  let mut bytes = b"DDS ".to_vec();
+  bytes.extend(header.iter().flat_map(|value| value.to_le_bytes()));
+  for level in &levels {
+    bytes.extend_from_slice(level);
+  }
+  writer.write_all(&bytes).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ddsfile::{D3DFormat, Dds};
+
+  fn test_image() -> Image {
+    let mut image = Image::new(16, 8);
+    for y in 0..8u32 {
+      for x in 0..16u32 {
+        image.set_pixel(x, y, ((x * 16) as u8, (y * 32) as u8, 90, (255 - x * 8) as u8));
+      }
+    }
+    image
+  }
+
+  fn encode(p_options: WriterOptions) -> Dds {
+    let mut bytes = Vec::new();
+    encode_dds(&mut bytes, &test_image(), &Some(p_options)).unwrap();
+    Dds::read(&bytes[..]).unwrap()
+  }
+
+  #[test]
+  fn rgba8_with_mipmaps_reads_back() {
+    let dds = encode(WriterOptions::default().generate_mipmaps(true));
+    assert_eq!((dds.get_width(), dds.get_height()), (16, 8));
+    assert_eq!(dds.get_d3d_format(), Some(D3DFormat::A8B8G8R8));
+    // 16x8, 8x4, 4x2, 2x1 and 1x1.
+    assert_eq!(dds.get_num_mipmap_levels(), 5);
+    let data = dds.get_data(0).unwrap();
+    assert_eq!(data.len(), (128 + 32 + 8 + 2 + 1) * 4);
+    assert_eq!(&data[..16 * 8 * 4], test_image().rgba());
+  }
+
+  #[test]
+  fn block_compressed_formats_read_back() {
+    let dds = encode(WriterOptions::default().texture_compression(TextureCompression::Bc1));
+    assert_eq!(dds.get_d3d_format(), Some(D3DFormat::DXT1));
+    assert_eq!(dds.get_num_mipmap_levels(), 1);
+    assert_eq!(dds.get_data(0).unwrap().len(), 4 * 2 * 8);
+
+    let options = WriterOptions::default()
+      .texture_compression(TextureCompression::Bc3)
+      .generate_mipmaps(true);
+    let dds = encode(options);
+    assert_eq!(dds.get_d3d_format(), Some(D3DFormat::DXT5));
+    assert_eq!(dds.get_num_mipmap_levels(), 5);
+    // Levels smaller than a block still take a whole block.
+    assert_eq!(dds.get_data(0).unwrap().len(), (8 + 2 + 1 + 1 + 1) * 16);
+  }
+}
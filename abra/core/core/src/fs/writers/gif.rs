@@ -4,6 +4,7 @@ use crate::fs::path::dirname;
 use crate::fs::writer_options::WriterOptions;
 use gif::{Encoder, Frame, Repeat};
 use std::fs::File;
+use std::io::{BufWriter, Write};
 
 /// Writes the image data to a GIF file
 pub fn write_gif(file: impl Into<String>, image: &Image, options: &Option<WriterOptions>) -> Result<(), String> {
@@ -12,9 +13,14 @@ pub fn write_gif(file: impl Into<String>, image: &Image, options: &Option<Writer
   mkdirp(&dir).unwrap_or_else(|_| panic!("Error creating directory {}", &dir));
 
   let file_handle = File::create(file).map_err(|e| e.to_string())?;
+  encode_gif(BufWriter::new(file_handle), image, options)
+}
+
+/// Encodes the image data as GIF into the given writer
+pub(crate) fn encode_gif<W: Write>(writer: W, image: &Image, options: &Option<WriterOptions>) -> Result<(), String> {
   let (width, height) = image.dimensions::<u16>();
 
-  let mut encoder = Encoder::new(file_handle, width, height, &[]).map_err(|e| e.to_string())?;
+  let mut encoder = Encoder::new(writer, width, height, &[]).map_err(|e| e.to_string())?;
 
   // Set repeat to loop infinitely by default
   encoder.set_repeat(Repeat::Infinite).map_err(|e| e.to_string())?;
@@ -41,10 +47,7 @@ pub fn write_gif(file: impl Into<String>, image: &Image, options: &Option<Writer
     frame.delay = 10; // Default 100ms delay
   }
 
-  encoder.write_frame(&frame).map_err(|e| e.to_string())?;
-
-  println!("GIF written successfully");
-  Ok(())
+  encoder.write_frame(&frame).map_err(|e| e.to_string())
 }
 
 /// Converts RGBA format to indexed color (palette-based) format using a simple approach
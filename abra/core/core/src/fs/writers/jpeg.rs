@@ -1,38 +1,50 @@
+use crate::Color;
 use crate::Image;
 use crate::fs::mkdirp;
 use crate::fs::path::dirname;
+use crate::fs::quality_search::encode_with_quality_target;
 use crate::fs::writer_options::WriterOptions;
 use std::fs::write;
 use turbojpeg::PixelFormat::RGB;
-use turbojpeg::compress;
+use turbojpeg::{compress, decompress};
 
-/// Writes the image data to a JPEG file
-pub fn write_jpg(file: impl Into<String>, image: &Image, options: &Option<WriterOptions>) -> Result<(), String> {
+/// Writes the image data to a JPEG file.
+/// Returns the quality that was used, which differs from the requested one when a size or SSIM target is set.
+pub fn write_jpg(file: impl Into<String>, image: &Image, options: &Option<WriterOptions>) -> Result<u8, String> {
   let file = file.into();
   let dir = dirname(file.as_str());
   mkdirp(&dir).unwrap_or_else(|_| panic!("Error creating directory {}", &dir));
-  // File::create(file.as_str()).map_err(|e| e.to_string())?;
-  let quality = match options {
-    Some(o) => o.quality,
-    None => 100,
-  };
-  println!("JPEG Quality set to {}", quality);
+  let (jpeg_data, quality) = encode_jpg(image, options)?;
+  write(file.as_str(), &jpeg_data).map_err(|e| e.to_string())?;
+  Ok(quality)
+}
 
+/// Encodes the image data as JPEG and returns the bytes along with the quality that was used
+pub(crate) fn encode_jpg(image: &Image, options: &Option<WriterOptions>) -> Result<(Vec<u8>, u8), String> {
   let (width, height) = image.dimensions::<u32>();
 
-  // Convert our RGBA image to an RGB buffer (JPEG doesn't support alpha)
-  let rgb_pixels = image.rgb();
+  // JPEG doesn't support alpha, so composite over the matte color before converting to RGB
+  let matte = options.as_ref().map_or_else(Color::white, |options| options.matte);
+  let mut flattened = image.clone();
+  flattened.flatten_onto(matte);
+  let rgb_pixels = flattened.rgb();
+
+  let encode = |quality: u8| {
+    // Build a turbojpeg Image<&[u8]> describing our RGB pixels
+    let tj_image = turbojpeg::Image {
+      pixels: &rgb_pixels[..],
+      width: width as usize,
+      pitch: (width as usize) * 3, // 3 bytes per pixel for RGB
+      height: height as usize,
+      format: RGB,
+    };
 
-  // Build a turbojpeg Image<&[u8]> describing our RGB pixels
-  let tj_image = turbojpeg::Image {
-    pixels: &rgb_pixels[..],
-    width: width as usize,
-    pitch: (width as usize) * 3, // 3 bytes per pixel for RGB
-    height: height as usize,
-    format: RGB,
+    // Compress into JPEG using TurboJPEG
+    compress(tj_image, quality as i32, turbojpeg::Subsamp::Sub2x2)
+      .map(|data| data.to_vec())
+      .map_err(|e| e.to_string())
   };
+  let decode = |bytes: &[u8]| decompress(bytes, RGB).map(|data| data.pixels).map_err(|e| e.to_string());
 
-  // Compress into JPEG using TurboJPEG
-  let jpeg_data = compress(tj_image, quality as i32, turbojpeg::Subsamp::Sub2x2).map_err(|e| e.to_string())?;
-  write(file.as_str(), &jpeg_data).map_err(|e| e.to_string())
+  encode_with_quality_target(options, &rgb_pixels, width as usize, height as usize, encode, decode)
 }
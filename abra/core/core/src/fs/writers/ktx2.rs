@@ -0,0 +1,183 @@
+use crate::Image;
+use crate::fs::mkdirp;
+use crate::fs::path::dirname;
+use crate::fs::writer_options::{TextureCompression, WriterOptions};
+use crate::fs::writers::texture::{block_bytes, texture_levels};
+use std::fs::write;
+use std::io::Write;
+
+const IDENTIFIER: [u8; 12] = [
+  0xab, b'K', b'T', b'X', b' ', b'2', b'0', 0xbb, b'\r', b'\n', 0x1a, b'\n',
+];
+const HEADER_LENGTH: usize = 80;
+const LEVEL_INDEX_LENGTH: usize = 24;
+
+/// A sample of the data format descriptor: bit offset, bit length, channel id, whether the channel is linear
+/// and the upper value.
+type Sample = (u32, u32, u32, bool, u32);
+
+/// Writes the image to a KTX2 texture file, as uncompressed RGBA8 or BC1/BC3 blocks depending on
+/// `WriterOptions::texture_compression`, with a full mipmap chain when `WriterOptions::generate_mipmaps` is set.
+/// The texels are tagged as sRGB, matching how images store their colors.
+pub fn write_ktx2(file: impl Into<String>, image: &Image, options: &Option<WriterOptions>) -> Result<(), String> {
+  let file = file.into();
+  let dir = dirname(&file);
+  mkdirp(&dir).unwrap_or_else(|_| panic!("Error creating directory {}", &dir));
+  let mut bytes = Vec::new();
+  encode_ktx2(&mut bytes, image, options)?;
+  write(file, bytes).map_err(|e| e.to_string())
+}
+
+/// Builds the basic data format descriptor that tells loaders how the texels are laid out.
+fn data_format_descriptor(p_compression: TextureCompression) -> Vec<u8> {
+  let (color_model, block_size, plane_bytes, samples): (u32, u32, u32, Vec<Sample>) = match p_compression {
+    TextureCompression::None => (
+      1,
+      1,
+      4,
+      vec![
+        (0, 8, 0, false, 255),
+        (8, 8, 1, false, 255),
+        (16, 8, 2, false, 255),
+        (24, 8, 15, true, 255),
+      ],
+    ),
+    // Channel 1 of the BC1A model marks that the block may contain transparency.
+    TextureCompression::Bc1 => (128, 4, 8, vec![(0, 64, 1, false, u32::MAX)]),
+    TextureCompression::Bc3 => (130, 4, 16, vec![(0, 64, 15, true, u32::MAX), (64, 64, 0, false, u32::MAX)]),
+  };
+  let block_length = 24 + 16 * samples.len() as u32;
+
+  let mut words = vec![
+    4 + block_length,
+    // Vendor and descriptor type 0 (Khronos basic), version 2 and the size of the block.
+    0,
+    2 | (block_length << 16),
+    // Color model, BT.709 primaries, sRGB transfer function and straight alpha.
+    color_model | (1 << 8) | (2 << 16),
+    // Texel block dimensions minus one.
+    (block_size - 1) | ((block_size - 1) << 8),
+    plane_bytes,
+    0,
+  ];
+  for (offset, length, channel, linear, upper) in samples {
+    let qualifiers = if linear { 1 << 4 } else { 0 };
+    words.extend([
+      offset | ((length - 1) << 16) | ((channel | qualifiers) << 24),
+      0,
+      0,
+      upper,
+    ]);
+  }
+  words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+/// Encodes the image as a KTX2 texture into the given writer.
+pub(crate) fn encode_ktx2<W: Write>(
+  mut writer: W, image: &Image, options: &Option<WriterOptions>,
+) -> Result<(), String> {
+  let compression = options
+    .as_ref()
+    .map_or(TextureCompression::None, |options| options.texture_compression);
+  let levels = texture_levels(image, options);
+  let (width, height) = image.dimensions::<u32>();
+  let vk_format: u32 = match compression {
+    TextureCompression::None => 43, // VK_FORMAT_R8G8B8A8_SRGB
+    TextureCompression::Bc1 => 134, // VK_FORMAT_BC1_RGBA_SRGB_BLOCK
+    TextureCompression::Bc3 => 138, // VK_FORMAT_BC3_SRGB_BLOCK
+  };
+
+  let dfd = data_format_descriptor(compression);
+  let dfd_offset = HEADER_LENGTH + LEVEL_INDEX_LENGTH * levels.len();
+  // Levels are stored from the smallest to the largest, each aligned to its texel block size.
+  let alignment = block_bytes(compression).unwrap_or(4);
+  let mut data = Vec::new();
+  let mut level_index = vec![(0u64, 0u64); levels.len()];
+  let data_start = dfd_offset + dfd.len();
+  for (index, level) in levels.iter().enumerate().rev() {
+    let padding = (alignment - (data_start + data.len()) % alignment) % alignment;
+    data.resize(data.len() + padding, 0);
+    level_index[index] = ((data_start + data.len()) as u64, level.len() as u64);
+    data.extend_from_slice(level);
+  }
+
+  let mut bytes = IDENTIFIER.to_vec();
+  // Format, type size, width, height, depth, layers, faces, levels and supercompression.
+  for value in [vk_format, 1, width, height, 0, 0, 1, levels.len() as u32, 0] {
+    bytes.extend_from_slice(&value.to_le_bytes());
+  }
+  // The descriptor, no key/value data and no supercompression data.
+  for value in [dfd_offset as u32, dfd.len() as u32, 0, 0] {
+    bytes.extend_from_slice(&value.to_le_bytes());
+  }
+  bytes.extend_from_slice(&[0; 16]);
+  for (offset, length) in level_index {
+    for value in [offset, length, length] {
+      bytes.extend_from_slice(&value.to_le_bytes());
+    }
+  }
+  bytes.extend_from_slice(&dfd);
+  bytes.extend_from_slice(&data);
+  writer.write_all(&bytes).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ktx2::{ColorModel, DfdBlockBasic, Format, Reader};
+
+  fn test_image() -> Image {
+    let mut image = Image::new(16, 8);
+    for y in 0..8u32 {
+      for x in 0..16u32 {
+        image.set_pixel(x, y, ((x * 16) as u8, (y * 32) as u8, 90, (255 - x * 8) as u8));
+      }
+    }
+    image
+  }
+
+  fn encode(p_options: WriterOptions) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    encode_ktx2(&mut bytes, &test_image(), &Some(p_options)).unwrap();
+    bytes
+  }
+
+  #[test]
+  fn rgba8_with_mipmaps_reads_back() {
+    let bytes = encode(WriterOptions::default().generate_mipmaps(true));
+    let reader = Reader::new(&bytes[..]).unwrap();
+    let header = reader.header();
+    assert_eq!(header.format, Some(Format::R8G8B8A8_SRGB));
+    assert_eq!((header.pixel_width, header.pixel_height, header.level_count), (16, 8, 5));
+    let sizes: Vec<usize> = reader.levels().map(|level| level.data.len()).collect();
+    assert_eq!(sizes, vec![512, 128, 32, 8, 4]);
+    assert_eq!(reader.levels().next().unwrap().data, test_image().rgba());
+
+    let block = reader.dfd_blocks().next().unwrap();
+    let basic = DfdBlockBasic::parse(block.data).unwrap();
+    assert_eq!(basic.header.color_model, Some(ColorModel::RGBSDA));
+    assert_eq!(basic.header.bytes_planes[0], 4);
+    assert_eq!(basic.sample_information().count(), 4);
+  }
+
+  #[test]
+  fn block_compressed_levels_are_aligned() {
+    let options = WriterOptions::default()
+      .texture_compression(TextureCompression::Bc3)
+      .generate_mipmaps(true);
+    let bytes = encode(options);
+    let reader = Reader::new(&bytes[..]).unwrap();
+    assert_eq!(reader.header().format, Some(Format::BC3_SRGB_BLOCK));
+    for level in reader.levels() {
+      assert_eq!((level.data.as_ptr() as usize - bytes.as_ptr() as usize) % 16, 0);
+    }
+    let basic = DfdBlockBasic::parse(reader.dfd_blocks().next().unwrap().data).unwrap();
+    assert_eq!(basic.header.color_model, Some(ColorModel::BC3));
+    assert_eq!(basic.header.texel_block_dimensions.map(|dim| dim.get()), [4, 4, 1, 1]);
+
+    let bytes = encode(WriterOptions::default().texture_compression(TextureCompression::Bc1));
+    let reader = Reader::new(&bytes[..]).unwrap();
+    assert_eq!(reader.header().format, Some(Format::BC1_RGBA_SRGB_BLOCK));
+    assert_eq!(reader.levels().next().unwrap().data.len(), 4 * 2 * 8);
+  }
+}
@@ -1,11 +1,12 @@
 use crate::Image;
 use crate::fs::mkdirp;
 use crate::fs::path::dirname;
-use crate::fs::writer_options::WriterOptions;
+use crate::fs::writer_options::{PngFilter, WriterOptions};
 
 use png::ColorType::Rgba;
-use png::Encoder;
+use png::{DeflateCompression, Encoder, Filter};
 use std::fs::File;
+use std::io::{BufWriter, Write};
 
 /// Writes the image data to a PNG file
 pub fn write_png(file: impl Into<String>, image: &Image, options: &Option<WriterOptions>) -> Result<(), String> {
@@ -13,38 +14,82 @@ pub fn write_png(file: impl Into<String>, image: &Image, options: &Option<Writer
   let dir = dirname(&file);
   mkdirp(&dir).unwrap_or_else(|_| panic!("Error creating directory {}", &dir));
   let file = File::create(file).map_err(|e| e.to_string())?;
-  let (width, height) = image.dimensions();
-  let mut encoder = Encoder::new(file, width, height);
+  encode_png(BufWriter::new(file), image, options)
+}
 
-  let channels = 4; // Always use RGBA
+/// Encodes the image data as PNG into the given writer
+pub(crate) fn encode_png<W: Write>(writer: W, image: &Image, options: &Option<WriterOptions>) -> Result<(), String> {
+  let (width, height) = image.dimensions();
+  let mut encoder = Encoder::new(writer, width, height);
 
   encoder.set_color(Rgba);
   encoder.set_depth(png::BitDepth::Eight);
 
-  // Set compression level based on quality (higher quality = less compression for speed)
   if let Some(opts) = options {
-    let compression = if opts.quality > 75 {
-      png::Compression::Fastest
-    } else if opts.quality > 25 {
-      png::Compression::Balanced
-    } else {
-      png::Compression::High
-    };
-    println!("PNG Compression level set to {:?}", compression);
-    encoder.set_compression(compression);
+    match opts.png_compression {
+      Some(0) => encoder.set_deflate_compression(DeflateCompression::NoCompression),
+      Some(level) => encoder.set_deflate_compression(DeflateCompression::Level(level.min(9))),
+      // Without an explicit level, derive one from quality (higher quality = less compression for speed)
+      None => encoder.set_compression(if opts.quality > 75 {
+        png::Compression::Fastest
+      } else if opts.quality > 25 {
+        png::Compression::Balanced
+      } else {
+        png::Compression::High
+      }),
+    }
+    if let Some(filter) = opts.png_filter {
+      encoder.set_filter(match filter {
+        PngFilter::None => Filter::NoFilter,
+        PngFilter::Sub => Filter::Sub,
+        PngFilter::Up => Filter::Up,
+        PngFilter::Average => Filter::Avg,
+        PngFilter::Paeth => Filter::Paeth,
+        PngFilter::Adaptive => Filter::Adaptive,
+      });
+    }
   } else {
     encoder.set_compression(png::Compression::default());
-    println!("PNG Compression level set to Balanced");
   }
 
-  let mut writer = encoder.write_header().unwrap();
-  if channels == 4 {
-    let pixels = image.rgba();
-    writer.write_image_data(pixels).unwrap();
-  } else {
-    let pixels = image.rgb();
-    writer.write_image_data(&pixels).unwrap();
+  let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+  writer.write_image_data(image.rgba()).map_err(|e| e.to_string())?;
+  writer.finish().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn encode(image: &Image, options: WriterOptions) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    encode_png(&mut bytes, image, &Some(options)).unwrap();
+    bytes
+  }
+
+  fn decode(bytes: &[u8]) -> Vec<u8> {
+    let mut reader = png::Decoder::new(std::io::Cursor::new(bytes)).read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    pixels.truncate(info.buffer_size());
+    pixels
   }
 
-  Ok(())
+  #[test]
+  fn higher_compression_level_produces_smaller_file_with_same_pixels() {
+    let mut image = Image::new(128, 128);
+    for y in 0..128u32 {
+      for x in 0..128u32 {
+        let noise = ((x * 7 + y * 13) ^ (x * y)) % 16;
+        image.set_pixel(x, y, ((x * 2) as u8, (y * 2) as u8, ((x + y) as u8).wrapping_add(noise as u8), 255));
+      }
+    }
+
+    let fast = encode(&image, WriterOptions::default().png_compression(1).png_filter(PngFilter::None));
+    let small = encode(&image, WriterOptions::default().png_compression(9).png_filter(PngFilter::Adaptive));
+
+    assert!(small.len() < fast.len(), "level 9 ({}) should be smaller than level 1 ({})", small.len(), fast.len());
+    assert_eq!(decode(&fast), image.rgba());
+    assert_eq!(decode(&small), image.rgba());
+  }
 }
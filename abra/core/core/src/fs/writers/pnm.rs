@@ -0,0 +1,89 @@
+use crate::Color;
+use crate::Image;
+use crate::fs::ImageFormat;
+use crate::fs::mkdirp;
+use crate::fs::path::dirname;
+use crate::fs::writer_options::WriterOptions;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// Writes the image data to a Netpbm file. The extension picks the variant: `.pgm` writes gray levels,
+/// `.pbm` writes a black and white bitmap and any other extension writes RGB (`.ppm`).
+pub fn write_pnm(file: impl Into<String>, image: &Image, options: &Option<WriterOptions>) -> Result<(), String> {
+  let file = file.into();
+  let dir = dirname(&file);
+  mkdirp(&dir).unwrap_or_else(|_| panic!("Error creating directory {}", &dir));
+
+  let format = match ImageFormat::from_path(&file) {
+    Some(format @ (ImageFormat::Pgm | ImageFormat::Pbm)) => format,
+    _ => ImageFormat::Ppm,
+  };
+  let file_handle = File::create(file).map_err(|e| e.to_string())?;
+  let mut writer = BufWriter::new(file_handle);
+  encode_pnm(&mut writer, image, format, options)?;
+  writer.flush().map_err(|e| e.to_string())
+}
+
+/// Encodes the image data as a Netpbm variant into the given writer.
+/// Transparency is flattened onto the matte color, since Netpbm has no alpha channel.
+pub(crate) fn encode_pnm<W: Write>(
+  mut writer: W, image: &Image, format: ImageFormat, options: &Option<WriterOptions>,
+) -> Result<(), String> {
+  let (width, height) = image.dimensions::<usize>();
+  let matte = options.as_ref().map_or_else(Color::white, |options| options.matte);
+  let ascii = options.as_ref().is_some_and(|options| options.pnm_ascii);
+  let mut flattened = image.clone();
+  flattened.flatten_onto(matte);
+  let rgb = flattened.rgb();
+  let luma = |px: &[u8]| (Color::from_rgba(px[0], px[1], px[2], 255).luminance() * 255.0).round() as u8;
+
+  let (magic, samples): (u8, Vec<u8>) = match format {
+    ImageFormat::Pgm => (2, rgb.chunks_exact(3).map(luma).collect()),
+    // In a bitmap 1 is black.
+    ImageFormat::Pbm => (1, rgb.chunks_exact(3).map(|px| (luma(px) < 128) as u8).collect()),
+    _ => (3, rgb),
+  };
+  let magic = if ascii { magic } else { magic + 3 };
+  let mut bytes = format!("P{}\n{} {}\n", magic, width, height).into_bytes();
+  if format != ImageFormat::Pbm {
+    bytes.extend_from_slice(b"255\n");
+  }
+
+  let row_len = samples.len() / height.max(1);
+  if ascii {
+    // Plain files should keep their lines short, so each image row is wrapped at 70 characters.
+    for row in samples.chunks(row_len.max(1)) {
+      let mut line = String::new();
+      for sample in row {
+        let text = sample.to_string();
+        if !line.is_empty() && line.len() + text.len() >= 70 {
+          bytes.extend_from_slice(line.as_bytes());
+          bytes.push(b'\n');
+          line.clear();
+        }
+        if !line.is_empty() {
+          line.push(' ');
+        }
+        line.push_str(&text);
+      }
+      bytes.extend_from_slice(line.as_bytes());
+      bytes.push(b'\n');
+    }
+  } else if format == ImageFormat::Pbm {
+    // Binary bitmaps pack 8 pixels per byte, with every row starting on a new byte.
+    for row in samples.chunks(width.max(1)) {
+      for pixels in row.chunks(8) {
+        bytes.push(
+          pixels
+            .iter()
+            .enumerate()
+            .fold(0u8, |byte, (i, bit)| byte | bit << (7 - i)),
+        );
+      }
+    }
+  } else {
+    bytes.extend_from_slice(&samples);
+  }
+
+  writer.write_all(&bytes).map_err(|e| e.to_string())
+}
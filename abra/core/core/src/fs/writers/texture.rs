@@ -0,0 +1,287 @@
+//! Mipmap generation and block compression shared by the GPU texture writers (DDS and KTX2).
+
+use crate::Image;
+use crate::fs::writer_options::{TextureCompression, WriterOptions};
+use crate::transform::{TransformAlgorithm, generate_mipmaps};
+
+/// The number of bytes of one 4x4 block, or `None` for uncompressed RGBA8.
+pub(crate) fn block_bytes(p_compression: TextureCompression) -> Option<usize> {
+  match p_compression {
+    TextureCompression::None => None,
+    TextureCompression::Bc1 => Some(8),
+    TextureCompression::Bc3 => Some(16),
+  }
+}
+
+/// Encodes the image as texture levels: the full size image followed, when mipmaps are requested,
+/// by every halved level down to 1x1 from `generate_mipmaps`. Each level holds its encoded texel data.
+pub(crate) fn texture_levels(p_image: &Image, p_options: &Option<WriterOptions>) -> Vec<Vec<u8>> {
+  let compression = p_options
+    .as_ref()
+    .map_or(TextureCompression::None, |options| options.texture_compression);
+  let encode = |level: &Image| {
+    let (width, height) = level.dimensions::<u32>();
+    encode_level(level.rgba(), width, height, compression)
+  };
+  if p_options.as_ref().is_some_and(|options| options.generate_mipmaps) {
+    generate_mipmaps(p_image, TransformAlgorithm::Bilinear)
+      .iter()
+      .map(encode)
+      .collect()
+  } else {
+    vec![encode(p_image)]
+  }
+}
+
+/// Encodes the pixels of one level, row by row for RGBA8 or as 4x4 blocks for block compression.
+fn encode_level(p_pixels: &[u8], p_width: u32, p_height: u32, p_compression: TextureCompression) -> Vec<u8> {
+  if p_compression == TextureCompression::None {
+    return p_pixels.to_vec();
+  }
+  let (blocks_x, blocks_y) = (p_width.div_ceil(4), p_height.div_ceil(4));
+  let mut out = Vec::with_capacity((blocks_x * blocks_y) as usize * block_bytes(p_compression).unwrap_or(0));
+  for block_y in 0..blocks_y {
+    for block_x in 0..blocks_x {
+      // Blocks that hang over the edge repeat the last row and column.
+      let mut block = [[0u8; 4]; 16];
+      for (i, texel) in block.iter_mut().enumerate() {
+        let x = (block_x * 4 + i as u32 % 4).min(p_width - 1);
+        let y = (block_y * 4 + i as u32 / 4).min(p_height - 1);
+        let offset = ((y * p_width + x) * 4) as usize;
+        texel.copy_from_slice(&p_pixels[offset..offset + 4]);
+      }
+      match p_compression {
+        TextureCompression::Bc1 => out.extend_from_slice(&bc1_block(&block, true)),
+        _ => {
+          out.extend_from_slice(&bc3_alpha_block(&block));
+          out.extend_from_slice(&bc1_block(&block, false));
+        }
+      }
+    }
+  }
+  out
+}
+
+/// Packs a color into RGB565.
+fn to_565(p_color: [f32; 3]) -> u16 {
+  let r = (p_color[0].clamp(0.0, 255.0) * 31.0 / 255.0).round() as u16;
+  let g = (p_color[1].clamp(0.0, 255.0) * 63.0 / 255.0).round() as u16;
+  let b = (p_color[2].clamp(0.0, 255.0) * 31.0 / 255.0).round() as u16;
+  (r << 11) | (g << 5) | b
+}
+
+/// Expands an RGB565 color the way GPUs decode it.
+fn from_565(p_color: u16) -> [i32; 3] {
+  let (r, g, b) = ((p_color >> 11) as i32, ((p_color >> 5) & 63) as i32, (p_color & 31) as i32);
+  [(r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2)]
+}
+
+/// Compresses a 4x4 block to BC1. The endpoints span the bounding box of the block's colors, slightly inset
+/// and flipped along the axes where the channels are anticorrelated, and every texel picks the closest palette color.
+/// - `p_block`: The 16 RGBA texels of the block, row by row.
+/// - `p_punch_through`: Whether texels with an alpha below 128 are stored as transparent. BC3 color blocks
+///   must not use this mode.
+fn bc1_block(p_block: &[[u8; 4]; 16], p_punch_through: bool) -> [u8; 8] {
+  let is_transparent = |texel: &[u8; 4]| p_punch_through && texel[3] < 128;
+  let opaque: Vec<[f32; 3]> = p_block
+    .iter()
+    .filter(|texel| !is_transparent(texel))
+    .map(|texel| [texel[0] as f32, texel[1] as f32, texel[2] as f32])
+    .collect();
+  if opaque.is_empty() {
+    // Equal endpoints select the three color mode, where index 3 is transparent.
+    return [0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff];
+  }
+
+  let mut min = [255.0f32; 3];
+  let mut max = [0.0f32; 3];
+  let mut mean = [0.0f32; 3];
+  for texel in &opaque {
+    for c in 0..3 {
+      min[c] = min[c].min(texel[c]);
+      max[c] = max[c].max(texel[c]);
+      mean[c] += texel[c] / opaque.len() as f32;
+    }
+  }
+  // The box diagonal from min to max only follows the colors when every channel rises with green.
+  for c in [0, 2] {
+    let covariance: f32 = opaque
+      .iter()
+      .map(|texel| (texel[c] - mean[c]) * (texel[1] - mean[1]))
+      .sum();
+    if covariance < 0.0 {
+      std::mem::swap(&mut min[c], &mut max[c]);
+    }
+  }
+  for c in 0..3 {
+    let inset = (max[c] - min[c]) / 16.0;
+    max[c] -= inset;
+    min[c] += inset;
+  }
+
+  let (a, b) = (to_565(max), to_565(min));
+  let three_color = p_block.iter().any(is_transparent);
+  // The order of the endpoints selects the mode: c0 > c1 for four colors, c0 <= c1 for three colors and transparency.
+  let (c0, c1) = if three_color {
+    (a.min(b), a.max(b))
+  } else {
+    (a.max(b), a.min(b))
+  };
+  let (e0, e1) = (from_565(c0), from_565(c1));
+  let mix = |w0: i32, w1: i32| [0, 1, 2].map(|c| (e0[c] * w0 + e1[c] * w1) / (w0 + w1));
+  let palette: Vec<[i32; 3]> = if three_color {
+    vec![e0, e1, mix(1, 1)]
+  } else {
+    vec![e0, e1, mix(2, 1), mix(1, 2)]
+  };
+
+  let mut indices = 0u32;
+  for (i, texel) in p_block.iter().enumerate() {
+    let index = if is_transparent(texel) {
+      3
+    } else {
+      let distance = |color: &[i32; 3]| (0..3).map(|c| (color[c] - texel[c] as i32).pow(2)).sum::<i32>();
+      (0..palette.len()).min_by_key(|&p| distance(&palette[p])).unwrap_or(0) as u32
+    };
+    indices |= index << (i * 2);
+  }
+
+  let mut out = [0u8; 8];
+  out[0..2].copy_from_slice(&c0.to_le_bytes());
+  out[2..4].copy_from_slice(&c1.to_le_bytes());
+  out[4..8].copy_from_slice(&indices.to_le_bytes());
+  out
+}
+
+/// Compresses the alpha of a 4x4 block to the 8 byte alpha part of a BC3 block, with eight levels
+/// spread evenly between the lowest and highest alpha.
+fn bc3_alpha_block(p_block: &[[u8; 4]; 16]) -> [u8; 8] {
+  let a0 = p_block.iter().map(|texel| texel[3]).max().unwrap_or(255);
+  let a1 = p_block.iter().map(|texel| texel[3]).min().unwrap_or(255);
+  let mut out = [a0, a1, 0, 0, 0, 0, 0, 0];
+  if a0 == a1 {
+    return out;
+  }
+
+  // With a0 > a1, indices 2 to 7 interpolate from a0 toward a1.
+  let palette: Vec<i32> = (0..8)
+    .map(|i| match i {
+      0 => a0 as i32,
+      1 => a1 as i32,
+      _ => (a0 as i32 * (8 - i) + a1 as i32 * (i - 1)) / 7,
+    })
+    .collect();
+  let mut indices = 0u64;
+  for (i, texel) in p_block.iter().enumerate() {
+    let index = (0..8)
+      .min_by_key(|&p| (palette[p] - texel[3] as i32).abs())
+      .unwrap_or(0) as u64;
+    indices |= index << (i * 3);
+  }
+  out[2..8].copy_from_slice(&indices.to_le_bytes()[..6]);
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use primitives::Color;
+
+  /// Decodes a BC1 block the way a GPU does.
+  fn decode_bc1(p_block: &[u8]) -> Vec<[i32; 4]> {
+    let c0 = u16::from_le_bytes([p_block[0], p_block[1]]);
+    let c1 = u16::from_le_bytes([p_block[2], p_block[3]]);
+    let (e0, e1) = (from_565(c0), from_565(c1));
+    let mix = |w0: i32, w1: i32| [0, 1, 2].map(|c| (e0[c] * w0 + e1[c] * w1) / (w0 + w1));
+    let palette = if c0 > c1 {
+      [e0, e1, mix(2, 1), mix(1, 2)].map(|[r, g, b]| [r, g, b, 255])
+    } else {
+      let [r, g, b] = mix(1, 1);
+      [
+        [e0[0], e0[1], e0[2], 255],
+        [e1[0], e1[1], e1[2], 255],
+        [r, g, b, 255],
+        [0, 0, 0, 0],
+      ]
+    };
+    let indices = u32::from_le_bytes([p_block[4], p_block[5], p_block[6], p_block[7]]);
+    (0..16).map(|i| palette[((indices >> (i * 2)) & 3) as usize]).collect()
+  }
+
+  fn gradient_block() -> [[u8; 4]; 16] {
+    let mut block = [[0u8; 4]; 16];
+    for (i, texel) in block.iter_mut().enumerate() {
+      let value = (i * 16) as u8;
+      *texel = [value, 255 - value, value / 2, 255];
+    }
+    block
+  }
+
+  #[test]
+  fn bc1_stays_close_to_the_source() {
+    let block = gradient_block();
+    let decoded = decode_bc1(&bc1_block(&block, true));
+    // Four palette colors spread over a 240 step gradient are at most about 40 steps from any texel.
+    for (texel, decoded) in block.iter().zip(decoded) {
+      for c in 0..3 {
+        assert!((texel[c] as i32 - decoded[c]).abs() <= 42, "{:?} {:?}", texel, decoded);
+      }
+      assert_eq!(decoded[3], 255);
+    }
+
+    // Transparent texels use the punch-through mode.
+    let mut cutout = block;
+    cutout[5][3] = 0;
+    let decoded = decode_bc1(&bc1_block(&cutout, true));
+    assert_eq!(decoded[5][3], 0);
+    assert_eq!(decoded[6][3], 255);
+  }
+
+  #[test]
+  fn bc3_alpha_keeps_levels() {
+    let mut block = gradient_block();
+    for (i, texel) in block.iter_mut().enumerate() {
+      texel[3] = (i * 17) as u8;
+    }
+    let encoded = bc3_alpha_block(&block);
+    let (a0, a1) = (encoded[0] as i32, encoded[1] as i32);
+    let mut bits = [0u8; 8];
+    bits[..6].copy_from_slice(&encoded[2..]);
+    let indices = u64::from_le_bytes(bits);
+    for (i, texel) in block.iter().enumerate() {
+      let alpha = match (indices >> (i * 3)) & 7 {
+        0 => a0,
+        1 => a1,
+        index => (a0 * (8 - index as i32) + a1 * (index as i32 - 1)) / 7,
+      };
+      assert!((alpha - texel[3] as i32).abs() <= 19, "{} {}", alpha, texel[3]);
+    }
+  }
+
+  #[test]
+  fn mipmaps_halve_down_to_one_pixel() {
+    let image = Image::new_from_color(10, 4, Color::from_rgba(200, 100, 50, 255));
+    let options = Some(WriterOptions::default().generate_mipmaps(true));
+    let levels = texture_levels(&image, &options);
+    // 10x4, 5x2, 2x1 and 1x1.
+    let sizes: Vec<usize> = levels.iter().map(|level| level.len()).collect();
+    assert_eq!(sizes, vec![160, 40, 8, 4]);
+    assert_eq!(levels[3], vec![200, 100, 50, 255]);
+
+    let options = Some(WriterOptions::default().texture_compression(TextureCompression::Bc3));
+    let levels = texture_levels(&image, &options);
+    assert_eq!(levels.len(), 1);
+    assert_eq!(levels[0].len(), 3 * 16);
+  }
+
+  #[test]
+  fn mipmaps_average_in_linear_light() {
+    // The formats are declared as sRGB, so a black and white checkerboard must average to half the light,
+    // about 188 in sRGB, rather than to the byte average of 128.
+    let mut image = Image::new_from_color(2, 2, Color::from_rgba(0, 0, 0, 255));
+    image.set_pixel(0, 0, (255, 255, 255, 255));
+    image.set_pixel(1, 1, (255, 255, 255, 255));
+    let levels = texture_levels(&image, &Some(WriterOptions::default().generate_mipmaps(true)));
+    assert_eq!(levels[1], vec![188, 188, 188, 255]);
+  }
+}
@@ -1,25 +1,149 @@
-use std::{fs::File, io::BufWriter};
+use std::fs::write;
+use std::io::Cursor;
 
 use crate::Image;
 use crate::fs::mkdirp;
 use crate::fs::path::dirname;
+use crate::fs::quality_search::encode_with_quality_target;
+use crate::fs::writer_options::WriterOptions;
 use image_webp as webp;
 use webp::ColorType::Rgba8;
 
-/// Writes the image data to a WebP file
-pub fn write_webp(file: impl Into<String>, img: &Image) -> Result<(), String> {
+/// Writes the image data to a WebP file.
+/// Returns the quality that was used (100 in lossless mode), which differs from the requested one
+/// when a size or SSIM target is set.
+pub fn write_webp(file: impl Into<String>, img: &Image, options: &Option<WriterOptions>) -> Result<u8, String> {
   let file = file.into();
   let dir = dirname(&file);
   mkdirp(&dir).unwrap_or_else(|_| panic!("Error creating directory {}", &dir));
-  let file = File::create(file).map_err(|e| e.to_string())?;
-  let writer = BufWriter::new(file);
-  let encoder = webp::WebPEncoder::new(writer);
-  let pixels = img.rgba();
-  let (width, height) = img.dimensions();
+  let (bytes, quality) = encode_webp(img, options)?;
+  write(file, bytes).map_err(|e| e.to_string())?;
+  Ok(quality)
+}
+
+/// Encodes the image data as WebP and returns the bytes along with the quality that was used.
+///
+/// The bitstream is always VP8L, there is no VP8 encoder. Unless `webp_lossless` is set, the color and alpha
+/// channels are quantized beforehand (according to `quality` and `webp_alpha_quality`), which lets the entropy coder
+/// produce much smaller files while keeping flat areas and hard edges crisp. Quality therefore only controls the
+/// quantization, and 100 writes the same pixels as lossless mode.
+pub(crate) fn encode_webp(img: &Image, options: &Option<WriterOptions>) -> Result<(Vec<u8>, u8), String> {
+  let alpha_quality = match options {
+    Some(opts) if !opts.webp_lossless => opts.webp_alpha_quality,
+    _ => return Ok((encode_rgba(img.rgba(), img)?, 100)),
+  };
+
+  let encode = |quality: u8| encode_rgba(&quantize(img.rgba(), quality, alpha_quality), img);
+  let decode = |bytes: &[u8]| {
+    let mut decoder = webp::WebPDecoder::new(Cursor::new(bytes)).map_err(|e| format!("{:?}", e))?;
+    let mut pixels = vec![0; decoder.output_buffer_size().unwrap_or(0)];
+    decoder.read_image(&mut pixels).map_err(|e| format!("{:?}", e))?;
+    let channels = if decoder.has_alpha() { 4 } else { 3 };
+    Ok(pixels.chunks_exact(channels).flat_map(|px| [px[0], px[1], px[2]]).collect())
+  };
+  let (width, height) = img.dimensions::<usize>();
+  encode_with_quality_target(options, &img.rgb(), width, height, encode, decode)
+}
 
-  encoder
+/// Encodes RGBA pixels with the dimensions of the given image.
+fn encode_rgba(pixels: &[u8], img: &Image) -> Result<Vec<u8>, String> {
+  let mut bytes = Vec::new();
+  let (width, height) = img.dimensions();
+  webp::WebPEncoder::new(&mut bytes)
     .encode(pixels, width, height, Rgba8)
-    .expect("error encoding webp");
+    .map_err(|e| format!("Failed to encode WebP image: {:?}", e))?;
+  Ok(bytes)
+}
+
+/// Quantizes the color and alpha channels to a step size derived from their quality (100 keeps every level).
+fn quantize(pixels: &[u8], quality: u8, alpha_quality: u8) -> Vec<u8> {
+  let step = |q: u8| 1 + (100 - q.min(100) as u32) * 31 / 100;
+  let color_step = step(quality);
+  let alpha_step = step(alpha_quality);
+  let snap = |v: u8, s: u32| (((v as u32 + s / 2) / s) * s).min(255) as u8;
+
+  let mut out = pixels.to_vec();
+  for px in out.chunks_exact_mut(4) {
+    px[0] = snap(px[0], color_step);
+    px[1] = snap(px[1], color_step);
+    px[2] = snap(px[2], color_step);
+    px[3] = snap(px[3], alpha_step);
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fs::quality_search::ssim;
+
+  fn test_image() -> Image {
+    let mut image = Image::new(64, 64);
+    for y in 0..64u32 {
+      for x in 0..64u32 {
+        let alpha = ((x * 37 + y * 91) ^ (x * y)) as u8;
+        image.set_pixel(x, y, ((x * 4) as u8, (y * 4) as u8, ((x ^ y) * 3) as u8, alpha));
+      }
+    }
+    image
+  }
+
+  fn encode(image: &Image, options: WriterOptions) -> Vec<u8> {
+    encode_webp(image, &Some(options)).unwrap().0
+  }
+
+  fn decode(bytes: &[u8]) -> Vec<u8> {
+    let mut decoder = webp::WebPDecoder::new(Cursor::new(bytes)).unwrap();
+    let mut pixels = vec![0; decoder.output_buffer_size().unwrap()];
+    decoder.read_image(&mut pixels).unwrap();
+    pixels
+  }
+
+  #[test]
+  fn lossless_round_trip_is_bit_identical() {
+    let image = test_image();
+    let bytes = encode(&image, WriterOptions::new(10).webp_lossless(true).webp_alpha_quality(0));
+    assert_eq!(decode(&bytes), image.rgba());
+  }
+
+  #[test]
+  fn quality_applies_by_default() {
+    let image = test_image();
+    let full = encode(&image, WriterOptions::default());
+    assert_eq!(decode(&full), image.rgba());
+    assert!(encode(&image, WriterOptions::new(20)).len() < full.len());
+  }
+
+  #[test]
+  fn alpha_quality_is_independent_of_color_quality() {
+    let image = test_image();
+    let crisp = encode(&image, WriterOptions::new(100).webp_lossless(false).webp_alpha_quality(100));
+    let light_alpha = encode(&image, WriterOptions::new(100).webp_lossless(false).webp_alpha_quality(0));
+    assert!(light_alpha.len() < crisp.len());
+
+    let decoded = decode(&light_alpha);
+    for (original, written) in image.rgba().chunks_exact(4).zip(decoded.chunks_exact(4)) {
+      assert_eq!(original[..3], written[..3]);
+      assert!((original[3] as i32 - written[3] as i32).abs() <= 16);
+    }
+  }
+
+  #[test]
+  fn target_size_lands_near_budget() {
+    let image = test_image();
+    let budget = encode(&image, WriterOptions::new(50).webp_lossless(false)).len();
+    let (bytes, quality) = encode_webp(&image, &Some(WriterOptions::default().target_size(budget))).unwrap();
+    assert!(bytes.len() <= budget);
+    assert!(bytes.len() as f64 >= budget as f64 * 0.9, "{} is not within 10% of {}", bytes.len(), budget);
+    assert!(quality >= 50);
+  }
 
-  Ok(())
+  #[test]
+  fn target_ssim_picks_lowest_quality_that_reaches_it() {
+    let image = test_image();
+    let (bytes, quality) = encode_webp(&image, &Some(WriterOptions::default().target_ssim(0.9))).unwrap();
+    assert!(quality < 100);
+    let decoded: Vec<u8> = decode(&bytes).chunks_exact(4).flat_map(|px| [px[0], px[1], px[2]]).collect();
+    assert!(ssim(&image.rgb(), &decoded, 64, 64) >= 0.9);
+  }
 }
@@ -5,7 +5,7 @@
 /// Anchor points define where an element should be positioned relative to its parent.
 /// For example, `Center` positions the element in the center, while `TopLeft` positions
 /// it at the top-left corner.
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Anchor {
   /// Anchors the element to the top left corner of the parent.
   TopLeft,
@@ -47,7 +47,7 @@ impl Anchor {
   /// let (x, y) = Anchor::Center.calculate_position(1000, 800, 100, 50);
   /// // x = 450, y = 375 (centers the 100x50 element in a 1000x800 parent)
   /// ```
-  pub(crate) fn calculate_position(
+  pub fn calculate_position(
     self, parent_width: i32, parent_height: i32, child_width: i32, child_height: i32,
   ) -> (i32, i32) {
     let x = match self {
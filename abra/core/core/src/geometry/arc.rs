@@ -0,0 +1,215 @@
+//! Circular and elliptical arc commands for paths and areas.
+//!
+//! Arcs are not stored as their own segment kind. Instead they are converted into cubic Bezier
+//! segments (at most 90° each) so flattening, stroking and rasterization keep working unchanged.
+
+use std::f32::consts::PI;
+
+use crate::{Area, Path, PointF};
+
+impl Path {
+  /// Adds a circular arc to the path.
+  /// If the current end point differs from the arc's start point, a straight line connects them first.
+  /// - `p_center`: The center of the circle the arc lies on.
+  /// - `p_radius`: The radius of the arc.
+  /// - `p_start_angle`: The angle in degrees where the arc starts (0° points along +x, 90° along +y).
+  /// - `p_sweep_angle`: The angle in degrees the arc spans; negative values sweep counter-clockwise.
+  pub fn arc_to(
+    &mut self, p_center: impl Into<PointF>, p_radius: f32, p_start_angle: f32, p_sweep_angle: f32,
+  ) -> &mut Self {
+    let center = p_center.into();
+    let start_angle = p_start_angle.to_radians();
+    let start = PointF::new(center.x + p_radius * start_angle.cos(), center.y + p_radius * start_angle.sin());
+    if self.end().distance_to(start) > 1e-4 {
+      self.line_to(start);
+    }
+    self.push_arc(center, (p_radius, p_radius), 0.0, start_angle, p_sweep_angle.to_radians(), None);
+    self
+  }
+
+  /// Adds an SVG-style elliptical arc from the current end point to `p_end`.
+  /// Radii that are too small to reach the end point are scaled up as described by the SVG specification.
+  /// - `p_rx`: The x radius of the ellipse.
+  /// - `p_ry`: The y radius of the ellipse.
+  /// - `p_x_rotation`: The rotation of the ellipse's x axis in degrees.
+  /// - `p_large_arc`: When true the arc spanning more than 180° is chosen.
+  /// - `p_sweep`: When true the arc is drawn in the positive-angle (clockwise on screen) direction.
+  /// - `p_end`: The end point of the arc.
+  pub fn elliptical_arc(
+    &mut self, p_rx: f32, p_ry: f32, p_x_rotation: f32, p_large_arc: bool, p_sweep: bool, p_end: impl Into<PointF>,
+  ) -> &mut Self {
+    let from = self.end();
+    let to = p_end.into();
+    if from.distance_to(to) < 1e-6 {
+      return self;
+    }
+    let mut rx = p_rx.abs();
+    let mut ry = p_ry.abs();
+    if rx < 1e-6 || ry < 1e-6 {
+      return self.line_to(to);
+    }
+
+    // Endpoint to center parameterization (SVG 1.1 implementation notes, F.6.5).
+    let phi = p_x_rotation.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+    let half_dx = (from.x - to.x) / 2.0;
+    let half_dy = (from.y - to.y) / 2.0;
+    let x1 = cos_phi * half_dx + sin_phi * half_dy;
+    let y1 = -sin_phi * half_dx + cos_phi * half_dy;
+
+    // Scale radii up when they cannot span the two points (F.6.6).
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+      let scale = lambda.sqrt();
+      rx *= scale;
+      ry *= scale;
+    }
+
+    let rx_sq = rx * rx;
+    let ry_sq = ry * ry;
+    let numerator = (rx_sq * ry_sq - rx_sq * y1 * y1 - ry_sq * x1 * x1).max(0.0);
+    let denominator = rx_sq * y1 * y1 + ry_sq * x1 * x1;
+    let mut coef = if denominator > 0.0 { (numerator / denominator).sqrt() } else { 0.0 };
+    if p_large_arc == p_sweep {
+      coef = -coef;
+    }
+    let cx1 = coef * rx * y1 / ry;
+    let cy1 = -coef * ry * x1 / rx;
+    let center = PointF::new(
+      cos_phi * cx1 - sin_phi * cy1 + (from.x + to.x) / 2.0,
+      sin_phi * cx1 + cos_phi * cy1 + (from.y + to.y) / 2.0,
+    );
+
+    let start_vec = PointF::new((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let end_vec = PointF::new((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+    let start_angle = start_vec.y.atan2(start_vec.x);
+    let mut sweep = end_vec.y.atan2(end_vec.x) - start_angle;
+    if p_sweep && sweep < 0.0 {
+      sweep += 2.0 * PI;
+    } else if !p_sweep && sweep > 0.0 {
+      sweep -= 2.0 * PI;
+    }
+
+    self.push_arc(center, (rx, ry), phi, start_angle, sweep, Some(to));
+    self
+  }
+
+  /// Appends cubic segments approximating an elliptical arc that starts at the current end point.
+  /// - `p_center`: The center of the ellipse.
+  /// - `p_radii`: The (x, y) radii of the ellipse.
+  /// - `p_phi`: The rotation of the ellipse in radians.
+  /// - `p_start`: The start angle in radians.
+  /// - `p_sweep`: The sweep in radians.
+  /// - `p_end`: An exact end point to snap the last segment onto, avoiding accumulated float error.
+  fn push_arc(
+    &mut self, p_center: PointF, p_radii: (f32, f32), p_phi: f32, p_start: f32, p_sweep: f32, p_end: Option<PointF>,
+  ) {
+    if p_sweep.abs() < 1e-6 {
+      return;
+    }
+    let (sin_phi, cos_phi) = p_phi.sin_cos();
+    // Maps a point on the unit circle onto the rotated ellipse.
+    let map = |p_x: f32, p_y: f32| -> PointF {
+      let x = p_x * p_radii.0;
+      let y = p_y * p_radii.1;
+      PointF::new(p_center.x + cos_phi * x - sin_phi * y, p_center.y + sin_phi * x + cos_phi * y)
+    };
+
+    let count = (p_sweep.abs() / (PI / 2.0) - 1e-4).ceil().max(1.0) as usize;
+    let step = p_sweep / count as f32;
+    // Control point distance for a unit circle arc of `step` radians.
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let mut angle = p_start;
+    for i in 0..count {
+      let next = angle + step;
+      let (sin_a, cos_a) = angle.sin_cos();
+      let (sin_b, cos_b) = next.sin_cos();
+      let to = match p_end {
+        Some(end) if i == count - 1 => end,
+        _ => map(cos_b, sin_b),
+      };
+      self.cubic_to(map(cos_a - k * sin_a, sin_a + k * cos_a), map(cos_b + k * sin_b, sin_b - k * cos_b), to);
+      angle = next;
+    }
+  }
+}
+
+impl Area {
+  /// Adds a circular arc to the area's outline.
+  /// - `p_center`: The center of the circle the arc lies on.
+  /// - `p_radius`: The radius of the arc.
+  /// - `p_start_angle`: The angle in degrees where the arc starts.
+  /// - `p_sweep_angle`: The angle in degrees the arc spans; negative values sweep counter-clockwise.
+  pub fn arc_to(
+    &mut self, p_center: impl Into<PointF>, p_radius: f32, p_start_angle: f32, p_sweep_angle: f32,
+  ) -> &mut Self {
+    self.path.arc_to(p_center, p_radius, p_start_angle, p_sweep_angle);
+    self
+  }
+
+  /// Adds an SVG-style elliptical arc to the area's outline.
+  /// - `p_rx`: The x radius of the ellipse.
+  /// - `p_ry`: The y radius of the ellipse.
+  /// - `p_x_rotation`: The rotation of the ellipse's x axis in degrees.
+  /// - `p_large_arc`: When true the arc spanning more than 180° is chosen.
+  /// - `p_sweep`: When true the arc is drawn in the positive-angle direction.
+  /// - `p_end`: The end point of the arc.
+  pub fn elliptical_arc(
+    &mut self, p_rx: f32, p_ry: f32, p_x_rotation: f32, p_large_arc: bool, p_sweep: bool, p_end: impl Into<PointF>,
+  ) -> &mut Self {
+    self.path.elliptical_arc(p_rx, p_ry, p_x_rotation, p_large_arc, p_sweep, p_end);
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(p_a: PointF, p_b: PointF) {
+    assert!(p_a.distance_to(p_b) < 1e-3, "expected {} to be close to {}", p_a, p_b);
+  }
+
+  #[test]
+  fn quarter_arc_ends_on_circle() {
+    let mut path = Path::new();
+    path.move_to((10.0, 0.0)).arc_to((0.0, 0.0), 10.0, 0.0, 90.0);
+    assert_eq!(path.segments().len(), 1);
+    assert_close(path.end(), PointF::new(0.0, 10.0));
+    // The midpoint of the curve should lie on the circle.
+    let (mid, _) = path.point_at(0.5);
+    assert!((mid.length() - 10.0).abs() < 0.05);
+  }
+
+  #[test]
+  fn four_quarter_arcs_close_a_circle() {
+    let mut path = Path::new();
+    path.move_to((60.0, 50.0));
+    for i in 0..4 {
+      path.arc_to((50.0, 50.0), 10.0, i as f32 * 90.0, 90.0);
+    }
+    assert_eq!(path.segments().len(), 4);
+    assert_close(path.end(), path.start());
+    assert!((path.length() - 2.0 * PI * 10.0).abs() < 0.5);
+  }
+
+  #[test]
+  fn elliptical_arc_half_circle() {
+    let mut path = Path::new();
+    path.move_to((0.0, 0.0)).elliptical_arc(10.0, 10.0, 0.0, false, true, (20.0, 0.0));
+    assert_close(path.end(), PointF::new(20.0, 0.0));
+    // Sweeping in the positive direction from left to right passes above the chord (negative y).
+    let (_, min_y, _, max_y) = path.bounds();
+    assert!((min_y + 10.0).abs() < 0.1);
+    assert!(max_y.abs() < 0.1);
+  }
+
+  #[test]
+  fn elliptical_arc_scales_small_radii() {
+    let mut path = Path::new();
+    path.move_to((0.0, 0.0)).elliptical_arc(1.0, 1.0, 0.0, false, true, (20.0, 0.0));
+    assert_close(path.end(), PointF::new(20.0, 0.0));
+    let (_, min_y, _, _) = path.bounds();
+    assert!((min_y + 10.0).abs() < 0.1);
+  }
+}
@@ -3,7 +3,8 @@ use std::{
   ops::{Add, Sub},
 };
 
-use crate::{AspectRatio, FromF32, Image, Path, Point, PointF, Segment, Size, ViewBox};
+use crate::geometry::boolean::{contour_edges, winding_number};
+use crate::{AspectRatio, FeatherCurve, FillRule, FromF32, Image, Path, Point, PointF, Segment, Size, ViewBox};
 // use crate::{
 //   Image,
 // };
@@ -15,8 +16,12 @@ use crate::{AspectRatio, FromF32, Image, Path, Point, PointF, Segment, Size, Vie
 pub struct Area {
   /// The underlying path outline that defines this closed area.
   pub path: Path,
-  /// The feather amount for the area edges.
+  /// The feather amount for the area edges, or the horizontal amount when `feather_y` is set.
   pub feather: u32,
+  /// The vertical feather amount, when it differs from the horizontal amount.
+  pub feather_y: Option<u32>,
+  /// The shape of the falloff across the feather; `None` falls off linearly.
+  pub feather_curve: Option<FeatherCurve>,
 }
 
 impl Area {
@@ -42,7 +47,10 @@ impl Area {
       .line_to((origin.x + size.width, origin.y))
       .line_to((origin.x + size.width, origin.y + size.height))
       .line_to((origin.x, origin.y + size.height));
-    Area { path, feather: 0 }
+    Area {
+      path,
+      ..Area::default()
+    }
   }
   /// Creates a circular area.
   /// - `p_center`: The center point of the circle.
@@ -74,7 +82,10 @@ impl Area {
       .cubic_to((center.x - ox, center.y + ry), (center.x - rx, center.y + oy), (center.x - rx, center.y))
       .cubic_to((center.x - rx, center.y - oy), (center.x - ox, center.y - ry), (center.x, center.y - ry));
 
-    Area { path, feather: 0 }
+    Area {
+      path,
+      ..Area::default()
+    }
   }
   /// Creates an area from a list of points.
   /// - `p_points`: The list of points defining the area.
@@ -86,41 +97,62 @@ impl Area {
         path.line_to((point[0], point[1]));
       }
     }
-    Area { path, feather: 0 }
+    Area {
+      path,
+      ..Area::default()
+    }
   }
   /// Sets the feather amount for the area edges.
   /// - `p_feather`: The feather radius in pixels.
   pub fn with_feather(mut self, p_feather: u32) -> Self {
     self.feather = p_feather;
+    self.feather_y = None;
     self
   }
-  /// Gets the feather amount for this Area.
-  pub fn feather(&self) -> u32 {
-    self.feather
+  /// Sets separate horizontal and vertical feather amounts for the area edges.
+  /// An amount of zero keeps the edges along that axis hard, e.g. for a band that only fades towards the top and bottom.
+  /// - `p_feather_x`: The horizontal feather radius in pixels.
+  /// - `p_feather_y`: The vertical feather radius in pixels.
+  pub fn with_feather_xy(mut self, p_feather_x: u32, p_feather_y: u32) -> Self {
+    self.feather = p_feather_x;
+    self.feather_y = Some(p_feather_y);
+    self
   }
-  /// Determines if a point is inside the area using the ray-casting algorithm.
+  /// Sets the shape of the falloff across the feather.
+  /// - `p_curve`: The falloff curve.
+  pub fn with_feather_curve(mut self, p_curve: FeatherCurve) -> Self {
+    self.feather_curve = Some(p_curve);
+    self
+  }
+  /// Gets the feather amount for this Area, the larger of the two amounts when they differ.
+  pub fn feather(&self) -> u32 {
+    self.feather.max(self.feather_y.unwrap_or(0))
+  }
+  /// Gets the horizontal and vertical feather amounts for this Area.
+  pub fn feather_xy(&self) -> (u32, u32) {
+    (self.feather, self.feather_y.unwrap_or(self.feather))
+  }
+  /// Gets the shape of the falloff across the feather, linear unless the area sets its own curve.
+  pub fn feather_curve(&self) -> FeatherCurve {
+    self.feather_curve.unwrap_or(FeatherCurve::Linear)
+  }
+  /// Gets the feather falloff at a point inside the area, from 0.0 on the edge to 1.0 one feather width inside.
+  /// - `p_contours`: The flattened contours of the area, which callers reuse across many points.
+  /// - `p_point`: The point to measure.
+  pub fn feather_falloff(&self, p_contours: &[Vec<PointF>], p_point: impl Into<PointF>) -> f32 {
+    let (feather_x, feather_y) = self.feather_xy();
+    crate::geometry::feather_falloff(p_contours, p_point, (feather_x as f32, feather_y as f32), self.feather_curve())
+  }
+  /// Determines if a point is inside the area.
+  /// The windings of every subpath are summed with the non-zero rule, the same rule used to fill the area, so a
+  /// subpath wound against the one around it cuts a hole.
   /// - `p_point`: The point to test.
   pub fn contains(&self, p_point: impl Into<PointF>) -> bool {
-    let point = p_point.into();
-    let pts = self.path.flatten(0.5);
-    let mut inside = false;
-    let n = pts.len();
-    let mut j = n - 1;
-
-    for i in 0..n {
-      let pi = pts[i];
-      let pj = pts[j];
-      if (pi.y > point.y) != (pj.y > point.y)
-        && (point.x < (pj.x - pi.x) * (point.y - pi.y) / (pj.y - pi.y + 0.00001) + pi.x)
-      {
-        inside = !inside;
-      }
-      j = i;
-    }
-
-    inside
+    let edges = contour_edges(&self.path.flatten_contours(0.5));
+    FillRule::NonZero.is_inside(winding_number(&edges, p_point.into()))
   }
   /// Sets the starting point of the area's (move to).
+  /// Calling this after other segments starts a new contour, which can be used to cut holes.
   /// - `p_start`: The starting point.
   pub fn move_to(&mut self, p_start: impl Into<PointF>) -> &mut Self {
     self.path.move_to(p_start);
@@ -165,8 +197,9 @@ impl Area {
   pub fn points(&self) -> Vec<PointF> {
     self.path.points()
   }
-  /// Gets the point at parameter t (0 to 1) along the area's path.
-  pub fn point_at(&self, p_t: f32) -> PointF {
+  /// Gets the point and unit tangent at a normalized arc length (0 to 1) along the area's path.
+  /// - `p_t`: The normalized arc length.
+  pub fn point_at(&self, p_t: f32) -> (PointF, PointF) {
     self.path.point_at(p_t)
   }
   /// Gets the point at parameter t within a specific segment of the area's path.
@@ -180,7 +213,12 @@ impl Area {
   pub fn flatten(&self, p_tolerance: f32) -> Vec<PointF> {
     self.path.flatten(p_tolerance)
   }
-  /// Gets an approximate length of the area's outline path.
+  /// Flattens the area's path into one closed polygon per subpath with the given tolerance.
+  /// - `p_tolerance`: The maximum allowed deviation from the original path.
+  pub fn flatten_contours(&self, p_tolerance: f32) -> Vec<Vec<PointF>> {
+    self.path.flatten_contours(p_tolerance)
+  }
+  /// Gets the length of the area's outline path.
   pub fn length(&self) -> f32 {
     self.path.length()
   }
@@ -260,6 +298,8 @@ impl Default for Area {
     Area {
       path: Path::default(),
       feather: 0,
+      feather_y: None,
+      feather_curve: None,
     }
   }
 }
@@ -272,7 +312,10 @@ impl Into<Path> for Area {
 
 impl From<Path> for Area {
   fn from(path: Path) -> Self {
-    Area { path, feather: 0 }
+    Area {
+      path,
+      ..Area::default()
+    }
   }
 }
 
@@ -292,11 +335,8 @@ impl From<&Area> for Area {
 impl Sub<Area> for Area {
   type Output = Area;
 
-  fn sub(self, _rhs: Area) -> Self::Output {
-    Area {
-      path: self.path.clone(),
-      feather: self.feather,
-    }
+  fn sub(self, rhs: Area) -> Self::Output {
+    self.difference(&rhs)
   }
 }
 
@@ -309,6 +349,8 @@ impl<T: Into<f32>> Sub<T> for Area {
     Area {
       path: self.path.clone(),
       feather: self.feather,
+      feather_y: self.feather_y,
+      feather_curve: self.feather_curve,
     }
   }
 }
@@ -316,11 +358,8 @@ impl<T: Into<f32>> Sub<T> for Area {
 impl Add<Area> for Area {
   type Output = Area;
 
-  fn add(self, _rhs: Area) -> Self::Output {
-    Area {
-      path: self.path.clone(),
-      feather: self.feather,
-    }
+  fn add(self, rhs: Area) -> Self::Output {
+    self.union(&rhs)
   }
 }
 
@@ -331,6 +370,39 @@ impl Add<f32> for Area {
     Area {
       path: self.path.clone(),
       feather: self.feather,
+      feather_y: self.feather_y,
+      feather_curve: self.feather_curve,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A 100x100 square with a 20x20 square inside it, wound the same way or against it.
+  fn nested_squares(p_hole: bool) -> Area {
+    let mut area = Area::rect((0.0, 0.0), (100.0, 100.0));
+    let inner = [(40.0, 40.0), (60.0, 40.0), (60.0, 60.0), (40.0, 60.0)];
+    let order: Vec<(f32, f32)> = if p_hole {
+      inner.into_iter().rev().collect()
+    } else {
+      inner.to_vec()
+    };
+    area.move_to(order[0]);
+    for point in &order[1..] {
+      area.line_to(*point);
     }
+    area
+  }
+
+  #[test]
+  fn contains_follows_the_nonzero_fill_rule() {
+    let hole = nested_squares(true);
+    assert!(hole.contains((10.0, 10.0)));
+    assert!(!hole.contains((50.0, 50.0)));
+    assert!(!hole.contains((150.0, 50.0)));
+    // Wound the same way, the inner square adds to the fill instead of cutting it, as when the area is drawn.
+    assert!(nested_squares(false).contains((50.0, 50.0)));
   }
 }
@@ -0,0 +1,374 @@
+//! Boolean operations (union, intersection, difference, xor) between paths and areas.
+//!
+//! Both operands are flattened into polygons and every edge is split wherever it crosses or touches
+//! another edge. Each resulting piece is then classified by sampling the fill on both of its sides:
+//! pieces that separate "inside the result" from "outside the result" are kept and oriented so the
+//! inside is always on the same side. The kept pieces are finally chained back into closed contours.
+//! Because every output contour is consistently oriented, the result fills the same way under both
+//! the nonzero and even-odd rules, and holes come out as contours running in the opposite direction.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{Area, Path, PointF};
+
+/// The tolerance used to flatten curves before combining paths.
+const FLATTEN_TOLERANCE: f32 = 0.25;
+/// The distance from an edge at which the fill on each side is sampled.
+const SAMPLE_OFFSET: f32 = 0.01;
+/// Edge parameters closer than this to an end point are treated as the end point itself.
+const PARAM_EPSILON: f32 = 1e-5;
+/// The grid used to merge end points that only differ by floating point error.
+const SNAP_SCALE: f32 = 256.0;
+
+/// The rule used to decide whether a point is inside a path with overlapping or nested contours.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FillRule {
+  /// A point is inside when the contours wind around it a non-zero number of times.
+  #[default]
+  NonZero,
+  /// A point is inside when a ray from it crosses the contours an odd number of times.
+  EvenOdd,
+}
+
+impl FillRule {
+  /// Returns true if a point with the given winding number is inside under this rule.
+  /// - `p_winding`: The winding number of the point.
+  pub fn is_inside(&self, p_winding: i32) -> bool {
+    match self {
+      FillRule::NonZero => p_winding != 0,
+      FillRule::EvenOdd => p_winding % 2 != 0,
+    }
+  }
+}
+
+/// The boolean operation used to combine two paths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BooleanOp {
+  /// Keeps everything covered by either path.
+  Union,
+  /// Keeps only what is covered by both paths.
+  Intersection,
+  /// Keeps what is covered by the first path but not the second.
+  Difference,
+  /// Keeps what is covered by exactly one of the paths.
+  Xor,
+}
+
+impl BooleanOp {
+  /// Combines the inside/outside state of both operands.
+  fn apply(&self, p_in_a: bool, p_in_b: bool) -> bool {
+    match self {
+      BooleanOp::Union => p_in_a || p_in_b,
+      BooleanOp::Intersection => p_in_a && p_in_b,
+      BooleanOp::Difference => p_in_a && !p_in_b,
+      BooleanOp::Xor => p_in_a != p_in_b,
+    }
+  }
+}
+
+impl Path {
+  /// Combines this path with another path and returns the outline of the result.
+  /// Both paths are treated as closed shapes. Curves are flattened, so the result is made of line segments only.
+  /// - `p_other`: The path to combine with.
+  /// - `p_op`: The boolean operation to apply.
+  /// - `p_fill_rule`: The rule used to decide what is inside each operand.
+  pub fn boolean(&self, p_other: &Path, p_op: BooleanOp, p_fill_rule: FillRule) -> Path {
+    let edges_a = contour_edges(&self.flatten_contours(FLATTEN_TOLERANCE));
+    let edges_b = contour_edges(&p_other.flatten_contours(FLATTEN_TOLERANCE));
+    let all_edges: Vec<(PointF, PointF)> = edges_a.iter().chain(edges_b.iter()).copied().collect();
+
+    let inside = |p_point: PointF| {
+      p_op.apply(
+        p_fill_rule.is_inside(winding_number(&edges_a, p_point)),
+        p_fill_rule.is_inside(winding_number(&edges_b, p_point)),
+      )
+    };
+
+    let mut seen = HashSet::new();
+    let mut kept = Vec::new();
+    for (from, to) in split_edges(&all_edges) {
+      let mid = from.lerp(to, 0.5);
+      let normal = (to - from).normalize().perpendicular() * SAMPLE_OFFSET;
+      let inside_left = inside(mid + normal);
+      let inside_right = inside(mid - normal);
+      if inside_left == inside_right {
+        continue;
+      }
+      // Orient every kept edge so the result's inside lies on its right-hand side.
+      let edge = if inside_right { (from, to) } else { (to, from) };
+      // Coincident edges from both operands classify identically; keep a single copy.
+      if seen.insert((snap(edge.0), snap(edge.1))) {
+        kept.push(edge);
+      }
+    }
+
+    chain_edges(&kept)
+  }
+
+  /// Returns the union of this path and another path using the nonzero fill rule.
+  /// - `p_other`: The path to merge with.
+  pub fn union(&self, p_other: &Path) -> Path {
+    self.boolean(p_other, BooleanOp::Union, FillRule::NonZero)
+  }
+
+  /// Returns the intersection of this path and another path using the nonzero fill rule.
+  /// - `p_other`: The path to intersect with.
+  pub fn intersection(&self, p_other: &Path) -> Path {
+    self.boolean(p_other, BooleanOp::Intersection, FillRule::NonZero)
+  }
+
+  /// Returns this path with another path subtracted from it using the nonzero fill rule.
+  /// - `p_other`: The path to subtract.
+  pub fn difference(&self, p_other: &Path) -> Path {
+    self.boolean(p_other, BooleanOp::Difference, FillRule::NonZero)
+  }
+
+  /// Returns the regions covered by exactly one of the two paths using the nonzero fill rule.
+  /// - `p_other`: The path to combine with.
+  pub fn xor(&self, p_other: &Path) -> Path {
+    self.boolean(p_other, BooleanOp::Xor, FillRule::NonZero)
+  }
+}
+
+impl Area {
+  /// Combines this area with another area. The feather of this area is kept.
+  /// - `p_other`: The area to combine with.
+  /// - `p_op`: The boolean operation to apply.
+  /// - `p_fill_rule`: The rule used to decide what is inside each operand.
+  pub fn boolean(&self, p_other: &Area, p_op: BooleanOp, p_fill_rule: FillRule) -> Area {
+    Area {
+      path: self.path.boolean(&p_other.path, p_op, p_fill_rule),
+      feather: self.feather,
+      feather_y: self.feather_y,
+      feather_curve: self.feather_curve,
+    }
+  }
+
+  /// Returns the union of this area and another area.
+  /// - `p_other`: The area to merge with.
+  pub fn union(&self, p_other: &Area) -> Area {
+    self.boolean(p_other, BooleanOp::Union, FillRule::NonZero)
+  }
+
+  /// Returns the intersection of this area and another area.
+  /// - `p_other`: The area to intersect with.
+  pub fn intersection(&self, p_other: &Area) -> Area {
+    self.boolean(p_other, BooleanOp::Intersection, FillRule::NonZero)
+  }
+
+  /// Returns this area with another area subtracted from it.
+  /// - `p_other`: The area to subtract.
+  pub fn difference(&self, p_other: &Area) -> Area {
+    self.boolean(p_other, BooleanOp::Difference, FillRule::NonZero)
+  }
+
+  /// Returns the regions covered by exactly one of the two areas.
+  /// - `p_other`: The area to combine with.
+  pub fn xor(&self, p_other: &Area) -> Area {
+    self.boolean(p_other, BooleanOp::Xor, FillRule::NonZero)
+  }
+}
+
+/// Converts flattened contours into closed edge lists, skipping zero-length edges.
+pub(crate) fn contour_edges(p_contours: &[Vec<PointF>]) -> Vec<(PointF, PointF)> {
+  let mut edges = Vec::new();
+  for contour in p_contours {
+    let n = contour.len();
+    if n < 3 {
+      continue;
+    }
+    for i in 0..n {
+      let from = contour[i];
+      let to = contour[(i + 1) % n];
+      if from.distance_to(to) > PARAM_EPSILON {
+        edges.push((from, to));
+      }
+    }
+  }
+  edges
+}
+
+/// Computes the nonzero winding number of a point against a list of closed edges.
+pub(crate) fn winding_number(p_edges: &[(PointF, PointF)], p_point: PointF) -> i32 {
+  let mut winding = 0;
+  for &(a, b) in p_edges {
+    let side = (b - a).cross(p_point - a);
+    if a.y <= p_point.y {
+      if b.y > p_point.y && side > 0.0 {
+        winding += 1;
+      }
+    } else if b.y <= p_point.y && side < 0.0 {
+      winding -= 1;
+    }
+  }
+  winding
+}
+
+/// Splits every edge at the points where it crosses or touches any other edge.
+fn split_edges(p_edges: &[(PointF, PointF)]) -> Vec<(PointF, PointF)> {
+  let mut pieces = Vec::new();
+  for (i, &(a, b)) in p_edges.iter().enumerate() {
+    let dir = b - a;
+    let len_sq = dir.length_squared();
+    let mut params = vec![0.0, 1.0];
+
+    for (j, &(c, d)) in p_edges.iter().enumerate() {
+      if i == j {
+        continue;
+      }
+      let other = d - c;
+      let denom = dir.cross(other);
+      let offset = c - a;
+      if denom.abs() > 1e-9 * len_sq.max(1.0) {
+        let t = offset.cross(other) / denom;
+        let u = offset.cross(dir) / denom;
+        if (-PARAM_EPSILON..=1.0 + PARAM_EPSILON).contains(&u) {
+          params.push(t);
+        }
+      } else if offset.cross(dir).abs() <= 1e-4 * len_sq.sqrt() {
+        // Collinear edges: split at the other edge's end points so overlaps become identical pieces.
+        params.push(offset.dot(dir) / len_sq);
+        params.push((d - a).dot(dir) / len_sq);
+      }
+    }
+
+    params.retain(|t| (0.0..=1.0).contains(t));
+    params.sort_by(|x, y| x.partial_cmp(y).unwrap());
+    params.dedup_by(|x, y| (*x - *y).abs() < PARAM_EPSILON);
+    for pair in params.windows(2) {
+      let from = a.lerp(b, pair[0]);
+      let to = if pair[1] >= 1.0 { b } else { a.lerp(b, pair[1]) };
+      if snap(from) != snap(to) {
+        pieces.push((from, to));
+      }
+    }
+  }
+  pieces
+}
+
+/// Chains oriented edges into closed contours.
+fn chain_edges(p_edges: &[(PointF, PointF)]) -> Path {
+  let mut outgoing: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+  for (i, edge) in p_edges.iter().enumerate() {
+    outgoing.entry(snap(edge.0)).or_default().push(i);
+  }
+
+  let mut used = vec![false; p_edges.len()];
+  let mut path = Path::new();
+  for first in 0..p_edges.len() {
+    if used[first] {
+      continue;
+    }
+    let start = snap(p_edges[first].0);
+    let mut contour = vec![p_edges[first].0];
+    let mut current = first;
+    loop {
+      used[current] = true;
+      let end = snap(p_edges[current].1);
+      if end == start {
+        break;
+      }
+      contour.push(p_edges[current].1);
+      let next = outgoing.get(&end).and_then(|candidates| candidates.iter().copied().find(|&i| !used[i]));
+      match next {
+        Some(next) => current = next,
+        None => break,
+      }
+    }
+
+    let contour = remove_collinear(contour);
+    if contour.len() < 3 {
+      continue;
+    }
+    path.move_to(contour[0]);
+    for point in &contour[1..] {
+      path.line_to(*point);
+    }
+  }
+  path
+}
+
+/// Removes points that lie on the straight line between their neighbours.
+fn remove_collinear(p_contour: Vec<PointF>) -> Vec<PointF> {
+  let n = p_contour.len();
+  if n < 3 {
+    return p_contour;
+  }
+  (0..n)
+    .filter(|&i| {
+      let prev = p_contour[(i + n - 1) % n];
+      let point = p_contour[i];
+      let next = p_contour[(i + 1) % n];
+      let a = point - prev;
+      let b = next - point;
+      a.cross(b).abs() > 1e-4 * a.length().max(b.length()) || a.dot(b) < 0.0
+    })
+    .map(|i| p_contour[i])
+    .collect()
+}
+
+/// Snaps a point onto a fine grid so nearly identical points compare equal.
+fn snap(p_point: PointF) -> (i64, i64) {
+  ((p_point.x * SNAP_SCALE).round() as i64, (p_point.y * SNAP_SCALE).round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rect(p_x: f32, p_y: f32, p_w: f32, p_h: f32) -> Path {
+    Area::rect((p_x, p_y), (p_w, p_h)).path
+  }
+
+  fn contains(p_path: &Path, p_rule: FillRule, p_point: (f32, f32)) -> bool {
+    let edges = contour_edges(&p_path.flatten_contours(FLATTEN_TOLERANCE));
+    p_rule.is_inside(winding_number(&edges, PointF::new(p_point.0, p_point.1)))
+  }
+
+  fn assert_fills(p_path: &Path, p_inside: &[(f32, f32)], p_outside: &[(f32, f32)]) {
+    for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+      for point in p_inside {
+        assert!(contains(p_path, rule, *point), "{:?} should be inside under {:?}", point, rule);
+      }
+      for point in p_outside {
+        assert!(!contains(p_path, rule, *point), "{:?} should be outside under {:?}", point, rule);
+      }
+    }
+  }
+
+  #[test]
+  fn rect_minus_circle_leaves_hole() {
+    let result = rect(0.0, 0.0, 100.0, 100.0).difference(&Area::circle((50.0, 50.0), 20.0).path);
+    assert_eq!(result.flatten_contours(FLATTEN_TOLERANCE).len(), 2);
+    assert_fills(&result, &[(5.0, 5.0), (95.0, 50.0), (50.0, 25.0)], &[(50.0, 50.0), (60.0, 55.0), (150.0, 50.0)]);
+  }
+
+  #[test]
+  fn union_of_overlapping_rects() {
+    let result = rect(0.0, 0.0, 20.0, 20.0).union(&rect(10.0, 10.0, 20.0, 20.0));
+    // The L-shaped outline has eight corners and no interior edges.
+    assert_eq!(result.points().len(), 8);
+    assert_fills(&result, &[(5.0, 5.0), (15.0, 15.0), (25.0, 25.0)], &[(25.0, 5.0), (5.0, 25.0)]);
+  }
+
+  #[test]
+  fn intersection_and_xor_of_overlapping_rects() {
+    let a = rect(0.0, 0.0, 20.0, 20.0);
+    let b = rect(10.0, 10.0, 20.0, 20.0);
+    let (min_x, min_y, max_x, max_y) = a.intersection(&b).bounds();
+    assert_eq!((min_x, min_y, max_x, max_y), (10.0, 10.0, 20.0, 20.0));
+    assert_fills(&a.xor(&b), &[(5.0, 5.0), (25.0, 25.0)], &[(15.0, 15.0), (25.0, 5.0)]);
+  }
+
+  #[test]
+  fn even_odd_operand_keeps_its_hole() {
+    // Two same-direction squares: a hole under even-odd but solid under nonzero.
+    let mut ring = rect(0.0, 0.0, 40.0, 40.0);
+    ring.append(&rect(10.0, 10.0, 20.0, 20.0));
+    let bar = rect(0.0, 15.0, 40.0, 10.0);
+    let even_odd = ring.boolean(&bar, BooleanOp::Union, FillRule::EvenOdd);
+    assert_fills(&even_odd, &[(5.0, 5.0), (20.0, 20.0)], &[(20.0, 12.0), (20.0, 28.0)]);
+    let nonzero = ring.boolean(&bar, BooleanOp::Union, FillRule::NonZero);
+    assert_fills(&nonzero, &[(20.0, 12.0), (20.0, 28.0)], &[]);
+  }
+}
@@ -0,0 +1,111 @@
+//! Falloff of feathered area edges.
+
+use crate::PointF;
+
+/// The shape of the falloff across a feathered edge, from the edge of an area to one feather width inside it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatherCurve {
+  /// The strength rises evenly, which leaves a visible crease where the feather meets the solid interior.
+  Linear,
+  /// The strength eases in and out of the feather, blending into the interior without a visible edge.
+  Smoothstep,
+  /// The strength rises steeply and flattens out well before the interior, like the edge of a gaussian blur.
+  Gaussian,
+}
+
+impl FeatherCurve {
+  /// Maps a position across the feather to a strength.
+  /// - `p_t`: The position across the feather, from 0.0 on the edge to 1.0 one feather width inside.
+  pub fn apply(&self, p_t: f32) -> f32 {
+    let t = p_t.clamp(0.0, 1.0);
+    match self {
+      FeatherCurve::Linear => t,
+      FeatherCurve::Smoothstep => t * t * (3.0 - 2.0 * t),
+      FeatherCurve::Gaussian => {
+        // A sigma of a third of the feather, scaled so the curve still ends at 1.0.
+        let falloff = |t: f32| 1.0 - (-4.5 * t * t).exp();
+        falloff(t) / falloff(1.0)
+      }
+    }
+  }
+}
+
+/// Gets the strength of a feathered edge at a point inside an area, from 0.0 on the edge to 1.0 once the point is a
+/// full feather width away from every edge.
+///
+/// The horizontal and vertical feather widths may differ, so the distance to the edges is measured with each axis
+/// scaled by its own width. A width of zero keeps the edges along that axis hard, e.g. a horizontal band with a
+/// vertical feather only fades out towards the top and bottom.
+/// - `p_contours`: The flattened contours of the area; each contour is treated as closed.
+/// - `p_point`: The point to measure, which is expected to be inside the area.
+/// - `p_feather`: The horizontal and vertical feather widths in pixels.
+/// - `p_curve`: The shape of the falloff.
+pub fn feather_falloff(
+  p_contours: &[Vec<PointF>], p_point: impl Into<PointF>, p_feather: (f32, f32), p_curve: FeatherCurve,
+) -> f32 {
+  let (feather_x, feather_y) = p_feather;
+  if feather_x <= 0.0 && feather_y <= 0.0 {
+    return 1.0;
+  }
+  // A tiny width stands in for zero so distances along that axis are immediately past the feather.
+  let scale = PointF::new(1.0 / feather_x.max(1e-3), 1.0 / feather_y.max(1e-3));
+  let point = p_point.into();
+  let query = PointF::new(point.x * scale.x, point.y * scale.y);
+  let edges = p_contours.iter().filter(|c| c.len() >= 2).flat_map(|contour| {
+    contour
+      .iter()
+      .zip(contour.iter().cycle().skip(1))
+      .map(|(p1, p2)| (*p1, *p2))
+  });
+  let mut min_distance = f32::MAX;
+  for (p1, p2) in edges {
+    let p1 = PointF::new(p1.x * scale.x, p1.y * scale.y);
+    let p2 = PointF::new(p2.x * scale.x, p2.y * scale.y);
+    let seg_vec = PointF::new(p2.x - p1.x, p2.y - p1.y);
+    let seg_len_sq = seg_vec.length_squared();
+    if seg_len_sq == 0.0 {
+      continue;
+    }
+    let query_vec = PointF::new(query.x - p1.x, query.y - p1.y);
+    let t = (query_vec.dot(seg_vec) / seg_len_sq).clamp(0.0, 1.0);
+    min_distance = min_distance.min(query.distance_to(p1.lerp(p2, t)));
+  }
+  if min_distance == f32::MAX {
+    return 1.0;
+  }
+  p_curve.apply(min_distance)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn square() -> Vec<Vec<PointF>> {
+    vec![vec![
+      PointF::new(0.0, 0.0),
+      PointF::new(20.0, 0.0),
+      PointF::new(20.0, 20.0),
+      PointF::new(0.0, 20.0),
+    ]]
+  }
+
+  #[test]
+  fn directional_feather_keeps_hard_sides() {
+    let contours = square();
+    // Soft towards the top, hard towards the left.
+    let near_top = feather_falloff(&contours, (10.0, 2.0), (0.0, 8.0), FeatherCurve::Linear);
+    let near_left = feather_falloff(&contours, (2.0, 10.0), (0.0, 8.0), FeatherCurve::Linear);
+    assert!((near_top - 0.25).abs() < 1e-4, "{}", near_top);
+    assert_eq!(near_left, 1.0);
+  }
+
+  #[test]
+  fn curves_span_the_feather() {
+    for curve in [FeatherCurve::Linear, FeatherCurve::Smoothstep, FeatherCurve::Gaussian] {
+      assert_eq!(curve.apply(0.0), 0.0);
+      assert!((curve.apply(1.0) - 1.0).abs() < 1e-6);
+      assert!(curve.apply(0.25) < curve.apply(0.75));
+    }
+    assert!(FeatherCurve::Gaussian.apply(0.5) > FeatherCurve::Linear.apply(0.5));
+  }
+}
@@ -0,0 +1,137 @@
+//! Arc-length measurement of paths.
+//!
+//! Curves are flattened once and a cumulative length table is built over the resulting polyline,
+//! so sampling by normalized distance is a binary search rather than a walk over the whole path.
+
+use crate::{Path, PointF};
+
+/// The tolerance used to flatten curves before measuring them.
+const MEASURE_TOLERANCE: f32 = 0.1;
+
+/// A precomputed arc-length table for a path.
+/// Build one when sampling the same path many times (e.g. placing brush dabs or animating along a path).
+///
+/// ```ignore
+/// let measure = PathMeasure::new(&path);
+/// let (point, tangent) = measure.point_at(0.5);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PathMeasure {
+  /// The start point of the path, returned when the path has no length.
+  start: PointF,
+  /// The straight pieces of the flattened path as (from, to).
+  pieces: Vec<(PointF, PointF)>,
+  /// The distance along the path at the end of each piece.
+  cumulative: Vec<f32>,
+}
+
+impl PathMeasure {
+  /// Builds the length table for a path.
+  /// Jumps between subpaths are not counted as length.
+  /// - `p_path`: The path to measure.
+  pub fn new(p_path: &Path) -> PathMeasure {
+    let mut pieces = Vec::new();
+    let mut cumulative = Vec::new();
+    let mut total = 0.0;
+    for contour in p_path.flatten_contours(MEASURE_TOLERANCE) {
+      for pair in contour.windows(2) {
+        let length = pair[0].distance_to(pair[1]);
+        if length <= 0.0 {
+          continue;
+        }
+        total += length;
+        pieces.push((pair[0], pair[1]));
+        cumulative.push(total);
+      }
+    }
+    PathMeasure {
+      start: p_path.start(),
+      pieces,
+      cumulative,
+    }
+  }
+
+  /// Returns the total length of the path.
+  pub fn length(&self) -> f32 {
+    self.cumulative.last().copied().unwrap_or(0.0)
+  }
+
+  /// Returns the point and unit tangent at a normalized distance along the path.
+  /// The tangent is zero when the path has no length.
+  /// - `p_t`: The normalized arc length (0 is the start, 1 is the end).
+  pub fn point_at(&self, p_t: f32) -> (PointF, PointF) {
+    self.point_at_distance(p_t.clamp(0.0, 1.0) * self.length())
+  }
+
+  /// Returns the point and unit tangent at an absolute distance along the path.
+  /// - `p_distance`: The distance from the start of the path in pixels.
+  pub fn point_at_distance(&self, p_distance: f32) -> (PointF, PointF) {
+    if self.pieces.is_empty() {
+      return (self.start, PointF::zero());
+    }
+    let distance = p_distance.clamp(0.0, self.length());
+    let index = self.cumulative.partition_point(|&d| d < distance).min(self.pieces.len() - 1);
+    let (from, to) = self.pieces[index];
+    let piece_start = if index == 0 { 0.0 } else { self.cumulative[index - 1] };
+    let piece_length = self.cumulative[index] - piece_start;
+    let t = ((distance - piece_start) / piece_length).clamp(0.0, 1.0);
+    (from.lerp(to, t), (to - from).normalize())
+  }
+}
+
+impl Path {
+  /// Returns the point and unit tangent at a normalized arc length along the path.
+  /// Equal steps in `p_t` cover equal distances, regardless of how the segments are laid out.
+  /// Use a `PathMeasure` when sampling the same path many times.
+  /// - `p_t`: The normalized arc length (0 is the start, 1 is the end).
+  pub fn point_at(&self, p_t: f32) -> (PointF, PointF) {
+    PathMeasure::new(self).point_at(p_t)
+  }
+
+  /// Returns the length of the path. Jumps between subpaths are not counted.
+  pub fn length(&self) -> f32 {
+    PathMeasure::new(self).length()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn straight_line_length_and_midpoint() {
+    let path = Path::line((0.0, 0.0), (100.0, 0.0));
+    assert_eq!(path.length(), 100.0);
+    let (point, tangent) = path.point_at(0.5);
+    assert_eq!(point, PointF::new(50.0, 0.0));
+    assert_eq!(tangent, PointF::new(1.0, 0.0));
+  }
+
+  #[test]
+  fn samples_by_distance_not_segment() {
+    // A short first segment and a long second one: t = 0.5 must land on the second segment.
+    let mut path = Path::new();
+    path.move_to((0.0, 0.0)).line_to((10.0, 0.0)).line_to((10.0, 90.0));
+    let measure = PathMeasure::new(&path);
+    assert_eq!(measure.length(), 100.0);
+    let (point, tangent) = measure.point_at(0.5);
+    assert_eq!(point, PointF::new(10.0, 40.0));
+    assert_eq!(tangent, PointF::new(0.0, 1.0));
+    assert_eq!(measure.point_at_distance(5.0).0, PointF::new(5.0, 0.0));
+  }
+
+  #[test]
+  fn subpath_jumps_are_not_measured() {
+    let mut path = Path::line((0.0, 0.0), (10.0, 0.0));
+    path.append(&Path::line((50.0, 50.0), (50.0, 60.0)));
+    assert_eq!(path.length(), 20.0);
+    assert_eq!(path.point_at(0.75).0, PointF::new(50.0, 55.0));
+  }
+
+  #[test]
+  fn empty_path_has_no_tangent() {
+    let path = Path::new();
+    assert_eq!(path.length(), 0.0);
+    assert_eq!(path.point_at(0.5), (PointF::zero(), PointF::zero()));
+  }
+}
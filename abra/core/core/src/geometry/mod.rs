@@ -1,21 +1,37 @@
 //! Geometry module
 
+mod anchor;
+mod arc;
 mod area;
+mod boolean;
+mod feather;
 mod line;
+mod measure;
 mod path;
 mod point;
 mod pointf;
+mod rect;
+mod sdf;
 mod shapes;
 mod size;
 mod stroke;
+#[cfg(feature = "svg")]
+mod svg_path;
 mod viewbox;
 
+pub use anchor::Anchor;
 pub use area::Area;
+pub use boolean::{BooleanOp, FillRule};
+pub use feather::{FeatherCurve, feather_falloff};
 pub use line::{bresenham, bresenham_from_points};
+pub use measure::PathMeasure;
 pub use path::{Path, Segment};
 pub use point::Point;
 pub use pointf::PointF;
+pub use rect::Rect;
 pub use shapes::*;
 pub use size::Size;
 pub use stroke::{LineCap, LineJoin};
-pub use viewbox::{Alignment, AspectRatio, PreserveAspectRatio, ViewBox};
+#[cfg(feature = "svg")]
+pub use svg_path::PathError;
+pub use viewbox::{Align, Alignment, AspectRatio, PreserveAspectRatio, ViewBox};
@@ -4,11 +4,17 @@ use crate::Size;
 
 use super::point::Point;
 use super::pointf::PointF;
+use super::measure::PathMeasure;
 use super::viewbox::{AspectRatio, ViewBox};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 /// A segment in a path.
 pub enum Segment {
+  /// Starts a new subpath at a point without drawing anything.
+  Move {
+    /// The starting point of the new subpath.
+    to: PointF,
+  },
   /// A straight line segment to a point.
   Line {
     /// The endpoint of the line segment.
@@ -36,6 +42,7 @@ pub enum Segment {
 /// A path represents a geometric shape made of lines and curves.
 /// Paths are geometric utilities that can be used for drawing, following, effects, and more.
 /// A path is not a closed shape. Use an Area for closed shapes.
+/// A path may hold several subpaths; every `move_to` after the first segment starts a new one.
 pub struct Path {
   /// The starting point of the path.
   start: PointF,
@@ -55,8 +62,13 @@ impl Path {
     path
   }
   /// Sets the starting point of the path (move to).
+  /// If the path already has segments, a new subpath is started at the point instead.
   pub fn move_to(&mut self, p_start: impl Into<PointF>) -> &mut Self {
-    self.start = p_start.into();
+    if self.segments.is_empty() {
+      self.start = p_start.into();
+    } else {
+      self.segments.push(Segment::Move { to: p_start.into() });
+    }
     self
   }
 
@@ -94,11 +106,7 @@ impl Path {
   /// Gets the ending point of the path.
   pub fn end(&self) -> PointF {
     if let Some(last_segment) = self.segments.last() {
-      match last_segment {
-        Segment::Line { to } => *to,
-        Segment::Quadratic { to, .. } => *to,
-        Segment::Cubic { to, .. } => *to,
-      }
+      last_segment.end()
     } else {
       self.start
     }
@@ -113,35 +121,47 @@ impl Path {
   pub fn points(&self) -> Vec<PointF> {
     let mut pts = vec![self.start];
     for segment in &self.segments {
-      match segment {
-        Segment::Line { to } => pts.push(*to),
-        Segment::Quadratic { to, .. } => pts.push(*to),
-        Segment::Cubic { to, .. } => pts.push(*to),
-      }
+      pts.push(segment.end());
     }
     pts
   }
 
-  /// Returns the point at parameter t (0 to 1) along the entire path.
-  /// Uses uniform parametric distribution (not arc-length).
-  pub fn point_at(&self, p_t: f32) -> PointF {
-    if self.segments.is_empty() {
-      return self.start;
-    }
-
-    let clamped_t = p_t.clamp(0.0, 1.0);
-    let num_segments = self.segments.len() as f32;
-    let segment_index = (clamped_t * num_segments).floor() as usize;
-    let segment_index = segment_index.min(self.segments.len() - 1);
-    let local_t = (clamped_t * num_segments) - segment_index as f32;
+  /// Returns true when the path contains more than one subpath.
+  pub fn has_subpaths(&self) -> bool {
+    self.segments.iter().any(|s| matches!(s, Segment::Move { .. }))
+  }
 
-    let prev_point = if segment_index == 0 {
-      self.start
-    } else {
-      self.point_at_segment(segment_index - 1, 1.0)
+  /// Splits the path into one path per subpath.
+  pub fn subpaths(&self) -> Vec<Path> {
+    let mut paths = Vec::new();
+    let mut current = Path {
+      start: self.start,
+      segments: Vec::new(),
     };
+    for segment in &self.segments {
+      if let Segment::Move { to } = segment {
+        paths.push(std::mem::replace(
+          &mut current,
+          Path {
+            start: *to,
+            segments: Vec::new(),
+          },
+        ));
+      } else {
+        current.segments.push(*segment);
+      }
+    }
+    paths.push(current);
+    paths
+  }
 
-    eval_segment(prev_point, &self.segments[segment_index], local_t)
+  /// Appends another path to this one as a new subpath.
+  /// If this path is empty the other path's start point becomes this path's start point.
+  /// - `p_other`: The path to append.
+  pub fn append(&mut self, p_other: &Path) -> &mut Self {
+    self.move_to(p_other.start);
+    self.segments.extend_from_slice(&p_other.segments);
+    self
   }
 
   /// Returns the point at parameter t within a specific segment.
@@ -161,48 +181,44 @@ impl Path {
 
   /// Flattens the path into a polyline (list of points) with the given tolerance.
   /// Tolerance determines how closely the polyline approximates curves.
+  /// Subpaths are joined end to start; use `flatten_contours` to keep them apart.
   pub fn flatten(&self, p_tolerance: f32) -> Vec<PointF> {
-    let mut result = vec![self.start];
+    self.flatten_contours(p_tolerance).concat()
+  }
+
+  /// Flattens the path into one polyline per subpath with the given tolerance.
+  /// Unlike `flatten`, subpaths are not joined, so each contour can be filled or stroked on its own.
+  pub fn flatten_contours(&self, p_tolerance: f32) -> Vec<Vec<PointF>> {
+    let mut contours = Vec::new();
+    let mut contour = vec![self.start];
     let mut current = self.start;
 
     for segment in &self.segments {
       match segment {
-        Segment::Line { to } => {
-          result.push(*to);
-          current = *to;
+        Segment::Move { to } => {
+          contours.push(std::mem::replace(&mut contour, vec![*to]));
         }
+        Segment::Line { to } => contour.push(*to),
         Segment::Quadratic { ctrl, to } => {
           let subdivisions = calculate_subdivisions(current, *ctrl, *to, p_tolerance);
           for i in 1..=subdivisions {
             let t = i as f32 / subdivisions as f32;
-            let pt = eval_segment(current, segment, t);
-            result.push(pt);
+            contour.push(eval_segment(current, segment, t));
           }
-          current = *to;
         }
         Segment::Cubic { ctrl1, ctrl2, to } => {
           let subdivisions = calculate_subdivisions_cubic(current, *ctrl1, *ctrl2, *to, p_tolerance);
           for i in 1..=subdivisions {
             let t = i as f32 / subdivisions as f32;
-            let pt = eval_segment(current, segment, t);
-            result.push(pt);
+            contour.push(eval_segment(current, segment, t));
           }
-          current = *to;
         }
       }
+      current = segment.end();
     }
 
-    result
-  }
-
-  /// Returns an approximate length of the path.
-  pub fn length(&self) -> f32 {
-    let flattened = self.flatten(0.5);
-    let mut total = 0.0;
-    for i in 1..flattened.len() {
-      total += flattened[i - 1].distance_to(flattened[i]);
-    }
-    total
+    contours.push(contour);
+    contours
   }
 
   /// Returns the bounding box of the path as (min_x, min_y, max_x, max_y).
@@ -344,6 +360,9 @@ impl Path {
 
     for segment in &self.segments {
       let transformed_segment = match segment {
+        Segment::Move { to } => Segment::Move {
+          to: p_viewbox.map_point(*to, p_viewport_width, p_viewport_height, p_aspect_ratio),
+        },
         Segment::Line { to } => Segment::Line {
           to: p_viewbox.map_point(*to, p_viewport_width, p_viewport_height, p_aspect_ratio),
         },
@@ -410,13 +429,14 @@ impl Path {
       return samples;
     }
 
+    let measure = PathMeasure::new(self);
     let mut current_t = spacing;
     while current_t < 1.0 {
-      samples.push(self.point_at(current_t));
+      samples.push(measure.point_at(current_t).0);
       current_t += spacing;
     }
 
-    samples.push(self.point_at(1.0));
+    samples.push(measure.point_at(1.0).0);
     samples
   }
 
@@ -443,9 +463,19 @@ impl Default for Path {
   }
 }
 
+impl Segment {
+  /// Gets the end point of the segment.
+  pub fn end(&self) -> PointF {
+    match self {
+      Segment::Move { to } | Segment::Line { to } | Segment::Quadratic { to, .. } | Segment::Cubic { to, .. } => *to,
+    }
+  }
+}
+
 /// Evaluates a segment at parameter t (0 to 1).
 fn eval_segment(p_prev: PointF, p_segment: &Segment, p_t: f32) -> PointF {
   match p_segment {
+    Segment::Move { to } => *to,
     Segment::Line { to } => p_prev.lerp(*to, p_t),
     Segment::Quadratic { ctrl, to } => {
       // Quadratic Bezier: B(t) = (1-t)^2 * P0 + 2(1-t)t * P1 + t^2 * P2
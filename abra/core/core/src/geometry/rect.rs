@@ -0,0 +1,80 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// An axis-aligned rectangle on the pixel grid.
+/// A rectangle with a zero width or height is empty.
+pub struct Rect {
+  /// The x-coordinate of the left edge.
+  pub x: i32,
+  /// The y-coordinate of the top edge.
+  pub y: i32,
+  /// The width of the rectangle in pixels.
+  pub width: u32,
+  /// The height of the rectangle in pixels.
+  pub height: u32,
+}
+
+impl Rect {
+  /// Creates a new rectangle.
+  /// - `p_x`: The x-coordinate of the left edge.
+  /// - `p_y`: The y-coordinate of the top edge.
+  /// - `p_width`: The width in pixels.
+  /// - `p_height`: The height in pixels.
+  pub fn new(p_x: i32, p_y: i32, p_width: u32, p_height: u32) -> Rect {
+    Rect {
+      x: p_x,
+      y: p_y,
+      width: p_width,
+      height: p_height,
+    }
+  }
+
+  /// The x-coordinate just past the right edge.
+  pub fn right(&self) -> i32 {
+    self.x + self.width as i32
+  }
+
+  /// The y-coordinate just past the bottom edge.
+  pub fn bottom(&self) -> i32 {
+    self.y + self.height as i32
+  }
+
+  /// Whether the rectangle covers no pixels.
+  pub fn is_empty(&self) -> bool {
+    self.width == 0 || self.height == 0
+  }
+
+  /// Returns the smallest rectangle containing both rectangles. Empty rectangles are ignored.
+  /// - `p_other`: The rectangle to merge with.
+  pub fn union(&self, p_other: &Rect) -> Rect {
+    if p_other.is_empty() {
+      return *self;
+    }
+    if self.is_empty() {
+      return *p_other;
+    }
+    let x = self.x.min(p_other.x);
+    let y = self.y.min(p_other.y);
+    let right = self.right().max(p_other.right());
+    let bottom = self.bottom().max(p_other.bottom());
+    Rect::new(x, y, (right - x) as u32, (bottom - y) as u32)
+  }
+}
+
+impl From<(i32, i32, u32, u32)> for Rect {
+  fn from(rect: (i32, i32, u32, u32)) -> Self {
+    Rect::new(rect.0, rect.1, rect.2, rect.3)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn union_covers_both_and_skips_empty() {
+    let a = Rect::new(-5, 10, 20, 5);
+    let b = Rect::new(0, 0, 10, 10);
+    assert_eq!(a.union(&b), Rect::new(-5, 0, 20, 15));
+    assert_eq!(a.union(&Rect::new(100, 100, 0, 4)), a);
+    assert_eq!(Rect::default().union(&b), b);
+  }
+}
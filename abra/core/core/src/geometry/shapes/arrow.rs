@@ -0,0 +1,75 @@
+use crate::Area;
+
+/// A right-pointing block arrow made of a rectangular shaft and a triangular head.
+///
+/// The arrow fills a 100x50 box. Rotate or flip the resulting area to point it in another direction.
+#[derive(Clone, Copy, Debug)]
+pub struct Arrow {
+  /// The fraction of the arrow's length used by the head.
+  head_ratio: f32,
+}
+
+impl Arrow {
+  /// Create a new arrow whose head takes up 40% of its length.
+  pub fn new() -> Arrow {
+    Arrow { head_ratio: 0.4 }
+  }
+
+  /// Sets how long the head of the arrow is.
+  /// - `p_head_ratio`: The fraction of the arrow's length used by the head (clamped to 0.05..=1.0).
+  pub fn with_head_ratio(mut self, p_head_ratio: f32) -> Arrow {
+    self.head_ratio = p_head_ratio.clamp(0.05, 1.0);
+    self
+  }
+
+  /// Gets the arrow as an area.
+  pub fn to_area(&self) -> Area {
+    let length = 100.0;
+    let height = 50.0;
+    let shaft_half = height * 0.2;
+    let mid = height / 2.0;
+    let head_start = length * (1.0 - self.head_ratio);
+
+    let mut path = Area::new();
+
+    path
+      .move_to((0.0, mid - shaft_half))
+      .line_to((head_start, mid - shaft_half))
+      .line_to((head_start, 0.0))
+      .line_to((length, mid))
+      .line_to((head_start, height))
+      .line_to((head_start, mid + shaft_half))
+      .line_to((0.0, mid + shaft_half))
+      .line_to((0.0, mid - shaft_half));
+
+    path
+  }
+}
+
+impl Default for Arrow {
+  fn default() -> Self {
+    Arrow::new()
+  }
+}
+
+impl From<Arrow> for Area {
+  fn from(arrow: Arrow) -> Self {
+    arrow.to_area()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn head_ratio_sets_where_the_head_starts() {
+    let arrow = Arrow::new().with_head_ratio(0.5).to_area();
+    assert_eq!(arrow.bounds::<f32>(), (0.0, 0.0, 100.0, 50.0));
+    // The shaft is a fifth of the height on either side of the middle, and the head is full height.
+    assert!(arrow.contains((25.0, 25.0)));
+    assert!(!arrow.contains((25.0, 5.0)));
+    assert!(arrow.contains((55.0, 5.0)));
+    assert!(!Arrow::new().with_head_ratio(0.2).to_area().contains((55.0, 5.0)));
+  }
+}
@@ -1,7 +1,11 @@
+mod arrow;
 mod heart;
-mod polygon;
+mod regular_polygon;
+mod speech_bubble;
 mod star;
 
+pub use arrow::Arrow;
 pub use heart::Heart;
-pub use polygon::Polygon;
+pub use regular_polygon::RegularPolygon;
+pub use speech_bubble::SpeechBubble;
 pub use star::Star;
@@ -0,0 +1,88 @@
+use crate::Area;
+
+/// A regular polygon shape where every side and every interior angle are equal.
+///
+/// The polygon is inscribed in a 100x100 box with the first vertex at the top. Use `with_rotation` to turn it, e.g.
+/// so a square or hexagon sits flat, and `to_area` to get the shape.
+#[derive(Clone, Copy, Debug)]
+pub struct RegularPolygon {
+  /// The number of sides.
+  sides: usize,
+  /// The rotation in degrees, clockwise from the first vertex at the top.
+  rotation: f32,
+}
+
+impl RegularPolygon {
+  /// Create a new regular polygon shape inscribed in a 100x100 box with the first vertex at the top.
+  /// - `p_sides`: The number of sides of the polygon (minimum of 3).
+  pub fn new(p_sides: usize) -> RegularPolygon {
+    if p_sides < 3 {
+      panic!("A regular polygon must have at least 3 sides");
+    }
+    RegularPolygon {
+      sides: p_sides,
+      rotation: 0.0,
+    }
+  }
+
+  /// Sets the rotation of the polygon. Half of the angle between two vertices, `180 / sides`, turns a point-up
+  /// polygon flat-up.
+  /// - `p_degrees`: The rotation in degrees, clockwise from the first vertex at the top.
+  pub fn with_rotation(mut self, p_degrees: f32) -> RegularPolygon {
+    self.rotation = p_degrees;
+    self
+  }
+
+  /// Gets the polygon as an area.
+  pub fn to_area(&self) -> Area {
+    let mut path = Area::new();
+
+    let angle_step = 360.0 / self.sides as f32;
+    let radius = 50.0;
+
+    for i in 0..self.sides {
+      let angle_rad = (i as f32 * angle_step + self.rotation - 90.0).to_radians(); // Start from the top
+      let x = radius * angle_rad.cos() + radius;
+      let y = radius * angle_rad.sin() + radius;
+
+      if i == 0 {
+        path.move_to((x, y));
+      } else {
+        path.line_to((x, y));
+      }
+    }
+
+    // Explicitly close by returning to the first point
+    let first = path.start();
+    path.line_to(first);
+
+    path
+  }
+}
+
+impl From<RegularPolygon> for Area {
+  fn from(polygon: RegularPolygon) -> Self {
+    polygon.to_area()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn vertices_sit_on_the_inscribed_circle() {
+    let hexagon = RegularPolygon::new(6).to_area();
+    let points = hexagon.flatten(1.0);
+    // Six corners and the closing point back at the top.
+    assert_eq!(points.len(), 7);
+    for point in &points {
+      assert!((point.distance_to((50.0, 50.0).into()) - 50.0).abs() < 1e-3, "{:?}", point);
+    }
+    assert!(hexagon.start().distance_to((50.0, 0.0).into()) < 1e-3);
+
+    // A turn by half the angle between vertices makes a square sit flat.
+    let (min_x, min_y, max_x, max_y) = RegularPolygon::new(4).with_rotation(45.0).to_area().bounds::<f32>();
+    assert!((max_x - min_x - 70.71).abs() < 0.01 && (max_y - min_y - 70.71).abs() < 0.01);
+  }
+}
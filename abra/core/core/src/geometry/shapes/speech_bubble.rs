@@ -0,0 +1,92 @@
+use crate::Area;
+
+/// A rounded rectangle speech bubble with a triangular tail hanging from its bottom edge.
+///
+/// The bubble fills a 100x100 box: the body occupies the top 75% and the tail the remaining 25%.
+#[derive(Clone, Copy, Debug)]
+pub struct SpeechBubble {
+  /// Where the tail sits along the bottom edge, from 0.0 (left) to 1.0 (right).
+  tail_position: f32,
+}
+
+impl SpeechBubble {
+  /// Create a new speech bubble with its tail in the middle of the bottom edge.
+  pub fn new() -> SpeechBubble {
+    SpeechBubble { tail_position: 0.5 }
+  }
+
+  /// Sets where the tail sits. Tails near an edge lean outwards, away from the body.
+  /// - `p_tail_position`: Where the tail sits along the bottom edge, from 0.0 (left) to 1.0 (right).
+  pub fn with_tail_position(mut self, p_tail_position: f32) -> SpeechBubble {
+    self.tail_position = p_tail_position.clamp(0.0, 1.0);
+    self
+  }
+
+  /// Gets the speech bubble as an area.
+  pub fn to_area(&self) -> Area {
+    let width = 100.0;
+    let body_height = 75.0;
+    let tail_height = 25.0;
+    let radius = 12.0;
+    let tail_width = 16.0;
+    // Magic number for approximating a quarter circle with a cubic Bezier curve.
+    let k = radius * 0.552_284_8;
+
+    // Keep the tail base clear of the rounded corners.
+    let min_x = radius + tail_width / 2.0;
+    let max_x = width - radius - tail_width / 2.0;
+    let tail_x = min_x + (max_x - min_x) * self.tail_position;
+    // Tip leans outwards so tails near an edge point away from the body.
+    let tip_x = tail_x + (self.tail_position - 0.5) * tail_width * 2.0;
+
+    let mut path = Area::new();
+
+    path
+      .move_to((radius, 0.0))
+      .line_to((width - radius, 0.0))
+      .cubic_to((width - radius + k, 0.0), (width, radius - k), (width, radius))
+      .line_to((width, body_height - radius))
+      .cubic_to((width, body_height - radius + k), (width - radius + k, body_height), (width - radius, body_height))
+      .line_to((tail_x + tail_width / 2.0, body_height))
+      .line_to((tip_x, body_height + tail_height))
+      .line_to((tail_x - tail_width / 2.0, body_height))
+      .line_to((radius, body_height))
+      .cubic_to((radius - k, body_height), (0.0, body_height - radius + k), (0.0, body_height - radius))
+      .line_to((0.0, radius))
+      .cubic_to((0.0, radius - k), (radius - k, 0.0), (radius, 0.0));
+
+    path
+  }
+}
+
+impl Default for SpeechBubble {
+  fn default() -> Self {
+    SpeechBubble::new()
+  }
+}
+
+impl From<SpeechBubble> for Area {
+  fn from(bubble: SpeechBubble) -> Self {
+    bubble.to_area()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn tail_follows_its_position() {
+    let centered = SpeechBubble::new().to_area();
+    assert!(centered.contains((50.0, 40.0)));
+    assert!(centered.contains((50.0, 80.0)));
+    assert!(!centered.contains((1.0, 1.0)), "rounded corners are cut off");
+    assert!(!centered.contains((20.0, 80.0)));
+
+    let left = SpeechBubble::new().with_tail_position(0.0).to_area();
+    assert!(left.contains((18.0, 80.0)));
+    assert!(!left.contains((50.0, 80.0)));
+    let (_, _, right, bottom) = left.bounds::<f32>();
+    assert_eq!((right, bottom), (100.0, 100.0));
+  }
+}
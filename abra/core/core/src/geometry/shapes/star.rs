@@ -1,26 +1,121 @@
 use crate::Area;
 
 /// A star shape defined using line segments.
-pub struct Star;
+///
+/// The star is built from alternating outer and inner points inscribed in a 100x100 box. Use the `with_*` methods
+/// to configure it and `to_area` to get the shape.
+/// ```ignore
+/// let spiky = Star::new().with_points(8).with_inner_ratio(0.3).to_area();
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Star {
+  /// The number of outer points.
+  points: usize,
+  /// The radius of the inner points relative to the radius of the outer points.
+  inner_ratio: f32,
+  /// The rotation in degrees, clockwise from the first point at the top.
+  rotation: f32,
+}
 
 impl Star {
-  /// Create a new star shape.
-  pub fn new() -> Area {
+  /// Create a new five-pointed star.
+  pub fn new() -> Star {
+    Star {
+      points: 5,
+      inner_ratio: 0.382,
+      rotation: 0.0,
+    }
+  }
+
+  /// Sets the number of outer points of the star.
+  /// - `p_points`: The number of points (minimum of 3).
+  pub fn with_points(mut self, p_points: usize) -> Star {
+    if p_points < 3 {
+      panic!("A star must have at least 3 points");
+    }
+    self.points = p_points;
+    self
+  }
+
+  /// Sets how spiky the star is. Low ratios make thin spikes, while a ratio of 1.0 makes a regular polygon with
+  /// twice as many sides as the star has points.
+  /// - `p_inner_ratio`: The radius of the inner points relative to the outer points, from 0.0 to 1.0.
+  pub fn with_inner_ratio(mut self, p_inner_ratio: f32) -> Star {
+    self.inner_ratio = p_inner_ratio.clamp(0.0, 1.0);
+    self
+  }
+
+  /// Sets the rotation of the star.
+  /// - `p_degrees`: The rotation in degrees, clockwise from the first point at the top.
+  pub fn with_rotation(mut self, p_degrees: f32) -> Star {
+    self.rotation = p_degrees;
+    self
+  }
+
+  /// Gets the star as an area.
+  pub fn to_area(&self) -> Area {
     let mut path = Area::new();
+    let radius = 50.0;
+    let vertices = self.points * 2;
+    let angle_step = 360.0 / vertices as f32;
 
-    path
-      .move_to((50.0, 0.0))
-      .line_to((61.8, 35.1))
-      .line_to((100.0, 38.2))
-      .line_to((69.1, 61.8))
-      .line_to((80.9, 100.0))
-      .line_to((50.0, 76.4))
-      .line_to((19.1, 100.0))
-      .line_to((30.9, 61.8))
-      .line_to((0.0, 38.2))
-      .line_to((38.2, 35.1))
-      .line_to((50.0, 0.0));
+    for i in 0..vertices {
+      let vertex_radius = if i % 2 == 0 { radius } else { radius * self.inner_ratio };
+      let angle_rad = (i as f32 * angle_step + self.rotation - 90.0).to_radians(); // Start from the top
+      let x = vertex_radius * angle_rad.cos() + radius;
+      let y = vertex_radius * angle_rad.sin() + radius;
+
+      if i == 0 {
+        path.move_to((x, y));
+      } else {
+        path.line_to((x, y));
+      }
+    }
+
+    // Explicitly close by returning to the first point
+    let first = path.start();
+    path.line_to(first);
 
     path
   }
 }
+
+impl Default for Star {
+  fn default() -> Self {
+    Star::new()
+  }
+}
+
+impl From<Star> for Area {
+  fn from(star: Star) -> Self {
+    star.to_area()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::RegularPolygon;
+
+  #[test]
+  fn full_inner_ratio_makes_a_regular_polygon() {
+    let star = Star::new().with_points(4).with_inner_ratio(1.0).to_area();
+    let octagon = RegularPolygon::new(8).to_area();
+    let star_points = star.path.flatten(1.0);
+    let octagon_points = octagon.path.flatten(1.0);
+    assert_eq!(star_points.len(), octagon_points.len());
+    for (a, b) in star_points.iter().zip(octagon_points.iter()) {
+      assert!(a.distance_to(*b) < 1e-3, "{:?} != {:?}", a, b);
+    }
+  }
+
+  #[test]
+  fn rotation_moves_the_first_point() {
+    let upright = Star::new().to_area();
+    let turned = Star::new().with_rotation(36.0).to_area();
+    assert!(upright.start().distance_to((50.0, 0.0).into()) < 1e-3);
+    // A turn by half the angle between points puts an inner point at the top.
+    assert!(turned.contains((50.0, 50.0 - 50.0 * 0.382 + 1.0)));
+    assert!(!turned.contains((50.0, 2.0)));
+  }
+}
@@ -35,6 +35,18 @@ impl Path {
   pub fn stroke(&self, p_width: f32, _p_join: LineJoin, p_cap: LineCap) -> Path {
     use std::f32::consts::PI;
 
+    // Stroke every subpath on its own so no outline is drawn across the jumps between them.
+    if self.has_subpaths() {
+      let mut stroke_path = Path::new();
+      for subpath in self.subpaths() {
+        let outline = subpath.stroke(p_width, _p_join.clone(), p_cap.clone());
+        if !outline.segments().is_empty() {
+          stroke_path.append(&outline);
+        }
+      }
+      return stroke_path;
+    }
+
     let _start = Instant::now();
     let half_width = p_width / 2.0;
     // Use a lower flatten tolerance to produce smoother joins and arcs.
@@ -263,6 +275,19 @@ impl Area {
   /// - `join`: How corners are drawn.
   pub fn stroke(&self, p_width: f32, _p_join: LineJoin) -> Area {
     let _ = &_p_join;
+
+    // Stroke every contour on its own so holes get their own outline.
+    if self.path.has_subpaths() {
+      let mut stroke_path = Path::new();
+      for subpath in self.path.subpaths() {
+        let outline = Area::from(subpath).stroke(p_width, _p_join.clone());
+        if !outline.segments().is_empty() {
+          stroke_path.append(&outline.path);
+        }
+      }
+      return stroke_path.into();
+    }
+
     let half_width = p_width / 2.0;
     let flattened = self.path.flatten(0.5);
 
@@ -0,0 +1,221 @@
+//! SVG path data (`d` attribute) parsing.
+//!
+//! Tokenizing is delegated to `svgtypes`, the same parser stack used by `read_svg` through `resvg`.
+//! This module resolves relative, shorthand and smooth commands into the path's own segments and
+//! routes arcs through `Path::elliptical_arc` so they flatten like any other curve.
+
+use std::fmt::Display;
+
+use svgtypes::{PathParser, PathSegment};
+
+use crate::{Path, PointF};
+
+/// Errors that can occur while building a path from SVG path data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathError {
+  /// The path data could not be parsed (unknown command, bad number, missing leading move command, ...).
+  InvalidData(String),
+}
+
+impl Display for PathError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PathError::InvalidData(msg) => write!(f, "Invalid path data: {}", msg),
+    }
+  }
+}
+
+impl std::error::Error for PathError {}
+
+/// The last control point used by smooth curve commands (`S`/`s` and `T`/`t`).
+#[derive(Clone, Copy)]
+enum LastControl {
+  None,
+  Cubic(PointF),
+  Quadratic(PointF),
+}
+
+impl Path {
+  /// Creates a path from an SVG path data string such as `"M10 10 L90 10 L50 90 Z"`.
+  /// Supports the M, L, H, V, C, S, Q, T, A and Z commands in both absolute (upper case) and relative
+  /// (lower case) form, including implicitly repeated commands. Each `M` after the first starts a new subpath.
+  /// - `p_data`: The SVG path data.
+  ///
+  /// ```ignore
+  /// let path = Path::from_svg("M0 0 h100 v100 h-100 z")?;
+  /// let area: Area = path.into();
+  /// ```
+  pub fn from_svg(p_data: &str) -> Result<Path, PathError> {
+    let mut path = Path::new();
+    let mut current = PointF::zero();
+    let mut subpath_start = PointF::zero();
+    let mut last_control = LastControl::None;
+
+    for segment in PathParser::from(p_data) {
+      let segment = segment.map_err(|e| PathError::InvalidData(e.to_string()))?;
+      // Resolves a coordinate pair against the current point for relative commands.
+      let resolve = |p_abs: bool, p_x: f64, p_y: f64| {
+        if p_abs {
+          PointF::new(p_x, p_y)
+        } else {
+          PointF::new(current.x as f64 + p_x, current.y as f64 + p_y)
+        }
+      };
+
+      match segment {
+        PathSegment::MoveTo { abs, x, y } => {
+          let to = resolve(abs, x, y);
+          path.move_to(to);
+          current = to;
+          subpath_start = to;
+          last_control = LastControl::None;
+        }
+        PathSegment::LineTo { abs, x, y } => {
+          let to = resolve(abs, x, y);
+          path.line_to(to);
+          current = to;
+          last_control = LastControl::None;
+        }
+        PathSegment::HorizontalLineTo { abs, x } => {
+          let to = if abs { PointF::new(x, current.y) } else { PointF::new(current.x as f64 + x, current.y) };
+          path.line_to(to);
+          current = to;
+          last_control = LastControl::None;
+        }
+        PathSegment::VerticalLineTo { abs, y } => {
+          let to = if abs { PointF::new(current.x, y) } else { PointF::new(current.x, current.y as f64 + y) };
+          path.line_to(to);
+          current = to;
+          last_control = LastControl::None;
+        }
+        PathSegment::CurveTo { abs, x1, y1, x2, y2, x, y } => {
+          let ctrl1 = resolve(abs, x1, y1);
+          let ctrl2 = resolve(abs, x2, y2);
+          let to = resolve(abs, x, y);
+          path.cubic_to(ctrl1, ctrl2, to);
+          current = to;
+          last_control = LastControl::Cubic(ctrl2);
+        }
+        PathSegment::SmoothCurveTo { abs, x2, y2, x, y } => {
+          // The first control point is the reflection of the previous cubic's second control point.
+          let ctrl1 = match last_control {
+            LastControl::Cubic(ctrl) => current * 2.0 - ctrl,
+            _ => current,
+          };
+          let ctrl2 = resolve(abs, x2, y2);
+          let to = resolve(abs, x, y);
+          path.cubic_to(ctrl1, ctrl2, to);
+          current = to;
+          last_control = LastControl::Cubic(ctrl2);
+        }
+        PathSegment::Quadratic { abs, x1, y1, x, y } => {
+          let ctrl = resolve(abs, x1, y1);
+          let to = resolve(abs, x, y);
+          path.quad_to(ctrl, to);
+          current = to;
+          last_control = LastControl::Quadratic(ctrl);
+        }
+        PathSegment::SmoothQuadratic { abs, x, y } => {
+          let ctrl = match last_control {
+            LastControl::Quadratic(ctrl) => current * 2.0 - ctrl,
+            _ => current,
+          };
+          let to = resolve(abs, x, y);
+          path.quad_to(ctrl, to);
+          current = to;
+          last_control = LastControl::Quadratic(ctrl);
+        }
+        PathSegment::EllipticalArc {
+          abs,
+          rx,
+          ry,
+          x_axis_rotation,
+          large_arc,
+          sweep,
+          x,
+          y,
+        } => {
+          let to = resolve(abs, x, y);
+          path.elliptical_arc(rx as f32, ry as f32, x_axis_rotation as f32, large_arc, sweep, to);
+          current = to;
+          last_control = LastControl::None;
+        }
+        PathSegment::ClosePath { .. } => {
+          if current != subpath_start {
+            path.line_to(subpath_start);
+          }
+          current = subpath_start;
+          last_control = LastControl::None;
+        }
+      }
+    }
+
+    Ok(path)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_absolute_lines() {
+    let path = Path::from_svg("M10 10 L90 10 L50 90 Z").unwrap();
+    assert_eq!(
+      path.points(),
+      vec![PointF::new(10, 10), PointF::new(90, 10), PointF::new(50, 90), PointF::new(10, 10)]
+    );
+  }
+
+  #[test]
+  fn parses_relative_and_implicit_commands() {
+    // `l` repeated implicitly, then horizontal/vertical shorthands.
+    let path = Path::from_svg("m10 10 l10 0 0 10 h-5 v5 z").unwrap();
+    assert_eq!(
+      path.points(),
+      vec![
+        PointF::new(10, 10),
+        PointF::new(20, 10),
+        PointF::new(20, 20),
+        PointF::new(15, 20),
+        PointF::new(15, 25),
+        PointF::new(10, 10),
+      ]
+    );
+  }
+
+  #[test]
+  fn implicit_lines_after_move() {
+    let path = Path::from_svg("M0 0 10 0 10 10").unwrap();
+    assert_eq!(path.points(), vec![PointF::new(0, 0), PointF::new(10, 0), PointF::new(10, 10)]);
+  }
+
+  #[test]
+  fn second_move_starts_subpath() {
+    let path = Path::from_svg("M0 0 L10 0 L10 10 Z M20 20 l5 0 0 5 z").unwrap();
+    let contours = path.flatten_contours(0.5);
+    assert_eq!(contours.len(), 2);
+    assert_eq!(contours[1][0], PointF::new(20, 20));
+  }
+
+  #[test]
+  fn parses_curves_and_arcs() {
+    let path = Path::from_svg("M0 0 C0 10 10 10 10 0 S20 -10 20 0 Q25 5 30 0 T40 0 A5 5 0 0 1 50 0").unwrap();
+    assert_eq!(path.end(), PointF::new(50, 0));
+    // The smooth cubic reflects the previous control point (10, 10) around (10, 0).
+    assert_eq!(
+      path.segments()[1],
+      crate::Segment::Cubic {
+        ctrl1: PointF::new(10, -10),
+        ctrl2: PointF::new(20, -10),
+        to: PointF::new(20, 0),
+      }
+    );
+  }
+
+  #[test]
+  fn rejects_invalid_data() {
+    assert!(matches!(Path::from_svg("L10 10"), Err(PathError::InvalidData(_))));
+    assert!(matches!(Path::from_svg("M10 10 X"), Err(PathError::InvalidData(_))));
+  }
+}
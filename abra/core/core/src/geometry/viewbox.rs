@@ -172,6 +172,47 @@ impl Default for Alignment {
   }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+/// The combined horizontal and vertical alignment, named after SVG's `preserveAspectRatio` values.
+pub enum Align {
+  /// Align to the left and top (`xMinYMin`).
+  XMinYMin,
+  /// Align to the center and top (`xMidYMin`).
+  XMidYMin,
+  /// Align to the right and top (`xMaxYMin`).
+  XMaxYMin,
+  /// Align to the left and middle (`xMinYMid`).
+  XMinYMid,
+  /// Align to the center and middle (`xMidYMid`).
+  #[default]
+  XMidYMid,
+  /// Align to the right and middle (`xMaxYMid`).
+  XMaxYMid,
+  /// Align to the left and bottom (`xMinYMax`).
+  XMinYMax,
+  /// Align to the center and bottom (`xMidYMax`).
+  XMidYMax,
+  /// Align to the right and bottom (`xMaxYMax`).
+  XMaxYMax,
+}
+
+impl Align {
+  /// Splits the alignment into its horizontal and vertical parts.
+  pub fn alignments(&self) -> (Alignment, Alignment) {
+    match self {
+      Align::XMinYMin => (Alignment::Min, Alignment::Min),
+      Align::XMidYMin => (Alignment::Mid, Alignment::Min),
+      Align::XMaxYMin => (Alignment::Max, Alignment::Min),
+      Align::XMinYMid => (Alignment::Min, Alignment::Mid),
+      Align::XMidYMid => (Alignment::Mid, Alignment::Mid),
+      Align::XMaxYMid => (Alignment::Max, Alignment::Mid),
+      Align::XMinYMax => (Alignment::Min, Alignment::Max),
+      Align::XMidYMax => (Alignment::Mid, Alignment::Max),
+      Align::XMaxYMax => (Alignment::Max, Alignment::Max),
+    }
+  }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 /// Complete aspect ratio preservation specification.
 pub struct AspectRatio {
@@ -219,6 +260,14 @@ impl AspectRatio {
       align_y: Alignment::Mid,
     }
   }
+
+  /// Sets where the content is positioned in the viewport when the aspect ratios differ.
+  /// Has no effect on `none`, which always fills the viewport.
+  /// - `p_align`: The alignment, e.g. `Align::XMinYMin` to align to the top-left corner.
+  pub fn with_align(mut self, p_align: Align) -> AspectRatio {
+    (self.align_x, self.align_y) = p_align.alignments();
+    self
+  }
 }
 
 impl Default for AspectRatio {
@@ -226,3 +275,21 @@ impl Default for AspectRatio {
     AspectRatio::meet()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn align_positions_content_in_a_wider_viewport() {
+    let viewbox = ViewBox::square(100.0);
+    let corner = PointF::new(0.0, 0.0);
+    let map = |p_align: Align| viewbox.map_point(corner, 300.0, 100.0, AspectRatio::meet().with_align(p_align));
+    assert_eq!(map(Align::XMinYMid), PointF::new(0.0, 0.0));
+    assert_eq!(map(Align::XMidYMid), PointF::new(100.0, 0.0));
+    assert_eq!(map(Align::XMaxYMid), PointF::new(200.0, 0.0));
+    // Sliced content overflows vertically instead, so the vertical alignment moves it.
+    let sliced = viewbox.map_point(corner, 300.0, 100.0, AspectRatio::slice().with_align(Align::XMidYMax));
+    assert_eq!(sliced, PointF::new(0.0, -200.0));
+  }
+}
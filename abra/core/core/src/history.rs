@@ -0,0 +1,276 @@
+//! A generic undo/redo stack.
+
+use std::time::{Duration, Instant};
+
+/// Measures how many bytes a state uses, for the memory limit.
+type MeasureFn<T> = Box<dyn Fn(&T) -> usize + Send + Sync>;
+
+/// A state in the history along with when it was pushed and its measured size.
+struct Snapshot<T> {
+  state: T,
+  pushed_at: Instant,
+  size: usize,
+}
+
+/// An undo/redo stack of snapshots, e.g. of `Canvas` or `Layer` states.
+///
+/// The current state is the one most recently pushed, undone to or redone to.
+/// Pushing a state after undoing discards the states that could have been redone.
+/// ```ignore
+/// let mut history = History::new().with_max_depth(50);
+/// history.push(canvas.clone());
+/// // ... edit ...
+/// history.push(canvas.clone());
+/// let previous = history.undo();
+/// ```
+pub struct History<T> {
+  snapshots: Vec<Snapshot<T>>,
+  /// The index of the current state in `snapshots`.
+  cursor: usize,
+  /// The maximum number of states that are kept.
+  max_depth: usize,
+  /// Pushes this soon after the previous one replace the current state instead of adding a new one.
+  coalesce_window: Option<Duration>,
+  /// The maximum total size of the kept states, and how to measure a state.
+  memory_limit: Option<(usize, MeasureFn<T>)>,
+  /// Whether the current state was reached by undo or redo, which stops it from being coalesced.
+  navigated: bool,
+}
+
+impl<T> Default for History<T> {
+  fn default() -> Self {
+    History::new()
+  }
+}
+
+impl<T> History<T> {
+  /// Creates an empty history that keeps up to 100 states.
+  pub fn new() -> Self {
+    History {
+      snapshots: Vec::new(),
+      cursor: 0,
+      max_depth: 100,
+      coalesce_window: None,
+      memory_limit: None,
+      navigated: false,
+    }
+  }
+
+  /// Sets the maximum number of states that are kept. The oldest states are dropped first.
+  /// - `p_depth`: The maximum number of states, at least 1.
+  pub fn with_max_depth(mut self, p_depth: usize) -> Self {
+    self.max_depth = p_depth.max(1);
+    self.trim();
+    self
+  }
+
+  /// Merges rapid edits, such as the steps of a brush stroke, into a single undo step.
+  /// A push within the window of the previous push replaces the current state instead of adding one.
+  /// - `p_window`: How soon after the previous push a new push is merged into it.
+  pub fn with_coalesce_window(mut self, p_window: Duration) -> Self {
+    self.coalesce_window = Some(p_window);
+    self
+  }
+
+  /// Limits the total memory used by the kept states. The oldest states are dropped first,
+  /// but the current state is always kept even when it alone is over the limit.
+  /// - `p_max_bytes`: The maximum total size of the states in bytes.
+  /// - `p_measure`: Returns the size of a state in bytes.
+  pub fn with_memory_limit(
+    mut self, p_max_bytes: usize, p_measure: impl Fn(&T) -> usize + Send + Sync + 'static,
+  ) -> Self {
+    for snapshot in &mut self.snapshots {
+      snapshot.size = p_measure(&snapshot.state);
+    }
+    self.memory_limit = Some((p_max_bytes, Box::new(p_measure)));
+    self.trim();
+    self
+  }
+
+  /// Adds a state after the current one, discarding any states that could have been redone.
+  /// - `p_state`: The new current state.
+  pub fn push(&mut self, p_state: T) {
+    self.push_at(p_state, Instant::now());
+  }
+
+  /// Adds a state as if it was pushed at a given time, which decides whether it is coalesced.
+  fn push_at(&mut self, p_state: T, p_now: Instant) {
+    self.snapshots.truncate(self.cursor + 1);
+    let size = self.memory_limit.as_ref().map_or(0, |(_, measure)| measure(&p_state));
+    let snapshot = Snapshot {
+      state: p_state,
+      pushed_at: p_now,
+      size,
+    };
+
+    let coalesce = !self.navigated
+      && self.coalesce_window.is_some_and(|window| {
+        self
+          .snapshots
+          .last()
+          .is_some_and(|last| p_now.saturating_duration_since(last.pushed_at) <= window)
+      });
+    self.navigated = false;
+    match self.snapshots.last_mut() {
+      // Keep the first push time so that a long run of edits cannot merge forever.
+      Some(last) if coalesce => {
+        last.state = snapshot.state;
+        last.size = snapshot.size;
+      }
+      _ => self.snapshots.push(snapshot),
+    }
+    self.cursor = self.snapshots.len() - 1;
+    self.trim();
+  }
+
+  /// Drops the oldest states until the depth and memory limits are met.
+  fn trim(&mut self) {
+    let mut excess = self.snapshots.len().saturating_sub(self.max_depth);
+    if let Some((max_bytes, _)) = &self.memory_limit {
+      let mut total: usize = self.snapshots.iter().map(|snapshot| snapshot.size).sum();
+      for snapshot in &self.snapshots[excess..] {
+        if total <= *max_bytes || excess >= self.cursor {
+          break;
+        }
+        total -= snapshot.size;
+        excess += 1;
+      }
+    }
+    let excess = excess.min(self.cursor);
+    self.snapshots.drain(..excess);
+    self.cursor -= excess;
+  }
+
+  /// Gets the current state.
+  pub fn current(&self) -> Option<&T> {
+    self.snapshots.get(self.cursor).map(|snapshot| &snapshot.state)
+  }
+
+  /// Gets a state by its index, where 0 is the oldest kept state.
+  /// - `p_index`: The index of the state.
+  pub fn get(&self, p_index: usize) -> Option<&T> {
+    self.snapshots.get(p_index).map(|snapshot| &snapshot.state)
+  }
+
+  /// Gets the index of the current state.
+  pub fn position(&self) -> usize {
+    self.cursor
+  }
+
+  /// Removes the state at an index. Removing the current state makes the previous state current.
+  /// - `p_index`: The index of the state to remove.
+  pub fn remove(&mut self, p_index: usize) -> Option<T> {
+    if p_index >= self.snapshots.len() {
+      return None;
+    }
+    let snapshot = self.snapshots.remove(p_index);
+    if p_index <= self.cursor {
+      self.cursor = self.cursor.saturating_sub(1);
+    }
+    Some(snapshot.state)
+  }
+
+  /// Checks whether there is a state before the current one.
+  pub fn can_undo(&self) -> bool {
+    self.cursor > 0
+  }
+
+  /// Checks whether there is a state after the current one.
+  pub fn can_redo(&self) -> bool {
+    self.cursor + 1 < self.snapshots.len()
+  }
+
+  /// Gets the number of kept states, including those that can be redone.
+  pub fn len(&self) -> usize {
+    self.snapshots.len()
+  }
+
+  /// Checks whether no states are kept.
+  pub fn is_empty(&self) -> bool {
+    self.snapshots.is_empty()
+  }
+
+  /// Removes every state.
+  pub fn clear(&mut self) {
+    self.snapshots.clear();
+    self.cursor = 0;
+    self.navigated = false;
+  }
+}
+
+impl<T: Clone> History<T> {
+  /// Steps back to the previous state and returns it, or `None` when there is nothing to undo.
+  pub fn undo(&mut self) -> Option<T> {
+    if !self.can_undo() {
+      return None;
+    }
+    self.cursor -= 1;
+    self.navigated = true;
+    self.current().cloned()
+  }
+
+  /// Steps forward to the next state and returns it, or `None` when there is nothing to redo.
+  pub fn redo(&mut self) -> Option<T> {
+    if !self.can_redo() {
+      return None;
+    }
+    self.cursor += 1;
+    self.navigated = true;
+    self.current().cloned()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn undo_and_redo() {
+    let mut history = History::new();
+    history.push("first");
+    history.push("second");
+    history.push("third");
+    assert_eq!(history.undo(), Some("second"));
+    assert_eq!(history.undo(), Some("first"));
+    assert_eq!(history.undo(), None);
+    assert_eq!(history.redo(), Some("second"));
+    assert_eq!(history.current(), Some(&"second"));
+
+    // A new state discards the redo branch.
+    history.push("fourth");
+    assert!(!history.can_redo());
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.undo(), Some("second"));
+  }
+
+  #[test]
+  fn limits_depth_and_memory() {
+    let mut history = History::new().with_max_depth(3);
+    for state in 0..5 {
+      history.push(state);
+    }
+    assert_eq!((history.len(), history.get(0)), (3, Some(&2)));
+
+    let mut history = History::new().with_memory_limit(10, |state: &Vec<u8>| state.len());
+    history.push(vec![0; 4]);
+    history.push(vec![1; 4]);
+    history.push(vec![2; 4]);
+    assert_eq!(history.len(), 2);
+    history.push(vec![3; 20]);
+    assert_eq!((history.len(), history.current().map(Vec::len)), (1, Some(20)));
+  }
+
+  #[test]
+  fn coalesces_rapid_pushes() {
+    let start = Instant::now();
+    let mut history = History::new().with_coalesce_window(Duration::from_millis(100));
+    history.push_at(1, start);
+    history.push_at(2, start + Duration::from_millis(50));
+    history.push_at(3, start + Duration::from_millis(500));
+    assert_eq!((history.len(), history.get(0)), (2, Some(&2)));
+    // A push right after undoing starts a new step instead of overwriting the undone-to state.
+    assert_eq!(history.undo(), Some(2));
+    history.push_at(4, start + Duration::from_millis(510));
+    assert_eq!((history.len(), history.get(0), history.current()), (2, Some(&2), Some(&4)));
+  }
+}
@@ -11,8 +11,9 @@
 use crate::Channels;
 use crate::Image;
 use crate::Settings;
-use crate::geometry::Area;
+use crate::geometry::{Area, PointF};
 use crate::image::gpu_registry::get_gpu_provider;
+use crate::image::progress::Progress;
 use rayon::prelude::*;
 // use std::borrow::Cow; // already imported above
 
@@ -21,6 +22,8 @@ use rayon::prelude::*;
 pub struct ApplyContext<'a> {
   pub area: Option<Vec<&'a Area>>,
   pub mask_image: Option<&'a [u8]>,
+  /// Whether the operation applies everywhere except inside the areas.
+  pub inverted: bool,
 }
 use std::borrow::Cow;
 
@@ -166,40 +169,56 @@ fn prepare_area_pixels<'a>(image: &'a Image, area: Option<&Area>, kernel_padding
   }
 }
 
+/// Compute how much of a pixel center lies inside an area (0.0 .. 1.0), fading in over the area's feather.
+fn area_coverage(area: &Area, contours: &[Vec<PointF>], gx: f32, gy: f32) -> f32 {
+  if !area.contains((gx, gy)) {
+    return 0.0;
+  }
+  if area.feather() > 0 {
+    area.feather_falloff(contours, (gx, gy))
+  } else {
+    1.0
+  }
+}
+
+/// Flatten the outline of an area once, so feathered coverage does not re-flatten it for every pixel.
+fn feather_contours(area: &Area) -> Vec<Vec<PointF>> {
+  if area.feather() > 0 {
+    area.path.flatten_contours(1.0)
+  } else {
+    vec![]
+  }
+}
+
 /// Compute a per-pixel alpha mask (0.0 .. 1.0) for a prepared area based on `Area` feathering and optional `Mask`.
 /// - `prepared`: prepared area metadata
 /// - `area`: optional area (may be None). If None, mask is all ones.
+/// - `excluded`: areas left out of the mask, used when the areas are inverted. Where they overlap, the strongest
+///   coverage wins, and their feathers fade the effect back in.
 /// - `mask_image`: optional RGBA mask image bytes (full image size RGBA bytes). If provided it will be sampled and combined multiplicatively.
-fn compute_area_mask(prepared: &PreparedAreaMeta, area: Option<&Area>, mask_image: Option<&[u8]>) -> Vec<f32> {
+fn compute_area_mask(
+  prepared: &PreparedAreaMeta, area: Option<&Area>, excluded: &[&Area], mask_image: Option<&[u8]>,
+) -> Vec<f32> {
   let width = prepared.rect_w as usize;
   let height = prepared.rect_h as usize;
   let rect_min_x = prepared.rect_min_x as i32;
   let rect_min_y = prepared.rect_min_y as i32;
 
   let mut mask: Vec<f32> = vec![0.0f32; width * height];
-  let feather_amount = area.map(|a| a.feather()).unwrap_or(0) as i32;
+  let area_contours = area.map(feather_contours).unwrap_or_default();
+  let excluded: Vec<(&Area, Vec<Vec<PointF>>)> = excluded.iter().map(|a| (*a, feather_contours(a))).collect();
 
   // compute area coverage / feather first (0..1)
   mask.par_chunks_mut(width).enumerate().for_each(|(py, chunk)| {
     for px in 0..chunk.len() {
       let gx = (rect_min_x as i32 + px as i32) as f32 + 0.5;
       let gy = (rect_min_y as i32 + py as i32) as f32 + 0.5;
-      let v = if let Some(a) = area {
-        if a.contains((gx, gy)) {
-          if feather_amount > 0 {
-            let closest = a.path.closest_point(gx, gy);
-            let d = ((gx - closest.x).powi(2) + (gy - closest.y).powi(2)).sqrt();
-            (d as f32 / feather_amount as f32).clamp(0.0, 1.0)
-          } else {
-            1.0
-          }
-        } else {
-          0.0
-        }
-      } else {
-        1.0
-      };
-      chunk[px] = v;
+      let v = area.map_or(1.0, |a| area_coverage(a, &area_contours, gx, gy));
+      let excluded_coverage = excluded
+        .iter()
+        .map(|(a, contours)| area_coverage(a, contours, gx, gy))
+        .fold(0.0f32, f32::max);
+      chunk[px] = v * (1.0 - excluded_coverage);
     }
   });
 
@@ -290,7 +309,8 @@ fn blend_area_pixels(image: &mut Image, processed: &[u8], prepared_meta: &Prepar
 /// a fast-path replacement if the entire image was processed; otherwise it computes a mask
 /// (area feather + optional mask image) and blends the processed buffer into place.
 fn apply_processed_pixels_to_image(
-  image: &mut Image, processed: Vec<u8>, prepared: &PreparedAreaMeta, area: Option<&Area>, mask_image: Option<&[u8]>,
+  image: &mut Image, processed: Vec<u8>, prepared: &PreparedAreaMeta, area: Option<&Area>, excluded: &[&Area],
+  mask_image: Option<&[u8]>,
 ) {
   let (image_w, image_h) = image.dimensions::<usize>();
   let full_image_processed = prepared.area_min_x == 0
@@ -298,13 +318,14 @@ fn apply_processed_pixels_to_image(
     && prepared.area_w as usize == image_w
     && prepared.area_h as usize == image_h
     && area.map(|a| a.feather()).unwrap_or(0) == 0
+    && excluded.is_empty()
     && mask_image.is_none();
 
   if full_image_processed {
     // Fast path: no blending required; replace the entire image
     image.set_rgba_owned(processed);
   } else {
-    let mask = compute_area_mask(prepared, area, mask_image);
+    let mask = compute_area_mask(prepared, area, excluded, mask_image);
     blend_area_pixels(image, processed.as_slice(), prepared, &mask);
   }
 }
@@ -316,20 +337,72 @@ fn apply_processed_pixels_to_image(
 /// - `p_kernel_padding`: Padding around the kernel for processing.
 /// - `p_processor`: Closure that processes the prepared image area.
 pub fn process_image<F>(
+  p_image: &mut Image, p_ctx: Option<ApplyContext<'_>>, p_kernel_padding: impl Into<i32>, mut p_processor: F,
+) where
+  F: FnMut(&mut Image) + Send,
+{
+  process_image_with_origin(p_image, p_ctx, p_kernel_padding, |image, _| p_processor(image));
+}
+
+/// Like [`process_image`], but the closure also receives a progress handle for its area, so slow processors can
+/// report how far they are, e.g. once per band of rows. With several areas each one gets an equal share of the
+/// progress, and the share of an area is completed when its processing returns.
+/// - `p_image`: The destination image to modify.
+/// - `p_options`: Optional `ApplyOptions` containing area and mask info.
+/// - `p_kernel_padding`: Padding around the kernel for processing.
+/// - `p_progress`: The progress covering all areas.
+/// - `p_processor`: Closure that processes the prepared image area and reports into the given progress.
+pub fn process_image_with_progress<F>(
+  p_image: &mut Image, p_ctx: Option<ApplyContext<'_>>, p_kernel_padding: impl Into<i32>, p_progress: Progress<'_>,
+  mut p_processor: F,
+) where
+  F: FnMut(&mut Image, Progress<'_>) + Send,
+{
+  let count = p_ctx
+    .as_ref()
+    .filter(|c| !c.inverted)
+    .and_then(|c| c.area.as_ref())
+    .map_or(1, |areas| areas.len());
+  let mut index = 0;
+  process_image_with_origin(p_image, p_ctx, p_kernel_padding, |image, _| {
+    let part = p_progress.part(index, count);
+    index += 1;
+    p_processor(image, part);
+    part.report(1.0);
+  });
+  // Areas handled by the GPU never reach the closure.
+  p_progress.report(1.0);
+}
+
+/// Like [`process_image`], but the closure also receives the position of the prepared image's top-left pixel
+/// within the destination image. This lets processors that depend on image-space coordinates, such as
+/// gradients, line up across the cropped areas.
+/// - `p_image`: The destination image to modify.
+/// - `p_options`: Optional `ApplyOptions` containing area and mask info.
+/// - `p_kernel_padding`: Padding around the kernel for processing.
+/// - `p_processor`: Closure that processes the prepared image area given its `(x, y)` origin.
+pub fn process_image_with_origin<F>(
   p_image: &mut Image, p_ctx: Option<ApplyContext<'_>>, p_kernel_padding: impl Into<i32>, p_processor: F,
 ) where
-  F: FnMut(&mut Image),
+  F: FnMut(&mut Image, (i32, i32)) + Send,
 {
   let start = std::time::Instant::now();
   // No auto-init here; provider should be registered by an integration crate (e.g., gpu_integration)
   // If a provider is present it will be used, otherwise CPU fallback.
-  let areas = p_ctx.as_ref().and_then(|c| c.area.clone());
+  // Without an area the whole image is processed. Inverted areas are processed as the whole image too, with the
+  // areas cut out of the blend mask instead.
+  let inverted = p_ctx.as_ref().is_some_and(|c| c.inverted);
+  let (areas, excluded): (Vec<Option<&Area>>, Vec<&Area>) = match p_ctx.as_ref().and_then(|c| c.area.clone()) {
+    Some(areas) if inverted => (vec![None], areas),
+    Some(areas) => (areas.into_iter().map(Some).collect(), vec![]),
+    None => (vec![None], vec![]),
+  };
   let mask: Option<&[u8]> = p_ctx.as_ref().and_then(|c| c.mask_image);
   let kernel_padding = p_kernel_padding.into();
   // Prepare a sub-area for processing
   let mut processor = p_processor;
-  for area in areas.unwrap() {
-    let prepared = prepare_area_pixels(p_image, Some(area), kernel_padding);
+  for area in areas {
+    let prepared = prepare_area_pixels(p_image, area, kernel_padding);
     if prepared.area_w == 0 || prepared.area_h == 0 {
       return;
     }
@@ -342,7 +415,7 @@ pub fn process_image<F>(
         match (provider.process)(&meta, prepared.pixels.as_ref()) {
           Ok(processed) => {
             println!("Processing using the GPU");
-            apply_processed_pixels_to_image(p_image, processed, &meta, Some(area), mask);
+            apply_processed_pixels_to_image(p_image, processed, &meta, area, &excluded, mask);
             println!("GPU processing took {:?}", start.elapsed());
             return;
           }
@@ -360,8 +433,9 @@ pub fn process_image<F>(
     let height = prepared.rect_h as usize;
     let pixels = prepared.pixels.as_ref();
     let mut tmp_img = Image::new_from_pixels(width as u32, height as u32, pixels.to_vec(), Channels::RGBA);
-    (processor)(&mut tmp_img);
-    apply_processed_pixels_to_image(p_image, tmp_img.into_rgba_vec(), &meta, Some(area), mask);
+    let origin = (meta.rect_min_x, meta.rect_min_y);
+    Settings::with_thread_budget(|| (processor)(&mut tmp_img, origin));
+    apply_processed_pixels_to_image(p_image, tmp_img.into_rgba_vec(), &meta, area, &excluded, mask);
     println!("CPU processing took {:?}", start.elapsed());
   }
 }
@@ -400,7 +474,7 @@ mod tests {
     let area = Area::rect((2.0, 2.0), (8.0, 8.0)).with_feather(4);
     let prepared = prepare_area_pixels(&img, Some(&area), 2);
     let meta = prepared.meta();
-    let mask = compute_area_mask(&meta, Some(&area), None);
+    let mask = compute_area_mask(&meta, Some(&area), &[], None);
     // center pixel should be near 1.0
     let center_x = (2 + 8 / 2) as usize;
     let center_y = (2 + 8 / 2) as usize;
@@ -413,6 +487,20 @@ mod tests {
     assert!(mask[idx2] < 1.0 && mask[idx2] > 0.0);
   }
 
+  #[test]
+  fn compute_area_mask_excludes_areas() {
+    let img = Image::new_from_color(16, 16, Color::from_rgba(255, 255, 255, 255));
+    let ring = Area::rect((2.0, 2.0), (12.0, 12.0)).difference(&Area::rect((6.0, 6.0), (4.0, 4.0)));
+    let prepared = prepare_area_pixels(&img, None, 0);
+    let meta = prepared.meta();
+    let mask = compute_area_mask(&meta, None, &[&ring], None);
+    let at = |x: usize, y: usize| mask[y * 16 + x];
+    // Outside the ring and inside its hole are affected, the ring itself is not.
+    assert_eq!(at(0, 0), 1.0);
+    assert_eq!(at(7, 7), 1.0);
+    assert_eq!(at(3, 3), 0.0);
+  }
+
   #[test]
   fn blend_area_pixels_blends() {
     let mut img = Image::new_from_color(8, 8, Color::from_rgba(0, 0, 0, 255));
@@ -421,7 +509,7 @@ mod tests {
     let area = Area::rect((2.0, 2.0), (4.0, 4.0)).with_feather(0);
     let prepared = prepare_area_pixels(&img, Some(&area), 0);
     let meta = prepared.meta();
-    let mask = compute_area_mask(&meta, Some(&area), None);
+    let mask = compute_area_mask(&meta, Some(&area), &[], None);
     blend_area_pixels(&mut img, &processed, &meta, &mask);
     // Check that center of area has changed to white
     let idx = ((3 * 8 + 3) * 4) as usize;
@@ -0,0 +1,229 @@
+//! Pixel-by-pixel image comparison for golden-file and visual regression tests.
+
+use crate::Image;
+
+/// The largest possible YIQ color difference, between black and white.
+const MAX_DELTA: f32 = 35215.0;
+/// How strongly matching pixels are faded toward white in the highlight image.
+const DIM_ALPHA: f32 = 0.1;
+/// The color of pixels that differ.
+const DIFF_COLOR: [u8; 4] = [255, 0, 0, 255];
+/// The color of pixels that differ only because of anti-aliasing.
+const ANTIALIASED_COLOR: [u8; 4] = [255, 255, 0, 255];
+
+/// Compares two images and returns a highlight image together with the number of differing pixels.
+///
+/// Colors are compared by their perceived difference after blending them onto white. Differing pixels are
+/// drawn red in the highlight image and matching pixels are drawn as a faded grayscale copy of `p_a`.
+/// Pixels that only differ because an edge moved by a fraction of a pixel are drawn yellow and not counted:
+/// a pixel counts as anti-aliased when it sits between a darker and a brighter neighbor and that neighbor is
+/// part of a flat area in both images.
+///
+/// When the sizes differ the images are compared over the larger size, and pixels outside either image differ.
+/// - `p_a`: The expected image.
+/// - `p_b`: The actual image.
+/// - `p_threshold`: The largest perceived color difference, from 0 to 255, at which pixels still match.
+pub fn diff(p_a: &Image, p_b: &Image, p_threshold: u8) -> (Image, u64) {
+  let (a_width, a_height) = p_a.dimensions::<usize>();
+  let (b_width, b_height) = p_b.dimensions::<usize>();
+  let (width, height) = (a_width.max(b_width), a_height.max(b_height));
+  let a = Pixels::new(p_a);
+  let b = Pixels::new(p_b);
+  // Compare squared YIQ deltas so the threshold scales linearly with the perceived difference.
+  let max_delta = MAX_DELTA * (p_threshold as f32 / 255.0).powi(2);
+
+  let mut output = vec![0u8; width * height * 4];
+  let mut count = 0u64;
+  for y in 0..height {
+    for x in 0..width {
+      let (pa, pb) = (a.get(x as isize, y as isize), b.get(x as isize, y as isize));
+      let color = match (pa, pb) {
+        (Some(pa), Some(pb)) if color_delta(pa, pb, false) <= max_delta => {
+          let gray = (255.0 + (luma(pa) - 255.0) * DIM_ALPHA) as u8;
+          [gray, gray, gray, 255]
+        }
+        (Some(_), Some(_)) if a.is_antialiased(x, y, &b) || b.is_antialiased(x, y, &a) => ANTIALIASED_COLOR,
+        _ => {
+          count += 1;
+          DIFF_COLOR
+        }
+      };
+      let i = (y * width + x) * 4;
+      output[i..i + 4].copy_from_slice(&color);
+    }
+  }
+
+  let mut image = Image::new(width as u32, height as u32);
+  image.set_rgba_owned(output);
+  (image, count)
+}
+
+/// Bounds checked access to the pixels of an image.
+struct Pixels<'a> {
+  data: &'a [u8],
+  width: usize,
+  height: usize,
+}
+
+impl<'a> Pixels<'a> {
+  fn new(p_image: &'a Image) -> Self {
+    let (width, height) = p_image.dimensions::<usize>();
+    Pixels {
+      data: p_image.rgba(),
+      width,
+      height,
+    }
+  }
+
+  fn get(&self, p_x: isize, p_y: isize) -> Option<&'a [u8]> {
+    if p_x < 0 || p_y < 0 || p_x as usize >= self.width || p_y as usize >= self.height {
+      return None;
+    }
+    let i = (p_y as usize * self.width + p_x as usize) * 4;
+    Some(&self.data[i..i + 4])
+  }
+
+  /// The pixels around a position that lie inside the image.
+  fn neighbors(&self, p_x: usize, p_y: usize) -> impl Iterator<Item = (usize, usize, &'a [u8])> + '_ {
+    (-1isize..=1)
+      .flat_map(|dy| (-1isize..=1).map(move |dx| (dx, dy)))
+      .filter(|&(dx, dy)| dx != 0 || dy != 0)
+      .filter_map(move |(dx, dy)| {
+        let (x, y) = (p_x as isize + dx, p_y as isize + dy);
+        self.get(x, y).map(|pixel| (x as usize, y as usize, pixel))
+      })
+  }
+
+  /// Whether the pixel has more than two identical neighbors. Pixels on the edge count the outside as one.
+  fn has_many_siblings(&self, p_x: usize, p_y: usize) -> bool {
+    let Some(pixel) = self.get(p_x as isize, p_y as isize) else {
+      return false;
+    };
+    let on_edge = p_x == 0 || p_y == 0 || p_x + 1 >= self.width || p_y + 1 >= self.height;
+    let equal = self.neighbors(p_x, p_y).filter(|(_, _, other)| *other == pixel).count();
+    equal + on_edge as usize > 2
+  }
+
+  /// Whether the pixel looks like an anti-aliased edge: it has both darker and brighter neighbors, few identical
+  /// ones, and its darkest or brightest neighbor belongs to a flat area in both images.
+  fn is_antialiased(&self, p_x: usize, p_y: usize, p_other: &Pixels) -> bool {
+    let Some(pixel) = self.get(p_x as isize, p_y as isize) else {
+      return false;
+    };
+    let on_edge = p_x == 0 || p_y == 0 || p_x + 1 >= self.width || p_y + 1 >= self.height;
+    let mut zeroes = on_edge as usize;
+    let (mut min, mut max) = (0.0f32, 0.0f32);
+    // Deltas are positive toward darker neighbors.
+    let (mut brightest, mut darkest) = ((0, 0), (0, 0));
+    for (x, y, neighbor) in self.neighbors(p_x, p_y) {
+      let delta = color_delta(pixel, neighbor, true);
+      if delta == 0.0 {
+        zeroes += 1;
+        if zeroes > 2 {
+          return false;
+        }
+      } else if delta < min {
+        min = delta;
+        brightest = (x, y);
+      } else if delta > max {
+        max = delta;
+        darkest = (x, y);
+      }
+    }
+    if min == 0.0 || max == 0.0 {
+      return false;
+    }
+    (self.has_many_siblings(darkest.0, darkest.1) && p_other.has_many_siblings(darkest.0, darkest.1))
+      || (self.has_many_siblings(brightest.0, brightest.1) && p_other.has_many_siblings(brightest.0, brightest.1))
+  }
+}
+
+/// The color of a pixel blended onto white.
+fn blend_onto_white(p_pixel: &[u8]) -> [f32; 3] {
+  let alpha = p_pixel[3] as f32 / 255.0;
+  [0, 1, 2].map(|c| 255.0 + (p_pixel[c] as f32 - 255.0) * alpha)
+}
+
+/// The brightness of a pixel blended onto white.
+fn luma(p_pixel: &[u8]) -> f32 {
+  brightness(blend_onto_white(p_pixel))
+}
+
+/// The Y (brightness) component of a color in the YIQ color space.
+fn brightness(p_color: [f32; 3]) -> f32 {
+  0.2988953 * p_color[0] + 0.5866225 * p_color[1] + 0.11448223 * p_color[2]
+}
+
+/// The perceived difference between two colors in the YIQ color space. When `p_luma_only` is set only the
+/// brightness difference is returned, which is positive when the first color is brighter.
+fn color_delta(p_a: &[u8], p_b: &[u8], p_luma_only: bool) -> f32 {
+  if p_a == p_b {
+    return 0.0;
+  }
+  let (a, b) = (blend_onto_white(p_a), blend_onto_white(p_b));
+  let dy = brightness(a) - brightness(b);
+  if p_luma_only {
+    return dy;
+  }
+  let i = |c: [f32; 3]| 0.59597799 * c[0] - 0.2741761 * c[1] - 0.3218019 * c[2];
+  let q = |c: [f32; 3]| 0.21147017 * c[0] - 0.5226171 * c[1] + 0.31114694 * c[2];
+  let (di, dq) = (i(a) - i(b), q(a) - q(b));
+  0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use primitives::Color;
+
+  #[test]
+  fn identical_images_have_no_differences() {
+    let mut image = Image::new_from_color(16, 16, Color::from_rgba(30, 90, 200, 255));
+    image.set_pixel(5, 5, (255, 255, 255, 128));
+    let (highlight, count) = diff(&image, &image.clone(), 0);
+    assert_eq!(count, 0);
+    assert_eq!(highlight.dimensions::<u32>(), (16, 16));
+    assert!(
+      highlight
+        .rgba()
+        .chunks_exact(4)
+        .all(|pixel| pixel[0] == pixel[1] && pixel[1] == pixel[2])
+    );
+  }
+
+  #[test]
+  fn changed_pixels_are_counted_and_highlighted() {
+    let a = Image::new_from_color(16, 16, Color::from_rgba(255, 255, 255, 255));
+    let mut b = a.clone();
+    for x in 4..8 {
+      b.set_pixel(x, 10, (0, 0, 0, 255));
+    }
+    b.set_pixel(1, 1, (250, 250, 250, 255));
+    let (highlight, count) = diff(&a, &b, 25);
+    // The slightly lighter pixel is within the threshold.
+    assert_eq!(count, 4);
+    assert_eq!(highlight.get_pixel(5, 10), Some((255, 0, 0, 255)));
+    assert_eq!(diff(&a, &b, 0).1, 5);
+
+    let (highlight, count) = diff(&a, &Image::new_from_color(16, 18, Color::from_rgba(255, 255, 255, 255)), 0);
+    assert_eq!((highlight.dimensions::<u32>(), count), ((16, 18), 32));
+  }
+
+  #[test]
+  fn shifted_edges_are_treated_as_antialiasing() {
+    // A hard edge from black to white, and the same edge with a gray transition pixel.
+    let mut a = Image::new_from_color(12, 12, Color::from_rgba(255, 255, 255, 255));
+    for y in 0..12 {
+      for x in 0..6 {
+        a.set_pixel(x, y, (0, 0, 0, 255));
+      }
+    }
+    let mut b = a.clone();
+    for y in 0..12 {
+      b.set_pixel(6, y, (128, 128, 128, 255));
+    }
+    let (highlight, count) = diff(&a, &b, 25);
+    assert_eq!(count, 0);
+    assert_eq!(highlight.get_pixel(6, 6), Some((255, 255, 0, 255)));
+  }
+}
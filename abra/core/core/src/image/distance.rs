@@ -0,0 +1,192 @@
+//! Distance fields, and the distance transforms and outlines built on them.
+
+use crate::{Color, Image};
+use primitives::Image as PrimitiveImage;
+use rayon::prelude::*;
+
+/// Trait for computing distance transforms.
+pub trait DistanceTransform {
+  /// Computes how far each pixel is from the shape, as a grayscale distance field.
+  /// - `p_threshold`: The smallest alpha at which a pixel is part of the shape.
+  fn distance_transform(&self, p_threshold: u8) -> Image;
+}
+
+/// Computes the distance in pixels from each pixel to the nearest pixel that is inside a shape.
+/// Pixels inside the shape are 0 away, and every pixel is infinitely far away when nothing is inside.
+/// - `p_inside`: Whether each pixel is inside the shape, row by row.
+/// - `p_width`: The width of the shape in pixels.
+/// - `p_height`: The height of the shape in pixels.
+pub fn distance_field(p_inside: &[bool], p_width: usize, p_height: usize) -> Vec<f32> {
+  // The exact squared distances come from a column pass followed by a row pass.
+  let columns: Vec<Vec<f64>> = (0..p_width)
+    .into_par_iter()
+    .map(|x| {
+      let column: Vec<f64> = (0..p_height)
+        .map(|y| if p_inside[y * p_width + x] { 0.0 } else { f64::INFINITY })
+        .collect();
+      squared_distances(&column)
+    })
+    .collect();
+  let mut distances = vec![0.0; p_inside.len()];
+  distances
+    .par_chunks_mut(p_width.max(1))
+    .enumerate()
+    .for_each(|(y, row)| {
+      let column_distances: Vec<f64> = columns.iter().map(|column| column[y]).collect();
+      for (distance, squared) in row.iter_mut().zip(squared_distances(&column_distances)) {
+        *distance = squared.sqrt() as f32;
+      }
+    });
+  distances
+}
+
+/// Computes the 1D squared distance transform of a sampled function, where each output is
+/// the smallest `(q - p)² + f(p)` over all `p`, using the lower envelope of parabolas.
+fn squared_distances(p_values: &[f64]) -> Vec<f64> {
+  let sources: Vec<usize> = (0..p_values.len()).filter(|&i| p_values[i].is_finite()).collect();
+  let Some(&first) = sources.first() else {
+    return p_values.to_vec();
+  };
+
+  // The parabolas in the lower envelope and where each one starts being the lowest.
+  let mut parabolas = vec![first];
+  let mut starts = vec![f64::NEG_INFINITY, f64::INFINITY];
+  let intersection =
+    |p: usize, q: usize| ((p_values[q] + (q * q) as f64) - (p_values[p] + (p * p) as f64)) / (2.0 * (q - p) as f64);
+  for &q in &sources[1..] {
+    let mut start = intersection(parabolas[parabolas.len() - 1], q);
+    while start <= starts[parabolas.len() - 1] {
+      parabolas.pop();
+      start = intersection(parabolas[parabolas.len() - 1], q);
+    }
+    starts.truncate(parabolas.len());
+    starts.push(start);
+    parabolas.push(q);
+    starts.push(f64::INFINITY);
+  }
+
+  let mut k = 0;
+  (0..p_values.len())
+    .map(|q| {
+      while starts[k + 1] < q as f64 {
+        k += 1;
+      }
+      let p = parabolas[k];
+      (q as f64 - p as f64).powi(2) + p_values[p]
+    })
+    .collect()
+}
+
+/// Whether each pixel's alpha is at least the threshold, row by row.
+fn shape_pixels(p_image: &Image, p_threshold: u8) -> Vec<bool> {
+  p_image
+    .rgba()
+    .chunks_exact(4)
+    .map(|pixel| pixel[3] >= p_threshold)
+    .collect()
+}
+
+/// Computes the exact distance from every pixel to the nearest pixel of the shape, as an opaque grayscale image.
+/// Pixels of the shape are black, and every other pixel is one gray level brighter per pixel of distance, up to white
+/// at 255 pixels or more. Thresholding the result gives the outline of the shape at any width.
+/// - `p_image`: The image whose shape is measured.
+/// - `p_threshold`: The smallest alpha at which a pixel is part of the shape.
+pub fn distance_transform(p_image: &Image, p_threshold: u8) -> Image {
+  let (width, height) = p_image.dimensions::<usize>();
+  let distances = distance_field(&shape_pixels(p_image, p_threshold), width, height);
+  let pixels = distances
+    .iter()
+    .flat_map(|distance| {
+      let level = distance.round().min(255.0) as u8;
+      [level, level, level, 255]
+    })
+    .collect();
+  let mut image = Image::new(width as u32, height as u32);
+  image.set_rgba_owned(pixels);
+  image
+}
+
+/// Draws an outline of the given width around the shape of the image, where the shape is every pixel with an alpha
+/// of at least half. The returned image holds only the outline, with anti-aliased edges, so it can be placed under
+/// or over the original.
+/// - `p_image`: The image to outline.
+/// - `p_width`: The width of the outline in pixels.
+/// - `p_color`: The color of the outline.
+pub fn outline(p_image: &Image, p_width: f32, p_color: Color) -> Image {
+  let (width, height) = p_image.dimensions::<usize>();
+  let distances = distance_field(&shape_pixels(p_image, 128), width, height);
+  let pixels = distances
+    .iter()
+    .flat_map(|&distance| {
+      // The ring starts next to the shape and fades out over the last pixel.
+      let coverage = if distance == 0.0 {
+        0.0
+      } else {
+        (p_width + 1.0 - distance).clamp(0.0, 1.0)
+      };
+      [
+        p_color.r,
+        p_color.g,
+        p_color.b,
+        (p_color.a as f32 * coverage).round() as u8,
+      ]
+    })
+    .collect();
+  let mut image = Image::new(width as u32, height as u32);
+  image.set_rgba_owned(pixels);
+  image
+}
+
+impl DistanceTransform for PrimitiveImage {
+  fn distance_transform(&self, p_threshold: u8) -> Image {
+    crate::image::distance::distance_transform(self, p_threshold)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn distances_to_the_nearest_inside_pixel() {
+    let mut inside = vec![false; 25];
+    inside[12] = true;
+    let distances = distance_field(&inside, 5, 5);
+    assert_eq!(distances[12], 0.0);
+    assert_eq!(distances[10], 2.0);
+    assert!((distances[0] - 8f32.sqrt()).abs() < 1e-6);
+    assert!(
+      distance_field(&[false; 4], 2, 2)
+        .iter()
+        .all(|distance| distance.is_infinite())
+    );
+  }
+
+  #[test]
+  fn centered_dot_gives_radial_rings() {
+    let mut image = Image::new(21, 21);
+    image.set_pixel(10, 10, (255, 255, 255, 255));
+    let field = image.distance_transform(128);
+    let level = |x: u32, y: u32| field.get_pixel(x, y).unwrap().0;
+    assert_eq!(level(10, 10), 0);
+    // Pixels at the same distance share a level, whatever their direction.
+    assert_eq!([level(15, 10), level(10, 5), level(13, 14), level(6, 7)], [5, 5, 5, 5]);
+    assert_eq!(level(0, 0), 14);
+    assert!(field.rgba().chunks_exact(4).all(|pixel| pixel[3] == 255));
+  }
+
+  #[test]
+  fn outline_surrounds_the_shape() {
+    let mut image = Image::new(21, 21);
+    for y in 8..13 {
+      for x in 8..13 {
+        image.set_pixel(x, y, (0, 0, 255, 255));
+      }
+    }
+    let ring = outline(&image, 2.0, Color::from_rgba(255, 0, 0, 255));
+    assert_eq!(ring.get_pixel(10, 10), Some((255, 0, 0, 0)));
+    assert_eq!(ring.get_pixel(7, 10), Some((255, 0, 0, 255)));
+    assert_eq!(ring.get_pixel(6, 10), Some((255, 0, 0, 255)));
+    assert_eq!(ring.get_pixel(5, 10).unwrap().3, 0);
+  }
+}
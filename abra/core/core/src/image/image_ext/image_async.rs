@@ -0,0 +1,100 @@
+use std::future::Future;
+
+use crate::fs::{EncodeError, ImageFormat, WriterOptions, encode_image};
+use primitives::Image as PrimitiveImage;
+
+/// Trait providing non-blocking versions of the `CoreImageFsExt` save and encode methods for async runtimes.
+///
+/// Encoding a large image can take long enough to stall every other task on a runtime thread, so the work is moved
+/// to Tokio's blocking thread pool. The image is copied before the future is returned, so it can be changed or
+/// dropped while the work runs. The futures must be awaited inside a Tokio runtime.
+pub trait CoreImageAsyncExt {
+  /// Saves the image to the specified file path without blocking the async runtime.
+  /// The format is taken from the file extension, and the bytes are identical to what `save` writes. Missing parent
+  /// directories are created.
+  /// - `file`: The file path to save the image to.
+  /// - `options`: Optional writer options.
+  fn save_async(
+    &self, file: impl Into<String>, options: impl Into<Option<WriterOptions>>,
+  ) -> impl Future<Output = Result<(), EncodeError>> + Send + 'static;
+  /// Encodes the image into memory without blocking the async runtime.
+  /// The bytes are identical to what `encode` returns for the same format and options.
+  /// - `format`: The output format.
+  /// - `options`: Optional writer options.
+  fn encode_async(
+    &self, format: ImageFormat, options: impl Into<Option<WriterOptions>>,
+  ) -> impl Future<Output = Result<Vec<u8>, EncodeError>> + Send + 'static;
+}
+
+/// Runs a job on the blocking thread pool, reporting a panic in the job as an encoding error.
+async fn run_blocking<T: Send + 'static>(
+  p_job: impl FnOnce() -> Result<T, EncodeError> + Send + 'static,
+) -> Result<T, EncodeError> {
+  tokio::task::spawn_blocking(p_job)
+    .await
+    .map_err(|error| EncodeError::Encoding(error.to_string()))?
+}
+
+impl CoreImageAsyncExt for PrimitiveImage {
+  fn save_async(
+    &self, file: impl Into<String>, options: impl Into<Option<WriterOptions>>,
+  ) -> impl Future<Output = Result<(), EncodeError>> + Send + 'static {
+    let file = file.into();
+    let options = options.into();
+    let image = self.clone();
+    async move {
+      let format = ImageFormat::from_path(&file).ok_or_else(|| EncodeError::UnsupportedFormat(file.clone()))?;
+      // Like the synchronous writers, create the folders the file goes in.
+      if let Some(parent) = std::path::Path::new(&file)
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+      {
+        tokio::fs::create_dir_all(parent)
+          .await
+          .map_err(|error| EncodeError::Io(error.to_string()))?;
+      }
+      run_blocking(move || {
+        let bytes = encode_image(&image, format, &options)?;
+        std::fs::write(&file, bytes).map_err(|error| EncodeError::Io(error.to_string()))
+      })
+      .await
+    }
+  }
+
+  fn encode_async(
+    &self, format: ImageFormat, options: impl Into<Option<WriterOptions>>,
+  ) -> impl Future<Output = Result<Vec<u8>, EncodeError>> + Send + 'static {
+    let options = options.into();
+    let image = self.clone();
+    run_blocking(move || encode_image(&image, format, &options))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::image::image_ext::CoreImageFsExt;
+
+  #[test]
+  fn async_results_match_sync_results() {
+    let mut image = PrimitiveImage::new(32, 16);
+    image.set_pixel(3, 4, (200, 100, 50, 255));
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let folder = std::env::temp_dir().join("abra_save_async");
+    let _ = std::fs::remove_dir_all(&folder);
+    let path = folder.join("nested").join("image.png").to_string_lossy().into_owned();
+
+    let (encoded, saved, unsupported) = runtime.block_on(async {
+      (
+        image.encode_async(ImageFormat::Png, None).await,
+        image.save_async(path.clone(), None).await,
+        image.save_async("image.tiff", None).await,
+      )
+    });
+    assert_eq!(encoded, image.encode(ImageFormat::Png, None));
+    assert_eq!(saved, Ok(()));
+    assert_eq!(std::fs::read(&path).unwrap(), encoded.unwrap());
+    assert!(matches!(unsupported, Err(EncodeError::UnsupportedFormat(_))));
+    let _ = std::fs::remove_dir_all(&folder);
+  }
+}
@@ -1,25 +1,57 @@
-use crate::fs::WriterOptions;
 use crate::fs::file_info::FileInfo;
+#[cfg(feature = "heic")]
+use crate::fs::readers::heic::read_heic;
+#[cfg(feature = "jxl")]
+use crate::fs::readers::jxl::read_jxl;
 use crate::fs::readers::svg::read_svg;
-use crate::fs::readers::{gif::read_gif, jpeg::read_jpg, png::read_png, webp::read_webp};
-use crate::fs::writers::{gif::write_gif, jpeg::write_jpg, png::write_png, webp::write_webp};
+use crate::fs::readers::{gif::read_gif, jpeg::read_jpg, png::read_png, pnm::read_pnm, webp::read_webp};
+use crate::fs::writers::{
+  dds::write_dds, gif::write_gif, jpeg::write_jpg, ktx2::write_ktx2, png::write_png, pnm::write_pnm, webp::write_webp,
+};
+use crate::fs::{EncodeError, ImageFormat, WriterOptions, encode_image};
 use primitives::Image as PrimitiveImage;
 
+/// Stands in for the HEIC reader when the `heic` feature, which needs the system libheif, is disabled.
+#[cfg(not(feature = "heic"))]
+fn read_heic(_file: &str) -> Result<FileInfo, String> {
+  Err("Reading HEIC and HEIF images requires the `heic` feature".to_string())
+}
+
+/// Stands in for the JPEG XL reader when the `jxl` feature is disabled.
+#[cfg(not(feature = "jxl"))]
+fn read_jxl(_file: &str) -> Result<FileInfo, String> {
+  Err("Reading JPEG XL images requires the `jxl` feature".to_string())
+}
+
 /// Trait providing core-level convenience methods for `Image` (IO methods that used to be inherent).
 /// Implemented for `primitives::Image` so callers can use `image.open()` and `image.save()`.
 pub trait CoreImageFsExt {
   /// Opens an image from the specified file path, replacing the current image data.
   /// - `file`: The file path to load the image from.
   fn open(&mut self, file: impl Into<String>);
+  /// Opens an image from the specified file path, returning an error instead of panicking
+  /// when the file is missing, unsupported or cannot be decoded.
+  /// - `file`: The file path to load the image from.
+  fn try_open(&mut self, file: impl Into<String>) -> Result<(), String>;
   /// Saves the image to the specified file path.
   /// - `file`: The file path to save the image to.
   /// - `options`: Optional writer options.
   fn save(&self, file: impl Into<String>, options: impl Into<Option<WriterOptions>>);
+  /// Encodes the image into memory instead of a file.
+  /// The bytes are identical to what `save` writes for the same format and options.
+  /// - `format`: The output format.
+  /// - `options`: Optional writer options.
+  fn encode(&self, format: ImageFormat, options: impl Into<Option<WriterOptions>>) -> Result<Vec<u8>, EncodeError>;
   /// Creates a new Image by loading it from the specified file path.
   /// - `file`: The file path to load the image from.
   fn new_from_path(file: impl Into<String>) -> Self
   where
     Self: Sized;
+  /// Creates a new Image by loading it from the specified file path, returning an error if it cannot be read.
+  /// - `file`: The file path to load the image from.
+  fn try_new_from_path(file: impl Into<String>) -> Result<Self, String>
+  where
+    Self: Sized;
 }
 
 impl CoreImageFsExt for PrimitiveImage {
@@ -29,39 +61,65 @@ impl CoreImageFsExt for PrimitiveImage {
     img
   }
 
+  fn try_new_from_path(file: impl Into<String>) -> Result<Self, String> {
+    let mut img = PrimitiveImage::new(0u32, 0u32);
+    img.try_open(file)?;
+    Ok(img)
+  }
+
   fn open(&mut self, file: impl Into<String>) {
+    if let Err(error) = self.try_open(file) {
+      panic!("{}", error);
+    }
+  }
+
+  fn try_open(&mut self, file: impl Into<String>) -> Result<(), String> {
     let file = file.into();
     let info: FileInfo;
     if file.ends_with(".jpg") || file.ends_with(".jpeg") {
-      info = read_jpg(&file).unwrap();
+      info = read_jpg(&file)?;
     } else if file.ends_with(".webp") {
-      info = read_webp(&file).unwrap();
+      info = read_webp(&file)?;
     } else if file.ends_with(".png") {
-      info = read_png(&file).unwrap();
+      info = read_png(&file)?;
     } else if file.ends_with(".gif") {
-      info = read_gif(&file).unwrap();
+      info = read_gif(&file)?;
     } else if file.ends_with(".svg") {
-      info = read_svg(&file).unwrap();
+      info = read_svg(&file)?;
+    } else if file.ends_with(".ppm") || file.ends_with(".pgm") || file.ends_with(".pbm") {
+      info = read_pnm(&file)?;
+    } else if file.ends_with(".heic") || file.ends_with(".heif") {
+      info = read_heic(&file)?;
+    } else if file.ends_with(".jxl") {
+      info = read_jxl(&file)?;
     } else {
-      panic!("Attempting to open unsupported file format");
+      return Err("Attempting to open unsupported file format".to_string());
     }
 
     self.set_new_pixels(&info.pixels, info.width, info.height);
+    Ok(())
   }
 
   fn save(&self, file: impl Into<String>, options: impl Into<Option<WriterOptions>>) {
     let options = options.into();
     let file = file.into();
-    if file.ends_with(".jpg") || file.ends_with(".jpeg") {
-      write_jpg(&file, &self, &options).unwrap();
-    } else if file.ends_with(".webp") {
-      write_webp(&file, &self).unwrap();
-    } else if file.ends_with(".png") {
-      write_png(&file, &self, &options).unwrap();
-    } else if file.ends_with(".gif") {
-      write_gif(&file, &self, &options).unwrap();
-    } else {
-      panic!("Attempting to save unsupported file format");
+    match ImageFormat::from_path(&file) {
+      Some(ImageFormat::Jpeg) => {
+        write_jpg(&file, self, &options).unwrap();
+      }
+      Some(ImageFormat::WebP) => {
+        write_webp(&file, self, &options).unwrap();
+      }
+      Some(ImageFormat::Png) => write_png(&file, self, &options).unwrap(),
+      Some(ImageFormat::Gif) => write_gif(&file, self, &options).unwrap(),
+      Some(ImageFormat::Ppm | ImageFormat::Pgm | ImageFormat::Pbm) => write_pnm(&file, self, &options).unwrap(),
+      Some(ImageFormat::Dds) => write_dds(&file, self, &options).unwrap(),
+      Some(ImageFormat::Ktx2) => write_ktx2(&file, self, &options).unwrap(),
+      None => panic!("Attempting to save unsupported file format"),
     }
   }
+
+  fn encode(&self, format: ImageFormat, options: impl Into<Option<WriterOptions>>) -> Result<Vec<u8>, EncodeError> {
+    encode_image(self, format, &options.into())
+  }
 }
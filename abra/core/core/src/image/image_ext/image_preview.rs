@@ -0,0 +1,118 @@
+use std::fmt::Write;
+
+use crate::transform::resize;
+use primitives::{Color, Image as PrimitiveImage};
+
+/// Characters from dark to bright, used to draw an image as text.
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// Trait providing text previews of an image, for eyeballing results in a terminal, e.g. over SSH.
+///
+/// The image is shrunk to the requested number of columns first. Terminal cells are about twice as tall as they
+/// are wide, so the previews keep the image's aspect ratio by using one line per two rows of pixels.
+pub trait CoreImagePreviewExt {
+  /// Draws the image as ASCII art, mapping the luminance of each cell to a character.
+  /// Bright pixels use dense characters, so the preview reads best on a dark terminal. Transparent pixels are blank.
+  /// - `width`: The number of columns.
+  fn to_ascii(&self, width: u32) -> String;
+  /// Draws the image with 24-bit ANSI colors, packing two rows of pixels into each line with half blocks.
+  /// Transparent pixels are drawn over black. Needs a terminal with true color support.
+  /// - `width`: The number of columns.
+  fn to_ansi_truecolor(&self, width: u32) -> String;
+}
+
+/// Shrinks a copy of the image to the preview width. The height is scaled to match, then halved when each character
+/// covers two rows of pixels. Returns `None` for empty images.
+fn preview_image(p_image: &PrimitiveImage, p_width: u32, p_rows_per_line: u32) -> Option<PrimitiveImage> {
+  let (width, height) = p_image.dimensions::<u32>();
+  if width == 0 || height == 0 || p_width == 0 {
+    return None;
+  }
+  let scaled_height = height as f32 * p_width as f32 / width as f32;
+  let lines = (scaled_height / 2.0).round().max(1.0) as u32;
+  let mut preview = p_image.clone();
+  resize(&mut preview, p_width, lines * p_rows_per_line, None);
+  Some(preview)
+}
+
+impl CoreImagePreviewExt for PrimitiveImage {
+  fn to_ascii(&self, width: u32) -> String {
+    let Some(preview) = preview_image(self, width, 1) else {
+      return String::new();
+    };
+    preview
+      .rgba()
+      .chunks_exact(width as usize * 4)
+      .map(|row| {
+        row
+          .chunks_exact(4)
+          .map(|p| {
+            let color = Color::from_rgba(p[0], p[1], p[2], p[3]);
+            let brightness = color.luminance() * p[3] as f32 / 255.0;
+            let index = (brightness * (ASCII_RAMP.len() - 1) as f32).round() as usize;
+            ASCII_RAMP[index.min(ASCII_RAMP.len() - 1)] as char
+          })
+          .collect::<String>()
+      })
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+
+  fn to_ansi_truecolor(&self, width: u32) -> String {
+    let Some(preview) = preview_image(self, width, 2) else {
+      return String::new();
+    };
+    let over_black = |p: &[u8]| {
+      let alpha = p[3] as u16;
+      let channel = |c: u8| (c as u16 * alpha / 255) as u8;
+      (channel(p[0]), channel(p[1]), channel(p[2]))
+    };
+    let row_bytes = width as usize * 4;
+    let mut out = String::new();
+    for (line, rows) in preview.rgba().chunks_exact(row_bytes * 2).enumerate() {
+      if line > 0 {
+        out.push('\n');
+      }
+      let (top, bottom) = rows.split_at(row_bytes);
+      for (upper, lower) in top.chunks_exact(4).zip(bottom.chunks_exact(4)) {
+        let (fr, fg, fb) = over_black(upper);
+        let (br, bg, bb) = over_black(lower);
+        // The upper half block takes the foreground color and the rest of the cell the background color.
+        let _ = write!(out, "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m\u{2580}", fr, fg, fb, br, bg, bb);
+      }
+      out.push_str("\x1b[0m");
+    }
+    out
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn ascii_preview_keeps_the_aspect_ratio() {
+    let mut image = PrimitiveImage::new_from_color(200, 100, Color::from_rgba(0, 0, 0, 255));
+    for y in 0..100 {
+      for x in 100..200 {
+        image.set_pixel(x, y, (255, 255, 255, 255));
+      }
+    }
+    let ascii = image.to_ascii(40);
+    let lines: Vec<&str> = ascii.lines().collect();
+    // 40 columns cover 20 rows of pixels, drawn as 10 lines.
+    assert_eq!(lines.len(), 10);
+    assert!(lines.iter().all(|line| line.len() == 40));
+    assert!(lines[5].starts_with(' ') && lines[5].ends_with('@'), "{}", lines[5]);
+  }
+
+  #[test]
+  fn ansi_preview_packs_two_rows_per_line() {
+    let image = PrimitiveImage::new_from_color(30, 60, Color::from_rgba(255, 0, 0, 255));
+    let ansi = image.to_ansi_truecolor(10);
+    assert_eq!(ansi.lines().count(), 10);
+    assert_eq!(ansi.matches('\u{2580}').count(), 100);
+    assert!(ansi.starts_with("\x1b[38;2;255;0;0m\x1b[48;2;255;0;0m"));
+    assert!(PrimitiveImage::new(0, 0).to_ascii(10).is_empty());
+  }
+}
@@ -1,11 +1,21 @@
 mod image_area;
+#[cfg(feature = "tokio")]
+mod image_async;
 mod image_ext;
+mod image_preview;
 mod image_provider;
 mod image_ref;
 mod image_size;
+#[cfg(feature = "svg")]
+mod image_svg;
 
 pub use image_area::*;
+#[cfg(feature = "tokio")]
+pub use image_async::*;
 pub use image_ext::*;
+pub use image_preview::*;
 pub use image_provider::*;
 pub use image_ref::*;
 pub use image_size::*;
+#[cfg(feature = "svg")]
+pub use image_svg::*;
@@ -1,7 +1,10 @@
 pub mod apply_area;
+pub mod compare;
+pub mod distance;
 pub mod gpu_op;
 pub mod gpu_registry;
 pub mod image_ext;
+pub mod progress;
 
 // Re-export the primitives Image type at `abra_core::Image` so existing imports continue to work.
 pub use primitives::Image;
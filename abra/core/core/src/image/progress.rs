@@ -0,0 +1,151 @@
+//! Progress reporting for long-running operations.
+//!
+//! A `ProgressReporter` owns the callback and hands out `Progress` handles. A handle covers a range of the overall
+//! progress and can be split into parts, e.g. one per area or pass, so nested steps report into their own share.
+//! Reports are throttled to steps of 5% and only ever move forward.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+/// The smallest change in progress, as a fraction of the whole, that is passed on to the callback.
+const STEP: f32 = 0.05;
+
+/// Receives the progress values of a reporter, which lets `Progress` handles stay free of the callback type.
+trait Sink: Sync {
+  fn emit(&self, p_value: f32);
+}
+
+/// Owns the callback that receives the progress of an operation, from 0.0 to 1.0.
+///
+/// The callback may be invoked from any of the threads the operation runs on, but never from two at the same time,
+/// as every call is made while holding a lock. Values increase in steps of at least 5% and end with 1.0 once
+/// `finish` is called.
+pub struct ProgressReporter<F: FnMut(f32) + Send> {
+  callback: Mutex<F>,
+  /// The last reported step, so threads can skip the lock when they have nothing new to report.
+  last_step: AtomicU32,
+}
+
+impl<F: FnMut(f32) + Send> ProgressReporter<F> {
+  /// Creates a reporter that passes progress to a callback.
+  /// - `p_callback`: Called with the overall progress, from 0.0 to 1.0.
+  pub fn new(p_callback: F) -> ProgressReporter<F> {
+    ProgressReporter {
+      callback: Mutex::new(p_callback),
+      last_step: AtomicU32::new(0),
+    }
+  }
+
+  /// Gets a handle covering the whole operation.
+  pub fn progress(&self) -> Progress<'_> {
+    Progress {
+      sink: self,
+      start: 0.0,
+      end: 1.0,
+    }
+  }
+
+  /// Reports that the operation is complete.
+  pub fn finish(&self) {
+    self.emit(1.0);
+  }
+}
+
+impl<F: FnMut(f32) + Send> Sink for ProgressReporter<F> {
+  fn emit(&self, p_value: f32) {
+    // Complete progress always gets through, anything else is rounded down to a step.
+    let step = if p_value >= 1.0 {
+      (1.0 / STEP).round() as u32
+    } else {
+      (p_value.max(0.0) / STEP) as u32
+    };
+    if step <= self.last_step.load(Ordering::Relaxed) {
+      return;
+    }
+    let mut callback = self.callback.lock().unwrap();
+    // Another thread may have reported a later step while this one waited for the lock.
+    if self.last_step.fetch_max(step, Ordering::Relaxed) < step {
+      callback((step as f32 * STEP).min(1.0));
+    }
+  }
+}
+
+/// A handle for reporting the progress of one part of an operation.
+#[derive(Clone, Copy)]
+pub struct Progress<'a> {
+  sink: &'a dyn Sink,
+  /// The overall progress at the start of this part.
+  start: f32,
+  /// The overall progress at the end of this part.
+  end: f32,
+}
+
+impl<'a> Progress<'a> {
+  /// Reports how far this part is.
+  /// - `p_fraction`: The completed fraction of this part, from 0.0 to 1.0.
+  pub fn report(&self, p_fraction: f32) {
+    self
+      .sink
+      .emit(self.start + (self.end - self.start) * p_fraction.clamp(0.0, 1.0));
+  }
+
+  /// Splits this part into equal shares and gets the handle of one of them.
+  /// - `p_index`: The index of the share.
+  /// - `p_count`: The number of shares.
+  pub fn part(&self, p_index: usize, p_count: usize) -> Progress<'a> {
+    let share = (self.end - self.start) / p_count.max(1) as f32;
+    Progress {
+      sink: self.sink,
+      start: self.start + share * p_index as f32,
+      end: self.start + share * (p_index + 1) as f32,
+    }
+  }
+
+  /// Gets a counter that reports this part as a number of equal units, such as rows, are completed.
+  /// - `p_total`: The number of units in this part.
+  pub fn counter(&self, p_total: usize) -> ProgressCounter<'a> {
+    ProgressCounter {
+      progress: *self,
+      done: AtomicUsize::new(0),
+      total: p_total.max(1),
+    }
+  }
+}
+
+/// Counts completed units of work, such as rows processed in parallel, and reports them as progress.
+pub struct ProgressCounter<'a> {
+  progress: Progress<'a>,
+  done: AtomicUsize,
+  total: usize,
+}
+
+impl ProgressCounter<'_> {
+  /// Marks one more unit as completed. Can be called from several threads at once.
+  pub fn tick(&self) {
+    let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+    self.progress.report(done as f32 / self.total as f32);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rayon::prelude::*;
+
+  #[test]
+  fn reports_are_throttled_and_increasing() {
+    let mut values = vec![];
+    {
+      let reporter = ProgressReporter::new(|value| values.push(value));
+      let progress = reporter.progress();
+      for index in 0..2 {
+        let counter = progress.part(index, 2).counter(1000);
+        (0..1000).into_par_iter().for_each(|_| counter.tick());
+      }
+      reporter.finish();
+    }
+    assert_eq!(values.len(), 20);
+    assert!(values.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", values);
+    assert_eq!(values.last(), Some(&1.0));
+  }
+}
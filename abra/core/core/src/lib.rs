@@ -1,40 +1,55 @@
+mod cancel;
 pub mod color;
 // mod debug;
 mod combine;
 mod fs;
 pub mod geometry;
+mod history;
 pub mod image;
 mod loader;
 pub mod settings;
 pub mod transform;
 
+pub use cancel::{CancellationToken, Cancelled};
 pub use color::*;
 pub use settings::Settings;
 pub use transform::*;
 // pub use debug::*;
 pub use combine::*;
-pub use fs::WriterOptions;
+pub use fs::{EncodeError, ImageFormat, PngFilter, TextureCompression, WriterOptions};
 // Re-export selected I/O helpers so other crates (e.g., abra wrapper) can access them
 pub use fs::file_info::FileInfo;
 // Explicitly export reader and writer functions to avoid ambiguous glob re-exports.
 pub use fs::readers::gif::read_gif;
+#[cfg(feature = "heic")]
+pub use fs::readers::heic::read_heic;
 pub use fs::readers::jpeg::read_jpg;
+#[cfg(feature = "jxl")]
+pub use fs::readers::jxl::read_jxl;
 pub use fs::readers::png::read_png;
+pub use fs::readers::pnm::read_pnm;
 pub use fs::readers::svg::read_svg;
 pub use fs::readers::webp::read_webp;
+pub use fs::writers::dds::write_dds;
 pub use fs::writers::gif::write_gif;
 pub use fs::writers::jpeg::write_jpg;
+pub use fs::writers::ktx2::write_ktx2;
 pub use fs::writers::png::write_png;
+pub use fs::writers::pnm::write_pnm;
 pub use fs::writers::webp::write_webp;
 pub use geometry::*;
+pub use history::History;
 // `image` module content moved to `primitives` crate and re-exported below.
 pub use loader::*;
 // Re-export primitives Image for workspace users. This replaces the core-defined Image type
 // so consumers can continue to use `use abra_core::Image;` with the new primitives implementation.
+pub use image::distance::DistanceTransform;
 pub use image::image_ext::ImageRef;
+pub use image::progress::{Progress, ProgressCounter, ProgressReporter};
 pub use primitives::Channels;
 pub use primitives::Color;
 pub use primitives::Image;
+pub use primitives::LinearImage;
 
 // lib.rs or geometry/mod.rs (a public crate-local trait)
 pub trait FromF32 {
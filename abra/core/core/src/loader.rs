@@ -3,7 +3,10 @@
 use crate::{Image, image::image_ext::*};
 use globwalk::glob;
 use rayon::{ThreadPoolBuilder, prelude::*};
+use std::fmt::Display;
+use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// An image loader that can load images from file paths or existing Arc<Image> instances.\
 /// If loading from file paths, images are loaded in sync or in parallel based on the method called.
@@ -34,61 +37,214 @@ pub enum ImageLoader<'a> {
 
 impl<'a> ImageLoader<'a> {
   /// Loads images in parallel.
+  /// Files that fail to load are collected in `LoadedImages::errors` instead of stopping the load.
   /// ```ignore
   /// let paths = vec!["path/to/image1.png", "path/to/image2.jpg"];
   /// let loader = ImageLoader::FromPaths(paths).load();
   /// ```
   pub fn load(self) -> LoadedImages {
-    let loaded_images = match self {
-      ImageLoader::FromPaths(paths) => LoadedImages {
-        images: load_images_parallel(paths),
-      },
-      ImageLoader::FromImages(images) => LoadedImages {
-        images: images.into_iter().map(|img| Arc::new(img)).collect(),
-      },
-      ImageLoader::FromFolders(folders, recursive) => {
-        let all_paths = get_paths_from_folders(folders, recursive);
-        println!("Found {} images in folders.", all_paths.len());
-        let images = load_images_parallel(all_paths.clone());
-        LoadedImages { images }
-      }
-      ImageLoader::FromGlob(patterns) => {
-        let all_paths = get_paths_from_glob(patterns);
-        println!("Found {} images from glob patterns.", all_paths.len());
-        let images = load_images_parallel(all_paths.clone());
-        LoadedImages { images }
-      }
-    };
-    loaded_images
+    ImageLoaderBuilder::new(self).load()
   }
 
   /// Load images synchronously (non-parallel).
+  /// Files that fail to load are collected in `LoadedImages::errors` instead of stopping the load.
   /// ```ignore
   /// let paths = vec!["path/to/image1.png", "path/to/image2.jpg"];
   /// let loader = ImageLoader::FromPaths(paths).load_sync();
   /// ```
   pub fn load_sync(self) -> LoadedImages {
-    match self {
-      ImageLoader::FromPaths(paths) => {
-        let images = load_images_sync(paths);
-        LoadedImages { images }
-      }
-      ImageLoader::FromImages(images) => LoadedImages {
-        images: images.into_iter().map(|img| Arc::new(img)).collect(),
-      },
-      ImageLoader::FromGlob(patterns) => {
-        let all_paths = get_paths_from_glob(patterns);
-        println!("Loaded {} images from glob patterns.", all_paths.len());
-        let images = load_images_sync(all_paths);
-        LoadedImages { images }
+    ImageLoaderBuilder::new(self).load_sync()
+  }
+
+  /// Sets the number of threads used to load images in parallel.
+  /// - `threads`: The number of threads. Values below 1 are treated as 1, which loads on the calling thread.
+  /// ```ignore
+  /// let loader = ImageLoader::FromGlob(vec!["assets/**/*.jpg"]).with_concurrency(2).load();
+  /// ```
+  pub fn with_concurrency(self, threads: usize) -> ImageLoaderBuilder<'a> {
+    ImageLoaderBuilder::new(self).with_concurrency(threads)
+  }
+
+  /// Sets a callback that is called each time a file finishes loading, whether it succeeded or not.
+  /// - `callback`: Receives the progress of the load. When loading in parallel it is called from the worker threads.
+  /// ```ignore
+  /// let loader = ImageLoader::FromGlob(vec!["assets/**/*.jpg"])
+  ///   .on_loaded(|progress| println!("{}/{} {}", progress.completed, progress.total, progress.path))
+  ///   .load();
+  /// ```
+  pub fn on_loaded(self, callback: impl Fn(&LoadProgress) + Send + Sync + 'a) -> ImageLoaderBuilder<'a> {
+    ImageLoaderBuilder::new(self).on_loaded(callback)
+  }
+
+  /// Sorts the files before they are loaded, smallest key first.
+  /// - `key`: What to sort by. See `SortKey`.
+  /// ```ignore
+  /// let loader = ImageLoader::FromGlob(vec!["photos/*.jpg"]).with_sort(SortKey::Name).load();
+  /// ```
+  pub fn with_sort(self, key: SortKey) -> ImageLoaderBuilder<'a> {
+    ImageLoaderBuilder::new(self).with_sort(key)
+  }
+
+  /// Sorts the files before they are loaded, largest key first (e.g. the most recently modified first).
+  /// - `key`: What to sort by. See `SortKey`.
+  pub fn with_sort_descending(self, key: SortKey) -> ImageLoaderBuilder<'a> {
+    ImageLoaderBuilder::new(self).with_sort_descending(key)
+  }
+
+  /// Loads at most a number of files, taken after sorting.
+  /// - `limit`: The maximum number of files to load.
+  /// ```ignore
+  /// // The 20 most recent photos.
+  /// let loader = ImageLoader::FromGlob(vec!["photos/*.jpg"])
+  ///   .with_sort_descending(SortKey::ModifiedTime)
+  ///   .with_limit(20)
+  ///   .load();
+  /// ```
+  pub fn with_limit(self, limit: usize) -> ImageLoaderBuilder<'a> {
+    ImageLoaderBuilder::new(self).with_limit(limit)
+  }
+}
+
+/// What `ImageLoader::with_sort` orders files by.
+/// The sort is stable: files with equal keys keep the order in which they were found.
+/// Files whose metadata cannot be read count as the smallest modified time or size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortKey {
+  /// The file name without its folder, compared byte by byte (so `"B.png"` comes before `"a.png"`).
+  Name,
+  /// The time the file was last modified.
+  ModifiedTime,
+  /// The size of the file in bytes.
+  Size,
+}
+
+/// The callback invoked by `ImageLoaderBuilder` after each file is loaded.
+type ProgressCallback<'a> = Box<dyn Fn(&LoadProgress) + Send + Sync + 'a>;
+
+/// An `ImageLoader` with loading options, created by `ImageLoader::with_concurrency` or `ImageLoader::on_loaded`.
+/// ```ignore
+/// let loader = ImageLoader::FromFolders(vec!["assets"], true)
+///   .with_concurrency(2)
+///   .on_loaded(|progress| println!("Loaded {} of {}", progress.completed, progress.total))
+///   .load();
+/// for error in loader.errors() {
+///   println!("{}", error);
+/// }
+/// ```
+pub struct ImageLoaderBuilder<'a> {
+  /// Where the images come from.
+  source: ImageLoader<'a>,
+  /// The number of loading threads, or `None` to pick one based on the number of files.
+  concurrency: Option<usize>,
+  /// Called after each file is loaded.
+  on_loaded: Option<ProgressCallback<'a>>,
+  /// The sort key and whether it is descending.
+  sort: Option<(SortKey, bool)>,
+  /// The maximum number of files to load.
+  limit: Option<usize>,
+}
+
+impl<'a> ImageLoaderBuilder<'a> {
+  /// Creates a builder with the default options for a source.
+  /// - `source`: Where the images come from.
+  pub fn new(source: ImageLoader<'a>) -> Self {
+    ImageLoaderBuilder {
+      source,
+      concurrency: None,
+      on_loaded: None,
+      sort: None,
+      limit: None,
+    }
+  }
+
+  /// Sets the number of threads used to load images in parallel.
+  /// - `threads`: The number of threads. Values below 1 are treated as 1, which loads on the calling thread.
+  pub fn with_concurrency(mut self, threads: usize) -> Self {
+    self.concurrency = Some(threads.max(1));
+    self
+  }
+
+  /// Sets a callback that is called each time a file finishes loading, whether it succeeded or not.
+  /// - `callback`: Receives the progress of the load. When loading in parallel it is called from the worker threads.
+  pub fn on_loaded(mut self, callback: impl Fn(&LoadProgress) + Send + Sync + 'a) -> Self {
+    self.on_loaded = Some(Box::new(callback));
+    self
+  }
+
+  /// Sorts the files before they are loaded, smallest key first.
+  /// Has no effect when loading from existing images.
+  /// - `key`: What to sort by. See `SortKey`.
+  pub fn with_sort(mut self, key: SortKey) -> Self {
+    self.sort = Some((key, false));
+    self
+  }
+
+  /// Sorts the files before they are loaded, largest key first (e.g. the most recently modified first).
+  /// Has no effect when loading from existing images.
+  /// - `key`: What to sort by. See `SortKey`.
+  pub fn with_sort_descending(mut self, key: SortKey) -> Self {
+    self.sort = Some((key, true));
+    self
+  }
+
+  /// Loads at most a number of files, taken after sorting.
+  /// Files that fail to load still count towards the limit.
+  /// - `limit`: The maximum number of files to load.
+  pub fn with_limit(mut self, limit: usize) -> Self {
+    self.limit = Some(limit);
+    self
+  }
+
+  /// Loads the images in parallel using the configured number of threads.
+  /// Without `with_concurrency`, up to 4 threads are used.
+  pub fn load(self) -> LoadedImages {
+    let concurrency = self.concurrency;
+    self.load_with(|total| concurrency.unwrap_or(total.clamp(1, 4)))
+  }
+
+  /// Loads the images one after another on the calling thread, ignoring `with_concurrency`.
+  pub fn load_sync(self) -> LoadedImages {
+    self.load_with(|_| 1)
+  }
+
+  /// Resolves the source into file paths and loads them.
+  /// - `threads`: Returns the number of threads to use for a number of files.
+  fn load_with(self, threads: impl Fn(usize) -> usize) -> LoadedImages {
+    let limit = self.limit.unwrap_or(usize::MAX);
+    let (mut paths, mut errors) = match self.source {
+      ImageLoader::FromImages(images) => {
+        return LoadedImages {
+          images: images.into_iter().take(limit).map(Arc::new).collect(),
+          errors: vec![],
+        };
       }
+      ImageLoader::FromPaths(paths) => (paths.into_iter().map(String::from).collect(), vec![]),
       ImageLoader::FromFolders(folders, recursive) => {
         let all_paths = get_paths_from_folders(folders, recursive);
-        println!("Loaded {} images from folders.", all_paths.len());
-        let images = load_images_sync(all_paths);
-        LoadedImages { images }
+        println!("Found {} images in folders.", all_paths.len());
+        (all_paths, vec![])
       }
+      ImageLoader::FromGlob(patterns) => {
+        let (all_paths, errors) = get_paths_from_glob(patterns);
+        println!("Found {} images from glob patterns.", all_paths.len());
+        (all_paths, errors)
+      }
+    };
+
+    if let Some((key, descending)) = self.sort {
+      paths = sort_paths(paths, key, descending);
     }
+    paths.truncate(limit);
+
+    let results = load_paths(&paths, threads(paths.len()), self.on_loaded.as_ref());
+    let mut images = Vec::with_capacity(results.len());
+    for result in results {
+      match result {
+        Ok(image) => images.push(image),
+        Err(error) => errors.push(error),
+      }
+    }
+    LoadedImages { images, errors }
   }
 }
 
@@ -98,6 +254,12 @@ impl<'a> Into<LoadedImages> for ImageLoader<'a> {
   }
 }
 
+impl<'a> From<ImageLoaderBuilder<'a>> for LoadedImages {
+  fn from(loader: ImageLoaderBuilder<'a>) -> Self {
+    loader.load()
+  }
+}
+
 /// A trait for converting various types into Arc<Image>.
 pub trait IntoImageArc {
   /// Converts the implementing type into an Arc<Image>.
@@ -125,10 +287,42 @@ impl IntoImageArc for Option<Arc<Image>> {
   }
 }
 
+/// An error for a file that could not be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadError {
+  /// The path (or glob pattern) that failed.
+  pub path: String,
+  /// What went wrong.
+  pub message: String,
+}
+
+impl Display for LoadError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "Failed to load {}: {}", self.path, self.message)
+  }
+}
+
+impl std::error::Error for LoadError {}
+
+/// The progress of a load, passed to the `on_loaded` callback.
+#[derive(Debug, Clone, Copy)]
+pub struct LoadProgress<'p> {
+  /// The path of the file that just finished loading.
+  pub path: &'p str,
+  /// Whether the file loaded successfully.
+  pub succeeded: bool,
+  /// The number of files that have finished loading, including this one.
+  pub completed: usize,
+  /// The total number of files being loaded.
+  pub total: usize,
+}
+
 /// A user-friendly wrapper around loaded images.
 pub struct LoadedImages {
   /// The loaded images.
   images: Vec<Arc<Image>>,
+  /// The files that failed to load.
+  errors: Vec<LoadError>,
 }
 
 impl LoadedImages {
@@ -183,18 +377,6 @@ impl LoadedImages {
     self
   }
 
-  /// Gets an image at the specified location.
-  /// - `index`: The index of the image to retrieve.
-  /// ```ignore
-  /// let loader = ImageLoader::FromPaths(image_paths).load();
-  /// if let Some(image) = loader.at(0) {
-  ///   // Use the image
-  /// }
-  /// ```
-  pub fn at(&self, index: impl Into<u32>) -> Option<Arc<Image>> {
-    self.images.get(index.into() as usize).cloned()
-  }
-
   /// Gets all loaded images.
   /// ```ignore
   /// let loader = ImageLoader::FromPaths(image_paths).load();
@@ -221,49 +403,147 @@ impl LoadedImages {
   pub fn last(&self) -> Option<Arc<Image>> {
     self.images.last().cloned()
   }
+
+  /// Gets the image at the specified index.
+  /// - `index`: The index of the image to retrieve.
+  /// ```ignore
+  /// let loader = ImageLoader::FromPaths(image_paths).load();
+  /// if let Some(image) = loader.get(1) {
+  ///   // Use the image
+  /// }
+  /// ```
+  pub fn get(&self, index: usize) -> Option<Arc<Image>> {
+    self.images.get(index).cloned()
+  }
+
+  /// Returns an iterator over the loaded images.
+  /// ```ignore
+  /// let loader = ImageLoader::FromPaths(image_paths).load();
+  /// let widths: Vec<u32> = loader.iter().map(|image| image.dimensions::<u32>().0).collect();
+  /// ```
+  pub fn iter(&self) -> std::slice::Iter<'_, Arc<Image>> {
+    self.images.iter()
+  }
+
+  /// Gets the number of loaded images.
+  pub fn len(&self) -> usize {
+    self.images.len()
+  }
+
+  /// Checks whether there are no loaded images.
+  pub fn is_empty(&self) -> bool {
+    self.images.is_empty()
+  }
+
+  /// Returns the images that match a predicate, sharing the image data with this collection.
+  /// - `predicate`: Returns true for the images to keep.
+  /// ```ignore
+  /// let loader = ImageLoader::FromPaths(image_paths).load();
+  /// let landscapes = loader.filter(|image| {
+  ///   let (width, height) = image.dimensions::<u32>();
+  ///   width > height
+  /// });
+  /// ```
+  pub fn filter(&self, predicate: impl Fn(&Image) -> bool) -> LoadedImages {
+    LoadedImages {
+      images: self.images.iter().filter(|image| predicate(image)).cloned().collect(),
+      errors: self.errors.clone(),
+    }
+  }
+
+  /// Gets the files that failed to load, in the order they were found.
+  /// ```ignore
+  /// let loader = ImageLoader::FromGlob(vec!["assets/**/*.png"]).load();
+  /// for error in loader.errors() {
+  ///   println!("{}", error);
+  /// }
+  /// ```
+  pub fn errors(&self) -> &[LoadError] {
+    &self.errors
+  }
+
+  /// Checks whether any file failed to load.
+  pub fn has_errors(&self) -> bool {
+    !self.errors.is_empty()
+  }
 }
 
-/// Loads multiple images in parallel from file paths.
-/// - `paths` - A vector of file paths to load images from
-/// ```ignore
-/// let paths = vec!["path/to/image1.png", "path/to/image2.jpg"];
-/// let images = load_images_parallel(paths);
-/// ```
-pub fn load_images_parallel(paths: Vec<impl Into<String>>) -> Vec<Arc<Image>> {
-  // Limit concurrency to avoid I/O thrash and decoder contention.
-  // Empirically, 2-4 threads tends to be faster for mixed HDD/SSD workloads and single-threaded decoders.
-  let threads = paths.len().clamp(1, 4);
-  load_images_parallel_with_threads(paths, threads)
+impl IntoIterator for LoadedImages {
+  type Item = Arc<Image>;
+  type IntoIter = std::vec::IntoIter<Arc<Image>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.images.into_iter()
+  }
 }
-/// Loads multiple images synchronously from file paths.
-/// - `paths` - A vector of file paths to load images from
-/// ```ignore
-/// let paths = vec!["path/to/image1.png", "path/to/image2.jpg"];
-/// let images = load_images_sync(paths);
-/// ```
-pub fn load_images_sync(paths: Vec<impl Into<String>>) -> Vec<Arc<Image>> {
-  paths
-    .into_iter()
-    .map(|path| Arc::new(Image::new_from_path(path.into())))
-    .collect()
+
+impl<'a> IntoIterator for &'a LoadedImages {
+  type Item = &'a Arc<Image>;
+  type IntoIter = std::slice::Iter<'a, Arc<Image>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.images.iter()
+  }
 }
 
-/// Loads multiple images in parallel with a bounded number of threads.
-/// This often outperforms unbounded parallelism for file I/O heavy workloads on HDDs/SSDs
-/// and when decoders are single-threaded.
-pub fn load_images_parallel_with_threads(paths: Vec<impl Into<String>>, threads: usize) -> Vec<Arc<Image>> {
+/// Loads files with a number of threads, keeping the results in the same order as the paths.
+/// - `paths`: The files to load.
+/// - `threads`: The number of threads. With 1 the files are loaded on the calling thread.
+/// - `on_loaded`: Called after each file is loaded.
+fn load_paths(
+  paths: &[String], threads: usize, on_loaded: Option<&ProgressCallback>,
+) -> Vec<Result<Arc<Image>, LoadError>> {
+  let total = paths.len();
+  let completed = AtomicUsize::new(0);
+  let load_one = |path: &String| {
+    let result = Image::try_new_from_path(path.as_str())
+      .map(Arc::new)
+      .map_err(|message| LoadError {
+        path: path.clone(),
+        message,
+      });
+    if let Some(on_loaded) = on_loaded {
+      on_loaded(&LoadProgress {
+        path,
+        succeeded: result.is_ok(),
+        completed: completed.fetch_add(1, Ordering::SeqCst) + 1,
+        total,
+      });
+    }
+    result
+  };
+
+  if threads <= 1 {
+    return paths.iter().map(load_one).collect();
+  }
   let pool = ThreadPoolBuilder::new()
-    .num_threads(threads.clamp(1, 4))
+    .num_threads(threads)
     .build()
     .expect("Failed to build rayon thread pool for image loading");
+  pool.install(|| paths.par_iter().map(load_one).collect())
+}
 
-  let paths: Vec<String> = paths.into_iter().map(|p| p.into()).collect();
-  pool.install(|| {
-    paths
-      .into_par_iter()
-      .map(|path| Arc::new(Image::new_from_path(path)))
-      .collect()
-  })
+/// Stably sorts file paths by a key.
+/// - `paths`: The paths to sort.
+/// - `key`: What to sort by.
+/// - `descending`: True to put the largest key first.
+fn sort_paths(paths: Vec<String>, key: SortKey, descending: bool) -> Vec<String> {
+  fn by<K: Ord>(paths: Vec<String>, descending: bool, key: impl Fn(&Path) -> K) -> Vec<String> {
+    let mut keyed: Vec<(K, String)> = paths.into_iter().map(|path| (key(Path::new(&path)), path)).collect();
+    // `sort_by` is stable, so equal keys keep their discovery order in both directions.
+    if descending {
+      keyed.sort_by(|a, b| b.0.cmp(&a.0));
+    } else {
+      keyed.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+    keyed.into_iter().map(|(_, path)| path).collect()
+  }
+
+  match key {
+    SortKey::Name => by(paths, descending, |path| path.file_name().map(|name| name.to_os_string())),
+    SortKey::ModifiedTime => by(paths, descending, |path| path.metadata().and_then(|meta| meta.modified()).ok()),
+    SortKey::Size => by(paths, descending, |path| path.metadata().map(|meta| meta.len()).ok()),
+  }
 }
 
 fn get_paths_from_folders(folders: Vec<impl Into<String>>, recursive: bool) -> Vec<String> {
@@ -291,20 +571,179 @@ fn get_paths_from_folders(folders: Vec<impl Into<String>>, recursive: bool) -> V
   all_paths
 }
 
-fn get_paths_from_glob(patterns: Vec<impl Into<String>>) -> Vec<String> {
+/// Expands glob patterns into file paths, collecting invalid patterns and unreadable entries as errors.
+pub fn get_paths_from_glob(patterns: Vec<impl Into<String>>) -> (Vec<String>, Vec<LoadError>) {
   let mut all_paths = vec![];
+  let mut errors = vec![];
   for pattern in patterns {
     let pattern = pattern.into();
-    for entry in glob(pattern.as_str()).expect("Failed to read glob pattern") {
+    let entries = match glob(pattern.as_str()) {
+      Ok(entries) => entries,
+      Err(e) => {
+        errors.push(LoadError {
+          path: pattern,
+          message: e.to_string(),
+        });
+        continue;
+      }
+    };
+    for entry in entries {
       match entry {
-        Ok(path) => all_paths.push(path.path().to_str().unwrap().to_string()),
-        Err(e) => println!("Error reading path: {:?}", e),
+        Ok(path) => all_paths.push(path.path().to_string_lossy().into_owned()),
+        Err(e) => errors.push(LoadError {
+          path: e
+            .path()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_else(|| pattern.clone()),
+          message: e.to_string(),
+        }),
       }
     }
   }
-  all_paths
+  (all_paths, errors)
 }
 pub fn is_supported_image_extension(extension: impl Into<String>) -> bool {
   let ext = extension.into().to_lowercase();
-  matches!(ext.as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg")
+  matches!(ext.as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" | "ppm" | "pgm" | "pbm")
+    || (cfg!(feature = "heic") && matches!(ext.as_str(), "heic" | "heif"))
+    || (cfg!(feature = "jxl") && ext == "jxl")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn loaded() -> LoadedImages {
+    ImageLoader::FromImages(vec![Image::new(4, 2), Image::new(2, 2), Image::new(3, 6)]).load()
+  }
+
+  #[test]
+  fn collection_accessors() {
+    let images = loaded();
+    assert_eq!(images.len(), 3);
+    assert!(!images.is_empty());
+    assert_eq!(images.get(2).unwrap().dimensions::<u32>(), (3, 6));
+    assert!(images.get(3).is_none());
+    let widths: Vec<u32> = images.iter().map(|image| image.dimensions::<u32>().0).collect();
+    assert_eq!(widths, vec![4, 2, 3]);
+    assert_eq!((&images).into_iter().count(), 3);
+  }
+
+  #[test]
+  fn collects_errors_and_reports_progress() {
+    let dir = std::env::temp_dir().join("abra_loader_collects_errors");
+    let good = dir.join("good.png");
+    let good = good.to_str().unwrap();
+    Image::new_from_color(3, 2, crate::Color::red()).save(good, None);
+    let missing = dir.join("missing.png");
+    let missing = missing.to_str().unwrap();
+    let unsupported = dir.join("notes.txt");
+    let unsupported = unsupported.to_str().unwrap();
+
+    for threads in [1, 3] {
+      let completed = AtomicUsize::new(0);
+      let images = ImageLoader::FromPaths(vec![missing, good, unsupported])
+        .with_concurrency(threads)
+        .on_loaded(|progress| {
+          assert_eq!(progress.total, 3);
+          completed.fetch_add(1, Ordering::SeqCst);
+        })
+        .load();
+      assert_eq!(completed.load(Ordering::SeqCst), 3);
+      assert_eq!(images.len(), 1);
+      assert_eq!(images.get(0).unwrap().dimensions::<u32>(), (3, 2));
+      let failed: Vec<&str> = images.errors().iter().map(|error| error.path.as_str()).collect();
+      assert_eq!(failed, vec![missing, unsupported]);
+    }
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn sorts_and_limits_files() {
+    let dir = std::env::temp_dir().join("abra_loader_sorts_and_limits");
+    let mut paths = vec![];
+    // Written out of name order, with sizes and modified times that disagree with the names.
+    for (name, size, age) in [("b.png", 4, 30), ("c.png", 1, 10), ("a.png", 8, 20)] {
+      let path = dir.join(name).to_str().unwrap().to_string();
+      Image::new_from_color(size, size, crate::Color::blue()).save(path.as_str(), None);
+      let modified = std::time::SystemTime::now() - std::time::Duration::from_secs(age);
+      std::fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+      paths.push(path);
+    }
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    let widths = |images: LoadedImages| {
+      images
+        .iter()
+        .map(|image| image.dimensions::<u32>().0)
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(widths(ImageLoader::FromPaths(paths.clone()).with_sort(SortKey::Name).load()), vec![8, 4, 1]);
+    assert_eq!(widths(ImageLoader::FromPaths(paths.clone()).with_sort(SortKey::Size).load()), vec![1, 4, 8]);
+    let recent = ImageLoader::FromPaths(paths.clone())
+      .with_sort_descending(SortKey::ModifiedTime)
+      .with_limit(2)
+      .load();
+    assert_eq!(widths(recent), vec![1, 8]);
+    // Equal keys keep their original order.
+    let duplicated = [paths[1], paths[0], paths[1]];
+    let sorted = sort_paths(duplicated.iter().map(|path| path.to_string()).collect(), SortKey::Name, true);
+    assert_eq!(sorted, vec![paths[1], paths[1], paths[0]]);
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn filter_shares_images() {
+    let images = loaded();
+    let landscapes = images.filter(|image| {
+      let (width, height) = image.dimensions::<u32>();
+      width > height
+    });
+    assert_eq!(landscapes.len(), 1);
+    assert!(Arc::ptr_eq(&landscapes.get(0).unwrap(), &images.get(0).unwrap()));
+    assert!(images.filter(|_| false).is_empty());
+  }
+
+  #[test]
+  fn heic_is_only_loaded_with_the_heic_feature() {
+    assert!(is_supported_image_extension("PNG"));
+    assert_eq!(is_supported_image_extension("heic"), cfg!(feature = "heic"));
+    assert_eq!(is_supported_image_extension("HEIF"), cfg!(feature = "heic"));
+    if !cfg!(feature = "heic") {
+      let error = Image::try_new_from_path("missing.heic").err().unwrap();
+      assert!(error.contains("heic"), "{}", error);
+    }
+  }
+
+  #[test]
+  fn loads_jxl_files_with_the_jxl_feature() {
+    use zune_core::{bit_depth::BitDepth, colorspace::ColorSpace, options::EncoderOptions};
+
+    assert_eq!(is_supported_image_extension("JXL"), cfg!(feature = "jxl"));
+    let pixels: Vec<u8> = (0..5 * 4 * 3).map(|i| (i * 21 % 256) as u8).collect();
+    let mut bytes = vec![];
+    zune_jpegxl::JxlSimpleEncoder::new(&pixels, EncoderOptions::new(5, 4, ColorSpace::RGB, BitDepth::Eight))
+      .encode(&mut bytes)
+      .unwrap();
+    let dir = std::env::temp_dir().join("abra_loader_jxl");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("image.jxl");
+    std::fs::write(&path, bytes).unwrap();
+    let path = path.to_str().unwrap();
+
+    let images = ImageLoader::FromPaths(vec![path]).load();
+    if cfg!(feature = "jxl") {
+      let expected: Vec<u8> = pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect();
+      assert_eq!(images.get(0).unwrap().rgba(), expected.as_slice());
+    } else {
+      let error = Image::try_new_from_path(path).err().unwrap();
+      assert!(error.contains("jxl"), "{}", error);
+    }
+    let _ = std::fs::remove_dir_all(dir);
+  }
 }
@@ -1,6 +1,8 @@
-use core::cell::RefCell;
 use paste::paste;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::env;
 use std::fs;
+use std::sync::{Arc, Mutex, RwLock};
 
 use saphyr::{LoadableYamlNode, Yaml};
 
@@ -17,40 +19,44 @@ macro_rules! yaml_settings_getters {
     $(
        #[doc = concat!("Gets the value of `", stringify!($getter), "`.")]
       pub fn $getter() -> $ret {
-        if SETTINGS.with(|s| s.borrow().is_none()) {
+        if SETTINGS.read().unwrap().is_none() {
           println!("Settings not initialized, initializing with default path.");
-          Settings::init();
+          // Invalid environment values leave the settings from the file, the error is reported by `init`.
+          let _ = Settings::init();
         }
-        let result = SETTINGS.with(|s| s.borrow().as_ref().unwrap().settings.$getter.clone());
+        let result = SETTINGS.read().unwrap().as_ref().unwrap().settings.$getter.clone();
         println!("Settings: Getting '{}': {:?}", stringify!($getter), result);
         result
       }
       paste! {
-        #[doc = concat!("Sets the value of `", stringify!($getter), "`.")]
+        #[doc = concat!("Sets the value of `", stringify!($getter), "` for every thread.")]
+        #[doc = "Operations that start after this call use the new value."]
         pub fn [<set_ $getter>](value: $ret) {
-          if SETTINGS.with(|s| s.borrow().is_none()) {
+          if SETTINGS.read().unwrap().is_none() {
             println!("Settings not initialized, initializing with default path.");
-            Settings::init();
+            let _ = Settings::init();
+          }
+          if let Some(settings) = SETTINGS.write().unwrap().as_mut() {
+            println!("Settings: Setting '{}': {:?}", stringify!($getter), &value);
+            settings.settings.$getter = value;
           }
-          SETTINGS.with(|s| {
-            if let Some(settings) = s.borrow_mut().as_mut() {
-              println!("Settings: Setting '{}': {:?}", stringify!($getter), &value);
-              settings.settings.$getter = value;
-            }
-          });
         }
       }
     )*
   };
 }
 
-thread_local! {
-  static SETTINGS: RefCell<Option<Settings>> = RefCell::new(None);
-}
+/// The settings shared by every thread, so a setter called on one thread applies to work started on any other.
+static SETTINGS: RwLock<Option<Settings>> = RwLock::new(None);
+
+/// The pool used for CPU processing when a thread count is set, along with its size.
+static THREAD_POOL: Mutex<Option<(usize, Arc<ThreadPool>)>> = Mutex::new(None);
 
 #[derive(Clone)]
 pub struct YamlSettings {
   gpu_enabled: bool,
+  /// The number of threads used for CPU processing, or 0 to use every core.
+  thread_count: usize,
   api_model_paths: Vec<String>,
 }
 
@@ -65,6 +71,7 @@ impl Default for Settings {
     Settings {
       settings: YamlSettings {
         gpu_enabled: true,
+        thread_count: 0,
         api_model_paths: Vec::new(),
       },
     }
@@ -73,16 +80,23 @@ impl Default for Settings {
 
 impl Settings {
   /// Initialize settings using the default settings file path.
-  pub fn init() -> Self {
+  /// The `ABRA_GPU` and `ABRA_THREADS` environment variables override the file, see `init_from_file`.
+  pub fn init() -> Result<Self, String> {
     Self::init_from_file("./settings.yml")
   }
   /// Initialize settings from a specified file path.
+  /// Environment variables take precedence over the file, so a run can be configured without editing it:
+  /// - `ABRA_GPU`: `1`/`true`/`on` or `0`/`false`/`off` to enable or disable GPU acceleration.
+  /// - `ABRA_THREADS`: The number of threads used for CPU processing, or `0` to use every core.
+  ///
+  /// Returns an error naming the variable when either one holds an invalid value. The settings from the file are
+  /// still stored in that case, so processing can go on without the override.
   /// - `p_file` - The file path to load settings from.
-  pub fn init_from_file(p_file: impl Into<String>) -> Self {
+  pub fn init_from_file(p_file: impl Into<String>) -> Result<Self, String> {
     let file = p_file.into();
     println!("Looking for settings file: {}", file);
     let settings_exist = fs::metadata(&file).is_ok();
-    let result;
+    let mut result: Settings;
     if settings_exist {
       println!("Found settings file, loading...");
       let settings = fs::read_to_string(&file).unwrap();
@@ -95,6 +109,12 @@ impl Settings {
             .and_then(|gpu| gpu.as_mapping_get("enabled"))
             .and_then(|v| v.as_bool())
             .unwrap_or(true),
+          thread_count: doc
+            .as_mapping_get("cpu")
+            .and_then(|cpu| cpu.as_mapping_get("threads"))
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(0) as usize)
+            .unwrap_or(0),
           api_model_paths: doc
             .as_mapping_get("api")
             .and_then(|api| api.as_mapping_get("model_paths"))
@@ -108,12 +128,109 @@ impl Settings {
       println!("Settings file not found, using defaults.");
       result = Default::default();
     }
-    SETTINGS.with(|s| s.replace(Some(result.clone())));
-    result
+    let overrides = env_overrides(env::var("ABRA_GPU").ok().as_deref(), env::var("ABRA_THREADS").ok().as_deref());
+    if let Ok((gpu_enabled, thread_count)) = overrides {
+      result.settings.gpu_enabled = gpu_enabled.unwrap_or(result.settings.gpu_enabled);
+      result.settings.thread_count = thread_count.unwrap_or(result.settings.thread_count);
+    }
+    *SETTINGS.write().unwrap() = Some(result.clone());
+    overrides.map(|_| result)
+  }
+
+  /// Runs an operation with the CPU thread budget from `thread_count`.
+  /// Parallel work started by the operation is limited to that many threads; with a count of 0 it runs as is.
+  /// - `p_op`: The operation to run.
+  pub fn with_thread_budget<R: Send>(p_op: impl FnOnce() -> R + Send) -> R {
+    let threads = Settings::thread_count();
+    if threads == 0 {
+      return p_op();
+    }
+    let pool = {
+      let mut cached = THREAD_POOL.lock().unwrap();
+      match cached.as_ref() {
+        Some((size, pool)) if *size == threads => pool.clone(),
+        _ => {
+          let pool = Arc::new(
+            ThreadPoolBuilder::new()
+              .num_threads(threads)
+              .build()
+              .expect("Failed to build rayon thread pool for the thread budget"),
+          );
+          *cached = Some((threads, pool.clone()));
+          pool
+        }
+      }
+    };
+    pool.install(p_op)
   }
 
   yaml_settings_getters!(
     gpu_enabled => bool,
+    thread_count => usize,
     api_model_paths => Vec<String>
   );
 }
+
+/// Parses the values of the `ABRA_GPU` and `ABRA_THREADS` environment variables, `None` when one is not set.
+/// Returns the GPU switch and the thread count, or an error naming the first invalid variable.
+/// - `p_gpu`: The value of `ABRA_GPU`.
+/// - `p_threads`: The value of `ABRA_THREADS`.
+fn env_overrides(p_gpu: Option<&str>, p_threads: Option<&str>) -> Result<(Option<bool>, Option<usize>), String> {
+  let gpu_enabled = p_gpu
+    .map(|value| parse_bool(value).ok_or_else(|| format!("Invalid ABRA_GPU value '{}'", value)))
+    .transpose()?;
+  let thread_count = p_threads
+    .map(|value| {
+      value
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| format!("Invalid ABRA_THREADS value '{}'", value))
+    })
+    .transpose()?;
+  Ok((gpu_enabled, thread_count))
+}
+
+/// Parses a boolean environment variable value, e.g. `1`, `true`, `on` or `0`, `false`, `off`.
+fn parse_bool(p_value: &str) -> Option<bool> {
+  match p_value.trim().to_ascii_lowercase().as_str() {
+    "1" | "true" | "on" | "yes" => Some(true),
+    "0" | "false" | "off" | "no" => Some(false),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_env_booleans() {
+    assert_eq!(parse_bool("0"), Some(false));
+    assert_eq!(parse_bool(" OFF "), Some(false));
+    assert_eq!(parse_bool("true"), Some(true));
+    assert_eq!(parse_bool("maybe"), None);
+  }
+
+  #[test]
+  fn parses_env_overrides() {
+    assert_eq!(env_overrides(None, None), Ok((None, None)));
+    assert_eq!(env_overrides(Some("off"), Some(" 3 ")), Ok((Some(false), Some(3))));
+    assert!(env_overrides(Some("maybe"), None).unwrap_err().contains("ABRA_GPU"));
+    assert!(env_overrides(None, Some("-1")).unwrap_err().contains("ABRA_THREADS"));
+  }
+
+  #[test]
+  fn thread_budget_follows_setter() {
+    // The settings are shared by the whole process, so the previous count is put back afterwards.
+    let previous = Settings::thread_count();
+    Settings::set_thread_count(2);
+    assert_eq!(Settings::with_thread_budget(rayon::current_num_threads), 2);
+    Settings::set_thread_count(3);
+    assert_eq!(Settings::with_thread_budget(rayon::current_num_threads), 3);
+    // Other threads see the value set here.
+    assert_eq!(std::thread::spawn(Settings::thread_count).join().unwrap(), 3);
+    Settings::set_thread_count(0);
+    assert_eq!(Settings::with_thread_budget(rayon::current_num_threads), rayon::current_num_threads());
+    Settings::set_thread_count(previous);
+  }
+}
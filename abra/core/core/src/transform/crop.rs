@@ -1,6 +1,6 @@
 use std::time::Instant;
 
-use crate::Image;
+use crate::{Anchor, Color, Image};
 use primitives::Image as PrimitiveImage;
 
 /// Trait for cropping functionality.
@@ -9,6 +9,20 @@ pub trait Crop {
   fn crop(&mut self, x: u32, y: u32, width: u32, height: u32);
 }
 
+/// Trait for cropping an image to an aspect ratio.
+pub trait CropToAspect {
+  /// Returns the largest crop of the image with the given aspect ratio, positioned by the anchor.
+  fn crop_to_aspect(&self, ratio: (u32, u32), anchor: Anchor) -> Image;
+}
+
+/// Trait for cropping to a rectangle that may extend past the edges of the image.
+pub trait CropWithFill {
+  /// Crops the image to the given rectangle, filling the parts outside of the image with a color.
+  fn crop_with_fill(&mut self, x: i32, y: i32, width: u32, height: u32, fill: Color);
+  /// Changes the size of the image without resampling it, positioning the image by the anchor.
+  fn resize_canvas(&mut self, width: u32, height: u32, anchor: Anchor, fill: Color);
+}
+
 /// Crop the image to the given dimensions.
 pub fn crop(image: &mut Image, x: u32, y: u32, width: u32, height: u32) {
   let _duration = Instant::now();
@@ -36,8 +50,142 @@ pub fn cropped(image: &Image, x: u32, y: u32, width: u32, height: u32) -> Image
   new_image
 }
 
+/// Crops the image to a rectangle that may extend past its edges, filling the parts outside of the image with a color.
+/// * `image` - The image to crop.
+/// * `x` - The left edge of the rectangle, which may be negative.
+/// * `y` - The top edge of the rectangle, which may be negative.
+/// * `width` - The width of the rectangle.
+/// * `height` - The height of the rectangle.
+/// * `fill` - The color of the parts outside of the image, e.g. `Color::transparent()`.
+pub fn crop_with_fill(image: &mut Image, x: i32, y: i32, width: u32, height: u32, fill: Color) {
+  let (old_width, old_height) = image.dimensions::<i64>();
+  let mut output = Image::new_from_color(width, height, fill);
+  // The part of the rectangle that overlaps the image, in image coordinates.
+  let (x, y) = (x as i64, y as i64);
+  let (left, top) = (x.max(0), y.max(0));
+  let (right, bottom) = ((x + width as i64).min(old_width), (y + height as i64).min(old_height));
+  if left < right && top < bottom {
+    let old_pixels = image.rgba();
+    let new_pixels = output.colors().as_slice_mut().expect("Image colors must be contiguous");
+    let row_len = (right - left) as usize * 4;
+    for old_y in top..bottom {
+      let old_start = (old_y * old_width + left) as usize * 4;
+      let new_start = ((old_y - y) * width as i64 + (left - x)) as usize * 4;
+      new_pixels[new_start..new_start + row_len].copy_from_slice(&old_pixels[old_start..old_start + row_len]);
+    }
+  }
+  *image = output;
+}
+
+/// Changes the size of the image without resampling it, like the canvas size option of an image editor.
+/// The image keeps its pixels and is positioned by the anchor, new space is filled with a color and parts that no
+/// longer fit are cut off.
+/// * `image` - The image to resize.
+/// * `width` - The new width.
+/// * `height` - The new height.
+/// * `anchor` - Where the image is positioned within the new size.
+/// * `fill` - The color of the new space, e.g. `Color::transparent()`.
+pub fn resize_canvas(image: &mut Image, width: u32, height: u32, anchor: Anchor, fill: Color) {
+  let (old_width, old_height) = image.dimensions::<i32>();
+  let (x, y) = anchor.calculate_position(width as i32, height as i32, old_width, old_height);
+  crop_with_fill(image, -x, -y, width, height, fill);
+}
+
+/// Returns the largest crop of the image with the given aspect ratio, positioned by the anchor.
+/// For example a 3:2 crop of a square image anchored at the center trims equal amounts from the top and bottom.
+/// The image is returned unchanged when either part of the ratio is zero.
+/// * `image` - The image to crop.
+/// * `ratio` - The aspect ratio as width and height, e.g. `(16, 9)`.
+/// * `anchor` - Where the crop is positioned within the image.
+pub fn crop_to_aspect(image: &Image, ratio: (u32, u32), anchor: Anchor) -> Image {
+  let (width, height) = image.dimensions::<u32>();
+  let (ratio_width, ratio_height) = (ratio.0 as u64, ratio.1 as u64);
+  if ratio_width == 0 || ratio_height == 0 {
+    return image.clone();
+  }
+  // Keep the full height when the image is wider than the ratio, and the full width otherwise.
+  let (crop_width, crop_height) = if width as u64 * ratio_height > height as u64 * ratio_width {
+    ((height as u64 * ratio_width / ratio_height) as u32, height)
+  } else {
+    (width, (width as u64 * ratio_height / ratio_width) as u32)
+  };
+  let (x, y) = anchor.calculate_position(width as i32, height as i32, crop_width as i32, crop_height as i32);
+  cropped(image, x as u32, y as u32, crop_width, crop_height)
+}
+
 impl Crop for PrimitiveImage {
   fn crop(&mut self, x: u32, y: u32, width: u32, height: u32) {
     crate::transform::crop(self, x, y, width, height);
   }
 }
+
+impl CropWithFill for PrimitiveImage {
+  fn crop_with_fill(&mut self, x: i32, y: i32, width: u32, height: u32, fill: Color) {
+    crate::transform::crop_with_fill(self, x, y, width, height, fill);
+  }
+
+  fn resize_canvas(&mut self, width: u32, height: u32, anchor: Anchor, fill: Color) {
+    crate::transform::resize_canvas(self, width, height, anchor, fill);
+  }
+}
+
+impl CropToAspect for PrimitiveImage {
+  fn crop_to_aspect(&self, ratio: (u32, u32), anchor: Anchor) -> Image {
+    crate::transform::crop_to_aspect(self, ratio, anchor)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A square image whose rows are numbered by their red channel.
+  fn numbered_rows() -> Image {
+    let mut image = Image::new(60, 60);
+    for y in 0..60 {
+      for x in 0..60 {
+        image.set_pixel(x, y, (y as u8, x as u8, 0, 255));
+      }
+    }
+    image
+  }
+
+  #[test]
+  fn center_crop_trims_equally() {
+    let image = numbered_rows().crop_to_aspect((3, 2), Anchor::Center);
+    assert_eq!(image.dimensions::<u32>(), (60, 40));
+    assert_eq!(image.get_pixel(0, 0), Some((10, 0, 0, 255)));
+    assert_eq!(image.get_pixel(59, 39), Some((49, 59, 0, 255)));
+  }
+
+  #[test]
+  fn anchor_positions_the_crop() {
+    let image = numbered_rows().crop_to_aspect((1, 2), Anchor::BottomRight);
+    assert_eq!(image.dimensions::<u32>(), (30, 60));
+    assert_eq!(image.get_pixel(0, 0), Some((0, 30, 0, 255)));
+
+    let image = numbered_rows().crop_to_aspect((16, 9), Anchor::TopLeft);
+    assert_eq!(image.dimensions::<u32>(), (60, 33));
+    assert_eq!(image.get_pixel(0, 0), Some((0, 0, 0, 255)));
+  }
+
+  #[test]
+  fn centered_crop_past_the_edges_adds_a_border() {
+    let mut image = Image::new_from_color(100, 100, Color::from_rgba(255, 0, 0, 255));
+    image.crop_with_fill(-50, -50, 200, 200, Color::from_rgba(0, 0, 255, 255));
+    assert_eq!(image.dimensions::<u32>(), (200, 200));
+    for (x, y) in [(0, 0), (49, 100), (150, 100), (100, 199)] {
+      assert_eq!(image.get_pixel(x, y), Some((0, 0, 255, 255)), "({}, {})", x, y);
+    }
+    for (x, y) in [(50, 50), (149, 149), (100, 100)] {
+      assert_eq!(image.get_pixel(x, y), Some((255, 0, 0, 255)), "({}, {})", x, y);
+    }
+
+    let mut image = numbered_rows();
+    image.resize_canvas(80, 40, Anchor::BottomRight, Color::transparent());
+    assert_eq!(image.dimensions::<u32>(), (80, 40));
+    assert_eq!(image.get_pixel(19, 0), Some((0, 0, 0, 0)));
+    assert_eq!(image.get_pixel(20, 0), Some((20, 0, 0, 255)));
+    assert_eq!(image.get_pixel(79, 39), Some((59, 59, 0, 255)));
+  }
+}
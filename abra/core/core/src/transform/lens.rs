@@ -0,0 +1,93 @@
+//! Radial lens distortion.
+
+use crate::Image;
+use crate::geometry::PointF;
+use crate::transform::sample_bilinear;
+use rayon::prelude::*;
+
+/// Applies or corrects radial lens distortion using the Brown-Conrady model.
+///
+/// Each output pixel at normalized distance `r` from the center samples the source at
+/// `r * (1 + k1 * r² + k2 * r⁴)`. Positive coefficients produce barrel distortion, bowing straight lines
+/// outward; negative coefficients produce pincushion distortion, or correct barrel distortion from a lens.
+/// Distances are normalized so the image corners are at `r = 1` when the center is in the middle.
+/// Pixels that sample outside of the image become transparent.
+///
+/// - `p_image`: The image to distort.
+/// - `p_k1`: The second order radial coefficient.
+/// - `p_k2`: The fourth order radial coefficient, which mostly affects the corners.
+/// - `p_center`: The optical center relative to the image size, where `(0.5, 0.5)` is the middle.
+pub fn lens_distortion(p_image: &mut Image, p_k1: f32, p_k2: f32, p_center: impl Into<PointF>) {
+  let (width, height) = p_image.dimensions::<u32>();
+  if width == 0 || height == 0 {
+    return;
+  }
+  let center = p_center.into();
+  let (center_x, center_y) = (center.x * width as f32, center.y * height as f32);
+  let radius = (width as f32).hypot(height as f32) / 2.0;
+
+  let source = p_image.clone();
+  let mut new_pixels = vec![0u8; (width * height * 4) as usize];
+  new_pixels.par_chunks_mut(4).enumerate().for_each(|(i, chunk)| {
+    // Sample at pixel centers so the distortion is symmetric around the optical center.
+    let dx = (i as u32 % width) as f32 + 0.5 - center_x;
+    let dy = (i as u32 / width) as f32 + 0.5 - center_y;
+    let r2 = (dx * dx + dy * dy) / (radius * radius);
+    let scale = 1.0 + p_k1 * r2 + p_k2 * r2 * r2;
+    let src_x = center_x + dx * scale - 0.5;
+    let src_y = center_y + dy * scale - 0.5;
+    chunk.copy_from_slice(&sample_bilinear(&source, src_x, src_y));
+  });
+
+  p_image.set_rgba_owned(new_pixels);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use primitives::Color;
+
+  /// Returns the x coordinate of the darkest opaque pixel in a row.
+  fn darkest_column(p_image: &Image, p_y: u32) -> u32 {
+    let (width, _) = p_image.dimensions::<u32>();
+    let brightness = |p_x: &u32| {
+      let (r, _, _, a) = p_image.get_pixel(*p_x, p_y).unwrap();
+      if a < 255 { u8::MAX } else { r }
+    };
+    (0..width).min_by_key(brightness).unwrap()
+  }
+
+  fn line_image() -> Image {
+    let mut image = Image::new_from_color(101, 101, Color::white());
+    for y in 0..101 {
+      image.set_pixel(80, y, (0, 0, 0, 255));
+    }
+    image
+  }
+
+  #[test]
+  fn barrel_distortion_bows_lines_outward() {
+    let mut image = line_image();
+    lens_distortion(&mut image, 0.3, 0.0, (0.5, 0.5));
+    let middle = darkest_column(&image, 50);
+    let top = darkest_column(&image, 5);
+    let bottom = darkest_column(&image, 95);
+    assert!(middle > top, "middle {} top {}", middle, top);
+    assert!(middle > bottom, "middle {} bottom {}", middle, bottom);
+    assert_eq!(top, bottom);
+  }
+
+  #[test]
+  fn pincushion_distortion_bows_lines_inward() {
+    let mut image = line_image();
+    lens_distortion(&mut image, -0.3, 0.0, (0.5, 0.5));
+    assert!(darkest_column(&image, 50) < darkest_column(&image, 5));
+  }
+
+  #[test]
+  fn zero_coefficients_keep_image() {
+    let mut image = line_image();
+    lens_distortion(&mut image, 0.0, 0.0, (0.5, 0.5));
+    assert_eq!(image.rgba(), line_image().rgba());
+  }
+}
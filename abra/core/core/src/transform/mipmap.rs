@@ -0,0 +1,247 @@
+use std::f32::consts::PI;
+
+use crate::Image;
+use crate::transform::{TransformAlgorithm, get_resize_algorithm};
+use primitives::{Image as PrimitiveImage, LinearImage};
+
+/// Trait for building mipmap chains.
+pub trait Mipmaps {
+  /// Builds the mipmap chain of the image: the image itself followed by successively halved copies down to 1x1.
+  /// - `p_filter`: The filter used for each halving step.
+  fn generate_mipmaps(&self, p_filter: TransformAlgorithm) -> Vec<Image>;
+}
+
+/// Builds the mipmap chain of an image: the image itself followed by successively halved copies down to 1x1.
+/// Each side is halved and rounded down, but never below one pixel, so the chain has
+/// `floor(log2(max(width, height))) + 1` levels.
+///
+/// Every level is filtered from the previous one in linear light, kept as `f32` between levels, so bright and dark
+/// details average to the brightness they have on screen instead of darkening as they would with sRGB values.
+/// Colors are weighted by alpha, so transparent pixels do not bleed their color into their neighbors. A side of odd
+/// length covers more than two source pixels per output pixel, so the filter footprint is stretched to cover all of
+/// them and no row or column is dropped.
+///
+/// NOTE: This is the single mipmap implementation; the DDS and KTX2 writers build their levels with it.
+/// - `p_image`: The image to build the chain from.
+/// - `p_filter`: The filter used for each halving step. `Bilinear` averages the pixels each output pixel covers,
+///   `Bicubic` and `Lanczos` keep more detail, and `NearestNeighbor` picks one pixel. The edge-directed algorithms
+///   only apply to upscaling and average like `Bilinear`, and `Auto` picks `Bilinear`.
+pub fn generate_mipmaps(p_image: &Image, p_filter: TransformAlgorithm) -> Vec<Image> {
+  let (mut width, mut height) = p_image.dimensions::<u32>();
+  let mut levels = vec![p_image.clone()];
+  if width == 0 || height == 0 {
+    return levels;
+  }
+
+  let mut linear = p_image.to_linear();
+  while width > 1 || height > 1 {
+    let (half_width, half_height) = ((width / 2).max(1), (height / 2).max(1));
+    let filter = get_resize_algorithm(p_filter, width, height, half_width, half_height);
+    linear = halve(&linear, half_width, half_height, filter);
+    (width, height) = (half_width, half_height);
+    levels.push(linear.to_srgb());
+  }
+  levels
+}
+
+/// Resamples a linear image down to half its size (or less) with the given filter, one axis at a time, weighting
+/// colors by alpha.
+fn halve(p_image: &LinearImage, p_width: u32, p_height: u32, p_filter: TransformAlgorithm) -> LinearImage {
+  let (source_width, source_height) = p_image.dimensions::<usize>();
+  let (width, height) = (p_width as usize, p_height as usize);
+  let mut premultiplied = p_image.pixels().to_vec();
+  for pixel in premultiplied.chunks_exact_mut(4) {
+    for c in 0..3 {
+      pixel[c] *= pixel[3];
+    }
+  }
+
+  let columns = footprints(source_width, width, p_filter);
+  let mut rows_done = vec![0.0f32; width * source_height * 4];
+  for y in 0..source_height {
+    for (x, taps) in columns.iter().enumerate() {
+      let out = (y * width + x) * 4;
+      for &(sx, weight) in taps {
+        let i = (y * source_width + sx) * 4;
+        for c in 0..4 {
+          rows_done[out + c] += premultiplied[i + c] * weight;
+        }
+      }
+    }
+  }
+
+  let rows = footprints(source_height, height, p_filter);
+  let mut out = vec![0.0f32; width * height * 4];
+  for (y, taps) in rows.iter().enumerate() {
+    for x in 0..width {
+      let o = (y * width + x) * 4;
+      for &(sy, weight) in taps {
+        let i = (sy * width + x) * 4;
+        for c in 0..4 {
+          out[o + c] += rows_done[i + c] * weight;
+        }
+      }
+      // Sharpening filters overshoot, so the results are brought back into range before removing the alpha weight.
+      let alpha = out[o + 3].clamp(0.0, 1.0);
+      for c in 0..3 {
+        out[o + c] = if alpha > 0.0 {
+          (out[o + c] / alpha).max(0.0)
+        } else {
+          0.0
+        };
+      }
+      out[o + 3] = alpha;
+    }
+  }
+  LinearImage::new_from_pixels(p_width, p_height, out)
+}
+
+/// The source pixels and normalized weights that make up each output pixel along one axis.
+/// Pixels past the ends repeat the edge pixel.
+fn footprints(p_source: usize, p_target: usize, p_filter: TransformAlgorithm) -> Vec<Vec<(usize, f32)>> {
+  let scale = p_source as f32 / p_target as f32;
+  (0..p_target)
+    .map(|i| {
+      let center = (i as f32 + 0.5) * scale;
+      let mut taps: Vec<(usize, f32)> = match p_filter {
+        TransformAlgorithm::NearestNeighbor => vec![((center as usize).min(p_source - 1), 1.0)],
+        TransformAlgorithm::Bicubic => kernel_taps(p_source, center, scale, 2.0, catmull_rom),
+        TransformAlgorithm::Lanczos => kernel_taps(p_source, center, scale, 3.0, lanczos3),
+        // The area of each source pixel that the output pixel covers, so an odd side gets a partial edge tap.
+        _ => {
+          let (from, to) = (i as f32 * scale, (i + 1) as f32 * scale);
+          (from.floor() as usize..(to.ceil() as usize).min(p_source))
+            .map(|j| (j, (to.min(j as f32 + 1.0) - from.max(j as f32)).max(0.0)))
+            .collect()
+        }
+      };
+      let total: f32 = taps.iter().map(|(_, weight)| weight).sum();
+      for (_, weight) in &mut taps {
+        *weight /= total;
+      }
+      taps
+    })
+    .collect()
+}
+
+/// The taps of a resampling kernel stretched by `p_scale` around `p_center`, with edge pixels repeated.
+fn kernel_taps(
+  p_source: usize, p_center: f32, p_scale: f32, p_support: f32, p_kernel: fn(f32) -> f32,
+) -> Vec<(usize, f32)> {
+  let radius = p_support * p_scale;
+  let from = (p_center - radius).floor() as i64;
+  let to = (p_center + radius).ceil() as i64;
+  (from..to)
+    .map(|j| {
+      let weight = p_kernel((j as f32 + 0.5 - p_center) / p_scale);
+      (j.clamp(0, p_source as i64 - 1) as usize, weight)
+    })
+    .collect()
+}
+
+/// The Catmull-Rom cubic kernel.
+fn catmull_rom(p_x: f32) -> f32 {
+  let x = p_x.abs();
+  if x < 1.0 {
+    1.5 * x * x * x - 2.5 * x * x + 1.0
+  } else if x < 2.0 {
+    -0.5 * x * x * x + 2.5 * x * x - 4.0 * x + 2.0
+  } else {
+    0.0
+  }
+}
+
+/// The Lanczos kernel with three lobes.
+fn lanczos3(p_x: f32) -> f32 {
+  let x = p_x.abs();
+  if x < 1e-6 {
+    1.0
+  } else if x < 3.0 {
+    let pi_x = PI * x;
+    3.0 * pi_x.sin() * (pi_x / 3.0).sin() / (pi_x * pi_x)
+  } else {
+    0.0
+  }
+}
+
+impl Mipmaps for PrimitiveImage {
+  fn generate_mipmaps(&self, p_filter: TransformAlgorithm) -> Vec<Image> {
+    crate::transform::generate_mipmaps(self, p_filter)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use primitives::Color;
+
+  #[test]
+  fn chain_halves_down_to_one_pixel() {
+    let image = Image::new_from_color(256, 256, Color::from_rgba(40, 120, 200, 255));
+    let levels = image.generate_mipmaps(TransformAlgorithm::Bilinear);
+    assert_eq!(levels.len(), 9);
+    for (i, level) in levels.iter().enumerate() {
+      assert_eq!(level.dimensions::<u32>(), (256 >> i, 256 >> i));
+    }
+
+    let levels = Image::new(300, 20).generate_mipmaps(TransformAlgorithm::Bilinear);
+    let sizes: Vec<(u32, u32)> = levels.iter().map(|level| level.dimensions()).collect();
+    assert_eq!(sizes.len(), 9);
+    assert_eq!(sizes[..6], [(300, 20), (150, 10), (75, 5), (37, 2), (18, 1), (9, 1)]);
+    assert_eq!(sizes[8], (1, 1));
+  }
+
+  #[test]
+  fn odd_sizes_keep_every_pixel() {
+    // The last column of a 3x3 image is white, so the single pixel of the next level holds a third of the light.
+    let mut image = Image::new_from_color(3, 3, Color::from_rgba(0, 0, 0, 255));
+    for y in 0..3 {
+      image.set_pixel(2, y, (255, 255, 255, 255));
+    }
+    for filter in [
+      TransformAlgorithm::Bilinear,
+      TransformAlgorithm::Bicubic,
+      TransformAlgorithm::Lanczos,
+    ] {
+      let levels = image.generate_mipmaps(filter);
+      assert_eq!(levels[1].dimensions::<u32>(), (1, 1));
+      assert_eq!(levels[1].get_pixel(0, 0), Some((156, 156, 156, 255)), "{}", filter);
+    }
+
+    // 5 pixels halve to 2, each covering two and a half of them, so the white pixel makes up 40% of the second.
+    let mut image = Image::new_from_color(5, 1, Color::from_rgba(0, 0, 0, 255));
+    image.set_pixel(4, 0, (255, 255, 255, 255));
+    let level = &image.generate_mipmaps(TransformAlgorithm::Bilinear)[1];
+    assert_eq!(level.get_pixel(0, 0), Some((0, 0, 0, 255)));
+    assert_eq!(level.get_pixel(1, 0), Some((170, 170, 170, 255)));
+  }
+
+  #[test]
+  fn downscaling_is_gamma_correct() {
+    // A black and white checkerboard averages to half the light, which is about 188 in sRGB rather than 128.
+    let mut image = Image::new(2, 2);
+    for (x, y) in [(0, 0), (1, 1)] {
+      image.set_pixel(x, y, (255, 255, 255, 255));
+    }
+    for (x, y) in [(1, 0), (0, 1)] {
+      image.set_pixel(x, y, (0, 0, 0, 255));
+    }
+    let levels = image.generate_mipmaps(TransformAlgorithm::Bilinear);
+    let pixel = levels[1].get_pixel(0, 0).unwrap();
+    assert_eq!(pixel, (188, 188, 188, 255));
+  }
+
+  #[test]
+  fn dark_tones_survive_averaging() {
+    // Dark shades share levels in 8-bit linear light, but not in f32, so a flat dark image stays exact.
+    for value in [1u8, 5, 17, 30, 48] {
+      let image = Image::new_from_color(4, 4, Color::from_rgba(value, value, value, 255));
+      let levels = image.generate_mipmaps(TransformAlgorithm::Bilinear);
+      assert_eq!(levels[2].get_pixel(0, 0), Some((value, value, value, 255)));
+    }
+    // Transparent pixels do not darken their neighbors.
+    let mut image = Image::new_from_color(2, 1, Color::from_rgba(200, 100, 50, 255));
+    image.set_pixel(1, 0, (0, 0, 0, 0));
+    assert_eq!(image.generate_mipmaps(TransformAlgorithm::Bilinear)[1].get_pixel(0, 0), Some((200, 100, 50, 128)));
+  }
+}
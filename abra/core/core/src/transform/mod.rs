@@ -4,12 +4,24 @@ mod algorithm;
 mod crop;
 mod flip;
 mod interpolation;
+mod lens;
+mod mipmap;
+mod orientation;
+mod output_sharpen;
 mod resize;
 mod rotate;
+mod symmetry;
+mod warp;
 
 pub use algorithm::*;
 pub use crop::*;
 pub use flip::*;
 pub use interpolation::*;
+pub use lens::*;
+pub use mipmap::*;
+pub use orientation::*;
+pub use output_sharpen::*;
 pub use resize::*;
 pub use rotate::*;
+pub use symmetry::*;
+pub use warp::*;
@@ -0,0 +1,178 @@
+//! Lossless orientation changes: quarter turns and flips.
+
+use crate::Image;
+use rayon::prelude::*;
+
+/// A combination of quarter turns and flips, one of the eight ways to orient an image without resampling.
+///
+/// Orientations compose exactly, so a sequence of rotations by multiples of 90 degrees and flips can be recorded
+/// and applied to the pixels once. Sequences that cancel out, such as four quarter turns, end up as the identity and
+/// need no pixel work at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Orientation {
+  /// Whether the image is flipped horizontally before it is turned.
+  flipped: bool,
+  /// The number of clockwise quarter turns, from 0 to 3.
+  quarter_turns: u8,
+}
+
+impl Orientation {
+  /// The orientation that leaves the image unchanged.
+  pub const IDENTITY: Orientation = Orientation {
+    flipped: false,
+    quarter_turns: 0,
+  };
+
+  /// Gets the orientation of a rotation, or `None` if the angle is not a multiple of 90 degrees.
+  /// - `p_degrees`: The rotation in degrees, where positive values rotate clockwise.
+  pub fn from_degrees(p_degrees: impl Into<f64>) -> Option<Orientation> {
+    let degrees = p_degrees.into();
+    let turns = degrees / 90.0;
+    if !turns.is_finite() || turns.fract() != 0.0 {
+      return None;
+    }
+    Some(Orientation::IDENTITY.rotated(turns.rem_euclid(4.0) as i32))
+  }
+
+  /// Whether the orientation leaves the image unchanged.
+  pub fn is_identity(&self) -> bool {
+    *self == Orientation::IDENTITY
+  }
+
+  /// Gets the orientation followed by a rotation.
+  /// - `p_quarter_turns`: The number of quarter turns, where positive values rotate clockwise.
+  pub fn rotated(self, p_quarter_turns: i32) -> Orientation {
+    Orientation {
+      flipped: self.flipped,
+      quarter_turns: (self.quarter_turns as i32 + p_quarter_turns).rem_euclid(4) as u8,
+    }
+  }
+
+  /// Gets the orientation followed by a horizontal flip.
+  pub fn flipped_horizontal(self) -> Orientation {
+    // Flipping after a turn is the same as flipping first and turning the other way.
+    Orientation {
+      flipped: !self.flipped,
+      quarter_turns: (4 - self.quarter_turns) % 4,
+    }
+  }
+
+  /// Gets the orientation followed by a vertical flip.
+  pub fn flipped_vertical(self) -> Orientation {
+    // A vertical flip is a horizontal flip followed by a half turn.
+    self.flipped_horizontal().rotated(2)
+  }
+
+  /// Gets the orientation followed by another orientation.
+  /// - `p_other`: The orientation applied afterwards.
+  pub fn then(self, p_other: Orientation) -> Orientation {
+    let flipped = if p_other.flipped {
+      self.flipped_horizontal()
+    } else {
+      self
+    };
+    flipped.rotated(p_other.quarter_turns as i32)
+  }
+
+  /// Gets the dimensions of an image after it is oriented.
+  /// - `p_width`: The width of the image.
+  /// - `p_height`: The height of the image.
+  pub fn dimensions<T>(&self, p_width: T, p_height: T) -> (T, T) {
+    if self.quarter_turns % 2 == 1 {
+      (p_height, p_width)
+    } else {
+      (p_width, p_height)
+    }
+  }
+
+  /// Orients the pixels of an image in a single pass. The pixels are moved, not resampled, so nothing is lost.
+  /// - `p_image`: The image to orient.
+  pub fn apply(&self, p_image: &mut Image) {
+    if self.is_identity() {
+      return;
+    }
+    let (width, height) = p_image.dimensions::<usize>();
+    let (new_width, new_height) = self.dimensions(width, height);
+    let old_pixels = p_image.rgba();
+    let mut new_pixels = vec![0u8; width * height * 4];
+
+    new_pixels.par_chunks_mut(4).enumerate().for_each(|(i, chunk)| {
+      let (mut x, mut y) = (i % new_width, i / new_width);
+      // Undo each clockwise quarter turn, then the flip, to find the source pixel.
+      let (mut turned_width, mut turned_height) = (new_width, new_height);
+      for _ in 0..self.quarter_turns {
+        (x, y) = (y, turned_width - 1 - x);
+        (turned_width, turned_height) = (turned_height, turned_width);
+      }
+      if self.flipped {
+        x = width - 1 - x;
+      }
+      let old_index = (y * width + x) * 4;
+      chunk.copy_from_slice(&old_pixels[old_index..old_index + 4]);
+    });
+
+    p_image.set_new_pixels(&new_pixels, new_width as u32, new_height as u32);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A 3x2 image where every pixel has a different red value.
+  fn numbered() -> Image {
+    let mut image = Image::new(3, 2);
+    for y in 0..2 {
+      for x in 0..3 {
+        image.set_pixel(x, y, ((y * 3 + x) as u8, 0, 0, 255));
+      }
+    }
+    image
+  }
+
+  fn reds(p_image: &Image) -> Vec<u8> {
+    p_image.rgba().chunks_exact(4).map(|pixel| pixel[0]).collect()
+  }
+
+  #[test]
+  fn quarter_turn_and_flips_move_pixels() {
+    let mut image = numbered();
+    Orientation::from_degrees(90).unwrap().apply(&mut image);
+    assert_eq!(image.dimensions::<u32>(), (2, 3));
+    assert_eq!(reds(&image), vec![3, 0, 4, 1, 5, 2]);
+
+    let mut image = numbered();
+    Orientation::IDENTITY.flipped_horizontal().apply(&mut image);
+    assert_eq!(reds(&image), vec![2, 1, 0, 5, 4, 3]);
+
+    let mut image = numbered();
+    Orientation::IDENTITY.flipped_vertical().apply(&mut image);
+    assert_eq!(reds(&image), vec![3, 4, 5, 0, 1, 2]);
+  }
+
+  #[test]
+  fn composed_orientation_matches_steps() {
+    let steps = [
+      Orientation::IDENTITY.rotated(1),
+      Orientation::IDENTITY.flipped_vertical(),
+      Orientation::IDENTITY.rotated(-1),
+      Orientation::IDENTITY.flipped_horizontal(),
+      Orientation::IDENTITY.rotated(1),
+    ];
+    let mut stepped = numbered();
+    let mut composed = Orientation::IDENTITY;
+    for step in steps {
+      step.apply(&mut stepped);
+      composed = composed.then(step);
+    }
+    let mut image = numbered();
+    composed.apply(&mut image);
+    assert_eq!(image.dimensions::<u32>(), stepped.dimensions::<u32>());
+    assert_eq!(reds(&image), reds(&stepped));
+
+    let full_turn = (0..4).fold(Orientation::IDENTITY, |orientation, _| orientation.rotated(1));
+    assert!(full_turn.is_identity());
+    assert_eq!(Orientation::from_degrees(-270.0), Some(Orientation::IDENTITY.rotated(1)));
+    assert_eq!(Orientation::from_degrees(45.0), None);
+  }
+}
@@ -0,0 +1,185 @@
+use crate::Image;
+use crate::ImageRef;
+use crate::transform::{TransformAlgorithm, resize};
+use rayon::prelude::*;
+
+/// Differences smaller than this many levels are left alone so flat areas and noise are not sharpened.
+const SHARPEN_THRESHOLD: f32 = 2.0;
+
+/// The sharpening amount and blur radius (sigma) suited to a downscale factor.
+/// Stronger reductions average more detail away, so they get a stronger and slightly wider mask.
+/// - `p_scale`: The ratio between the old and the new size, e.g. `4.0` when shrinking to a quarter.
+fn sharpen_settings(p_scale: f32) -> (f32, f32) {
+  let stops = p_scale.max(1.0).log2();
+  let amount = (0.3 * stops).min(1.0);
+  let sigma = (0.5 + 0.1 * stops).min(1.0);
+  (amount, sigma)
+}
+
+/// Builds a normalized 1D Gaussian kernel of `2 * radius + 1` weights that sum to 1.
+/// - `p_radius`: The number of weights on each side of the center.
+/// - `p_sigma`: The standard deviation. A sigma of 0 or less keeps only the center weight.
+///
+/// NOTE: This is the single Gaussian kernel of the workspace; the blur filters, the output sharpening and the
+/// local Gaussian means of the adjustments use it.
+pub fn gaussian_kernel(p_radius: u32, p_sigma: f32) -> Vec<f32> {
+  let radius = p_radius as i32;
+  if p_sigma <= 0.0 {
+    return (-radius..=radius).map(|i| if i == 0 { 1.0 } else { 0.0 }).collect();
+  }
+  let weights: Vec<f32> = (-radius..=radius)
+    .map(|i| (-(i * i) as f32 / (2.0 * p_sigma * p_sigma)).exp())
+    .collect();
+  let sum: f32 = weights.iter().sum();
+  weights.into_iter().map(|w| w / sum).collect()
+}
+
+/// Applies an unsharp mask to the color channels, leaving alpha unchanged.
+/// - `p_image`: The image to sharpen.
+/// - `p_amount`: How much of the difference to the blurred image is added back.
+/// - `p_sigma`: The blur radius of the mask.
+fn unsharp_mask(p_image: &mut Image, p_amount: f32, p_sigma: f32) {
+  let (width, height) = p_image.dimensions::<usize>();
+  if width == 0 || height == 0 {
+    return;
+  }
+  // Three standard deviations hold nearly all of the weight.
+  let kernel = gaussian_kernel((p_sigma * 3.0).ceil() as u32, p_sigma);
+  let radius = (kernel.len() / 2) as isize;
+  let src = p_image.rgba();
+
+  // Separable blur with clamped edges: horizontal pass, then vertical pass.
+  let mut horizontal = vec![0.0f32; width * height * 3];
+  horizontal.par_chunks_mut(width * 3).enumerate().for_each(|(y, row)| {
+    for x in 0..width {
+      let mut sum = [0.0f32; 3];
+      for (k, weight) in kernel.iter().enumerate() {
+        let sx = (x as isize + k as isize - radius).clamp(0, width as isize - 1) as usize;
+        let i = (y * width + sx) * 4;
+        for c in 0..3 {
+          sum[c] += src[i + c] as f32 * weight;
+        }
+      }
+      row[x * 3..x * 3 + 3].copy_from_slice(&sum);
+    }
+  });
+
+  let mut out = src.to_vec();
+  out.par_chunks_mut(width * 4).enumerate().for_each(|(y, row)| {
+    for x in 0..width {
+      let mut blurred = [0.0f32; 3];
+      for (k, weight) in kernel.iter().enumerate() {
+        let sy = (y as isize + k as isize - radius).clamp(0, height as isize - 1) as usize;
+        let i = (sy * width + x) * 3;
+        for c in 0..3 {
+          blurred[c] += horizontal[i + c] * weight;
+        }
+      }
+      let pixel = &mut row[x * 4..x * 4 + 4];
+      for (value, blurred) in pixel.iter_mut().zip(blurred) {
+        let detail = *value as f32 - blurred;
+        if detail.abs() > SHARPEN_THRESHOLD {
+          *value = (*value as f32 + detail * p_amount).round().clamp(0.0, 255.0) as u8;
+        }
+      }
+    }
+  });
+  p_image.set_rgba_owned(out);
+}
+
+/// Resize the image and sharpen the result for display, like the "sharpen for screen" output setting of photo
+/// editors. Downscaling averages fine detail away, so an unsharp mask tuned to the reduction restores crispness.
+/// - `p_image`: The image to resize.
+/// - `p_width`: The target width.
+/// - `p_height`: The target height.
+/// - `p_algorithm`: The resizing algorithm to use. If None, the best algorithm will be selected automatically.
+/// - `p_sharpen_amount`: How strongly to sharpen, from `0.0` (no sharpening) to `2.0`. If None, the amount is
+///   picked from the downscale factor: none when enlarging, `0.3` at half size and `0.6` at a quarter.
+pub fn resize_sharpened<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_width: u32, p_height: u32, p_algorithm: impl Into<Option<TransformAlgorithm>>,
+  p_sharpen_amount: impl Into<Option<f32>>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let (old_width, old_height) = image.dimensions::<u32>();
+  resize(&mut *image, p_width, p_height, p_algorithm);
+
+  let scale = (old_width as f32 / p_width.max(1) as f32).max(old_height as f32 / p_height.max(1) as f32);
+  let (auto_amount, sigma) = sharpen_settings(scale);
+  let amount = p_sharpen_amount
+    .into()
+    .map_or(auto_amount, |amount| amount.clamp(0.0, 2.0));
+  if amount > 0.0 {
+    unsharp_mask(image, amount, sigma);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Color;
+
+  /// Stripes eight pixels wide, which soften when reduced to a quarter.
+  fn stripes() -> Image {
+    let mut image = Image::new_from_color(64, 64, Color::from_rgba(60, 60, 60, 255));
+    for y in 0..64 {
+      for x in (0..64).filter(|x| (x / 8) % 2 == 1) {
+        image.set_pixel(x, y, (190, 190, 190, 255));
+      }
+    }
+    image
+  }
+
+  /// The difference between the brightest and darkest red value of a row.
+  fn contrast(p_image: &Image) -> u8 {
+    let (width, _) = p_image.dimensions::<u32>();
+    let row: Vec<u8> = (0..width).map(|x| p_image.get_pixel(x, 4).unwrap().0).collect();
+    row.iter().max().unwrap() - row.iter().min().unwrap()
+  }
+
+  #[test]
+  fn sharpening_restores_contrast_after_downscale() {
+    let mut plain = stripes();
+    resize(&mut plain, 16, 16, TransformAlgorithm::Bilinear);
+    let mut sharpened = stripes();
+    resize_sharpened(&mut sharpened, 16, 16, TransformAlgorithm::Bilinear, None);
+
+    assert_eq!(sharpened.dimensions::<u32>(), (16, 16));
+    assert!(contrast(&sharpened) > contrast(&plain), "{} <= {}", contrast(&sharpened), contrast(&plain));
+    assert_eq!(sharpened.get_pixel(3, 3).unwrap().3, 255);
+  }
+
+  #[test]
+  fn zero_amount_and_enlarging_match_plain_resize() {
+    let mut plain = stripes();
+    resize(&mut plain, 16, 16, TransformAlgorithm::Bilinear);
+    let mut unsharpened = stripes();
+    resize_sharpened(&mut unsharpened, 16, 16, TransformAlgorithm::Bilinear, 0.0);
+    assert_eq!(unsharpened.rgba(), plain.rgba());
+
+    let mut enlarged = stripes();
+    resize(&mut enlarged, 96, 96, TransformAlgorithm::Bilinear);
+    let mut auto = stripes();
+    resize_sharpened(&mut auto, 96, 96, TransformAlgorithm::Bilinear, None);
+    assert_eq!(auto.rgba(), enlarged.rgba());
+  }
+
+  #[test]
+  fn gaussian_kernel_is_normalized_and_symmetric() {
+    let kernel = gaussian_kernel(3, 1.5);
+    assert_eq!(kernel.len(), 7);
+    assert!((kernel.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+    assert_eq!(kernel[0], kernel[6]);
+    assert!(kernel[3] > kernel[2] && kernel[2] > kernel[1]);
+    assert_eq!(gaussian_kernel(0, 0.0), vec![1.0]);
+  }
+
+  #[test]
+  fn amount_follows_downscale_factor() {
+    assert_eq!(sharpen_settings(1.0), (0.0, 0.5));
+    let (half, _) = sharpen_settings(2.0);
+    let (quarter, quarter_sigma) = sharpen_settings(4.0);
+    assert!((half - 0.3).abs() < 1e-6 && (quarter - 0.6).abs() < 1e-6);
+    assert!(quarter_sigma > 0.5 && sharpen_settings(1000.0) == (1.0, 1.0));
+  }
+}
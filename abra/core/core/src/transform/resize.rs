@@ -37,6 +37,15 @@ pub trait Resize {
   /// - `p_height`: The amount to change the height by. Positive values increase the height, negative values decrease it.
   /// - `p_algorithm`: The resizing algorithm to use. If None, the best algorithm will be selected automatically.
   fn resize_height_relative(&mut self, p_height: i32, p_algorithm: impl Into<Option<TransformAlgorithm>>);
+  /// Resize the image to fit within a box keeping the aspect ratio, and return the new size.
+  /// - `p_max_width`: The width of the box.
+  /// - `p_max_height`: The height of the box.
+  /// - `p_allow_upscale`: Whether images smaller than the box are enlarged to fill it.
+  /// - `p_algorithm`: The resizing algorithm to use. If None, the best algorithm will be selected automatically.
+  fn resize_to_fit(
+    &mut self, p_max_width: u32, p_max_height: u32, p_allow_upscale: bool,
+    p_algorithm: impl Into<Option<TransformAlgorithm>>,
+  ) -> (u32, u32);
 }
 
 /// Resize using Edge Direct NEDI algorithm.
@@ -639,6 +648,42 @@ pub fn height_relative(p_image: &mut Image, p_height: i32, p_algorithm: impl Int
   height(p_image, new_height, p_algorithm);
 }
 
+/// Calculates the largest size with the same aspect ratio as the given size that fits within a box.
+/// Sizes that already fit are returned unchanged unless upscaling is allowed. Neither side is made smaller than 1.
+/// - `p_width`: The width to fit.
+/// - `p_height`: The height to fit.
+/// - `p_max_width`: The width of the box.
+/// - `p_max_height`: The height of the box.
+/// - `p_allow_upscale`: Whether sizes smaller than the box are enlarged to fill it.
+pub fn fit_size(p_width: u32, p_height: u32, p_max_width: u32, p_max_height: u32, p_allow_upscale: bool) -> (u32, u32) {
+  if p_width == 0 || p_height == 0 {
+    return (p_width, p_height);
+  }
+  let scale = (p_max_width as f64 / p_width as f64).min(p_max_height as f64 / p_height as f64);
+  let scale = if p_allow_upscale { scale } else { scale.min(1.0) };
+  let new_width = ((p_width as f64 * scale).round() as u32).max(1);
+  let new_height = ((p_height as f64 * scale).round() as u32).max(1);
+  (new_width, new_height)
+}
+
+/// Resize the image to fit within a box keeping the aspect ratio, the usual way to make a thumbnail.
+/// For example a `1000x500` image fit into a `400x400` box becomes `400x200`.
+/// Resizing will only be performed if the dimensions have changed.
+/// - `p_image`: The image to resize.
+/// - `p_max_width`: The width of the box.
+/// - `p_max_height`: The height of the box.
+/// - `p_allow_upscale`: Whether images smaller than the box are enlarged to fill it.
+/// - `p_algorithm`: The resizing algorithm to use. If None, the best algorithm will be selected automatically.
+pub fn resize_to_fit(
+  p_image: &mut Image, p_max_width: u32, p_max_height: u32, p_allow_upscale: bool,
+  p_algorithm: impl Into<Option<TransformAlgorithm>>,
+) -> (u32, u32) {
+  let (old_width, old_height) = p_image.dimensions::<u32>();
+  let (new_width, new_height) = fit_size(old_width, old_height, p_max_width, p_max_height, p_allow_upscale);
+  resize(p_image, new_width, new_height, p_algorithm);
+  (new_width, new_height)
+}
+
 /// Resize the image by a scale factor.
 ///
 /// # Arguments
@@ -689,4 +734,28 @@ impl Resize for PrimitiveImage {
   fn resize_height_relative(&mut self, p_height: i32, p_algorithm: impl Into<Option<TransformAlgorithm>>) {
     crate::transform::height_relative(self, p_height, p_algorithm);
   }
+
+  fn resize_to_fit(
+    &mut self, p_max_width: u32, p_max_height: u32, p_allow_upscale: bool,
+    p_algorithm: impl Into<Option<TransformAlgorithm>>,
+  ) -> (u32, u32) {
+    crate::transform::resize_to_fit(self, p_max_width, p_max_height, p_allow_upscale, p_algorithm)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn resize_to_fit_keeps_aspect_and_only_upscales_when_allowed() {
+    let mut image = Image::new(1000, 500);
+    assert_eq!(image.resize_to_fit(400, 400, false, None), (400, 200));
+    assert_eq!(image.dimensions::<u32>(), (400, 200));
+
+    assert_eq!(image.resize_to_fit(800, 800, false, None), (400, 200));
+    assert_eq!(image.resize_to_fit(800, 300, true, None), (600, 300));
+    assert_eq!(image.dimensions::<u32>(), (600, 300));
+    assert_eq!(fit_size(3000, 10, 100, 100, false), (100, 1));
+  }
 }
@@ -5,7 +5,7 @@ use primitives::Image as PrimitiveImage;
 
 use rayon::prelude::*;
 
-use super::{TransformAlgorithm, resize::get_resize_algorithm};
+use super::{Orientation, TransformAlgorithm, resize::get_resize_algorithm};
 
 /// Trait for rotating images.
 pub trait Rotate {
@@ -610,29 +610,20 @@ pub fn rotate(p_image: &mut Image, p_degrees: impl Into<f64>, p_algorithm: impl
   // DebugTransform::Rotate(resolved_algorithm, degrees, old_width, old_height, new_width, new_height, duration).log();
 }
 
-/// Rotates the image 90 degrees clockwise.
+/// Rotates the image 90 degrees clockwise. The pixels are moved, not resampled, so nothing is lost.
 /// * `image` - The image to rotate.
-/// * `algorithm` - The interpolation algorithm to use. When `None`, an appropriate algorithm is selected automatically.
-pub fn rotate_90(p_image: &mut Image, p_algorithm: impl Into<Option<TransformAlgorithm>>) {
-  let (_resolved_algorithm, _old_width, _old_height, _new_width, _new_height, _duration) =
-    rotate_internal(p_image, 90.0, p_algorithm);
-  // DebugTransform::Rotate(resolved_algorithm, 90.0, old_width, old_height, new_width, new_height, duration).log();
+pub fn rotate_90(p_image: &mut Image) {
+  Orientation::IDENTITY.rotated(1).apply(p_image);
 }
 
-/// Rotates the image 90 degrees counter-clockwise.
+/// Rotates the image 90 degrees counter-clockwise. The pixels are moved, not resampled, so nothing is lost.
 /// * `image` - The image to rotate.
-/// * `algorithm` - The interpolation algorithm to use. When `None`, an appropriate algorithm is selected automatically.
-pub fn rotate_90_ccw(p_image: &mut Image, p_algorithm: impl Into<Option<TransformAlgorithm>>) {
-  let (_resolved_algorithm, _old_width, _old_height, _new_width, _new_height, _duration) =
-    rotate_internal(p_image, -90.0, p_algorithm);
-  // DebugTransform::Rotate(resolved_algorithm, -90.0, old_width, old_height, new_width, new_height, duration).log();
+pub fn rotate_90_ccw(p_image: &mut Image) {
+  Orientation::IDENTITY.rotated(-1).apply(p_image);
 }
 
-/// Rotates the image 180 degrees.
+/// Rotates the image 180 degrees. The pixels are moved, not resampled, so nothing is lost.
 /// * `image` - The image to rotate.
-/// * `algorithm` - The interpolation algorithm to use. When `None`, an appropriate algorithm is selected automatically.
-pub fn rotate_180(p_image: &mut Image, p_algorithm: impl Into<Option<TransformAlgorithm>>) {
-  let (_resolved_algorithm, _old_width, _old_height, _new_width, _new_height, _duration) =
-    rotate_internal(p_image, 180.0, p_algorithm);
-  // DebugTransform::Rotate(resolved_algorithm, 180.0, old_width, old_height, new_width, new_height, duration).log();
+pub fn rotate_180(p_image: &mut Image) {
+  Orientation::IDENTITY.rotated(2).apply(p_image);
 }
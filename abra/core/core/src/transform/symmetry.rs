@@ -0,0 +1,145 @@
+//! Mirror and kaleidoscope symmetry effects.
+
+use crate::Image;
+use crate::geometry::PointF;
+use crate::transform::sample_bilinear;
+use rayon::prelude::*;
+use std::f32::consts::PI;
+
+/// The line an image is reflected across by `mirror`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorAxis {
+  /// A vertical line; the left side is reflected onto the right side.
+  Vertical,
+  /// A horizontal line; the top side is reflected onto the bottom side.
+  Horizontal,
+}
+
+/// Reflects one wedge of the image around a center to fill the whole image with a kaleidoscope pattern.
+///
+/// The wedge starting at `p_angle` and spanning half a segment is mirrored into the other half, and that segment is
+/// repeated around the center, so the result has `p_segments`-fold rotational symmetry as well as mirror symmetry.
+/// Neighboring wedges are reflections of each other, so there are no seams between them. Every output pixel is
+/// sampled from the source with bilinear interpolation, and samples past the edge of the image repeat the edge.
+///
+/// - `p_image`: The image to sample the wedge from.
+/// - `p_segments`: The number of times the pattern repeats around the center. Values below 1 are treated as 1.
+/// - `p_center`: The center of the pattern relative to the image size, where `(0.5, 0.5)` is the middle.
+/// - `p_angle`: The direction in degrees, clockwise from the positive x axis, that the sampled wedge starts at.
+pub fn kaleidoscope(p_image: &Image, p_segments: u32, p_center: impl Into<PointF>, p_angle: f32) -> Image {
+  let (width, height) = p_image.dimensions::<u32>();
+  let mut output = Image::new(width, height);
+  if width == 0 || height == 0 {
+    return output;
+  }
+  let center = p_center.into();
+  let (center_x, center_y) = (center.x * width as f32, center.y * height as f32);
+  let segment = 2.0 * PI / p_segments.max(1) as f32;
+  let start = p_angle.to_radians();
+
+  let mut new_pixels = vec![0u8; (width * height * 4) as usize];
+  new_pixels.par_chunks_mut(4).enumerate().for_each(|(i, chunk)| {
+    let dx = (i as u32 % width) as f32 + 0.5 - center_x;
+    let dy = (i as u32 / width) as f32 + 0.5 - center_y;
+    let radius = dx.hypot(dy);
+    // Fold the angle into the first segment, then mirror its second half onto the first.
+    let mut theta = (dy.atan2(dx) - start).rem_euclid(segment);
+    if theta > segment / 2.0 {
+      theta = segment - theta;
+    }
+    let src_x = center_x + radius * (theta + start).cos() - 0.5;
+    let src_y = center_y + radius * (theta + start).sin() - 0.5;
+    chunk.copy_from_slice(&sample_clamped(p_image, src_x, src_y));
+  });
+
+  output.set_rgba_owned(new_pixels);
+  output
+}
+
+/// Reflects one side of the image across an axis onto the other side, making it symmetric.
+///
+/// The left side is kept for a vertical axis and the top side for a horizontal axis. When the axis is off center,
+/// the reflection of pixels past the edge of the kept side repeats that edge.
+///
+/// - `p_image`: The image to mirror.
+/// - `p_axis`: The direction of the axis to reflect across.
+/// - `p_position`: The position of the axis relative to the image width or height, where `0.5` is the middle.
+pub fn mirror(p_image: &Image, p_axis: MirrorAxis, p_position: f32) -> Image {
+  let (width, height) = p_image.dimensions::<u32>();
+  let mut output = Image::new(width, height);
+  if width == 0 || height == 0 {
+    return output;
+  }
+
+  let mut new_pixels = vec![0u8; (width * height * 4) as usize];
+  new_pixels.par_chunks_mut(4).enumerate().for_each(|(i, chunk)| {
+    let (x, y) = ((i as u32 % width) as f32 + 0.5, (i as u32 / width) as f32 + 0.5);
+    let (src_x, src_y) = match p_axis {
+      MirrorAxis::Vertical => {
+        let axis = p_position * width as f32;
+        (if x > axis { 2.0 * axis - x } else { x }, y)
+      }
+      MirrorAxis::Horizontal => {
+        let axis = p_position * height as f32;
+        (x, if y > axis { 2.0 * axis - y } else { y })
+      }
+    };
+    chunk.copy_from_slice(&sample_clamped(p_image, src_x - 0.5, src_y - 0.5));
+  });
+
+  output.set_rgba_owned(new_pixels);
+  output
+}
+
+/// Samples the image with bilinear interpolation, clamping the position to the image so the edges repeat.
+fn sample_clamped(p_image: &Image, p_x: f32, p_y: f32) -> [u8; 4] {
+  let (width, height) = p_image.dimensions::<u32>();
+  sample_bilinear(p_image, p_x.clamp(0.0, (width - 1) as f32), p_y.clamp(0.0, (height - 1) as f32))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// An image without any symmetry of its own.
+  fn pattern(p_size: u32) -> Image {
+    let mut image = Image::new(p_size, p_size);
+    for y in 0..p_size {
+      for x in 0..p_size {
+        image.set_pixel(x, y, ((x * 7) as u8, (y * 5) as u8, ((x * y) % 251) as u8, 255));
+      }
+    }
+    image
+  }
+
+  #[test]
+  fn four_segments_have_fourfold_symmetry() {
+    let size = 41;
+    let image = kaleidoscope(&pattern(size), 4, (0.5, 0.5), 10.0);
+    for y in 0..size {
+      for x in 0..size {
+        // A quarter turn around the center pixel.
+        let (a, b) = (image.get_pixel(x, y).unwrap(), image.get_pixel(size - 1 - y, x).unwrap());
+        for (c1, c2) in [(a.0, b.0), (a.1, b.1), (a.2, b.2)] {
+          assert!((c1 as i32 - c2 as i32).abs() <= 2, "({}, {}): {:?} {:?}", x, y, a, b);
+        }
+        assert_eq!(a.3, 255);
+      }
+    }
+  }
+
+  #[test]
+  fn mirror_reflects_across_axis() {
+    let source = pattern(20);
+    let image = mirror(&source, MirrorAxis::Vertical, 0.5);
+    for y in 0..20 {
+      for x in 0..10 {
+        assert_eq!(image.get_pixel(x, y), source.get_pixel(x, y));
+        assert_eq!(image.get_pixel(19 - x, y), source.get_pixel(x, y));
+      }
+    }
+    let image = mirror(&source, MirrorAxis::Horizontal, 0.25);
+    assert_eq!(image.get_pixel(3, 7), source.get_pixel(3, 2));
+    assert_eq!(image.get_pixel(3, 19), source.get_pixel(3, 0));
+  }
+}
@@ -0,0 +1,165 @@
+//! Liquify-style brush warps for local distortion.
+//!
+//! Each warp only changes the pixels within the brush radius and uses backward mapping: every output pixel
+//! samples the source with bilinear interpolation, so the result has no holes. Calling a warp repeatedly with
+//! small offsets along a stroke lets an editor implement drag-to-warp.
+
+use crate::Image;
+use crate::geometry::PointF;
+use crate::transform::sample_bilinear;
+use rayon::prelude::*;
+
+/// The brush weight at a distance from the center: `1.0` at the center, easing smoothly to `0.0` at the radius.
+fn falloff(p_distance: f32, p_radius: f32) -> f32 {
+  let t = (p_distance / p_radius).min(1.0);
+  let inner = 1.0 - t * t;
+  inner * inner
+}
+
+/// Replaces the pixels within the brush radius with samples from the positions returned by `p_source`.
+/// - `p_image`: The image to warp.
+/// - `p_center`: The center of the brush in pixels.
+/// - `p_radius`: The radius of the brush in pixels.
+/// - `p_source`: Maps an output position and its brush weight to the source position to sample.
+fn warp<F>(p_image: &mut Image, p_center: PointF, p_radius: f32, p_source: F)
+where
+  F: Fn(f32, f32, f32) -> (f32, f32) + Sync,
+{
+  let (width, height) = p_image.dimensions::<u32>();
+  if width == 0 || height == 0 || p_radius <= 0.0 {
+    return;
+  }
+  // Only the brush's bounding box can change.
+  let left = (p_center.x - p_radius).floor().max(0.0) as u32;
+  let top = (p_center.y - p_radius).floor().max(0.0) as u32;
+  let right = ((p_center.x + p_radius).ceil().max(0.0) as u32).min(width);
+  let bottom = ((p_center.y + p_radius).ceil().max(0.0) as u32).min(height);
+  if left >= right || top >= bottom {
+    return;
+  }
+
+  let source = p_image.clone();
+  let (max_x, max_y) = ((width - 1) as f32, (height - 1) as f32);
+  let mut pixels = source.rgba().to_vec();
+  pixels
+    .par_chunks_mut(width as usize * 4)
+    .enumerate()
+    .skip(top as usize)
+    .take((bottom - top) as usize)
+    .for_each(|(y, row)| {
+      for x in left..right {
+        // Work at pixel centers so the warp is symmetric around the brush center.
+        let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+        let weight = falloff((px - p_center.x).hypot(py - p_center.y), p_radius);
+        if weight <= 0.0 {
+          continue;
+        }
+        let (sx, sy) = p_source(px, py, weight);
+        // Clamp to the image so brushes near the border smear the edge instead of pulling in transparency.
+        let pixel = sample_bilinear(&source, (sx - 0.5).clamp(0.0, max_x), (sy - 0.5).clamp(0.0, max_y));
+        let i = x as usize * 4;
+        row[i..i + 4].copy_from_slice(&pixel);
+      }
+    });
+  p_image.set_rgba_owned(pixels);
+}
+
+/// Pushes the pixels under a brush in a direction, strongest at the center and fading out at the radius,
+/// like the forward warp tool of a liquify filter.
+/// - `p_image`: The image to warp.
+/// - `p_center`: The center of the brush in pixels.
+/// - `p_radius`: The radius of the brush in pixels.
+/// - `p_dx`: How far to push horizontally in pixels.
+/// - `p_dy`: How far to push vertically in pixels.
+/// - `p_strength`: How much of the offset is applied, from `0.0` to `1.0`. Keep the offset below about the
+///   radius for each call so the image does not fold over itself, and use several calls for longer strokes.
+pub fn warp_push(
+  p_image: &mut Image, p_center: impl Into<PointF>, p_radius: f32, p_dx: f32, p_dy: f32, p_strength: f32,
+) {
+  let strength = p_strength.clamp(0.0, 1.0);
+  warp(p_image, p_center.into(), p_radius, |x, y, weight| (x - p_dx * strength * weight, y - p_dy * strength * weight));
+}
+
+/// Pulls the pixels under a brush toward its center, shrinking the features there.
+/// - `p_image`: The image to warp.
+/// - `p_center`: The center of the brush in pixels.
+/// - `p_radius`: The radius of the brush in pixels.
+/// - `p_strength`: How strongly to pinch, from `0.0` to `1.0`.
+pub fn warp_pinch(p_image: &mut Image, p_center: impl Into<PointF>, p_radius: f32, p_strength: f32) {
+  let center = p_center.into();
+  let strength = p_strength.clamp(0.0, 1.0);
+  warp(p_image, center, p_radius, |x, y, weight| {
+    let scale = 1.0 + strength * weight;
+    (center.x + (x - center.x) * scale, center.y + (y - center.y) * scale)
+  });
+}
+
+/// Pushes the pixels under a brush away from its center, enlarging the features there.
+/// - `p_image`: The image to warp.
+/// - `p_center`: The center of the brush in pixels.
+/// - `p_radius`: The radius of the brush in pixels.
+/// - `p_strength`: How strongly to bloat, from `0.0` to `1.0`.
+pub fn warp_bloat(p_image: &mut Image, p_center: impl Into<PointF>, p_radius: f32, p_strength: f32) {
+  let center = p_center.into();
+  let strength = p_strength.clamp(0.0, 1.0);
+  warp(p_image, center, p_radius, |x, y, weight| {
+    let scale = 1.0 - 0.5 * strength * weight;
+    (center.x + (x - center.x) * scale, center.y + (y - center.y) * scale)
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use primitives::Color;
+
+  /// A white image with a black vertical line.
+  fn line_image(p_x: u32) -> Image {
+    let mut image = Image::new_from_color(41, 41, Color::white());
+    for y in 0..41 {
+      image.set_pixel(p_x, y, (0, 0, 0, 255));
+    }
+    image
+  }
+
+  /// The x coordinate of the darkest pixel in a row.
+  fn darkest_column(p_image: &Image, p_y: u32) -> u32 {
+    (0..41).min_by_key(|x| p_image.get_pixel(*x, p_y).unwrap().0).unwrap()
+  }
+
+  #[test]
+  fn push_moves_features_within_radius_smoothly() {
+    let mut image = line_image(20);
+    warp_push(&mut image, (20.5, 20.5), 10.0, 5.0, 0.0, 1.0);
+
+    let center = darkest_column(&image, 20);
+    let edge = darkest_column(&image, 27);
+    assert!((23..=25).contains(&center), "center moved to {}", center);
+    assert!(edge > 20 && edge < center, "edge moved to {}", edge);
+    // Outside the brush nothing changes.
+    assert_eq!(darkest_column(&image, 5), 20);
+    assert_eq!(image.get_pixel(20, 35), Some((0, 0, 0, 255)));
+    assert_eq!(image.get_pixel(35, 20), Some((255, 255, 255, 255)));
+    // Bilinear sampling leaves no transparent holes.
+    assert!(image.rgba().chunks(4).all(|px| px[3] == 255));
+  }
+
+  #[test]
+  fn zero_strength_leaves_image_unchanged() {
+    let mut image = line_image(20);
+    warp_push(&mut image, (20.5, 20.5), 10.0, 5.0, 0.0, 0.0);
+    assert_eq!(image.rgba(), line_image(20).rgba());
+  }
+
+  #[test]
+  fn pinch_pulls_in_and_bloat_pushes_out() {
+    let mut pinched = line_image(25);
+    warp_pinch(&mut pinched, (20.5, 20.5), 12.0, 1.0);
+    assert!(darkest_column(&pinched, 20) < 25);
+
+    let mut bloated = line_image(25);
+    warp_bloat(&mut bloated, (20.5, 20.5), 12.0, 1.0);
+    assert!(darkest_column(&bloated, 20) > 25);
+    assert_eq!(darkest_column(&bloated, 2), 25);
+  }
+}
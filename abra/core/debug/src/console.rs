@@ -174,18 +174,26 @@ impl DebugEffects {
     match self {
       // #endregion: Transform Outputs
       DebugEffects::DropShadow(options, duration) => debug_println!(
-        "    Effect::DropShadow: distance={}; opacity={}; angle={}; size={}; spread={}; blend_mode={}; fill={}; time={:?}",
+        "    Effect::DropShadow: distance={}; opacity={}; angle={}; size={}; blur={}; spread={}; knockout={}; blend_mode={}; fill={}; time={:?}",
         options.distance,
         options.opacity,
         options.angle,
         options.size,
+        options.blur,
         options.spread,
+        options.knockout,
         blend_mode_name(options.blend_mode),
         options.fill,
         duration
       ),
       DebugEffects::Stroke(options, duration) => {
-        debug_println!("    Effect::Stroke: fill={}; size={}; time={:?}", options.fill, options.size, duration)
+        debug_println!(
+          "    Effect::Stroke: fill={}; size={}; alignment={:?}; time={:?}",
+          options.fill,
+          options.size,
+          options.alignment,
+          duration
+        )
       }
     }
   }
@@ -1,5 +1,16 @@
 use abra_core::{Area, Color, Fill};
 
+/// How a gradient brush color is laid out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorDynamics {
+  /// Every dab shows the whole gradient from its left to its right edge, and strokes show it across their width.
+  #[default]
+  PerDab,
+  /// The color progresses through the gradient from the start to the end of the stroke.
+  /// A single dab has no stroke to follow and uses the first color.
+  AlongStroke,
+}
+
 /// A brush represents a drawing tool with a specific size.
 /// It encapsulates properties such as size, shape (area), and fill color.
 /// Brushes can be used for painting, drawing, and other graphical operations.
@@ -22,6 +33,14 @@ pub struct Brush {
   hardness: f32,
   /// The opacity of the brush (0.0 to 1.0).
   opacity: f32,
+  /// How a gradient color is laid out.
+  color_dynamics: ColorDynamics,
+  /// How far stamps are scattered from each dab position, as a multiple of the brush size.
+  scatter: f32,
+  /// How many stamps each dab position spawns when scattering.
+  scatter_count: u32,
+  /// The seed that places the scattered stamps.
+  seed: u64,
 }
 
 impl Brush {
@@ -34,6 +53,10 @@ impl Brush {
       color: Fill::Solid(Color::black()),
       hardness: 0.0,
       opacity: 1.0,
+      color_dynamics: ColorDynamics::PerDab,
+      scatter: 0.0,
+      scatter_count: 1,
+      seed: 0,
     }
   }
   /// Sets the size of the brush.
@@ -54,18 +77,42 @@ impl Brush {
     self.color = p_color.into();
     self
   }
-  /// Sets the hardness of the brush.
+  /// Sets the hardness of the brush. Soft brushes fade out smoothly from the center, hard brushes keep an
+  /// opaque core and only soften the outermost pixel.
   /// - `p_hardness`: The hardness value to set for the brush (0.0 to 1.0).
   pub fn with_hardness(mut self, p_hardness: f32) -> Self {
     self.hardness = p_hardness.clamp(0.0, 1.0);
     self
   }
-  /// Sets the opacity of the brush.
+  /// Sets the opacity of the brush. This is the most coverage a whole stroke can reach, so parts of a stroke that
+  /// overlap themselves do not build up beyond it.
   /// - `p_opacity`: The opacity value to set for the brush (0.0 to 1.0).
   pub fn with_opacity(mut self, p_opacity: f32) -> Self {
     self.opacity = p_opacity.clamp(0.0, 1.0);
     self
   }
+  /// Sets how a gradient color is laid out. Solid and image colors are not affected.
+  /// - `p_color_dynamics`: Whether the gradient spans each dab or the length of the stroke.
+  pub fn with_color_dynamics(mut self, p_color_dynamics: ColorDynamics) -> Self {
+    self.color_dynamics = p_color_dynamics;
+    self
+  }
+  /// Scatters the brush: every dab position spawns several stamps at random spots around it, which turns
+  /// strokes into sprays of particles instead of solid lines.
+  /// - `p_amount`: How far stamps land from the dab position, as a multiple of the brush size. `0.0` disables
+  ///   scattering.
+  /// - `p_count`: How many stamps each dab position spawns, at least `1`.
+  pub fn with_scatter(mut self, p_amount: f32, p_count: u32) -> Self {
+    self.scatter = p_amount.max(0.0);
+    self.scatter_count = p_count.max(1);
+    self
+  }
+  /// Sets the seed of the brush's randomness. The same seed always scatters stamps to the same spots.
+  /// - `p_seed`: The seed to use.
+  pub fn with_seed(mut self, p_seed: u64) -> Self {
+    self.seed = p_seed;
+    self
+  }
   /// Returns the size of the brush.
   pub fn size(&self) -> u32 {
     self.size
@@ -86,4 +133,20 @@ impl Brush {
   pub fn opacity(&self) -> f32 {
     self.opacity
   }
+  /// Returns how a gradient color is laid out.
+  pub fn color_dynamics(&self) -> ColorDynamics {
+    self.color_dynamics
+  }
+  /// Returns how far stamps are scattered, as a multiple of the brush size.
+  pub fn scatter(&self) -> f32 {
+    self.scatter
+  }
+  /// Returns how many stamps each dab position spawns when scattering.
+  pub fn scatter_count(&self) -> u32 {
+    self.scatter_count
+  }
+  /// Returns the seed of the brush's randomness.
+  pub fn seed(&self) -> u64 {
+    self.seed
+  }
 }
@@ -4,8 +4,8 @@
 //! The rasterizer gathers sample colors for a pixel and averages them; a
 //! `Compositor` determines how this averaged source color should be
 //! blended with the already-written destination pixel. This module
-//! provides the `Compositor` trait and a few implementations, including
-//! a source-over compositing rule and a simple overwrite compositor.
+//! provides the `Compositor` trait and its implementations: a source-over
+//! compositing rule and a compositor that blends with a blend mode.
 //!
 //! Key points
 //! - Compositors are responsible for combining source RGBA with a
@@ -14,8 +14,8 @@
 //! - Implementations must be `Sync` (they are called by the parallel
 //!   rasterization code).
 //! - Core implementations: `SourceOverCompositor` (standard alpha
-//!   compositing) and `OverwriteCompositor` (replace destination with
-//!   source, used for legacy behavior or special effects).
+//!   compositing) and `BlendCompositor` (mixes the source with the
+//!   destination through a blend function such as multiply or screen).
 //!
 //! Example
 //! ```ignore
@@ -23,6 +23,8 @@
 //! let out = comp.composite(255, 0, 0, 128, 1.0, 0, 0, 255, 255);
 //! ```
 
+use abra_core::blend::BlendFn;
+
 /// Trait for compositing a source color on top of a destination color.
 ///
 /// Implementations provide a `composite` method that computes an output
@@ -91,3 +93,69 @@ impl Compositor for SourceOverCompositor {
     )
   }
 }
+
+/// Compositing through a blend mode, for drawing with multiply, screen, additive and the other blend functions
+/// without going through layers.
+///
+/// The blend function combines the source color with the destination color, where the destination is the bottom
+/// color and the source the top color. The blended color is then composited source-over using the source alpha and
+/// coverage, so soft brush edges fade into the destination. Over transparent pixels the source color is used as is.
+///
+/// Example
+/// ```ignore
+/// let compositor = BlendCompositor::new(abra_core::blend::multiply);
+/// let out = compositor.composite(128, 128, 128, 255, 1.0, 255, 0, 0, 255);
+/// // out is (128, 0, 0, 255).
+/// ```
+pub struct BlendCompositor {
+  blend: BlendFn,
+}
+
+impl BlendCompositor {
+  /// Creates a compositor that blends with the given blend function.
+  /// - `p_blend`: The blend function, called with the destination and the source color.
+  pub fn new(p_blend: BlendFn) -> Self {
+    BlendCompositor { blend: p_blend }
+  }
+}
+
+impl Compositor for BlendCompositor {
+  fn composite(
+    &self, p_src_r: u8, p_src_g: u8, p_src_b: u8, p_src_a: u8, p_coverage: f32, p_dst_r: u8, p_dst_g: u8, p_dst_b: u8,
+    p_dst_a: u8,
+  ) -> (u8, u8, u8, u8) {
+    // Blend the opaque colors, the alphas are handled by the source-over step.
+    let (blend_r, blend_g, blend_b, _) =
+      (self.blend)((p_dst_r, p_dst_g, p_dst_b, 255), (p_src_r, p_src_g, p_src_b, 255));
+    // Where the destination is partly transparent, the source shows through unblended.
+    let dst_a = p_dst_a as f32 / 255.0;
+    let mix = |src: u8, blended: u8| (src as f32 * (1.0 - dst_a) + blended as f32 * dst_a).round() as u8;
+    SourceOverCompositor.composite(
+      mix(p_src_r, blend_r),
+      mix(p_src_g, blend_g),
+      mix(p_src_b, blend_b),
+      p_src_a,
+      p_coverage,
+      p_dst_r,
+      p_dst_g,
+      p_dst_b,
+      p_dst_a,
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::blend;
+
+  #[test]
+  fn blend_compositor_blends_with_opaque_destination() {
+    let compositor = BlendCompositor::new(blend::multiply);
+    assert_eq!(compositor.composite(128, 128, 128, 255, 1.0, 255, 0, 0, 255), (128, 0, 0, 255));
+    // Half coverage lands halfway between the destination and the blended color.
+    assert_eq!(compositor.composite(0, 0, 0, 255, 0.5, 200, 200, 200, 255), (100, 100, 100, 255));
+    // Over transparency the source is drawn as is.
+    assert_eq!(compositor.composite(10, 20, 30, 255, 1.0, 0, 0, 0, 0), (10, 20, 30, 255));
+  }
+}
@@ -4,7 +4,8 @@
 //! rasterizer to limit its work and to determine which sub-pixel samples
 //! are part of a fill or stroke. This module implements several useful
 //! masks:
-//! * `PolygonCoverage` — point-in-polygon coverage for arbitrary polygons.
+//! * `PolygonCoverage` — point-in-polygon coverage for arbitrary polygons,
+//!   including polygons made of several contours (holes, disjoint shapes).
 //! * `BrushCoverageMask` — a polygonal region with radial alpha falloff
 //!   (useful for painting brushes).
 //! * `FullCoverage` — a trivial mask covering the entire image.
@@ -54,12 +55,14 @@ pub trait CoverageMask: Sync {
 
 /// A polygon-based coverage mask.
 ///
-/// This implementation stores a flat vector of vertices and performs a
-/// point-in-polygon test for `contains`. It also computes a bounding box
-/// that can be used by the rasterizer to optimize pixel iteration.
+/// This implementation stores one vertex list per contour and performs a
+/// point-in-polygon test for `contains`. Windings of every contour are
+/// summed, so a contour wound against its parent cuts a hole. It also
+/// computes a bounding box that can be used by the rasterizer to optimize
+/// pixel iteration.
 pub struct PolygonCoverage {
-  /// Pre-flattened polygon vertices.
-  polygon: Vec<(f32, f32)>,
+  /// Pre-flattened polygon vertices, one list per contour.
+  contours: Vec<Vec<(f32, f32)>>,
 }
 
 impl PolygonCoverage {
@@ -77,20 +80,39 @@ impl PolygonCoverage {
   /// let poly = PolygonCoverage::new(vec![PointF::new(0.0,0.0), PointF::new(20.0,0.0), PointF::new(20.0,20.0)]);
   /// ```
   pub fn new(p_points: Vec<PointF>) -> Self {
+    PolygonCoverage::new_from_contours(vec![p_points])
+  }
+
+  /// Creates a new `PolygonCoverage` from several contours, such as the
+  /// output of `Path::flatten_contours`. Each contour is implicitly closed.
+  ///
+  /// Parameters
+  /// - `p_contours`: One vector of vertices per contour.
+  ///
+  /// Example
+  /// ```ignore
+  /// let poly = PolygonCoverage::new_from_contours(area.path.flatten_contours(0.5));
+  /// ```
+  pub fn new_from_contours(p_contours: Vec<Vec<PointF>>) -> Self {
     PolygonCoverage {
-      polygon: p_points.iter().map(|p| (p.x, p.y)).collect(),
+      contours: p_contours
+        .iter()
+        .filter(|c| !c.is_empty())
+        .map(|c| c.iter().map(|p| (p.x, p.y)).collect())
+        .collect(),
     }
   }
 
   fn compute_bounds(&self) -> (f32, f32, f32, f32) {
-    if self.polygon.is_empty() {
+    let mut points = self.contours.iter().flatten();
+    let Some(&(first_x, first_y)) = points.next() else {
       return (0.0, 0.0, 0.0, 0.0);
-    }
-    let mut min_x = self.polygon[0].0;
-    let mut min_y = self.polygon[0].1;
-    let mut max_x = self.polygon[0].0;
-    let mut max_y = self.polygon[0].1;
-    for &(x, y) in &self.polygon {
+    };
+    let mut min_x = first_x;
+    let mut min_y = first_y;
+    let mut max_x = first_x;
+    let mut max_y = first_y;
+    for &(x, y) in points {
       min_x = min_x.min(x);
       min_y = min_y.min(y);
       max_x = max_x.max(x);
@@ -104,16 +126,25 @@ impl PolygonCoverage {
   /// Returns `true` when the given point is inside the polygon. This
   /// method is an implementation detail but is used by `contains`.
   fn point_in_polygon(&self, p_point: (f32, f32)) -> bool {
-    if self.polygon.is_empty() {
-      return false;
-    }
+    self.winding_number(p_point) != 0
+  }
+
+  /// Sums the winding number of every contour around the given point.
+  fn winding_number(&self, p_point: (f32, f32)) -> i32 {
+    self
+      .contours
+      .iter()
+      .map(|contour| Self::contour_winding(contour, p_point))
+      .sum()
+  }
 
-    // Non-zero winding rule
+  /// Computes the winding number of a single closed contour around a point.
+  fn contour_winding(p_contour: &[(f32, f32)], p_point: (f32, f32)) -> i32 {
     let mut winding = 0i32;
-    let mut j = self.polygon.len() - 1;
-    for i in 0..self.polygon.len() {
-      let (xi, yi) = self.polygon[i];
-      let (xj, yj) = self.polygon[j];
+    let mut j = p_contour.len() - 1;
+    for i in 0..p_contour.len() {
+      let (xi, yi) = p_contour[i];
+      let (xj, yj) = p_contour[j];
       if yi <= p_point.1 {
         if yj > p_point.1 {
           // upward crossing
@@ -133,7 +164,7 @@ impl PolygonCoverage {
       }
       j = i;
     }
-    winding != 0
+    winding
   }
 }
 
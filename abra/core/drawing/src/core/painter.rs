@@ -1,56 +1,177 @@
-use abra_core::{Area, Image, LineCap, LineJoin, Path, PointF};
+use abra_core::{Area, Fill, Image, LineCap, LineJoin, Path, PathMeasure, PointF};
 
 use crate::{
-  CoverageMask, PolygonCoverage, Rasterizer, SampleGrid, Shader, SourceOverCompositor,
+  ColorDynamics, Compositor, CoverageMask, PolygonCoverage, Rasterizer, SampleGrid, Shader, SourceOverCompositor,
   brush::brush::Brush,
   shader_from_fill_with_path,
-  shaders::{brush_dabs_shader::BrushDabsShader, brush_shader::BrushShader, stroke_brush_shader::StrokeBrushShader},
+  shaders::{
+    brush_dabs_shader::BrushDabsShader, brush_shader::BrushShader, opacity_shader::OpacityShader,
+    stroke_brush_shader::StrokeBrushShader,
+  },
 };
 
+/// Caps the alpha of a brush shader at the brush opacity.
+fn with_brush_opacity(p_shader: Box<dyn Shader + Send + Sync>, p_brush: &Brush) -> Box<dyn Shader + Send + Sync> {
+  if p_brush.opacity() >= 1.0 {
+    p_shader
+  } else {
+    Box::new(OpacityShader::new(p_shader, p_brush.opacity()))
+  }
+}
+
+/// A pseudo-random offset within the scatter radius of a brush. The same brush seed, dab and stamp index
+/// always give the same offset, so scattered strokes are reproducible.
+/// - `p_brush`: The scattering brush.
+/// - `p_dab`: Identifies the dab position the stamp belongs to.
+/// - `p_stamp`: The index of the stamp within the dab.
+fn scatter_offset(p_brush: &Brush, p_dab: u32, p_stamp: u32) -> PointF {
+  let random = |p_salt: u64| {
+    // SplitMix64 finalizer over the seed and the stamp's identity.
+    let mut z = p_brush.seed() ^ ((p_dab as u64) << 32 | p_stamp as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = z.wrapping_add(p_salt.wrapping_mul(0xD1B5_4A32_D192_ED03));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    ((z ^ (z >> 31)) >> 40) as f32 / (1u64 << 24) as f32
+  };
+  // Uniform over the disc: the square root keeps stamps from bunching up at the center.
+  let radius = p_brush.scatter() * p_brush.size() as f32 * random(1).sqrt();
+  let angle = random(2) * std::f32::consts::TAU;
+  PointF::new(radius * angle.cos(), radius * angle.sin())
+}
+
 /// Unified drawing context for an image.
 pub struct Painter<'a> {
   image: &'a mut Image,
+  compositor: &'a dyn Compositor,
+  clip: Option<&'a dyn CoverageMask>,
+  sample_grid: SampleGrid,
 }
 
 impl<'a> Painter<'a> {
-  /// Creates a new painter for the given image.
+  /// Creates a new painter for the given image. The painter composites source-over until another
+  /// compositor is set with [`Painter::with_compositor`].
   pub fn new(image: &'a mut Image) -> Self {
-    Painter { image }
+    Painter {
+      image,
+      compositor: &SourceOverCompositor,
+      clip: None,
+      sample_grid: SampleGrid::from_aa_level(2),
+    }
+  }
+
+  /// Sets the compositor that merges painted colors into the image, such as a
+  /// [`BlendCompositor`](crate::BlendCompositor) to paint with a blend mode.
+  /// - `p_compositor`: The compositor to use.
+  pub fn with_compositor(mut self, p_compositor: &'a dyn Compositor) -> Self {
+    self.compositor = p_compositor;
+    self
   }
 
-  /// Paints a single brush dab at a specific position.
+  /// Clips everything the painter draws to a mask, such as a `PolygonCoverage` of a path.
+  /// - `p_clip`: The mask to clip to.
+  pub fn with_clip(mut self, p_clip: &'a dyn CoverageMask) -> Self {
+    self.clip = Some(p_clip);
+    self
+  }
+
+  /// Sets the supersampling used to anti-alias what the painter draws. Brushes default to 2x2 samples,
+  /// raise it for thin strokes or lower it for speed.
+  /// - `p_sample_grid`: The sample grid to use.
+  pub fn with_sample_grid(mut self, p_sample_grid: SampleGrid) -> Self {
+    self.sample_grid = p_sample_grid;
+    self
+  }
+
+  /// Rasterizes a shader within a coverage onto the image with the painter's compositor, clip and sample grid.
+  fn rasterize(&mut self, p_coverage: &dyn CoverageMask, p_shader: &dyn Shader) {
+    let mut rasterizer = Rasterizer::new(p_coverage, p_shader, self.compositor, self.sample_grid);
+    if let Some(clip) = self.clip {
+      rasterizer = rasterizer.with_clip(clip);
+    }
+    rasterizer.rasterize(self.image);
+  }
+
+  /// Paints a single brush dab at a specific position. A scattering brush spawns its stamps around the position.
   /// - `x`: The x-coordinate to paint at.
   /// - `y`: The y-coordinate to paint at.
   /// - `brush`: The brush to use for painting.
   pub fn dab_brush(&mut self, x: f32, y: f32, brush: &Brush) {
+    let fill = match (brush.color(), brush.color_dynamics()) {
+      // A lone dab is the start of a stroke.
+      (Fill::Gradient(gradient), ColorDynamics::AlongStroke) => Fill::Solid(gradient.get_color_type(0.0)),
+      (fill, _) => fill.clone(),
+    };
+    if brush.scatter() <= 0.0 {
+      self.stamp(x, y, brush, fill);
+      return;
+    }
+    // Mix the position into the seed so dabs at different spots do not repeat the same pattern.
+    let key = x.to_bits() ^ y.to_bits().rotate_left(16);
+    for index in 0..brush.scatter_count() {
+      let offset = scatter_offset(brush, key, index);
+      self.stamp(x + offset.x, y + offset.y, brush, fill.clone());
+    }
+  }
+
+  /// Paints one stamp of the brush shape at a position.
+  /// - `x`: The x-coordinate of the stamp center.
+  /// - `y`: The y-coordinate of the stamp center.
+  /// - `brush`: The brush providing the shape, size, hardness and opacity.
+  /// - `fill`: The color of the stamp.
+  fn stamp(&mut self, x: f32, y: f32, brush: &Brush, fill: Fill) {
     let size = brush.size() as f32;
     let area = brush.area();
-    let fill = brush.color();
 
     let scale_factor = size / 10.0;
 
     let tolerance = 0.5;
-    let flattened: Vec<PointF> = area
+    let contours: Vec<Vec<PointF>> = area
       .path
-      .flatten(tolerance)
+      .flatten_contours(tolerance)
       .into_iter()
-      .map(|p| PointF::new(p.x * scale_factor + x, p.y * scale_factor + y))
+      .map(|contour| {
+        contour
+          .into_iter()
+          .map(|p| PointF::new(p.x * scale_factor + x, p.y * scale_factor + y))
+          .collect()
+      })
       .collect();
 
-    let coverage = PolygonCoverage::new(flattened);
+    let coverage = PolygonCoverage::new_from_contours(contours);
     // Wrap inner shader with BrushShader to apply alpha falloff based on hardness
     // Build a default gradient path spanning the dab horizontally so
     // linear gradients without explicit direction are visible.
     let dab_path = Path::line((x - size / 2.0, y), (x + size / 2.0, y));
-    let inner_shader = shader_from_fill_with_path(fill.clone(), Some(dab_path));
+    let inner_shader = shader_from_fill_with_path(fill, Some(dab_path));
     let max_distance = size / 2.0;
-    let shader: Box<dyn Shader + Send + Sync> =
-      Box::new(BrushShader::new(inner_shader, x, y, max_distance, brush.hardness()));
-    let compositor = SourceOverCompositor;
-    let sample_grid = SampleGrid::from_aa_level(2);
-    let rasterizer = Rasterizer::new(&coverage, shader.as_ref(), &compositor, sample_grid);
+    let shader =
+      with_brush_opacity(Box::new(BrushShader::new(inner_shader, x, y, max_distance, brush.hardness())), brush);
+    self.rasterize(&coverage, shader.as_ref());
+  }
 
-    rasterizer.rasterize(self.image);
+  /// Scatters stamps along a path: dab positions are spaced half a brush size apart and each spawns
+  /// the brush's scatter count of stamps around it.
+  /// - `path`: The path to follow.
+  /// - `brush`: The scattering brush.
+  fn scatter_along(&mut self, path: &Path, brush: &Brush) {
+    let measure = PathMeasure::new(path);
+    let length = measure.length();
+    let spacing = (brush.size() as f32 / 2.0).max(1.0);
+    let steps = (length / spacing).ceil() as u32;
+    for step in 0..=steps {
+      let distance = (step as f32 * spacing).min(length);
+      let (point, _) = measure.point_at_distance(distance);
+      let fill = match (brush.color(), brush.color_dynamics()) {
+        (Fill::Gradient(gradient), ColorDynamics::AlongStroke) => {
+          Fill::Solid(gradient.get_color_type(if length > 0.0 { distance / length } else { 0.0 }))
+        }
+        (fill, _) => fill.clone(),
+      };
+      for index in 0..brush.scatter_count() {
+        let offset = scatter_offset(brush, step, index);
+        self.stamp(point.x + offset.x, point.y + offset.y, brush, fill.clone());
+      }
+    }
   }
 
   /// Strokes a path with a brush by converting it into a stroked area
@@ -58,6 +179,10 @@ impl<'a> Painter<'a> {
   /// - `path`: The path to stroke.
   /// - `brush`: The brush to use for stroking.
   pub fn stroke_with_brush(&mut self, path: &Path, brush: &Brush) {
+    if brush.scatter() > 0.0 {
+      self.scatter_along(path, brush);
+      return;
+    }
     let width = brush.size() as f32;
 
     // Convert open path into an area and then create a stroked outline
@@ -67,29 +192,20 @@ impl<'a> Painter<'a> {
 
     // Build flattened polygon coverage for the stroked area
     let tolerance = 0.5;
-    let flattened: Vec<PointF> = stroke_area
-      .path
-      .flatten(tolerance)
-      .into_iter()
-      .map(|p| PointF::new(p.x, p.y))
-      .collect();
+    let coverage = PolygonCoverage::new_from_contours(stroke_area.path.flatten_contours(tolerance));
 
-    let coverage = PolygonCoverage::new(flattened);
-
-    // Create inner shader from fill and wrap in StrokeBrushShader to compute falloff from path centerline
-    // For stroke brushes, prefer the stroke path as the gradient direction so
-    // gradients are oriented along the stroke centerline.
+    // Create inner shader from fill and wrap in StrokeBrushShader to compute falloff from path centerline.
+    // Gradients are laid out by the stroke shader itself according to the brush color dynamics.
     let inner_shader = shader_from_fill_with_path(brush.color().clone(), Some(path.clone()));
     // Path stroke shading falloff radius is (width / 2)
     let max_distance = width / 2.0;
-    let shader: Box<dyn Shader + Send + Sync> =
-      Box::new(StrokeBrushShader::new(inner_shader, path.clone(), max_distance, brush.hardness()));
-
-    let compositor = SourceOverCompositor;
-    let sample_grid = SampleGrid::from_aa_level(2);
-    let rasterizer = Rasterizer::new(&coverage, shader.as_ref(), &compositor, sample_grid);
+    let mut stroke_shader = StrokeBrushShader::new(inner_shader, path.clone(), max_distance, brush.hardness());
+    if let Fill::Gradient(gradient) = brush.color() {
+      stroke_shader = stroke_shader.with_gradient(gradient.clone(), brush.color_dynamics());
+    }
+    let shader = with_brush_opacity(Box::new(stroke_shader), brush);
 
-    rasterizer.rasterize(self.image);
+    self.rasterize(&coverage, shader.as_ref());
   }
 
   pub fn fill_area_with_brush(&mut self, area: &Area, brush: &Brush) {
@@ -101,14 +217,7 @@ impl<'a> Painter<'a> {
     // We compute a grid of brush centers across the area's bounding box and
     // paint a dab at each center if it falls inside the polygon coverage.
     let tolerance = 0.5;
-    let flattened: Vec<PointF> = area
-      .path
-      .flatten(tolerance)
-      .into_iter()
-      .map(|p| PointF::new(p.x, p.y))
-      .collect();
-
-    let coverage = PolygonCoverage::new(flattened);
+    let coverage = PolygonCoverage::new_from_contours(area.path.flatten_contours(tolerance));
     if let Some((min_x, min_y, max_x, max_y)) = coverage.bounds() {
       // Build a grid of dab centers across the polygon and collect
       // those inside the coverage. We will shade all centers in a
@@ -161,12 +270,9 @@ impl<'a> Painter<'a> {
         // direction are visible across the whole area.
         let bounds_path = Path::line((min_x, min_y), (max_x, min_y));
         let inner_shader = shader_from_fill_with_path(brush.color().clone(), Some(bounds_path));
-        let shader: Box<dyn Shader + Send + Sync> =
-          Box::new(BrushDabsShader::new(inner_shader, centers, radius, brush.hardness()));
-        let compositor = SourceOverCompositor;
-        let sample_grid = SampleGrid::from_aa_level(2);
-        let rasterizer = Rasterizer::new(&coverage, shader.as_ref(), &compositor, sample_grid);
-        rasterizer.rasterize(self.image);
+        let shader =
+          with_brush_opacity(Box::new(BrushDabsShader::new(inner_shader, centers, radius, brush.hardness())), brush);
+        self.rasterize(&coverage, shader.as_ref());
       }
     }
   }
@@ -176,23 +282,143 @@ impl<'a> Painter<'a> {
 /// - `p_x`: The x-coordinate to paint at.
 /// - `p_y`: The y-coordinate to paint at.
 /// - `brush`: The brush to use for painting.
-pub fn paint_with_brush(image: &mut Image, p_x: impl Into<f64>, p_y: impl Into<f64>, brush: &Brush) {
-  let mut painter = Painter::new(image);
+/// - `p_compositor`: How the paint is merged into the image, source-over when `None`.
+pub fn paint_with_brush(
+  image: &mut Image, p_x: impl Into<f64>, p_y: impl Into<f64>, brush: &Brush, p_compositor: Option<&dyn Compositor>,
+) {
+  let mut painter = Painter::new(image).with_compositor(p_compositor.unwrap_or(&SourceOverCompositor));
   painter.dab_brush(p_x.into() as f32, p_y.into() as f32, brush);
 }
 /// Strokes a path with a brush to create a continuous line using a temporary painter.
 /// - `image`: The target image to paint on.
 /// - `path`: The path to stroke.
 /// - `brush`: The brush to use for stroking.
-pub fn stroke_with_brush(image: &mut Image, path: &Path, brush: &Brush) {
-  let mut painter = Painter::new(image);
+/// - `p_compositor`: How the paint is merged into the image, source-over when `None`.
+pub fn stroke_with_brush(image: &mut Image, path: &Path, brush: &Brush, p_compositor: Option<&dyn Compositor>) {
+  let mut painter = Painter::new(image).with_compositor(p_compositor.unwrap_or(&SourceOverCompositor));
   painter.stroke_with_brush(path, brush);
 }
 /// Fills an area with a brush using a temporary painter.
 /// - `image`: The target image to paint on.
 /// - `area`: The area to fill.
 /// - `brush`: The brush to use for filling.
-pub fn fill_area_with_brush(image: &mut Image, area: &Area, brush: &Brush) {
-  let mut painter = Painter::new(image);
+/// - `p_compositor`: How the paint is merged into the image, source-over when `None`.
+pub fn fill_area_with_brush(image: &mut Image, area: &Area, brush: &Brush, p_compositor: Option<&dyn Compositor>) {
+  let mut painter = Painter::new(image).with_compositor(p_compositor.unwrap_or(&SourceOverCompositor));
   painter.fill_area_with_brush(area, brush);
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::{Color, Gradient};
+
+  fn black_to_white() -> Gradient {
+    Gradient::from_to(Color::from_rgba(0, 0, 0, 255), Color::from_rgba(255, 255, 255, 255))
+  }
+
+  #[test]
+  fn opacity_caps_brush_coverage() {
+    let mut image = Image::new_from_color(40, 40, Color::transparent());
+    let brush = Brush::new()
+      .with_size(20)
+      .with_color(Color::red())
+      .with_hardness(1.0)
+      .with_opacity(0.5);
+    paint_with_brush(&mut image, 20.0, 20.0, &brush, None);
+    assert_eq!(image.get_pixel(20, 20), Some((255, 0, 0, 128)));
+
+    // A stroke that crosses itself does not build up past the opacity.
+    let mut image = Image::new_from_color(40, 40, Color::transparent());
+    let mut path = Path::new();
+    path
+      .move_to((5.0, 20.0))
+      .line_to((35.0, 20.0))
+      .line_to((20.0, 5.0))
+      .line_to((20.0, 35.0));
+    stroke_with_brush(&mut image, &path, &brush.with_size(6), None);
+    assert_eq!(image.get_pixel(20, 20).unwrap().3, 128);
+  }
+
+  #[test]
+  fn scatter_spreads_stamps_around_the_path() {
+    let stroke = |p_seed: u64| {
+      let mut image = Image::new_from_color(120, 60, Color::transparent());
+      let brush = Brush::new()
+        .with_size(4)
+        .with_color(Color::red())
+        .with_hardness(1.0)
+        .with_scatter(3.0, 3)
+        .with_seed(p_seed);
+      stroke_with_brush(&mut image, &Path::line((10.0, 30.0), (110.0, 30.0)), &brush, None);
+      image
+    };
+    let image = stroke(7);
+    let painted = |y: u32| (0..120).filter(|x| image.get_pixel(*x, y).unwrap().3 > 0).count();
+    // Stamps land above and below the path, and the path itself is not a solid line.
+    assert!((20..28).map(painted).sum::<usize>() > 0);
+    assert!((33..41).map(painted).sum::<usize>() > 0);
+    assert!(painted(30) < 100, "{}", painted(30));
+    assert!((0..15).chain(45..60).all(|y| painted(y) == 0));
+
+    // The same seed places the stamps in the same spots, another seed does not.
+    assert_eq!(stroke(7).rgba(), image.rgba());
+    assert_ne!(stroke(8).rgba(), image.rgba());
+  }
+
+  #[test]
+  fn along_stroke_progresses_from_start_to_end() {
+    let mut image = Image::new_from_color(100, 20, Color::transparent());
+    let brush = Brush::new()
+      .with_size(10)
+      .with_color(black_to_white())
+      .with_hardness(1.0)
+      .with_color_dynamics(ColorDynamics::AlongStroke);
+    stroke_with_brush(&mut image, &Path::line((5.0, 10.0), (95.0, 10.0)), &brush, None);
+
+    let start = image.get_pixel(10, 10).unwrap().0;
+    let middle = image.get_pixel(50, 10).unwrap().0;
+    let end = image.get_pixel(90, 10).unwrap().0;
+    assert!(start < 30 && (110..=145).contains(&middle) && end > 225, "{} {} {}", start, middle, end);
+    // Across the stroke the color stays the same.
+    assert_eq!(image.get_pixel(50, 7).unwrap().0, image.get_pixel(50, 12).unwrap().0);
+  }
+
+  #[test]
+  fn per_dab_spans_the_gradient_across_the_stroke() {
+    let mut image = Image::new_from_color(100, 20, Color::transparent());
+    let brush = Brush::new()
+      .with_size(10)
+      .with_color(black_to_white())
+      .with_hardness(1.0);
+    stroke_with_brush(&mut image, &Path::line((5.0, 10.0), (95.0, 10.0)), &brush, None);
+
+    // Above the centerline is the left of a stroke going right.
+    let left = image.get_pixel(50, 7).unwrap().0;
+    let right = image.get_pixel(50, 12).unwrap().0;
+    assert!(left < right, "{} >= {}", left, right);
+    assert_eq!(image.get_pixel(20, 7).unwrap().0, left);
+  }
+
+  #[test]
+  fn additive_compositor_brightens_the_overlap() {
+    let gray = Color::from_rgba(100, 100, 100, 255);
+    let brush = Brush::new()
+      .with_size(10)
+      .with_color(Color::white())
+      .with_hardness(1.0)
+      .with_opacity(0.5);
+    let additive = crate::BlendCompositor::new(abra_core::blend::linear_dodge);
+
+    let mut image = Image::new_from_color(40, 20, gray);
+    paint_with_brush(&mut image, 10.0, 10.0, &brush, Some(&additive));
+    paint_with_brush(&mut image, 15.0, 10.0, &brush, Some(&additive));
+
+    // Each dab adds to the gray, so the overlap of the two dabs is the brightest.
+    let single = image.get_pixel(7, 10).unwrap();
+    let overlap = image.get_pixel(12, 10).unwrap();
+    assert!(single.0 > 150 && single.0 == single.1 && single.3 == 255, "{:?}", single);
+    assert!(overlap.0 > single.0 + 20, "{:?} {:?}", overlap, single);
+    assert_eq!(image.get_pixel(30, 10), Some((100, 100, 100, 255)));
+  }
+}
@@ -9,6 +9,8 @@
 //! Design notes
 //! - The rasterizer relies on `CoverageMask` to limit work. Masks can be
 //!   arbitrary geometry: polygons, brushes, or full-screen coverage.
+//! - An optional clip mask, set with `Rasterizer::with_clip`, is tested
+//!   per sample alongside the coverage, so clipped edges stay anti-aliased.
 //! - `Shader` implementations return RGBA per-sample at non-integer
 //!   coordinates—this allows gradients, textures, and brush falloff.
 //! - The `Compositor` controls how the averaged source color is blended
@@ -57,6 +59,7 @@ pub struct Rasterizer<'a> {
   shader: &'a dyn Shader,
   compositor: &'a dyn Compositor,
   sample_grid: SampleGrid,
+  clip: Option<&'a dyn CoverageMask>,
 }
 
 impl<'a> Rasterizer<'a> {
@@ -81,14 +84,36 @@ impl<'a> Rasterizer<'a> {
       shader: p_shader,
       compositor: p_compositor,
       sample_grid: p_sample_grid,
+      clip: None,
     }
   }
 
+  /// Clips the rasterization to a mask, like the clip operation of a 2D canvas: only samples inside
+  /// both the coverage and the clip are painted. The clip uses the fill rule of its own mask.
+  ///
+  /// Parameters
+  /// - `p_clip`: the mask to clip to, such as a `PolygonCoverage` of a path.
+  ///
+  /// Example
+  /// ```ignore
+  /// let clip = PolygonCoverage::new_from_contours(circle.flatten_contours(0.5));
+  /// Rasterizer::new(&coverage, &shader, &compositor, grid).with_clip(&clip).rasterize(&mut image);
+  /// ```
+  pub fn with_clip(mut self, p_clip: &'a dyn CoverageMask) -> Self {
+    self.clip = Some(p_clip);
+    self
+  }
+
+  /// Tests if a sample is inside the coverage and the clip.
+  fn covers(&self, p_x: f32, p_y: f32) -> bool {
+    self.coverage.contains(p_x, p_y) && self.clip.is_none_or(|clip| clip.contains(p_x, p_y))
+  }
+
   /// Rasterizes the configured coverage and writes into `p_image`.
   ///
   /// The rasterizer will only iterate pixels within the coverage bounds
   /// (if provided) and will sample sub-pixel locations according to the
-  /// `SampleGrid`. For each sample inside the coverage mask, it will use
+  /// `SampleGrid`. For each sample inside the coverage mask and the clip, it will use
   /// the `Shader` to obtain a color and blend the averaged color with the
   /// existing destination pixel using the `Compositor`.
   ///
@@ -108,10 +133,20 @@ impl<'a> Rasterizer<'a> {
       .expect("Image colors must be contiguous");
     let total_samples = self.sample_grid.total_samples() as f32;
     // Determine bounds from coverage (if available) so we only iterate pixels that may be affected.
-    let (min_x_f, min_y_f, max_x_f, max_y_f) = match self.coverage.bounds() {
+    let (mut min_x_f, mut min_y_f, mut max_x_f, mut max_y_f) = match self.coverage.bounds() {
       Some((min_x, min_y, max_x, max_y)) => (min_x, min_y, max_x, max_y),
       None => (0.0, 0.0, width as f32, height as f32),
     };
+    // Nothing outside the clip can be painted either.
+    if let Some((min_x, min_y, max_x, max_y)) = self.clip.and_then(|clip| clip.bounds()) {
+      min_x_f = min_x_f.max(min_x);
+      min_y_f = min_y_f.max(min_y);
+      max_x_f = max_x_f.min(max_x);
+      max_y_f = max_y_f.min(max_y);
+    }
+    if min_x_f > max_x_f || min_y_f > max_y_f {
+      return;
+    }
     let min_x = min_x_f.floor().max(0.0) as u32;
     let min_y = min_y_f.floor().max(0.0) as u32;
     let max_x = max_x_f.ceil().min(width as f32 - 1.0) as u32;
@@ -140,7 +175,7 @@ impl<'a> Rasterizer<'a> {
           let mut a_sum = 0.0;
 
           for (sub_x, sub_y) in self.sample_grid.samples(x, y) {
-            if self.coverage.contains(sub_x, sub_y) {
+            if self.covers(sub_x, sub_y) {
               coverage_count += 1.0;
               let (r, g, b, a) = self.shader.shade(sub_x, sub_y);
               r_sum += r as f32;
@@ -176,3 +211,35 @@ impl<'a> Rasterizer<'a> {
     // DebugDrawing::Rasterization(start.elapsed()).log();
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::shaders::solid_shader::SolidShader;
+  use crate::{PolygonCoverage, SourceOverCompositor};
+  use abra_core::{Area, Color};
+
+  fn coverage(p_area: Area) -> PolygonCoverage {
+    PolygonCoverage::new_from_contours(p_area.path.flatten_contours(0.5))
+  }
+
+  #[test]
+  fn clip_limits_fill_to_clip_shape() {
+    let mut image = Image::new_from_color(40, 40, Color::transparent());
+    let rect = coverage(Area::rect((0.0, 0.0), (40.0, 40.0)));
+    let circle = coverage(Area::circle((20.0, 20.0), 8.0));
+    let shader = SolidShader::new(Color::red());
+    Rasterizer::new(&rect, &shader, &SourceOverCompositor, SampleGrid::from_aa_level(2))
+      .with_clip(&circle)
+      .rasterize(&mut image);
+
+    assert_eq!(image.get_pixel(20, 20), Some((255, 0, 0, 255)));
+    assert_eq!(image.get_pixel(20, 14), Some((255, 0, 0, 255)));
+    assert_eq!(image.get_pixel(2, 2), Some((0, 0, 0, 0)));
+    assert_eq!(image.get_pixel(26, 26), Some((0, 0, 0, 0)));
+    assert_eq!(image.get_pixel(20, 30), Some((0, 0, 0, 0)));
+    // The clipped edge is anti-aliased like any other edge.
+    assert!(image.rgba().chunks(4).any(|px| px[3] > 0 && px[3] < 255));
+    assert_eq!((0..40).filter(|x| image.get_pixel(*x, 20).unwrap().3 > 0).count(), 16);
+  }
+}
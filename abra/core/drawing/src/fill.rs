@@ -1,4 +1,4 @@
-use abra_core::{Area, Fill, Image, Path, PointF};
+use abra_core::{Area, Color, Fill, Image, Path, PointF, Size};
 
 use crate::shaders::fill_feather_shader::FillFeatherShader;
 use crate::{PolygonCoverage, Rasterizer, SampleGrid, SourceOverCompositor, shader_from_fill_with_path};
@@ -6,8 +6,8 @@ use crate::{PolygonCoverage, Rasterizer, SampleGrid, SourceOverCompositor, shade
 /// Fills the area with the specified fill style.
 /// - `p_area`: The area to fill.
 /// - `p_fill`: The fill type to use on the area.
-pub fn fill(p_area: impl Into<Area>, p_fill: impl Into<Fill>) -> Image {
-  let fill = p_fill.into();
+/// - `p_sample_grid`: The supersampling used to anti-alias the edges, the image's anti-aliasing level when `None`.
+pub fn fill(p_area: impl Into<Area>, p_fill: impl Into<Fill>, p_sample_grid: impl Into<Option<SampleGrid>>) -> Image {
   let area = p_area.into();
   let (min_x, min_y, max_x, max_y) = area.bounds::<f32>();
   let width = (max_x - min_x).ceil();
@@ -17,36 +17,67 @@ pub fn fill(p_area: impl Into<Area>, p_fill: impl Into<Fill>) -> Image {
     return Image::new(1, 1);
   }
 
-  let mut image = Image::new(width as u32, height as u32);
+  rasterize_area(&area, p_fill.into(), PointF::new(min_x, min_y), (width as u32, height as u32), p_sample_grid.into())
+}
+
+/// Renders the anti-aliased coverage of an area, including its feather, in image coordinates.
+/// The result is white, with the coverage of each pixel in its alpha channel. The edges are rasterized exactly like
+/// `fill` rasterizes them, so a mask made from the coverage lines up with a fill of the same area.
+/// - `p_area`: The area to render.
+/// - `p_size`: The size of the image the area is positioned in.
+/// - `p_sample_grid`: The supersampling used to anti-alias the edges, the image's anti-aliasing level when `None`.
+pub fn fill_coverage(
+  p_area: impl Into<Area>, p_size: impl Into<Size>, p_sample_grid: impl Into<Option<SampleGrid>>,
+) -> Image {
+  let size = p_size.into();
+  let width = size.width.ceil().max(1.0) as u32;
+  let height = size.height.ceil().max(1.0) as u32;
+  rasterize_area(&p_area.into(), Color::white().into(), PointF::new(0.0, 0.0), (width, height), p_sample_grid.into())
+}
+
+/// Rasterizes an area into a new image whose top-left pixel lies at `p_origin`.
+fn rasterize_area(
+  p_area: &Area, p_fill: Fill, p_origin: PointF, p_size: (u32, u32), p_sample_grid: Option<SampleGrid>,
+) -> Image {
+  let (min_x, min_y, max_x, _) = p_area.bounds::<f32>();
+  let mut image = Image::new(p_size.0, p_size.1);
 
-  // Flatten the path and translate to image-local coordinates
+  // Flatten every contour of the path and translate to image-local coordinates
   let tolerance = 0.5;
-  let flattened: Vec<PointF> = area
+  let contours: Vec<Vec<PointF>> = p_area
     .path
-    .flatten(tolerance)
+    .flatten_contours(tolerance)
     .iter()
-    .map(|p| PointF::new(p.x - min_x, p.y - min_y))
+    .map(|contour| {
+      contour
+        .iter()
+        .map(|p| PointF::new(p.x - p_origin.x, p.y - p_origin.y))
+        .collect()
+    })
     .collect();
 
   // Build coverage mask
-  let coverage = PolygonCoverage::new(flattened.clone());
+  let coverage = PolygonCoverage::new_from_contours(contours.clone());
 
   // Build shader from fill. If the gradient has no explicit direction, use the
   // area bounding box to create a horizontal gradient path so the gradient
   // is visible across the area.
   let fallback_path = Some(Path::line((min_x, min_y), (max_x, min_y)));
-  let mut shader = shader_from_fill_with_path(fill.clone(), fallback_path);
+  let mut shader = shader_from_fill_with_path(p_fill, fallback_path);
   // Apply area feathering by wrapping the shader when area has feather set
-  if area.feather() > 0 {
-    // max_distance is in pixels
-    shader = Box::new(FillFeatherShader::new_from_flattened(shader, flattened.clone(), area.feather() as f32));
+  if p_area.feather() > 0 {
+    // Feather widths are in pixels.
+    let (feather_x, feather_y) = p_area.feather_xy();
+    let curve = p_area.feather_curve();
+    shader =
+      Box::new(FillFeatherShader::new_from_contours(shader, contours, (feather_x as f32, feather_y as f32), curve));
   }
 
   // Use source-over compositing
   let compositor = SourceOverCompositor;
 
-  // Use anti-aliasing level from image
-  let sample_grid = SampleGrid::from_aa_level(image.anti_aliasing_level);
+  // Use anti-aliasing level from image unless a grid is given
+  let sample_grid = p_sample_grid.unwrap_or(SampleGrid::from_aa_level(image.anti_aliasing_level));
 
   // Rasterize
   let rasterizer = Rasterizer::new(&coverage, shader.as_ref(), &compositor, sample_grid);
@@ -58,14 +89,14 @@ pub fn fill(p_area: impl Into<Area>, p_fill: impl Into<Fill>) -> Image {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use abra_core::{Area, Color};
+  use abra_core::{Area, Color, LineCap, LineJoin};
 
   #[test]
   fn fill_with_feather_sets_alpha_near_edge() {
     // 20x20 image, rectangle area with feather 4.
     let area = Area::rect((2.0, 2.0), (16.0, 16.0)).with_feather(4);
     let color = Color::from_rgba(0, 0, 0, 255);
-    let img = fill(area, color);
+    let img = fill(area, color, None);
     // Check center pixel alpha (should be fully opaque)
     let (w, h) = img.dimensions::<u32>();
     let cx = w / 2;
@@ -75,4 +106,47 @@ mod tests {
     let near_edge = img.get_pixel(3, 3).unwrap().3;
     assert!(near_edge < 255);
   }
+
+  #[test]
+  fn fill_with_vertical_feather_keeps_sides_hard() {
+    let area = Area::rect((2.0, 2.0), (16.0, 16.0)).with_feather_xy(0, 4);
+    let img = fill(area, Color::from_rgba(0, 0, 0, 255), None);
+    // Soft towards the top and bottom, hard towards the left and right.
+    assert!(img.get_pixel(8, 1).unwrap().3 < 255);
+    assert!(img.get_pixel(8, 14).unwrap().3 < 255);
+    assert_eq!(img.get_pixel(1, 8).unwrap().3, 255);
+    assert_eq!(img.get_pixel(14, 8).unwrap().3, 255);
+  }
+
+  #[test]
+  fn fill_leaves_hole_for_reversed_inner_contour() {
+    // Outer square clockwise, inner square counter-clockwise.
+    let mut area = Area::rect((0.0, 0.0), (20.0, 20.0));
+    area
+      .move_to((5.0, 5.0))
+      .line_to((5.0, 15.0))
+      .line_to((15.0, 15.0))
+      .line_to((15.0, 5.0));
+    let img = fill(area, Color::from_rgba(0, 0, 0, 255), None);
+    assert_eq!(img.get_pixel(2, 2).unwrap().3, 255);
+    assert_eq!(img.get_pixel(10, 10).unwrap().3, 0);
+  }
+
+  #[test]
+  fn higher_sample_factor_smooths_thin_lines() {
+    let line: Area = Path::line((2.0, 2.0), (38.0, 30.0))
+      .stroke(1.0, LineJoin::Miter, LineCap::Butt)
+      .into();
+    // The number of distinct alpha levels along the line edges.
+    let levels = |p_factor: u32| {
+      let img = fill(line.clone(), Color::from_rgba(0, 0, 0, 255), SampleGrid::from_aa_level(p_factor));
+      let mut alphas: Vec<u8> = img.rgba().chunks(4).map(|px| px[3]).collect();
+      alphas.sort();
+      alphas.dedup();
+      alphas.len()
+    };
+    assert_eq!(levels(1), 2);
+    assert!(levels(4) > 8, "{}", levels(4));
+    assert!(levels(8) > levels(4));
+  }
 }
@@ -27,6 +27,7 @@ mod shaders {
   pub mod fill_feather_shader;
   pub mod image_shader;
   pub mod linear_gradient_shader;
+  pub mod opacity_shader;
   pub mod solid_shader;
   pub mod stroke_brush_shader;
 }
@@ -35,11 +36,11 @@ mod brush {
 }
 mod fill;
 
-pub use brush::brush::Brush;
-pub use core::compositor::{Compositor, SourceOverCompositor};
+pub use brush::brush::{Brush, ColorDynamics};
+pub use core::compositor::{BlendCompositor, Compositor, SourceOverCompositor};
 pub use core::coverage::{CoverageMask, PolygonCoverage};
 pub use core::painter::*;
 pub use core::rasterize::Rasterizer;
 pub use core::sampling::SampleGrid;
 pub use core::shader::{Shader, shader_from_fill, shader_from_fill_with_path};
-pub use fill::fill;
+pub use fill::{fill, fill_coverage};
@@ -1,4 +1,5 @@
 use crate::Shader;
+use crate::shaders::brush_shader::brush_falloff;
 use abra_core::PointF;
 
 /// A shader that paints multiple brush dabs in a single pass.
@@ -26,28 +27,12 @@ impl BrushDabsShader {
     }
   }
 
-  // compute alpha falloff based on distance^2 (avoid sqrt inside loops by using squared distances)
+  // compute alpha falloff based on distance^2 so the loop over dabs can skip the far ones without a sqrt
   fn compute_alpha_falloff(&self, dist_sq: f32) -> f32 {
-    let max_d_sq = self.max_distance * self.max_distance;
-    if dist_sq >= max_d_sq {
+    if dist_sq >= self.max_distance * self.max_distance {
       return 0.0;
     }
-    let normalized_sq = dist_sq / max_d_sq;
-    // We need a function that behaves similarly to the original falloff.
-    // Convert squared normalized distance back to normalized distance for falloff curve.
-    let normalized = normalized_sq.sqrt();
-    if self.hardness < 0.5 {
-      let t = self.hardness * 2.0;
-      let quadratic = 1.0 - (normalized * normalized);
-      let linear = 1.0 - normalized;
-      quadratic * (1.0 - t) + linear * t
-    } else {
-      let t = (self.hardness - 0.5) * 2.0;
-      let linear = 1.0 - normalized;
-      let hard_edge = if normalized < 0.5 { 1.0 } else { 0.0 };
-      linear * (1.0 - t) + hard_edge * t
-    }
-    .max(0.0)
+    brush_falloff(dist_sq.sqrt(), self.max_distance, self.hardness)
   }
 }
 
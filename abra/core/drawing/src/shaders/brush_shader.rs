@@ -48,34 +48,34 @@ impl BrushShader {
 
   /// Computes alpha falloff based on distance from center and hardness.
   ///
-  /// This is the same falloff function used in `BrushCoverageMask` and
-  /// returns a factor in `[0.0, 1.0]` that is multiplied into the
+  /// Returns a factor in `[0.0, 1.0]` that is multiplied into the
   /// alpha component of the underlying `inner` shader.
   fn compute_alpha_falloff(&self, p_x: f32, p_y: f32) -> f32 {
     let dx = p_x - self.center_x;
     let dy = p_y - self.center_y;
-    let distance = (dx * dx + dy * dy).sqrt();
-
-    if distance >= self.max_distance {
-      return 0.0;
-    }
-
-    let normalized_dist = distance / self.max_distance;
-
-    let falloff = if self.hardness < 0.5 {
-      let t = self.hardness * 2.0;
-      let quadratic = 1.0 - (normalized_dist * normalized_dist);
-      let linear = 1.0 - normalized_dist;
-      quadratic * (1.0 - t) + linear * t
-    } else {
-      let t = (self.hardness - 0.5) * 2.0;
-      let linear = 1.0 - normalized_dist;
-      let hard_edge = if normalized_dist < 0.5 { 1.0 } else { 0.0 };
-      linear * (1.0 - t) + hard_edge * t
-    };
+    brush_falloff((dx * dx + dy * dy).sqrt(), self.max_distance, self.hardness)
+  }
+}
 
-    falloff.max(0.0)
+/// The alpha factor of a brush at a distance from its center, shared by all brush shaders.
+///
+/// Hardness sets the size of the fully opaque core: `0.0` has no core and fades over the whole radius,
+/// `1.0` keeps the core up to the last pixel so only a one pixel anti-aliased rim remains. Between the core and
+/// the radius the alpha follows a smoothstep, which starts and ends flat so the profile has no visible kink
+/// and the soft brushes look like a Gaussian.
+/// - `p_distance`: The distance from the brush center in pixels.
+/// - `p_radius`: The radius at which alpha becomes zero.
+/// - `p_hardness`: 0.0 (soft) .. 1.0 (hard).
+pub(crate) fn brush_falloff(p_distance: f32, p_radius: f32, p_hardness: f32) -> f32 {
+  if p_distance >= p_radius {
+    return 0.0;
   }
+  let core = p_hardness.clamp(0.0, 1.0) * (p_radius - 1.0).max(0.0);
+  if p_distance <= core {
+    return 1.0;
+  }
+  let t = (p_distance - core) / (p_radius - core);
+  1.0 - t * t * (3.0 - 2.0 * t)
 }
 
 impl Shader for BrushShader {
@@ -89,3 +89,45 @@ impl Shader for BrushShader {
     (r, g, b, a)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// The alpha profile from the center to the edge of a brush with a radius of 50 pixels.
+  fn profile(p_hardness: f32) -> Vec<f32> {
+    (0..=100)
+      .map(|i| brush_falloff(i as f32 * 0.5, 50.0, p_hardness))
+      .collect()
+  }
+
+  #[test]
+  fn medium_hardness_falls_off_smoothly() {
+    let alpha = profile(0.5);
+    assert_eq!(alpha[0], 1.0);
+    assert_eq!(alpha[40], 1.0);
+    assert_eq!(alpha[100], 0.0);
+    let steps: Vec<f32> = alpha.windows(2).map(|pair| pair[0] - pair[1]).collect();
+    assert!(steps.iter().all(|step| *step >= 0.0), "alpha increases along the radius");
+    // The slope changes gradually everywhere, including where the core ends and at the rim.
+    let largest_bend = steps
+      .windows(2)
+      .map(|pair| (pair[1] - pair[0]).abs())
+      .fold(0.0, f32::max);
+    assert!(largest_bend < 0.005, "kink of {}", largest_bend);
+  }
+
+  #[test]
+  fn hardness_spans_soft_to_crisp() {
+    let soft = profile(0.0);
+    let hard = profile(1.0);
+    // Soft brushes start fading right away, hard ones stay opaque until the last pixel.
+    assert!(soft[20] < 0.95 && soft[80] > 0.0);
+    assert_eq!(hard[97], 1.0);
+    assert!(hard[99] > 0.0 && hard[99] < 1.0);
+    // Raising the hardness never makes a point more transparent.
+    for (lower, higher) in profile(0.3).iter().zip(profile(0.7)) {
+      assert!(higher >= *lower);
+    }
+  }
+}
@@ -0,0 +1,31 @@
+use crate::Shader;
+
+/// Wraps another shader and scales its alpha by a constant opacity.
+///
+/// Used to cap the coverage of a whole brush stroke, so overlapping parts of
+/// a single stroke never exceed the brush opacity.
+pub(crate) struct OpacityShader {
+  inner: Box<dyn Shader + Send + Sync>,
+  opacity: f32,
+}
+
+impl OpacityShader {
+  /// Creates a new `OpacityShader`.
+  ///
+  /// Parameters
+  /// - `p_inner`: boxed inner shader providing the RGBA values
+  /// - `p_opacity`: 0.0 (transparent) .. 1.0 (unchanged)
+  pub fn new(p_inner: Box<dyn Shader + Send + Sync>, p_opacity: f32) -> Self {
+    OpacityShader {
+      inner: p_inner,
+      opacity: p_opacity.clamp(0.0, 1.0),
+    }
+  }
+}
+
+impl Shader for OpacityShader {
+  fn shade(&self, p_x: f32, p_y: f32) -> (u8, u8, u8, u8) {
+    let (r, g, b, a) = self.inner.shade(p_x, p_y);
+    (r, g, b, (a as f32 * self.opacity).round() as u8)
+  }
+}
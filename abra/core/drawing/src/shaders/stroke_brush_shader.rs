@@ -1,5 +1,6 @@
-use crate::Shader;
-use abra_core::{Path, PointF};
+use crate::shaders::brush_shader::brush_falloff;
+use crate::{ColorDynamics, Shader};
+use abra_core::{Gradient, Path, PointF};
 
 /// Stroke brush shader: applies hardness-based falloff relative to a path centerline.
 ///
@@ -23,6 +24,18 @@ pub(crate) struct StrokeBrushShader {
   hardness: f32,
   // Pre-flattened path points for fast closest-point queries during shading.
   flattened: Vec<PointF>,
+  /// Distance along the path at each flattened point.
+  lengths: Vec<f32>,
+  /// A gradient laid out along or across the stroke that replaces the inner shader's color.
+  gradient: Option<(Gradient, ColorDynamics)>,
+}
+
+/// The point on a stroke's centerline closest to a sample.
+struct ClosestPoint {
+  /// Distance from the sample to the centerline, negative on the left of the stroke direction.
+  signed_distance: f32,
+  /// Distance along the path from its start.
+  length: f32,
 }
 
 impl StrokeBrushShader {
@@ -44,57 +57,65 @@ impl StrokeBrushShader {
   ) -> Self {
     // Pre-flatten the path to a set of points; choose a tolerance that balances accuracy and performance.
     let flattened = p_path.flatten(1.0);
+    let mut lengths = Vec::with_capacity(flattened.len());
+    let mut total = 0.0;
+    for (i, point) in flattened.iter().enumerate() {
+      if i > 0 {
+        total += flattened[i - 1].distance_to(*point);
+      }
+      lengths.push(total);
+    }
     StrokeBrushShader {
       inner: p_inner,
       path: p_path,
       max_distance: p_max_distance.into() as f32,
       hardness: p_hardness.into().clamp(0.0, 1.0) as f32,
       flattened,
+      lengths,
+      gradient: None,
     }
   }
 
+  /// Colors the stroke with a gradient instead of the inner shader.
+  ///
+  /// Parameters
+  /// - `p_gradient`: gradient providing the colors
+  /// - `p_dynamics`: `PerDab` spans the gradient across the stroke from its left to its right edge,
+  ///   `AlongStroke` spans it from the start to the end of the path by distance traveled
+  pub fn with_gradient(mut self, p_gradient: Gradient, p_dynamics: ColorDynamics) -> Self {
+    self.gradient = Some((p_gradient, p_dynamics));
+    self
+  }
+
   /// Computes the falloff (a factor in 0..1) given a perpendicular distance
   /// from the stroke's centerline. This function is used by `shade` to
   /// modulate the alpha channel.
   /// - `p_distance`: perpendicular distance from the stroke centerline.
   fn compute_alpha_falloff_from_distance(&self, p_distance: f32) -> f32 {
-    if p_distance >= self.max_distance {
-      return 0.0;
-    }
-    let normalized_dist = p_distance / self.max_distance;
-
-    if self.hardness < 0.5 {
-      let t = self.hardness * 2.0;
-      let quadratic = 1.0 - (normalized_dist * normalized_dist);
-      let linear = 1.0 - normalized_dist;
-      (quadratic * (1.0 - t) + linear * t).max(0.0)
-    } else {
-      let t = (self.hardness - 0.5) * 2.0;
-      let linear = 1.0 - normalized_dist;
-      let hard_edge = if normalized_dist < 0.5 { 1.0 } else { 0.0 };
-      (linear * (1.0 - t) + hard_edge * t).max(0.0)
-    }
+    brush_falloff(p_distance, self.max_distance, self.hardness)
   }
 }
 
 impl Shader for StrokeBrushShader {
   fn shade(&self, p_x: f32, p_y: f32) -> (u8, u8, u8, u8) {
-    let (r, g, b, mut a) = self.inner.shade(p_x, p_y);
-    // Compute closest point on the path and distance to it
-    let center_point = self.closest_point_on_flattened(p_x, p_y);
-
-    let dx = p_x - center_point.x;
-    let dy = p_y - center_point.y;
-    let dist = (dx * dx + dy * dy).sqrt();
-    let falloff = self.compute_alpha_falloff_from_distance(dist);
-    // (Debug prints removed)
+    let closest = self.closest_point_on_flattened(p_x, p_y);
+    let (r, g, b, mut a) = match &self.gradient {
+      Some((gradient, ColorDynamics::PerDab)) => {
+        gradient.get_color(0.5 + closest.signed_distance / (2.0 * self.max_distance))
+      }
+      Some((gradient, ColorDynamics::AlongStroke)) => {
+        let total = self.lengths.last().copied().unwrap_or(0.0);
+        gradient.get_color(if total > 0.0 { closest.length / total } else { 0.0 })
+      }
+      None => self.inner.shade(p_x, p_y),
+    };
+    let falloff = self.compute_alpha_falloff_from_distance(closest.signed_distance.abs());
     a = ((a as f32) * falloff) as u8;
     (r, g, b, a)
   }
 }
 
 impl StrokeBrushShader {
-  // Finds the closest point on the pre-flattened path to (p_x, p_y).
   /// Finds the closest point on the pre-flattened path to `(p_x, p_y)`.
   ///
   /// This uses a simple linear search of the flattened polyline and
@@ -103,14 +124,19 @@ impl StrokeBrushShader {
   /// Parameters
   /// - `p_x`, `p_y`: the query point in device coordinates.
   ///
-  /// Returns a `PointF` representing the point on the path closest to the query.
-  fn closest_point_on_flattened(&self, p_x: f32, p_y: f32) -> PointF {
+  /// Returns the signed distance to the path and how far along the path the closest point is.
+  fn closest_point_on_flattened(&self, p_x: f32, p_y: f32) -> ClosestPoint {
+    let query = PointF::new(p_x, p_y);
     if self.flattened.len() < 2 {
-      return self.path.start();
+      return ClosestPoint {
+        signed_distance: query.distance_to(self.path.start()),
+        length: 0.0,
+      };
     }
-    let query = PointF::new(p_x, p_y);
-    let mut min_distance = f32::MAX;
-    let mut closest = self.flattened[0];
+    let mut closest = ClosestPoint {
+      signed_distance: query.distance_to(self.flattened[0]),
+      length: 0.0,
+    };
     for i in 0..self.flattened.len() - 1 {
       let p1 = self.flattened[i];
       let p2 = self.flattened[i + 1];
@@ -123,9 +149,17 @@ impl StrokeBrushShader {
       let t = (query_vec.dot(seg_vec) / seg_len_sq).clamp(0.0, 1.0);
       let candidate = p1.lerp(p2, t);
       let dist = query.distance_to(candidate);
-      if dist < min_distance {
-        min_distance = dist;
-        closest = candidate;
+      if dist < closest.signed_distance.abs() {
+        // With y pointing down, a negative cross product puts the query on the left of the segment.
+        let side = if seg_vec.x * query_vec.y - seg_vec.y * query_vec.x < 0.0 {
+          -1.0
+        } else {
+          1.0
+        };
+        closest = ClosestPoint {
+          signed_distance: dist * side,
+          length: self.lengths[i] + (self.lengths[i + 1] - self.lengths[i]) * t,
+        };
       }
     }
     closest
@@ -0,0 +1,111 @@
+use crate::common::*;
+use abra_core::gaussian_kernel;
+
+/// Sums premultiplied pixels, each scaled by its kernel weight.
+fn weighted_sum(p_samples: impl Iterator<Item = ([f32; 4], f32)>) -> [f32; 4] {
+  p_samples.fold([0.0f32; 4], |mut sum, (value, weight)| {
+    for (total, channel) in sum.iter_mut().zip(value) {
+      *total += channel * weight;
+    }
+    sum
+  })
+}
+
+/// Blurs the alpha channel with a separable Gaussian kernel and leaves the color channels as they are.
+/// Pixels that were fully transparent have no color of their own, so they take the alpha-weighted average color of
+/// their neighbors. This bleeds the edge colors outward instead of fading them into black.
+fn apply_alpha_blur(image: &mut Image, radius: u32) {
+  let (width, height) = image.dimensions::<usize>();
+  if width == 0 || height == 0 {
+    return;
+  }
+  // The same kernel as the Gaussian blur, with a standard deviation of half the radius.
+  let kernel = gaussian_kernel(radius, radius as f32 / 2.0);
+  let kernel_radius = radius as i32;
+  // Premultiplied color and alpha, so the color of transparent pixels carries no weight.
+  let premultiplied: Vec<[f32; 4]> = image
+    .rgba()
+    .chunks_exact(4)
+    .map(|pixel| {
+      let alpha = pixel[3] as f32;
+      let scale = alpha / 255.0;
+      [
+        pixel[0] as f32 * scale,
+        pixel[1] as f32 * scale,
+        pixel[2] as f32 * scale,
+        alpha,
+      ]
+    })
+    .collect();
+
+  // Horizontal pass
+  let mut horizontal = vec![[0.0f32; 4]; width * height];
+  horizontal.par_chunks_mut(width).enumerate().for_each(|(y, row)| {
+    for (x, value) in row.iter_mut().enumerate() {
+      *value = weighted_sum((-kernel_radius..=kernel_radius).map(|k| {
+        let px = (x as i32 + k).clamp(0, width as i32 - 1) as usize;
+        (premultiplied[y * width + px], kernel[(k + kernel_radius) as usize])
+      }));
+    }
+  });
+
+  // Vertical pass, written straight into the pixels
+  let mut pixels = image.to_rgba_vec();
+  pixels.par_chunks_mut(width * 4).enumerate().for_each(|(y, row)| {
+    for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+      let blurred = weighted_sum((-kernel_radius..=kernel_radius).map(|k| {
+        let py = (y as i32 + k).clamp(0, height as i32 - 1) as usize;
+        (horizontal[py * width + x], kernel[(k + kernel_radius) as usize])
+      }));
+      if pixel[3] == 0 && blurred[3] > 0.0 {
+        for c in 0..3 {
+          pixel[c] = (blurred[c] * 255.0 / blurred[3]).round().clamp(0.0, 255.0) as u8;
+        }
+      }
+      pixel[3] = blurred[3].round().clamp(0.0, 255.0) as u8;
+    }
+  });
+
+  image.set_rgba_owned(pixels);
+}
+
+/// Blurs only the alpha channel of an image with a Gaussian kernel, leaving the color channels untouched.
+/// Useful for feathering cutouts and building soft masks or glows without smearing color into transparent areas.
+/// - `p_image`: The image whose alpha channel is blurred.
+/// - `p_radius`: The radius of the Gaussian kernel.
+/// - `p_apply_options`: Additional options for applying the blur.
+pub fn blur_alpha<'a>(p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_apply_options: impl Into<Options>) {
+  if p_radius == 0 {
+    return;
+  }
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_filter!(apply_alpha_blur, image, p_apply_options, p_radius as i32, p_radius);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::Image;
+
+  #[test]
+  fn softens_alpha_edge_and_keeps_color() {
+    let mut img = Image::new_transparent(20, 20);
+    for y in 5..15u32 {
+      for x in 5..15u32 {
+        img.set_pixel(x, y, (200, 100, 50, 255));
+      }
+    }
+    blur_alpha(&mut img, 3, None);
+    // The interior keeps its exact color and stays opaque.
+    assert_eq!(img.get_pixel(10, 10), Some((200, 100, 50, 255)));
+    // The edge is now partly transparent on both sides without any color change.
+    let (r, g, b, a) = img.get_pixel(5, 10).unwrap();
+    assert_eq!((r, g, b), (200, 100, 50));
+    assert!(a > 0 && a < 255, "inner edge alpha was {}", a);
+    // Pixels outside the shape take its color instead of the black of the transparent pixels, so no dark fringe.
+    let (r, g, b, a) = img.get_pixel(4, 10).unwrap();
+    assert_eq!((r, g, b), (200, 100, 50));
+    assert!(a > 0 && a < 128, "outer edge alpha was {}", a);
+  }
+}
@@ -1,39 +1,24 @@
 use crate::common::*;
 use abra_core::if_pick;
-use abra_core::{Channels, Resize};
+use abra_core::{Area, Channels, Resize, gaussian_kernel};
 
 use std::time::Instant;
 
-use abra_core::image::apply_area::process_image;
+use abra_core::image::apply_area::process_image_with_progress;
+use abra_core::{Progress, ProgressReporter};
 use options::get_ctx;
 
-fn gaussian_kernel_1d(radius: u32) -> Vec<f32> {
-  let mut kernel = vec![0.0; (2 * radius + 1) as usize];
-  let sigma = radius as f32 / 2.0;
-  let pi = std::f32::consts::PI;
-
-  // Fill kernel symmetrically
-  for x in 0..=radius {
-    let value = (-(x as f32 * x as f32) / (2.0 * sigma * sigma)).exp() / (2.0 * pi * sigma * sigma);
-    kernel[radius as usize + x as usize] = value;
-    kernel[radius as usize - x as usize] = value;
-  }
-
-  // Compute full kernel sum and normalize so the kernel sums to 1.0.
-  let sum = kernel.iter().copied().sum::<f32>();
-  if sum > 0.0 {
-    kernel.iter_mut().for_each(|value| *value /= sum);
-  }
-
-  kernel
-}
-
 /// Applies a Gaussian blur to an image using separable convolution.
 /// Uses two passes: horizontal and vertical for O(r) complexity instead of O(r²).
 /// * `p_image` - A mutable reference to the image to be blurred.
 /// * `p_radius` - The radius of the Gaussian kernel.
-fn separable_gaussian_blur_pixels(pixels: &[u8], width: usize, height: usize, p_radius: u32) -> Vec<u8> {
-  let kernel = gaussian_kernel_1d(p_radius);
+/// * `p_progress` - Receives a tick for every row of both passes.
+fn separable_gaussian_blur_pixels(
+  pixels: &[u8], width: usize, height: usize, p_radius: u32, p_progress: Progress<'_>,
+) -> Vec<u8> {
+  let rows = p_progress.counter(height * 2);
+  // The standard deviation is half the radius.
+  let kernel = gaussian_kernel(p_radius, p_radius as f32 / 2.0);
   let kernel_radius = p_radius as i32;
   // kernel_radius is no longer used here; separable implementation computes its kernel locally.
   let width_i32 = width as i32;
@@ -69,6 +54,7 @@ fn separable_gaussian_blur_pixels(pixels: &[u8], width: usize, height: usize, p_
       chunk[off + 2] = bb;
       chunk[off + 3] = aa;
     }
+    rows.tick();
   });
 
   // Vertical pass: read from horizontal buffer, write into vertical buffer
@@ -97,6 +83,7 @@ fn separable_gaussian_blur_pixels(pixels: &[u8], width: usize, height: usize, p_
       chunk[off + 2] = bb;
       chunk[off + 3] = aa;
     }
+    rows.tick();
   });
 
   vertical
@@ -106,7 +93,22 @@ fn separable_gaussian_blur_pixels(pixels: &[u8], width: usize, height: usize, p_
 /// - `p_radius`: The radius of the Gaussian kernel.
 /// - `p_options`: Additional options for applying the blur.
 pub fn gaussian_blur<'a>(p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_apply_options: impl Into<Options>) {
+  gaussian_blur_with_progress(p_image, p_radius, p_apply_options, |_| {});
+}
+
+/// Applies a Gaussian blur to an image like `gaussian_blur`, reporting how far the blur is as it runs.
+/// - `p_image`: The image to be blurred.
+/// - `p_radius`: The radius of the Gaussian kernel.
+/// - `p_options`: Additional options for applying the blur.
+/// - `p_progress`: Called with the progress from 0.0 to 1.0 in steps of 5%. It can be called from any of the
+///   threads the blur runs on, but never from two at the same time.
+pub fn gaussian_blur_with_progress<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_apply_options: impl Into<Options>,
+  p_progress: impl FnMut(f32) + Send,
+) {
+  let reporter = ProgressReporter::new(p_progress);
   if p_radius == 0 {
+    reporter.finish();
     return;
   }
 
@@ -120,8 +122,13 @@ pub fn gaussian_blur<'a>(p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_appl
   let image_h = image_h as i32;
   let options = p_apply_options.into();
 
-  let areas = options.as_ref().and_then(|o| o.area());
-  for area in areas.unwrap() {
+  // Without an area the whole image is blurred.
+  let areas: Vec<Option<&Area>> = match options.as_ref().and_then(|o| o.area()) {
+    Some(areas) => areas.iter().map(Some).collect(),
+    None => vec![None],
+  };
+  let area_count = areas.len();
+  for (index, area) in areas.into_iter().enumerate() {
     let ctx = get_ctx(options.as_ref());
     // Precompute area-based downsampling decision so the closure stays small and focused.
     // let large_area_ratio = options
@@ -134,15 +141,16 @@ pub fn gaussian_blur<'a>(p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_appl
     //     (area_w * area_h) > (image_w as i64 * image_h as i64 / 4)
     //   })
     //   .unwrap_or(false);
-    let large_area_ratio = {
+    let large_area_ratio = area.is_none_or(|area| {
       let (min_x, min_y, max_x, max_y) = area.bounds::<i32>();
       let area_w = (max_x - min_x) as i64;
       let area_h = (max_y - min_y) as i64;
       (area_w * area_h) > (image_w as i64 * image_h as i64 / 4)
-    };
+    });
 
     // Let apply_processing prepare the pixels and handle area/feather/mask+blending.
-    process_image(image, ctx, kernel_radius, |img| {
+    let progress = reporter.progress().part(index, area_count);
+    process_image_with_progress(image, ctx, kernel_radius, progress, |img, progress| {
       let pixels = img.to_rgba_vec();
       let (width, height) = img.dimensions::<u32>();
 
@@ -160,20 +168,21 @@ pub fn gaussian_blur<'a>(p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_appl
 
         // Apply separable gaussian on the small image (no area), this is faster because of far fewer pixels.
         let blurred_small =
-          separable_gaussian_blur_pixels(tmp_img.rgba(), down_w as usize, down_h as usize, new_radius);
+          separable_gaussian_blur_pixels(tmp_img.rgba(), down_w as usize, down_h as usize, new_radius, progress);
         tmp_img.set_rgba_owned(blurred_small);
 
         // Upscale back to original processing size
         tmp_img.resize(width as u32, height as u32, None);
         tmp_img.into_rgba_vec()
       } else {
-        separable_gaussian_blur_pixels(&pixels, width as usize, height as usize, p_radius)
+        separable_gaussian_blur_pixels(&pixels, width as usize, height as usize, p_radius, progress)
       };
 
       // Write processed pixels back to the provided sub-image. apply_processing will handle blending.
       img.set_rgba_owned(vertical);
     });
   }
+  reporter.finish();
   println!("Gaussian blur took: {:?}", start.elapsed());
   // DebugFilters::GaussianBlur(radius as f32, duration.elapsed()).log();
 }
@@ -183,7 +192,7 @@ mod tests {
   use options::ApplyOptions;
 
   use super::gaussian_blur;
-  use abra_core::{Area, Image};
+  use abra_core::{Area, Image, gaussian_kernel};
 
   #[test]
   fn gaussian_blur_area_writes_back_only_area() {
@@ -232,6 +241,34 @@ mod tests {
     assert!(changed_count > 0, "No pixels in the blurred area changed");
   }
 
+  #[test]
+  fn gaussian_blur_inverted_area_keeps_area() {
+    let mut img = Image::new(16, 16);
+    for y in 0..16u32 {
+      for x in 0..16u32 {
+        let value = if (x + y) % 2 == 0 { 255u8 } else { 0u8 };
+        img.set_pixel(x, y, (value, value, value, 255));
+      }
+    }
+    let orig = img.to_rgba_vec();
+
+    let options = ApplyOptions::new()
+      .with_area(Area::rect((4.0, 4.0), (8.0, 8.0)))
+      .inverted();
+    gaussian_blur(&mut img, 2, options);
+
+    let changed = |x: u32, y: u32| {
+      let idx = ((y * 16 + x) * 4) as usize;
+      img.rgba()[idx..idx + 4] != orig[idx..idx + 4]
+    };
+    for y in 4..12u32 {
+      for x in 4..12u32 {
+        assert!(!changed(x, y), "pixel ({}, {}) inside the area changed", x, y);
+      }
+    }
+    assert!(changed(1, 1) && changed(14, 8), "pixels outside the area were not blurred");
+  }
+
   #[test]
   fn separable_blur_changes_pixels() {
     let mut img = Image::new(8, 8);
@@ -242,12 +279,29 @@ mod tests {
     }
     img.set_pixel(3, 3, (255u8, 0u8, 0u8, 255));
     let pixels = img.to_rgba_vec();
-    let out = super::separable_gaussian_blur_pixels(&pixels, 8, 8, 2);
+    let reporter = abra_core::ProgressReporter::new(|_| {});
+    let out = super::separable_gaussian_blur_pixels(&pixels, 8, 8, 2, reporter.progress());
     // Ensure center changed
     let idx = ((2 * 8 + 2) * 4) as usize;
     assert!(out[idx] != pixels[idx] || out[idx + 1] != pixels[idx + 1] || out[idx + 2] != pixels[idx + 2]);
   }
 
+  #[test]
+  fn progress_is_reported_for_each_area() {
+    let mut img = Image::new(64, 64);
+    let areas = [
+      Area::rect((0.0, 0.0), (20.0, 20.0)),
+      Area::rect((30.0, 30.0), (20.0, 20.0)),
+    ];
+    let mut values = vec![];
+    super::gaussian_blur_with_progress(&mut img, 3, ApplyOptions::new().with_areas(areas.to_vec()), |value| {
+      values.push(value)
+    });
+    assert!(values.len() > 2, "{:?}", values);
+    assert!(values.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", values);
+    assert_eq!(values.last(), Some(&1.0));
+  }
+
   #[test]
   fn horizontal_pass_changes_pixels() {
     let mut img = Image::new(8, 8);
@@ -258,7 +312,7 @@ mod tests {
     }
     img.set_pixel(3, 3, (255u8, 0u8, 0u8, 255));
     let pixels = img.to_rgba_vec();
-    let kernel = super::gaussian_kernel_1d(2);
+    let kernel = gaussian_kernel(2, 1.0);
     let width = 8usize;
     let y = 3usize;
     let mut horiz = vec![0u8; width * 4];
@@ -301,7 +355,7 @@ mod tests {
     }
     img.set_pixel(3, 3, (255u8, 0u8, 0u8, 255));
     let pixels = img.to_rgba_vec();
-    let kernel = super::gaussian_kernel_1d(2);
+    let kernel = gaussian_kernel(2, 1.0);
     let width = 8usize;
     let height = 8usize;
     let kernel_radius = 2i32;
@@ -1,7 +1,15 @@
 use crate::common::*;
 
+use abra_core::image::apply_area::process_image_with_origin;
+use abra_core::{CancellationToken, Cancelled, Color, Resize, TransformAlgorithm};
+use options::get_ctx;
+use std::sync::Arc;
+
 use crate::noise::NoiseDistribution;
 
+/// Padding in pixels around each area handed to the lens blur.
+const LENS_PADDING: i32 = 1;
+
 #[derive(Clone, Copy, Debug)]
 /// The aperture shape for the lens blur iris (number of blades)
 pub enum ApertureShape {
@@ -63,7 +71,7 @@ pub struct NoiseOptions {
   pub distribution: NoiseDistribution,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 /// Options for lens blur filter
 pub struct LensBlurOptions {
   /// Iris (aperture) configuration
@@ -74,6 +82,14 @@ pub struct LensBlurOptions {
   pub noise: Option<NoiseOptions>,
   /// Number of samples per pixel. Higher is smoother but slower.
   pub samples: u32,
+  /// Grayscale depth map where brightness is the distance from the camera (black is near, white is far).
+  /// It is stretched to the image size when the dimensions differ. None blurs every pixel with the full iris.
+  pub depth_map: Option<Arc<Image>>,
+  /// The depth in [0.0, 1.0] that is in focus. Only used with a depth map.
+  pub focal_distance: f32,
+  /// How far in [0.0, 1.0] a depth may be from `focal_distance` and still stay sharp.
+  /// Beyond it the blur grows with the distance until it reaches the full iris radius.
+  pub focal_range: f32,
 }
 
 impl Default for IrisOptions {
@@ -94,13 +110,34 @@ impl Default for LensBlurOptions {
       specular: None,
       noise: None,
       samples: 32,
+      depth_map: None,
+      focal_distance: 0.0,
+      focal_range: 0.0,
     }
   }
 }
 
-#[inline]
-fn luminance_rgb(r: f32, g: f32, b: f32) -> f32 {
-  0.2126 * r + 0.7152 * g + 0.0722 * b
+/// Per-pixel blur strength in [0.0, 1.0] from the depth map, where 0.0 keeps the pixel sharp.
+/// - `depth`: The depth map values of the full image in [0.0, 1.0].
+/// - `depth_width`: The width of the full image.
+/// - `origin`: The position of the processed region within the full image.
+/// - `size`: The size of the processed region.
+/// - `options`: The lens blur options holding the focal distance and range.
+fn blur_amounts(
+  depth: &[f32], depth_width: usize, origin: (i32, i32), size: (usize, usize), options: &LensBlurOptions,
+) -> Vec<f32> {
+  let range = options.focal_range.clamp(0.0, 1.0);
+  let falloff = (1.0 - range).max(f32::EPSILON);
+  let (w, h) = size;
+  let mut amounts = Vec::with_capacity(w * h);
+  for y in 0..h {
+    for x in 0..w {
+      let index = (origin.1 as usize + y) * depth_width + origin.0 as usize + x;
+      let distance = (depth[index] - options.focal_distance).abs();
+      amounts.push(((distance - range) / falloff).clamp(0.0, 1.0));
+    }
+  }
+  amounts
 }
 
 // Regular polygon radius for given angle theta.
@@ -109,14 +146,14 @@ fn luminance_rgb(r: f32, g: f32, b: f32) -> f32 {
 fn polygon_radius(theta: f32, blades: u32, r: f32) -> f32 {
   let n = blades as f32;
   let k = (std::f32::consts::PI / n).cos();
-  let a = (theta % (2.0 * std::f32::consts::PI / n)) - (std::f32::consts::PI / n);
+  let a = theta.rem_euclid(2.0 * std::f32::consts::PI / n) - (std::f32::consts::PI / n);
   (k * r) / a.cos()
 }
 
 // Boundary radius blending from polygon to circle based on blade_curvature in [0,1].
+// `theta` is measured relative to the aperture rotation and `r` is the circumradius.
 #[inline]
-fn iris_boundary(theta: f32, iris: &IrisOptions) -> f32 {
-  let r = iris.radius as f32;
+fn iris_boundary(theta: f32, iris: &IrisOptions, r: f32) -> f32 {
   let rp = polygon_radius(theta, iris.shape.blades(), r);
   let rc = r;
   rp * (1.0 - iris.blade_curvature) + rc * iris.blade_curvature
@@ -139,16 +176,128 @@ fn iris_sample_offset(i: u32, n: u32, iris: &IrisOptions) -> (f32, f32) {
   let r_unit = u.sqrt(); // area-uniform radius in [0,1]
   let mut theta = 2.0 * std::f32::consts::PI * v;
   theta += iris.rotation;
-  let r_boundary = iris_boundary(theta, iris);
+  let r_boundary = iris_boundary(theta - iris.rotation, iris, iris.radius as f32);
   let r = r_unit * r_boundary;
   let (dx, dy) = (r * theta.cos(), r * theta.sin());
   (dx, dy)
 }
 
+/// The aperture shape rasterized at a given radius, stored as one horizontal span per row.
+struct ApertureMask {
+  /// The distance in rows from the center to the top and bottom span.
+  reach: i32,
+  /// The first and last column offset inside the shape for each row from `-reach` to `reach`.
+  spans: Vec<Option<(i32, i32)>>,
+  /// The number of pixels inside the shape.
+  area: f32,
+}
+
+impl ApertureMask {
+  fn new(iris: &IrisOptions, radius: f32) -> Self {
+    let reach = radius.ceil() as i32;
+    let mut area = 0;
+    let spans = (-reach..=reach)
+      .map(|dy| {
+        let inside: Vec<i32> = (-reach..=reach)
+          .filter(|&dx| {
+            let distance = ((dx * dx + dy * dy) as f32).sqrt();
+            let theta = (dy as f32).atan2(dx as f32) - iris.rotation;
+            distance <= iris_boundary(theta, iris, radius).max(0.5)
+          })
+          .collect();
+        area += inside.len();
+        inside.first().zip(inside.last()).map(|(&first, &last)| (first, last))
+      })
+      .collect();
+    ApertureMask {
+      reach,
+      spans,
+      area: area.max(1) as f32,
+    }
+  }
+}
+
+/// Spreads the light of every highlight above the specular threshold over the aperture shape, so bright
+/// points render as bokeh balls. The gathered blur already holds each highlight once, so only the extra
+/// brightness is scattered, divided over the shape's area.
+/// - `out`: The gathered blur to add the bokeh to.
+/// - `src`: The source pixels.
+/// - `size`: The width and height of the image.
+/// - `options`: The lens blur configuration with the iris shape.
+/// - `specular`: The highlight threshold and brightness.
+/// - `amounts`: Optional per-pixel blur strength that scales the aperture.
+fn add_bokeh(
+  out: &mut [u8], src: &[u8], size: (usize, usize), options: &LensBlurOptions, specular: SpecularOptions,
+  amounts: Option<&[f32]>,
+) {
+  let boost = specular.brightness.max(1.0) - 1.0;
+  let radius = options.iris.radius as usize;
+  let (w, h) = size;
+  if boost <= 0.0 {
+    return;
+  }
+
+  // One mask per whole-pixel radius, since a depth map scales the aperture per pixel
+  let masks: Vec<ApertureMask> = (0..=radius)
+    .map(|r| ApertureMask::new(&options.iris, r as f32))
+    .collect();
+
+  // Highlights bucketed by row: (x, mask index, scattered light per pixel)
+  let mut highlights: Vec<Vec<(i32, usize, [f32; 3])>> = vec![Vec::new(); h];
+  for (idx, pixel) in src.chunks_exact(4).enumerate() {
+    let (r, g, b) = (pixel[0] as f32, pixel[1] as f32, pixel[2] as f32);
+    if Color::luma_709(r, g, b) / 255.0 <= specular.threshold {
+      continue;
+    }
+    let amount = amounts.map_or(1.0, |amounts| amounts[idx]);
+    let mask = (radius as f32 * amount).round() as usize;
+    if mask == 0 {
+      continue;
+    }
+    let scale = boost * (pixel[3] as f32 / 255.0) / masks[mask].area;
+    highlights[idx / w].push(((idx % w) as i32, mask, [r * scale, g * scale, b * scale]));
+  }
+
+  let reach = radius as i32;
+  out.par_chunks_mut(w * 4).enumerate().for_each(|(y, row)| {
+    let y = y as i32;
+    let mut light = vec![0.0f32; w * 3];
+    let mut lit = false;
+    for source_y in (y - reach).max(0)..=(y + reach).min(h as i32 - 1) {
+      for &(x, mask_index, color) in &highlights[source_y as usize] {
+        let mask = &masks[mask_index];
+        let dy = y - source_y;
+        if dy.abs() > mask.reach {
+          continue;
+        }
+        if let Some((from, to)) = mask.spans[(dy + mask.reach) as usize] {
+          for px in (x + from).max(0)..=(x + to).min(w as i32 - 1) {
+            let i = px as usize * 3;
+            light[i] += color[0];
+            light[i + 1] += color[1];
+            light[i + 2] += color[2];
+          }
+          lit = true;
+        }
+      }
+    }
+    if lit {
+      for (pixel, light) in row.chunks_exact_mut(4).zip(light.chunks_exact(3)) {
+        for (channel, value) in pixel.iter_mut().zip(light) {
+          *channel = (*channel as f32 + value).clamp(0.0, 255.0) as u8;
+        }
+      }
+    }
+  });
+}
+
 /// Applies a lens blur to an image with polygonal/circular iris, specular highlights and optional noise.
 /// - `image`: target image buffer
 /// - `p_options`: lens blur configuration
-fn apply_lens_blur(p_image: &mut Image, p_options: LensBlurOptions) {
+/// - `p_amounts`: optional per-pixel blur strength in [0.0, 1.0] that scales the iris
+fn apply_lens_blur(
+  p_image: &mut Image, p_options: &LensBlurOptions, p_amounts: Option<&[f32]>, p_cancel: &CancellationToken,
+) {
   let samples = p_options.samples.max(1);
   let (width, height) = p_image.dimensions::<u32>();
   if p_options.iris.radius == 0 || width == 0 || height == 0 {
@@ -169,6 +318,17 @@ fn apply_lens_blur(p_image: &mut Image, p_options: LensBlurOptions) {
   out.par_chunks_mut(4).enumerate().for_each(|(idx, dst_px)| {
     let x = (idx % w) as u32;
     let y = (idx / w) as u32;
+    // Once cancelled the result is thrown away, so the remaining pixels are skipped.
+    if p_cancel.is_cancelled() {
+      return;
+    }
+
+    // Pixels on the focal plane stay sharp
+    let amount = p_amounts.map_or(1.0, |amounts| amounts[idx]);
+    if amount <= 0.0 {
+      dst_px.copy_from_slice(&src[idx * 4..idx * 4 + 4]);
+      return;
+    }
 
     let mut acc_r = 0.0f32;
     let mut acc_g = 0.0f32;
@@ -176,11 +336,11 @@ fn apply_lens_blur(p_image: &mut Image, p_options: LensBlurOptions) {
     let mut acc_a = 0.0f32;
 
     for (dx, dy) in &offsets {
-      let fx = x as f32 + *dx;
-      let fy = y as f32 + *dy;
+      let fx = x as f32 + *dx * amount;
+      let fy = y as f32 + *dy * amount;
 
       // Bilinear sample from source snapshot
-      let (mut r, mut g, mut b, a) = {
+      let (r, g, b, a) = {
         // Manual bilinear from src snapshot without Image borrow complications
         let (wi, hi) = (width as i32, height as i32);
         let sx = fx.clamp(0.0, (wi - 1) as f32);
@@ -212,16 +372,6 @@ fn apply_lens_blur(p_image: &mut Image, p_options: LensBlurOptions) {
         (lerp(r0, r1, ty), lerp(g0, g1, ty), lerp(b0, b1, ty), lerp(a0, a1, ty))
       };
 
-      if let Some(spec) = p_options.specular {
-        let lum = luminance_rgb(r, g, b) / 255.0;
-        if lum > spec.threshold {
-          let m = spec.brightness.max(1.0);
-          r *= m;
-          g *= m;
-          b *= m;
-        }
-      }
-
       acc_r += r;
       acc_g += g;
       acc_b += b;
@@ -237,23 +387,212 @@ fn apply_lens_blur(p_image: &mut Image, p_options: LensBlurOptions) {
     dst_px[2] = rgb[2].clamp(0.0, 255.0) as u8;
     dst_px[3] = a as u8;
   });
+  if p_cancel.is_cancelled() {
+    return;
+  }
+
+  if let Some(specular) = p_options.specular {
+    add_bokeh(&mut out, src, (w, h), p_options, specular, p_amounts);
+  }
 
   p_image.set_rgba_owned(out);
 
   if let Some(noise) = p_options.noise {
-    if noise.amount > 0.0 {
-      crate::noise::noise(p_image, noise.amount, noise.distribution, None);
+    if noise.amount > 0.0 && !p_cancel.is_cancelled() {
+      crate::noise::add_noise(p_image, noise.amount, noise.distribution, true, 0, None);
     }
   }
 }
 /// Applies a lens blur to an image with polygonal/circular iris, specular highlights and optional noise.
+/// With a depth map in the options, pixels near the focal plane stay sharp and the rest blur with their depth.
 /// - `p_image`: target image buffer
 /// - `p_options`: lens blur configuration
 /// - `p_apply_options`: additional options for applying the blur
 pub fn lens_blur<'a>(
   p_image: impl Into<ImageRef<'a>>, p_options: LensBlurOptions, p_apply_options: impl Into<Options>,
 ) {
+  // A token that is never cancelled lets the blur run to completion.
+  let _ = lens_blur_cancellable(p_image, p_options, p_apply_options, &CancellationToken::new());
+}
+
+/// Applies a lens blur like `lens_blur`, stopping early when the token is cancelled.
+/// When `Err(Cancelled)` is returned, the image is unchanged or, with several areas, only partly blurred.
+/// - `p_image`: target image buffer
+/// - `p_options`: lens blur configuration
+/// - `p_apply_options`: additional options for applying the blur
+/// - `p_cancel`: the token that is checked while the blur runs
+pub fn lens_blur_cancellable<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_options: LensBlurOptions, p_apply_options: impl Into<Options>,
+  p_cancel: &CancellationToken,
+) -> Result<(), Cancelled> {
+  p_cancel.check()?;
   let mut image_ref: ImageRef = p_image.into();
   let image = &mut image_ref as &mut Image;
-  apply_filter!(apply_lens_blur, image, p_apply_options, 1, p_options);
+  let options: Options = p_apply_options.into();
+  let (width, height) = image.dimensions::<u32>();
+
+  // Depth values of the full image, matched to its size
+  let depth: Option<Vec<f32>> = p_options.depth_map.as_ref().map(|depth_map| {
+    let mut depth_map = (**depth_map).clone();
+    if depth_map.dimensions::<u32>() != (width, height) {
+      depth_map.resize(width, height, TransformAlgorithm::Bilinear);
+    }
+    depth_map
+      .rgba()
+      .chunks_exact(4)
+      .map(|p| Color::luma_709(p[0] as f32, p[1] as f32, p[2] as f32) / 255.0)
+      .collect()
+  });
+
+  // The processor only sees the padded region around each area, which starts at the given origin
+  process_image_with_origin(image, get_ctx(options.as_ref()), LENS_PADDING, |img, origin| {
+    let amounts = depth.as_ref().map(|depth| {
+      let size = img.dimensions::<usize>();
+      blur_amounts(depth, width as usize, origin, size, &p_options)
+    });
+    apply_lens_blur(img, &p_options, amounts.as_deref(), p_cancel);
+  });
+  p_cancel.check()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn depth_map_keeps_focal_plane_sharp() {
+    // Vertical stripes so any horizontal blur changes the pixels.
+    let mut image = Image::new(40, 20);
+    for y in 0..20u32 {
+      for x in 0..40u32 {
+        let v = if x % 2 == 0 { 255 } else { 0 };
+        image.set_pixel(x, y, (v, v, v, 255));
+      }
+    }
+    // The left half is near (black) and the right half is far (white).
+    let mut depth = Image::new_from_color(40, 20, abra_core::Color::black());
+    for y in 0..20u32 {
+      for x in 20..40u32 {
+        depth.set_pixel(x, y, (255, 255, 255, 255));
+      }
+    }
+    let original = image.clone();
+    let options = LensBlurOptions {
+      depth_map: Some(Arc::new(depth)),
+      focal_distance: 0.0,
+      focal_range: 0.2,
+      ..Default::default()
+    };
+    lens_blur(&mut image, options.clone(), None);
+    assert_eq!(image.get_pixel(5, 10), original.get_pixel(5, 10));
+    assert_eq!(image.get_pixel(6, 10), original.get_pixel(6, 10));
+    let (r, _, _, _) = image.get_pixel(30, 10).unwrap();
+    assert!(r > 40 && r < 215, "far pixel was not blurred: {}", r);
+
+    // The area version samples the depth map at the same image positions.
+    let mut area_image = original.clone();
+    let area = abra_core::Area::rect((0.0, 5.0), (40.0, 10.0));
+    lens_blur(&mut area_image, options, ApplyOptions::new().with_area(area));
+    assert_eq!(area_image.get_pixel(5, 10), original.get_pixel(5, 10));
+    assert_eq!(area_image.get_pixel(30, 10), image.get_pixel(30, 10));
+  }
+
+  #[test]
+  fn cancelled_blur_leaves_image_unchanged() {
+    let mut image = Image::new(20, 20);
+    image.set_pixel(10, 10, (255, 255, 255, 255));
+    let original = image.clone();
+    let cancel = CancellationToken::new();
+    cancel.clone().cancel();
+    let result = lens_blur_cancellable(&mut image, LensBlurOptions::default(), None, &cancel);
+    assert_eq!(result, Err(Cancelled));
+    assert_eq!(image.rgba(), original.rgba());
+
+    let result = lens_blur_cancellable(&mut image, LensBlurOptions::default(), None, &CancellationToken::new());
+    assert_eq!(result, Ok(()));
+    assert_ne!(image.rgba(), original.rgba());
+  }
+
+  #[test]
+  fn blur_cancelled_from_another_thread_stops_early() {
+    // Large enough that the blur is still running when the other thread cancels it.
+    let mut image = Image::new(1500, 1500);
+    image.set_pixel(750, 750, (255, 255, 255, 255));
+    let original = image.clone();
+    let cancel = CancellationToken::new();
+    let canceller = {
+      let cancel = cancel.clone();
+      std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        cancel.cancel();
+      })
+    };
+    let options = LensBlurOptions {
+      samples: 256,
+      ..LensBlurOptions::default()
+    };
+    let result = lens_blur_cancellable(&mut image, options, None, &cancel);
+    canceller.join().unwrap();
+    assert_eq!(result, Err(Cancelled));
+    assert_eq!(image.rgba(), original.rgba());
+  }
+
+  /// Renders a single bright point on black with a sharp-edged aperture of radius 8.
+  fn bokeh_of_point(shape: ApertureShape, blade_curvature: f32) -> Image {
+    let mut image = Image::new_from_color(41, 41, abra_core::Color::black());
+    image.set_pixel(20, 20, (255, 255, 255, 255));
+    let options = LensBlurOptions {
+      iris: IrisOptions {
+        shape,
+        radius: 8,
+        blade_curvature,
+        rotation: 0.0,
+      },
+      specular: Some(SpecularOptions {
+        brightness: 300.0,
+        threshold: 0.9,
+      }),
+      ..Default::default()
+    };
+    lens_blur(&mut image, options, None);
+    image
+  }
+
+  #[test]
+  fn bright_point_blooms_into_aperture_shape() {
+    let lit = |image: &Image, x: u32, y: u32| image.get_pixel(x, y).unwrap().0 > 200;
+
+    // A hexagon reaches the full radius at its corners (along the x axis) but not at its flat sides.
+    let hexagon = bokeh_of_point(ApertureShape::Hexagon, 0.0);
+    assert!(lit(&hexagon, 20, 20) && lit(&hexagon, 27, 20) && lit(&hexagon, 13, 20));
+    assert!(lit(&hexagon, 20, 26) && !lit(&hexagon, 20, 28));
+    assert!(!lit(&hexagon, 29, 20) && !lit(&hexagon, 20, 12));
+
+    // A fully curved iris is a circle of the same radius.
+    let circle = bokeh_of_point(ApertureShape::Hexagon, 1.0);
+    assert!(lit(&circle, 20, 28) && lit(&circle, 28, 20) && lit(&circle, 25, 25));
+    assert!(!lit(&circle, 29, 20) && !lit(&circle, 27, 27));
+  }
+
+  #[test]
+  fn without_specular_a_point_stays_dim() {
+    let mut image = Image::new_from_color(41, 41, abra_core::Color::black());
+    image.set_pixel(20, 20, (255, 255, 255, 255));
+    lens_blur(&mut image, LensBlurOptions::default(), None);
+    assert!(image.get_pixel(24, 20).unwrap().0 < 100);
+  }
+
+  #[test]
+  fn without_depth_map_blurs_everything() {
+    let mut image = Image::new(20, 20);
+    for y in 0..20u32 {
+      for x in 0..20u32 {
+        let v = if x % 2 == 0 { 255 } else { 0 };
+        image.set_pixel(x, y, (v, v, v, 255));
+      }
+    }
+    lens_blur(&mut image, LensBlurOptions::default(), None);
+    let (r, _, _, _) = image.get_pixel(10, 10).unwrap();
+    assert!(r > 40 && r < 215, "pixel was not blurred: {}", r);
+  }
 }
@@ -1,3 +1,4 @@
+mod alpha;
 mod blur;
 mod r#box;
 mod focus;
@@ -6,10 +7,13 @@ mod lens;
 mod motion;
 mod surface;
 
+pub use alpha::blur_alpha;
 pub use blur::blur;
 pub use r#box::box_blur;
 pub use focus::{BlurType, FocusBlurOptions, FocusShape, focus_blur};
-pub use gaussian::gaussian_blur;
-pub use lens::{ApertureShape, IrisOptions, LensBlurOptions, NoiseOptions, SpecularOptions, lens_blur};
+pub use gaussian::{gaussian_blur, gaussian_blur_with_progress};
+pub use lens::{
+  ApertureShape, IrisOptions, LensBlurOptions, NoiseOptions, SpecularOptions, lens_blur, lens_blur_cancellable,
+};
 pub use motion::motion_blur;
-pub use surface::surface_blur;
+pub use surface::{surface_blur, surface_blur_ex, surface_blur_with_progress};
@@ -1,15 +1,33 @@
 use crate::common::*;
 
 use abra_core::color::Histogram;
+use abra_core::image::apply_area::process_image_with_progress;
 use abra_core::transform::{Resize, TransformAlgorithm};
+use abra_core::{Progress, ProgressReporter};
+use options::get_ctx;
+
+/// Histogram weight of a pixel that lies fully inside the blur window.
+/// Pixels on the outer ring of a fractional radius get a proportional share of it.
+const FULL_WEIGHT: u64 = 256;
+
+/// Converts an RGB color to full range YCbCr (BT.601).
+fn rgb_to_ycbcr(r: u8, g: u8, b: u8) -> [u8; 3] {
+  let (r, g, b) = (r as f32, g as f32, b as f32);
+  let y = 0.299 * r + 0.587 * g + 0.114 * b;
+  let cb = 128.0 - 0.168736 * r - 0.331264 * g + 0.5 * b;
+  let cr = 128.0 + 0.5 * r - 0.418688 * g - 0.081312 * b;
+  [y, cb, cr].map(|value| value.round().clamp(0.0, 255.0) as u8)
+}
 
-fn apply_surface_blur(image: &mut Image, radius: u32, threshold: u8) {
-  if radius == 0 {
+fn apply_surface_blur(
+  image: &mut Image, radius: f32, luma_threshold: u8, chroma_threshold: u8, progress: Progress<'_>,
+) {
+  if radius <= 0.0 {
     return;
   }
 
   // For large radii, use sub-sampling approximation for speed if dimensions allow
-  if radius > 50 {
+  if radius > 50.0 {
     let (w, h) = image.dimensions::<u32>();
     let factor = 2;
     if w >= factor && h >= factor {
@@ -21,7 +39,7 @@ fn apply_surface_blur(image: &mut Image, radius: u32, threshold: u8) {
       downsampled.resize(new_w, new_h, TransformAlgorithm::Bilinear);
 
       // Apply blur at reduced resolution
-      apply_surface_blur(&mut downsampled, radius / factor, threshold);
+      apply_surface_blur(&mut downsampled, radius / factor as f32, luma_threshold, chroma_threshold, progress);
 
       // Upsample using bicubic for better quality
       downsampled.resize(w, h, TransformAlgorithm::Bicubic);
@@ -40,58 +58,78 @@ fn apply_surface_blur(image: &mut Image, radius: u32, threshold: u8) {
   let (w, h) = (width as usize, height as usize);
   let mut out = vec![0u8; w * h * 4];
 
-  let r = radius as i32;
+  // Whole pixels covered by the window, plus a partially weighted outer ring for fractional radii
+  let inner = radius.floor() as i32;
+  let reach = radius.ceil() as i32;
+  let ring_weight = if reach == inner {
+    FULL_WEIGHT
+  } else {
+    ((radius - inner as f32) * FULL_WEIGHT as f32).round() as u64
+  };
+
+  // Luma and chroma are filtered separately, so convert every pixel once up front
+  let ycbcr: Vec<[u8; 3]> = src.chunks_exact(4).map(|p| rgb_to_ycbcr(p[0], p[1], p[2])).collect();
 
   // Process rows in parallel for better cache locality
+  let rows = progress.counter(h);
   out.par_chunks_mut(w * 4).enumerate().for_each(|(y, row)| {
     // Allocate histogram once per row - reuse across pixels
     let mut hist = Histogram::new();
 
     for x in 0..w {
-      let center_idx = (y * w + x) * 4;
-      let cr = src[center_idx];
-      let cg = src[center_idx + 1];
-      let cb = src[center_idx + 2];
-      let ca = src[center_idx + 3];
+      let center_idx = y * w + x;
+      let [cy, ccb, ccr] = ycbcr[center_idx];
 
-      // Clear and get mutable access to histogram arrays
+      // Clear and get mutable access to histogram arrays (Y, Cb and Cr)
       hist.clear();
-      let (r_hist, g_hist, b_hist) = hist.rgb_mut();
+      let (y_hist, cb_hist, cr_hist) = hist.rgb_mut();
 
       // Build histogram of neighbors
-      for dy in -r..=r {
+      for dy in -reach..=reach {
         let ny = ((y as i32 + dy).max(0).min(h as i32 - 1)) as usize;
-        let ny_offset = ny * w * 4;
+        let ny_offset = ny * w;
 
-        for dx in -r..=r {
+        for dx in -reach..=reach {
           let nx = ((x as i32 + dx).max(0).min(w as i32 - 1)) as usize;
-          let n_idx = ny_offset + nx * 4;
+          let weight = if dx.abs().max(dy.abs()) > inner {
+            ring_weight
+          } else {
+            FULL_WEIGHT
+          };
 
           // Use unsafe for faster access - bounds already validated
-          let (nr, ng, nb) =
-            unsafe { (*src.get_unchecked(n_idx), *src.get_unchecked(n_idx + 1), *src.get_unchecked(n_idx + 2)) };
+          let [ny_, ncb, ncr] = unsafe { *ycbcr.get_unchecked(ny_offset + nx) };
 
-          r_hist[nr as usize] += 1;
-          g_hist[ng as usize] += 1;
-          b_hist[nb as usize] += 1;
+          y_hist[ny_ as usize] += weight;
+          cb_hist[ncb as usize] += weight;
+          cr_hist[ncr as usize] += weight;
         }
       }
 
-      // Compute weighted average within threshold using histogram
-      let out_r = hist.red_weighted_average(cr, threshold);
-      let out_g = hist.green_weighted_average(cg, threshold);
-      let out_b = hist.blue_weighted_average(cb, threshold);
-
+      // Compute weighted averages within the thresholds using the histograms
+      let delta_y = hist.red_weighted_average(cy, luma_threshold) as f32 - cy as f32;
+      let delta_cb = hist.green_weighted_average(ccb, chroma_threshold) as f32 - ccb as f32;
+      let delta_cr = hist.blue_weighted_average(ccr, chroma_threshold) as f32 - ccr as f32;
+
+      // Apply the change as an RGB offset so untouched pixels keep their exact color
+      let src_pixel = &src[center_idx * 4..(center_idx + 1) * 4];
+      let deltas = [
+        delta_y + 1.402 * delta_cr,
+        delta_y - 0.344136 * delta_cb - 0.714136 * delta_cr,
+        delta_y + 1.772 * delta_cb,
+      ];
       let dst = &mut row[x * 4..(x + 1) * 4];
-      dst[0] = out_r;
-      dst[1] = out_g;
-      dst[2] = out_b;
-      dst[3] = ca;
+      for ((out, &value), delta) in dst.iter_mut().zip(src_pixel).zip(deltas) {
+        *out = (value as f32 + delta).round().clamp(0.0, 255.0) as u8;
+      }
+      dst[3] = src_pixel[3];
     }
+    rows.tick();
   });
 
   image.set_rgba_owned(out);
 }
+
 /// Applies a surface blur to an image.
 /// - `p_image`: The image to be blurred.
 /// - `p_radius`: The radius of the surface blur.
@@ -99,8 +137,93 @@ fn apply_surface_blur(image: &mut Image, radius: u32, threshold: u8) {
 /// - `p_apply_options`: Additional options for applying the blur.
 pub fn surface_blur<'a>(
   p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_threshold: u8, p_apply_options: impl Into<Options>,
+) {
+  surface_blur_ex(p_image, p_radius as f32, p_threshold, p_threshold, p_apply_options);
+}
+
+/// Applies a surface blur with a fractional radius and separate thresholds for brightness and color.
+/// Skin tones vary mostly in luminance, so a higher luma threshold smooths texture while a lower
+/// chroma threshold keeps color edges such as lips and eyes intact.
+/// - `p_image`: The image to be blurred.
+/// - `p_radius`: The radius of the surface blur. Fractional radii partially weight the outer ring of pixels.
+/// - `p_luma_threshold`: The maximum luminance difference of neighbors that are averaged.
+/// - `p_chroma_threshold`: The maximum chrominance difference of neighbors that are averaged.
+/// - `p_apply_options`: Additional options for applying the blur.
+pub fn surface_blur_ex<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_radius: f32, p_luma_threshold: u8, p_chroma_threshold: u8,
+  p_apply_options: impl Into<Options>,
+) {
+  surface_blur_with_progress(p_image, p_radius, p_luma_threshold, p_chroma_threshold, p_apply_options, |_| {});
+}
+
+/// Applies a surface blur like `surface_blur_ex`, reporting how far the blur is as it runs.
+/// - `p_image`: The image to be blurred.
+/// - `p_radius`: The radius of the surface blur. Fractional radii partially weight the outer ring of pixels.
+/// - `p_luma_threshold`: The maximum luminance difference of neighbors that are averaged.
+/// - `p_chroma_threshold`: The maximum chrominance difference of neighbors that are averaged.
+/// - `p_apply_options`: Additional options for applying the blur.
+/// - `p_progress`: Called with the progress from 0.0 to 1.0 in steps of 5%. It can be called from any of the
+///   threads the blur runs on, but never from two at the same time.
+pub fn surface_blur_with_progress<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_radius: f32, p_luma_threshold: u8, p_chroma_threshold: u8,
+  p_apply_options: impl Into<Options>, p_progress: impl FnMut(f32) + Send,
 ) {
   let mut image_ref: ImageRef = p_image.into();
   let image = &mut image_ref as &mut Image;
-  apply_filter!(apply_surface_blur, image, p_apply_options, p_radius as i32, p_radius, p_threshold);
+  let padding = p_radius.max(0.0).ceil() as i32;
+  let options = p_apply_options.into();
+  let reporter = ProgressReporter::new(p_progress);
+  process_image_with_progress(image, get_ctx(options.as_ref()), padding, reporter.progress(), |img, progress| {
+    apply_surface_blur(img, p_radius, p_luma_threshold, p_chroma_threshold, progress);
+  });
+  reporter.finish();
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A noisy gray left half next to a flat orange right half of about the same luminance.
+  fn test_image() -> Image {
+    let mut img = Image::new(12, 6);
+    for y in 0..6u32 {
+      for x in 0..12u32 {
+        let pixel = if x < 6 {
+          let v = if (x + y) % 2 == 0 { 120 } else { 136 };
+          (v, v, v, 255)
+        } else {
+          (220, 90, 60, 255)
+        };
+        img.set_pixel(x, y, pixel);
+      }
+    }
+    img
+  }
+
+  #[test]
+  fn luma_threshold_smooths_brightness_and_chroma_threshold_keeps_color_edges() {
+    let mut img = test_image();
+    surface_blur_ex(&mut img, 1.5, 20, 4, None);
+    // The luma noise is averaged away.
+    let (r, g, b, _) = img.get_pixel(2, 2).unwrap();
+    assert!((126..=130).contains(&r) && r == g && g == b, "gray was {:?}", (r, g, b));
+    // The orange half is too far away in chroma to bleed into the gray half, and stays exact itself.
+    assert_eq!(img.get_pixel(9, 2), Some((220, 90, 60, 255)));
+    let (r, g, b, _) = img.get_pixel(5, 2).unwrap();
+    assert!(r.abs_diff(g) <= 1 && g.abs_diff(b) <= 1, "edge picked up color {:?}", (r, g, b));
+
+    // A loose chroma threshold lets the color spread across the edge.
+    let mut img = test_image();
+    surface_blur_ex(&mut img, 1.5, 20, 80, None);
+    let (r, _, b, _) = img.get_pixel(5, 2).unwrap();
+    assert!(r > b + 10, "edge stayed gray {:?}", img.get_pixel(5, 2));
+  }
+
+  #[test]
+  fn low_luma_threshold_keeps_noise() {
+    let mut img = test_image();
+    let original = img.to_rgba_vec();
+    surface_blur_ex(&mut img, 2.0, 4, 4, None);
+    assert_eq!(img.to_rgba_vec(), original);
+  }
 }
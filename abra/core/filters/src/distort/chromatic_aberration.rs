@@ -0,0 +1,96 @@
+use crate::common::*;
+use abra_core::PointF;
+use abra_core::transform::*;
+
+fn apply_chromatic_aberration(p_image: &mut Image, p_amount: f32, p_center: PointF) {
+  let (width, height) = p_image.dimensions::<u32>();
+  if width == 0 || height == 0 {
+    return;
+  }
+  let (center_x, center_y) = (p_center.x * width as f32, p_center.y * height as f32);
+  // The distance to the farthest corner, where the channels are offset by the full amount.
+  let max_radius = [
+    (0.0, 0.0),
+    (width as f32, 0.0),
+    (0.0, height as f32),
+    (width as f32, height as f32),
+  ]
+  .iter()
+  .map(|(x, y)| (x - center_x).hypot(y - center_y))
+  .fold(0.0f32, f32::max)
+  .max(1.0);
+  let scale = p_amount / max_radius;
+
+  let original_image = p_image.clone();
+  let original_pixels = original_image.rgba();
+  let mut new_pixels = vec![0u8; (width * height * 4) as usize];
+
+  new_pixels.par_chunks_mut(4).enumerate().for_each(|(i, chunk)| {
+    let (x, y) = (i as u32 % width, i as u32 / width);
+    let dx = x as f32 + 0.5 - center_x;
+    let dy = y as f32 + 0.5 - center_y;
+    // Red is magnified and blue is shrunk around the center, like a lens that refracts them differently.
+    let sample = |p_scale: f32| {
+      let src_x = (center_x + dx * p_scale - 0.5).clamp(0.0, (width - 1) as f32);
+      let src_y = (center_y + dy * p_scale - 0.5).clamp(0.0, (height - 1) as f32);
+      sample_bilinear(&original_image, src_x, src_y)
+    };
+    let idx = i * 4;
+    chunk[0] = sample(1.0 - scale)[0];
+    chunk[1] = original_pixels[idx + 1];
+    chunk[2] = sample(1.0 + scale)[2];
+    chunk[3] = original_pixels[idx + 3];
+  });
+
+  p_image.set_new_pixels(&new_pixels, width, height);
+}
+
+/// Applies a chromatic aberration effect that offsets the red and blue channels radially from a center point,
+/// producing colored fringes that grow toward the edges like those of a cheap lens.
+/// - `p_image`: The image to apply the effect to.
+/// - `p_amount`: How far the red and blue channels are offset at the farthest corner, in pixels. Negative values
+///   swap the direction of the fringes.
+/// - `p_center`: The center of the effect relative to the filtered region, where `(0.5, 0.5)` is the middle.
+/// - `p_apply_options`: Options to specify for the filter.
+pub fn chromatic_aberration<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_amount: f32, p_center: impl Into<PointF>, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let p_center = p_center.into();
+  apply_filter!(apply_chromatic_aberration, image, p_apply_options, 1, p_amount, p_center);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::{Area, Color};
+
+  fn square_image() -> Image {
+    let mut image = Image::new_from_color(41, 41, Color::black());
+    for y in 10..31 {
+      for x in 10..31 {
+        image.set_pixel(x, y, (255, 255, 255, 255));
+      }
+    }
+    image
+  }
+
+  #[test]
+  fn offsets_red_and_blue_away_from_center() {
+    let mut image = square_image();
+    let options = ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (41.0, 41.0)));
+    chromatic_aberration(&mut image, 6.0, (0.5, 0.5), options);
+
+    // Just outside the right edge of the square red has spread out while blue has pulled in.
+    let (r, g, b, _) = image.get_pixel(32, 20).unwrap();
+    assert!(r > 0);
+    assert_eq!((g, b), (0, 0));
+    // Just inside the edge blue is missing.
+    let (r, g, b, _) = image.get_pixel(29, 20).unwrap();
+    assert_eq!((r, g), (255, 255));
+    assert!(b < 255);
+    // The center does not move.
+    assert_eq!(image.get_pixel(20, 20), Some((255, 255, 255, 255)));
+  }
+}
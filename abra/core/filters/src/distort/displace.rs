@@ -0,0 +1,173 @@
+use crate::common::*;
+use abra_core::transform::{TransformAlgorithm, resize, sample_bilinear};
+
+/// How [`displace`] fills pixels that are displaced past the edge of the image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplaceEdge {
+  /// Repeat the nearest edge pixel.
+  Repeat,
+  /// Wrap around to the opposite side of the image, which suits tileable textures.
+  Wrap,
+  /// Leave the pixel transparent.
+  Transparent,
+}
+
+/// Samples the image at a fractional position, resolving positions outside the image with the edge mode.
+fn sample(p_image: &Image, p_x: f32, p_y: f32, p_edge: DisplaceEdge) -> [u8; 4] {
+  let (width, height) = p_image.dimensions::<u32>();
+  let (max_x, max_y) = ((width - 1) as f32, (height - 1) as f32);
+  let (x, y) = match p_edge {
+    DisplaceEdge::Repeat => (p_x.clamp(0.0, max_x), p_y.clamp(0.0, max_y)),
+    // Positions between the last pixel and the wrapped first one keep the last pixel instead of blending
+    // with the transparent outside.
+    DisplaceEdge::Wrap => (p_x.rem_euclid(width as f32).min(max_x), p_y.rem_euclid(height as f32).min(max_y)),
+    DisplaceEdge::Transparent => (p_x, p_y),
+  };
+  sample_bilinear(p_image, x, y)
+}
+
+/// Displaces the pixels of the prepared region, reading the full-size map at image coordinates.
+/// - `p_origin`: The position of the region's top-left pixel within the image.
+/// - `p_map_width`: The width of the map, which matches the image.
+fn apply_displace(
+  p_image: &mut Image, p_origin: (i32, i32), p_map: &[u8], p_map_width: usize, p_scale: (f32, f32),
+  p_edge: DisplaceEdge,
+) {
+  let (width, height) = p_image.dimensions::<usize>();
+  if width == 0 || height == 0 {
+    return;
+  }
+  let source = p_image.clone();
+  let (origin_x, origin_y) = (p_origin.0 as usize, p_origin.1 as usize);
+
+  let mut out = source.to_rgba_vec();
+  out.par_chunks_mut(width * 4).enumerate().for_each(|(y, row)| {
+    for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+      let i = ((origin_y + y) * p_map_width + origin_x + x) * 4;
+      // Mid gray (128) is the neutral value, black and white displace by the full scale in opposite directions.
+      let dx = (p_map[i] as f32 - 128.0) / 128.0 * p_scale.0;
+      let dy = (p_map[i + 1] as f32 - 128.0) / 128.0 * p_scale.1;
+      if dx == 0.0 && dy == 0.0 {
+        continue;
+      }
+      pixel.copy_from_slice(&sample(&source, x as f32 + dx, y as f32 + dy, p_edge));
+    }
+  });
+  p_image.set_rgba_owned(out);
+}
+
+/// Offsets each pixel by an amount read from a displacement map, like the Displace filter of photo editors.
+/// This can wrap textures around shapes, fit graphics onto wrinkled surfaces or fake glass and water.
+///
+/// The red channel of the map moves pixels horizontally and the green channel vertically. A value of 128 leaves
+/// the pixel in place, so a flat 50% gray map changes nothing. Brighter values take the pixel from the right
+/// (or below) and darker values from the left (or above). The map is stretched to the size of the image when
+/// their dimensions differ, so it lines up with the image whether or not an area is filtered.
+/// - `p_image`: The image to apply the filter to.
+/// - `p_map`: The displacement map.
+/// - `p_scale_x`: The horizontal displacement in pixels for a white or black map value.
+/// - `p_scale_y`: The vertical displacement in pixels for a white or black map value.
+/// - `p_edge`: How pixels displaced past the edge of the image are filled. With an area, pixels wrap around the
+///   area's bounds grown by the largest displacement.
+/// - `p_apply_options`: Options to specify for the filter.
+pub fn displace<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_map: &Image, p_scale_x: f32, p_scale_y: f32, p_edge: DisplaceEdge,
+  p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let (width, height) = image.dimensions::<u32>();
+  if width == 0 || height == 0 {
+    return;
+  }
+  let mut map = p_map.clone();
+  if map.dimensions::<u32>() != (width, height) {
+    resize(&mut map, width, height, TransformAlgorithm::Bilinear);
+  }
+  // Displaced samples near the edge of an area read real pixels from the padding. One more pixel covers the
+  // neighbor that bilinear sampling blends with.
+  let padding = p_scale_x.abs().max(p_scale_y.abs()).ceil() as i32 + 1;
+  let options = p_apply_options.into();
+  let ctx = options::get_ctx(options.as_ref());
+  abra_core::image::apply_area::process_image_with_origin(image, ctx, padding, |img, origin| {
+    apply_displace(img, origin, map.rgba(), width as usize, (p_scale_x, p_scale_y), p_edge);
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::{Area, Color};
+
+  /// A black image with a single white column.
+  fn column(p_x: u32) -> Image {
+    let mut image = Image::new_from_color(16, 8, Color::from_rgba(0, 0, 0, 255));
+    for y in 0..8 {
+      image.set_pixel(p_x, y, (255, 255, 255, 255));
+    }
+    image
+  }
+
+  #[test]
+  fn gray_map_leaves_image_unchanged() {
+    let mut image = column(5);
+    // A smaller map is stretched to the image, which keeps a flat map flat.
+    let map = Image::new_from_color(4, 4, Color::from_rgba(128, 128, 128, 255));
+    displace(&mut image, &map, 20.0, 20.0, DisplaceEdge::Repeat, None);
+    assert_eq!(image.rgba(), column(5).rgba());
+  }
+
+  #[test]
+  fn map_channels_shift_pixels() {
+    // Red 192 reads the pixel 2 to the right, so the column moves 2 pixels left.
+    let mut image = column(5);
+    let map = Image::new_from_color(16, 8, Color::from_rgba(192, 128, 128, 255));
+    displace(&mut image, &map, 4.0, 4.0, DisplaceEdge::Repeat, None);
+    assert_eq!(image.get_pixel(3, 4), Some((255, 255, 255, 255)));
+    assert_eq!(image.get_pixel(5, 4), Some((0, 0, 0, 255)));
+
+    // Half a pixel of displacement blends neighbors.
+    let mut image = column(5);
+    let map = Image::new_from_color(16, 8, Color::from_rgba(144, 128, 128, 255));
+    displace(&mut image, &map, 4.0, 4.0, DisplaceEdge::Repeat, None);
+    let (r, ..) = image.get_pixel(4, 4).unwrap();
+    assert!((120..=135).contains(&r), "{}", r);
+  }
+
+  #[test]
+  fn edge_modes_fill_pixels_past_the_border() {
+    let map = Image::new_from_color(16, 8, Color::from_rgba(192, 128, 128, 255));
+
+    let mut repeat = column(15);
+    displace(&mut repeat, &map, 4.0, 0.0, DisplaceEdge::Repeat, None);
+    assert_eq!(repeat.get_pixel(15, 4), Some((255, 255, 255, 255)));
+
+    let mut wrap = column(0);
+    displace(&mut wrap, &map, 4.0, 0.0, DisplaceEdge::Wrap, None);
+    assert_eq!(wrap.get_pixel(14, 4), Some((255, 255, 255, 255)));
+
+    let mut transparent = column(0);
+    displace(&mut transparent, &map, 4.0, 0.0, DisplaceEdge::Transparent, None);
+    assert_eq!(transparent.get_pixel(15, 4), Some((0, 0, 0, 0)));
+    assert_eq!(transparent.get_pixel(10, 4), Some((0, 0, 0, 255)));
+  }
+
+  #[test]
+  fn map_lines_up_with_the_image_inside_an_area() {
+    // Only the right half of the map displaces, so an area there must read that half of the map.
+    let mut map = Image::new_from_color(16, 8, Color::from_rgba(128, 128, 128, 255));
+    for y in 0..8 {
+      for x in 8..16 {
+        map.set_pixel(x, y, (192, 128, 128, 255));
+      }
+    }
+    let mut image = column(12);
+    let area = Area::rect((10.0, 0.0), (4.0, 8.0));
+    displace(&mut image, &map, 4.0, 0.0, DisplaceEdge::Transparent, ApplyOptions::new().with_area(area));
+    // The column moves 2 pixels left, and the pixel it came from reads the real image outside the area.
+    assert_eq!(image.get_pixel(10, 4), Some((255, 255, 255, 255)));
+    assert_eq!(image.get_pixel(12, 4), Some((0, 0, 0, 255)));
+    assert_eq!(image.get_pixel(13, 4), Some((0, 0, 0, 255)));
+    assert_eq!(image.get_pixel(14, 4), Some((0, 0, 0, 255)));
+  }
+}
@@ -1,5 +1,9 @@
+mod chromatic_aberration;
+mod displace;
 mod pinch;
 mod ripple;
 
+pub use chromatic_aberration::chromatic_aberration;
+pub use displace::*;
 pub use pinch::pinch;
 pub use ripple::*;
@@ -1,59 +1,120 @@
-use crate::{blur::blur, kernel::apply_kernel, sobel::sobel_horizontal};
-use abra_core::Image;
-use adjustments::color::grayscale;
-
-// TODO: Implement the glowing_edges filter to look a little more like Photoshop's glowing edges filter.
-/// Applies the glowing edges filter to the image.
-pub fn glowing_edges(image: &mut Image, edge_width: u32, _edge_brightness: u32, _smoothness: u32) {
-  // Step 1: Convert to grayscale
-  let mut clone = image.clone();
-  grayscale(&mut clone, None);
-
-  // Step 2: Apply Sobel filter to detect edges
-  sobel_horizontal(&mut clone);
-
-  // Step 3: Adjust edge width by dilating the edges
-  for _ in 0..edge_width {
-    apply_kernel(&mut clone, &[0.0, 0.5, 0.0, 0.5, 1.0, 0.5, 0.0, 0.5, 0.0]);
+use crate::common::*;
+use crate::sobel::sobel_gradient;
+
+fn apply_glowing_edges(p_image: &mut Image, p_width: u32, p_brightness: f32) {
+  let (width, height) = p_image.dimensions::<usize>();
+  let src = p_image.rgba();
+  let at = |x: usize, y: usize, dx: isize, dy: isize, c: usize| {
+    let sx = (x as isize + dx).clamp(0, width as isize - 1) as usize;
+    let sy = (y as isize + dy).clamp(0, height as isize - 1) as usize;
+    src[(sy * width + sx) * 4 + c] as f32
+  };
+
+  // Sobel edge strength of each color channel, so the lines keep the colors of the edges they trace.
+  let mut edges = vec![0.0f32; width * height * 3];
+  edges.par_chunks_mut(width * 3).enumerate().for_each(|(y, row)| {
+    for (x, pixel) in row.chunks_exact_mut(3).enumerate() {
+      for (c, value) in pixel.iter_mut().enumerate() {
+        let (gx, gy) = sobel_gradient(|dx, dy| at(x, y, dx, dy, c));
+        // A full black to white step gives 4 * 255 in each direction.
+        *value = gx.hypot(gy) / 4.0;
+      }
+    }
+  });
+
+  // Widen the lines by taking the strongest edge within the radius.
+  let reach = p_width.saturating_sub(1) as isize;
+  let mut out = vec![0u8; width * height * 4];
+  out.par_chunks_mut(width * 4).enumerate().for_each(|(y, row)| {
+    for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+      let mut strongest = [0.0f32; 3];
+      for dy in -reach..=reach {
+        let sy = y as isize + dy;
+        if sy < 0 || sy >= height as isize {
+          continue;
+        }
+        for dx in -reach..=reach {
+          let sx = x as isize + dx;
+          if sx < 0 || sx >= width as isize {
+            continue;
+          }
+          let i = (sy as usize * width + sx as usize) * 3;
+          for (c, value) in strongest.iter_mut().enumerate() {
+            *value = value.max(edges[i + c]);
+          }
+        }
+      }
+      for (channel, value) in pixel.iter_mut().zip(strongest) {
+        *channel = (value * p_brightness).round().clamp(0.0, 255.0) as u8;
+      }
+      pixel[3] = src[(y * width + x) * 4 + 3];
+    }
+  });
+  p_image.set_rgba_owned(out);
+}
+
+/// Traces the edges of the image as glowing neon lines on black, in the colors of the edges they follow.
+/// - `p_image`: The image to apply the filter to.
+/// - `p_width`: The width of the lines in pixels, at least `1`.
+/// - `p_brightness`: How bright the lines are, from `0.0` to `10.0`. At `1.0` a black to white edge becomes white.
+/// - `p_apply_options`: Options to specify for the filter.
+pub fn glowing_edges<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_width: u32, p_brightness: f32, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let width = p_width.max(1);
+  let brightness = p_brightness.clamp(0.0, 10.0);
+  apply_filter!(apply_glowing_edges, image, p_apply_options, width as i32, width, brightness);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::{Area, Color};
+
+  /// A black left half and an orange right half.
+  fn split() -> Image {
+    let mut image = Image::new_from_color(20, 10, Color::from_rgba(0, 0, 0, 255));
+    for y in 0..10 {
+      for x in 10..20 {
+        image.set_pixel(x, y, (255, 128, 0, 255));
+      }
+    }
+    image
+  }
+
+  #[test]
+  fn edges_become_colored_lines_on_black() {
+    let mut image = split();
+    glowing_edges(&mut image, 1, 1.0, None);
+    let (r, g, b, a) = image.get_pixel(10, 5).unwrap();
+    assert!(r > 150 && g > 60 && g < r && b == 0 && a == 255, "{} {} {}", r, g, b);
+    assert_eq!(image.get_pixel(3, 5), Some((0, 0, 0, 255)));
+    assert_eq!(image.get_pixel(16, 5), Some((0, 0, 0, 255)));
+    // Only the two columns on either side of the edge light up.
+    assert_eq!(image.get_pixel(12, 5), Some((0, 0, 0, 255)));
   }
 
-  // Step 4: Adjust edge brightness
-  let pixels = clone.empty_pixel_vec();
-  // pixels.par_chunks_mut(4).enumerate().for_each(|(i, pixel)| {
-  //   let edge_pixel_r = clone.r[i] as f32;
-  //   let edge_pixel_g = clone.g[i] as f32;
-  //   let edge_pixel_b = clone.b[i] as f32;
-
-  //   let brightness_r = edge_pixel_r * edge_brightness as f32;
-  //   let brightness_g = edge_pixel_g * edge_brightness as f32;
-  //   let brightness_b = edge_pixel_b * edge_brightness as f32;
-
-  //   pixel[0] = brightness_r.min(255.0) as u8;
-  //   pixel[1] = brightness_g.min(255.0) as u8;
-  //   pixel[2] = brightness_b.min(255.0) as u8;
-  //   pixel[3] = 255;
-  // });
-  clone.set_rgba_owned(pixels);
-
-  // Step 5: Apply Gaussian blur to smooth the edges
-  blur(&mut clone, None);
-
-  // image.set_pixels(clone.rgba().to_vec());
-
-  // Step 6: Combine the edges with the original image
-  // for (original_pixel, edge_pixel) in clone.r.par_iter_mut().zip(image.r.iter_mut()) {
-  //   let blended_pixel = (*original_pixel as f32 * 0.5 + *edge_pixel as f32 * 0.5).min(255.0) as u8;
-  //   *original_pixel = blended_pixel;
-  // }
-
-  // let mut pixels = image.empty_pixel_vec();
-  // pixels.par_chunks_mut(4).enumerate().for_each(|(i, pixel)| {
-  //   let original_pixel = image.r[i] as f32;
-  //   let blended_pixel = (original_pixel as f32 * 0.5 + pixel[0] as f32 * 0.5).min(255.0) as u8;
-  //   pixel[0] = blended_pixel;
-  //   pixel[1] = blended_pixel;
-  //   pixel[2] = blended_pixel;
-  // });
-
-  // image.copy_channel_data(&clone);
+  #[test]
+  fn width_and_brightness_shape_the_lines() {
+    let mut wide = split();
+    glowing_edges(&mut wide, 3, 1.0, None);
+    assert!(wide.get_pixel(12, 5).unwrap().0 > 150);
+    assert_eq!(wide.get_pixel(14, 5), Some((0, 0, 0, 255)));
+
+    let mut dim = split();
+    glowing_edges(&mut dim, 1, 0.25, None);
+    let mut bright = split();
+    glowing_edges(&mut bright, 1, 1.0, None);
+    assert!(dim.get_pixel(10, 5).unwrap().0 < bright.get_pixel(10, 5).unwrap().0 / 2 + 2);
+  }
+
+  #[test]
+  fn respects_area() {
+    let mut image = split();
+    glowing_edges(&mut image, 1, 1.0, ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (20.0, 5.0))));
+    assert!(image.get_pixel(10, 2).unwrap().0 > 150);
+    assert_eq!(image.get_pixel(16, 8), Some((255, 128, 0, 255)));
+  }
 }
@@ -3,10 +3,12 @@
 pub mod blur;
 pub mod distort;
 pub mod edges;
+pub mod morphology;
 pub mod noise;
 pub mod sharpen;
 pub mod smooth;
 pub mod sobel;
+pub mod stylize;
 
 mod kernel;
 
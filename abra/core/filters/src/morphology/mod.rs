@@ -0,0 +1,3 @@
+mod operations;
+
+pub use operations::{StructuringElement, close, dilate, erode, open};
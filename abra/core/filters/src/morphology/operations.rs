@@ -0,0 +1,233 @@
+use crate::common::*;
+
+/// The neighborhood a morphological operation looks at around each pixel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StructuringElement {
+  /// Every pixel within the radius horizontally and vertically.
+  Square,
+  /// The pixels in the same row or column within the radius.
+  Cross,
+  /// Every pixel within the radius.
+  Disk,
+}
+
+impl StructuringElement {
+  /// How far the element reaches to each side on every row from `-p_radius` to `p_radius`.
+  fn half_widths(&self, p_radius: u32) -> Vec<usize> {
+    let radius = p_radius as i64;
+    (-radius..=radius)
+      .map(|dy| match self {
+        StructuringElement::Square => p_radius as usize,
+        StructuringElement::Cross => {
+          if dy == 0 {
+            p_radius as usize
+          } else {
+            0
+          }
+        }
+        StructuringElement::Disk => (((radius * radius - dy * dy) as f64).sqrt() + 1e-6).floor() as usize,
+      })
+      .collect()
+  }
+}
+
+/// Replaces every color channel with the largest or smallest value under the structuring element.
+/// Pixels past the edges repeat the edge pixel, and alpha is left unchanged.
+/// - `p_image`: The image to filter.
+/// - `p_radius`: The radius of the structuring element.
+/// - `p_shape`: The shape of the structuring element.
+/// - `p_maximum`: Whether the largest value is taken (dilation) or the smallest (erosion).
+fn apply_morphology(p_image: &mut Image, p_radius: u32, p_shape: StructuringElement, p_maximum: bool) {
+  let (width, height) = p_image.dimensions::<usize>();
+  if p_radius == 0 || width == 0 || height == 0 {
+    return;
+  }
+  let pick = |a: u8, b: u8| if p_maximum { a.max(b) } else { a.min(b) };
+  let src = p_image.rgba();
+  let radius = p_radius as usize;
+
+  // A square is separable: after a horizontal pass only the column under each pixel is left to check.
+  let half_widths = p_shape.half_widths(p_radius);
+  let (rows, half_widths) = if p_shape == StructuringElement::Square {
+    (horizontal_pass(src, width, radius, pick), vec![0; half_widths.len()])
+  } else {
+    (src.to_vec(), half_widths)
+  };
+
+  let mut out = src.to_vec();
+  out.par_chunks_mut(width * 4).enumerate().for_each(|(y, row)| {
+    for x in 0..width {
+      let index = (y * width + x) * 4;
+      let mut value = [rows[index], rows[index + 1], rows[index + 2]];
+      for (i, &half_width) in half_widths.iter().enumerate() {
+        let sy = (y + i).saturating_sub(radius).min(height - 1);
+        let (start, end) = (x.saturating_sub(half_width), (x + half_width).min(width - 1));
+        for sx in start..=end {
+          let index = (sy * width + sx) * 4;
+          for c in 0..3 {
+            value[c] = pick(value[c], rows[index + c]);
+          }
+        }
+      }
+      row[x * 4..x * 4 + 3].copy_from_slice(&value);
+    }
+  });
+  p_image.set_rgba_owned(out);
+}
+
+/// Takes the extreme of every color channel over a horizontal line of `2 * p_radius + 1` pixels.
+fn horizontal_pass(p_src: &[u8], p_width: usize, p_radius: usize, p_pick: impl Fn(u8, u8) -> u8 + Sync) -> Vec<u8> {
+  let mut out = p_src.to_vec();
+  out.par_chunks_mut(p_width * 4).enumerate().for_each(|(y, row)| {
+    let source = &p_src[y * p_width * 4..(y + 1) * p_width * 4];
+    for x in 0..p_width {
+      for sx in x.saturating_sub(p_radius)..=(x + p_radius).min(p_width - 1) {
+        for c in 0..3 {
+          row[x * 4 + c] = p_pick(row[x * 4 + c], source[sx * 4 + c]);
+        }
+      }
+    }
+  });
+  out
+}
+
+/// Dilates the image: every color channel takes the largest value under the structuring element, so bright
+/// areas grow and dark details shrink. This is also known as a maximum filter. Alpha is left unchanged.
+/// - `p_image`: The image to filter.
+/// - `p_radius`: The radius of the structuring element in pixels.
+/// - `p_shape`: The shape of the structuring element.
+/// - `p_apply_options`: Options for applying the filter.
+pub fn dilate<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_shape: StructuringElement, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_filter!(apply_morphology, image, p_apply_options, p_radius as i32, p_radius, p_shape, true);
+}
+
+/// Erodes the image: every color channel takes the smallest value under the structuring element, so dark
+/// areas grow and bright details shrink. This is also known as a minimum filter. Alpha is left unchanged.
+/// - `p_image`: The image to filter.
+/// - `p_radius`: The radius of the structuring element in pixels.
+/// - `p_shape`: The shape of the structuring element.
+/// - `p_apply_options`: Options for applying the filter.
+pub fn erode<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_shape: StructuringElement, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_filter!(apply_morphology, image, p_apply_options, p_radius as i32, p_radius, p_shape, false);
+}
+
+/// Erodes and then dilates the image, which removes bright specks smaller than the structuring element while
+/// keeping the size of larger bright areas.
+fn apply_open(p_image: &mut Image, p_radius: u32, p_shape: StructuringElement) {
+  apply_morphology(p_image, p_radius, p_shape, false);
+  apply_morphology(p_image, p_radius, p_shape, true);
+}
+
+/// Dilates and then erodes the image, which fills dark holes and gaps smaller than the structuring element while
+/// keeping the size of larger dark areas.
+fn apply_close(p_image: &mut Image, p_radius: u32, p_shape: StructuringElement) {
+  apply_morphology(p_image, p_radius, p_shape, true);
+  apply_morphology(p_image, p_radius, p_shape, false);
+}
+
+/// Opens the image (an erosion followed by a dilation), removing bright specks smaller than the structuring
+/// element while keeping the size of larger bright areas. Alpha is left unchanged.
+/// - `p_image`: The image to filter.
+/// - `p_radius`: The radius of the structuring element in pixels.
+/// - `p_shape`: The shape of the structuring element.
+/// - `p_apply_options`: Options for applying the filter.
+pub fn open<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_shape: StructuringElement, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_filter!(apply_open, image, p_apply_options, 2 * p_radius as i32, p_radius, p_shape);
+}
+
+/// Closes the image (a dilation followed by an erosion), filling dark holes and gaps smaller than the
+/// structuring element while keeping the size of larger dark areas. Alpha is left unchanged.
+/// - `p_image`: The image to filter.
+/// - `p_radius`: The radius of the structuring element in pixels.
+/// - `p_shape`: The shape of the structuring element.
+/// - `p_apply_options`: Options for applying the filter.
+pub fn close<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_shape: StructuringElement, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_filter!(apply_close, image, p_apply_options, 2 * p_radius as i32, p_radius, p_shape);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::Color;
+
+  /// A white square covering `p_start..p_end` on both axes of a black 20x20 image.
+  fn square_image(p_start: u32, p_end: u32) -> Image {
+    let mut image = Image::new_from_color(20, 20, Color::from_rgba(0, 0, 0, 255));
+    for y in p_start..p_end {
+      for x in p_start..p_end {
+        image.set_pixel(x, y, (255, 255, 255, 255));
+      }
+    }
+    image
+  }
+
+  fn white_pixels(p_image: &Image) -> Vec<(u32, u32)> {
+    let mut pixels = vec![];
+    for y in 0..20 {
+      for x in 0..20 {
+        if p_image.get_pixel(x, y).unwrap().0 == 255 {
+          pixels.push((x, y));
+        }
+      }
+    }
+    pixels
+  }
+
+  #[test]
+  fn erode_shrinks_a_square_by_the_radius() {
+    for shape in [
+      StructuringElement::Square,
+      StructuringElement::Cross,
+      StructuringElement::Disk,
+    ] {
+      let mut image = square_image(5, 15);
+      erode(&mut image, 2, shape, None);
+      assert_eq!(white_pixels(&image), white_pixels(&square_image(7, 13)), "{:?}", shape);
+    }
+  }
+
+  #[test]
+  fn dilate_grows_by_the_shape() {
+    let mut image = square_image(10, 11);
+    dilate(&mut image, 2, StructuringElement::Square, None);
+    assert_eq!(white_pixels(&image), white_pixels(&square_image(8, 13)));
+
+    let mut image = square_image(10, 11);
+    dilate(&mut image, 2, StructuringElement::Cross, None);
+    assert_eq!(white_pixels(&image).len(), 9);
+
+    // A disk of radius 2 covers 13 pixels: the 3x3 block and the four tips.
+    let mut image = square_image(10, 11);
+    dilate(&mut image, 2, StructuringElement::Disk, None);
+    assert_eq!(white_pixels(&image).len(), 13);
+  }
+
+  #[test]
+  fn open_removes_specks_and_close_fills_holes() {
+    let mut image = square_image(5, 15);
+    image.set_pixel(1, 1, (255, 255, 255, 255));
+    open(&mut image, 1, StructuringElement::Square, None);
+    assert_eq!(white_pixels(&image), white_pixels(&square_image(5, 15)));
+
+    let mut image = square_image(5, 15);
+    image.set_pixel(9, 9, (0, 0, 0, 255));
+    close(&mut image, 1, StructuringElement::Square, None);
+    assert_eq!(white_pixels(&image), white_pixels(&square_image(5, 15)));
+  }
+}
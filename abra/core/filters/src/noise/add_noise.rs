@@ -9,7 +9,7 @@ pub enum NoiseDistribution {
   Gaussian,
 }
 
-fn hash3(u: u32, v: u32, w: u32) -> u32 {
+pub(super) fn hash3(u: u32, v: u32, w: u32) -> u32 {
   // A simple integer hash (Thomas Wang mix)
   let mut x = u.wrapping_mul(374761393) ^ v.wrapping_mul(668265263) ^ w.wrapping_mul(2246822519);
   x ^= x >> 13;
@@ -17,47 +17,103 @@ fn hash3(u: u32, v: u32, w: u32) -> u32 {
   x ^ (x >> 16)
 }
 
-fn rand01(seed: u32) -> f32 {
+pub(super) fn rand01(seed: u32) -> f32 {
   (seed as f32) / (u32::MAX as f32)
 }
 
-fn gaussian_from_uniform(u1: f32, u2: f32) -> f32 {
+pub(super) fn gaussian_from_uniform(u1: f32, u2: f32) -> f32 {
   // Standard normal via Box-Muller (one sample)
   let r = (-2.0 * u1.max(1e-7).ln()).sqrt();
   let theta = 2.0 * std::f32::consts::PI * u2;
   r * theta.cos()
 }
 
-fn apply_add_noise(p_image: &mut Image, amount: f32, distribution: NoiseDistribution) {
+/// Folds a 64-bit seed into the 32 bits used by the hash.
+pub(super) fn fold_seed(p_seed: u64) -> u32 {
+  (p_seed as u32) ^ ((p_seed >> 32) as u32)
+}
+
+fn apply_add_noise(p_image: &mut Image, amount: f32, distribution: NoiseDistribution, monochromatic: bool, seed: u64) {
   let src = p_image.rgba();
   let (width, height) = p_image.dimensions::<usize>();
   let mut out = vec![0u8; width * height * 4];
+  let seed = fold_seed(seed);
 
   out.par_chunks_mut(4).enumerate().for_each(|(idx, dst_px)| {
     let x = (idx % width) as u32;
     let y = (idx / width) as u32;
-    let seed1 = hash3(x, y, 0);
-    let seed2 = hash3(x ^ 0x9E3779B9, y ^ 0x85EBCA6B, 0 ^ 0xC2B2AE35);
-    let n = match distribution {
-      NoiseDistribution::Uniform => (rand01(seed1) * 2.0 - 1.0) * amount,
-      NoiseDistribution::Gaussian => gaussian_from_uniform(rand01(seed1), rand01(seed2)) * amount,
+    let sample = |channel: u32| {
+      // Channel 0 keeps the original single-channel sequence so monochromatic noise is unchanged.
+      let w = seed.wrapping_add(channel.wrapping_mul(0x27D4EB2F));
+      let seed1 = hash3(x, y, w);
+      let seed2 = hash3(x ^ 0x9E3779B9, y ^ 0x85EBCA6B, w ^ 0xC2B2AE35);
+      let n = match distribution {
+        NoiseDistribution::Uniform => (rand01(seed1) * 2.0 - 1.0) * amount,
+        NoiseDistribution::Gaussian => gaussian_from_uniform(rand01(seed1), rand01(seed2)) * amount,
+      };
+      n * 3.0
     };
-    let noise_value = n * 3.0;
-    dst_px[0] = (src[idx * 4] as f32 + noise_value).clamp(0.0, 255.0) as u8;
-    dst_px[1] = (src[idx * 4 + 1] as f32 + noise_value).clamp(0.0, 255.0) as u8;
-    dst_px[2] = (src[idx * 4 + 2] as f32 + noise_value).clamp(0.0, 255.0) as u8;
+    let mono = sample(0);
+    for channel in 0..3 {
+      let noise_value = if monochromatic { mono } else { sample(channel as u32) };
+      dst_px[channel] = (src[idx * 4 + channel] as f32 + noise_value).clamp(0.0, 255.0) as u8;
+    }
     dst_px[3] = src[idx * 4 + 3];
   });
   p_image.set_rgba_owned(out);
 }
 
-/// Applies a despeckle filter to the image, removing isolated noise pixels while preserving edges.
+/// Adds random noise to the image. The same noise is generated for the same seed.
 /// - `p_image`: The image to apply the filter to.
+/// - `p_amount`: The strength of the noise. Each unit shifts the channels by up to 3 levels (uniform)
+///   or by a standard deviation of 3 levels (Gaussian).
+/// - `p_distribution`: How the noise values are distributed.
+/// - `p_monochromatic`: Whether the same noise is added to all channels, which only changes brightness.
+/// - `p_seed`: The seed of the noise.
 /// - `p_apply_options`: Options to specify for the filter.
-pub fn noise<'a>(
-  p_image: impl Into<ImageRef<'a>>, amount: f32, distribution: NoiseDistribution, p_apply_options: impl Into<Options>,
+pub fn add_noise<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_amount: f32, p_distribution: NoiseDistribution, p_monochromatic: bool,
+  p_seed: u64, p_apply_options: impl Into<Options>,
 ) {
   let mut image_ref: ImageRef = p_image.into();
   let image = &mut image_ref as &mut Image;
-  apply_filter!(apply_add_noise, image, p_apply_options, 1, amount, distribution);
+  apply_filter!(apply_add_noise, image, p_apply_options, 1, p_amount, p_distribution, p_monochromatic, p_seed);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::Area;
+
+  fn gray_image() -> Image {
+    Image::new_from_color(16, 16, abra_core::Color::from_rgb(128, 128, 128))
+  }
+
+  fn whole_image() -> ApplyOptions {
+    ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (16.0, 16.0)))
+  }
+
+  #[test]
+  fn seed_makes_noise_reproducible() {
+    let mut a = gray_image();
+    let mut b = gray_image();
+    let mut c = gray_image();
+    add_noise(&mut a, 5.0, NoiseDistribution::Gaussian, false, 42, whole_image());
+    add_noise(&mut b, 5.0, NoiseDistribution::Gaussian, false, 42, whole_image());
+    add_noise(&mut c, 5.0, NoiseDistribution::Gaussian, false, 43, whole_image());
+    assert_eq!(a.rgba(), b.rgba());
+    assert_ne!(a.rgba(), c.rgba());
+    assert_ne!(a.rgba(), gray_image().rgba());
+  }
+
+  #[test]
+  fn monochromatic_noise_keeps_gray() {
+    let mut mono = gray_image();
+    add_noise(&mut mono, 5.0, NoiseDistribution::Uniform, true, 7, whole_image());
+    assert!(mono.rgba().chunks(4).all(|px| px[0] == px[1] && px[1] == px[2]));
+
+    let mut color = gray_image();
+    add_noise(&mut color, 5.0, NoiseDistribution::Uniform, false, 7, whole_image());
+    assert!(color.rgba().chunks(4).any(|px| px[0] != px[1] || px[1] != px[2]));
+  }
 }
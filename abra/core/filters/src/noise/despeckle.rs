@@ -1,115 +1,22 @@
 use crate::common::*;
 
-use abra_core::color::Histogram;
+use super::dust_and_scratches::apply_selective_median;
 
 fn apply_despeckle(p_image: &mut Image, p_radius: f32, p_threshold: f32) {
-  let (width, height) = p_image.dimensions::<u32>();
-  if width == 0 || height == 0 {
-    return;
-  }
-
-  let src = p_image.rgba();
-  let mut out = p_image.empty_pixel_vec();
-  let w = width as usize;
-  let h = height as usize;
-
   // Convert radius to integer (clamp to reasonable range 1-30)
-  let r = p_radius.clamp(1.0, 30.0).round() as i32;
+  let r = p_radius.clamp(1.0, 30.0).round() as u32;
   // Convert threshold from 0-255 range to integer
-  let thr = p_threshold.clamp(0.0, 255.0).round() as i32;
-
-  out.par_chunks_mut(w * 4).enumerate().for_each(|(y, row_out)| {
-    // Allocate histogram once per row - uses Box<[u64; 256]> internally
-    let mut hist = Histogram::new();
-
-    for x in 0..w {
-      let idx = (y * w + x) * 4;
-
-      // Use unsafe for direct access - bounds already checked by dimensions
-      let (cr, cg, cb, ca) = unsafe {
-        (
-          *src.get_unchecked(idx) as i32,
-          *src.get_unchecked(idx + 1) as i32,
-          *src.get_unchecked(idx + 2) as i32,
-          *src.get_unchecked(idx + 3),
-        )
-      };
-
-      // Clear and get mutable access to histogram arrays
-      hist.clear();
-      let (r_hist, g_hist, b_hist) = hist.rgb_mut();
-
-      let mut min_lum = i32::MAX;
-      let mut max_lum = i32::MIN;
-      let mut pixel_count = 0u64;
-
-      // Iterate over kernel neighborhood and build histograms
-      for dy in -r..=r {
-        let ny = ((y as i32 + dy).max(0).min(h as i32 - 1)) as usize;
-        let ny_offset = ny * w * 4;
-
-        for dx in -r..=r {
-          let nx = ((x as i32 + dx).max(0).min(w as i32 - 1)) as usize;
-          let n_idx = ny_offset + nx * 4;
-
-          // Use unsafe for faster access
-          let (nr, ng, nb) =
-            unsafe { (*src.get_unchecked(n_idx), *src.get_unchecked(n_idx + 1), *src.get_unchecked(n_idx + 2)) };
-
-          r_hist[nr as usize] += 1;
-          g_hist[ng as usize] += 1;
-          b_hist[nb as usize] += 1;
-          pixel_count += 1;
-
-          // Compute luminance using integer approximation
-          let lum = (299 * (nr as i32) + 587 * (ng as i32) + 114 * (nb as i32)) / 1000;
-          min_lum = min_lum.min(lum);
-          max_lum = max_lum.max(lum);
-        }
-      }
-
-      // Current pixel luminance
-      let center_lum = (299 * cr + 587 * cg + 114 * cb) / 1000;
-
-      // Compute median using histogram (much faster than sorting!)
-      let mr = hist.red_median(pixel_count) as i32;
-      let mg = hist.green_median(pixel_count) as i32;
-      let mb = hist.blue_median(pixel_count) as i32;
-
-      // If pixel is an outlier (local min or local max), replace with median
-      let mut out_r = cr as u8;
-      let mut out_g = cg as u8;
-      let mut out_b = cb as u8;
-
-      if center_lum == min_lum || center_lum == max_lum {
-        out_r = mr as u8;
-        out_g = mg as u8;
-        out_b = mb as u8;
-      } else {
-        // Selective median: if the center differs enough from median, replace
-        let d_r = (cr - mr).abs();
-        let d_g = (cg - mg).abs();
-        let d_b = (cb - mb).abs();
-        let diff = d_r.max(d_g).max(d_b);
-        if diff > thr {
-          out_r = mr as u8;
-          out_g = mg as u8;
-          out_b = mb as u8;
-        }
-      }
-
-      let out_idx = x * 4;
-      row_out[out_idx] = out_r;
-      row_out[out_idx + 1] = out_g;
-      row_out[out_idx + 2] = out_b;
-      row_out[out_idx + 3] = ca;
-    }
-  });
-
-  p_image.set_rgba_owned(out);
+  let thr = p_threshold.clamp(0.0, 255.0).round() as u8;
+  // Unlike dust and scratches, local extremes are always treated as specks.
+  apply_selective_median(p_image, r, thr, true);
 }
+
 /// Applies a despeckle filter to the image, removing isolated noise pixels while preserving edges.
+/// Pixels that are the darkest or brightest in their neighborhood are always replaced by the median; use
+/// `dust_and_scratches` to only replace pixels that differ from their surroundings by more than the threshold.
 /// - `p_image`: The image to apply the filter to.
+/// - `p_radius`: The radius of the neighborhood (1-30).
+/// - `p_threshold`: How much a pixel must differ from the median (0-255) before it is replaced.
 /// - `p_apply_options`: Options to specify for the filter.
 pub fn despeckle<'a>(
   p_image: impl Into<ImageRef<'a>>, p_radius: f32, p_threshold: f32, p_apply_options: impl Into<Options>,
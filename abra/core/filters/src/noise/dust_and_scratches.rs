@@ -0,0 +1,183 @@
+use crate::common::*;
+
+use abra_core::color::Histogram;
+
+/// Replaces pixels with the median of their neighborhood when they stand out from it.
+/// - `p_image`: The image to filter.
+/// - `p_radius`: The radius of the square neighborhood.
+/// - `p_threshold`: How much a channel must differ from the median before the pixel is replaced.
+/// - `p_replace_extremes`: Whether pixels that are the darkest or brightest in their neighborhood are always replaced.
+pub(crate) fn apply_selective_median(p_image: &mut Image, p_radius: u32, p_threshold: u8, p_replace_extremes: bool) {
+  let (width, height) = p_image.dimensions::<u32>();
+  if width == 0 || height == 0 {
+    return;
+  }
+
+  let src = p_image.rgba();
+  let mut out = p_image.empty_pixel_vec();
+  let w = width as usize;
+  let h = height as usize;
+
+  let r = p_radius as i32;
+  let thr = p_threshold as i32;
+
+  out.par_chunks_mut(w * 4).enumerate().for_each(|(y, row_out)| {
+    // Allocate histogram once per row - uses Box<[u64; 256]> internally
+    let mut hist = Histogram::new();
+
+    for x in 0..w {
+      let idx = (y * w + x) * 4;
+
+      // Use unsafe for direct access - bounds already checked by dimensions
+      let (cr, cg, cb, ca) = unsafe {
+        (
+          *src.get_unchecked(idx) as i32,
+          *src.get_unchecked(idx + 1) as i32,
+          *src.get_unchecked(idx + 2) as i32,
+          *src.get_unchecked(idx + 3),
+        )
+      };
+
+      // Clear and get mutable access to histogram arrays
+      hist.clear();
+      let (r_hist, g_hist, b_hist) = hist.rgb_mut();
+
+      let mut min_lum = i32::MAX;
+      let mut max_lum = i32::MIN;
+      let mut pixel_count = 0u64;
+
+      // Iterate over kernel neighborhood and build histograms
+      for dy in -r..=r {
+        let ny = ((y as i32 + dy).max(0).min(h as i32 - 1)) as usize;
+        let ny_offset = ny * w * 4;
+
+        for dx in -r..=r {
+          let nx = ((x as i32 + dx).max(0).min(w as i32 - 1)) as usize;
+          let n_idx = ny_offset + nx * 4;
+
+          // Use unsafe for faster access
+          let (nr, ng, nb) =
+            unsafe { (*src.get_unchecked(n_idx), *src.get_unchecked(n_idx + 1), *src.get_unchecked(n_idx + 2)) };
+
+          r_hist[nr as usize] += 1;
+          g_hist[ng as usize] += 1;
+          b_hist[nb as usize] += 1;
+          pixel_count += 1;
+
+          // Compute luminance using integer approximation
+          let lum = (299 * (nr as i32) + 587 * (ng as i32) + 114 * (nb as i32)) / 1000;
+          min_lum = min_lum.min(lum);
+          max_lum = max_lum.max(lum);
+        }
+      }
+
+      // Current pixel luminance
+      let center_lum = (299 * cr + 587 * cg + 114 * cb) / 1000;
+
+      // Compute median using histogram (much faster than sorting!)
+      let mr = hist.red_median(pixel_count) as i32;
+      let mg = hist.green_median(pixel_count) as i32;
+      let mb = hist.blue_median(pixel_count) as i32;
+
+      // If pixel is an outlier (local min or local max), replace with median
+      let mut out_r = cr as u8;
+      let mut out_g = cg as u8;
+      let mut out_b = cb as u8;
+
+      if p_replace_extremes && (center_lum == min_lum || center_lum == max_lum) {
+        out_r = mr as u8;
+        out_g = mg as u8;
+        out_b = mb as u8;
+      } else {
+        // Selective median: if the center differs enough from median, replace
+        let d_r = (cr - mr).abs();
+        let d_g = (cg - mg).abs();
+        let d_b = (cb - mb).abs();
+        let diff = d_r.max(d_g).max(d_b);
+        if diff > thr {
+          out_r = mr as u8;
+          out_g = mg as u8;
+          out_b = mb as u8;
+        }
+      }
+
+      let out_idx = x * 4;
+      row_out[out_idx] = out_r;
+      row_out[out_idx + 1] = out_g;
+      row_out[out_idx + 2] = out_b;
+      row_out[out_idx + 3] = ca;
+    }
+  });
+
+  p_image.set_rgba_owned(out);
+}
+fn apply_dust_and_scratches(p_image: &mut Image, p_radius: u32, p_threshold: u8) {
+  if p_radius == 0 {
+    return;
+  }
+  apply_selective_median(p_image, p_radius, p_threshold, false);
+}
+
+/// Removes dust, scratches and other small defects by blurring the image with a median filter and only keeping
+/// the blurred value where a pixel differs from it by more than a threshold. Detail that is close to its
+/// surroundings is left untouched.
+/// - `p_image`: The image to apply the filter to.
+/// - `p_radius`: How far around each pixel to look for a replacement, in pixels. Defects should fit in this radius.
+/// - `p_threshold`: How much a pixel must differ from its surroundings (0-255) before it is replaced. 0 replaces every pixel.
+/// - `p_apply_options`: Options to specify for the filter.
+pub fn dust_and_scratches<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_threshold: u8, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_filter!(apply_dust_and_scratches, image, p_apply_options, p_radius as i32, p_radius, p_threshold);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::{Area, Image};
+  use options::ApplyOptions;
+
+  fn whole_image() -> ApplyOptions {
+    ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (9.0, 9.0)))
+  }
+
+  fn gradient_image() -> Image {
+    let mut img = Image::new(9, 9);
+    for y in 0..9u32 {
+      for x in 0..9u32 {
+        let value = (x * 8) as u8 + 64;
+        img.set_pixel(x, y, (value, value, value, 255));
+      }
+    }
+    img
+  }
+
+  #[test]
+  fn removes_speck_and_keeps_gradient() {
+    let original = gradient_image();
+    let mut img = original.clone();
+    img.set_pixel(4, 4, (255u8, 255u8, 255u8, 255));
+
+    dust_and_scratches(&mut img, 1, 20, whole_image());
+
+    assert_eq!(img.get_pixel(4, 4), original.get_pixel(4, 4));
+    // The gradient differs from its median by less than the threshold everywhere else, so it is untouched.
+    for (x, y) in [(0, 0), (3, 4), (8, 8)] {
+      assert_eq!(img.get_pixel(x, y), original.get_pixel(x, y));
+    }
+  }
+
+  #[test]
+  fn threshold_keeps_faint_detail() {
+    let mut img = gradient_image();
+    img.set_pixel(4, 4, (110u8, 110u8, 110u8, 255));
+
+    dust_and_scratches(&mut img, 1, 20, whole_image());
+    assert_eq!(img.get_pixel(4, 4), Some((110, 110, 110, 255)));
+
+    dust_and_scratches(&mut img, 1, 5, whole_image());
+    assert_eq!(img.get_pixel(4, 4), Some((96, 96, 96, 255)));
+  }
+}
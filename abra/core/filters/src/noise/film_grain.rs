@@ -0,0 +1,105 @@
+use crate::common::*;
+
+use super::add_noise::{gaussian_from_uniform, hash3, rand01};
+
+/// The seed of the grain pattern, so the same image always gets the same grain.
+const GRAIN_SEED: u32 = 0x5EED_6A1E;
+
+/// Returns a Gaussian value for a point of the grain lattice.
+fn lattice(p_x: i32, p_y: i32, p_octave: u32) -> f32 {
+  let w = GRAIN_SEED.wrapping_add(p_octave.wrapping_mul(0x9E3779B9));
+  let u1 = rand01(hash3(p_x as u32, p_y as u32, w));
+  let u2 = rand01(hash3(p_x as u32 ^ 0x85EBCA6B, p_y as u32 ^ 0xC2B2AE35, w));
+  gaussian_from_uniform(u1, u2)
+}
+
+/// Samples smoothly interpolated lattice noise, which gives clumps of about `p_size` pixels instead of single-pixel noise.
+fn value_noise(p_x: f32, p_y: f32, p_size: f32, p_octave: u32) -> f32 {
+  let (fx, fy) = (p_x / p_size, p_y / p_size);
+  let (x0, y0) = (fx.floor(), fy.floor());
+  let smooth = |p_t: f32| p_t * p_t * (3.0 - 2.0 * p_t);
+  let (tx, ty) = (smooth(fx - x0), smooth(fy - y0));
+  let (x0, y0) = (x0 as i32, y0 as i32);
+  let top = lattice(x0, y0, p_octave) * (1.0 - tx) + lattice(x0 + 1, y0, p_octave) * tx;
+  let bottom = lattice(x0, y0 + 1, p_octave) * (1.0 - tx) + lattice(x0 + 1, y0 + 1, p_octave) * tx;
+  top * (1.0 - ty) + bottom * ty
+}
+
+fn apply_film_grain(p_image: &mut Image, p_intensity: f32, p_grain_size: f32) {
+  let src = p_image.rgba();
+  let (width, _) = p_image.dimensions::<usize>();
+  let mut out = p_image.empty_pixel_vec();
+  let size = p_grain_size.max(0.5);
+  // Scales the intensity to a standard deviation in levels; 1.0 is very heavy grain.
+  let strength = p_intensity.max(0.0) * 64.0;
+
+  out.par_chunks_mut(4).enumerate().for_each(|(idx, dst_px)| {
+    let (x, y) = ((idx % width) as f32, (idx / width) as f32);
+    // A coarse and a fine octave make the clumps irregular, like silver halide crystals.
+    let grain = value_noise(x, y, size, 0) + 0.5 * value_noise(x, y, size * 0.5, 1);
+    let (r, g, b) = (src[idx * 4] as f32, src[idx * 4 + 1] as f32, src[idx * 4 + 2] as f32);
+    let luma = (0.299 * r + 0.587 * g + 0.114 * b) / 255.0;
+    // Grain is most visible in the midtones and fades out in deep shadows and bright highlights.
+    let response = 4.0 * luma * (1.0 - luma);
+    let offset = grain * strength * response;
+    dst_px[0] = (r + offset).round().clamp(0.0, 255.0) as u8;
+    dst_px[1] = (g + offset).round().clamp(0.0, 255.0) as u8;
+    dst_px[2] = (b + offset).round().clamp(0.0, 255.0) as u8;
+    dst_px[3] = src[idx * 4 + 3];
+  });
+  p_image.set_rgba_owned(out);
+}
+
+/// Adds organic, photographic film grain to the image. The grain is strongest in the midtones and fades out
+/// in the shadows and highlights. The same image always gets the same grain.
+/// - `p_image`: The image to apply the filter to.
+/// - `p_intensity`: The strength of the grain between 0 and 1.
+/// - `p_grain_size`: The approximate size of a grain in pixels.
+/// - `p_apply_options`: Options to specify for the filter.
+pub fn film_grain<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_intensity: f32, p_grain_size: f32, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_filter!(apply_film_grain, image, p_apply_options, 1, p_intensity, p_grain_size);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::{Area, Color};
+
+  fn whole_image() -> ApplyOptions {
+    ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (32.0, 32.0)))
+  }
+
+  fn mean_change(p_gray: u8) -> f32 {
+    let original = Image::new_from_color(32, 32, Color::from_rgb(p_gray, p_gray, p_gray));
+    let mut img = original.clone();
+    film_grain(&mut img, 0.5, 2.0, whole_image());
+    let total: u32 = img
+      .rgba()
+      .iter()
+      .zip(original.rgba())
+      .map(|(a, b)| a.abs_diff(*b) as u32)
+      .sum();
+    total as f32 / (32.0 * 32.0 * 4.0)
+  }
+
+  #[test]
+  fn grain_is_strongest_in_midtones() {
+    let midtones = mean_change(128);
+    assert!(midtones > 5.0, "midtone change was {}", midtones);
+    assert!(mean_change(10) < midtones / 2.0);
+    assert!(mean_change(245) < midtones / 2.0);
+  }
+
+  #[test]
+  fn grain_is_deterministic() {
+    let mut a = Image::new_from_color(32, 32, Color::from_rgb(100, 120, 140));
+    let mut b = a.clone();
+    film_grain(&mut a, 0.3, 1.5, whole_image());
+    film_grain(&mut b, 0.3, 1.5, whole_image());
+    assert_eq!(a.rgba(), b.rgba());
+  }
+}
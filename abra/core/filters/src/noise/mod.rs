@@ -1,7 +1,11 @@
 mod add_noise;
 mod despeckle;
+mod dust_and_scratches;
+mod film_grain;
 mod median;
 
-pub use add_noise::{NoiseDistribution, noise};
+pub use add_noise::{NoiseDistribution, add_noise};
 pub use despeckle::despeckle;
+pub use dust_and_scratches::dust_and_scratches;
+pub use film_grain::film_grain;
 pub use median::median;
@@ -26,3 +26,32 @@ pub fn sobel_vertical<'a>(image: impl Into<ImageRef<'a>>) {
   ];
   apply_kernel(image, &kernel_y);
 }
+
+/// Gets the signed Sobel gradient at a point, positive where the values rise to the right and downward.\
+/// A full step from 0 to `v` across the point gives `4 * v` in that direction.
+/// - `p_sample`: Reads the value at an offset of -1 to 1 on each axis from the point.
+///
+/// NOTE: This is the single 3x3 Sobel gradient of the filters; emboss and glowing edges use it.
+pub fn sobel_gradient(p_sample: impl Fn(isize, isize) -> f32) -> (f32, f32) {
+  let gx = p_sample(1, -1) + 2.0 * p_sample(1, 0) + p_sample(1, 1)
+    - p_sample(-1, -1)
+    - 2.0 * p_sample(-1, 0)
+    - p_sample(-1, 1);
+  let gy = p_sample(-1, 1) + 2.0 * p_sample(0, 1) + p_sample(1, 1)
+    - p_sample(-1, -1)
+    - 2.0 * p_sample(0, -1)
+    - p_sample(1, -1);
+  (gx, gy)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn gradient_points_toward_rising_values() {
+    assert_eq!(sobel_gradient(|dx, _| if dx > 0 { 1.0 } else { 0.0 }), (4.0, 0.0));
+    assert_eq!(sobel_gradient(|_, dy| if dy < 0 { 1.0 } else { 0.0 }), (0.0, -4.0));
+    assert_eq!(sobel_gradient(|_, _| 0.5), (0.0, 0.0));
+  }
+}
@@ -0,0 +1,189 @@
+use crate::common::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The algorithm used to dither an image.
+pub enum DitherMethod {
+  /// Error diffusion that spreads each pixel's quantization error to its four unvisited neighbors.
+  #[default]
+  FloydSteinberg,
+  /// A repeating 8x8 Bayer threshold pattern, giving a regular cross-hatched texture.
+  Ordered,
+  /// Error diffusion that only spreads three quarters of the error, giving higher contrast (classic Macintosh look).
+  Atkinson,
+}
+
+/// The 8x8 Bayer threshold matrix with values from 0 to 63.
+const BAYER_8X8: [[u8; 8]; 8] = [
+  [0, 32, 8, 40, 2, 34, 10, 42],
+  [48, 16, 56, 24, 50, 18, 58, 26],
+  [12, 44, 4, 36, 14, 46, 6, 38],
+  [60, 28, 52, 20, 62, 30, 54, 22],
+  [3, 35, 11, 43, 1, 33, 9, 41],
+  [51, 19, 59, 27, 49, 17, 57, 25],
+  [15, 47, 7, 39, 13, 45, 5, 37],
+  [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// The neighbors (dx, dy) that receive error and the fraction of the error they receive.
+const FLOYD_STEINBERG: [(i32, i32, f32); 4] = [
+  (1, 0, 7.0 / 16.0),
+  (-1, 1, 3.0 / 16.0),
+  (0, 1, 5.0 / 16.0),
+  (1, 1, 1.0 / 16.0),
+];
+const ATKINSON: [(i32, i32, f32); 6] = [
+  (1, 0, 1.0 / 8.0),
+  (2, 0, 1.0 / 8.0),
+  (-1, 1, 1.0 / 8.0),
+  (0, 1, 1.0 / 8.0),
+  (1, 1, 1.0 / 8.0),
+  (0, 2, 1.0 / 8.0),
+];
+
+/// Rounds a channel value to the nearest of the evenly spaced levels.
+fn quantize(p_value: f32, p_step: f32) -> f32 {
+  ((p_value / p_step).round() * p_step).clamp(0.0, 255.0).round()
+}
+
+fn apply_ordered(p_image: &mut Image, p_step: f32) {
+  let (width, _) = p_image.dimensions::<usize>();
+  let mut pixels = p_image.rgba().to_vec();
+  pixels.par_chunks_mut(4).enumerate().for_each(|(i, px)| {
+    let threshold = BAYER_8X8[(i / width) % 8][(i % width) % 8] as f32;
+    // Shifts the value by up to half a step either way before rounding.
+    let offset = ((threshold + 0.5) / 64.0 - 0.5) * p_step;
+    for channel in px.iter_mut().take(3) {
+      *channel = quantize(*channel as f32 + offset, p_step) as u8;
+    }
+  });
+  p_image.set_rgba_owned(pixels);
+}
+
+fn apply_error_diffusion(p_image: &mut Image, p_step: f32, p_kernel: &[(i32, i32, f32)]) {
+  let (width, height) = p_image.dimensions::<usize>();
+  let src = p_image.rgba();
+  let mut values: Vec<f32> = src.iter().map(|value| *value as f32).collect();
+  let mut out = src.to_vec();
+
+  // Error diffusion depends on the pixels before it, so it runs in scanline order.
+  for y in 0..height {
+    for x in 0..width {
+      let idx = (y * width + x) * 4;
+      for channel in 0..3 {
+        let old = values[idx + channel];
+        let new = quantize(old, p_step);
+        out[idx + channel] = new as u8;
+        let error = old - new;
+        for &(dx, dy, weight) in p_kernel {
+          let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+          if nx >= 0 && (nx as usize) < width && (ny as usize) < height {
+            values[(ny as usize * width + nx as usize) * 4 + channel] += error * weight;
+          }
+        }
+      }
+    }
+  }
+  p_image.set_rgba_owned(out);
+}
+
+fn apply_dither(p_image: &mut Image, p_method: DitherMethod, p_levels: u32) {
+  let step = 255.0 / (p_levels.clamp(2, 256) - 1) as f32;
+  match p_method {
+    DitherMethod::FloydSteinberg => apply_error_diffusion(p_image, step, &FLOYD_STEINBERG),
+    DitherMethod::Atkinson => apply_error_diffusion(p_image, step, &ATKINSON),
+    DitherMethod::Ordered => apply_ordered(p_image, step),
+  }
+}
+
+/// Reduces each color channel to a number of evenly spaced levels, using dithering to simulate the
+/// tones in between. Alpha is left unchanged.
+/// - `p_image`: The image to apply the filter to.
+/// - `p_method`: The dithering algorithm.
+/// - `p_levels`: The number of levels per channel, between 2 (1-bit) and 256.
+/// - `p_apply_options`: Options to specify for the filter.
+pub fn dither<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_method: DitherMethod, p_levels: u32, p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_filter!(apply_dither, image, p_apply_options, 0, p_method, p_levels);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::Area;
+
+  /// A horizontal black to white gradient.
+  fn gradient_image() -> Image {
+    let mut image = Image::new(64, 32);
+    for y in 0..32u32 {
+      for x in 0..64u32 {
+        let value = (x * 255 / 63) as u8;
+        image.set_pixel(x, y, (value, value, value, 255));
+      }
+    }
+    image
+  }
+
+  fn dither_gradient(p_method: DitherMethod) -> Image {
+    let mut image = gradient_image();
+    let options = ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (64.0, 32.0)));
+    dither(&mut image, p_method, 2, options);
+    image
+  }
+
+  /// The average brightness of a band of columns.
+  fn band_mean(p_image: &Image, p_from: u32, p_to: u32) -> f32 {
+    let mut total = 0u32;
+    for y in 0..32 {
+      for x in p_from..p_to {
+        total += p_image.get_pixel(x, y).unwrap().0 as u32;
+      }
+    }
+    total as f32 / ((p_to - p_from) * 32) as f32
+  }
+
+  #[test]
+  fn one_bit_dither_preserves_average_tone() {
+    let original = gradient_image();
+    for method in [
+      DitherMethod::FloydSteinberg,
+      DitherMethod::Ordered,
+      DitherMethod::Atkinson,
+    ] {
+      let image = dither_gradient(method);
+      assert!(
+        image
+          .rgba()
+          .chunks(4)
+          .all(|px| (px[0] == 0 || px[0] == 255) && px[3] == 255)
+      );
+      for band in [(8, 16), (28, 36), (48, 56)] {
+        let expected = band_mean(&original, band.0, band.1);
+        let actual = band_mean(&image, band.0, band.1);
+        // Atkinson drops a quarter of the error, so it drifts more in the midtones.
+        let tolerance = if method == DitherMethod::Atkinson { 40.0 } else { 20.0 };
+        assert!((expected - actual).abs() < tolerance, "{:?} {:?}: {} vs {}", method, band, expected, actual);
+      }
+    }
+  }
+
+  #[test]
+  fn floyd_steinberg_diffuses_error_across_rows() {
+    let image = dither_gradient(DitherMethod::FloydSteinberg);
+    // Mid-gray must alternate within rows and also differ between rows rather than forming vertical stripes.
+    let row = |p_y: u32| (24..40).map(|x| image.get_pixel(x, p_y).unwrap().0).collect::<Vec<_>>();
+    assert!(row(10).contains(&0) && row(10).contains(&255));
+    assert!((10..20).any(|y| row(y) != row(10)));
+  }
+
+  #[test]
+  fn more_levels_reduce_error() {
+    let mut image = gradient_image();
+    let options = ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (64.0, 32.0)));
+    dither(&mut image, DitherMethod::FloydSteinberg, 5, options);
+    let levels = [0u8, 64, 128, 191, 255];
+    assert!(image.rgba().chunks(4).all(|px| levels.contains(&px[0])));
+  }
+}
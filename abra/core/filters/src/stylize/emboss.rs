@@ -0,0 +1,94 @@
+use crate::common::*;
+use crate::sobel::sobel_gradient;
+
+fn apply_emboss(p_image: &mut Image, p_angle: f32, p_depth: f32) {
+  let (width, height) = p_image.dimensions::<usize>();
+  let src = p_image.rgba();
+  let luma: Vec<f32> = src
+    .chunks_exact(4)
+    .map(|px| 0.299 * px[0] as f32 + 0.587 * px[1] as f32 + 0.114 * px[2] as f32)
+    .collect();
+  // The light direction in image coordinates, where y points down.
+  let (light_x, light_y) = (p_angle.to_radians().cos(), -p_angle.to_radians().sin());
+
+  let mut out = vec![0u8; width * height * 4];
+  out.par_chunks_mut(width * 4).enumerate().for_each(|(y, row)| {
+    let at = |x: usize, dx: isize, dy: isize| {
+      let sx = (x as isize + dx).clamp(0, width as isize - 1) as usize;
+      let sy = (y as isize + dy).clamp(0, height as isize - 1) as usize;
+      luma[sy * width + sx]
+    };
+    for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+      // Sobel gradient of the brightness, which is treated as the height of the surface.
+      let (gx, gy) = sobel_gradient(|dx, dy| at(x, dx, dy));
+      // Slopes that rise toward the light are lit, the ones that fall away from it are in shadow.
+      let shade = -(gx * light_x + gy * light_y) / 4.0;
+      let value = (128.0 + shade * p_depth).round().clamp(0.0, 255.0) as u8;
+      pixel[0] = value;
+      pixel[1] = value;
+      pixel[2] = value;
+      pixel[3] = src[(y * width + x) * 4 + 3];
+    }
+  });
+  p_image.set_rgba_owned(out);
+}
+
+/// Turns the image into a gray relief, as if it were pressed into metal and lit from one side.
+/// Flat areas become mid gray and edges are highlighted or shaded depending on the light direction.
+/// - `p_image`: The image to apply the filter to.
+/// - `p_angle`: The direction the light comes from in degrees, counterclockwise from the right.
+///   `135.0` lights the image from the top left.
+/// - `p_depth`: How strongly edges stand out, from `0.0` (flat gray) to `10.0`. `1.0` is a natural relief.
+/// - `p_apply_options`: Options to specify for the filter.
+pub fn emboss<'a>(p_image: impl Into<ImageRef<'a>>, p_angle: f32, p_depth: f32, p_apply_options: impl Into<Options>) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  let depth = p_depth.clamp(0.0, 10.0);
+  apply_filter!(apply_emboss, image, p_apply_options, 1, p_angle, depth);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::{Area, Color};
+
+  /// A light square on a dark background.
+  fn square() -> Image {
+    let mut image = Image::new_from_color(20, 20, Color::from_rgba(40, 40, 40, 255));
+    for y in 6..14 {
+      for x in 6..14 {
+        image.set_pixel(x, y, (220, 220, 220, 255));
+      }
+    }
+    image
+  }
+
+  #[test]
+  fn light_angle_decides_which_edges_are_lit() {
+    let mut image = square();
+    emboss(&mut image, 135.0, 1.0, None);
+    // The top-left edges face the light, the bottom-right edges are in shadow and flat areas are mid gray.
+    assert!(image.get_pixel(6, 10).unwrap().0 > 200);
+    assert!(image.get_pixel(10, 6).unwrap().0 > 200);
+    assert!(image.get_pixel(13, 10).unwrap().0 < 60);
+    assert_eq!(image.get_pixel(10, 10), Some((128, 128, 128, 255)));
+    assert_eq!(image.get_pixel(2, 2), Some((128, 128, 128, 255)));
+
+    let mut flipped = square();
+    emboss(&mut flipped, 315.0, 1.0, None);
+    assert!(flipped.get_pixel(6, 10).unwrap().0 < 60);
+    assert!(flipped.get_pixel(13, 10).unwrap().0 > 200);
+  }
+
+  #[test]
+  fn depth_and_area_limit_the_effect() {
+    let mut flat = square();
+    emboss(&mut flat, 135.0, 0.0, None);
+    assert!(flat.rgba().chunks(4).all(|px| px[..3] == [128, 128, 128]));
+
+    let mut image = square();
+    emboss(&mut image, 135.0, 1.0, ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (10.0, 20.0))));
+    assert!(image.get_pixel(6, 10).unwrap().0 > 200);
+    assert_eq!(image.get_pixel(13, 10), Some((220, 220, 220, 255)));
+  }
+}
@@ -0,0 +1,133 @@
+use crate::common::*;
+use abra_core::transform::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The shape of the ink dots in a halftone.
+pub enum DotShape {
+  /// Round dots, the classic newspaper look.
+  #[default]
+  Circle,
+  /// Square dots.
+  Square,
+  /// Diamond (45° square) dots that join into a checkerboard in the midtones.
+  Diamond,
+  /// Parallel lines whose thickness follows the tone.
+  Line,
+}
+
+impl DotShape {
+  /// Returns how much of a pixel is covered by ink.
+  /// - `p_x`: The horizontal offset from the cell center, in pixels.
+  /// - `p_y`: The vertical offset from the cell center, in pixels.
+  /// - `p_size`: The size of a cell in pixels.
+  /// - `p_coverage`: The fraction of the cell that should be inked, between 0 and 1.
+  fn ink(&self, p_x: f32, p_y: f32, p_size: f32, p_coverage: f32) -> f32 {
+    // Each shape's extent is chosen so that the inked area matches the coverage.
+    let (distance, extent) = match self {
+      DotShape::Circle => (p_x.hypot(p_y), p_size * (p_coverage / std::f32::consts::PI).sqrt()),
+      DotShape::Square => (p_x.abs().max(p_y.abs()), p_size * p_coverage.sqrt() / 2.0),
+      DotShape::Diamond => (p_x.abs() + p_y.abs(), p_size * (p_coverage / 2.0).sqrt()),
+      DotShape::Line => (p_y.abs(), p_size * p_coverage / 2.0),
+    };
+    // A one pixel wide ramp anti-aliases the edge of the dot.
+    (extent - distance + 0.5).clamp(0.0, 1.0)
+  }
+}
+
+fn apply_halftone(p_image: &mut Image, p_dot_size: u32, p_angle: f32, p_shape: DotShape) {
+  let (width, height) = p_image.dimensions::<u32>();
+  if width == 0 || height == 0 {
+    return;
+  }
+  let size = p_dot_size.max(2) as f32;
+  let (sin, cos) = p_angle.to_radians().sin_cos();
+
+  let original_image = p_image.clone();
+  let original_pixels = original_image.rgba();
+  let mut new_pixels = vec![0u8; (width * height * 4) as usize];
+
+  new_pixels.par_chunks_mut(4).enumerate().for_each(|(i, chunk)| {
+    let x = (i as u32 % width) as f32 + 0.5;
+    let y = (i as u32 / width) as f32 + 0.5;
+    // Rotate into screen space, find the cell, and rotate the cell center back to sample the tone there.
+    let u = x * cos + y * sin;
+    let v = -x * sin + y * cos;
+    let (cell_u, cell_v) = (((u / size).floor() + 0.5) * size, ((v / size).floor() + 0.5) * size);
+    let center_x = cell_u * cos - cell_v * sin;
+    let center_y = cell_u * sin + cell_v * cos;
+    let sample_x = (center_x - 0.5).clamp(0.0, (width - 1) as f32);
+    let sample_y = (center_y - 0.5).clamp(0.0, (height - 1) as f32);
+    let [r, g, b, _] = sample_bilinear(&original_image, sample_x, sample_y);
+    let luma = (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0;
+
+    let ink = p_shape.ink(u - cell_u, v - cell_v, size, 1.0 - luma);
+    let value = ((1.0 - ink) * 255.0).round() as u8;
+    chunk[0] = value;
+    chunk[1] = value;
+    chunk[2] = value;
+    chunk[3] = original_pixels[i * 4 + 3];
+  });
+
+  p_image.set_new_pixels(&new_pixels, width, height);
+}
+
+/// Renders the image as a grid of black ink dots on white paper, where each dot is sized by the brightness
+/// of the image under it, like a printed halftone.
+/// - `p_image`: The image to apply the effect to.
+/// - `p_dot_size`: The distance between dot centers in pixels (minimum 2).
+/// - `p_angle`: The angle of the dot grid in degrees. Print screens commonly use 45°.
+/// - `p_shape`: The shape of the dots.
+/// - `p_apply_options`: Options to specify for the filter.
+pub fn halftone<'a>(
+  p_image: impl Into<ImageRef<'a>>, p_dot_size: u32, p_angle: f32, p_shape: DotShape,
+  p_apply_options: impl Into<Options>,
+) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_filter!(apply_halftone, image, p_apply_options, p_dot_size as i32, p_dot_size, p_angle, p_shape);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::{Area, Color};
+
+  fn ink_ratio(p_gray: u8, p_shape: DotShape) -> f32 {
+    let mut image = Image::new_from_color(40, 40, Color::from_rgb(p_gray, p_gray, p_gray));
+    let options = ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (40.0, 40.0)));
+    halftone(&mut image, 8, 0.0, p_shape, options);
+    let ink: u32 = image.rgba().chunks(4).map(|px| 255 - px[0] as u32).sum();
+    ink as f32 / (40.0 * 40.0 * 255.0)
+  }
+
+  #[test]
+  fn dot_size_follows_tone() {
+    for shape in [DotShape::Circle, DotShape::Square, DotShape::Diamond, DotShape::Line] {
+      assert_eq!(ink_ratio(255, shape), 0.0, "{:?}", shape);
+      let dark = ink_ratio(64, shape);
+      let light = ink_ratio(192, shape);
+      assert!(dark > light, "{:?}: {} <= {}", shape, dark, light);
+      // The inked area approximates the tone.
+      assert!((ink_ratio(128, shape) - 0.5).abs() < 0.1, "{:?}", shape);
+    }
+  }
+
+  #[test]
+  fn output_is_black_ink_on_white() {
+    let options = ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (16.0, 16.0)));
+    let mut image = Image::new_from_color(16, 16, Color::from_rgb(128, 0, 0));
+    halftone(&mut image, 8, 45.0, DotShape::Circle, options.clone());
+    assert!(
+      image
+        .rgba()
+        .chunks(4)
+        .all(|px| px[0] == px[1] && px[1] == px[2] && px[3] == 255)
+    );
+
+    let mut image = Image::new_from_color(16, 16, Color::from_rgb(128, 0, 0));
+    halftone(&mut image, 8, 0.0, DotShape::Circle, options);
+    // The middle of a cell is fully inked and the corners are paper.
+    assert_eq!(image.get_pixel(4, 4).unwrap().0, 0);
+    assert_eq!(image.get_pixel(0, 0).unwrap().0, 255);
+  }
+}
@@ -0,0 +1,132 @@
+use crate::common::*;
+
+/// Summed-area tables of the color channels and luminance, so the mean and variance of any rectangle are O(1).
+struct IntegralImages {
+  width: usize,
+  /// Sums of red, green, blue, luminance and squared luminance, with an extra zero row and column.
+  sums: Vec<[f64; 5]>,
+}
+
+impl IntegralImages {
+  fn new(p_pixels: &[u8], p_width: usize, p_height: usize) -> IntegralImages {
+    let stride = p_width + 1;
+    let mut sums = vec![[0.0f64; 5]; stride * (p_height + 1)];
+    for y in 0..p_height {
+      let mut row = [0.0f64; 5];
+      for x in 0..p_width {
+        let idx = (y * p_width + x) * 4;
+        let (r, g, b) = (p_pixels[idx] as f64, p_pixels[idx + 1] as f64, p_pixels[idx + 2] as f64);
+        let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+        for (total, value) in row.iter_mut().zip([r, g, b, luma, luma * luma]) {
+          *total += value;
+        }
+        let above = sums[y * stride + x + 1];
+        sums[(y + 1) * stride + x + 1] = std::array::from_fn(|i| row[i] + above[i]);
+      }
+    }
+    IntegralImages { width: p_width, sums }
+  }
+
+  /// Returns the mean color and the luminance variance of the inclusive rectangle.
+  fn stats(&self, p_x0: usize, p_y0: usize, p_x1: usize, p_y1: usize) -> ([f64; 3], f64) {
+    let stride = self.width + 1;
+    let at = |p_x: usize, p_y: usize| self.sums[p_y * stride + p_x];
+    let (a, b, c, d) = (at(p_x0, p_y0), at(p_x1 + 1, p_y0), at(p_x0, p_y1 + 1), at(p_x1 + 1, p_y1 + 1));
+    let count = ((p_x1 - p_x0 + 1) * (p_y1 - p_y0 + 1)) as f64;
+    let sum: [f64; 5] = std::array::from_fn(|i| d[i] - b[i] - c[i] + a[i]);
+    let mean_luma = sum[3] / count;
+    ([sum[0] / count, sum[1] / count, sum[2] / count], sum[4] / count - mean_luma * mean_luma)
+  }
+}
+
+fn apply_kuwahara(p_image: &mut Image, p_radius: u32) {
+  let (width, height) = p_image.dimensions::<usize>();
+  if width == 0 || height == 0 || p_radius == 0 {
+    return;
+  }
+  let radius = p_radius as usize;
+  let src = p_image.rgba();
+  let integrals = IntegralImages::new(src, width, height);
+  let mut out = p_image.empty_pixel_vec();
+
+  out.par_chunks_mut(4).enumerate().for_each(|(i, px)| {
+    let (x, y) = (i % width, i / width);
+    let (left, right) = (x.saturating_sub(radius), (x + radius).min(width - 1));
+    let (top, bottom) = (y.saturating_sub(radius), (y + radius).min(height - 1));
+    // The four overlapping quadrants that share the center pixel; the flattest one wins.
+    let quadrants = [
+      (left, top, x, y),
+      (x, top, right, y),
+      (left, y, x, bottom),
+      (x, y, right, bottom),
+    ];
+    let (mean, _) = quadrants
+      .iter()
+      .map(|&(x0, y0, x1, y1)| integrals.stats(x0, y0, x1, y1))
+      .min_by(|a, b| a.1.total_cmp(&b.1))
+      .unwrap();
+    px[0] = mean[0].round() as u8;
+    px[1] = mean[1].round() as u8;
+    px[2] = mean[2].round() as u8;
+    px[3] = src[i * 4 + 3];
+  });
+
+  p_image.set_rgba_owned(out);
+}
+
+/// Applies a Kuwahara filter, which gives the image a smooth, painterly look while keeping edges sharp.
+/// The window around each pixel is split into four quadrants and the pixel takes the average color of the
+/// quadrant with the least variation, so it never averages across an edge.
+/// - `p_image`: The image to apply the filter to.
+/// - `p_radius`: The size of each quadrant in pixels. Larger values give broader brush strokes.
+/// - `p_apply_options`: Options to specify for the filter.
+pub fn kuwahara<'a>(p_image: impl Into<ImageRef<'a>>, p_radius: u32, p_apply_options: impl Into<Options>) {
+  let mut image_ref: ImageRef = p_image.into();
+  let image = &mut image_ref as &mut Image;
+  apply_filter!(apply_kuwahara, image, p_apply_options, p_radius as i32, p_radius);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use abra_core::Area;
+
+  /// A noisy dark left half and a noisy light right half.
+  fn noisy_edge_image() -> Image {
+    let mut image = Image::new(20, 20);
+    for y in 0..20u32 {
+      for x in 0..20u32 {
+        let noise = ((x * 7 + y * 13) % 5) as u8 * 4;
+        let base = if x < 10 { 40 } else { 200 };
+        image.set_pixel(x, y, (base + noise, base + noise, base + noise, 255));
+      }
+    }
+    image
+  }
+
+  #[test]
+  fn smooths_regions_and_keeps_edges() {
+    let mut image = noisy_edge_image();
+    kuwahara(&mut image, 3, ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (20.0, 20.0))));
+
+    for y in 4..16 {
+      // Each side is flattened toward its average without bleeding across the edge.
+      let dark = image.get_pixel(9, y).unwrap().0;
+      let light = image.get_pixel(10, y).unwrap().0;
+      assert!((40..=48).contains(&dark), "dark {}", dark);
+      assert!((200..=208).contains(&light), "light {}", light);
+    }
+    let row: Vec<u8> = (2..8).map(|x| image.get_pixel(x, 10).unwrap().0).collect();
+    let spread = row.iter().max().unwrap() - row.iter().min().unwrap();
+    assert!(spread < 16, "spread {}", spread);
+  }
+
+  #[test]
+  fn respects_area() {
+    let original = noisy_edge_image();
+    let mut image = original.clone();
+    kuwahara(&mut image, 2, ApplyOptions::new().with_area(Area::rect((0.0, 0.0), (5.0, 5.0))));
+    assert_eq!(image.get_pixel(15, 15), original.get_pixel(15, 15));
+    assert_ne!(image.rgba(), original.rgba());
+  }
+}
@@ -0,0 +1,9 @@
+mod dither;
+mod emboss;
+mod halftone;
+mod kuwahara;
+
+pub use dither::{DitherMethod, dither};
+pub use emboss::emboss;
+pub use halftone::{DotShape, halftone};
+pub use kuwahara::kuwahara;
@@ -1,6 +1,6 @@
-use abra_core::{Area, Color, Image, PointF, blend};
+use abra_core::{Area, Color, Image, PointF, Size, blend};
 
-use drawing::fill;
+use drawing::{fill, fill_coverage};
 
 /// Helper trait to convert various types into an optional PointF
 pub trait IntoOptionalPointF {
@@ -68,6 +68,21 @@ impl Mask {
     Mask { image_mask: image }
   }
 
+  /// Creates a mask from the anti-aliased coverage of an area, e.g. to select exactly the pixels a fill of the same
+  /// area paints. Covered pixels are white, uncovered pixels black, and edge and feather pixels gray.
+  /// - `p_area`: The area to select, in image coordinates.
+  /// - `p_size`: The size of the mask.
+  pub fn new_from_area(p_area: &Area, p_size: impl Into<Size>) -> Mask {
+    let mut image = fill_coverage(p_area, p_size, None);
+    if let Some(pixels) = image.colors().as_slice_mut() {
+      for pixel in pixels.chunks_exact_mut(4) {
+        let coverage = pixel[3];
+        pixel.copy_from_slice(&[coverage, coverage, coverage, 255]);
+      }
+    }
+    Mask { image_mask: image }
+  }
+
   /// Create a mask by consuming an Image.
   pub fn from_image(img: Image) -> Mask {
     Mask { image_mask: img }
@@ -88,7 +103,7 @@ impl Mask {
   pub fn draw_area(&mut self, p_area: &Area, p_color: Color, p_at: impl IntoOptionalPointF) {
     let color = self.to_color(p_color);
     let position = p_at.into_optional_point_f().unwrap_or(PointF::new(0, 0));
-    let filled_image = fill(p_area, color);
+    let filled_image = fill(p_area, color, None);
     blend::blend_images_at(
       &mut self.image_mask,
       &filled_image,
@@ -291,6 +306,28 @@ mod tests {
     assert!(found_partial, "Expected to find partial-coverage pixels for feathered area");
   }
 
+  #[test]
+  fn area_mask_matches_fill_coverage() {
+    // A circle whose bounds start on whole pixels, so the fill's pixels line up with the mask's.
+    let area = Area::circle((16.0, 16.0), 10.0);
+    let filled = fill(&area, Color::black(), None);
+    let mask = Mask::new_from_area(&area, (32, 32));
+    let (width, height) = filled.dimensions::<u32>();
+    let mut partial = 0;
+    for y in 0..height {
+      for x in 0..width {
+        let alpha = filled.get_pixel(x, y).unwrap().3;
+        assert_eq!(mask.image().get_pixel(x + 6, y + 6).unwrap().0, alpha, "pixel ({}, {})", x + 6, y + 6);
+        if alpha > 0 && alpha < 255 {
+          partial += 1;
+        }
+      }
+    }
+    assert!(partial > 0, "Expected anti-aliased edge pixels");
+    assert_eq!(mask.image().get_pixel(0, 0).unwrap().0, 0);
+    assert_eq!(mask.image().get_pixel(16, 16).unwrap().0, 255);
+  }
+
   #[test]
   fn draw_star_area_offset_is_correct() {
     use abra_core::image::image_ext::*;
@@ -298,7 +335,7 @@ mod tests {
     let img = Image::new_from_color(200, 200, Color::from_rgba(255, 255, 255, 255));
     let mut mask = Mask::new_from_image(&img);
     // Create a star area sized to half the image and not positioned explicitly
-    let area = Star::new().fit_with_aspect(img.size() / 2, AspectRatio::meet());
+    let area = Star::new().to_area().fit_with_aspect(img.size() / 2, AspectRatio::meet());
     mask.draw_area(&area, Color::black(), None);
     // compute topmost row with non-white pixel
     let mut topmost: Option<u32> = None;
@@ -324,7 +361,7 @@ mod tests {
     use abra_core::{AspectRatio, Star};
     let img = Image::new_from_color(200, 200, Color::from_rgba(255, 255, 255, 255));
     let mut mask = Mask::new_from_image(&img);
-    let area = Star::new().fit_with_aspect(img.size() / 2, AspectRatio::meet());
+    let area = Star::new().to_area().fit_with_aspect(img.size() / 2, AspectRatio::meet());
     // Draw with an explicit offset
     mask.draw_area(&area, Color::black(), (10.0, 20.0));
     // compute topmost row with non-white pixel
@@ -4,17 +4,18 @@
 //! that control how an operation is applied to an image. The two main options are:
 //! - `Mask`: controls the per-pixel strength of the operation (black = no effect,
 //!   white = full effect, grayscale = partial effect).
-//! - `Area`: restricts the operation to a particular region (optionally feathered).
+//! - `Area`: restricts the operation to a particular region (optionally feathered). Areas can be combined with
+//!   boolean operations and inverted, so an operation can apply everywhere except a region.
 
-use abra_core::Area;
 use abra_core::image::apply_area::ApplyContext;
+use abra_core::{Area, BooleanOp, FillRule};
 use mask::Mask;
 
 pub type Options = Option<ApplyOptions>;
 
 /// Options for applying an effect (filter, adjustment, etc.) to an image.
 /// ```ignore
-/// use abra::{Area, Image, Heart, mask::Mask, options::ApplyOptions};
+/// use abra::{Area, BooleanOp, Image, Heart, mask::Mask, options::ApplyOptions};
 ///
 /// let mut image = Image::new_from_path("images/input.png");
 /// let mask = Heart::new().fit(200, 200);
@@ -25,6 +26,14 @@ pub type Options = Option<ApplyOptions>;
 ///   .with_area(area);
 ///
 /// blur::gaussian_blur(&mut image, 5.0, opts);
+///
+/// // Blur everything except a ring around the center.
+/// let opts = ApplyOptions::new()
+///   .with_area(Area::circle((200.0, 200.0), 150.0))
+///   .with_area_op(Area::circle((200.0, 200.0), 50.0), BooleanOp::Difference)
+///   .inverted();
+///
+/// blur::gaussian_blur(&mut image, 5.0, opts);
 /// ```
 #[derive(Clone, Debug)]
 pub struct ApplyOptions {
@@ -38,11 +47,19 @@ pub struct ApplyOptions {
   /// If an area has a feather on its edges, then the filter will be applied
   /// gradually from the edge of the area to the feathered region.
   area: Option<Vec<Area>>,
+  /// Whether the area is inverted.
+  /// If set, the filter will be applied everywhere except within the area,
+  /// fading in across the feathered edges of the area.
+  inverted: bool,
 }
 
 impl Default for ApplyOptions {
   fn default() -> Self {
-    Self { mask: None, area: None }
+    Self {
+      mask: None,
+      area: None,
+      inverted: false,
+    }
   }
 }
 
@@ -56,6 +73,7 @@ impl ApplyOptions {
     ApplyContext {
       area: self.area.as_ref().map(|v| v.iter().collect()),
       mask_image: self.mask.as_ref().map(|m| m.image().rgba()),
+      inverted: self.inverted,
     }
   }
   /// Sets a mask to be used by the filter.
@@ -76,21 +94,43 @@ impl ApplyOptions {
     self.area = Some(p_area.into());
     self
   }
+  /// Combines the last area set with another area, e.g. to cut a hole into it. The feather of the last area is kept.
+  /// Without an area set, the other area is combined with an empty area.
+  /// - `p_area`: The `Area` to combine with the last area.
+  /// - `p_op`: The boolean operation used to combine the areas.
+  pub fn with_area_op(mut self, p_area: impl Into<Area>, p_op: BooleanOp) -> Self {
+    let area = p_area.into();
+    let areas = self.area.get_or_insert_with(Vec::new);
+    match areas.last_mut() {
+      Some(last) => *last = last.boolean(&area, p_op, FillRule::NonZero),
+      None => areas.push(Area::new().boolean(&area, p_op, FillRule::NonZero)),
+    }
+    self
+  }
+  /// Inverts the area, so the filter is applied everywhere except within it. Calling it again reverts the inversion.
+  /// Inverting has no effect when no area is set.
+  pub fn inverted(mut self) -> Self {
+    self.inverted = !self.inverted;
+    self
+  }
   /// Returns a reference to the mask if set.
   pub fn mask(&self) -> Option<&Mask> {
     self.mask.as_ref()
   }
   /// Returns a reference to the area if set.
+  /// Inverted areas return `None`, as the operation then processes the whole image and the areas only shape where
+  /// the result is blended in.
   pub fn area(&self) -> Option<&[Area]> {
-    self.area.as_deref()
+    if self.inverted { None } else { self.area.as_deref() }
+  }
+  /// Returns whether the area is inverted.
+  pub fn is_inverted(&self) -> bool {
+    self.inverted
   }
 }
 
 /// Convert an optional ApplyOptions into the lightweight core ApplyContext used by core helpers.
 /// This helper lives in the `options` crate to avoid a circular dependency (core -> options -> core).
 pub fn get_ctx<'a>(opts: Option<&'a ApplyOptions>) -> Option<ApplyContext<'a>> {
-  opts.map(|o| ApplyContext {
-    area: o.area().map(|v| v.iter().collect()),
-    mask_image: o.mask().map(|m| m.image().rgba()),
-  })
+  opts.map(|o| o.ctx())
 }
@@ -1,5 +1,18 @@
+/// The channel layout of a pixel buffer.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Channels {
+  /// Red, green, blue and alpha.
   RGBA,
+  /// Red, green and blue, without alpha.
   RGB,
 }
+
+impl Channels {
+  /// Returns the number of channels in the layout.
+  pub fn count(&self) -> usize {
+    match self {
+      Channels::RGBA => 4,
+      Channels::RGB => 3,
+    }
+  }
+}
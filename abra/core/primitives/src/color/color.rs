@@ -5,6 +5,7 @@ use rayon::prelude::*;
 use crate::color::to_rgb::hsl_to_rgb;
 use crate::color::to_rgb::hsv_to_rgb;
 
+use super::colors_list::CSS_COLORS;
 use super::to_hsl::rgb_to_hsl;
 use super::to_hsv::rgb_to_hsv;
 
@@ -49,6 +50,23 @@ impl Display for Color {
   }
 }
 
+/// Errors that can occur while creating a color.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorError {
+  /// The string is not a valid 3, 4, 6 or 8 digit hex color.
+  InvalidHex(String),
+}
+
+impl Display for ColorError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ColorError::InvalidHex(hex) => write!(f, "Invalid hex color: {}", hex),
+    }
+  }
+}
+
+impl std::error::Error for ColorError {}
+
 impl Color {
   pub fn default() -> Self {
     Self {
@@ -75,8 +93,8 @@ impl Color {
     let (r, g, b) = hsv_to_rgb(h, s, v);
     Self { r, g, b, a: 255 }
   }
-  /// Creates a color from a hexadecimal value (alpha set to 255).
-  pub fn from_hex(hex: u32) -> Self {
+  /// Creates a color from a hexadecimal value such as `0xFF8800` (alpha set to 255).
+  pub fn from_hex_value(hex: u32) -> Self {
     Self {
       r: ((hex >> 16) & 0xFF) as u8,
       g: ((hex >> 8) & 0xFF) as u8,
@@ -84,20 +102,40 @@ impl Color {
       a: 255,
     }
   }
-  /// Creates a color from a hexadecimal string (e.g., "#RRGGBB" or "#RRGGBBAA").
-  pub fn from_hex_string(hex: &str) -> Self {
-    let hex = hex.trim_start_matches('#');
-    let hex_value = u32::from_str_radix(hex, 16).unwrap_or(0);
-    match hex.len() {
-      6 => Self::from_hex(hex_value),
-      8 => Self {
-        r: ((hex_value >> 24) & 0xFF) as u8,
-        g: ((hex_value >> 16) & 0xFF) as u8,
-        b: ((hex_value >> 8) & 0xFF) as u8,
-        a: (hex_value & 0xFF) as u8,
-      },
-      _ => Self::default(),
+  /// Creates a color from a hexadecimal string.
+  /// Accepts the `RGB`, `RGBA`, `RRGGBB` and `RRGGBBAA` forms, with or without a leading `#`.
+  /// - `hex`: The hex string, e.g. `"#ff8800"` or `"f80c"`.
+  pub fn from_hex(hex: &str) -> Result<Self, ColorError> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+      return Err(ColorError::InvalidHex(hex.to_string()));
+    }
+    let nibble = |i: usize| u8::from_str_radix(&digits[i..i + 1], 16).unwrap();
+    let byte = |i: usize| u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).unwrap();
+    match digits.len() {
+      3 => Ok(Self::from_rgb(nibble(0) * 17, nibble(1) * 17, nibble(2) * 17)),
+      4 => Ok(Self::from_rgba(nibble(0) * 17, nibble(1) * 17, nibble(2) * 17, nibble(3) * 17)),
+      6 => Ok(Self::from_rgb(byte(0), byte(1), byte(2))),
+      8 => Ok(Self::from_rgba(byte(0), byte(1), byte(2), byte(3))),
+      _ => Err(ColorError::InvalidHex(hex.to_string())),
+    }
+  }
+  /// Creates a color from a CSS color name such as `"rebeccapurple"` or `"transparent"`.
+  /// The lookup is case-insensitive. Returns `None` for unknown names.
+  /// - `name`: The CSS color name.
+  pub fn named(name: &str) -> Option<Self> {
+    let name = name.trim().to_ascii_lowercase();
+    if name == "transparent" {
+      return Some(Self::transparent());
     }
+    CSS_COLORS
+      .binary_search_by(|(css_name, _)| (*css_name).cmp(name.as_str()))
+      .ok()
+      .map(|index| Self::from_hex_value(CSS_COLORS[index].1))
+  }
+  /// Returns the color as a lowercase `#rrggbbaa` hex string.
+  pub fn to_hex(&self) -> String {
+    format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
   }
   /// Creates a color from HSL values (alpha set to 255).
   pub fn from_hsl(h: f32, s: f32, l: f32) -> Self {
@@ -147,6 +185,12 @@ impl Color {
     let (r, g, b) = self.rgb();
     (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0
   }
+  /// Weights red, green and blue with the BT.709 (HDTV and sRGB) coefficients `0.2126`, `0.7152` and `0.0722`.
+  /// The result has the same scale as the channels. With linear channels it is the relative luminance, with encoded
+  /// ones the luma.
+  pub fn luma_709(p_r: f32, p_g: f32, p_b: f32) -> f32 {
+    0.2126 * p_r + 0.7152 * p_g + 0.0722 * p_b
+  }
   /// Calculates the average color from a slice of colors represented as u8 values.
   pub fn average(p_colors: &[u8]) -> Self {
     let len = p_colors.len() as u32;
@@ -325,3 +369,38 @@ impl Color {
     Self::from_rgba(r, g, b, 255)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn from_hex_accepts_all_forms() {
+    assert_eq!(Color::from_hex("#ff8800").unwrap().rgba(), (255, 136, 0, 255));
+    assert_eq!(Color::from_hex("ff8800cc").unwrap().rgba(), (255, 136, 0, 204));
+    assert_eq!(Color::from_hex("#f80").unwrap().rgba(), (255, 136, 0, 255));
+    assert_eq!(Color::from_hex("F80C").unwrap().rgba(), (255, 136, 0, 204));
+  }
+
+  #[test]
+  fn from_hex_rejects_malformed_input() {
+    for hex in ["", "#", "#ff", "#ff880", "#gg8800", "#ff8800cc00", "+f80"] {
+      assert_eq!(Color::from_hex(hex).unwrap_err(), ColorError::InvalidHex(hex.to_string()));
+    }
+  }
+
+  #[test]
+  fn named_is_case_insensitive() {
+    assert_eq!(Color::named("rebeccapurple").unwrap().rgba(), (102, 51, 153, 255));
+    assert_eq!(Color::named("DarkSlateGrey").unwrap().rgba(), (47, 79, 79, 255));
+    assert_eq!(Color::named("transparent").unwrap().rgba(), (0, 0, 0, 0));
+    assert!(Color::named("notacolor").is_none());
+  }
+
+  #[test]
+  fn to_hex_round_trips() {
+    let color = Color::from_rgba(18, 52, 86, 120);
+    assert_eq!(color.to_hex(), "#12345678");
+    assert_eq!(Color::from_hex(&color.to_hex()).unwrap().rgba(), color.rgba());
+  }
+}
@@ -0,0 +1,171 @@
+//! Color interpolation and weighted mixing.
+
+use super::Color;
+use super::to_hsl::rgb_to_hsl;
+use super::to_lab::{rgb_to_oklab, srgb_u8_to_linear_f32};
+use super::to_rgb::{hsl_to_rgb, linear_f32_to_srgb_u8, oklab_to_rgb};
+
+/// The space in which colors are interpolated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+  /// Interpolates the gamma-encoded sRGB values directly. Fast, but midpoints can look dark and muddy.
+  #[default]
+  Srgb,
+  /// Interpolates in linear light, which matches how light physically mixes and keeps midpoints bright.
+  LinearRgb,
+  /// Interpolates in the Oklab perceptual space, giving even steps in perceived lightness and hue
+  /// and avoiding the gray dead zone between complementary colors.
+  Oklab,
+  /// Interpolates hue (along the shortest way around the color wheel), saturation and lightness.
+  Hsl,
+}
+
+impl ColorSpace {
+  /// Converts a color's RGB channels into components that can be mixed linearly in this space.
+  fn decompose(&self, p_color: &Color) -> [f32; 4] {
+    match self {
+      ColorSpace::Srgb => [p_color.r as f32 / 255.0, p_color.g as f32 / 255.0, p_color.b as f32 / 255.0, 0.0],
+      ColorSpace::LinearRgb => [
+        srgb_u8_to_linear_f32(p_color.r),
+        srgb_u8_to_linear_f32(p_color.g),
+        srgb_u8_to_linear_f32(p_color.b),
+        0.0,
+      ],
+      ColorSpace::Oklab => {
+        let (l, a, b) = rgb_to_oklab(p_color.r, p_color.g, p_color.b);
+        [l, a, b, 0.0]
+      }
+      ColorSpace::Hsl => {
+        // Hue is stored as a vector scaled by saturation so grays carry no hue and averaging
+        // follows the shortest way around the wheel.
+        let (h, s, l) = rgb_to_hsl(p_color.r, p_color.g, p_color.b);
+        let (sin, cos) = h.to_radians().sin_cos();
+        [cos * s, sin * s, s, l]
+      }
+    }
+  }
+
+  /// Converts mixed components back into 8-bit sRGB channels.
+  fn compose(&self, p_components: [f32; 4]) -> (u8, u8, u8) {
+    let [c0, c1, c2, c3] = p_components;
+    let to_u8 = |p_value: f32| (p_value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    match self {
+      ColorSpace::Srgb => (to_u8(c0), to_u8(c1), to_u8(c2)),
+      ColorSpace::LinearRgb => (linear_f32_to_srgb_u8(c0), linear_f32_to_srgb_u8(c1), linear_f32_to_srgb_u8(c2)),
+      ColorSpace::Oklab => oklab_to_rgb(c0, c1, c2),
+      ColorSpace::Hsl => {
+        let hue = c1.atan2(c0).to_degrees().rem_euclid(360.0);
+        hsl_to_rgb(hue, c2.clamp(0.0, 1.0), c3.clamp(0.0, 1.0))
+      }
+    }
+  }
+}
+
+impl Color {
+  /// Interpolates between this color and another, treating alpha as an independent channel.
+  /// - `p_other`: The color at `p_t = 1`.
+  /// - `p_t`: The interpolation amount between 0 and 1.
+  /// - `p_space`: The space in which the colors are interpolated. Alpha is always interpolated linearly.
+  pub fn lerp(&self, p_other: &Color, p_t: f32, p_space: ColorSpace) -> Color {
+    let t = p_t.clamp(0.0, 1.0);
+    let from = p_space.decompose(self);
+    let to = p_space.decompose(p_other);
+    let mixed = std::array::from_fn(|i| from[i] + (to[i] - from[i]) * t);
+    let (r, g, b) = p_space.compose(mixed);
+    let alpha = self.a as f32 + (p_other.a as f32 - self.a as f32) * t;
+    Color::from_rgba(r, g, b, alpha.round() as u8)
+  }
+
+  /// Interpolates between this color and another using premultiplied alpha.
+  /// Unlike `lerp`, a fully transparent color contributes no color of its own, so fading to
+  /// `Color::transparent()` does not darken the result.
+  /// - `p_other`: The color at `p_t = 1`.
+  /// - `p_t`: The interpolation amount between 0 and 1.
+  /// - `p_space`: The space in which the colors are interpolated.
+  pub fn lerp_premultiplied(&self, p_other: &Color, p_t: f32, p_space: ColorSpace) -> Color {
+    let t = p_t.clamp(0.0, 1.0);
+    Color::mix_many(&[(*self, 1.0 - t), (*p_other, t)], p_space)
+  }
+
+  /// Blends any number of colors by weight using premultiplied alpha.
+  /// Weights do not need to add up to 1; they are normalized. Negative weights are treated as 0.
+  /// Returns `Color::transparent()` when the list is empty or all weights are 0.
+  /// - `p_colors`: The colors and their weights.
+  /// - `p_space`: The space in which the colors are blended.
+  pub fn mix_many(p_colors: &[(Color, f32)], p_space: ColorSpace) -> Color {
+    let total_weight: f32 = p_colors.iter().map(|(_, weight)| weight.max(0.0)).sum();
+    if total_weight <= 0.0 {
+      return Color::transparent();
+    }
+
+    let mut sum = [0.0f32; 4];
+    let mut alpha_sum = 0.0;
+    for (color, weight) in p_colors {
+      let weight = weight.max(0.0) / total_weight * (color.a as f32 / 255.0);
+      for (total, component) in sum.iter_mut().zip(p_space.decompose(color)) {
+        *total += component * weight;
+      }
+      alpha_sum += weight;
+    }
+
+    if alpha_sum <= 0.0 {
+      return Color::transparent();
+    }
+    let (r, g, b) = p_space.compose(sum.map(|total| total / alpha_sum));
+    Color::from_rgba(r, g, b, (alpha_sum * 255.0).round() as u8)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lerp_in_srgb_and_linear() {
+    let black = Color::black();
+    let white = Color::white();
+    assert_eq!(black.lerp(&white, 0.5, ColorSpace::Srgb).rgba(), (128, 128, 128, 255));
+    // Half the light of white is much brighter than the sRGB midpoint.
+    assert_eq!(black.lerp(&white, 0.5, ColorSpace::LinearRgb).rgba(), (188, 188, 188, 255));
+    assert_eq!(black.lerp(&white, 0.0, ColorSpace::LinearRgb).rgba(), black.rgba());
+    assert_eq!(black.lerp(&white, 1.0, ColorSpace::LinearRgb).rgba(), white.rgba());
+  }
+
+  #[test]
+  fn premultiplied_lerp_keeps_color_when_fading_out() {
+    let red = Color::red();
+    let transparent = Color::transparent();
+    assert_eq!(red.lerp(&transparent, 0.5, ColorSpace::Srgb).rgba(), (128, 0, 0, 128));
+    assert_eq!(red.lerp_premultiplied(&transparent, 0.5, ColorSpace::Srgb).rgba(), (255, 0, 0, 128));
+  }
+
+  #[test]
+  fn mix_many_normalizes_weights() {
+    let mixed = Color::mix_many(&[(Color::red(), 2.0), (Color::blue(), 2.0)], ColorSpace::Srgb);
+    assert_eq!(mixed.rgba(), (128, 0, 128, 255));
+    let mixed = Color::mix_many(&[(Color::red(), 3.0), (Color::blue(), 1.0)], ColorSpace::Srgb);
+    assert_eq!(mixed.rgba(), (191, 0, 64, 255));
+    assert_eq!(Color::mix_many(&[], ColorSpace::Srgb).rgba(), (0, 0, 0, 0));
+  }
+
+  #[test]
+  fn oklab_round_trips_and_avoids_gray_midpoint() {
+    for color in [Color::red(), Color::from_rgb(12, 200, 99), Color::white(), Color::black()] {
+      let (l, a, b) = rgb_to_oklab(color.r, color.g, color.b);
+      assert_eq!(oklab_to_rgb(l, a, b), color.rgb());
+    }
+    let mid = Color::blue().lerp(&Color::yellow(), 0.5, ColorSpace::Oklab);
+    let srgb_mid = Color::blue().lerp(&Color::yellow(), 0.5, ColorSpace::Srgb);
+    // The perceptual midpoint is lighter than the flat sRGB gray.
+    assert!(mid.luminance() > srgb_mid.luminance());
+  }
+
+  #[test]
+  fn hsl_takes_the_short_way_around_the_wheel() {
+    // Red (0°) to magenta (300°) passes through rose (330°), not green.
+    let mid = Color::red().lerp(&Color::magenta(), 0.5, ColorSpace::Hsl);
+    let (hue, saturation, _) = mid.hsl();
+    assert!((hue - 330.0).abs() < 1.0, "hue was {}", hue);
+    assert!(saturation > 0.99);
+  }
+}
@@ -1,12 +1,14 @@
 pub mod color;
 pub mod colors_list;
+pub mod mix;
 pub mod to_hsl;
 pub mod to_hsv;
 pub mod to_lab;
 pub mod to_rgb;
 
-pub use color::Color;
+pub use color::{Color, ColorError};
 pub use colors_list::*;
+pub use mix::*;
 pub use to_hsl::*;
 pub use to_hsv::*;
 pub use to_lab::*;
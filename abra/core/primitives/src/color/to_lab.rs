@@ -73,3 +73,25 @@ pub fn hsv_to_lab(h: f32, s: f32, v: f32) -> (f32, f32, f32) {
 pub fn srgb_u8_to_linear_f32(v: u8) -> f32 {
   srgb_to_linear(v as f32 / 255.0)
 }
+/// Converts sRGB color to the Oklab perceptual color space.
+/// - `r`: The red channel (0-255).
+/// - `g`: The green channel (0-255).
+/// - `b`: The blue channel (0-255).
+///
+/// Returns a tuple `(L, a, b)` where `L` is in 0-1 and `a`/`b` are roughly in -0.4 to 0.4.
+#[allow(clippy::excessive_precision)]
+pub fn rgb_to_oklab(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+  let r = srgb_to_linear(r as f32 / 255.0);
+  let g = srgb_to_linear(g as f32 / 255.0);
+  let b = srgb_to_linear(b as f32 / 255.0);
+
+  let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+  let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+  let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+  (
+    0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+    1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+    0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+  )
+}
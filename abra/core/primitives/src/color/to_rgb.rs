@@ -137,6 +137,24 @@ pub fn lab_to_rgb(l: f32, a: f32, b: f32) -> (u8, u8, u8) {
   let b_srgb = linear_to_srgb(b_lin).clamp(0.0, 1.0);
   ((r_srgb * 255.0).round() as u8, (g_srgb * 255.0).round() as u8, (b_srgb * 255.0).round() as u8)
 }
+/// Converts Oklab color to RGB color space. Out-of-gamut colors are clamped.
+/// - `l`: The lightness component (0-1).
+/// - `a`: The green-red component.
+/// - `b`: The blue-yellow component.
+///
+/// Returns a tuple `(R, G, B)` representing the RGB color.
+#[allow(clippy::excessive_precision)]
+pub fn oklab_to_rgb(l: f32, a: f32, b: f32) -> (u8, u8, u8) {
+  let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+  let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+  let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+  let r_lin = 4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_;
+  let g_lin = -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_;
+  let b_lin = -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_;
+
+  (linear_f32_to_srgb_u8(r_lin), linear_f32_to_srgb_u8(g_lin), linear_f32_to_srgb_u8(b_lin))
+}
 /// Converts a linear RGB channel (0-1) to an sRGB u8 (0-255) with gamma correction
 /// and clamping applied.
 /// - `c`: The linear RGB channel value (0-1).
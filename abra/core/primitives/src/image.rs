@@ -1,10 +1,12 @@
+use core::hash::{Hash, Hasher};
 use core::ops::{Add, Div, Mul, Sub};
 use ndarray::{Array1, Axis};
 use rayon::prelude::*;
 use std::sync::Arc;
 
 use crate::channels::Channels;
-use crate::color::Color;
+use crate::color::{Color, srgb_u8_to_linear_f32};
+use crate::linear_image::LinearImage;
 
 /// Minimal Image type with RGBA buffer representation (Arc-backed for cheap cloning).
 ///
@@ -13,9 +15,12 @@ use crate::color::Color;
 /// array. When a mutation happens, the buffer will be cloned on write using
 /// `Arc::make_mut` (copy-on-write semantics).
 ///
+/// Images compare and hash by content: two images are equal when they have the same dimensions and pixels, so an
+/// image can be used as a key when caching the results of expensive filters.
+///
 /// ```ignore
 /// let mut img = Image::new(64, 64);
-/// img.clear_color(Color::from_rgba(255, 255, 255, 255));
+/// img.fill_solid(Color::from_rgba(255, 255, 255, 255));
 /// ```
 #[derive(Debug, Clone)]
 pub struct Image {
@@ -55,14 +60,34 @@ impl Image {
   /// - `p_channels`: The channel format of the input pixel buffer.
   ///
   /// This function consumes the provided `Vec<u8>` and avoids extra copies when
-  /// possible.
+  /// possible. Panics when the buffer does not hold exactly `width * height` pixels.
   pub fn new_from_pixels(p_width: u32, p_height: u32, p_pixels: Vec<u8>, p_channels: Channels) -> Image {
-    let mut img = Image::new(p_width, p_height);
     match p_channels {
-      Channels::RGBA => img.set_rgba_owned(p_pixels),
-      Channels::RGB => img.set_rgb_owned(p_pixels),
+      Channels::RGBA => {
+        let expected = p_width as usize * p_height as usize * 4;
+        assert_eq!(
+          p_pixels.len(),
+          expected,
+          "RGBA data length mismatch for a {}x{} image: expected {}, got {}",
+          p_width,
+          p_height,
+          expected,
+          p_pixels.len()
+        );
+        // Adopt the buffer as is instead of allocating a zeroed one first.
+        let mut img = Image::new(0u32, 0u32);
+        img.width = p_width;
+        img.height = p_height;
+        img.color_len = p_width * p_height * 4;
+        img.set_rgba_owned(p_pixels);
+        img
+      }
+      Channels::RGB => {
+        let mut img = Image::new(p_width, p_height);
+        img.set_rgb_owned(p_pixels);
+        img
+      }
     }
-    img
   }
 
   /// Create a new image with a solid color fill.
@@ -71,13 +96,40 @@ impl Image {
   /// - `p_height`: The height of the image in pixels.
   /// - `color`: The color to fill the image with.
   ///
+  /// The buffer is allocated already filled, so no per-pixel writes are needed.
+  ///
   /// ```ignore
   /// let img = Image::new_from_color(100, 100, Color::from_rgba(255, 0, 0, 255));
   /// ```
   pub fn new_from_color(p_width: u32, p_height: u32, color: Color) -> Image {
-    let mut img = Image::new(p_width, p_height);
-    img.clear_color(color);
-    img
+    Image {
+      width: p_width,
+      height: p_height,
+      color_len: p_width * p_height * 4,
+      colors: Arc::new(Self::solid_buffer(p_width as usize * p_height as usize, color)),
+      anti_aliasing_level: 4,
+    }
+  }
+
+  /// Create a new fully transparent image.
+  ///
+  /// - `p_width`: The width of the image in pixels.
+  /// - `p_height`: The height of the image in pixels.
+  ///
+  /// This is the same as `Image::new`, which zero-initializes the buffer.
+  pub fn new_transparent(p_width: u32, p_height: u32) -> Image {
+    Image::new(p_width, p_height)
+  }
+
+  /// Build a contiguous RGBA buffer of `p_pixels` pixels of one color.
+  fn solid_buffer(p_pixels: usize, p_color: Color) -> Array1<u8> {
+    let rgba = [p_color.r, p_color.g, p_color.b, p_color.a];
+    if rgba.iter().all(|channel| *channel == p_color.r) {
+      // Grays, black, white and transparent are a single repeated byte, which is a plain memset.
+      Array1::from_elem(p_pixels * 4, p_color.r)
+    } else {
+      Array1::from_vec(rgba.repeat(p_pixels))
+    }
   }
 
   /// Return a zeroed RGBA Vec<u8> the same size as this image.
@@ -90,16 +142,20 @@ impl Image {
   /// Fill the entire image with a solid color.
   ///
   /// - `p_color`: The color to fill into every pixel.
-  pub fn clear_color(&mut self, p_color: Color) {
-    let size = (self.width * self.height) as usize;
-    let mut pixels = Vec::with_capacity(size * 4);
-    for _ in 0..size {
-      pixels.push(p_color.r);
-      pixels.push(p_color.g);
-      pixels.push(p_color.b);
-      pixels.push(p_color.a);
+  ///
+  /// The buffer is written in place when it is not shared with a clone; otherwise a new filled buffer is
+  /// allocated instead of copying pixels that would be overwritten anyway.
+  pub fn fill_solid(&mut self, p_color: Color) {
+    let size = self.width as usize * self.height as usize;
+    match Arc::get_mut(&mut self.colors).and_then(|colors| colors.as_slice_mut()) {
+      Some(pixels) => {
+        let rgba = [p_color.r, p_color.g, p_color.b, p_color.a];
+        pixels
+          .chunks_exact_mut(4)
+          .for_each(|pixel| pixel.copy_from_slice(&rgba));
+      }
+      None => self.colors = Arc::new(Self::solid_buffer(size, p_color)),
     }
-    *Arc::make_mut(&mut self.colors) = Array1::from_shape_vec(size * 4, pixels).unwrap();
   }
 
   /// Replace the pixel buffer with the provided slice of RGBA data.
@@ -229,19 +285,24 @@ impl Image {
   ///   top-left of the source should be placed. Negative values are allowed and
   ///   will clip the source accordingly.
   pub fn draw_image_at(&mut self, p_src: &Image, p_point: (i32, i32)) {
-    let dest_x = p_point.0 as i32;
-    let dest_y = p_point.1 as i32;
-
-    for y in 0..p_src.height as i32 {
-      for x in 0..p_src.width as i32 {
-        let target_x = dest_x + x;
-        let target_y = dest_y + y;
-        if target_x >= 0 && target_y >= 0 && target_x < self.width as i32 && target_y < self.height as i32 {
-          if let Some(pixel) = p_src.get_pixel(x as u32, y as u32) {
-            self.set_pixel(target_x as u32, target_y as u32, pixel);
-          }
-        }
-      }
+    // Only the part of the source that lands inside this image is copied, one row at a time.
+    let (dest_x, dest_y) = (p_point.0 as i64, p_point.1 as i64);
+    let left = dest_x.max(0);
+    let top = dest_y.max(0);
+    let right = (dest_x + p_src.width as i64).min(self.width as i64);
+    let bottom = (dest_y + p_src.height as i64).min(self.height as i64);
+    if left >= right || top >= bottom {
+      return;
+    }
+    let (src_width, dest_width) = (p_src.width as usize, self.width as usize);
+    let row_len = (right - left) as usize * 4;
+    let src_x = (left - dest_x) as usize;
+    let source = p_src.rgba();
+    let pixels = self.colors().as_slice_mut().expect("Image colors must be contiguous");
+    for y in top..bottom {
+      let src_start = ((y - dest_y) as usize * src_width + src_x) * 4;
+      let dest_start = (y as usize * dest_width + left as usize) * 4;
+      pixels[dest_start..dest_start + row_len].copy_from_slice(&source[src_start..src_start + row_len]);
     }
   }
 
@@ -274,14 +335,66 @@ impl Image {
 
   /// Consume the Image and return the underlying RGBA Vec<u8>.
   ///
-  /// If the underlying `Arc` is shared, the buffer will be cloned and returned.
+  /// If the underlying `Arc` is shared, the buffer will be cloned and returned,
+  /// otherwise the buffer is returned without copying.
   pub fn into_rgba_vec(self) -> Vec<u8> {
     match Arc::try_unwrap(self.colors) {
+      Ok(arr) if arr.is_standard_layout() => {
+        let len = arr.len();
+        let (mut data, offset) = arr.into_raw_vec_and_offset();
+        let offset = offset.unwrap_or(0);
+        if offset != 0 || data.len() != len {
+          data = data[offset..offset + len].to_vec();
+        }
+        data
+      }
       Ok(arr) => arr.to_vec(),
       Err(arc) => arc.to_vec(),
     }
   }
 
+  /// Composite the image over a solid background color in place.
+  ///
+  /// - `p_background`: The color placed behind every pixel. An opaque color removes all transparency,
+  ///   while a transparent color leaves the image unchanged.
+  pub fn flatten_onto(&mut self, p_background: Color) {
+    let back_alpha = p_background.a as f32 / 255.0;
+    if back_alpha == 0.0 {
+      return;
+    }
+    let back = [p_background.r as f32, p_background.g as f32, p_background.b as f32];
+    self.mut_pixels(|mut pixel| {
+      let alpha = pixel[3] as f32 / 255.0;
+      let behind = back_alpha * (1.0 - alpha);
+      let out_alpha = alpha + behind;
+      for channel in 0..3 {
+        pixel[channel] = ((pixel[channel] as f32 * alpha + back[channel] * behind) / out_alpha).round() as u8;
+      }
+      pixel[3] = (out_alpha * 255.0).round() as u8;
+    });
+  }
+
+  /// Convert the image from sRGB encoding to linear light, with `f32` values from 0.0 to 1.0.
+  ///
+  /// Averaging, blending and resizing in linear light mixes colors like real light does, which avoids dark
+  /// fringes between bright colors. Convert back with `LinearImage::to_srgb`; the round trip is exact to one level.
+  pub fn to_linear(&self) -> LinearImage {
+    let lut: [f32; 256] = std::array::from_fn(|i| srgb_u8_to_linear_f32(i as u8));
+    let pixels = self
+      .rgba()
+      .par_chunks_exact(4)
+      .flat_map_iter(|p| {
+        [
+          lut[p[0] as usize],
+          lut[p[1] as usize],
+          lut[p[2] as usize],
+          p[3] as f32 / 255.0,
+        ]
+      })
+      .collect();
+    LinearImage::new_from_pixels(self.width, self.height, pixels)
+  }
+
   /// Return an owned Vec<u8> containing only the RGB channels (no alpha).
   pub fn rgb(&self) -> Vec<u8> {
     self
@@ -293,6 +406,55 @@ impl Image {
       .collect()
   }
 
+  /// Split the image into one grayscale image per channel: red, green, blue and alpha, in that order.
+  /// Each channel image stores the channel's value in its red, green and blue channels and is fully opaque,
+  /// so it can be processed with any function and recombined with `merge_channels`.
+  pub fn split_channels(&self) -> Vec<Image> {
+    (0..4)
+      .map(|channel| {
+        let pixels: Vec<u8> = self
+          .rgba()
+          .par_chunks_exact(4)
+          .flat_map_iter(|pixel| [pixel[channel], pixel[channel], pixel[channel], 255])
+          .collect();
+        Image::new_from_pixels(self.width, self.height, pixels, Channels::RGBA)
+      })
+      .collect()
+  }
+
+  /// Combine grayscale channel images, such as those from `split_channels`, into one image.
+  /// The red channel of each image is used as the value of its channel. With the `RGB` layout the result is
+  /// fully opaque.
+  ///
+  /// - `p_channels`: One image per channel of the layout, in red, green, blue (and alpha) order.
+  /// - `p_layout`: The channels the images hold.
+  ///
+  /// Returns an error when the number of images does not match the layout or their sizes differ.
+  pub fn merge_channels(p_channels: &[Image], p_layout: Channels) -> Result<Image, String> {
+    if p_channels.len() != p_layout.count() {
+      return Err(format!("Expected {} channel images for {:?}, got {}", p_layout.count(), p_layout, p_channels.len()));
+    }
+    let (width, height) = (p_channels[0].width, p_channels[0].height);
+    if let Some(other) = p_channels
+      .iter()
+      .find(|image| (image.width, image.height) != (width, height))
+    {
+      return Err(format!(
+        "Channel images must have the same size, got {}x{} and {}x{}",
+        width, height, other.width, other.height
+      ));
+    }
+
+    let sources: Vec<&[u8]> = p_channels.iter().map(|image| image.rgba()).collect();
+    let mut pixels = vec![255u8; width as usize * height as usize * 4];
+    pixels.par_chunks_exact_mut(4).enumerate().for_each(|(i, pixel)| {
+      for (channel, source) in sources.iter().enumerate() {
+        pixel[channel] = source[i * 4];
+      }
+    });
+    Ok(Image::new_from_pixels(width, height, pixels, Channels::RGBA))
+  }
+
   /// Return the image dimensions as a tuple of `T` (generic integer type).
   ///
   /// - `T`: The integer type to convert the dimensions to (for example `usize`).
@@ -388,6 +550,26 @@ impl Image {
       });
   }
 
+  /// Computes a 64-bit digest of the image content: the dimensions and every RGBA byte.
+  ///
+  /// The digest only depends on the content, so it is the same across runs and platforms for identical pixels and
+  /// can be stored, e.g. as part of a cache file name. Different images may share a digest, although it is unlikely.
+  pub fn content_hash(&self) -> u64 {
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    // FNV-1a over whole words, with a rotation so the high bytes of each word reach the low bits of the digest.
+    let mix = |hash: u64, word: u64| (hash ^ word).wrapping_mul(PRIME).rotate_left(29);
+    let mut hash = mix(0xcbf2_9ce4_8422_2325, (self.width as u64) << 32 | self.height as u64);
+    let mut words = self.rgba().chunks_exact(8);
+    for word in &mut words {
+      hash = mix(hash, u64::from_le_bytes(word.try_into().unwrap()));
+    }
+    // The buffer holds whole pixels, so at most one pixel is left over.
+    let mut rest = [0u8; 8];
+    let remainder = words.remainder();
+    rest[..remainder.len()].copy_from_slice(remainder);
+    mix(hash, u64::from_le_bytes(rest))
+  }
+
   #[cfg(test)]
   /// For tests: return a raw pointer to the underlying buffer for pointer comparison
   /// between clones to verify copy-on-write behavior.
@@ -400,6 +582,27 @@ impl Image {
   }
 }
 
+impl PartialEq for Image {
+  /// Compares the dimensions and pixels of two images. Settings such as `anti_aliasing_level` are not content and
+  /// are ignored.
+  fn eq(&self, other: &Self) -> bool {
+    if self.width != other.width || self.height != other.height {
+      return false;
+    }
+    // Clones share their buffer until one of them is changed.
+    Arc::ptr_eq(&self.colors, &other.colors) || self.rgba() == other.rgba()
+  }
+}
+
+impl Eq for Image {}
+
+impl Hash for Image {
+  /// Hashes the content digest, so equal images always hash the same.
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    state.write_u64(self.content_hash());
+  }
+}
+
 impl<T: Into<f32>> Mul<T> for &mut Image {
   type Output = ();
 
@@ -475,3 +678,125 @@ impl<T: Into<f32>> Add<T> for &mut Image {
     });
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn images_compare_and_hash_by_content() {
+    let image = Image::new_from_color(3, 2, Color::from_rgba(10, 20, 30, 255));
+    let mut same = Image::new_from_pixels(3, 2, [10, 20, 30, 255].repeat(6), Channels::RGBA);
+    same.anti_aliasing_level = 1;
+    assert_eq!(image, same);
+    assert_eq!(image.content_hash(), same.content_hash());
+    // The digest is fixed for these pixels, so it can be stored between runs.
+    assert_eq!(image.content_hash(), 0xf076_a118_e359_3901);
+
+    let mut changed = image.clone();
+    changed.set_pixel(2, 1, (10, 20, 31, 255));
+    assert_ne!(image, changed);
+    assert_ne!(image.content_hash(), changed.content_hash());
+    let reshaped = Image::new_from_pixels(2, 3, image.to_rgba_vec(), Channels::RGBA);
+    assert_ne!(image, reshaped);
+    assert_ne!(image.content_hash(), reshaped.content_hash());
+
+    let mut cache = std::collections::HashMap::new();
+    cache.insert(image, "blurred");
+    assert_eq!(cache.get(&same), Some(&"blurred"));
+    assert_eq!(cache.get(&changed), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "RGBA data length mismatch for a 2x2 image: expected 16, got 12")]
+  fn adopting_pixels_checks_their_length() {
+    Image::new_from_pixels(2, 2, vec![0; 12], Channels::RGBA);
+  }
+
+  #[test]
+  fn split_and_merge_channels_round_trip() {
+    let pixels: Vec<u8> = (0..5 * 3 * 4).map(|i| (i * 37 % 256) as u8).collect();
+    let image = Image::new_from_pixels(5, 3, pixels, Channels::RGBA);
+    let channels = image.split_channels();
+    assert_eq!(channels.len(), 4);
+    assert_eq!(channels[1].get_pixel(0, 0), Some((37, 37, 37, 255)));
+
+    let merged = Image::merge_channels(&channels, Channels::RGBA).unwrap();
+    assert_eq!(merged.rgba(), image.rgba());
+    let opaque = Image::merge_channels(&channels[..3], Channels::RGB).unwrap();
+    assert_eq!(opaque.get_pixel(0, 0), Some((0, 37, 74, 255)));
+
+    assert!(Image::merge_channels(&channels[..3], Channels::RGBA).is_err());
+    let mismatched = [channels[0].clone(), channels[1].clone(), Image::new(4, 3)];
+    assert!(Image::merge_channels(&mismatched, Channels::RGB).is_err());
+  }
+
+  #[test]
+  fn pixel_buffer_round_trips_without_copying() {
+    let pixels = vec![7u8; 3 * 2 * 4];
+    let pointer = pixels.as_ptr();
+    let image = Image::new_from_pixels(3, 2, pixels, Channels::RGBA);
+    assert_eq!(image.dimensions::<u32>(), (3, 2));
+    let shared = image.clone();
+    let copied = shared.into_rgba_vec();
+    assert_ne!(copied.as_ptr(), pointer);
+    let moved = image.into_rgba_vec();
+    assert_eq!(moved.as_ptr(), pointer);
+  }
+
+  #[test]
+  fn new_from_color_fills_every_pixel() {
+    let color = Color::from_rgba(10, 20, 30, 40);
+    let image = Image::new_from_color(3, 2, color);
+    assert_eq!(image.rgba(), [10, 20, 30, 40].repeat(6).as_slice());
+    let gray = Image::new_from_color(2, 2, Color::from_rgba(7, 7, 7, 7));
+    assert_eq!(gray.rgba(), [7u8; 16].as_slice());
+    assert_eq!(Image::new_transparent(2, 2).rgba(), [0u8; 16].as_slice());
+  }
+
+  #[test]
+  fn fill_solid_writes_in_place_or_detaches_clones() {
+    let mut image = Image::new(4, 4);
+    let ptr = image.buffer_ptr();
+    image.fill_solid(Color::red());
+    assert_eq!(image.buffer_ptr(), ptr);
+    assert_eq!(image.get_pixel(3, 3), Some((255, 0, 0, 255)));
+
+    let clone = image.clone();
+    image.fill_solid(Color::blue());
+    assert_eq!(image.get_pixel(0, 0), Some((0, 0, 255, 255)));
+    assert_eq!(clone.get_pixel(0, 0), Some((255, 0, 0, 255)));
+  }
+
+  #[test]
+  fn flatten_onto_composites_over_background() {
+    let mut image = Image::new_from_color(2, 1, Color::from_rgba(0, 0, 0, 128));
+    image.set_pixel(1, 0, (0, 0, 0, 0));
+    image.flatten_onto(Color::from_rgba(255, 255, 255, 255));
+    assert_eq!(image.get_pixel(0, 0), Some((127, 127, 127, 255)));
+    assert_eq!(image.get_pixel(1, 0), Some((255, 255, 255, 255)));
+
+    let mut kept = Image::new_from_color(1, 1, Color::from_rgba(10, 20, 30, 40));
+    kept.flatten_onto(Color::from_rgba(0, 0, 0, 0));
+    assert_eq!(kept.get_pixel(0, 0), Some((10, 20, 30, 40)));
+  }
+
+  #[test]
+  fn linear_round_trip_restores_colors() {
+    let pixels: Vec<u8> = (0..=255u8).flat_map(|v| [v, 255 - v, v / 2, 200]).collect();
+    let original = Image::new_from_pixels(256, 1, pixels, Channels::RGBA);
+    let linear = original.to_linear();
+    // Linear light darkens the midtones but keeps black, white and alpha.
+    let pixel = |x: usize| &linear.pixels()[x * 4..x * 4 + 4];
+    assert!((pixel(128)[0] - 0.2159).abs() < 1e-3, "{}", pixel(128)[0]);
+    assert_eq!(pixel(255), &[1.0, 0.0, srgb_u8_to_linear_f32(127), 200.0 / 255.0]);
+    let image = linear.to_srgb();
+
+    for (before, after) in original.rgba().chunks(4).zip(image.rgba().chunks(4)) {
+      for (b, a) in before.iter().zip(after) {
+        assert!(b.abs_diff(*a) <= 1, "{} came back as {}", b, a);
+      }
+      assert_eq!(before[3], after[3]);
+    }
+  }
+}
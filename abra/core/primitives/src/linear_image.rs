@@ -0,0 +1,78 @@
+use rayon::prelude::*;
+
+use crate::channels::Channels;
+use crate::color::linear_f32_to_srgb_u8;
+use crate::image::Image;
+
+/// An image in linear light, created with `Image::to_linear`.
+///
+/// The pixels are `f32` RGBA values from 0.0 to 1.0 with straight (not premultiplied) alpha. Keeping them in
+/// floating point means the darkest shades, which share levels when linear light is stored in 8 bits, survive
+/// processing and the conversion back with `to_srgb`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearImage {
+  width: u32,
+  height: u32,
+  pixels: Vec<f32>,
+}
+
+impl LinearImage {
+  /// Create a linear image from RGBA pixels, row by row from the top left.
+  ///
+  /// - `p_width`: The width of the image in pixels.
+  /// - `p_height`: The height of the image in pixels.
+  /// - `p_pixels`: The linear RGBA values, `width * height * 4` of them.
+  pub fn new_from_pixels(p_width: u32, p_height: u32, p_pixels: Vec<f32>) -> LinearImage {
+    assert_eq!(
+      p_pixels.len(),
+      p_width as usize * p_height as usize * 4,
+      "expected {} RGBA values for a {}x{} linear image",
+      p_width as usize * p_height as usize * 4,
+      p_width,
+      p_height
+    );
+    LinearImage {
+      width: p_width,
+      height: p_height,
+      pixels: p_pixels,
+    }
+  }
+
+  /// Return the image dimensions as a tuple of `T` (generic integer type).
+  pub fn dimensions<T>(&self) -> (T, T)
+  where
+    T: TryFrom<u64>,
+    <T as TryFrom<u64>>::Error: std::fmt::Debug,
+  {
+    (T::try_from(self.width as u64).unwrap(), T::try_from(self.height as u64).unwrap())
+  }
+
+  /// Borrow the linear RGBA values.
+  pub fn pixels(&self) -> &[f32] {
+    &self.pixels
+  }
+
+  /// Borrow the linear RGBA values mutably, e.g. to blend or average them in linear light.
+  pub fn pixels_mut(&mut self) -> &mut [f32] {
+    &mut self.pixels
+  }
+
+  /// Convert the image back to an sRGB encoded `Image`. Values outside 0.0 to 1.0 are clamped.
+  /// This reverses `Image::to_linear`.
+  pub fn to_srgb(&self) -> Image {
+    let pixels = self
+      .pixels
+      .par_chunks_exact(4)
+      .flat_map_iter(|p| {
+        let alpha = (p[3] * 255.0).round().clamp(0.0, 255.0) as u8;
+        [
+          linear_f32_to_srgb_u8(p[0]),
+          linear_f32_to_srgb_u8(p[1]),
+          linear_f32_to_srgb_u8(p[2]),
+          alpha,
+        ]
+      })
+      .collect();
+    Image::new_from_pixels(self.width, self.height, pixels, Channels::RGBA)
+  }
+}
@@ -1,30 +1,32 @@
 use std::sync::Arc;
 
 use abra::canvas::prelude::*;
+use abra::plugin::PluginError;
 use abra::prelude::*;
 
-use crate::{CollagePlugin, CollageStyle};
+use crate::{CollagePlugin, CollageStyle, cell_span};
 
 impl CollagePlugin {
-  pub(crate) fn grid_collage(&mut self) -> Canvas {
-    // Get the total number of cells in the grid.
-    let mut cell_count = 0;
-    if let CollageStyle::Grid(columns, rows) = self.style {
-      cell_count = columns * rows;
-    }
-    // The width of each cell in the grid.
-    let cell_width = self.size.0 / (cell_count as f32).sqrt() as u32;
-    // The height of each cell in the grid.
-    let cell_height = self.size.1 / (cell_count as f32).sqrt() as u32;
+  pub(crate) fn grid_collage(&mut self) -> Result<Canvas, PluginError> {
+    let (columns, rows) = match self.style {
+      CollageStyle::Grid(columns, rows) => (columns.max(1), rows.max(1)),
+      _ => (1, 1),
+    };
+    let ((left, top), (width, height)) = self.content_area();
+    let spacing = self.spacing();
     // Create a canvas sized to the requested collage dimensions to avoid
     // defaulting to the size of the first added child canvas.
     let root_canvas = Canvas::new_blank("Collage", self.size.0, self.size.1);
 
     self.set_background(&root_canvas);
 
-    for i in 0..cell_count {
+    for i in 0..columns * rows {
+      // The cells shrink to leave the spacing between them.
+      let (cell_x, cell_width) = cell_span(width, columns, i % columns, spacing);
+      let (cell_y, cell_height) = cell_span(height, rows, i / columns, spacing);
+
       // Get a random image from the provided images.
-      let original_image = self.select_random_image();
+      let original_image = self.select_random_image()?;
       let mut image = (*original_image).clone();
 
       // Rotate the image if the option.rotation is set
@@ -32,7 +34,7 @@ impl CollagePlugin {
         image.rotate(self.select_range(rotation), None);
       }
 
-      let trans_image = Arc::new(Image::new_from_color(cell_width, cell_height, Color::transparent()));
+      let trans_image = Arc::new(Image::new_transparent(cell_width.max(1), cell_height.max(1)));
       let canvas = Canvas::new("Cell")
         .add_layer_from_image("empty", trans_image, None)
         .add_layer_from_image(
@@ -45,14 +47,9 @@ impl CollagePlugin {
           ),
         );
 
-      root_canvas.add_canvas(
-        canvas,
-        Some(AddCanvasOptions::new().with_position(
-          ((i % (cell_count as f32).sqrt() as u32) * cell_width) as i32,
-          ((i / (cell_count as f32).sqrt() as u32) * cell_height) as i32,
-        )),
-      );
+      root_canvas
+        .add_canvas(canvas, Some(AddCanvasOptions::new().with_position((left + cell_x) as i32, (top + cell_y) as i32)));
     }
-    root_canvas
+    Ok(root_canvas)
   }
 }
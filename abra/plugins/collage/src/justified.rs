@@ -0,0 +1,80 @@
+use abra::canvas::prelude::*;
+use abra::plugin::PluginError;
+
+use crate::{CollagePlugin, CollageStyle};
+
+impl CollagePlugin {
+  pub(crate) fn justified_collage(&mut self) -> Result<Canvas, PluginError> {
+    let target_row_height = match self.style {
+      CollageStyle::Justified { target_row_height } => target_row_height.max(1),
+      _ => self.size.1,
+    };
+    let root_canvas = Canvas::new_blank("Justified Collage", self.size.0, self.size.1);
+
+    self.set_background(&root_canvas);
+
+    let ((left, top), (width, height)) = self.content_area();
+    if width == 0 {
+      return Ok(root_canvas);
+    }
+    let spacing = self.spacing();
+    let bottom = top + height;
+
+    let mut row_top = top;
+    while row_top < bottom {
+      // Add images to the row until scaling it to the full width makes it no taller than the target.
+      let mut row = vec![];
+      let row_width = loop {
+        let image = self.select_random_image()?;
+        let (image_width, image_height) = image.dimensions::<u32>();
+        row.push((image, image_width.max(1) as f32 / image_height.max(1) as f32));
+        let aspects: f32 = row.iter().map(|(_, aspect)| aspect).sum();
+        let row_width = width.saturating_sub(spacing * (row.len() as u32 - 1));
+        if row_width as f32 / aspects <= target_row_height as f32 || row_width == 0 {
+          break row_width;
+        }
+      };
+
+      let aspects: Vec<f32> = row.iter().map(|(_, aspect)| *aspect).collect();
+      let (row_height, widths) = justify_row(&aspects, row_width);
+      // The images of the last row are cropped so that they stop at the bottom.
+      let visible_height = row_height.min(bottom - row_top);
+      let mut x = left;
+      for ((image, _), cell_width) in row.into_iter().zip(widths) {
+        self.add_fitted_image(&root_canvas, image, (x as i32, row_top as i32), (cell_width, visible_height));
+        x += cell_width + spacing;
+      }
+      row_top += row_height + spacing;
+    }
+
+    Ok(root_canvas)
+  }
+}
+
+/// Scales a row of images with the given aspect ratios to span a width exactly.
+/// Returns the row height and the width of each image, where the last image absorbs any rounding.
+fn justify_row(p_aspects: &[f32], p_width: u32) -> (u32, Vec<u32>) {
+  let total: f32 = p_aspects.iter().sum();
+  let height = ((p_width as f32 / total).round() as u32).max(1);
+  let mut widths: Vec<u32> = p_aspects
+    .iter()
+    .map(|aspect| (aspect / total * p_width as f32).round() as u32)
+    .collect();
+  if let Some((last, rest)) = widths.split_last_mut() {
+    *last = p_width.saturating_sub(rest.iter().sum());
+  }
+  (height, widths)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rows_span_the_width() {
+    let (height, widths) = justify_row(&[1.5, 0.75, 1.0], 1000);
+    assert_eq!(height, 308);
+    assert_eq!(widths.iter().sum::<u32>(), 1000);
+    assert_eq!(widths, vec![462, 231, 307]);
+  }
+}
@@ -1,25 +1,24 @@
 use std::sync::Arc;
 
 use abra::canvas::prelude::*;
+use abra::plugin::PluginError;
 use abra::prelude::*;
 
 use rand::{Rng, prelude::SliceRandom};
 use rayon::prelude::*;
 
-use crate::{CollagePlugin, CollageStyle};
+use crate::{CollagePlugin, CollageStyle, cell_span};
 
 impl CollagePlugin {
-  pub(crate) fn layered_grid_collage(&mut self) -> Canvas {
+  pub(crate) fn layered_grid_collage(&mut self) -> Result<Canvas, PluginError> {
     // Get grid dimensions (columns, rows) and total number of cells.
     let (columns, rows, cell_count) = if let CollageStyle::LayeredGrid(c, r) = self.style {
       (c, r, c * r)
     } else {
       (1, 1, 1)
     };
-    // The width of each cell in the grid.
-    let cell_width = self.size.0 / columns;
-    // The height of each cell in the grid.
-    let cell_height = self.size.1 / rows;
+    let ((left, top), (width, height)) = self.content_area();
+    let spacing = self.spacing();
     // The root canvas for the collage. Create with explicit size so it doesn't
     // automatically resize to the size of the first child canvas.
     let root_canvas = Canvas::new_blank("Collage", self.size.0, self.size.1);
@@ -31,7 +30,7 @@ impl CollagePlugin {
 
     let mut selected_data = vec![];
     for _ in 0..cell_count {
-      let image = self.select_random_image();
+      let image = self.select_random_image()?;
       let rotation = self
         .options
         .as_ref()
@@ -51,12 +50,15 @@ impl CollagePlugin {
       .enumerate()
       .map(|(idx, (image, rotation, scale))| {
         let i = item_vec[idx];
-        let position = (((i % columns) * cell_width) as i32, ((i / columns) * cell_height) as i32);
+        let (cell_x, cell_width) = cell_span(width, columns, i % columns, spacing);
+        let (cell_y, cell_height) = cell_span(height, rows, i / columns, spacing);
+        let position = ((left + cell_x) as i32, (top + cell_y) as i32);
 
-        let (scale_width, scale_height) = ((cell_width as f32 * scale) as u32, (cell_height as f32 * scale) as u32);
+        let (scale_width, scale_height) =
+          (((cell_width as f32 * scale) as u32).max(1), ((cell_height as f32 * scale) as u32).max(1));
 
         // Create canvas and apply transformations in parallel
-        let transform_image = Arc::new(Image::new_from_color(scale_width, scale_height, Color::transparent()));
+        let transform_image = Arc::new(Image::new_transparent(scale_width, scale_height));
         let canvas = Canvas::new("Cell")
           .add_layer_from_image("empty", transform_image, None)
           .add_layer_from_image(
@@ -95,6 +97,6 @@ impl CollagePlugin {
       root_canvas.add_canvas(canvas, Some(canvas_options.clone()));
     }
 
-    root_canvas
+    Ok(root_canvas)
   }
 }
@@ -5,11 +5,14 @@ use abra::drawing::prelude::*;
 use abra::plugin::{Plugin, PluginError, PluginResult};
 use abra::prelude::*;
 
+use rand::SeedableRng;
 use rand::prelude::{IndexedRandom, Rng};
-use rand::rngs::ThreadRng;
+use rand::rngs::StdRng;
 
 mod grid;
+mod justified;
 mod layered_grid;
+mod masonry;
 mod random;
 
 pub mod prelude {
@@ -32,6 +35,14 @@ pub struct CollageOptions {
   background: Fill,
   /// The effects to apply to each layer in the collage.
   effects: Option<LayerEffects>,
+  /// The gap in pixels between neighboring images.
+  spacing: u32,
+  /// The gap in pixels between the images and the edges of the canvas.
+  padding: u32,
+  /// How likely each input image is to be selected, in input order.
+  weights: Option<Vec<f32>>,
+  /// Whether images are reused once all of them have been placed.
+  allow_repeats: bool,
 }
 
 impl CollageOptions {
@@ -42,6 +53,10 @@ impl CollageOptions {
       scale: (1.0, 1.0),
       background: Fill::Solid(Color::transparent()),
       effects: None,
+      spacing: 0,
+      padding: 0,
+      weights: None,
+      allow_repeats: true,
     }
   }
 
@@ -68,6 +83,38 @@ impl CollageOptions {
     self.effects = Some(effects);
     self
   }
+
+  /// Sets the gap between neighboring images, which shows the background.
+  /// Grid styles shrink their cells to make room for it, while `Random` tries to keep images this far apart.
+  /// - `p_spacing`: The gap in pixels.
+  pub fn with_spacing(mut self, p_spacing: u32) -> Self {
+    self.spacing = p_spacing;
+    self
+  }
+
+  /// Sets the gap between the images and the edges of the canvas, which shows the background.
+  /// - `p_padding`: The gap in pixels.
+  pub fn with_padding(mut self, p_padding: u32) -> Self {
+    self.padding = p_padding;
+    self
+  }
+
+  /// Sets how likely each image is to be picked, so that some images show up more often than others.
+  /// An image with a weight of 2 is twice as likely to be picked as one with a weight of 1,
+  /// and one with a weight of 0 is only used once every other image has been.
+  /// - `p_weights`: One weight per input image, in input order. Negative weights are treated as 0.
+  pub fn with_weights(mut self, p_weights: &[f32]) -> Self {
+    self.weights = Some(p_weights.to_vec());
+    self
+  }
+
+  /// Sets whether images are reused once all of them have been placed. Enabled by default.
+  /// When disabled, creating a collage that needs more images than were provided fails.
+  /// - `p_allow_repeats`: Whether images can be reused.
+  pub fn with_allow_repeats(mut self, p_allow_repeats: bool) -> Self {
+    self.allow_repeats = p_allow_repeats;
+    self
+  }
 }
 
 pub enum CollageStyle {
@@ -85,6 +132,16 @@ pub enum CollageStyle {
   /// A random collage where images are placed at random positions.
   /// - `count`: Number of images to include in the random collage.
   Random(u32),
+  /// A masonry collage of equal width columns, where each image keeps its aspect ratio
+  /// and goes to the shortest column. Images repeat until every column reaches the bottom,
+  /// where the last image of each column is cropped to fit.
+  /// - `columns`: Number of columns.
+  Masonry { columns: u32 },
+  /// A justified collage of rows that span the full width, where each image keeps its aspect ratio
+  /// and each row is scaled to be about the target height. Images repeat until the rows reach the bottom,
+  /// where the images of the last row are cropped to fit.
+  /// - `target_row_height`: The preferred height of a row in pixels.
+  Justified { target_row_height: u32 },
 }
 
 /// A plugin that creates collages from multiple images.
@@ -100,7 +157,8 @@ pub struct CollagePlugin {
   /// Indices of images already selected to avoid duplicates.
   selected_images: Vec<usize>,
   /// Random number generator for consistent randomness across selections.
-  rng: ThreadRng,
+  /// Seeded from entropy unless `with_seed` is used.
+  rng: StdRng,
 }
 
 impl CollagePlugin {
@@ -113,7 +171,7 @@ impl CollagePlugin {
       images: loaded.all(),
       options: None,
       selected_images: Vec::new(),
-      rng: rand::rng(),
+      rng: StdRng::from_os_rng(),
     }
   }
 
@@ -127,23 +185,66 @@ impl CollagePlugin {
     self
   }
 
-  /// Selects a random image from the provided images.
-  /// Ensures no duplicates until all images have been used.
+  /// Seeds the random number generator so that image selection, placement, rotation and scale
+  /// are the same on every run with the same seed and images.
+  /// - `p_seed`: The seed for the random number generator.
+  pub fn with_seed(mut self, p_seed: u64) -> Self {
+    self.rng = StdRng::seed_from_u64(p_seed);
+    self
+  }
+
+  /// Selects a random image from the provided images, biased by the image weights.
+  /// Ensures no duplicates until all images have been used, after which images repeat
+  /// unless repeats are disabled.
   /// If there are more images than cells in the collage, not all images will be used.
-  fn select_random_image(&mut self) -> Arc<Image> {
+  fn select_random_image(&mut self) -> Result<Arc<Image>, PluginError> {
+    if self.images.is_empty() {
+      return Err(PluginError::invalid_parameters("CollagePlugin needs at least one image"));
+    }
+
     let available_indices: Vec<usize> = (0..self.images.len())
       .filter(|i| !self.selected_images.contains(i))
       .collect();
 
     if available_indices.is_empty() {
+      if !self.options.as_ref().is_none_or(|opts| opts.allow_repeats) {
+        return Err(PluginError::invalid_parameters(format!(
+          "CollagePlugin needs more than the {} images provided because repeats are disabled",
+          self.images.len()
+        )));
+      }
       // Reset selected images if all have been used
       self.selected_images.clear();
       return self.select_random_image();
     }
 
-    let &selected_index = available_indices.choose(&mut self.rng).unwrap();
+    let weights = self.options.as_ref().and_then(|opts| opts.weights.as_ref());
+    let weight = |index: &usize| {
+      weights
+        .and_then(|weights| weights.get(*index))
+        .map_or(1.0, |weight| weight.max(0.0))
+    };
+    // Images with no weight are still picked once every weighted image has been used.
+    let &selected_index = match available_indices.choose_weighted(&mut self.rng, weight) {
+      Ok(index) => index,
+      Err(_) => available_indices.choose(&mut self.rng).unwrap(),
+    };
     self.selected_images.push(selected_index);
-    self.images[selected_index].clone()
+    Ok(self.images[selected_index].clone())
+  }
+
+  /// Checks that the options match the provided images.
+  fn validate(&self) -> Result<(), PluginError> {
+    if let Some(weights) = self.options.as_ref().and_then(|opts| opts.weights.as_ref())
+      && weights.len() != self.images.len()
+    {
+      return Err(PluginError::invalid_parameters(format!(
+        "CollagePlugin got {} weights for {} images",
+        weights.len(),
+        self.images.len()
+      )));
+    }
+    Ok(())
   }
 
   fn select_range(&mut self, range: (f32, f32)) -> f32 {
@@ -151,6 +252,41 @@ impl CollagePlugin {
     self.rng.random_range(min..=max)
   }
 
+  /// Gets the gap between neighboring images.
+  fn spacing(&self) -> u32 {
+    self.options.as_ref().map_or(0, |opts| opts.spacing)
+  }
+
+  /// Gets the area inside the padding as its top left corner and size.
+  fn content_area(&self) -> ((u32, u32), (u32, u32)) {
+    let padding = self.options.as_ref().map_or(0, |opts| opts.padding);
+    let padding = padding.min(self.size.0 / 2).min(self.size.1 / 2);
+    ((padding, padding), (self.size.0 - padding * 2, self.size.1 - padding * 2))
+  }
+
+  /// Adds an image to the collage scaled to fill a cell, along with the collage's layer effects.
+  /// The image is cropped around its center when the cell does not have the same aspect ratio.
+  fn add_fitted_image(&self, root_canvas: &Canvas, image: Arc<Image>, position: (i32, i32), size: (u32, u32)) {
+    let cell_image = Arc::new(Image::new_transparent(size.0.max(1), size.1.max(1)));
+    let canvas = Canvas::new("Cell")
+      .add_layer_from_image("empty", cell_image, None)
+      .add_layer_from_image(
+        "image",
+        image,
+        Some(
+          NewLayerOptions::new()
+            .with_anchor(Anchor::Center)
+            .with_size(LayerSize::Cover(None)),
+        ),
+      );
+    if let Some(effects) = self.options.as_ref().and_then(|opts| opts.effects.clone())
+      && let Some(image_layer) = canvas.get_layer_by_name("image")
+    {
+      image_layer.set_effects(effects);
+    }
+    root_canvas.add_canvas(canvas, Some(AddCanvasOptions::new().with_position(position.0, position.1)));
+  }
+
   fn set_background(&self, root_canvas: &Canvas) {
     if let Some(options) = &self.options {
       let background = match options.background.clone() {
@@ -162,7 +298,7 @@ impl CollagePlugin {
           let mut bg_image = Image::new(self.size.0, self.size.1);
           let brush = Brush::new().with_color(gradient.clone());
           let area = Area::new_from_image(&bg_image);
-          fill_area_with_brush(&mut bg_image, &area, &brush);
+          fill_area_with_brush(&mut bg_image, &area, &brush, None);
 
           Canvas::new("Background Color").add_layer_from_image("background color", Arc::new(bg_image), None)
         }
@@ -183,6 +319,20 @@ impl CollagePlugin {
   }
 }
 
+/// Splits a length into equal cells separated by a gap, spreading the leftover pixels so the cells fill it exactly.
+/// Returns the offset and length of a cell.
+/// - `p_length`: The length to split.
+/// - `p_count`: The number of cells.
+/// - `p_index`: The index of the cell.
+/// - `p_spacing`: The gap between neighboring cells.
+pub(crate) fn cell_span(p_length: u32, p_count: u32, p_index: u32, p_spacing: u32) -> (u32, u32) {
+  let count = p_count.max(1);
+  let available = p_length.saturating_sub(p_spacing * (count - 1));
+  let start = p_index * available / count;
+  let end = (p_index + 1) * available / count;
+  (start + p_index * p_spacing, end - start)
+}
+
 impl Plugin for CollagePlugin {
   fn name(&self) -> &str {
     "Collage"
@@ -195,17 +345,28 @@ impl Plugin for CollagePlugin {
   fn apply(&mut self) -> Result<PluginResult, PluginError> {
     let start = std::time::Instant::now();
     let mut plugin_result = PluginResult::new();
+    self.validate()?;
+    // Every collage starts with all images available.
+    self.selected_images.clear();
     match &self.style {
       CollageStyle::Grid(_columns, _rows) => {
-        let collage_result = self.grid_collage();
+        let collage_result = self.grid_collage()?;
         plugin_result.add_canvas(collage_result);
       }
       CollageStyle::LayeredGrid(_columns, _rows) => {
-        let collage_result = self.layered_grid_collage();
+        let collage_result = self.layered_grid_collage()?;
         plugin_result.add_canvas(collage_result);
       }
       CollageStyle::Random(_count) => {
-        let collage_result = self.random_collage();
+        let collage_result = self.random_collage()?;
+        plugin_result.add_canvas(collage_result);
+      }
+      CollageStyle::Masonry { .. } => {
+        let collage_result = self.masonry_collage()?;
+        plugin_result.add_canvas(collage_result);
+      }
+      CollageStyle::Justified { .. } => {
+        let collage_result = self.justified_collage()?;
         plugin_result.add_canvas(collage_result);
       }
     };
@@ -218,3 +379,85 @@ impl Plugin for CollagePlugin {
     Ok(plugin_result)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn plugin(p_seed: u64) -> CollagePlugin {
+    let images: Vec<Image> = (0..5).map(|_| Image::new(4, 4)).collect();
+    CollagePlugin::new((64, 64), ImageLoader::FromImages(images).load()).with_seed(p_seed)
+  }
+
+  #[test]
+  fn same_seed_makes_same_choices() {
+    let draw = |p_plugin: &mut CollagePlugin| {
+      (0..10)
+        .map(|_| {
+          let image = p_plugin.select_random_image().unwrap();
+          let index = p_plugin.images.iter().position(|other| Arc::ptr_eq(other, &image));
+          (index, p_plugin.select_range((-45.0, 45.0)).to_bits())
+        })
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(draw(&mut plugin(7)), draw(&mut plugin(7)));
+    assert_ne!(draw(&mut plugin(7)), draw(&mut plugin(8)));
+  }
+
+  #[test]
+  fn weights_and_repeats() {
+    let images: Vec<Image> = (1..=3).map(|size| Image::new(size, size)).collect();
+    let options = CollageOptions::new().with_weights(&[8.0, 1.0, 0.0]);
+    let mut plugin = CollagePlugin::new((64, 64), ImageLoader::FromImages(images.clone()).load())
+      .with_options(options.clone())
+      .with_seed(3);
+    let mut first_picks = [0; 3];
+    for _ in 0..200 {
+      plugin.selected_images.clear();
+      let size = plugin.select_random_image().unwrap().dimensions::<u32>().0;
+      first_picks[size as usize - 1] += 1;
+    }
+    assert!(first_picks[0] > first_picks[1] * 4, "{:?}", first_picks);
+    assert_eq!(first_picks[2], 0);
+
+    // A 2x2 grid needs four cells but only three images were given.
+    let mut plugin = CollagePlugin::new((64, 64), ImageLoader::FromImages(images.clone()).load())
+      .with_options(options.clone().with_allow_repeats(false));
+    assert!(matches!(plugin.apply(), Err(PluginError::InvalidParameters(_))));
+    let mut plugin = CollagePlugin::new((64, 64), ImageLoader::FromImages(images).load())
+      .with_style(CollageStyle::Grid(3, 1))
+      .with_options(options.with_allow_repeats(false));
+    assert!(plugin.apply().is_ok());
+  }
+
+  #[test]
+  fn cells_fill_the_length() {
+    let spans: Vec<_> = (0..3).map(|index| cell_span(100, 3, index, 0)).collect();
+    assert_eq!(spans, vec![(0, 33), (33, 33), (66, 34)]);
+    let spans: Vec<_> = (0..2).map(|index| cell_span(100, 2, index, 20)).collect();
+    assert_eq!(spans, vec![(0, 40), (60, 40)]);
+  }
+
+  #[test]
+  fn grid_spacing_shows_background() {
+    let images = vec![Image::new_from_color(10, 10, Color::white()); 4];
+    let mut plugin = CollagePlugin::new((100, 100), ImageLoader::FromImages(images).load())
+      .with_style(CollageStyle::Grid(2, 2))
+      .with_options(
+        CollageOptions::new()
+          .with_background(Color::red())
+          .with_spacing(20)
+          .with_padding(5),
+      );
+    let image = plugin.grid_collage().unwrap().as_image();
+    let red = Some((255, 0, 0, 255));
+    let white = Some((255, 255, 255, 255));
+    // Padding, the gutters between cells and their crossing show the background.
+    for (x, y) in [(2, 2), (50, 20), (20, 50), (50, 50), (97, 97)] {
+      assert_eq!(image.get_pixel(x, y), red, "({}, {})", x, y);
+    }
+    for (x, y) in [(20, 20), (80, 20), (20, 80), (80, 80)] {
+      assert_eq!(image.get_pixel(x, y), white, "({}, {})", x, y);
+    }
+  }
+}
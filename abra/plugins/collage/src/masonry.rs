@@ -0,0 +1,39 @@
+use abra::canvas::prelude::*;
+use abra::plugin::PluginError;
+
+use crate::{CollagePlugin, CollageStyle, cell_span};
+
+impl CollagePlugin {
+  pub(crate) fn masonry_collage(&mut self) -> Result<Canvas, PluginError> {
+    let columns = match self.style {
+      CollageStyle::Masonry { columns } => columns.max(1),
+      _ => 1,
+    };
+    let root_canvas = Canvas::new_blank("Masonry Collage", self.size.0, self.size.1);
+
+    self.set_background(&root_canvas);
+
+    let ((left, top), (width, height)) = self.content_area();
+    let spacing = self.spacing();
+    let bottom = top + height;
+    // The top of the next image in each column.
+    let mut tops = vec![top; columns as usize];
+    while let Some((column, &image_top)) = tops
+      .iter()
+      .enumerate()
+      .filter(|(_, y)| **y < bottom)
+      .min_by_key(|(_, y)| **y)
+    {
+      let image = self.select_random_image()?;
+      let (x, cell_width) = cell_span(width, columns, column as u32, spacing);
+      let (image_width, image_height) = image.dimensions::<u32>();
+      let cell_height = ((image_height as f32 * cell_width as f32 / image_width.max(1) as f32).round() as u32).max(1);
+      // The last image of a column is cropped so that it stops at the bottom.
+      let visible_height = cell_height.min(bottom - image_top);
+      self.add_fitted_image(&root_canvas, image, ((left + x) as i32, image_top as i32), (cell_width, visible_height));
+      tops[column] = image_top + cell_height + spacing;
+    }
+
+    Ok(root_canvas)
+  }
+}
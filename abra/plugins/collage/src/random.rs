@@ -1,6 +1,7 @@
 use crate::{CollagePlugin, CollageStyle};
 
 use abra::canvas::prelude::*;
+use abra::plugin::PluginError;
 use abra::prelude::*;
 
 use rand::Rng;
@@ -9,7 +10,7 @@ use rayon::prelude::*;
 use std::sync::Arc;
 
 impl CollagePlugin {
-  pub(crate) fn random_collage(&mut self) -> Canvas {
+  pub(crate) fn random_collage(&mut self) -> Result<Canvas, PluginError> {
     // Get the total number of images to include in the collage.
     // The ColorStyle::Random will always be true here.
     let total_images = match &self.style {
@@ -17,31 +18,56 @@ impl CollagePlugin {
       _ => self.images.len() as u32,
     };
 
-    let (root_canvas_width, root_canvas_height) = self.size;
-    let root_canvas = Canvas::new_blank("Random Collage", root_canvas_width, root_canvas_height);
+    let root_canvas = Canvas::new_blank("Random Collage", self.size.0, self.size.1);
+    let ((left, top), (width, height)) = self.content_area();
+    let spacing = self.spacing();
+    // The bounds of the images placed so far, to keep new images away from them.
+    let mut placed: Vec<(i32, i32, i32, i32)> = vec![];
 
     self.set_background(&root_canvas);
 
     (0..total_images)
       .into_iter()
       .map(|_| {
-        let image = self.select_random_image();
+        let image = self.select_random_image()?;
         let options = self.options.as_mut().unwrap().clone();
         let rotation = self.select_range(options.rotation);
         let scale = self.select_range(options.scale);
-        let (width, height) = image.dimensions::<u32>();
-        let width_range = root_canvas_width.saturating_sub((width as f32 * scale) as u32);
-        let height_range = root_canvas_height.saturating_sub((height as f32 * scale) as u32);
-        let position =
-          PointF::new(self.rng.random_range(0..=width_range as i32), self.rng.random_range(0..=height_range as i32));
-        (image, rotation, scale, position)
+        let (image_width, image_height) = image.dimensions::<u32>();
+        let (scale_width, scale_height) = ((image_width as f32 * scale) as u32, (image_height as f32 * scale) as u32);
+        let width_range = width.saturating_sub(scale_width);
+        let height_range = height.saturating_sub(scale_height);
+        let mut random_bounds = || {
+          let x = (left + self.rng.random_range(0..=width_range)) as i32;
+          let y = (top + self.rng.random_range(0..=height_range)) as i32;
+          (x, y, x + scale_width as i32, y + scale_height as i32)
+        };
+        // Try a few positions and keep the first that is far enough from the placed images,
+        // falling back to the one furthest from them.
+        let mut bounds = random_bounds();
+        if spacing > 0 {
+          let mut best = (separation(&placed, bounds), bounds);
+          for _ in 0..PLACEMENT_ATTEMPTS {
+            if best.0 >= spacing as i32 {
+              break;
+            }
+            let candidate = random_bounds();
+            let candidate_separation = separation(&placed, candidate);
+            if candidate_separation > best.0 {
+              best = (candidate_separation, candidate);
+            }
+          }
+          bounds = best.1;
+        }
+        placed.push(bounds);
+        Ok((image, rotation, scale, PointF::new(bounds.0, bounds.1)))
       })
-      .collect::<Vec<(Arc<Image>, f32, f32, PointF)>>()
+      .collect::<Result<Vec<(Arc<Image>, f32, f32, PointF)>, PluginError>>()?
       .into_par_iter()
-      .for_each(|(image, rotation, scale, position)| {
+      .map(|(image, rotation, scale, position)| {
         let (width, height) = image.dimensions::<u32>();
         let (scale_width, scale_height) = ((width as f32 * scale) as u32, (height as f32 * scale) as u32);
-        let transform_image = Arc::new(Image::new_from_color(scale_width, scale_height, Color::transparent()));
+        let transform_image = Arc::new(Image::new_transparent(scale_width, scale_height));
         let canvas = Canvas::new("Random Image")
           .add_layer_from_image("empty", transform_image, None)
           .add_layer_from_image("image", image, Some(NewLayerOptions::new().with_size(LayerSize::Cover(None))));
@@ -59,9 +85,33 @@ impl CollagePlugin {
           .with_position(position.x as i32, position.y as i32)
           .with_rotation(rotation);
 
-        root_canvas.add_canvas(canvas, Some(canvas_options.clone()));
+        (canvas, canvas_options)
+      })
+      .collect::<Vec<_>>()
+      .into_iter()
+      // Add the canvases in selection order so that seeded collages stack the same way on every run.
+      .for_each(|(canvas, canvas_options)| {
+        root_canvas.add_canvas(canvas, Some(canvas_options));
       });
 
-    root_canvas
+    Ok(root_canvas)
   }
 }
+
+/// The number of extra positions tried for each image when looking for one that keeps the spacing.
+const PLACEMENT_ATTEMPTS: usize = 20;
+
+/// Gets the smallest gap between a rectangle and any of the placed rectangles, which is negative when they overlap.
+/// The rectangles are given as `(left, top, right, bottom)`.
+fn separation(p_placed: &[(i32, i32, i32, i32)], p_bounds: (i32, i32, i32, i32)) -> i32 {
+  let (left, top, right, bottom) = p_bounds;
+  p_placed
+    .iter()
+    .map(|&(other_left, other_top, other_right, other_bottom)| {
+      let gap_x = (other_left - right).max(left - other_right);
+      let gap_y = (other_top - bottom).max(top - other_bottom);
+      gap_x.max(gap_y)
+    })
+    .min()
+    .unwrap_or(i32::MAX)
+}
@@ -11,21 +11,22 @@ pub fn main() {
   let soft_brush = Brush::new().with_size(20).with_color(Color::red()).with_hardness(0.0);
 
   // Paint with soft brush at a few positions
-  paint_with_brush(&mut image, 50.0, 50.0, &soft_brush);
-  paint_with_brush(&mut image, 100.0, 50.0, &soft_brush);
-  paint_with_brush(&mut image, 150.0, 50.0, &soft_brush);
+  paint_with_brush(&mut image, 50.0, 50.0, &soft_brush, None);
+  paint_with_brush(&mut image, 100.0, 50.0, &soft_brush, None);
+  paint_with_brush(&mut image, 150.0, 50.0, &soft_brush, None);
 
   // Create a blue hard-edged brush (hardness = 1.0)
   let hard_brush = Brush::new().with_size(15).with_color(Color::blue()).with_hardness(1.0);
 
   // Paint with hard brush at different positions
-  paint_with_brush(&mut image, 50.0, 100.0, &hard_brush);
-  paint_with_brush(&mut image, 100.0, 100.0, &hard_brush);
+  paint_with_brush(&mut image, 50.0, 100.0, &hard_brush, None);
+  paint_with_brush(&mut image, 100.0, 100.0, &hard_brush, None);
 
-  // Create a green brush for path stroking
+  // Create a rainbow brush for path stroking whose color changes from the start to the end of the stroke
   let stroke_brush = Brush::new()
     .with_size(20)
     .with_color(Gradient::hue())
+    .with_color_dynamics(ColorDynamics::AlongStroke)
     .with_hardness(0.0);
 
   // Create a path and stroke it with the brush
@@ -38,7 +39,7 @@ pub fn main() {
 
   // Stroke the path with the brush to create a continuous line
 
-  stroke_with_brush(&mut image, &path, &stroke_brush);
+  stroke_with_brush(&mut image, &path, &stroke_brush, None);
 
   image.save("out/brush.png", None);
 }
@@ -15,7 +15,7 @@ pub fn main() {
   fill_image.resize_width(size.0, TransformAlgorithm::EdgeDirectNEDI);
 
   let area = Heart::new().fit(size);
-  let filled_image = fill(area.clone(), &fill_image);
+  let filled_image = fill(area.clone(), &fill_image, None);
 
   // let filled_image = area.fill(Color::from_rgba(255, 0, 0, 255));
   let (width, height) = filled_image.dimensions::<i32>();
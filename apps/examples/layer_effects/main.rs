@@ -14,7 +14,7 @@ pub fn main() {
   if let Some(layer) = canvas.get_layer_by_name("Image") {
     layer
       .effects()
-      .with_drop_shadow(DropShadow::new().with_size(40.0))
+      .with_drop_shadow(DropShadow::new().with_blur(40.0))
       .with_stroke(Stroke::new().with_size(20).with_opacity(0.5));
   }
 
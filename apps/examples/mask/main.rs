@@ -9,7 +9,7 @@ pub fn main() {
   let mut mask = Mask::new_from_image(&image);
 
   let size = image.size();
-  let star = Star::new().fit_with_aspect(size / 2, AspectRatio::meet());
+  let star = Star::new().to_area().fit_with_aspect(size / 2, AspectRatio::meet());
   let heart = Heart::new().fit_with_aspect(size - 100, AspectRatio::meet());
   mask.draw_area(&star.with_feather(30), Color::black(), None);
   mask.draw_area(&heart.with_feather(30), Color::black(), (5, 200));
@@ -18,7 +18,7 @@ pub fn main() {
   let load_start = std::time::Instant::now();
 
   let loader = ImageLoader::FromGlob(vec!["assets/**/*{boob,tit,chest}*.{jpg}"]).load();
-  let img = loader.at(1u8);
+  let img = loader.get(1);
   let path = Path::new().line_to((1024 * 3, 1024 * 2)).clone();
   let mut colors = img.unwrap().as_ref().clone();
   let colors = colors.colors().as_slice().unwrap();
@@ -49,7 +49,7 @@ pub fn main() {
                 .with_angle(45.0)
                 .with_distance(20.0)
                 // .with_opacity(0.2)
-                .with_size(100.0),
+                .with_blur(100.0),
             ),
         ),
     );
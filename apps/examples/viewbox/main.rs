@@ -6,7 +6,7 @@ fn main() {
   let _ = std::fs::create_dir_all("out");
   // Define a heart shape in normalized 0-100 coordinate space
   // This is the "abstract" or "viewBox" coordinate system
-  let heart = Polygon::new(5);
+  let heart = RegularPolygon::new(5).to_area();
 
   // Create three images at different sizes
   let mut image_small = Image::new(120, 120);
@@ -26,15 +26,15 @@ fn main() {
 
   // Areas return from fit/stretch, which are closed shapes (no caps needed, only joins)
   let stroke_100 = heart_100.stroke(5.0, LineJoin::Round);
-  let filled_100 = fill(stroke_100, color.clone());
+  let filled_100 = fill(stroke_100, color.clone(), None);
   image_small.draw_image_at(&filled_100, (10, 10));
 
   let stroke_300 = heart_300.stroke(5.0, LineJoin::Miter);
-  let filled_300 = fill(stroke_300, color.clone());
+  let filled_300 = fill(stroke_300, color.clone(), None);
   image_medium.draw_image_at(&filled_300, (0, 0));
 
   let stroke_600 = heart_600.stroke(5.0, LineJoin::Bevel);
-  let filled_600 = fill(stroke_600, color.clone());
+  let filled_600 = fill(stroke_600, color.clone(), None);
   image_large.draw_image_at(&filled_600, (0, 0));
 
   // Save at different sizes
@@ -54,11 +54,25 @@ fn main() {
   let mut image_stretch = Image::new(800, 400); // Wide viewport
   let heart_stretched = heart.stretch((800.0, 400.0));
   let stroke_stretched = heart_stretched.stroke(3.0, LineJoin::Miter);
-  let filled_stretched = fill(stroke_stretched, Fill::Solid(Color::from_rgba(100, 150, 255, 255)));
+  let filled_stretched = fill(stroke_stretched, Fill::Solid(Color::from_rgba(100, 150, 255, 255)), None);
   image_stretch.draw_image_at(&filled_stretched, (0, 0));
   image_stretch.save("out/heart_stretched.png", None);
 
   println!();
   println!("✅ Generated heart_stretched.png (800x400)");
   println!("   Using AspectRatio::none() to stretch and fill the viewport");
+
+  // Position the shape inside a wide viewport instead of always centering it
+  let mut image_aligned = Image::new(800, 200);
+  for align in [Align::XMinYMid, Align::XMidYMid, Align::XMaxYMid] {
+    let aligned = heart.fit_with_aspect((800.0, 200.0), AspectRatio::meet().with_align(align));
+    let (min_x, min_y, _, _) = aligned.bounds::<i32>();
+    let filled_aligned = fill(aligned, Fill::Solid(Color::from_rgba(255, 150, 100, 255)), None);
+    image_aligned.draw_image_at(&filled_aligned, (min_x, min_y));
+  }
+  image_aligned.save("out/heart_aligned.png", None);
+
+  println!();
+  println!("✅ Generated heart_aligned.png (800x200)");
+  println!("   Using AspectRatio::meet().with_align() to place the heart left, center and right");
 }
@@ -156,7 +156,7 @@ fn trans_to_black_gradient(width: u32, height: u32) -> Image {
 
 fn circle_image(radius: u32, color: Color) -> Image {
   let mut image = Image::new(radius * 2 + 2, radius * 2 + 2);
-  image.clear_color(Color::transparent());
+  image.fill_solid(Color::transparent());
   let center = Point::new(radius as i32, radius as i32);
 
   // Create a circular path using multiple line segments
@@ -1,5 +1,8 @@
 use crate::apply_options::ApplyOptions;
+use crate::color::Color;
 use crate::common::*;
+use crate::gradient::Gradient;
+use abra::abra_core::{Path, PointF};
 use abra::adjustments::prelude::*;
 
 #[napi]
@@ -16,6 +19,22 @@ pub fn auto_color(layer: &mut Layer, options: Option<&ApplyOptions>) {
   layer.mark_dirty();
 }
 
+#[napi]
+/// Automatically adjusts the colors of a layer with explicit clip percentages.
+/// @param layer The layer to adjust.
+/// @param shadowClip The percentage of pixels per channel clipped to black (0 to 50).
+/// @param highlightClip The percentage of pixels per channel clipped to white (0 to 50).
+/// @param snapNeutralMidtones Whether to remove the average color cast from the midtones.
+/// @param options Optional adjustment options, including area and mask.
+pub fn auto_color_ex(
+  layer: &mut Layer, shadow_clip: f64, highlight_clip: f64, snap_neutral_midtones: bool, options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  color::auto_color_ex(&mut *layer_ref, shadow_clip as f32, highlight_clip as f32, snap_neutral_midtones, options);
+  layer.mark_dirty();
+}
+
 #[napi]
 /// Automatically adjusts the tone of a layer.
 /// @param layer The layer to adjust.
@@ -27,6 +46,19 @@ pub fn auto_tone(layer: &mut Layer, options: Option<&ApplyOptions>) {
   layer.mark_dirty();
 }
 
+#[napi]
+/// Automatically adjusts the tone of a layer with explicit clip percentages.
+/// @param layer The layer to adjust.
+/// @param shadowClip The percentage of pixels per channel clipped to black (0 to 50).
+/// @param highlightClip The percentage of pixels per channel clipped to white (0 to 50).
+/// @param options Optional adjustment options, including area and mask.
+pub fn auto_tone_ex(layer: &mut Layer, shadow_clip: f64, highlight_clip: f64, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  color::auto_tone_ex(&mut *layer_ref, shadow_clip as f32, highlight_clip as f32, options);
+  layer.mark_dirty();
+}
+
 #[napi]
 /// Converts the colors of a layer to grayscale.
 /// @param layer The layer to convert to grayscale.
@@ -38,6 +70,36 @@ pub fn grayscale(layer: &mut Layer, options: Option<&ApplyOptions>) {
   layer.mark_dirty();
 }
 
+#[napi]
+/// Converts the colors of a layer to grayscale using the given method to weigh the colors.
+/// @param layer The layer to convert to grayscale.
+/// @param method How the gray value is computed ("rec709", "rec601", "average", "lightness", "luminosity" or "custom").
+/// @param weights The red, green and blue weights used by the "custom" method.
+/// @param options Optional adjustment options, including area and mask.
+pub fn grayscale_with(
+  layer: &mut Layer, method: String, weights: Option<Vec<f64>>, options: Option<&ApplyOptions>,
+) -> napi::Result<()> {
+  let method = match method.as_str() {
+    "rec709" => color::LumaMethod::Rec709,
+    "rec601" => color::LumaMethod::Rec601,
+    "average" => color::LumaMethod::Average,
+    "lightness" => color::LumaMethod::Lightness,
+    "luminosity" => color::LumaMethod::Luminosity,
+    "custom" => match weights.as_deref() {
+      Some([r, g, b]) => color::LumaMethod::Custom(*r as f32, *g as f32, *b as f32),
+      _ => return Err(napi::Error::new(napi::Status::InvalidArg, "the custom method requires three weights")),
+    },
+    other => {
+      return Err(napi::Error::new(napi::Status::InvalidArg, format!("unknown grayscale method \"{}\"", other)));
+    }
+  };
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  color::grayscale_with(&mut *layer_ref, method, options);
+  layer.mark_dirty();
+  Ok(())
+}
+
 #[napi]
 /// Inverts the colors of a layer.
 /// @param layer The layer to invert.
@@ -73,10 +135,22 @@ pub fn contrast(layer: &mut Layer, value: f64, options: Option<&ApplyOptions>) {
   layer.mark_dirty();
 }
 
+#[napi]
+/// Increases or decreases the local contrast of a layer in the midtones without changing its overall brightness.
+/// @param layer The layer to adjust.
+/// @param amount The amount of local contrast to add (-100 to 100). Negative values soften.
+/// @param options Optional adjustment options, including area and mask.
+pub fn clarity(layer: &mut Layer, amount: f64, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  levels::clarity(&mut *layer_ref, amount as f32, options);
+  layer.mark_dirty();
+}
+
 #[napi]
 /// Adjusts the exposure of a layer.
 /// @param layer The layer to adjust.
-/// @param exposure The exposure adjustment value.
+/// @param exposure The exposure change in stops. Each stop doubles the light.
 /// @param offset The offset adjustment value.
 /// @param gamma The gamma adjustment value.
 /// @param options Optional adjustment options, including area and mask.
@@ -87,15 +161,325 @@ pub fn exposure(layer: &mut Layer, exposure: f64, offset: f64, gamma: f64, optio
   layer.mark_dirty();
 }
 
+#[napi]
+/// Lifts the shadows and recovers the highlights of a layer based on the brightness around each pixel.
+/// @param layer The layer to adjust.
+/// @param shadowAmount How much dark areas are brightened (0 to 100).
+/// @param highlightAmount How much bright areas are darkened (0 to 100).
+/// @param radius The blur radius in pixels of the neighborhood.
+/// @param options Optional adjustment options, including area and mask.
+pub fn shadows_highlights(
+  layer: &mut Layer, shadow_amount: f64, highlight_amount: f64, radius: u32, options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  levels::shadows_highlights(&mut *layer_ref, shadow_amount as f32, highlight_amount as f32, radius, options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Changes the exposure of a layer in linear light and compresses the highlights so they roll off instead of clipping.
+/// @param layer The layer to adjust.
+/// @param operator The tone mapping curve ("reinhard", "aces" or "filmic").
+/// @param exposure The exposure change in stops applied before the curve.
+/// @param options Optional adjustment options, including area and mask.
+pub fn tone_map(layer: &mut Layer, operator: String, exposure: f64, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let operator = match operator.as_str() {
+    "aces" => levels::ToneMapOperator::Aces,
+    "filmic" => levels::ToneMapOperator::Filmic,
+    _ => levels::ToneMapOperator::Reinhard,
+  };
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  levels::tone_map(&mut *layer_ref, operator, exposure as f32, options);
+  layer.mark_dirty();
+}
+
 #[napi]
 /// Adjusts the vibrance of a layer.
 /// @param layer The layer to adjust.
-/// @param vibrance The vibrance adjustment value.
-/// @param saturation The saturation adjustment value.
+/// @param vibrance The vibrance adjustment value (-100 to 100).
+/// @param saturation The saturation adjustment value (-100 to 100).
 /// @param options Optional adjustment options, including area and mask.
-pub fn vibrance(layer: &mut Layer, vibrance: f64, saturation: f64, options: Option<&ApplyOptions>) {
+pub fn vibrance(layer: &mut Layer, vibrance: f64, saturation: f64, options: Option<&ApplyOptions>) -> napi::Result<()> {
+  check_range("vibrance", vibrance, -100.0, 100.0)?;
+  check_range("saturation", saturation, -100.0, 100.0)?;
   let layer_ref = layer.get_underlying_layer_mut();
   let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
   levels::vibrance(&mut *layer_ref, vibrance as f32, saturation as f32, Some(options));
   layer.mark_dirty();
+  Ok(())
+}
+
+#[napi]
+/// Adjusts the hue, saturation and lightness of a layer.
+/// @param layer The layer to adjust.
+/// @param hue The hue rotation in degrees (-180 to 180).
+/// @param saturation The saturation adjustment value (-100 to 100), where -100 is grayscale.
+/// @param lightness The lightness adjustment value (-100 to 100), where -100 is black and 100 is white.
+/// @param options Optional adjustment options, including area and mask.
+pub fn hue_saturation(
+  layer: &mut Layer, hue: f64, saturation: f64, lightness: f64, options: Option<&ApplyOptions>,
+) -> napi::Result<()> {
+  check_range("hue", hue, -180.0, 180.0)?;
+  check_range("saturation", saturation, -100.0, 100.0)?;
+  check_range("lightness", lightness, -100.0, 100.0)?;
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  levels::hue_saturation(&mut *layer_ref, hue, saturation, lightness, options);
+  layer.mark_dirty();
+  Ok(())
+}
+
+#[napi(object)]
+/// A control point of a tone curve.
+pub struct CurvePoint {
+  /// The input value (0 to 255).
+  pub x: f64,
+  /// The output value (0 to 255).
+  pub y: f64,
+}
+
+#[napi]
+/// Remaps the red, green and blue channels of a layer through a tone curve.
+/// @param layer The layer to adjust.
+/// @param points The control points of the curve. The curve passes through every point.
+/// @param options Optional adjustment options, including area and mask.
+pub fn curves(layer: &mut Layer, points: Vec<CurvePoint>, options: Option<&ApplyOptions>) -> napi::Result<()> {
+  if points.is_empty() {
+    return Err(napi::Error::from_reason("curves requires at least one point"));
+  }
+  let mut curve = Vec::with_capacity(points.len());
+  for point in &points {
+    check_range("point x", point.x, 0.0, 255.0)?;
+    check_range("point y", point.y, 0.0, 255.0)?;
+    curve.push((point.x.round() as u8, point.y.round() as u8));
+  }
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  levels::curves(&mut *layer_ref, &curve, options);
+  layer.mark_dirty();
+  Ok(())
+}
+
+#[napi]
+/// Remaps the tonal range of a layer.
+/// @param layer The layer to adjust.
+/// @param inputBlack The input black point (0 to 254).
+/// @param inputWhite The input white point (1 to 255), greater than the input black point.
+/// @param gamma The midtone gamma (0.1 to 9.99), where 1 means no change.
+/// @param outputBlack The output black point (0 to 255).
+/// @param outputWhite The output white point (0 to 255).
+/// @param options Optional adjustment options, including area and mask.
+pub fn levels(
+  layer: &mut Layer, input_black: u32, input_white: u32, gamma: f64, output_black: u32, output_white: u32,
+  options: Option<&ApplyOptions>,
+) -> napi::Result<()> {
+  for (name, value) in [
+    ("inputBlack", input_black),
+    ("inputWhite", input_white),
+    ("outputBlack", output_black),
+    ("outputWhite", output_white),
+  ] {
+    check_range(name, value as f64, 0.0, 255.0)?;
+  }
+  if input_black >= input_white {
+    return Err(napi::Error::from_reason("inputBlack must be less than inputWhite"));
+  }
+  check_range("gamma", gamma, 0.1, 9.99)?;
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  levels::levels(
+    &mut *layer_ref,
+    input_black as u8,
+    input_white as u8,
+    gamma,
+    output_black as u8,
+    output_white as u8,
+    options,
+  );
+  layer.mark_dirty();
+  Ok(())
+}
+
+/// Returns a JS error when a value is not a finite number within a range.
+fn check_range(p_name: &str, p_value: f64, p_min: f64, p_max: f64) -> napi::Result<()> {
+  if p_value.is_finite() && p_value >= p_min && p_value <= p_max {
+    Ok(())
+  } else {
+    Err(napi::Error::from_reason(format!("{} must be between {} and {}, got {}", p_name, p_min, p_max, p_value)))
+  }
+}
+
+#[napi]
+/// Binarizes a layer using a threshold that adapts to the brightness around each pixel.
+/// @param layer The layer to adjust.
+/// @param blockSize The size of the neighborhood in pixels.
+/// @param offset The amount subtracted from the local average to get the threshold.
+/// @param method How the local average is computed ("mean" or "gaussian").
+/// @param options Optional adjustment options, including area and mask.
+pub fn threshold_adaptive(
+  layer: &mut Layer, block_size: u32, offset: i32, method: String, options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let method = match method.as_str() {
+    "gaussian" => color::AdaptiveMethod::Gaussian,
+    _ => color::AdaptiveMethod::Mean,
+  };
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  color::threshold_adaptive(&mut *layer_ref, block_size, offset, method, options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Removes a solid-colored background by making pixels close to the key color transparent.
+/// @param layer The layer to key.
+/// @param key The background color to remove.
+/// @param tolerance How far a color may be from the key (0.0 to 1.0) and still become fully transparent.
+/// @param softness The width of the falloff (0.0 to 1.0) over which pixels fade back to opaque.
+/// @param spill Optional amount (0.0 to 1.0) of the key color's cast to remove from the remaining pixels.
+/// @param options Optional adjustment options, including area and mask.
+pub fn chroma_key(
+  layer: &mut Layer, key: &Color, tolerance: f64, softness: f64, spill: Option<f64>, options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let default_options = ApplyOptions::default();
+  let options = options.unwrap_or(&default_options);
+  color::chroma_key(&mut *layer_ref, key.inner, tolerance as f32, softness as f32, options.to_apply_options());
+  if let Some(spill) = spill {
+    color::suppress_spill(&mut *layer_ref, key.inner, spill as f32, options.to_apply_options());
+  }
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Shifts the hue, saturation and lightness of colors close to a target hue, leaving other colors untouched.
+/// @param layer The layer to adjust.
+/// @param target A color with the hue to select.
+/// @param hueRange How far in degrees a hue may be from the target and still be fully selected.
+/// @param hue The hue rotation in degrees.
+/// @param saturation The saturation multiplier, where 1.0 is unchanged.
+/// @param lightness The lightness offset between -1.0 and 1.0.
+/// @param options Optional adjustment options, including area and mask.
+pub fn replace_color(
+  layer: &mut Layer, target: &Color, hue_range: f64, hue: f64, saturation: f64, lightness: f64,
+  options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let shift = color::HslShift::new(hue as f32, saturation as f32, lightness as f32);
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  color::replace_color(&mut *layer_ref, target.inner, hue_range as f32, shift, options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Corrects the white balance of a layer along the blue-yellow and green-magenta axes.
+/// @param layer The layer to adjust.
+/// @param temperature The shift between blue (-100) and yellow (100).
+/// @param tint The shift between green (-100) and magenta (100).
+/// @param options Optional adjustment options, including area and mask.
+pub fn white_balance(layer: &mut Layer, temperature: f64, tint: f64, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  color::white_balance(&mut *layer_ref, temperature as f32, tint as f32, options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Corrects the white balance of a layer so that the pixel at the given position becomes a neutral gray.
+/// @param layer The layer to adjust.
+/// @param x The x-coordinate of the pixel that should be gray.
+/// @param y The y-coordinate of the pixel that should be gray.
+/// @param options Optional adjustment options, including area and mask.
+pub fn white_balance_from_gray(layer: &mut Layer, x: i32, y: i32, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  color::white_balance_from_gray(&mut *layer_ref, (x, y), options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Applies a photo filter of any color to a layer, like placing a colored gel in front of the lens.
+/// @param layer The layer to adjust.
+/// @param color The color of the filter.
+/// @param density The density of the filter (0.0 to 1.0).
+/// @param preserveLuminosity Whether to keep the brightness of every pixel so only its color shifts.
+/// @param options Optional adjustment options, including area and mask.
+pub fn photo_filter(
+  layer: &mut Layer, color: &Color, density: f64, preserve_luminosity: bool, options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  levels::photo_filter(&mut *layer_ref, color.inner, density, preserve_luminosity, options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Remaps the colors of a layer through a LUT loaded from an Adobe/Resolve `.cube` file.
+/// @param layer The layer to adjust.
+/// @param path The path to the `.cube` file.
+/// @param options Optional adjustment options, including area and mask.
+pub fn apply_cube_lut(layer: &mut Layer, path: String, options: Option<&ApplyOptions>) -> napi::Result<()> {
+  let lut = levels::Lut::from_cube(path).map_err(napi::Error::from_reason)?;
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  lut.apply(&mut *layer_ref, options);
+  layer.mark_dirty();
+  Ok(())
+}
+
+#[napi]
+/// Applies a gamma correction to a layer. Black and white stay unchanged.
+/// @param layer The layer to adjust.
+/// @param gamma The gamma (0.1 to 9.99). Values above 1 brighten the midtones.
+/// @param options Optional adjustment options, including area and mask.
+pub fn gamma(layer: &mut Layer, gamma: f64, options: Option<&ApplyOptions>) -> napi::Result<()> {
+  check_range("gamma", gamma, 0.1, 9.99)?;
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  levels::gamma(&mut *layer_ref, gamma, options);
+  layer.mark_dirty();
+  Ok(())
+}
+
+#[napi]
+/// Fills a layer with a linear gradient from one point to another, painted over the existing pixels.
+/// The points are in layer coordinates, so an area or mask only limits which pixels are filled.
+/// @param layer The layer to fill.
+/// @param gradient The gradient to fill with.
+/// @param startX The horizontal position of the first color.
+/// @param startY The vertical position of the first color.
+/// @param endX The horizontal position of the last color.
+/// @param endY The vertical position of the last color.
+/// @param options Optional adjustment options, including area and mask.
+pub fn linear_gradient(
+  layer: &mut Layer, gradient: &Gradient, start_x: f64, start_y: f64, end_x: f64, end_y: f64,
+  options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  let path = Path::line((start_x as f32, start_y as f32), (end_x as f32, end_y as f32));
+  color::linear_gradient(&mut *layer_ref, path, gradient.inner.clone(), options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Fills a layer with a radial gradient, painted over the existing pixels. The first color is at the focal
+/// point and the last on the circle.
+/// @param layer The layer to fill.
+/// @param gradient The gradient to fill with.
+/// @param centerX The horizontal center of the circle.
+/// @param centerY The vertical center of the circle.
+/// @param radius The radius of the circle in pixels.
+/// @param focal Optional `[x, y]` position where the gradient starts. Defaults to the center.
+/// @param options Optional adjustment options, including area and mask.
+pub fn radial_gradient(
+  layer: &mut Layer, gradient: &Gradient, center_x: f64, center_y: f64, radius: f64, focal: Option<(f64, f64)>,
+  options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  let focal = focal.map(PointF::from);
+  color::radial_gradient(&mut *layer_ref, (center_x, center_y), radius as f32, focal, gradient.inner.clone(), options);
+  layer.mark_dirty();
 }
@@ -8,11 +8,16 @@ use crate::common::*;
 pub struct ApplyOptions {
   area: Option<Vec<Area>>,
   mask: Option<Mask>,
+  inverted: bool,
 }
 
 impl Default for ApplyOptions {
   fn default() -> Self {
-    Self { area: None, mask: None }
+    Self {
+      area: None,
+      mask: None,
+      inverted: false,
+    }
   }
 }
 
@@ -23,6 +28,7 @@ impl ApplyOptions {
     Self {
       area: area.map(|areas| areas.into_iter().cloned().collect()),
       mask: mask.cloned(),
+      inverted: false,
     }
   }
 
@@ -36,6 +42,11 @@ impl ApplyOptions {
     self.mask.clone()
   }
 
+  #[napi(getter)]
+  pub fn inverted(&self) -> bool {
+    self.inverted
+  }
+
   #[napi]
   pub fn set_area(&mut self, area: Option<Vec<&Area>>) -> &Self {
     self.area = area.map(|areas| areas.into_iter().cloned().collect());
@@ -48,6 +59,12 @@ impl ApplyOptions {
     self
   }
 
+  #[napi]
+  pub fn set_inverted(&mut self, inverted: bool) -> &Self {
+    self.inverted = inverted;
+    self
+  }
+
   pub(crate) fn to_apply_options(&self) -> AbraApplyOptions {
     let mut apply_opts = AbraApplyOptions::new();
     if let Some(area) = &self.area {
@@ -57,6 +74,9 @@ impl ApplyOptions {
     if let Some(mask) = &self.mask {
       apply_opts = apply_opts.with_mask(mask.inner.clone());
     }
+    if self.inverted {
+      apply_opts = apply_opts.inverted();
+    }
     apply_opts
   }
 }
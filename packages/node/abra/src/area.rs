@@ -1,5 +1,5 @@
 use crate::common::*;
-use abra::abra_core::geometry::Area as AbraArea;
+use abra::abra_core::geometry::{Area as AbraArea, FeatherCurve};
 
 #[napi]
 #[derive(Clone)]
@@ -49,6 +49,39 @@ impl Area {
     let inner = self.inner.clone();
     self.inner = inner.with_feather(feather as u32);
   }
+
+  #[napi]
+  /// Set separate horizontal and vertical feather amounts of the Area.
+  /// @param featherX The horizontal feather amount in pixels; 0 keeps the left and right edges hard.
+  /// @param featherY The vertical feather amount in pixels; 0 keeps the top and bottom edges hard.
+  pub fn set_feather_xy(&mut self, feather_x: u32, feather_y: u32) {
+    let inner = self.inner.clone();
+    self.inner = inner.with_feather_xy(feather_x, feather_y);
+  }
+
+  #[napi]
+  /// Set the shape of the falloff across the feather.
+  /// @param curve The falloff curve ("linear", "smoothstep" or "gaussian").
+  pub fn set_feather_curve(&mut self, curve: String) {
+    let curve = match curve.as_str() {
+      "smoothstep" => FeatherCurve::Smoothstep,
+      "gaussian" => FeatherCurve::Gaussian,
+      _ => FeatherCurve::Linear,
+    };
+    let inner = self.inner.clone();
+    self.inner = inner.with_feather_curve(curve);
+  }
+
+  #[napi]
+  /// Render the Area as a signed distance field texture, where 128 lies on the outline.
+  /// @param size The (width, height) of the texture.
+  /// @param spread The distance in pixels on each side of the outline that the 0-255 range covers.
+  /// ```
+  /// let sdf = Area.rect((8, 8), (48, 48)).toSdf((64, 64), 4);
+  /// ```
+  pub fn to_sdf(&self, size: (u32, u32), spread: f64) -> ImageData {
+    ImageData::from(self.inner.to_sdf(size, spread as f32))
+  }
 }
 
 impl From<AbraArea> for Area {
@@ -43,12 +43,21 @@ impl Color {
   #[napi(factory)]
   /// Creates a new color from a hex value (e.g., 0xRRG, 0xRRGGBBAA).
   pub fn from_hex(hex: u32) -> Self {
-    AbraColor::from_hex(hex).into()
+    AbraColor::from_hex_value(hex).into()
   }
   #[napi(factory)]
-  /// Creates a new color from a hex string (e.g., "#RRGGBB" or "#RRGGBBAA").
-  pub fn from_hex_string(hex: String) -> Self {
-    AbraColor::from_hex_string(&hex).into()
+  /// Creates a new color from a hex string (e.g., "#RGB", "#RGBA", "#RRGGBB" or "#RRGGBBAA").
+  pub fn from_hex_string(hex: String) -> napi::Result<Self> {
+    AbraColor::from_hex(&hex)
+      .map(Into::into)
+      .map_err(|e| napi::Error::from_reason(e.to_string()))
+  }
+  #[napi]
+  /// Creates a new color from a CSS color name (case-insensitive).
+  /// @param name The CSS color name, e.g. "rebeccapurple".
+  /// @return The color, or null if the name is unknown.
+  pub fn named(name: String) -> Option<Color> {
+    AbraColor::named(&name).map(Into::into)
   }
   #[napi(factory)]
   /// Creates a new color from HSL values (e.g., hue, saturation, lightness).
@@ -86,6 +95,12 @@ impl Color {
     self.inner.a
   }
   #[napi]
+  /// Returns the color as a hex string.
+  /// @return The color in `#rrggbbaa` form.
+  pub fn to_hex(&self) -> String {
+    self.inner.to_hex()
+  }
+  #[napi]
   /// Calculates the contrast ratio between this color and another color.
   /// @param other The other color to compare with.
   /// @return The contrast ratio as a float.
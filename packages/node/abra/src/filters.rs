@@ -1,3 +1,5 @@
 pub mod blur;
 pub mod distort;
+pub mod morphology;
 pub mod noise;
+pub mod stylize;
@@ -24,6 +24,21 @@ pub fn surface_blur(layer: &mut Layer, radius: f64, threshold: f64, options: Opt
   layer.mark_dirty();
 }
 #[napi]
+/// Applies a surface blur with separate luminance and chrominance thresholds.
+/// @param layer The layer to apply the surface blur to.
+/// @param radius The radius of the surface blur. Fractional values are supported.
+/// @param lumaThreshold The threshold for brightness differences.
+/// @param chromaThreshold The threshold for color differences.
+/// @param options Optional apply options for masking and area.
+pub fn surface_blur_ex(
+  layer: &mut Layer, radius: f64, luma_threshold: f64, chroma_threshold: f64, options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  blur::surface_blur_ex(&mut *layer_ref, radius as f32, luma_threshold as u8, chroma_threshold as u8, options);
+  layer.mark_dirty();
+}
+#[napi]
 /// Blurs the image using a box blur algorithm.
 /// @param layer The layer to apply the box blur to.
 /// @param radius The radius of the box blur.
@@ -34,6 +49,17 @@ pub fn box_blur(layer: &mut Layer, radius: f64, options: Option<&ApplyOptions>)
   blur::box_blur(&mut *layer_ref, radius as f64, options);
   layer.mark_dirty();
 }
+#[napi]
+/// Blurs only the alpha channel of the image, leaving its colors untouched.
+/// @param layer The layer whose alpha channel is blurred.
+/// @param radius The radius of the Gaussian blur.
+/// @param options Optional apply options for masking and area.
+pub fn blur_alpha(layer: &mut Layer, radius: f64, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  blur::blur_alpha(&mut *layer_ref, radius as u32, options);
+  layer.mark_dirty();
+}
 
 #[napi(object)]
 /// Options for iris (aperture) in lens blur.
@@ -131,6 +157,7 @@ pub fn lens_blur(layer: &mut Layer, options: Option<LensBlurOptions>, apply_opti
     specular,
     noise,
     samples: options.as_ref().map_or(0, |opts| opts.samples),
+    ..Default::default()
   };
 
   let apply_options = apply_options.unwrap_or(&ApplyOptions::default()).to_apply_options();
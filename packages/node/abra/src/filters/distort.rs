@@ -11,6 +11,22 @@ pub fn pinch(layer: &mut Layer, amount: f64, options: Option<&ApplyOptions>) {
   layer.mark_dirty();
 }
 
+#[napi]
+/// Offsets the red and blue channels radially from a center point, producing colored fringes toward the edges.
+/// @param layer The layer to apply the effect to.
+/// @param amount How far the red and blue channels are offset at the farthest corner, in pixels.
+/// @param centerX The horizontal center of the effect, where 0.5 is the middle.
+/// @param centerY The vertical center of the effect, where 0.5 is the middle.
+/// @param options Optional apply options for masking and area.
+pub fn chromatic_aberration(
+  layer: &mut Layer, amount: f64, center_x: f64, center_y: f64, options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  distort::chromatic_aberration(&mut *layer_ref, amount as f32, (center_x, center_y), options);
+  layer.mark_dirty();
+}
+
 #[napi]
 /// Applies a ripple distortion effect to the image.
 /// @param layer The layer to apply the ripple effect to.
@@ -43,3 +59,28 @@ pub fn ripple(
   distort::ripple(&mut *layer_ref, amount as f32, ripple_size, ripple_shape, options);
   layer.mark_dirty();
 }
+
+#[napi]
+/// Offsets each pixel by an amount read from a displacement map. The red channel of the map moves pixels
+/// horizontally and the green channel vertically, and 50% gray leaves them in place.
+/// @param layer The layer to apply the effect to.
+/// @param map The layer to use as the displacement map. It is stretched to the layer when their sizes differ.
+/// @param scaleX The horizontal displacement in pixels for a white or black map value.
+/// @param scaleY The vertical displacement in pixels for a white or black map value.
+/// @param edge How pixels displaced past the edge are filled. Defaults to "repeat".
+/// @param options Optional apply options for masking and area.
+pub fn displace(
+  layer: &mut Layer, map: &Layer, scale_x: f64, scale_y: f64,
+  #[napi(ts_arg_type = "\"repeat\" | \"wrap\" | \"transparent\"")] edge: Option<String>, options: Option<&ApplyOptions>,
+) {
+  let map_image = map.get_underlying_layer().image();
+  let edge = match edge.as_deref() {
+    Some("wrap") => distort::DisplaceEdge::Wrap,
+    Some("transparent") => distort::DisplaceEdge::Transparent,
+    _ => distort::DisplaceEdge::Repeat,
+  };
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  distort::displace(&mut *layer_ref, &map_image, scale_x as f32, scale_y as f32, edge, options);
+  layer.mark_dirty();
+}
@@ -0,0 +1,63 @@
+use crate::common::*;
+use abra::filters::prelude::morphology::StructuringElement;
+
+/// Maps a structuring element name to the element, defaulting to a square.
+fn structuring_element(shape: &str) -> StructuringElement {
+  match shape {
+    "cross" => StructuringElement::Cross,
+    "disk" => StructuringElement::Disk,
+    _ => StructuringElement::Square,
+  }
+}
+
+#[napi]
+/// Dilates the image, giving every pixel the brightest value around it (a maximum filter).
+/// @param layer The layer to apply the filter to.
+/// @param radius The radius of the structuring element in pixels.
+/// @param shape The shape of the structuring element ("square", "cross" or "disk").
+/// @param options Optional apply options for masking and area.
+pub fn dilate(layer: &mut Layer, radius: u32, shape: String, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  morphology::dilate(&mut *layer_ref, radius, structuring_element(&shape), options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Erodes the image, giving every pixel the darkest value around it (a minimum filter).
+/// @param layer The layer to apply the filter to.
+/// @param radius The radius of the structuring element in pixels.
+/// @param shape The shape of the structuring element ("square", "cross" or "disk").
+/// @param options Optional apply options for masking and area.
+pub fn erode(layer: &mut Layer, radius: u32, shape: String, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  morphology::erode(&mut *layer_ref, radius, structuring_element(&shape), options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Erodes and then dilates the image, removing bright specks smaller than the structuring element.
+/// @param layer The layer to apply the filter to.
+/// @param radius The radius of the structuring element in pixels.
+/// @param shape The shape of the structuring element ("square", "cross" or "disk").
+/// @param options Optional apply options for masking and area.
+pub fn morphological_open(layer: &mut Layer, radius: u32, shape: String, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  morphology::open(&mut *layer_ref, radius, structuring_element(&shape), options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Dilates and then erodes the image, filling dark holes smaller than the structuring element.
+/// @param layer The layer to apply the filter to.
+/// @param radius The radius of the structuring element in pixels.
+/// @param shape The shape of the structuring element ("square", "cross" or "disk").
+/// @param options Optional apply options for masking and area.
+pub fn morphological_close(layer: &mut Layer, radius: u32, shape: String, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  morphology::close(&mut *layer_ref, radius, structuring_element(&shape), options);
+  layer.mark_dirty();
+}
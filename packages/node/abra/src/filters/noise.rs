@@ -9,7 +9,7 @@ use abra::filters::prelude::{noise::NoiseDistribution, *};
 /// @param options Optional apply options for masking and area.
 pub fn noise(layer: &mut Layer, amount: f64, distribution: String, options: Option<&ApplyOptions>) {
   let layer_ref = layer.get_underlying_layer_mut();
-  noise::noise(
+  noise::add_noise(
     &mut *layer_ref,
     amount as f32,
     match distribution.as_str() {
@@ -17,12 +17,48 @@ pub fn noise(layer: &mut Layer, amount: f64, distribution: String, options: Opti
       "gaussian" => NoiseDistribution::Gaussian,
       _ => NoiseDistribution::Uniform,
     },
+    true,
+    0,
     options.unwrap_or(&ApplyOptions::default()).to_apply_options(),
   );
   println!("Added noise: amount={}, distribution={}", amount, distribution);
   layer.mark_dirty();
 }
 
+#[napi]
+/// Adds reproducible random noise to the image.
+/// @param layer The layer to apply the noise to.
+/// @param amount The amount of noise to add.
+/// @param distribution The distribution type of the noise ("uniform" or "gaussian").
+/// @param monochromatic Whether the same noise is added to all channels.
+/// @param seed The seed of the noise; the same seed always produces the same noise.
+/// @param options Optional apply options for masking and area.
+pub fn add_noise(
+  layer: &mut Layer, amount: f64, distribution: String, monochromatic: bool, seed: u32, options: Option<&ApplyOptions>,
+) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let distribution = match distribution.as_str() {
+    "gaussian" => NoiseDistribution::Gaussian,
+    _ => NoiseDistribution::Uniform,
+  };
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  noise::add_noise(layer_ref, amount as f32, distribution, monochromatic, seed as u64, options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Adds film grain that is strongest in the midtones.
+/// @param layer The layer to apply the grain to.
+/// @param intensity The strength of the grain between 0.0 and 1.0.
+/// @param grainSize The approximate size of a grain in pixels.
+/// @param options Optional apply options for masking and area.
+pub fn film_grain(layer: &mut Layer, intensity: f64, grain_size: f64, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  noise::film_grain(layer_ref, intensity as f32, grain_size as f32, options);
+  layer.mark_dirty();
+}
+
 #[napi]
 /// Applies a despeckle filter to the image.
 /// @param layer The layer to apply the despeckle filter to.
@@ -36,6 +72,19 @@ pub fn despeckle<'a>(layer: &mut Layer, radius: f64, threshold: f64, options: Op
   layer.mark_dirty();
 }
 
+#[napi]
+/// Removes dust and scratches by replacing pixels that differ from their median-blurred surroundings.
+/// @param layer The layer to apply the filter to.
+/// @param radius How far around each pixel to look for a replacement, in pixels.
+/// @param threshold How much a pixel must differ from its surroundings (0-255) before it is replaced.
+/// @param options Optional apply options for masking and area.
+pub fn dust_and_scratches(layer: &mut Layer, radius: u32, threshold: u8, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  noise::dust_and_scratches(layer_ref, radius, threshold, options);
+  layer.mark_dirty();
+}
+
 #[napi]
 /// Applies a median filter to the image.
 /// @param layer The layer to apply the median filter to.
@@ -0,0 +1,78 @@
+use crate::common::*;
+use abra::filters::prelude::stylize::{DitherMethod, DotShape};
+
+#[napi]
+/// Renders the image as a grid of ink dots sized by the brightness under them.
+/// @param layer The layer to apply the effect to.
+/// @param dotSize The distance between dot centers in pixels.
+/// @param angle The angle of the dot grid in degrees.
+/// @param shape The shape of the dots ("circle", "square", "diamond" or "line").
+/// @param options Optional apply options for masking and area.
+pub fn halftone(layer: &mut Layer, dot_size: u32, angle: f64, shape: String, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let shape = match shape.as_str() {
+    "square" => DotShape::Square,
+    "diamond" => DotShape::Diamond,
+    "line" => DotShape::Line,
+    _ => DotShape::Circle,
+  };
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  stylize::halftone(&mut *layer_ref, dot_size, angle as f32, shape, options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Reduces each channel to a number of levels and dithers the tones in between.
+/// @param layer The layer to apply the effect to.
+/// @param method The dithering algorithm ("floyd-steinberg", "ordered" or "atkinson").
+/// @param levels The number of levels per channel, between 2 and 256.
+/// @param options Optional apply options for masking and area.
+pub fn dither(layer: &mut Layer, method: String, levels: u32, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let method = match method.as_str() {
+    "ordered" => DitherMethod::Ordered,
+    "atkinson" => DitherMethod::Atkinson,
+    _ => DitherMethod::FloydSteinberg,
+  };
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  stylize::dither(&mut *layer_ref, method, levels, options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Applies a Kuwahara filter for a smooth, painterly look that keeps edges sharp.
+/// @param layer The layer to apply the effect to.
+/// @param radius The size of each quadrant in pixels.
+/// @param options Optional apply options for masking and area.
+pub fn kuwahara(layer: &mut Layer, radius: u32, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  stylize::kuwahara(&mut *layer_ref, radius, options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Turns the layer into a gray relief, as if it were pressed into metal and lit from one side.
+/// @param layer The layer to apply the effect to.
+/// @param angle The direction the light comes from in degrees, counterclockwise from the right.
+/// @param depth How strongly edges stand out (0.0 to 10.0).
+/// @param options Optional apply options for masking and area.
+pub fn emboss(layer: &mut Layer, angle: f64, depth: f64, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  stylize::emboss(&mut *layer_ref, angle as f32, depth as f32, options);
+  layer.mark_dirty();
+}
+
+#[napi]
+/// Traces the edges of the layer as glowing neon lines on black.
+/// @param layer The layer to apply the effect to.
+/// @param width The width of the lines in pixels.
+/// @param brightness How bright the lines are (0.0 to 10.0).
+/// @param options Optional apply options for masking and area.
+pub fn glowing_edges(layer: &mut Layer, width: u32, brightness: f64, options: Option<&ApplyOptions>) {
+  let layer_ref = layer.get_underlying_layer_mut();
+  let options = options.unwrap_or(&ApplyOptions::default()).to_apply_options();
+  edges::glowing_edges(&mut *layer_ref, width, brightness as f32, options);
+  layer.mark_dirty();
+}
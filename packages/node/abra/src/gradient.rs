@@ -46,6 +46,16 @@ impl Gradient {
     AbraGradient::evenly(colors.into_iter().map(|c| c.inner.clone()).collect()).into()
   }
 
+  #[napi(factory)]
+  /// Creates a gradient from the colors of an image along a line, averaging the pixels around each stop.
+  /// @param data The image to sample.
+  /// @param line The path to sample along, in pixel coordinates.
+  /// @param stops The number of evenly spaced stops, at least 2.
+  /// @return The resulting gradient with the sampled colors.
+  pub fn from_image(data: ImageData, line: &Path, stops: u32) -> napi::Result<Self> {
+    Ok(AbraGradient::from_image(&image_data_to_image(&data)?, line.inner.clone(), stops as usize).into())
+  }
+
   #[napi(factory)]
   /// Creates a rainbow gradient.
   /// @return The resulting rainbow gradient.
@@ -88,6 +98,23 @@ impl Gradient {
   pub fn reverse(&mut self) -> Gradient {
     self.inner.clone().reverse().into()
   }
+
+  #[napi]
+  /// Rotates the hue of every stop around the color wheel.
+  /// @param degrees The amount to rotate the hue by.
+  /// @return The rotated gradient.
+  pub fn rotate_hue(&self, degrees: f64) -> Gradient {
+    self.inner.rotate_hue(degrees as f32).into()
+  }
+
+  #[napi]
+  /// Extracts a portion of the gradient and stretches it over the 0.0 to 1.0 range.
+  /// @param start The position where the new gradient starts.
+  /// @param end The position where the new gradient ends.
+  /// @return The extracted gradient.
+  pub fn subrange(&self, start: f64, end: f64) -> Gradient {
+    self.inner.subrange(start as f32, end as f32).into()
+  }
 }
 
 impl From<AbraGradient> for Gradient {
@@ -3,9 +3,9 @@ use std::sync::Arc;
 use crate::common::*;
 use abra::canvas::prelude::Layer as AbraLayer;
 use abra::{
-  abra_core::blend::{self, blend_mode_name},
-  canvas::prelude::Anchor,
-  prelude::{Channels, Image, Rotate},
+  abra_core::Anchor,
+  abra_core::blend::{BLEND_MODES, blend_mode_from_name, blend_mode_name},
+  prelude::Rotate,
   transform::prelude::resize,
 };
 
@@ -18,6 +18,13 @@ pub struct Position {
   pub y: i32,
 }
 
+#[napi]
+/// Gets the names of every blend mode accepted by `Layer.setBlendMode`.
+/// @returns The blend mode names, e.g. `"multiply"` or `"soft-light"`.
+pub fn blend_modes() -> Vec<String> {
+  BLEND_MODES.iter().map(|(name, _, _)| name.to_string()).collect()
+}
+
 #[napi]
 pub struct Layer {
   pub(crate) inner: AbraLayer,
@@ -74,50 +81,20 @@ impl Layer {
     self
   }
 
-  #[napi(
-    ts_type = "(blendMode: 'normal' | 'darken' | 'average' | 'multiply' | 'color-burn' | 'linear-burn' | 'lighten' | 'screen' | 'color-dodge' | 'linear-dodge' | 'overlay' | 'soft-light' | 'hard-light' | 'vivid-light' | 'linear-light' | 'pin-light' | 'hard-mix' | 'difference' | 'exclusion' | 'subtract' | 'divide' | 'hue' | 'saturation' | 'color' | 'luminosity' | 'reflect' | 'glow' | 'phoenix' | 'negation' | 'grain-extract' | 'grain-merge'): this"
-  )]
+  #[napi]
   /// Sets the blend mode of the layer.
-  /// @param blendMode The new blend mode of the layer.
-  pub fn set_blend_mode(&mut self, blend_mode: String) -> &Self {
-    let blend_fn = match blend_mode.as_str() {
-      "normal" => blend::normal,
-      "darken" => blend::darken,
-      "reflect" => blend::reflect,
-      "glow" => blend::glow,
-      "phoenix" => blend::phoenix,
-      "negation" => blend::negation,
-      "grain-extract" => blend::grain_extract,
-      "grain-merge" => blend::grain_merge,
-      "darker-color" => blend::darker_color,
-      "average" => blend::average,
-      "multiply" => blend::multiply,
-      "color-burn" => blend::color_burn,
-      "linear-burn" => blend::linear_burn,
-      "lighten" => blend::lighten,
-      "lighter-color" => blend::lighter_color,
-      "screen" => blend::screen,
-      "color-dodge" => blend::color_dodge,
-      "linear-dodge" => blend::linear_dodge,
-      "overlay" => blend::overlay,
-      "soft-light" => blend::soft_light,
-      "hard-light" => blend::hard_light,
-      "vivid-light" => blend::vivid_light,
-      "linear-light" => blend::linear_light,
-      "pin-light" => blend::pin_light,
-      "hard-mix" => blend::hard_mix,
-      "difference" => blend::difference,
-      "exclusion" => blend::exclusion,
-      "subtract" => blend::subtract,
-      "divide" => blend::divide,
-      "hue" => blend::hue,
-      "saturation" => blend::saturation,
-      "color" => blend::color,
-      "luminosity" => blend::luminosity,
-      _ => blend::normal,
-    };
+  /// @param blendMode The name of the new blend mode, one of `blendModes()`.
+  /// @throws If the blend mode name is unknown.
+  pub fn set_blend_mode(&mut self, blend_mode: String) -> napi::Result<&Self> {
+    let blend_fn = blend_mode_from_name(&blend_mode).ok_or_else(|| {
+      napi::Error::from_reason(format!(
+        "Unknown blend mode '{}', expected one of: {}",
+        blend_mode,
+        blend_modes().join(", ")
+      ))
+    })?;
     self.inner.set_blend_mode(blend_fn);
-    self
+    Ok(self)
   }
 
   #[napi]
@@ -176,22 +153,16 @@ impl Layer {
       resized_img
     };
 
-    ImageData {
-      data: Buffer::from(img.rgba().to_vec()),
-      width: new_width,
-      height: new_height,
-    }
+    ImageData::from(img)
   }
 
   #[napi]
   /// Sets a new image for the layer.
   /// @param data The new image data for the layer.
-  pub fn set_image_data(&mut self, data: ImageData) -> &Self {
-    let image_data = data.data;
-    let image = Image::new_from_pixels(data.width, data.height, image_data.to_vec(), Channels::RGBA);
-    let image = Arc::new(image);
+  pub fn set_image_data(&mut self, data: ImageData) -> napi::Result<&Self> {
+    let image = Arc::new(image_data_to_image(&data)?);
     self.inner.set_image(image);
-    self
+    Ok(self)
   }
 
   #[napi]
@@ -1,4 +1,3 @@
-use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
 pub mod adjustments;
@@ -15,23 +14,17 @@ pub mod path;
 pub mod project;
 
 pub(crate) mod common {
-  pub use crate::ImageData;
   pub use crate::apply_options::ApplyOptions;
   pub use crate::layer::Layer;
   pub use crate::metadata::{LayerMetadata, ProjectMetadata};
   pub use crate::project::Project;
+  pub use crate::{ImageData, image_data_to_image};
   pub use abra::filters::prelude::{noise::NoiseDistribution, *};
   pub use napi::bindgen_prelude::Buffer;
   pub use napi_derive::napi;
 }
 
-/// Result of opening an image, includes dimensions for canvas rendering.
-#[napi(object, js_name = "AbraImageData")]
-pub struct ImageData {
-  pub data: Buffer,
-  pub width: u32,
-  pub height: u32,
-}
+pub use alakazam_image_data::{ImageData, image_data_to_image, image_to_image_data};
 
 #[napi]
 #[derive(Clone)]
@@ -1,5 +1,4 @@
 use crate::{common::*, generate_image::transparent_pattern, layer::layer_metadata};
-use napi::bindgen_prelude::Buffer;
 use napi_derive::napi;
 
 use abra::{
@@ -105,12 +104,7 @@ impl Project {
     let start = std::time::Instant::now();
     let image = self.canvas.as_image();
     println!("Composite took {:?}", start.elapsed());
-    let (width, height) = image.dimensions();
-    ImageData {
-      data: Buffer::from(image.rgba().to_vec()),
-      width,
-      height,
-    }
+    ImageData::from(image)
   }
 
   #[napi(getter)]
@@ -355,13 +349,7 @@ impl Project {
     if new_width != width || new_height != height {
       canvas.transform().resize(new_width, new_height, None);
     }
-    let (width, height) = canvas.dimensions::<u32>();
-
-    ImageData {
-      data: Buffer::from(canvas.as_image().rgba().to_vec()),
-      width: width,
-      height: height,
-    }
+    ImageData::from(canvas.as_image())
   }
 
   #[napi]
@@ -1,3 +1,6 @@
+use std::time::Duration;
+
+use abra::abra_core;
 use napi::bindgen_prelude::Reference;
 
 use crate::common::*;
@@ -44,20 +47,40 @@ impl HistoryEntry {
   }
 }
 
+#[napi(object, js_name = "AbraHistoryOptions")]
+/// Options for a history.
+pub struct HistoryOptions {
+  /// The maximum number of entries that are kept. Defaults to 100.
+  pub max_depth: Option<u32>,
+  /// Entries added within this many milliseconds of the previous one replace it instead of adding an undo step.
+  pub coalesce_ms: Option<u32>,
+  /// The maximum total size in bytes of the entries' image data. The oldest entries are dropped first.
+  pub max_bytes: Option<f64>,
+}
+
 #[napi(js_name = "AbraHistory")]
 pub struct History {
   project_id: String,
-  entries: Vec<HistoryEntry>,
+  entries: abra_core::History<HistoryEntry>,
 }
 
 #[napi]
 impl History {
   #[napi(constructor)]
-  pub fn new(project_id: String) -> Self {
-    Self {
-      project_id,
-      entries: Vec::new(),
+  pub fn new(project_id: String, options: Option<HistoryOptions>) -> Self {
+    let mut entries = abra_core::History::new();
+    if let Some(options) = options {
+      if let Some(max_depth) = options.max_depth {
+        entries = entries.with_max_depth(max_depth as usize);
+      }
+      if let Some(coalesce_ms) = options.coalesce_ms {
+        entries = entries.with_coalesce_window(Duration::from_millis(coalesce_ms as u64));
+      }
+      if let Some(max_bytes) = options.max_bytes {
+        entries = entries.with_memory_limit(max_bytes.max(0.0) as usize, |entry: &HistoryEntry| entry.data.data.len());
+      }
     }
+    Self { project_id, entries }
   }
 
   #[napi(getter)]
@@ -72,8 +95,14 @@ impl History {
     self.project_id.clone()
   }
 
+  #[napi(getter)]
+  /// Get the index of the current entry.
+  pub fn position(&self) -> u32 {
+    self.entries.position() as u32
+  }
+
   #[napi]
-  /// Add a new entry to the end of the history.
+  /// Add a new entry after the current one, discarding any entries that could have been redone.
   /// - `item`: The history entry to add.
   pub fn add(&mut self, item: Reference<HistoryEntry>) {
     self.entries.push((*item).clone());
@@ -84,13 +113,7 @@ impl History {
   /// @param index The index of the entry to remove.
   /// @returns true if the entry was removed, false if the index was out of bounds
   pub fn remove(&mut self, index: u32) -> bool {
-    let index = index as usize;
-    if index < self.entries.len() {
-      self.entries.remove(index);
-      true
-    } else {
-      false
-    }
+    self.entries.remove(index as usize).is_some()
   }
 
   #[napi]
@@ -105,4 +128,42 @@ impl History {
   pub fn get(&self, index: u32) -> Option<HistoryEntry> {
     self.entries.get(index as usize).cloned()
   }
+
+  #[napi]
+  /// Get the current entry.
+  pub fn current(&self) -> Option<HistoryEntry> {
+    self.entries.current().cloned()
+  }
+
+  #[napi]
+  /// Step back to the previous entry.
+  /// @returns The previous entry, or null if there is nothing to undo.
+  pub fn undo(&mut self) -> Option<HistoryEntry> {
+    self.entries.undo()
+  }
+
+  #[napi]
+  /// Step forward to the next entry.
+  /// @returns The next entry, or null if there is nothing to redo.
+  pub fn redo(&mut self) -> Option<HistoryEntry> {
+    self.entries.redo()
+  }
+
+  #[napi]
+  /// Check whether there is an entry to undo to.
+  pub fn can_undo(&self) -> bool {
+    self.entries.can_undo()
+  }
+
+  #[napi]
+  /// Check whether there is an entry to redo to.
+  pub fn can_redo(&self) -> bool {
+    self.entries.can_redo()
+  }
+
+  #[napi]
+  /// Remove every entry.
+  pub fn clear(&mut self) {
+    self.entries.clear();
+  }
 }
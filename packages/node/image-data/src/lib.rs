@@ -0,0 +1,75 @@
+//! The `ImageData` object shared by the node bindings and its conversions to and from `abra` images.
+//!
+//! Pixels crossing into JavaScript are handed over without copying when the image owns its buffer,
+//! while pixels coming from JavaScript are copied exactly once, because the `Buffer` memory belongs to JS.
+
+use abra::prelude::{Channels, Image};
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+
+/// Result of opening an image, includes dimensions for canvas rendering.
+#[napi(object, js_name = "AbraImageData")]
+pub struct ImageData {
+  /// The RGBA pixels, 4 bytes per pixel.
+  pub data: Buffer,
+  /// The width of the image in pixels.
+  pub width: u32,
+  /// The height of the image in pixels.
+  pub height: u32,
+}
+
+impl Clone for ImageData {
+  fn clone(&self) -> Self {
+    let data = self.data.to_vec();
+    let buffer = Buffer::from(data);
+    Self {
+      data: buffer,
+      width: self.width,
+      height: self.height,
+    }
+  }
+}
+
+impl From<Image> for ImageData {
+  /// Moves the image's pixel buffer into the `ImageData`. No pixels are copied unless the buffer is shared.
+  fn from(p_image: Image) -> Self {
+    let (width, height) = p_image.dimensions::<u32>();
+    ImageData {
+      data: Buffer::from(p_image.into_rgba_vec()),
+      width,
+      height,
+    }
+  }
+}
+
+/// Creates an image from an `ImageData`, copying the pixels out of the JS buffer once.
+/// Returns an `InvalidArg` error when the buffer does not hold exactly `width * height * 4` bytes.
+/// - `p_data`: The image data.
+pub fn image_data_to_image(p_data: &ImageData) -> napi::Result<Image> {
+  let expected = p_data.width as usize * p_data.height as usize * 4;
+  if p_data.data.len() != expected {
+    return Err(napi::Error::new(
+      napi::Status::InvalidArg,
+      format!(
+        "ImageData of {}x{} needs {} bytes of RGBA data, got {}",
+        p_data.width,
+        p_data.height,
+        expected,
+        p_data.data.len()
+      ),
+    ));
+  }
+  Ok(Image::new_from_pixels(p_data.width, p_data.height, p_data.data.to_vec(), Channels::RGBA))
+}
+
+/// Creates an `ImageData` from an image, copying the pixels once.
+/// Prefer `ImageData::from(image)` when the image is no longer needed, which avoids the copy.
+/// - `p_image`: The image to convert.
+pub fn image_to_image_data(p_image: &Image) -> ImageData {
+  let (width, height) = p_image.dimensions::<u32>();
+  ImageData {
+    data: Buffer::from(p_image.rgba().to_vec()),
+    width,
+    height,
+  }
+}